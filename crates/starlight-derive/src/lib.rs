@@ -1,5 +1,5 @@
 use quote::quote;
-use synstructure::{decl_derive, Structure};
+use synstructure::{decl_derive, BindStyle, Structure};
 
 decl_derive!([Trace, attributes(unsafe_ignore_trace)] => derive_trace);
 
@@ -10,15 +10,17 @@ fn derive_trace(mut s: Structure<'_>) -> proc_macro2::TokenStream {
             .iter()
             .any(|attr| attr.path.is_ident("unsafe_ignore_trace"))
     });
+    // fields are traced through `&mut` so that a moving collector can update them in place.
+    s.bind_with(|_| BindStyle::RefMut);
     let trace_body = s.each(|bi| quote!(mark(#bi,tracer)));
 
     let trace_impl = s.gen_impl(quote! {
 
         gen unsafe impl Trace for @Self {
-        #[inline] fn trace(&self,tracer: &mut dyn Tracer) {
+        #[inline] fn trace(&mut self,tracer: &mut dyn Tracer) {
             #[allow(dead_code)]
             #[inline]
-            fn mark<T: Trace + ?Sized>(it: &T,tracer: &mut dyn Tracer) {
+            fn mark<T: Trace + ?Sized>(it: &mut T,tracer: &mut dyn Tracer) {
                 Trace::trace(it,tracer);
             }
             match *self { #trace_body }
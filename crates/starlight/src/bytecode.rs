@@ -18,25 +18,40 @@ use opcodes::Op;
 use starlight_derive::Trace;
 #[derive(Trace)]
 pub struct ByteCode {
-    #[unsafe_ignore_trace]
     pub name: Symbol,
     #[unsafe_ignore_trace]
     pub code: Vec<u8>,
     #[unsafe_ignore_trace]
     pub code_start: *mut u8,
     pub codes: Vec<Gc<ByteCode>>,
+    /// Final structures of the object literals with static keys, see `OP_CREATE_LITERAL_OBJ`.
+    pub structures: Vec<Gc<Structure>>,
     pub feedback: Vec<TypeFeedBack>,
     pub literals: Vec<JsValue>,
     #[unsafe_ignore_trace]
     pub literals_start: *mut JsValue,
-    #[unsafe_ignore_trace]
     pub names: Vec<Symbol>,
-    #[unsafe_ignore_trace]
     pub params: Vec<Symbol>,
+    /// Rest parameter, bound to an array of the arguments past `params`.
+    pub rest: Option<Symbol>,
+    /// Parameters before the first one with a default value or the rest parameter, the
+    /// `length` of functions created from this code.
     #[unsafe_ignore_trace]
-    pub strict: bool,
+    pub length: u32,
+    /// No parameter has a default value and there is no rest parameter, only then the
+    /// `arguments` object of sloppy code is mapped to the parameters.
+    #[unsafe_ignore_trace]
+    pub simple_params: bool,
     #[unsafe_ignore_trace]
+    pub strict: bool,
     pub var_names: Vec<Symbol>,
+    /// Names of the bindings resolved to frame slots at compile time, indexed by slot.
+    pub local_names: Vec<Symbol>,
+    /// `(offset, line, column)` triples sorted by offset, code from `offset` up to the next
+    /// triple was compiled from the statement starting at `line` and `column`. Both are 1-based,
+    /// columns count bytes.
+    #[unsafe_ignore_trace]
+    pub positions: Vec<(u32, u32, u32)>,
 }
 
 impl ByteCode {
@@ -51,6 +66,11 @@ impl ByteCode {
                     _ => unreachable!(),
                 }
             }
+            for (ix, local) in self.local_names.iter().enumerate() {
+                if let Symbol::Key(s) = local {
+                    writeln!(output, " local %{} {}", ix, s)?;
+                }
+            }
             let start = self.code.as_ptr() as *mut u8;
             let mut pc = self.code.as_ptr() as *mut u8;
             while pc <= self.code.last().unwrap() as *const u8 as *mut u8 {
@@ -83,6 +103,116 @@ impl ByteCode {
                         pc = pc.add(4);
                         writeln!(output, "set_prop @{}, fdbk @{}", name, feedback)?;
                     }
+                    Op::OP_CREATE_ARR => {
+                        writeln!(output, "create_arr")?;
+                    }
+                    Op::OP_ARRAY_PUSH => {
+                        writeln!(output, "array_push")?;
+                    }
+                    Op::OP_ARRAY_SPREAD => {
+                        writeln!(output, "array_spread")?;
+                    }
+                    Op::OP_CREATE_OBJ => {
+                        writeln!(output, "create_obj")?;
+                    }
+                    Op::OP_INIT_PROP => {
+                        writeln!(output, "init_prop")?;
+                    }
+                    Op::OP_CREATE_LITERAL_OBJ => {
+                        let structure = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "create_literal_obj %{}", structure)?;
+                    }
+                    Op::OP_OBJECT_SPREAD => {
+                        writeln!(output, "object_spread")?;
+                    }
+                    Op::OP_GET_PROP_FOR_UPDATE => {
+                        let name = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        let feedback = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "get_prop_for_update @{}, fdbk @{}", name, feedback)?;
+                    }
+                    Op::OP_UPDATE_PROP => {
+                        let name = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        let feedback = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "update_prop @{}, fdbk @{}", name, feedback)?;
+                    }
+                    Op::OP_PUSH_THIS => {
+                        writeln!(output, "push_this")?;
+                    }
+                    Op::OP_PUSH_NEW_TARGET => {
+                        writeln!(output, "push_new_target")?;
+                    }
+                    Op::OP_NEW_REGEXP => {
+                        writeln!(output, "new_regexp")?;
+                    }
+                    Op::OP_DEBUGGER => {
+                        let line = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "debugger line {}", line)?;
+                    }
+                    Op::OP_GET_LOCAL => {
+                        let ix = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "get_local %{}", ix)?;
+                    }
+                    Op::OP_SET_LOCAL => {
+                        let ix = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "set_local %{}", ix)?;
+                    }
+                    Op::OP_FOR_IN_SETUP => {
+                        writeln!(output, "for_in_setup")?;
+                    }
+                    Op::OP_DECL_VAR => {
+                        let name = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "decl_var @{}", name)?;
+                    }
+                    Op::OP_DECL_GLOBAL_VAR => {
+                        let name = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "decl_global_var @{}", name)?;
+                    }
+                    Op::OP_DECL_PRIVATE_NAME => {
+                        let name = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "decl_private_name @{}", name)?;
+                    }
+                    Op::OP_INIT_PRIVATE_METHOD => {
+                        writeln!(output, "init_private_method")?;
+                    }
+                    Op::OP_ADD_PRIVATE => {
+                        writeln!(output, "add_private")?;
+                    }
+                    Op::OP_GET_PRIVATE => {
+                        writeln!(output, "get_private")?;
+                    }
+                    Op::OP_SET_PRIVATE => {
+                        writeln!(output, "set_private")?;
+                    }
+                    Op::OP_DEFINE_METHOD => {
+                        writeln!(output, "define_method")?;
+                    }
+                    Op::OP_FOR_IN_NEXT => {
+                        let off = pc.cast::<i32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(
+                            output,
+                            "for_in_next {}[->{}]",
+                            off,
+                            (pc as usize - start as usize) as i32 + off
+                        )?;
+                    }
+                    Op::OP_DELETE => {
+                        writeln!(output, "delete")?;
+                    }
+                    Op::OP_DELETE_VAR => {
+                        writeln!(output, "delete_var")?;
+                    }
                     Op::OP_PUSH_LIT => {
                         let ix = pc.cast::<u32>().read_unaligned();
                         pc = pc.add(4);
@@ -224,6 +354,40 @@ impl ByteCode {
                     Op::OP_POP_SCOPE => {
                         writeln!(output, "pop_scope")?;
                     }
+                    Op::OP_TRY_PUSH_CATCH => {
+                        let off = pc.cast::<i32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(
+                            output,
+                            "try_push_catch {}[->{}]",
+                            off,
+                            (pc as usize - start as usize) as i32 + off
+                        )?;
+                    }
+                    Op::OP_TRY_PUSH_FINALLY => {
+                        let off = pc.cast::<i32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(
+                            output,
+                            "try_push_finally {}[->{}]",
+                            off,
+                            (pc as usize - start as usize) as i32 + off
+                        )?;
+                    }
+                    Op::OP_TRY_POP => {
+                        writeln!(output, "try_pop")?;
+                    }
+                    Op::OP_THROW => {
+                        writeln!(output, "throw")?;
+                    }
+                    Op::OP_ENTER_CATCH => {
+                        let name = pc.cast::<u32>().read_unaligned();
+                        pc = pc.add(4);
+                        writeln!(output, "enter_catch @{}", name)?;
+                    }
+                    Op::OP_EXIT_CATCH => {
+                        writeln!(output, "exit_catch")?;
+                    }
                     _ => todo!("{:?}", op),
                 }
             }
@@ -234,17 +398,53 @@ impl ByteCode {
         vm.allocate(Self {
             name,
             var_names: vec![],
+            local_names: vec![],
             code: vec![],
             code_start: null_mut(),
             codes: vec![],
+            structures: vec![],
             feedback: vec![],
             literals: vec![],
             literals_start: null_mut(),
             names: vec![],
             params: Vec::from(params),
+            rest: None,
+            length: params.len() as u32,
+            simple_params: true,
             strict,
+            positions: vec![],
         })
     }
+
+    /// Source line the instruction at `offset` was compiled from, 0 when it is unknown.
+    pub fn line_at(&self, offset: u32) -> u32 {
+        self.position_at(offset).0
+    }
+
+    /// Source line and column the instruction at `offset` was compiled from, `(0, 0)` when it
+    /// is unknown.
+    pub fn position_at(&self, offset: u32) -> (u32, u32) {
+        let ix = match self
+            .positions
+            .binary_search_by_key(&offset, |&(at, _, _)| at)
+        {
+            Ok(ix) => ix,
+            Err(0) => return (0, 0),
+            Err(ix) => ix - 1,
+        };
+        let (_, line, column) = self.positions[ix];
+        (line, column)
+    }
+
+    /// Source map for external debuggers: `(offset, line, column)` triples sorted by
+    /// instruction offset, instructions from `offset` up to the next triple were compiled from
+    /// the statement at `line` and `column`. Lines and columns are 1-based, columns count bytes.
+    pub fn source_map(&self) -> Vec<(usize, u32, u32)> {
+        self.positions
+            .iter()
+            .map(|&(offset, line, column)| (offset as usize, line, column))
+            .collect()
+    }
 }
 
 impl Cell for ByteCode {}
@@ -270,10 +470,21 @@ pub enum TypeFeedBack {
     None,
     X,
 }
+
+/// Counters of the property inline caches, see [VirtualMachine::ic_stats]. Only lookups made
+/// while a feedback slot holds a structure are counted.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct IcStats {
+    /// Property accesses served by the structure cached in their feedback slot.
+    pub hits: usize,
+    /// Property accesses to an object that did not match the cached structure.
+    pub misses: usize,
+}
+
 unsafe impl Trace for TypeFeedBack {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         match self {
-            Self::Structure(ref x, _, _) => x.trace(tracer),
+            Self::Structure(x, _, _) => x.trace(tracer),
             _ => (),
         }
     }
@@ -343,7 +554,7 @@ impl ByteCodeBuilder {
     }
 }
 unsafe impl Trace for ByteCodeBuilder {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.code.trace(tracer);
     }
 }
@@ -148,18 +148,19 @@ pub enum Op {
 
     OP_IN,
 
-    /// Takes 2 values from the stack, treats the top of the stack as property name
-    /// and the next value must be an object, an array or a string.
-    /// If it's an object, pushes the value of its named property onto the stack.
-    /// If it's an array or a string, returns a value at a given position.
+    /// Takes 2 values from the stack, treats the top of the stack as the object and
+    /// the next value as a computed property key.
+    /// Array index keys of objects with dense elements are read straight from the
+    /// elements vector, any other key is converted with ToPropertyKey first.
+    ///
+    /// `( key obj -- obj[key] )`
     OP_GET,
 
-    /// Takes 3 items from the stack: value, property name, object. Sets the given
-    /// property of a given object to a given value, pushes value back onto the
-    /// stack.
+    /// Takes 3 items from the stack: value, property key, object. Sets the given
+    /// property of a given object to a given value, existing dense elements are
+    /// overwritten in place.
     ///
-    ///
-    /// `( a b c -- a[b]=c )`
+    /// `( val key obj -- )`
     OP_SET,
 
     OP_GET_PROP,
@@ -306,7 +307,7 @@ pub enum Op {
     ///
     /// `( A: a -- T: a )`
     ///
-    /// TODO: implement me
+    /// Unlike a `catch` block the `finally` block also runs while an interrupt unwinds.
     OP_TRY_PUSH_FINALLY,
 
     /// Pops a value (bcode offset of `finally` or `catch` block) from "try
@@ -350,21 +351,142 @@ pub enum Op {
     /// - otherwise, thrown value is kept into `v7->vals.thrown_error`
     OP_THROW,
 
-    /// Used when we enter the `catch` block. Takes a varint argument -- index of
-    /// the exception variable name in the literals table.
+    /// Used when we enter the `catch` block. Takes an argument -- index of
+    /// the exception variable name in the names table.
     ///
-    /// Pops the exception value from the stack, creates a private frame,
-    /// sets exception property on it with the given name. pushes this
-    /// private frame to call stack.
+    /// Pops the exception value from the stack, pushes a scope with the
+    /// exception bound to the given name. A `catch` without binding drops
+    /// the value instead.
     ///
     /// `( e -- )`
     OP_ENTER_CATCH,
 
-    /// Ued when we exit from the `catch` block. Merely pops the private frame
-    /// from the call stack.
+    /// Used when we exit from the `catch` block. Merely pops the scope
+    /// pushed by `OP_ENTER_CATCH`.
     ///
     /// `( -- )`
     OP_EXIT_CATCH,
 
+    /// Appends value `a` to the end of array `arr`. If `a` is empty a hole is appended.
+    ///
+    /// `( arr a -- arr )`
+    OP_ARRAY_PUSH,
+
+    /// Appends every element of iterable or array-like `a` to the end of array `arr`.
+    ///
+    /// `( arr a -- arr )`
+    OP_ARRAY_SPREAD,
+
+    /// Defines own enumerable data property `key` with value `a` on `obj`, used by object literals.
+    ///
+    /// `( obj key a -- obj )`
+    OP_INIT_PROP,
+
+    /// Pushes `new.target` of the current frame.
+    ///
+    /// `( -- a )`
+    OP_PUSH_NEW_TARGET,
+
+    /// Creates a new RegExp object from the `pattern` and `flags` strings of a regular expression literal.
+    ///
+    /// `( pattern flags -- regexp )`
+    OP_NEW_REGEXP,
+
+    /// Calls the hook registered with `VirtualMachine::on_debugger`, does nothing if there is none.
+    /// The operand is the 1-based source line of the `debugger` statement.
+    ///
+    /// `( -- )`
+    OP_DEBUGGER,
+
+    /// Takes a 4-byte argument -- index of a local slot resolved at compile time.
+    /// Pushes the value stored in that slot of the current frame.
+    ///
+    /// `( -- a )`
+    OP_GET_LOCAL,
+
+    /// Takes 1 value from the stack and a 4-byte argument -- index of a local slot
+    /// resolved at compile time. Stores the value into that slot of the current frame.
+    ///
+    /// `( a -- )`
+    OP_SET_LOCAL,
+
+    /// Takes the object of a `for...in` statement and pushes the state of the loop: the object,
+    /// an array holding the keys to visit and the index of the next key. Values other than
+    /// objects have no keys to visit.
+    ///
+    /// `( obj -- obj keys index )`
+    OP_FOR_IN_SETUP,
+
+    /// Takes a 4-byte argument -- offset to jump to once all keys were visited, the loop state
+    /// stays on the stack in that case. Otherwise pushes the next key that is still a property
+    /// of the object, keys deleted during the loop are skipped.
+    ///
+    /// `( obj keys index -- obj keys index' key )`
+    OP_FOR_IN_NEXT,
+
+    /// Takes a 4-byte argument -- name id of a private name such as `#x`. Creates a new private
+    /// name and binds it to that name in the current scope, which only the class body can see.
+    ///
+    /// `( -- )`
+    OP_DECL_PRIVATE_NAME,
+
+    /// Stores the function of a private method with its private name.
+    ///
+    /// `( fn name -- )`
+    OP_INIT_PRIVATE_METHOD,
+
+    /// Adds a private element to an object being constructed, throws a TypeError if the object
+    /// already has it. The value is ignored for private methods.
+    ///
+    /// `( obj value name -- )`
+    OP_ADD_PRIVATE,
+
+    /// Reads a private element, throws a TypeError if the object does not have it.
+    ///
+    /// `( obj name -- value )`
+    OP_GET_PRIVATE,
+
+    /// Writes a private field, throws a TypeError if the object does not have it.
+    ///
+    /// `( value obj name -- )`
+    OP_SET_PRIVATE,
+
+    /// Defines a method of a class as a non-enumerable property.
+    ///
+    /// `( obj key fn -- obj )`
+    OP_DEFINE_METHOD,
+
+    /// Creates a `var` binding of global code on the global object, unless it already exists.
+    /// Function code declares its `var`s in the frame scope with OP_DECL_VAR instead.
+    ///
+    /// `( -- )`
+    OP_DECL_GLOBAL_VAR,
+
+    /// Constructs an object literal with static keys from the structure the compiler built for
+    /// it, the operand indexes `ByteCode::structures`. Values are stored to the slots in order.
+    ///
+    /// `( v1 ... vn -- obj )`
+    OP_CREATE_LITERAL_OBJ,
+
+    /// Copies the own enumerable properties of `a` to object literal `obj`, `{...a}`. May
+    /// replace `obj` by an object sharing the structure of `a` when `obj` is still empty.
+    ///
+    /// `( obj a -- obj )`
+    OP_OBJECT_SPREAD,
+
+    /// Reads `obj.name` for a compound assignment `obj.name op= rhs` and keeps `obj` for the
+    /// `OP_UPDATE_PROP` writing the result back. Takes a 4-byte name id and a feedback vector id.
+    ///
+    /// `( obj -- obj old )`
+    OP_GET_PROP_FOR_UPDATE,
+
+    /// Writes the result of a compound assignment back to `obj.name`. Takes a 4-byte name id and
+    /// the feedback vector id of the `OP_GET_PROP_FOR_UPDATE` that read the old value, so the
+    /// property is looked up once when `obj` has the cached structure. Otherwise it is a
+    /// regular put.
+    ///
+    /// `( obj value -- )`
+    OP_UPDATE_PROP,
+
     OP_MAX,
 }
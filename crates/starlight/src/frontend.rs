@@ -1,12 +1,13 @@
 use hashbrown::HashMap;
-use scope_analyzer::{Scope, VisitFnDecl};
+use scope_analyzer::{resolve_locals, Scope, ScopeKind, VisitFnDecl};
+use swc_common::{sync::Lrc, BytePos, SourceFile, Span, Spanned};
 use swc_ecmascript::ast::*;
 
 use crate::{
     bytecode::opcodes::*,
     bytecode::*,
     heap::cell::{Gc, Trace, Tracer},
-    runtime::symbol::Symbol,
+    runtime::{attributes::*, structure::StructureBuilder, symbol::Symbol},
     vm::VirtualMachineRef,
 };
 
@@ -17,6 +18,14 @@ pub struct Compiler {
     builder: ByteCodeBuilder,
     vm: VirtualMachineRef,
     fmap: HashMap<Symbol, u32>,
+    /// Bindings of the compiled function that live in frame slots, see [resolve_locals].
+    locals: HashMap<Symbol, u32>,
+    /// Source the code is compiled from, used to map spans to line numbers.
+    source: Lrc<SourceFile>,
+    /// Whether this is global code, its `var`s are properties of the global object.
+    global_code: bool,
+    /// First early error found, see [Compiler::syntax_error].
+    error: Option<String>,
 }
 impl Compiler {
     pub fn intern_str(&mut self, s: &str) -> Symbol {
@@ -64,7 +73,12 @@ impl Compiler {
         }
     }
 
-    pub fn compile_script(mut vm: VirtualMachineRef, p: &Script) -> Gc<ByteCode> {
+    /// Compiles `p` as global code, fails with the message of the first early error.
+    pub fn compile_script(
+        mut vm: VirtualMachineRef,
+        p: &Script,
+        source: Lrc<SourceFile>,
+    ) -> Result<Gc<ByteCode>, String> {
         let ctx = vm.space().new_local_context();
         let name = vm.intern("<global>");
         let mut code = ctx.new_local(ByteCode::new(&mut vm, name, &[], false));
@@ -75,7 +89,11 @@ impl Compiler {
                 name_map: Default::default(),
             },
             fmap: Default::default(),
+            locals: Default::default(),
             vm: vm,
+            source,
+            global_code: true,
+            error: None,
         };
 
         let is_strict = match p.body.get(0) {
@@ -85,19 +103,43 @@ impl Compiler {
                         let s: &str = &x.sym;
                         s == "use strict"
                     }
+                    Expr::Lit(Lit::Str(x)) => {
+                        let s: &str = &x.value;
+                        s == "use strict"
+                    }
                     _ => false,
                 },
                 _ => false,
             },
             None => false,
         };
-        code.strict = is_strict;
+        code.strict = is_strict || vm.options().strict;
         compiler.compile(&p.body);
         compiler.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
         compiler.builder.emit(Op::OP_RET, &[], false);
-        compiler.builder.finish()
+        match compiler.error.take() {
+            Some(error) => Err(error),
+            None => Ok(compiler.builder.finish()),
+        }
+    }
+
+    /// Records an early error at `pos`. Compilation carries on so the code emitted so far stays
+    /// consistent, but the script is rejected with a `SyntaxError` before any of it runs.
+    fn syntax_error(&mut self, pos: BytePos, msg: &str) {
+        if self.error.is_none() {
+            let (line, column) = self.position_of(pos);
+            self.error = Some(format!("{} at {}:{}", msg, line, column));
+        }
+    }
+    /// Rejects syntax at `span` the compiler does not implement yet, `what` names it.
+    fn unsupported(&mut self, span: Span, what: &str) {
+        self.syntax_error(span.lo, &format!("{} is not supported", what));
     }
     pub fn compile_fn(&mut self, fun: &Function) {
+        self.compile_fn_with_prologue(fun, |_| {});
+    }
+    /// Compiles `fun` with the code emitted by `prologue` running before its body.
+    pub fn compile_fn_with_prologue(&mut self, fun: &Function, prologue: impl FnOnce(&mut Self)) {
         let is_strict = match fun.body {
             Some(ref body) => {
                 if body.stmts.is_empty() {
@@ -109,6 +151,10 @@ impl Compiler {
                                 let s: &str = &x.sym;
                                 s == "use strict"
                             }
+                            Expr::Lit(Lit::Str(x)) => {
+                                let s: &str = &x.value;
+                                s == "use strict"
+                            }
                             _ => false,
                         },
                         _ => false,
@@ -117,7 +163,30 @@ impl Compiler {
             }
             None => false,
         };
-        self.builder.code.strict = is_strict;
+        // code nested in strict code is strict as well.
+        self.builder.code.strict |= is_strict || self.vm.options().strict;
+        for name in resolve_locals(fun) {
+            let name = self.intern_str(&name);
+            let slot = self.builder.code.local_names.len() as u32;
+            self.builder.code.local_names.push(name);
+            self.locals.insert(name, slot);
+        }
+        // a parameter with a default value is assigned it when its argument is `undefined`.
+        for param in fun.params.iter() {
+            if let Pat::Assign(ref assign) = param.pat {
+                if let Pat::Ident(ref id) = &*assign.left {
+                    let name = self.intern(id);
+                    self.emit_get_var(name);
+                    self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                    self.builder.emit(Op::OP_EQ_EQ, &[], false);
+                    let jend = self.cjmp(false);
+                    self.emit(&assign.right, true);
+                    self.emit_set_var(name);
+                    jend(self);
+                }
+            }
+        }
+        prologue(self);
         match fun.body {
             Some(ref body) => {
                 self.compile(&body.stmts);
@@ -128,42 +197,25 @@ impl Compiler {
         self.builder.emit(Op::OP_RET, &[], false);
         self.builder.finish();
     }
+    /// Compiles the body of a script or function.
+    ///
+    /// Declarations are instantiated before the first statement runs: function declarations
+    /// are bound to their function objects first, then `var`s that are not bound yet are
+    /// declared as `undefined`. Function declarations directly in `body` are not evaluated
+    /// again at their position, so an assignment to a `var` of the same name that runs first
+    /// is not undone.
     pub fn compile(&mut self, body: &[Stmt]) {
-        let ctx = self.vm.space().new_local_context();
         let mut i = 0;
+        let mut scope = Scope::analyze_stmts(body);
+
         VisitFnDecl::visit(body, &mut |decl| {
             let name = self.intern(&decl.ident);
-            let params = decl
-                .function
-                .params
-                .iter()
-                .map(|x: &Param| match x.pat {
-                    Pat::Ident(ref x) => self.intern(x),
-                    _ => todo!(),
-                })
-                .collect::<Vec<Symbol>>();
-            let mut code = ctx.new_local(ByteCode::new(&mut self.vm, name, &params, false));
-            let mut compiler = Compiler {
-                builder: ByteCodeBuilder {
-                    code: *code,
-                    val_map: Default::default(),
-                    name_map: Default::default(),
-                },
-                fmap: Default::default(),
-                vm: self.vm,
-            };
-
-            compiler.compile_fn(&decl.function);
-            let ix = self.builder.code.codes.len();
-            self.builder.code.codes.push(*code);
-            self.fmap.insert(name, ix as _);
-            let nix = self.builder.get_sym(name);
-            self.builder.emit(Op::OP_GET_FUNCTION, &[ix as _], false);
-
-            self.builder.emit(Op::OP_SET_VAR, &[nix as _], true);
+            let ix = self.compile_function(name, &decl.function, |_| {});
+            self.fmap.insert(name, ix);
+            self.builder.emit(Op::OP_GET_FUNCTION, &[ix], false);
+            self.emit_decl_var(name);
+            self.emit_set_var(name);
         });
-        let mut scope = Scope::analyze_stmts(body);
-
         for var in scope.vars.iter() {
             match var.1.kind() {
                 scope_analyzer::BindingKind::Var => {
@@ -172,6 +224,15 @@ impl Compiler {
                     if !self.builder.code.var_names.contains(&name) {
                         self.builder.code.var_names.push(name);
                     }
+                    // `var`s of nested functions belong to their own frame.
+                    let nested = var
+                        .1
+                        .path()
+                        .iter()
+                        .any(|kind| matches!(kind, ScopeKind::Function | ScopeKind::Arrow));
+                    if !nested {
+                        self.emit_decl_var(name);
+                    }
                 }
                 scope_analyzer::BindingKind::Function => {
                     let s: &str = &(var.0).0;
@@ -184,43 +245,348 @@ impl Compiler {
             }
         }
 
-        for stmt in body {
-            self.emit_stmt(stmt);
+        for (i, stmt) in body.iter().enumerate() {
+            match stmt {
+                Stmt::Decl(Decl::Fn(_)) => continue,
+                // a trailing expression statement of global code is the value of the script.
+                Stmt::Expr(expr) if self.global_code && i + 1 == body.len() => {
+                    self.mark_line(stmt.span().lo);
+                    self.emit(&expr.expr, true);
+                    self.builder.emit(Op::OP_RET, &[], false);
+                }
+                _ => self.emit_stmt(stmt),
+            }
         }
     }
 
+    /// Compiles `function` into a nested code block and returns its index for `OP_GET_FUNCTION`.
+    pub fn compile_function(
+        &mut self,
+        name: Symbol,
+        function: &Function,
+        prologue: impl FnOnce(&mut Self),
+    ) -> u32 {
+        let ctx = self.vm.space().new_local_context();
+        let mut params = vec![];
+        let mut rest = None;
+        for param in function.params.iter() {
+            match param.pat {
+                Pat::Ident(ref x) if rest.is_none() => params.push(self.intern(x)),
+                Pat::Assign(ref x) if rest.is_none() => match &*x.left {
+                    Pat::Ident(ref x) => params.push(self.intern(x)),
+                    _ => self.unsupported(param.span, "Destructuring parameters"),
+                },
+                Pat::Rest(ref x) if rest.is_none() => match &*x.arg {
+                    Pat::Ident(ref x) => rest = Some(self.intern(x)),
+                    _ => self.unsupported(param.span, "Destructuring parameters"),
+                },
+                _ => self.unsupported(param.span, "Destructuring parameters"),
+            }
+        }
+        let length = function
+            .params
+            .iter()
+            .take_while(|x: &&Param| !matches!(x.pat, Pat::Rest(_) | Pat::Assign(_)))
+            .count();
+        let strict = self.builder.code.strict;
+        let mut code = ByteCode::new(&mut self.vm, name, &params, strict);
+        code.rest = rest;
+        code.length = length as u32;
+        code.simple_params = function
+            .params
+            .iter()
+            .all(|x| matches!(x.pat, Pat::Ident(_)));
+        let code = ctx.new_local(code);
+        let mut compiler = Compiler {
+            builder: ByteCodeBuilder {
+                code: *code,
+                val_map: Default::default(),
+                name_map: Default::default(),
+            },
+            fmap: Default::default(),
+            locals: Default::default(),
+            vm: self.vm,
+            source: self.source.clone(),
+            global_code: false,
+            error: None,
+        };
+
+        compiler.compile_fn_with_prologue(function, prologue);
+        if self.error.is_none() {
+            self.error = compiler.error.take();
+        }
+        let ix = self.builder.code.codes.len();
+        self.builder.code.codes.push(*code);
+        ix as _
+    }
+
+    /// Symbol a private name is bound to in the class scope, `#x` for `#x`.
+    fn private_name(&mut self, name: &PrivateName) -> Symbol {
+        self.intern_str(&format!("#{}", name.id.sym))
+    }
+
+    /// Compiles a class definition and leaves its constructor on the stack.
+    ///
+    /// Private names are bound in a scope pushed for the class body so only its methods can
+    /// refer to them. Instance fields are initialized by the constructor before its body runs.
+    pub fn emit_class(&mut self, class: &Class, name: Symbol) {
+        if let Some(ref super_class) = class.super_class {
+            self.unsupported(super_class.span(), "Class inheritance");
+        }
+        self.builder.emit(Op::OP_PUSH_SCOPE, &[], false);
+        for member in class.body.iter() {
+            let key = match member {
+                ClassMember::PrivateProp(prop) => &prop.key,
+                ClassMember::PrivateMethod(method) => &method.key,
+                _ => continue,
+            };
+            let key = self.private_name(key);
+            let ix = self.builder.get_sym(key);
+            self.builder.emit(Op::OP_DECL_PRIVATE_NAME, &[ix], false);
+        }
+        for member in class.body.iter() {
+            if let ClassMember::PrivateMethod(method) = member {
+                if method.kind != MethodKind::Method {
+                    self.unsupported(method.span, "Private accessors");
+                    continue;
+                }
+                let key = self.private_name(&method.key);
+                let ix = self.compile_function(key, &method.function, |_| {});
+                self.builder.emit(Op::OP_GET_FUNCTION, &[ix], false);
+                self.emit_get_var(key);
+                self.builder.emit(Op::OP_INIT_PRIVATE_METHOD, &[], false);
+            }
+        }
+
+        let ctor = class.body.iter().find_map(|member| match member {
+            ClassMember::Constructor(ctor) => Some(ctor),
+            _ => None,
+        });
+        let mut params = vec![];
+        for param in ctor.iter().flat_map(|ctor| ctor.params.iter()) {
+            match param {
+                ParamOrTsParamProp::Param(param) => params.push(param.clone()),
+                ParamOrTsParamProp::TsParamProp(prop) => {
+                    self.unsupported(prop.span, "Parameter properties")
+                }
+            }
+        }
+        let constructor = Function {
+            params,
+            decorators: vec![],
+            span: class.span,
+            body: Some(
+                ctor.and_then(|ctor| ctor.body.clone())
+                    .unwrap_or(BlockStmt {
+                        span: class.span,
+                        stmts: vec![],
+                    }),
+            ),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: None,
+        };
+        let ix = self.compile_function(name, &constructor, |this| {
+            this.emit_instance_elements(class)
+        });
+        self.builder.emit(Op::OP_GET_FUNCTION, &[ix], false);
+
+        for member in class.body.iter() {
+            match member {
+                ClassMember::Method(method) => {
+                    if method.kind != MethodKind::Method {
+                        self.unsupported(method.span, "Accessors in classes");
+                        continue;
+                    }
+                    if !method.is_static {
+                        self.builder.emit(Op::OP_DUP, &[], false);
+                        let ix = self.builder.get_sym(Symbol::prototype());
+                        self.builder.emit(Op::OP_GET_PROP, &[ix], true);
+                    }
+                    self.emit_prop_name(&method.key);
+                    let key = match &method.key {
+                        PropName::Ident(id) => self.intern(id),
+                        PropName::Str(s) => self.intern_str(&s.value),
+                        _ => self.intern_str("anonymous"),
+                    };
+                    let ix = self.compile_function(key, &method.function, |_| {});
+                    self.builder.emit(Op::OP_GET_FUNCTION, &[ix], false);
+                    self.builder.emit(Op::OP_DEFINE_METHOD, &[], false);
+                    if !method.is_static {
+                        self.builder.emit(Op::OP_DROP, &[], false);
+                    }
+                }
+                ClassMember::ClassProp(prop) if prop.is_static => {
+                    self.emit_class_prop_key(prop);
+                    self.emit_opt(prop.value.as_deref());
+                    self.builder.emit(Op::OP_INIT_PROP, &[], false);
+                }
+                ClassMember::PrivateProp(prop) if prop.is_static => {
+                    self.builder.emit(Op::OP_DUP, &[], false);
+                    self.emit_opt(prop.value.as_deref());
+                    let key = self.private_name(&prop.key);
+                    self.emit_get_var(key);
+                    self.builder.emit(Op::OP_ADD_PRIVATE, &[], false);
+                }
+                ClassMember::PrivateMethod(method) if method.is_static => {
+                    self.builder.emit(Op::OP_DUP, &[], false);
+                    self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                    let key = self.private_name(&method.key);
+                    self.emit_get_var(key);
+                    self.builder.emit(Op::OP_ADD_PRIVATE, &[], false);
+                }
+                _ => (),
+            }
+        }
+        self.builder.emit(Op::OP_POP_SCOPE, &[], false);
+    }
+
+    /// Adds private methods and then fields, in declaration order, to `this`.
+    fn emit_instance_elements(&mut self, class: &Class) {
+        for member in class.body.iter() {
+            if let ClassMember::PrivateMethod(method) = member {
+                if !method.is_static {
+                    self.builder.emit(Op::OP_PUSH_THIS, &[], false);
+                    self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                    let key = self.private_name(&method.key);
+                    self.emit_get_var(key);
+                    self.builder.emit(Op::OP_ADD_PRIVATE, &[], false);
+                }
+            }
+        }
+        for member in class.body.iter() {
+            match member {
+                ClassMember::PrivateProp(prop) if !prop.is_static => {
+                    self.builder.emit(Op::OP_PUSH_THIS, &[], false);
+                    self.emit_opt(prop.value.as_deref());
+                    let key = self.private_name(&prop.key);
+                    self.emit_get_var(key);
+                    self.builder.emit(Op::OP_ADD_PRIVATE, &[], false);
+                }
+                ClassMember::ClassProp(prop) if !prop.is_static => {
+                    self.builder.emit(Op::OP_PUSH_THIS, &[], false);
+                    self.emit_class_prop_key(prop);
+                    self.emit_opt(prop.value.as_deref());
+                    self.builder.emit(Op::OP_INIT_PROP, &[], false);
+                    self.builder.emit(Op::OP_DROP, &[], false);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn emit_class_prop_key(&mut self, prop: &ClassProp) {
+        match &*prop.key {
+            Expr::Ident(id) if !prop.computed => {
+                self.emit_prop_name(&PropName::Ident(id.clone()));
+            }
+            key => self.emit(key, true),
+        }
+    }
+
+    /// Pushes the value of `expr`, or `undefined` when there is none.
+    fn emit_opt(&mut self, expr: Option<&Expr>) {
+        match expr {
+            Some(expr) => self.emit(expr, true),
+            None => self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false),
+        }
+    }
+
+    fn emit_member_obj(&mut self, obj: &ExprOrSuper) {
+        match obj {
+            ExprOrSuper::Expr(expr) => self.emit(expr, true),
+            ExprOrSuper::Super(sup) => {
+                self.unsupported(sup.span, "`super`");
+                self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+            }
+        }
+    }
+
+    /// `obj.#x = value`
+    fn emit_private_assign(&mut self, member: &MemberExpr, value: &Expr) {
+        self.emit(value, true);
+        self.emit_member_obj(&member.obj);
+        self.emit(&member.prop, true);
+        self.builder.emit(Op::OP_SET_PRIVATE, &[], false);
+    }
+
+    /// `x op= value`
+    fn emit_compound_var(&mut self, name: Symbol, op: AssignOp, value: &Expr) {
+        self.emit_get_var(name);
+        self.emit(value, true);
+        self.builder.emit(compound_op(op), &[], false);
+        self.emit_set_var(name);
+    }
+
+    /// `obj.x op= value`. The object is evaluated first, then the old value is read and only
+    /// then `value`. `OP_UPDATE_PROP` writes the result back through the feedback slot of the
+    /// `OP_GET_PROP_FOR_UPDATE` that read it.
+    fn emit_compound_member(&mut self, member: &MemberExpr, op: AssignOp, value: &Expr) {
+        let name = match (member.computed, &*member.prop) {
+            (false, Expr::Ident(id)) => {
+                let s: &str = &id.sym;
+                let name = self.vm.intern(s);
+                self.builder.get_sym(name)
+            }
+            _ => {
+                self.unsupported(
+                    member.span,
+                    "Compound assignment to a computed or private member",
+                );
+                self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                return;
+            }
+        };
+        self.emit_member_obj(&member.obj);
+        let feedback = self.builder.code.feedback.len() as u32;
+        self.builder.emit(Op::OP_GET_PROP_FOR_UPDATE, &[name], true);
+        self.emit(value, true);
+        self.builder.emit(compound_op(op), &[], false);
+        self.builder
+            .emit(Op::OP_UPDATE_PROP, &[name, feedback], false);
+    }
+
     pub fn emit(&mut self, expr: &Expr, used: bool) {
         match expr {
             Expr::Call(call) => {
                 for arg in call.args.iter().rev() {
-                    if arg.spread.is_some() {
-                        todo!("spread");
+                    if let Some(spread) = arg.spread {
+                        self.unsupported(spread, "Spread arguments");
                     }
                     self.emit(&arg.expr, true);
                 }
 
                 match call.callee {
-                    ExprOrSuper::Super(_) => todo!(), // todo super call
+                    ExprOrSuper::Super(ref sup) => {
+                        self.unsupported(sup.span, "`super` calls");
+                        self.builder.emit(Op::OP_PUSH_EMPTY, &[], false);
+                        self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                    }
                     ExprOrSuper::Expr(ref expr) => match &**expr {
+                        Expr::Member(member) if matches!(&*member.prop, Expr::PrivateName(_)) => {
+                            self.emit_member_obj(&member.obj);
+                            self.builder.emit(Op::OP_DUP, &[], false);
+                            self.emit(&member.prop, true);
+                            self.builder.emit(Op::OP_GET_PRIVATE, &[], false);
+                        }
                         Expr::Member(member) => {
-                            let name = if let Expr::Ident(id) = &*member.prop {
-                                let s: &str = &id.sym;
-                                let name = self.intern_str(s);
-                                self.builder.get_sym(name)
-                            } else {
-                                unreachable!()
-                            };
-                            match member.obj {
-                                ExprOrSuper::Expr(ref expr) => {
-                                    self.emit(expr, true);
-                                    self.builder.emit(Op::OP_DUP, &[], false);
+                            self.emit_member_obj(&member.obj);
+                            self.builder.emit(Op::OP_DUP, &[], false);
+                            match (member.computed, &*member.prop) {
+                                (false, Expr::Ident(id)) => {
+                                    let s: &str = &id.sym;
+                                    let name = self.intern_str(s);
+                                    let name = self.builder.get_sym(name);
+                                    self.builder.emit(Op::OP_GET_PROP, &[name], true);
                                 }
-                                ExprOrSuper::Super(_super) => {
-                                    todo!()
+                                // `obj[key]()`, the key goes below the object `OP_GET` pops.
+                                (_, prop) => {
+                                    self.emit(prop, true);
+                                    self.builder.emit(Op::OP_SWAP, &[], false);
+                                    self.builder.emit(Op::OP_GET, &[], false);
                                 }
                             }
-
-                            self.builder.emit(Op::OP_GET_PROP, &[name], true);
                         }
                         _ => {
                             self.builder.emit(Op::OP_PUSH_EMPTY, &[], false);
@@ -236,8 +602,8 @@ impl Compiler {
                 let argc = call.args.as_ref().map(|x| x.len() as u32).unwrap_or(0);
                 if let Some(ref args) = call.args {
                     for arg in args.iter().rev() {
-                        if arg.spread.is_some() {
-                            todo!("spread");
+                        if let Some(spread) = arg.spread {
+                            self.unsupported(spread, "Spread arguments");
                         }
                         self.emit(&arg.expr, true);
                     }
@@ -254,17 +620,50 @@ impl Compiler {
                 }
             }
 
+            Expr::This(_) => {
+                if used {
+                    self.builder.emit(Op::OP_PUSH_THIS, &[], false);
+                }
+            }
+            Expr::MetaProp(meta) => {
+                let (meta, prop): (&str, &str) = (&meta.meta.sym, &meta.prop.sym);
+                match (meta, prop) {
+                    ("new", "target") => {
+                        if used {
+                            self.builder.emit(Op::OP_PUSH_NEW_TARGET, &[], false);
+                        }
+                    }
+                    _ => {
+                        self.unsupported(expr.span(), "`import.meta`");
+                        if used {
+                            self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                        }
+                    }
+                }
+            }
+            Expr::Paren(paren) => {
+                self.emit(&paren.expr, used);
+            }
             Expr::Ident(name) => {
                 let s: &str = &name.sym;
                 let name = self.intern_str(s);
-                let ix = self.builder.get_sym(name);
                 if used {
-                    self.builder.emit(Op::OP_GET_VAR, &[ix], true);
+                    self.emit_get_var(name);
                 }
             }
 
             Expr::Member(member) => {
-                let name = if let Expr::Ident(id) = &*member.prop {
+                if let Expr::PrivateName(private) = &*member.prop {
+                    self.emit_member_obj(&member.obj);
+                    let name = self.private_name(private);
+                    self.emit_get_var(name);
+                    self.builder.emit(Op::OP_GET_PRIVATE, &[], false);
+                    if !used {
+                        self.builder.emit(Op::OP_DROP, &[], false);
+                    }
+                    return;
+                }
+                let name = if let (false, Expr::Ident(id)) = (member.computed, &*member.prop) {
                     let s: &str = &id.sym;
                     let name = self.vm.intern(s);
                     Some(self.builder.get_sym(name))
@@ -272,14 +671,7 @@ impl Compiler {
                     self.emit(&member.prop, true);
                     None
                 };
-                match member.obj {
-                    ExprOrSuper::Expr(ref expr) => {
-                        self.emit(expr, true);
-                    }
-                    ExprOrSuper::Super(_super) => {
-                        todo!()
-                    }
-                }
+                self.emit_member_obj(&member.obj);
 
                 if let Some(ix) = name {
                     self.builder.emit(Op::OP_GET_PROP, &[ix], true);
@@ -290,17 +682,40 @@ impl Compiler {
                     self.builder.emit(Op::OP_DROP, &[], false);
                 }
             }
+            Expr::Assign(assign)
+                if matches!(
+                    assign.op,
+                    AssignOp::ExpAssign
+                        | AssignOp::AndAssign
+                        | AssignOp::OrAssign
+                        | AssignOp::NullishAssign
+                ) =>
+            {
+                self.unsupported(assign.span, "This assignment operator");
+            }
             Expr::Assign(assign) => match &assign.left {
                 PatOrExpr::Pat(x) => match &**x {
+                    Pat::Ident(id) if assign.op != AssignOp::Assign => {
+                        let name = self.intern(id);
+                        self.emit_compound_var(name, assign.op, &assign.right);
+                    }
                     Pat::Ident(id) => {
-                        self.emit(&assign.right, true);
-                        let ix = self.get_ident(id);
-                        self.builder.emit(Op::OP_SET_VAR, &[ix], true);
+                        let name = self.intern(id);
+                        self.emit_named(&assign.right, name);
+                        self.emit_set_var(name);
                     }
                     Pat::Expr(e) => match &**e {
+                        Expr::Member(member) if assign.op != AssignOp::Assign => {
+                            self.emit_compound_member(member, assign.op, &assign.right);
+                        }
+                        Expr::Member(member) if matches!(&*member.prop, Expr::PrivateName(_)) => {
+                            self.emit_private_assign(member, &assign.right);
+                        }
                         Expr::Member(member) => {
                             self.emit(&assign.right, true);
-                            let name = if let Expr::Ident(id) = &*member.prop {
+                            let name = if let (false, Expr::Ident(id)) =
+                                (member.computed, &*member.prop)
+                            {
                                 let s: &str = &id.sym;
                                 let name = self.vm.intern(s);
                                 Some(self.builder.get_sym(name))
@@ -308,14 +723,7 @@ impl Compiler {
                                 self.emit(&member.prop, true);
                                 None
                             };
-                            match member.obj {
-                                ExprOrSuper::Expr(ref expr) => {
-                                    self.emit(expr, true);
-                                }
-                                ExprOrSuper::Super(_super) => {
-                                    todo!()
-                                }
-                            }
+                            self.emit_member_obj(&member.obj);
 
                             if let Some(ix) = name {
                                 self.builder.emit(Op::OP_SET_PROP, &[ix], true);
@@ -323,29 +731,38 @@ impl Compiler {
                                 self.builder.emit(Op::OP_SET, &[], false);
                             }
                         }
-                        _ => todo!(),
+                        _ => self.unsupported(assign.span, "This assignment target"),
                     },
-                    _ => todo!(),
+                    _ => self.unsupported(assign.span, "Destructuring assignment"),
                 },
                 PatOrExpr::Expr(e) => match &**e {
-                    Expr::Member(member) => {
-                        self.emit(&assign.right, true);
-                        let name = if let Expr::Ident(id) = &*member.prop {
-                            let s: &str = &id.sym;
-                            let name = self.vm.intern(s);
-                            Some(self.builder.get_sym(name))
+                    Expr::Ident(id) => {
+                        let name = self.intern(id);
+                        if assign.op == AssignOp::Assign {
+                            self.emit_named(&assign.right, name);
+                            self.emit_set_var(name);
                         } else {
-                            self.emit(&member.prop, true);
-                            None
-                        };
-                        match member.obj {
-                            ExprOrSuper::Expr(ref expr) => {
-                                self.emit(expr, true);
-                            }
-                            ExprOrSuper::Super(_super) => {
-                                todo!()
-                            }
+                            self.emit_compound_var(name, assign.op, &assign.right);
                         }
+                    }
+                    Expr::Member(member) if assign.op != AssignOp::Assign => {
+                        self.emit_compound_member(member, assign.op, &assign.right);
+                    }
+                    Expr::Member(member) if matches!(&*member.prop, Expr::PrivateName(_)) => {
+                        self.emit_private_assign(member, &assign.right);
+                    }
+                    Expr::Member(member) => {
+                        self.emit(&assign.right, true);
+                        let name =
+                            if let (false, Expr::Ident(id)) = (member.computed, &*member.prop) {
+                                let s: &str = &id.sym;
+                                let name = self.vm.intern(s);
+                                Some(self.builder.get_sym(name))
+                            } else {
+                                self.emit(&member.prop, true);
+                                None
+                            };
+                        self.emit_member_obj(&member.obj);
 
                         if let Some(ix) = name {
                             self.builder.emit(Op::OP_SET_PROP, &[ix], true);
@@ -353,12 +770,148 @@ impl Compiler {
                             self.builder.emit(Op::OP_SET, &[], false);
                         }
                     }
-                    _ => todo!(),
+                    _ => self.unsupported(assign.span, "This assignment target"),
                 },
             },
+            Expr::Object(object_lit) if self.static_keys(object_lit).is_some() => {
+                let keys = self.static_keys(object_lit).unwrap();
+                for (prop, &name) in object_lit.props.iter().zip(keys.iter()) {
+                    if let PropOrSpread::Prop(prop) = prop {
+                        match &**prop {
+                            Prop::Shorthand(id) => self.emit(&Expr::Ident(id.clone()), true),
+                            Prop::KeyValue(kv) => self.emit_named(&kv.value, name),
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+                let ix = self.literal_structure(keys);
+                self.builder.emit(Op::OP_CREATE_LITERAL_OBJ, &[ix], false);
+                if !used {
+                    self.builder.emit(Op::OP_DROP, &[], false);
+                }
+            }
+            Expr::Object(object_lit) => {
+                self.builder.emit(Op::OP_CREATE_OBJ, &[], false);
+                for prop in object_lit.props.iter() {
+                    match prop {
+                        PropOrSpread::Prop(prop) => match &**prop {
+                            Prop::Shorthand(id) => {
+                                self.emit_prop_name(&PropName::Ident(id.clone()));
+                                self.emit(&Expr::Ident(id.clone()), true);
+                            }
+                            Prop::KeyValue(kv) => {
+                                self.emit_prop_name(&kv.key);
+                                match &kv.key {
+                                    PropName::Ident(id) => {
+                                        let name = self.intern(id);
+                                        self.emit_named(&kv.value, name);
+                                    }
+                                    PropName::Str(s) => {
+                                        let name = self.intern_str(&s.value);
+                                        self.emit_named(&kv.value, name);
+                                    }
+                                    _ => self.emit(&kv.value, true),
+                                }
+                            }
+                            prop => {
+                                self.unsupported(
+                                    prop.span(),
+                                    "Accessors and methods in object literals",
+                                );
+                                continue;
+                            }
+                        },
+                        PropOrSpread::Spread(spread) => {
+                            self.emit(&spread.expr, true);
+                            self.builder.emit(Op::OP_OBJECT_SPREAD, &[], false);
+                            continue;
+                        }
+                    }
+                    self.builder.emit(Op::OP_INIT_PROP, &[], false);
+                }
+                if !used {
+                    self.builder.emit(Op::OP_DROP, &[], false);
+                }
+            }
+            Expr::Array(array_lit) => {
+                self.builder.emit(Op::OP_CREATE_ARR, &[], false);
+                for elem in array_lit.elems.iter() {
+                    match elem {
+                        Some(elem) => {
+                            self.emit(&elem.expr, true);
+                            if elem.spread.is_some() {
+                                self.builder.emit(Op::OP_ARRAY_SPREAD, &[], false);
+                            } else {
+                                self.builder.emit(Op::OP_ARRAY_PUSH, &[], false);
+                            }
+                        }
+                        None => {
+                            self.builder.emit(Op::OP_PUSH_EMPTY, &[], false);
+                            self.builder.emit(Op::OP_ARRAY_PUSH, &[], false);
+                        }
+                    }
+                }
+                if !used {
+                    self.builder.emit(Op::OP_DROP, &[], false);
+                }
+            }
+            Expr::Unary(unary) => match unary.op {
+                UnaryOp::Delete => {
+                    match &*unary.arg {
+                        Expr::Member(member) => {
+                            self.emit_member_obj(&member.obj);
+                            match &*member.prop {
+                                Expr::Ident(id) if !member.computed => {
+                                    let val = id.sym.to_string();
+                                    let mut vm = self.vm;
+                                    let ix = self.builder.get_val(&mut vm, Val::Str(val));
+                                    self.builder.emit(Op::OP_PUSH_LIT, &[ix], false);
+                                }
+                                prop => self.emit(prop, true),
+                            }
+                            self.builder.emit(Op::OP_DELETE, &[], false);
+                        }
+                        Expr::Ident(id) => {
+                            if self.builder.code.strict {
+                                self.syntax_error(
+                                    id.span.lo,
+                                    "Delete of an unqualified identifier in strict mode",
+                                );
+                            }
+                            let val = id.sym.to_string();
+                            let mut vm = self.vm;
+                            let ix = self.builder.get_val(&mut vm, Val::Str(val));
+                            self.builder.emit(Op::OP_PUSH_LIT, &[ix], false);
+                            self.builder.emit(Op::OP_DELETE_VAR, &[], false);
+                        }
+                        arg => {
+                            // `delete` of anything that is not a reference evaluates
+                            // the operand and always yields `true`.
+                            self.emit(arg, false);
+                            self.builder.emit(Op::OP_PUSH_TRUE, &[], false);
+                        }
+                    }
+                    if !used {
+                        self.builder.emit(Op::OP_DROP, &[], false);
+                    }
+                }
+                _ => {
+                    self.unsupported(unary.span, "This unary operator");
+                    if used {
+                        self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                    }
+                }
+            },
+            Expr::Fn(fun) => {
+                let name = match fun.ident {
+                    Some(ref id) => self.intern(id),
+                    None => self.intern_str(""),
+                };
+                self.emit_fn_expr(fun, name, used);
+            }
             Expr::Bin(binary) => {
-                self.emit(&binary.right, true);
                 self.emit(&binary.left, true);
+                self.emit(&binary.right, true);
                 match binary.op {
                     BinaryOp::Add => {
                         self.builder.emit(Op::OP_ADD, &[], false);
@@ -372,6 +925,9 @@ impl Compiler {
                     BinaryOp::Div => {
                         self.builder.emit(Op::OP_DIV, &[], false);
                     }
+                    BinaryOp::Mod => {
+                        self.builder.emit(Op::OP_REM, &[], false);
+                    }
                     BinaryOp::EqEq => {
                         self.builder.emit(Op::OP_EQ, &[], false);
                     }
@@ -382,29 +938,103 @@ impl Compiler {
                     BinaryOp::GtEq => self.builder.emit(Op::OP_GE, &[], false),
                     BinaryOp::Lt => self.builder.emit(Op::OP_LT, &[], false),
                     BinaryOp::LtEq => self.builder.emit(Op::OP_LE, &[], false),
-                    _ => todo!(),
+                    _ => {
+                        self.unsupported(binary.span, "This binary operator");
+                        self.builder.emit(Op::OP_DROP, &[], false);
+                    }
                 }
 
                 if !used {
                     self.builder.emit(Op::OP_DROP, &[], false);
                 }
             }
-            _ => todo!(),
+            _ => {
+                self.unsupported(expr.span(), "This expression");
+                if used {
+                    self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                }
+            }
+        }
+    }
+
+    /// Emits `expr`, an anonymous function expression is named `name` after the binding or
+    /// property it is assigned to.
+    fn emit_named(&mut self, expr: &Expr, name: Symbol) {
+        match expr {
+            Expr::Fn(fun) if fun.ident.is_none() => self.emit_fn_expr(fun, name, true),
+            _ => self.emit(expr, true),
+        }
+    }
+
+    /// Emits the function object of `fun`. The name of a named function expression is not
+    /// bound inside of its body.
+    fn emit_fn_expr(&mut self, fun: &FnExpr, name: Symbol, used: bool) {
+        let ix = self.compile_function(name, &fun.function, |_| {});
+        if used {
+            self.builder.emit(Op::OP_GET_FUNCTION, &[ix], false);
+        }
+    }
+
+    /// Keys of a non-empty object literal made only of identifier and string keys that are not
+    /// array indices, each appearing once. Such a literal always ends up with the same structure.
+    fn static_keys(&mut self, object_lit: &ObjectLit) -> Option<Vec<Symbol>> {
+        if object_lit.props.is_empty() {
+            return None;
+        }
+        let mut keys = Vec::with_capacity(object_lit.props.len());
+        for prop in object_lit.props.iter() {
+            let name = match prop {
+                PropOrSpread::Prop(prop) => match &**prop {
+                    Prop::Shorthand(id) => self.intern(id),
+                    Prop::KeyValue(kv) => match &kv.key {
+                        PropName::Ident(id) => self.intern(id),
+                        PropName::Str(s) => self.intern_str(&s.value),
+                        _ => return None,
+                    },
+                    _ => return None,
+                },
+                PropOrSpread::Spread(_) => return None,
+            };
+            if !matches!(name, Symbol::Key(_)) || keys.contains(&name) {
+                return None;
+            }
+            keys.push(name);
         }
+        Some(keys)
+    }
+
+    /// Structure an object literal with `keys` ends up with, built once for each key list.
+    /// Returns its index in `ByteCode::structures`.
+    fn literal_structure(&mut self, keys: Vec<Symbol>) -> u32 {
+        let mut vm = self.vm;
+        let structure = match vm.global_data().literal_structures.get(&keys) {
+            Some(&structure) => structure,
+            None => {
+                let prototype = vm.global_data().object_prototype;
+                let mut builder = StructureBuilder::new(prototype);
+                for &key in keys.iter() {
+                    builder.add(key, create_data(AttrExternal::new(Some(W | E | C))));
+                }
+                let structure = builder.build(&mut vm, false, false);
+                vm.global_data_mut()
+                    .literal_structures
+                    .insert(keys, structure);
+                vm.structure_stats_mut().literal_structures += 1;
+                structure
+            }
+        };
+        let ix = self.builder.code.structures.len() as u32;
+        self.builder.code.structures.push(structure);
+        ix
     }
 
     pub fn emit_stmt(&mut self, stmt: &Stmt) {
+        self.mark_line(stmt.span().lo);
         match stmt {
             Stmt::Expr(expr) => {
                 self.emit(&expr.expr, false);
             }
-            Stmt::Block(block) => {
-                self.builder.emit(Op::OP_PUSH_SCOPE, &[], false);
-                for stmt in block.stmts.iter() {
-                    self.emit_stmt(stmt);
-                }
-                self.builder.emit(Op::OP_POP_SCOPE, &[], false);
-            }
+            Stmt::Block(block) => self.emit_block(block),
             Stmt::Return(ret) => {
                 match ret.arg {
                     Some(ref arg) => self.emit(&**arg, true),
@@ -437,13 +1067,180 @@ impl Compiler {
                     let sym = self.vm.intern(s);
                     let ix = *self.fmap.get(&sym).unwrap();
                     self.builder.emit(Op::OP_GET_FUNCTION, &[ix], false);
-                    let nix = self.builder.get_sym(sym);
-                    self.builder.emit(Op::OP_SET_VAR, &[nix], true);
+                    self.emit_set_var(sym);
+                }
+                Decl::Class(class) => {
+                    let name = self.intern(&class.ident);
+                    if !self.builder.code.var_names.contains(&name) {
+                        self.builder.code.var_names.push(name);
+                    }
+                    self.emit_class(&class.class, name);
+                    self.emit_decl_var(name);
+                    self.emit_set_var(name);
                 }
                 _ => (),
             },
+            Stmt::ForIn(for_in) => {
+                self.emit(&for_in.right, true);
+                self.builder.emit(Op::OP_FOR_IN_SETUP, &[], false);
+                let head = self.builder.code.code.len();
+                self.builder.emit(Op::OP_FOR_IN_NEXT, &[0], false);
+                let name = match &for_in.left {
+                    VarDeclOrPat::VarDecl(VarDecl { decls, .. }) => match &decls[0].name {
+                        Pat::Ident(id) => Some(self.intern(id)),
+                        _ => None,
+                    },
+                    VarDeclOrPat::Pat(Pat::Ident(id)) => Some(self.intern(id)),
+                    _ => None,
+                };
+                match name {
+                    Some(name) => self.emit_set_var(name),
+                    None => {
+                        self.unsupported(for_in.left.span(), "Destructuring in `for-in` heads");
+                        self.builder.emit(Op::OP_DROP, &[], false);
+                    }
+                }
+                self.emit_stmt(&for_in.body);
+                let p = self.builder.code.code.len();
+                let back = head as i32 - (p + 5) as i32;
+                self.builder.emit(Op::OP_JMP, &[back as u32], false);
+                let to = (self.builder.code.code.len() - (head + 5)) as u32;
+                self.builder.code.code[head + 1..head + 5].copy_from_slice(&to.to_le_bytes());
+                // drop the loop state.
+                for _ in 0..3 {
+                    self.builder.emit(Op::OP_DROP, &[], false);
+                }
+            }
+            Stmt::While(while_stmt) => {
+                let head = self.builder.code.code.len();
+                self.emit(&while_stmt.test, true);
+                let jend = self.cjmp(false);
+                self.emit_stmt(&while_stmt.body);
+                let p = self.builder.code.code.len();
+                let back = head as i32 - (p + 5) as i32;
+                self.builder.emit(Op::OP_JMP, &[back as u32], false);
+                jend(self);
+            }
+            Stmt::Try(try_stmt) => self.emit_try(try_stmt),
+            Stmt::Throw(throw) => {
+                self.emit(&throw.arg, true);
+                self.builder.emit(Op::OP_THROW, &[], false);
+            }
+            Stmt::Debugger(debugger) => {
+                let line = self.position_of(debugger.span.lo).0;
+                self.builder.emit(Op::OP_DEBUGGER, &[line], false);
+            }
 
-            _ => todo!(),
+            _ => self.unsupported(stmt.span(), "This statement"),
+        }
+    }
+    /// Line and byte column of `pos`, both are 1-based and `(0, 0)` means the position is unknown.
+    fn position_of(&self, pos: BytePos) -> (u32, u32) {
+        match self.source.lookup_line(pos) {
+            Some(line) => {
+                let start = self.source.lines[line];
+                (line as u32 + 1, (pos - start).0 + 1)
+            }
+            None => (0, 0),
+        }
+    }
+    /// Records that code emitted from here on is compiled from the statement at `pos`.
+    fn mark_line(&mut self, pos: BytePos) {
+        let (line, column) = self.position_of(pos);
+        let offset = self.builder.code.code.len() as u32;
+        let positions = &mut self.builder.code.positions;
+        match positions.last_mut() {
+            Some(last) if (last.1, last.2) == (line, column) => {}
+            Some(last) if last.0 == offset => *last = (offset, line, column),
+            _ => positions.push((offset, line, column)),
+        }
+    }
+    pub fn emit_block(&mut self, block: &BlockStmt) {
+        self.builder.emit(Op::OP_PUSH_SCOPE, &[], false);
+        for stmt in block.stmts.iter() {
+            self.emit_stmt(stmt);
+        }
+        self.builder.emit(Op::OP_POP_SCOPE, &[], false);
+    }
+    /// A thrown value unwinds to the innermost handler pushed by `OP_TRY_PUSH_*`, which
+    /// restores the operand stack and scope of the `try` statement and pushes the value.
+    /// `finally` is emitted twice, once after normal completion and once on a path that runs
+    /// it and rethrows. A `return` from `try` or `catch` does not run `finally`.
+    pub fn emit_try(&mut self, stmt: &TryStmt) {
+        let rethrow = stmt.finalizer.as_ref().map(|_| self.try_push(true));
+        match stmt.handler {
+            Some(ref handler) => {
+                let catch = self.try_push(false);
+                self.emit_block(&stmt.block);
+                self.builder.emit(Op::OP_TRY_POP, &[], false);
+                let end = self.jmp();
+                catch(self);
+                match handler.param {
+                    Some(Pat::Ident(ref id)) => {
+                        let name = self.intern(id);
+                        let ix = self.builder.get_sym(name);
+                        self.builder.emit(Op::OP_ENTER_CATCH, &[ix], false);
+                        for stmt in handler.body.stmts.iter() {
+                            self.emit_stmt(stmt);
+                        }
+                        self.builder.emit(Op::OP_EXIT_CATCH, &[], false);
+                    }
+                    Some(ref pat) => {
+                        self.unsupported(pat.span(), "Destructuring in `catch` parameters");
+                        self.builder.emit(Op::OP_DROP, &[], false);
+                        self.emit_block(&handler.body);
+                    }
+                    // `catch {}` has no binding to create, the thrown value is dropped.
+                    None => {
+                        self.builder.emit(Op::OP_DROP, &[], false);
+                        self.emit_block(&handler.body);
+                    }
+                }
+                end(self);
+            }
+            None => self.emit_block(&stmt.block),
+        }
+        if let (Some(rethrow), Some(finalizer)) = (rethrow, &stmt.finalizer) {
+            self.builder.emit(Op::OP_TRY_POP, &[], false);
+            self.emit_block(finalizer);
+            let end = self.jmp();
+            rethrow(self);
+            self.emit_block(finalizer);
+            self.builder.emit(Op::OP_THROW, &[], false);
+            end(self);
+        }
+    }
+    /// Pushes the value of variable `name`, read from its frame slot when it has one.
+    pub fn emit_get_var(&mut self, name: Symbol) {
+        match self.locals.get(&name) {
+            Some(&slot) => self.builder.emit(Op::OP_GET_LOCAL, &[slot], false),
+            None => {
+                let ix = self.builder.get_sym(name);
+                self.builder.emit(Op::OP_GET_VAR, &[ix], true);
+            }
+        }
+    }
+    /// Pops a value and assigns it to variable `name`, through its frame slot when it has one.
+    /// Creates the binding of a `var` or function declaration unless it lives in a frame slot.
+    /// Declaring an existing binding does nothing.
+    pub fn emit_decl_var(&mut self, name: Symbol) {
+        if self.locals.contains_key(&name) {
+            return;
+        }
+        let ix = self.builder.get_sym(name);
+        if self.global_code {
+            self.builder.emit(Op::OP_DECL_GLOBAL_VAR, &[ix], false);
+        } else {
+            self.builder.emit(Op::OP_DECL_VAR, &[ix], false);
+        }
+    }
+    pub fn emit_set_var(&mut self, name: Symbol) {
+        match self.locals.get(&name) {
+            Some(&slot) => self.builder.emit(Op::OP_SET_LOCAL, &[slot], false),
+            None => {
+                let ix = self.builder.get_sym(name);
+                self.builder.emit(Op::OP_SET_VAR, &[ix], true);
+            }
         }
     }
     pub fn cjmp(&mut self, cond: bool) -> impl FnOnce(&mut Self) {
@@ -480,6 +1277,41 @@ impl Compiler {
             //this.builder.code.code[p] = ins as u8;
         }
     }
+    /// Emits `OP_TRY_PUSH_CATCH` or `OP_TRY_PUSH_FINALLY`, the returned closure points its
+    /// handler at the current position.
+    pub fn try_push(&mut self, finally: bool) -> impl FnOnce(&mut Self) {
+        let p = self.builder.code.code.len();
+        let op = if finally {
+            Op::OP_TRY_PUSH_FINALLY
+        } else {
+            Op::OP_TRY_PUSH_CATCH
+        };
+        self.builder.emit(op, &[0], false);
+
+        move |this: &mut Self| {
+            let to = this.builder.code.code.len() - (p + 5);
+            let bytes = (to as u32).to_le_bytes();
+            this.builder.code.code[p + 1..p + 5].copy_from_slice(&bytes);
+        }
+    }
+    /// Pushes property name of an object literal entry.
+    pub fn emit_prop_name(&mut self, name: &PropName) {
+        match name {
+            PropName::Ident(id) => {
+                let val = id.sym.to_string();
+                let mut vm = self.vm;
+                let ix = self.builder.get_val(&mut vm, Val::Str(val));
+                self.builder.emit(Op::OP_PUSH_LIT, &[ix], false);
+            }
+            PropName::Str(x) => self.emit_lit(&Lit::Str(x.clone())),
+            PropName::Num(x) => self.emit_lit(&Lit::Num(x.clone())),
+            PropName::Computed(computed) => self.emit(&computed.expr, true),
+            _ => {
+                self.unsupported(name.span(), "BigInt property names");
+                self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+            }
+        }
+    }
     pub fn emit_lit(&mut self, lit: &Lit) {
         match lit {
             Lit::Null(_) => self.builder.emit(Op::OP_PUSH_NULL, &[], false),
@@ -508,7 +1340,18 @@ impl Compiler {
                     self.builder.emit(Op::OP_PUSH_FALSE, &[], false);
                 }
             }
-            _ => todo!("Other literals"),
+            Lit::Regex(x) => {
+                let mut vm = self.vm;
+                let pattern = self.builder.get_val(&mut vm, Val::Str(x.exp.to_string()));
+                let flags = self.builder.get_val(&mut vm, Val::Str(x.flags.to_string()));
+                self.builder.emit(Op::OP_PUSH_LIT, &[pattern], false);
+                self.builder.emit(Op::OP_PUSH_LIT, &[flags], false);
+                self.builder.emit(Op::OP_NEW_REGEXP, &[], false);
+            }
+            _ => {
+                self.unsupported(lit.span(), "This literal");
+                self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+            }
         }
     }
     pub fn emit_var_decl(&mut self, var: &VarDecl) {
@@ -519,7 +1362,7 @@ impl Compiler {
                         let s: &str = &name.sym;
                         let name = self.vm.intern(s);
                         let ix = self.builder.get_sym(name);
-                        self.emit(init, true);
+                        self.emit_named(init, name);
                         match var.kind {
                             VarDeclKind::Let => self.builder.emit(Op::OP_DECL_LET, &[ix], true),
                             VarDeclKind::Const => {
@@ -527,7 +1370,7 @@ impl Compiler {
                             }
                             VarDeclKind::Var => {
                                 //self.builder.code.var_names.push(name);
-                                self.builder.emit(Op::OP_SET_VAR, &[ix], true);
+                                self.emit_set_var(name);
                             }
                         }
                     }
@@ -535,26 +1378,47 @@ impl Compiler {
                         let s: &str = &name.sym;
                         let name = self.vm.intern(s);
                         let ix = self.builder.get_sym(name);
-                        self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
                         match var.kind {
-                            VarDeclKind::Let => self.builder.emit(Op::OP_DECL_LET, &[ix], true),
+                            VarDeclKind::Let => {
+                                self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
+                                self.builder.emit(Op::OP_DECL_LET, &[ix], true)
+                            }
                             VarDeclKind::Const => {
+                                self.builder.emit(Op::OP_PUSH_UNDEFINED, &[], false);
                                 self.builder.emit(Op::OP_DECL_IMMUTABLE, &[ix], true)
                             }
-                            VarDeclKind::Var => {
-                                self.builder.emit(Op::OP_SET_VAR, &[ix], true);
-                                self.builder.code.var_names.push(name);
-                            }
+                            // the binding was created when the body was entered, a `var`
+                            // without initializer keeps its current value.
+                            VarDeclKind::Var => (),
                         }
                     }
                 },
-                _ => todo!(),
+                pat => self.unsupported(pat.span(), "Destructuring declarations"),
             }
         }
     }
 }
+
+/// Binary opcode applied by the compound assignment operator `op`.
+fn compound_op(op: AssignOp) -> Op {
+    match op {
+        AssignOp::AddAssign => Op::OP_ADD,
+        AssignOp::SubAssign => Op::OP_SUB,
+        AssignOp::MulAssign => Op::OP_MUL,
+        AssignOp::DivAssign => Op::OP_DIV,
+        AssignOp::ModAssign => Op::OP_REM,
+        AssignOp::LShiftAssign => Op::OP_LSHIFT,
+        AssignOp::RShiftAssign => Op::OP_RSHIFT,
+        AssignOp::ZeroFillRShiftAssign => Op::OP_URSHIFT,
+        AssignOp::BitOrAssign => Op::OP_OR,
+        AssignOp::BitXorAssign => Op::OP_XOR,
+        AssignOp::BitAndAssign => Op::OP_AND,
+        _ => unreachable!("{:?} is rejected by Compiler::emit", op),
+    }
+}
+
 unsafe impl Trace for Compiler {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.builder.trace(tracer);
     }
 }
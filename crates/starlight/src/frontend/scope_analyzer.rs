@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use swc_atoms::JsWord;
 use swc_common::DUMMY_SP;
 use swc_ecmascript::ast::{
-    ArrowExpr, BlockStmt, BlockStmtOrExpr, CatchClause, ClassDecl, ClassExpr, DoWhileStmt, Expr,
-    FnDecl, FnExpr, ForInStmt, ForOfStmt, ForStmt, Function, Ident, ImportDefaultSpecifier,
+    ArrowExpr, BlockStmt, BlockStmtOrExpr, CatchClause, Class, ClassDecl, ClassExpr, DoWhileStmt,
+    Expr, FnDecl, FnExpr, ForInStmt, ForOfStmt, ForStmt, Function, Ident, ImportDefaultSpecifier,
     ImportNamedSpecifier, ImportStarAsSpecifier, Invalid, Param, Pat, Program, Stmt, SwitchStmt,
-    VarDecl, VarDeclKind, WhileStmt, WithStmt,
+    UnaryExpr, UnaryOp, VarDecl, VarDeclKind, WhileStmt, WithStmt,
 };
 use swc_ecmascript::utils::find_ids;
 use swc_ecmascript::utils::ident::IdentLike;
@@ -83,7 +83,6 @@ pub struct Var {
 
 impl Var {
     /// Empty path means root scope.
-    #[allow(dead_code)]
     pub fn path(&self) -> &[ScopeKind] {
         &self.path
     }
@@ -316,6 +315,145 @@ impl Visit for Analyzer<'_> {
     }
 }
 
+/// Function-level bindings of `function` that can live in frame slots instead of
+/// the scope object, in slot order.
+///
+/// A binding qualifies when it is a parameter, a `var` or a function declaration of
+/// `function` itself, is never referenced from a nested function, is not shadowed by a
+/// block-scoped declaration and is not deleted. Functions using `eval`, `with` or
+/// `arguments` get no slots at all since those can observe the scope dynamically.
+pub fn resolve_locals(function: &Function) -> Vec<JsWord> {
+    let mut resolver = LocalResolver {
+        depth: 0,
+        declared: vec![],
+        excluded: Default::default(),
+        dynamic: false,
+    };
+    for param in function.params.iter() {
+        for id in find_ids::<_, Id>(&param.pat) {
+            resolver.declare(id.0);
+        }
+    }
+    // default values run in the frame of the function and may close over its parameters.
+    for param in function.params.iter() {
+        param
+            .pat
+            .visit_with(&Invalid { span: DUMMY_SP }, &mut resolver);
+    }
+    if let Some(ref body) = function.body {
+        body.stmts
+            .visit_with(&Invalid { span: DUMMY_SP }, &mut resolver);
+    }
+    if resolver.dynamic {
+        return vec![];
+    }
+    let excluded = resolver.excluded;
+    resolver
+        .declared
+        .into_iter()
+        .filter(|name| !excluded.contains(name))
+        .collect()
+}
+
+struct LocalResolver {
+    /// Nesting level of functions below the one being resolved.
+    depth: usize,
+    declared: Vec<JsWord>,
+    excluded: HashSet<JsWord>,
+    dynamic: bool,
+}
+
+impl LocalResolver {
+    fn declare(&mut self, name: JsWord) {
+        if !self.declared.contains(&name) {
+            self.declared.push(name);
+        }
+    }
+
+    fn nested<F>(&mut self, op: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.depth += 1;
+        op(self);
+        self.depth -= 1;
+    }
+}
+
+impl Visit for LocalResolver {
+    fn visit_function(&mut self, n: &Function, _: &dyn Node) {
+        self.nested(|r| n.visit_children_with(r))
+    }
+
+    fn visit_arrow_expr(&mut self, n: &ArrowExpr, _: &dyn Node) {
+        self.nested(|r| n.visit_children_with(r))
+    }
+
+    fn visit_fn_decl(&mut self, n: &FnDecl, _: &dyn Node) {
+        if self.depth == 0 {
+            self.declare(n.ident.sym.clone());
+        }
+        n.function.visit_with(n, self);
+    }
+
+    fn visit_var_decl(&mut self, n: &VarDecl, _: &dyn Node) {
+        if self.depth == 0 {
+            for decl in n.decls.iter() {
+                for id in find_ids::<_, Id>(&decl.name) {
+                    match n.kind {
+                        VarDeclKind::Var => self.declare(id.0),
+                        _ => {
+                            self.excluded.insert(id.0);
+                        }
+                    }
+                }
+            }
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, n: &ClassDecl, _: &dyn Node) {
+        self.excluded.insert(n.ident.sym.clone());
+        n.class.visit_with(n, self);
+    }
+
+    /// The constructor and field initializers of a class are compiled into their own function.
+    fn visit_class(&mut self, n: &Class, _: &dyn Node) {
+        self.nested(|r| n.visit_children_with(r))
+    }
+
+    fn visit_catch_clause(&mut self, n: &CatchClause, _: &dyn Node) {
+        if let Some(ref pat) = n.param {
+            for id in find_ids::<_, Id>(pat) {
+                self.excluded.insert(id.0);
+            }
+        }
+        n.body.visit_with(n, self);
+    }
+
+    fn visit_with_stmt(&mut self, n: &WithStmt, _: &dyn Node) {
+        self.dynamic = true;
+        n.visit_children_with(self);
+    }
+
+    fn visit_unary_expr(&mut self, n: &UnaryExpr, _: &dyn Node) {
+        if let (UnaryOp::Delete, Expr::Ident(id)) = (n.op, &*n.arg) {
+            self.excluded.insert(id.sym.clone());
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_ident(&mut self, n: &Ident, _: &dyn Node) {
+        let s: &str = &n.sym;
+        if s == "eval" || s == "arguments" {
+            self.dynamic = true;
+        }
+        if self.depth > 0 {
+            self.excluded.insert(n.sym.clone());
+        }
+    }
+}
+
 pub struct VisitFnDecl<'a> {
     cb: &'a mut dyn FnMut(&FnDecl),
 }
@@ -324,6 +462,14 @@ impl<'a> Visit for VisitFnDecl<'a> {
     fn visit_fn_decl(&mut self, n: &FnDecl, _: &dyn Node) {
         (self.cb)(n);
     }
+
+    // declarations in class members belong to their methods.
+    fn visit_class(&mut self, _: &Class, _: &dyn Node) {}
+
+    // and those in function expressions and arrows to their own frame.
+    fn visit_function(&mut self, _: &Function, _: &dyn Node) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr, _: &dyn Node) {}
 }
 
 #[cfg(test)]
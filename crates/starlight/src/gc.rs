@@ -1,3 +1,6 @@
 pub mod block;
 pub mod block_set;
 pub mod space;
+#[cfg(any(debug_assertions, feature = "verify-heap"))]
+pub mod verify;
+pub mod weak;
@@ -69,6 +69,9 @@ pub struct HeapBlock {
     link: LinkedListLink,
     cell_size: usize,
     freelist: FreeList,
+    /// Index of the first cell that was never handed out. Cells from here on are not on the
+    /// freelist and are allocated by bumping this index.
+    unallocated: usize,
     free: bool,
     storage: [*mut Header; 0],
 }
@@ -119,17 +122,10 @@ impl HeapBlock {
                 link: LinkedListLink::new(),
                 cell_size,
                 freelist: FreeList::new(),
+                unallocated: 0,
                 free: true,
                 storage: [],
             });
-
-            let mut freelist = FreeList::new();
-            (*memory).for_each_cell(|cell| {
-                //(*cell).zap(1);
-                // (*cell).set_tag(GC_DEAD);
-                freelist.free(Address::from_ptr(cell));
-            });
-            (*memory).freelist = freelist;
             NonNull::new_unchecked(memory)
         }
     }
@@ -163,16 +159,42 @@ impl HeapBlock {
                 freelist.free(Address::from_ptr(cell));
             }
         });
+        if free {
+            // nothing survived, the whole block is bump allocated again.
+            (*block).freelist = FreeList::new();
+            (*block).unallocated = 0;
+            return SweepResult::Free;
+        }
         (*block).freelist = freelist;
+        (*block).unallocated = (*block).cell_count();
         if (*block).freelist.is_empty() {
             SweepResult::Full
-        } else if free {
-            SweepResult::Free
         } else {
             SweepResult::Recyclable
         }
     }
 
+    /// Number of cells that are not freed. Cells that died since the last sweep are counted too.
+    pub fn live_cells(&mut self) -> usize {
+        let mut live = 0;
+        self.for_each_cell(|cell| unsafe {
+            if !(*cell).is_zapped() {
+                live += 1;
+            }
+        });
+        live
+    }
+
+    /// Makes every cell of this block available for bump allocation again.
+    ///
+    /// # Safety
+    ///
+    /// All cells in the block have to be zapped already, their destructors are not run.
+    pub unsafe fn reset(&mut self) {
+        self.freelist = FreeList::new();
+        self.unallocated = 0;
+    }
+
     pub fn storage(&mut self) -> *mut u8 {
         self.storage.as_ptr() as usize as *mut u8
         //unsafe { round_up_to_multiple_of(16, self.storage.as_ptr() as usize) as *mut u8 }
@@ -206,10 +228,23 @@ impl HeapBlock {
     }
     #[inline(always)]
     pub fn allocate(&mut self) -> *mut Header {
-        if self.freelist.is_empty() {
-            return null_mut();
+        if !self.freelist.is_empty() {
+            return self.freelist.allocate().to_mut_ptr();
         }
-        self.freelist.allocate().to_mut_ptr()
+        if self.unallocated < self.cell_count() {
+            let cell = self.cell(self.unallocated);
+            self.unallocated += 1;
+            return cell;
+        }
+        null_mut()
+    }
+    /// Hands out all cells that were never allocated as a `[start, end)` range for bump
+    /// allocation. The range is empty when the block was recycled.
+    pub fn take_unallocated(&mut self) -> (*mut u8, *mut u8) {
+        let start = self.cell(self.unallocated).cast::<u8>();
+        let end = self.cell(self.cell_count()).cast::<u8>();
+        self.unallocated = self.cell_count();
+        (start, end)
     }
     pub fn from_cell(addr: *mut Header) -> *mut Self {
         (addr as usize & !(BLOCK_SIZE - 1)) as *mut _
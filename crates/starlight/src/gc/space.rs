@@ -53,9 +53,10 @@ pub fn size_class_index_for(size: usize) -> Option<usize> {
 }
 
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, HashSet, VecDeque},
     mem::size_of,
     ptr::{null_mut, NonNull},
+    rc::Rc,
 };
 
 use crate::heap::{
@@ -65,16 +66,23 @@ use crate::heap::{
     context::{LocalContext, LocalContextInner, PersistentContext},
     precise_allocation::PreciseAllocation,
 };
+use crate::runtime::symbol::Symbol;
 pub struct ConservativeRoots {
     pub scan: Vec<(*mut u8, *mut u8)>,
 }
 
-use super::{block::*, block_set::BlockSet};
+#[cfg(any(debug_assertions, feature = "verify-heap"))]
+use super::verify::{HeapError, Verifier};
+use super::{
+    block::*,
+    block_set::BlockSet,
+    weak::{WeakGc, WeakSlot},
+};
 
-#[cfg(not(miri))]
+#[cfg(not(any(miri, target_arch = "wasm32")))]
 use crate::heap::constraint::SimpleMarkingConstraint;
 use intrusive_collections::{LinkedList, UnsafeRef};
-#[cfg(not(miri))]
+#[cfg(not(any(miri, target_arch = "wasm32")))]
 use wtf_rs::stack_bounds::StackBounds;
 use wtf_rs::{keep_on_stack, list::LinkedList as SegmentedList};
 pub struct SmallArena {
@@ -93,6 +101,10 @@ pub struct SmallArena {
     unavailbe_blocks: LinkedList<BlockAdapter>,
     /// Current block where this arena allocates.
     current: *mut HeapBlock,
+    /// Bump allocation range taken from `current`, see [HeapBlock::take_unallocated]. Most
+    /// allocations only advance `cursor`.
+    cursor: *mut u8,
+    limit: *mut u8,
     /// Arena's cell size. All pointers returned by this arena always have `cell_size` free bytes available.
     cell_size: usize,
 }
@@ -102,6 +114,8 @@ impl SmallArena {
         Self {
             cell_size,
             current: null_mut(),
+            cursor: null_mut(),
+            limit: null_mut(),
             unavailbe_blocks: LinkedList::new(BlockAdapter::new()),
             recyclable_blocks: LinkedList::new(BlockAdapter::new()),
             free_blocks: LinkedList::new(BlockAdapter::new()),
@@ -116,8 +130,14 @@ impl SmallArena {
             .unwrap_or(null_mut())
     }
 
+    #[inline(always)]
     pub fn allocate(&mut self, space: &mut Space) -> Address {
         unsafe {
+            if self.cursor < self.limit {
+                let cell = self.cursor;
+                self.cursor = cell.add(self.cell_size);
+                return Address::from_ptr(cell);
+            }
             if self.current.is_null() {
                 return self.allocate_slow(space);
             }
@@ -130,28 +150,108 @@ impl SmallArena {
         }
     }
 
+    /// Makes `block` the block this arena allocates in and takes its unallocated cells for
+    /// bump allocation.
+    unsafe fn set_current(&mut self, space: &mut Space, block: *mut HeapBlock) {
+        space.block_acquisitions += 1;
+        self.current = block;
+        let (cursor, limit) = (*block).take_unallocated();
+        self.cursor = cursor;
+        self.limit = limit;
+    }
+
+    #[inline(never)]
     unsafe fn allocate_slow(&mut self, space: &mut Space) -> Address {
         if !self.current.is_null() {
             self.unavailbe_blocks
                 .push_back(UnsafeRef::from_raw(self.current));
+            self.current = null_mut();
         }
-        if let Some(block) = self.recyclable_blocks.pop_front() {
+        while let Some(block) = self.recyclable_blocks.pop_front() {
             let block = UnsafeRef::into_raw(block);
             let p = (*block).allocate();
             if !p.is_null() {
-                self.current = block;
+                self.set_current(space, block);
                 return Address::from_ptr(p);
             }
+            self.unavailbe_blocks.push_back(UnsafeRef::from_raw(block));
+        }
+        let block = self.free_block(space);
+        self.set_current(space, block);
+        self.allocate(space)
+    }
+
+    /// Takes a free block, allocating a new one when there is none.
+    unsafe fn free_block(&mut self, space: &mut Space) -> *mut HeapBlock {
+        match self.free_blocks.pop_front() {
+            Some(block) => UnsafeRef::into_raw(block),
+            None => {
+                let block = HeapBlock::create_with_cell_size(self.cell_size).as_ptr();
+                space.block_set.add(block);
+                block
+            }
         }
-        if let Some(block) = self.free_blocks.pop_front() {
+    }
+
+    /// Moves the cells of recyclable blocks into as few free blocks as they fit in and records
+    /// where every cell went in `forwarding`. Emptied blocks become free blocks. Nothing moves
+    /// when it would not free at least one block.
+    ///
+    /// Has to run right after a sweep, when every cell that is not zapped is live.
+    unsafe fn evacuate(
+        &mut self,
+        space: &mut Space,
+        forwarding: &mut HashMap<*mut Header, NonNull<Header>>,
+    ) {
+        let mut sources = vec![];
+        let mut live = 0;
+        while let Some(block) = self.recyclable_blocks.pop_front() {
             let block = UnsafeRef::into_raw(block);
-            self.current = block;
-            return Address::from_ptr((*block).allocate());
+            live += (*block).live_cells();
+            sources.push(block);
+        }
+        let per_block = match sources.first() {
+            Some(&block) => (*block).cell_count(),
+            None => return,
+        };
+        if (live + per_block - 1) / per_block >= sources.len() {
+            for block in sources {
+                self.recyclable_blocks.push_back(UnsafeRef::from_raw(block));
+            }
+            return;
+        }
+        let mut target: *mut HeapBlock = null_mut();
+        for block in sources {
+            for i in 0..(*block).cell_count() {
+                let cell = (*block).cell(i);
+                if (*cell).is_zapped() {
+                    continue;
+                }
+                let mut to = if target.is_null() {
+                    null_mut()
+                } else {
+                    (*target).allocate()
+                };
+                if to.is_null() {
+                    if !target.is_null() {
+                        self.unavailbe_blocks.push_back(UnsafeRef::from_raw(target));
+                    }
+                    target = self.free_block(space);
+                    to = (*target).allocate();
+                }
+                // the copy owns everything the cell owned, the original is freed without
+                // running its destructor.
+                std::ptr::copy_nonoverlapping(cell.cast::<u8>(), to.cast::<u8>(), self.cell_size);
+                (*cell).zap(2);
+                forwarding.insert(cell, NonNull::new_unchecked(to));
+            }
+            (*block).reset();
+            self.free_blocks.push_back(UnsafeRef::from_raw(block));
+        }
+        if !target.is_null() {
+            self.recyclable_blocks
+                .push_back(UnsafeRef::from_raw(target));
         }
-        let block = HeapBlock::create_with_cell_size(self.cell_size).as_ptr();
-        self.current = block;
-        space.block_set.add(block);
-        Address::from_ptr((*block).allocate())
     }
     /// Sweep arena blocks and push them to correct listsl.
     ///
@@ -183,6 +283,9 @@ impl SmallArena {
                 self.recyclable_blocks
                     .push_back(UnsafeRef::from_raw(self.current));
                 self.current = null_mut();
+                // cells left in the bump range are zapped and end up on the freelist.
+                self.cursor = null_mut();
+                self.limit = null_mut();
             }
             while let Some(block) = self.recyclable_blocks.pop_front() {
                 let block = UnsafeRef::into_raw(block);
@@ -205,6 +308,28 @@ impl SmallArena {
     }
 }
 
+/// Smallest and largest number of bytes allocated between two collections triggered by
+/// allocation, see [GcStats::threshold].
+pub const MIN_GC_THRESHOLD: usize = 64 * 1024;
+pub const MAX_GC_THRESHOLD: usize = 256 * 1024 * 1024;
+
+/// Collector statistics, see [Space::gc_stats].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GcStats {
+    /// Number of collections so far, whether forced or triggered by allocation.
+    pub collections: usize,
+    /// Bytes that can be allocated after a collection before the next one is triggered. It
+    /// doubles after a collection that freed less than a quarter of the heap and halves after
+    /// one that freed more than three quarters.
+    pub threshold: usize,
+    /// Bytes allocated since the last collection.
+    pub allocated: usize,
+    /// Bytes of live cells found by the last collection.
+    pub live: usize,
+    /// Bytes freed by the last collection.
+    pub last_freed: usize,
+}
+
 pub struct Space {
     arenas: [*mut SmallArena; SIZE_CLASSES.len()],
     block_set: BlockSet,
@@ -214,8 +339,15 @@ pub struct Space {
     scopes: SegmentedList<Option<NonNull<LocalContextInner>>>,
     persistent: *mut LocalContextInner,
     ndefers: u32,
-    max_heap_size: usize,
-    allocated: usize,
+    stats: GcStats,
+    allocations: usize,
+    /// Number of times an arena had to switch to another block, see [Space::block_acquisitions].
+    block_acquisitions: usize,
+    gc_stress: bool,
+    /// Symbols live objects hold, only gathered by [Space::collect_symbols].
+    live_symbols: Option<HashSet<*const u8>>,
+    /// Slots of live [WeakGc] references, cleared when their target is not marked.
+    weak_slots: Vec<WeakSlot>,
 }
 
 impl Space {
@@ -230,8 +362,15 @@ impl Space {
                 roots: Default::default(),
             })),
             ndefers: 0,
-            max_heap_size: 64 * 1024,
-            allocated: 0,
+            stats: GcStats {
+                threshold: MIN_GC_THRESHOLD,
+                ..Default::default()
+            },
+            allocations: 0,
+            block_acquisitions: 0,
+            gc_stress: false,
+            live_symbols: None,
+            weak_slots: vec![],
             block_set: BlockSet::new(),
             sp: 0,
             precise_allocations: vec![],
@@ -262,8 +401,9 @@ impl Space {
         self.constraints.push(Box::new(x));
     }
     fn add_core_constraints(&mut self) {
-        // we do not want to mark stack when running MIRI.
-        #[cfg(not(miri))]
+        // we do not want to mark stack when running MIRI. WebAssembly keeps most locals outside
+        // of linear memory where they can not be scanned, see [Space::allocate_raw].
+        #[cfg(not(any(miri, target_arch = "wasm32")))]
         self.add_constraint(SimpleMarkingConstraint::new(
             "Conservative Roots",
             |marking| {
@@ -303,6 +443,7 @@ impl Space {
             return;
         }
         self.sp = dummy as usize;
+        let symbols = self.live_symbols.take();
 
         let mut task = Marking {
             gc: self,
@@ -312,12 +453,17 @@ impl Space {
                 scan: Vec::with_capacity(2),
             },
             file: None,
+            symbols,
+            forwarding: None,
         };
 
         task.run();
 
         let visited = task.bytes_visited;
+        let symbols = task.symbols.take();
         drop(task);
+        self.live_symbols = symbols;
+        self.clear_weak_slots();
         for arena in self.arenas.iter().copied() {
             unsafe {
                 (*arena).sweep();
@@ -335,8 +481,48 @@ impl Space {
             }
         });
         self.precise_allocations.sort_unstable();
-        self.allocated = visited;
-        self.max_heap_size = (visited as f64 * 1.7) as usize;
+        self.update_stats(visited);
+    }
+
+    /// Adapts the threshold to how much the collection that found `live` bytes freed. A heap that
+    /// is mostly live is collected less often, one that is mostly garbage more often.
+    fn update_stats(&mut self, live: usize) {
+        let stats = &mut self.stats;
+        let before = stats.live + stats.allocated;
+        let freed = before.saturating_sub(live);
+        if freed * 4 < before {
+            stats.threshold = (stats.threshold * 2).min(MAX_GC_THRESHOLD);
+        } else if freed * 4 > before * 3 {
+            stats.threshold = (stats.threshold / 2).max(MIN_GC_THRESHOLD);
+        }
+        stats.collections += 1;
+        stats.allocated = 0;
+        stats.live = live;
+        stats.last_freed = freed;
+    }
+
+    pub fn gc_stats(&self) -> GcStats {
+        self.stats
+    }
+
+    /// Marks are still set at this point, slots pointing at unmarked cells are cleared before the
+    /// cells are swept. Slots no [WeakGc] refers to anymore are dropped.
+    fn clear_weak_slots(&mut self) {
+        self.weak_slots.retain(|slot| {
+            if let Some(cell) = slot.get() {
+                if unsafe { (*cell.as_ptr()).tag() } != GC_MARKED {
+                    slot.set(None);
+                }
+            }
+            slot.get().is_some() && Rc::strong_count(slot) > 1
+        });
+    }
+
+    /// Creates a reference to `target` that does not keep it alive, see [WeakGc].
+    pub fn new_weak<T: Cell + ?Sized>(&mut self, target: Gc<T>) -> WeakGc<T> {
+        let slot = Rc::new(std::cell::Cell::new(Some(target.cell)));
+        self.weak_slots.push(slot.clone());
+        WeakGc::new(slot)
     }
 
     pub fn gc(&mut self) {
@@ -347,11 +533,160 @@ impl Space {
         }
     }
 
-    pub fn collect_if_necessary(&mut self) {
-        if self.allocated <= self.max_heap_size {
-            return;
+    /// When enabled a full collection runs before every allocation. This is slow and exists only
+    /// to catch objects that are not rooted across allocation points.
+    pub fn set_gc_stress(&mut self, enabled: bool) {
+        self.gc_stress = enabled;
+    }
+
+    pub fn is_gc_stress(&self) -> bool {
+        self.gc_stress
+    }
+
+    /// Runs a full collection and returns the addresses of all interned symbols held by live
+    /// objects, `None` while collection is deferred.
+    pub fn collect_symbols(&mut self) -> Option<HashSet<*const u8>> {
+        if self.ndefers > 0 {
+            return None;
+        }
+        self.live_symbols = Some(HashSet::new());
+        self.gc();
+        self.live_symbols.take()
+    }
+
+    /// Number of objects allocated since this space was created.
+    pub fn allocation_count(&self) -> usize {
+        self.allocations
+    }
+
+    /// Number of times allocation left the bump allocation fast path to pick up a recycled,
+    /// free or new block.
+    pub fn block_acquisitions(&self) -> usize {
+        self.block_acquisitions
+    }
+
+    /// Number of blocks that hold both live and free cells. A single live cell keeps a block
+    /// from being freed, [Space::compact] packs such cells together. Cells that died since the
+    /// last collection count as live.
+    pub fn partially_filled_blocks(&self) -> usize {
+        self.block_set
+            .set
+            .iter()
+            .filter(|&&block| unsafe {
+                let live = (*block).live_cells();
+                live > 0 && live < (*block).cell_count()
+            })
+            .count()
+    }
+
+    /// Collects garbage and moves the live cells of partially filled blocks into fresh blocks,
+    /// freeing the blocks they came from. Returns the number of moved cells. Precise
+    /// allocations never move.
+    ///
+    /// Every `Gc` edge traced from the roots and the heap is updated, see [Tracer::trace_edge],
+    /// as are [WeakGc] references.
+    ///
+    /// # Safety
+    ///
+    /// Pointers held anywhere else are left dangling and untraced maps keyed by the address of
+    /// a cell have to be rebuilt, see
+    /// [VirtualMachine::compact_heap](crate::vm::VirtualMachine::compact_heap).
+    pub unsafe fn compact(&mut self) -> usize {
+        self.gc();
+        let mut forwarding = HashMap::new();
+        for arena in self.arenas.iter().copied() {
+            (*arena).evacuate(self, &mut forwarding);
+        }
+        if !forwarding.is_empty() {
+            self.update_edges(&forwarding);
+        }
+        forwarding.len()
+    }
+
+    /// Points every edge at a moved cell to where it was moved to. Roots go through the same
+    /// constraints marking uses, then every cell in the heap is traced once.
+    unsafe fn update_edges(&mut self, forwarding: &HashMap<*mut Header, NonNull<Header>>) {
+        let blocks = self.block_set.set.iter().copied().collect::<Vec<_>>();
+        let precise = self.precise_allocations.clone();
+        let mut task = Marking {
+            gc: self,
+            bytes_visited: 0,
+            worklist: VecDeque::new(),
+            cons: ConservativeRoots { scan: vec![] },
+            file: None,
+            symbols: None,
+            forwarding: Some(forwarding),
+        };
+        task.process_constraints();
+        task.process_roots();
+        for block in blocks {
+            for i in 0..(*block).cell_count() {
+                let cell = (*block).cell(i);
+                if !(*cell).is_zapped() {
+                    (*cell).get_dyn().trace(&mut task);
+                }
+            }
+        }
+        for alloc in precise {
+            (*(*alloc).cell()).get_dyn().trace(&mut task);
+        }
+        drop(task);
+        for slot in self.weak_slots.iter() {
+            if let Some(cell) = slot.get() {
+                if let Some(&to) = forwarding.get(&cell.as_ptr()) {
+                    slot.set(Some(to));
+                }
+            }
+        }
+    }
+
+    /// Calls `f` on every cell in the heap, both in blocks and precise allocations.
+    ///
+    /// Freed cells are skipped but cells that became unreachable since the last collection are
+    /// still reported, run [Space::gc] first to only see live cells.
+    pub fn for_each_cell(&self, mut f: impl FnMut(&dyn Cell)) {
+        unsafe {
+            for block in self.block_set.set.iter() {
+                (**block).for_each_cell(|cell| {
+                    let cell = cell as *mut Header;
+                    if !(*cell).is_zapped() {
+                        f((*cell).get_dyn());
+                    }
+                });
+            }
+            for alloc in self.precise_allocations.iter() {
+                let cell = (**alloc).cell();
+                if !(*cell).is_zapped() {
+                    f((*cell).get_dyn());
+                }
+            }
+        }
+    }
+
+    /// Checks every edge of the heap and the consistency of structures, see
+    /// [verify](super::verify).
+    #[cfg(any(debug_assertions, feature = "verify-heap"))]
+    pub fn verify(&self) -> Result<(), HeapError> {
+        let mut verifier = Verifier::default();
+        unsafe {
+            for block in self.block_set.set.iter() {
+                (**block).for_each_cell(|cell| verifier.add_cell(cell as *mut Header));
+            }
+            for alloc in self.precise_allocations.iter() {
+                verifier.add_cell((**alloc).cell());
+            }
+        }
+        verifier.run()
+    }
+
+    /// Runs a collection once more than [GcStats::threshold] bytes were allocated since the
+    /// last one, returns whether it did.
+    pub fn collect_if_necessary(&mut self) -> bool {
+        if !self.gc_stress && self.stats.allocated <= self.stats.threshold {
+            return false;
         }
         self.gc();
+        true
     }
     #[inline(never)]
     unsafe fn alloc_slow(&mut self, size: usize) -> Address {
@@ -369,11 +704,15 @@ impl Space {
     /// This function is unsafe since it returns partially initialized data.
     /// Only first 8 bytes is initialized with GC object header.
     ///
-    ///
+    /// On wasm32 the stack is not scanned, objects only referenced from Rust locals would be
+    /// freed. Allocation never collects there, embedders collect between evaluations with
+    /// [VirtualMachine::collect_garbage_if_needed](crate::vm::VirtualMachine::collect_garbage_if_needed).
     #[inline]
     pub unsafe fn allocate_raw(&mut self, size: usize) -> Address {
+        #[cfg(not(target_arch = "wasm32"))]
         self.collect_if_necessary();
-        self.allocated += size;
+        self.stats.allocated += size;
+        self.allocations += 1;
         if size > 4080 {
             self.alloc_slow(size)
         } else {
@@ -404,6 +743,32 @@ impl Space {
             }
         }
     }
+
+    /// Allocate a variable-length cell: `header` followed by `len` elements of `elem_size`
+    /// bytes each, i.e the layout of a `#[repr(C)]` struct ending in a `[E; 0]` field.
+    ///
+    /// Trailing elements are zeroed and have to be initialized by the caller. `T::compute_size`
+    /// must return `size_of::<T>() + len * elem_size` so that marking accounts for the whole
+    /// allocation.
+    pub fn alloc_varlen<T: Cell>(&mut self, header: T, len: usize, elem_size: usize) -> Gc<T> {
+        unsafe {
+            let data_size = size_of::<T>() + len * elem_size;
+            let size = round_up_to_multiple_of(16, data_size + size_of::<Header>());
+            let memory = self.allocate_raw(size).to_mut_ptr::<Header>();
+            assert!(!memory.is_null());
+
+            memory.write(Header::new(object_ty_of(&header)));
+            (*memory).set_tag(GC_WHITE);
+            let data = (*memory).data_start().to_mut_ptr::<T>();
+            data.write(header);
+            std::ptr::write_bytes(data.cast::<u8>().add(size_of::<T>()), 0, len * elem_size);
+            debug_assert_eq!((*data).compute_size(), data_size);
+            Gc {
+                cell: NonNull::new_unchecked(memory),
+                marker: Default::default(),
+            }
+        }
+    }
 }
 
 pub struct Marking<'a> {
@@ -413,6 +778,11 @@ pub struct Marking<'a> {
     cons: ConservativeRoots,
     #[allow(dead_code)]
     file: Option<&'a mut std::fs::File>,
+    /// Symbols seen while tracing, `None` when nobody is interested in them.
+    symbols: Option<HashSet<*const u8>>,
+    /// New addresses of moved cells while [Space::compact] updates edges. Nothing is marked
+    /// then, edges to moved cells are rewritten instead.
+    forwarding: Option<&'a HashMap<*mut Header, NonNull<Header>>>,
 }
 
 impl<'a> Marking<'a> {
@@ -583,7 +953,26 @@ impl<'a> Marking<'a> {
 
 impl<'a> Tracer for Marking<'a> {
     fn trace(&mut self, hdr: *mut Header) {
-        self.mark(hdr);
+        if self.forwarding.is_none() {
+            self.mark(hdr);
+        }
+    }
+
+    fn trace_edge(&mut self, edge: &mut NonNull<Header>) {
+        match self.forwarding {
+            Some(forwarding) => {
+                if let Some(&to) = forwarding.get(&edge.as_ptr()) {
+                    *edge = to;
+                }
+            }
+            None => self.mark(edge.as_ptr()),
+        }
+    }
+
+    fn trace_symbol(&mut self, sym: Symbol) {
+        if let (Some(symbols), Symbol::Key(key)) = (self.symbols.as_mut(), sym) {
+            symbols.insert(key.as_ptr());
+        }
     }
 }
 
@@ -627,3 +1016,160 @@ impl Drop for SmallArena {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MIN_GC_THRESHOLD;
+    use crate::{
+        heap::cell::Gc,
+        runtime::{array::JsArray, object::JsObject, symbol::Symbol, value::JsValue},
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_bump_allocation_fast_path() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut kept = ctx.new_local(JsObject::new_empty(&mut vm));
+            let name = vm.intern("x");
+            assert!(kept.put(&mut vm, name, JsValue::new(42), false).is_ok());
+
+            let allocations = vm.space().allocation_count();
+            let acquisitions = vm.space().block_acquisitions();
+            for _ in 0..20000 {
+                JsObject::new_empty(&mut vm);
+            }
+            let allocations = vm.space().allocation_count() - allocations;
+            let acquisitions = vm.space().block_acquisitions() - acquisitions;
+            assert!(allocations >= 20000);
+            // a block holds dozens of cells of every size class objects use, collections in
+            // between hand out whole free blocks again.
+            assert!(
+                acquisitions * 32 < allocations,
+                "{} block acquisitions for {} allocations",
+                acquisitions,
+                allocations
+            );
+
+            vm.space().gc();
+            assert_eq!(
+                kept.get(&mut vm, name)
+                    .unwrap_or_else(|_| panic!())
+                    .as_int32(),
+                42
+            );
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_threshold_adapts() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let stats = vm.gc_stats();
+        for _ in 0..20000 {
+            JsObject::new_empty(&mut vm);
+        }
+        let garbage = vm.gc_stats();
+        assert!(garbage.collections > stats.collections);
+        assert!(garbage.last_freed > 0);
+        assert!(garbage.threshold >= MIN_GC_THRESHOLD);
+
+        {
+            let ctx = vm.space().new_local_context();
+            let mut kept = ctx.new_local(JsArray::new(&mut vm, 0));
+            for i in 0..50000 {
+                let ctx = vm.space().new_local_context();
+                let obj = ctx.new_local(JsObject::new_empty(&mut vm));
+                let val = JsValue::new(*obj);
+                assert!(kept.put(&mut vm, Symbol::Indexed(i), val, false).is_ok());
+            }
+            let live = vm.gc_stats();
+            assert!(live.collections > garbage.collections);
+            assert!(
+                live.threshold > garbage.threshold,
+                "threshold {} did not grow from {}",
+                live.threshold,
+                garbage.threshold
+            );
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_compaction() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "key = {};
+             map = new Map();
+             map.set(key, 42);
+             proto = {};
+             before = Object.create(proto);",
+        );
+        assert!(res.is_ok());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut kept = ctx.new_local(JsArray::new(&mut vm, 0));
+            let scope = unsafe { vm.root_scope() };
+            let name = vm.intern("x");
+            // every 16th object survives, leaving most blocks sparsely used.
+            for i in 0..20000 {
+                let ctx = vm.space().new_local_context();
+                let mut obj = ctx.new_local(JsObject::new_empty(&mut vm));
+                if i % 16 == 0 {
+                    assert!(obj
+                        .put(&mut vm, name, JsValue::new(i as i32), false)
+                        .is_ok());
+                    let val = JsValue::new(*obj);
+                    assert!(kept
+                        .put(&mut vm, Symbol::Indexed(i / 16), val, false)
+                        .is_ok());
+                }
+            }
+            let first = kept.get(&mut vm, Symbol::Indexed(0));
+            let first = scope.root(first.unwrap_or_else(|_| panic!()).as_object());
+            vm.space().gc();
+            let partial = vm.space().partially_filled_blocks();
+            // `kept` and `first` are the only cells held outside of the heap, both are updated.
+            assert!(unsafe { vm.compact_heap() } > 0);
+            assert!(
+                vm.space().partially_filled_blocks() < partial,
+                "{} partially filled blocks before compaction, {} after",
+                partial,
+                vm.space().partially_filled_blocks()
+            );
+
+            for i in 0..1250 {
+                let obj = kept
+                    .get(&mut vm, Symbol::Indexed(i))
+                    .unwrap_or_else(|_| panic!());
+                let x = obj
+                    .as_object()
+                    .get(&mut vm, name)
+                    .unwrap_or_else(|_| panic!());
+                assert_eq!(x.as_int32(), i as i32 * 16);
+            }
+            let zero = kept
+                .get(&mut vm, Symbol::Indexed(0))
+                .unwrap_or_else(|_| panic!());
+            assert!(Gc::ptr_eq(first.get(), zero.as_object()));
+        }
+        #[cfg(debug_assertions)]
+        assert_eq!(vm.verify_heap(), Ok(()));
+
+        let res = vm.eval(
+            "found = map.get(key);
+             other = map.get({});
+             after = Object.create(proto);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get("found").as_int32(), 42);
+        assert!(get("other").is_undefined());
+        // the root structure of `proto` is still found under its new address.
+        let (before, after) = (get("before").as_object(), get("after").as_object());
+        assert_eq!(before.structure().id(), after.structure().id());
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,180 @@
+//! Heap verification.
+//!
+//! [Space::verify](super::space::Space::verify) walks every cell that is not freed, checks that
+//! each `Gc` edge it traces points at a live cell of the same space and that structures agree
+//! with their property tables and transitions. It is a test oracle: the collector never calls it,
+//! tests call [VirtualMachine::verify_heap](crate::vm::VirtualMachine::verify_heap) right after
+//! [Space::gc](super::space::Space::gc) so an edge the collector missed shows up as an edge to a
+//! freed cell instead of a crash much later.
+//!
+//! Cells that became unreachable since the last collection are walked too. Their edges are still
+//! valid since a cell can only be freed together with every cell pointing at it.
+//!
+//! Only built with `debug_assertions` or the `verify-heap` feature.
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    heap::cell::{Gc, Header, Tracer},
+    runtime::structure::{Structure, TargetTable},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeapError {
+    /// A cell of type `from` holds an edge to an address that is not a cell of this heap.
+    DanglingEdge { from: &'static str, to: usize },
+    /// A cell of type `from` holds an edge to a cell that was freed or never allocated.
+    FreedEdge { from: &'static str, to: usize },
+    /// The structure at `at` is inconsistent.
+    Structure { at: usize, reason: String },
+}
+
+impl fmt::Display for HeapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeapError::DanglingEdge { from, to } => {
+                write!(f, "{} points to {:#x} which is not a heap cell", from, to)
+            }
+            HeapError::FreedEdge { from, to } => {
+                write!(f, "{} points to freed cell {:#x}", from, to)
+            }
+            HeapError::Structure { at, reason } => write!(f, "structure {:#x}: {}", at, reason),
+        }
+    }
+}
+
+impl std::error::Error for HeapError {}
+
+/// Cells of a space, filled by [Space::verify](super::space::Space::verify).
+#[derive(Default)]
+pub(crate) struct Verifier {
+    live: HashSet<usize>,
+    freed: HashSet<usize>,
+}
+
+impl Verifier {
+    /// # Safety
+    ///
+    /// `cell` must be a cell slot of the space that is verified.
+    pub(crate) unsafe fn add_cell(&mut self, cell: *mut Header) {
+        if (*cell).is_zapped() {
+            self.freed.insert(cell as usize);
+        } else {
+            self.live.insert(cell as usize);
+        }
+    }
+
+    pub(crate) fn run(&self) -> Result<(), HeapError> {
+        let mut structures: Vec<&Structure> = vec![];
+        for &cell in self.live.iter() {
+            let cell = unsafe { (*(cell as *mut Header)).get_dyn() };
+            let mut edges = Edges {
+                verifier: self,
+                from: cell.get_typename(),
+                error: None,
+            };
+            cell.trace(&mut edges);
+            if let Some(error) = edges.error {
+                return Err(error);
+            }
+            if let Some(structure) = cell.downcast_ref::<Structure>() {
+                structures.push(structure);
+            }
+        }
+
+        // Unique structures share their property table with the structure they were created
+        // from, only the newest of them keeps its slot count in sync with the table.
+        let mut slots: HashMap<Gc<TargetTable>, usize> = HashMap::new();
+        for structure in structures.iter() {
+            if let Some(table) = structure.table() {
+                let size = slots.entry(table).or_insert(0);
+                *size = (*size).max(structure.get_slots_size());
+            }
+        }
+        for structure in structures {
+            let slots_size = match structure.table() {
+                Some(table) => slots[&table],
+                None => structure.get_slots_size(),
+            };
+            structure
+                .verify(slots_size)
+                .map_err(|reason| HeapError::Structure {
+                    at: structure as *const Structure as usize,
+                    reason,
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the edges of a single cell, keeps the first bad one.
+struct Edges<'a> {
+    verifier: &'a Verifier,
+    from: &'static str,
+    error: Option<HeapError>,
+}
+
+impl<'a> Tracer for Edges<'a> {
+    fn trace(&mut self, header: *mut Header) {
+        if self.error.is_some() {
+            return;
+        }
+        let to = header as usize;
+        if self.verifier.freed.contains(&to) {
+            self.error = Some(HeapError::FreedEdge {
+                from: self.from,
+                to,
+            });
+        } else if !self.verifier.live.contains(&to) {
+            self.error = Some(HeapError::DanglingEdge {
+                from: self.from,
+                to,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_verify_after_collection() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function fill(list, i, make) {
+                 if (i === 100) {
+                     return list;
+                 }
+                 list[i] = make(i);
+                 return fill(list, i + 1, make);
+             }
+             function kept(i) { return { x: i, y: [i, 'str' + i] }; }
+             function dropped(i) { return { a: i, b: i, c: i }; }
+             shared = fill([], 0, kept);
+             garbage = fill([], 0, dropped);
+             garbage = null;
+             large = new Array(5000);
+             large[4999] = { last: true };
+
+             dict = { a: 1, b: 2 };
+             delete dict.a;
+             dict.c = 3;
+             Object.defineProperty(dict, 'd', { value: 4, enumerable: true });
+             delete dict.c;
+             dict.e = 5;
+             out = '' + dict.b + ',' + dict.d + ',' + dict.e;",
+        );
+        assert!(res.is_ok());
+        assert_eq!(vm.verify_heap(), Ok(()));
+        vm.space().gc();
+        assert_eq!(vm.verify_heap(), Ok(()));
+
+        let global = vm.global_object();
+        let value = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(value.as_string().as_str(), "2,4,5");
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,41 @@
+//! Weak references to heap cells.
+//!
+//! A [WeakGc] does not keep its target alive. Every weak reference shares a slot with the
+//! [Space](super::space::Space) that created it, after marking the space clears the slots of
+//! targets that were not marked so a collected cell is never handed out again.
+use std::{cell::Cell as SlotCell, marker::PhantomData, ptr::NonNull, rc::Rc};
+
+use crate::heap::cell::{Cell, Gc, Header, Trace};
+
+pub(crate) type WeakSlot = Rc<SlotCell<Option<NonNull<Header>>>>;
+
+pub struct WeakGc<T: Cell + ?Sized> {
+    slot: WeakSlot,
+    marker: PhantomData<T>,
+}
+
+impl<T: Cell + ?Sized> WeakGc<T> {
+    pub(crate) fn new(slot: WeakSlot) -> Self {
+        Self {
+            slot,
+            marker: PhantomData,
+        }
+    }
+
+    /// The target of this reference, `None` once it was collected.
+    pub fn upgrade(&self) -> Option<Gc<T>> {
+        self.slot.get().map(|cell| Gc {
+            cell,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<T: Cell + ?Sized> Clone for WeakGc<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.slot.clone())
+    }
+}
+
+// Tracing a weak reference is a no-op, that is what makes it weak.
+unsafe impl<T: Cell + ?Sized> Trace for WeakGc<T> {}
@@ -7,6 +7,7 @@ pub mod block_set;
 pub mod cell;
 pub mod constraint;
 pub mod context;
+pub mod handle;
 pub mod precise_allocation;
 //pub mod space;
 pub mod tiny_bloom_filter;
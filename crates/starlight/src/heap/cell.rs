@@ -1,9 +1,14 @@
 use crate::{
-    runtime::{class::Class, structure::Structure},
+    runtime::{class::Class, structure::Structure, symbol::Symbol},
     vm::VirtualMachine,
 };
 
-use super::{addr::Address, block::Block, precise_allocation::PreciseAllocation};
+use super::{
+    addr::Address,
+    block::Block,
+    context::{Local, LocalContext},
+    precise_allocation::PreciseAllocation,
+};
 use core::{mem::size_of, mem::transmute};
 #[cfg(feature = "debug-snapshots")]
 use erased_serde::serialize_trait_object;
@@ -11,6 +16,7 @@ use minivec::MiniVec;
 use mopa::{mopafy, Any};
 use std::collections::HashMap;
 use std::{
+    hash::{Hash, Hasher},
     marker::PhantomData,
     ops::{Deref, DerefMut},
     ptr::NonNull,
@@ -24,6 +30,15 @@ pub const GC_UNMARKED: u8 = GC_WHITE;
 
 pub trait Tracer {
     fn trace(&mut self, header: *mut Header);
+    /// Called by `Gc` with the pointer it holds. The compacting collector rewrites `edge` when
+    /// it moved the cell, other tracers only look at the pointer, see
+    /// [Space::compact](crate::gc::space::Space::compact).
+    fn trace_edge(&mut self, edge: &mut NonNull<Header>) {
+        self.trace(edge.as_ptr())
+    }
+    /// Called for every interned symbol held by a traced value. Only the collector cares about
+    /// these, to find symbols that can be evicted from the symbol table.
+    fn trace_symbol(&mut self, _sym: Symbol) {}
 }
 /// Indicates that a type can be traced by a garbage collector.
 ///
@@ -44,9 +59,12 @@ pub unsafe trait Trace: Any {
     /// Some types (like `Gc`) need special actions taken when they're traced,
     /// but those are somewhat rare and are usually already provided by the garbage collector.
     ///
+    /// Fields are passed to the tracer by `&mut` so the compacting collector can point them at
+    /// the new address of a moved cell, see [Tracer::trace_edge].
+    ///
     /// ## Always Permitted
     /// - Reading your own memory (includes iteration)
-    ///   - Interior mutation is undefined behavior, even if you use `RefCell`
+    ///   - Writing it yourself is undefined behavior, even if you use `RefCell`
     /// - Calling `Tracer::trace` with the specified collector
     ///   - `Tracer::trace` already verifies that it owns the data, so you don't need to do that
     /// - Panicking
@@ -62,9 +80,18 @@ pub unsafe trait Trace: Any {
     ///     - With an automatically derived implementation you will never miss a field
     /// - Invoking this function directly, without delegating to `Tracer`
     #[allow(unused_variables)]
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         /* no-op */
     }
+
+    /// Whether values of this type can hold garbage collected pointers. Containers use this to
+    /// skip tracing their elements entirely, e.g a `GcArray<u8>` is never walked.
+    fn needs_trace() -> bool
+    where
+        Self: Sized,
+    {
+        true
+    }
 }
 mopafy!(Trace);
 
@@ -241,7 +268,11 @@ pub struct Gc<T: Cell + ?Sized> {
 
 macro_rules! impl_prim {
     ($($t:ty)*) => {$(
-        unsafe impl Trace for $t {}
+        unsafe impl Trace for $t {
+            fn needs_trace() -> bool {
+                false
+            }
+        }
         impl Cell for $t {}
     )*
     };
@@ -329,9 +360,50 @@ impl<T: Cell> DerefMut for Gc<T> {
     }
 }
 
+impl<T: Cell> Gc<T> {
+    /// Projects `this` onto data inside of the cell, usually one of its fields.
+    ///
+    /// Holding `&T::field` obtained through `Deref` does not keep the cell alive, so a collection
+    /// can free it under the reference. The returned [Projection] roots `this` in `ctx` and keeps
+    /// the cell, and so the projected data, alive for as long as it is used.
+    pub fn map<'a, U: ?Sized>(
+        this: Self,
+        ctx: &'a LocalContext<'a>,
+        f: impl FnOnce(&T) -> &U,
+    ) -> Projection<'a, T, U> {
+        let field = f(&*this) as *const U;
+        Projection {
+            parent: ctx.new_local(this),
+            field,
+        }
+    }
+}
+
+/// Data inside of a GC cell that keeps the cell rooted, see [Gc::map].
+pub struct Projection<'a, T: Cell, U: ?Sized> {
+    parent: Local<'a, Gc<T>>,
+    field: *const U,
+}
+
+impl<'a, T: Cell, U: ?Sized> Projection<'a, T, U> {
+    /// Cell this projection points into.
+    pub fn parent(&self) -> Gc<T> {
+        *self.parent
+    }
+}
+
+impl<'a, T: Cell, U: ?Sized> Deref for Projection<'a, T, U> {
+    type Target = U;
+    fn deref(&self) -> &U {
+        // `parent` keeps the cell alive. Cells only move in `VirtualMachine::compact_heap`, which
+        // must not be called while a projection is around.
+        unsafe { &*self.field }
+    }
+}
+
 unsafe impl<T: Cell + ?Sized> Trace for Gc<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
-        tracer.trace(self.cell.as_ptr());
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        tracer.trace_edge(&mut self.cell);
     }
 }
 
@@ -343,19 +415,51 @@ impl<T: Cell + ?Sized> Clone for Gc<T> {
     }
 }
 
+/// Identity comparison, two `Gc`s are equal when they point to the same cell.
+///
+/// Together with the `Hash` impl below this lets `Gc` be used as a key in internal maps (cycle
+/// detection and the like). Both rely on cells not moving: traced `HashMap`s move their keys to
+/// the right bucket when [VirtualMachine::compact_heap] moves a cell, other maps keyed by `Gc`
+/// have to be rebuilt after it.
+impl<T: Cell + ?Sized> PartialEq for Gc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Gc::ptr_eq(*self, *other)
+    }
+}
+
+impl<T: Cell + ?Sized> Eq for Gc<T> {}
+
+impl<T: Cell + ?Sized> Hash for Gc<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cell.hash(state);
+    }
+}
+
 impl<K: Cell, V: Cell> Cell for HashMap<K, V> {}
-unsafe impl<K: Trace, V: Trace> Trace for HashMap<K, V> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
-        for (k, v) in self.iter() {
-            k.trace(tracer);
+unsafe impl<K: Trace + Clone + Eq + Hash, V: Trace> Trace for HashMap<K, V> {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        let mut moved = vec![];
+        for (k, v) in self.iter_mut() {
+            // keys can't be written in place, a copy is traced instead.
+            let mut key = k.clone();
+            key.trace(tracer);
             v.trace(tracer);
+            if key != *k {
+                moved.push((k.clone(), key));
+            }
+        }
+        // the compacting collector moved these keys, they hash differently at their new address.
+        for (old, new) in moved {
+            if let Some(v) = self.remove(&old) {
+                self.insert(new, v);
+            }
         }
     }
 }
 
 impl<T: Cell> Cell for Option<T> {}
 unsafe impl<T: Trace> Trace for Option<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         match self {
             Some(elem) => elem.trace(tracer),
             _ => (),
@@ -393,18 +497,49 @@ impl<T: Cell> serde::Serialize for Heap<T> {
 
 impl<T: Cell> Cell for Vec<T> {}
 unsafe impl<T: Trace> Trace for Vec<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
-        for elem in self.iter() {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        for elem in self.iter_mut() {
             elem.trace(tracer);
         }
     }
 }
 unsafe impl<T: Trace> Trace for MiniVec<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
-        for elem in self.iter() {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        for elem in self.iter_mut() {
             elem.trace(tracer);
         }
     }
 }
 
 impl<T: Cell> Cell for MiniVec<T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_gc_identity_keys() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let a = ctx.new_local(vm.space().alloc(1i32));
+            let b = ctx.new_local(vm.space().alloc(1i32));
+            let a2 = *a;
+            assert!(*a == a2);
+            assert!(*a != *b);
+
+            let mut map = HashMap::new();
+            map.insert(*a, "a");
+            map.insert(*b, "b");
+            assert_eq!(map.len(), 2);
+            assert_eq!(map.get(&a2), Some(&"a"));
+            assert_eq!(map.get(&*b), Some(&"b"));
+            map.insert(a2, "again");
+            assert_eq!(map.len(), 2);
+            assert_eq!(map[&*a], "again");
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,195 @@
+//! Scoped roots for embedders.
+//!
+//! A [RootScope] marks the top of the handle stack of a [VirtualMachine] when it is created,
+//! every value rooted through it is pushed onto that stack and the collector marks the whole
+//! stack. Dropping the scope pops everything rooted since it was created, so scopes have to be
+//! dropped in the reverse order they were created in, which plain Rust scoping does. Dropping
+//! them in any other order, or forgetting one, panics when the enclosing scope is dropped.
+//!
+//! Unlike [LocalContext](super::context::LocalContext) roots are not released one by one, a
+//! [Handle] is the position of the rooted pointer on the stack and dropping it does nothing.
+//! Reading through that position keeps handles valid when
+//! [VirtualMachine::compact_heap] moves cells. On wasm32 the native stack is not scanned, so
+//! this is how values held in Rust locals survive allocation.
+use std::{marker::PhantomData, ops::Deref};
+
+use super::cell::{Cell, Gc};
+use crate::{runtime::value::JsValue, vm::VirtualMachine};
+
+pub struct RootScope {
+    vm: *mut VirtualMachine,
+    base: usize,
+    /// Number of scopes that were open when this one was created.
+    depth: usize,
+}
+
+impl RootScope {
+    pub(crate) fn new(vm: &mut VirtualMachine) -> Self {
+        let depth = vm.root_scopes;
+        vm.root_scopes += 1;
+        Self {
+            base: vm.handles.len(),
+            depth,
+            vm,
+        }
+    }
+
+    /// Roots `value` until this scope is dropped.
+    pub fn root<T: Cell + ?Sized>(&self, value: Gc<T>) -> Handle<'_, T> {
+        unsafe {
+            (*self.vm).handles.push(value.as_dyn());
+            Handle {
+                vm: self.vm,
+                index: (*self.vm).handles.len() - 1,
+                marker: PhantomData,
+            }
+        }
+    }
+
+    /// Roots the cell of `value` until this scope is dropped, other values are returned as is.
+    pub fn root_value(&self, value: JsValue) -> JsValue {
+        if value.is_cell() {
+            unsafe {
+                (*self.vm).handles.push(value.as_cell());
+            }
+        }
+        value
+    }
+
+    /// Number of values rooted through this scope and the scopes nested in it.
+    pub fn len(&self) -> usize {
+        unsafe { (*self.vm).handles.len() - self.base }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for RootScope {
+    fn drop(&mut self) {
+        unsafe {
+            let vm = &mut *self.vm;
+            assert_eq!(
+                vm.root_scopes,
+                self.depth + 1,
+                "RootScope dropped while a scope created after it is still open"
+            );
+            vm.root_scopes = self.depth;
+            vm.handles.truncate(self.base);
+        }
+    }
+}
+
+/// A value rooted by the [RootScope] it borrows.
+pub struct Handle<'a, T: Cell + ?Sized> {
+    vm: *mut VirtualMachine,
+    index: usize,
+    marker: PhantomData<(&'a RootScope, Gc<T>)>,
+}
+
+impl<'a, T: Cell + ?Sized> Handle<'a, T> {
+    pub fn get(&self) -> Gc<T> {
+        **self
+    }
+}
+
+impl<'a, T: Cell + ?Sized> Clone for Handle<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: Cell + ?Sized> Copy for Handle<'a, T> {}
+
+impl<'a, T: Cell + ?Sized> Deref for Handle<'a, T> {
+    type Target = Gc<T>;
+    fn deref(&self) -> &Gc<T> {
+        // the slot was pushed as a `Gc<T>` and every `Gc` has the same layout.
+        unsafe {
+            let slot: *const Gc<dyn Cell> = &(*self.vm).handles[self.index];
+            &*slot.cast::<Gc<T>>()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RootScope;
+    use crate::{
+        gc::weak::WeakGc,
+        runtime::string::JsString,
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    type Weak = Vec<WeakGc<JsString>>;
+
+    // Allocation happens in frames that are gone by the time the collector scans the stack, so
+    // only the handle stack keeps the strings alive.
+    #[inline(never)]
+    fn allocate(vm: &mut VirtualMachine, scope: &RootScope, count: usize) -> (Weak, Weak) {
+        let mut rooted = vec![];
+        let mut dropped = vec![];
+        for i in 0..count {
+            let kept = JsString::new(vm, format!("kept {}", i));
+            scope.root(kept);
+            rooted.push(vm.space().new_weak(kept));
+            let garbage = JsString::new(vm, format!("dropped {}", i));
+            dropped.push(vm.space().new_weak(garbage));
+        }
+        (rooted, dropped)
+    }
+
+    #[inline(never)]
+    fn allocate_until_collection(vm: &mut VirtualMachine) {
+        let collections = vm.gc_stats().collections;
+        while vm.gc_stats().collections == collections {
+            JsString::new(vm, "garbage");
+        }
+    }
+
+    fn alive(strings: &Weak, prefix: &str) -> bool {
+        strings.iter().enumerate().all(|(i, s)| match s.upgrade() {
+            Some(s) => s.as_str() == format!("{} {}", prefix, i),
+            None => false,
+        })
+    }
+
+    #[test]
+    fn test_root_scope() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let scope = unsafe { vm.root_scope() };
+        let (rooted, dropped) = allocate(&mut vm, &scope, 50);
+        assert_eq!(scope.len(), 50);
+        allocate_until_collection(&mut vm);
+        assert!(alive(&rooted, "kept"));
+        assert!(dropped.iter().all(|s| s.upgrade().is_none()));
+
+        let nested = {
+            let inner = unsafe { vm.root_scope() };
+            let (nested, _) = allocate(&mut vm, &inner, 10);
+            assert_eq!((scope.len(), inner.len()), (60, 10));
+            vm.space().gc();
+            assert!(alive(&nested, "kept"));
+            nested
+        };
+        assert_eq!(scope.len(), 50);
+        vm.space().gc();
+        assert!(alive(&rooted, "kept"));
+        assert!(nested.iter().all(|s| s.upgrade().is_none()));
+
+        drop(scope);
+        vm.space().gc();
+        assert!(rooted.iter().all(|s| s.upgrade().is_none()));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    #[should_panic(expected = "RootScope dropped")]
+    fn test_root_scope_order() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let outer = unsafe { vm.root_scope() };
+        let _inner = unsafe { vm.root_scope() };
+        drop(outer);
+    }
+}
@@ -2,39 +2,88 @@ use crate::{
     bytecode::{opcodes::Op, TypeFeedBack},
     runtime::{
         arguments::Arguments,
+        array::JsArray,
+        attributes::*,
         env::Env,
         error::{JsError, JsTypeError},
         function::JsVMFunction,
+        iterator::iterate_or_arraylike,
         js_arguments::JsArguments,
         object::{JsHint, JsObject, ObjectTag},
+        private_name::JsPrivateName,
+        property_descriptor::DataDescriptor,
+        regexp::JsRegExp,
         slot::Slot,
         string::JsString,
         structure::Structure,
-        symbol::Symbol,
+        symbol::{is_array_index, Symbol},
         value::{CMP_FALSE, CMP_TRUE},
     },
 };
-use frame::FrameBase;
-use std::ptr::null_mut;
+use frame::{FrameBase, TryHandler};
+use std::{io::Write, ptr::null_mut};
 
 use crate::{bytecode::ByteCode, heap::cell::Gc, runtime::value::JsValue, vm::VirtualMachine};
 
 pub mod frame;
-const LOG: bool = true;
+/// Object operand of the private element opcodes, only objects can have private elements.
+fn private_target(
+    vm: &mut VirtualMachine,
+    obj: JsValue,
+    name: Gc<JsPrivateName>,
+) -> Result<Gc<JsObject>, JsValue> {
+    if obj.is_object() {
+        return Ok(obj.as_object());
+    }
+    let msg = format!(
+        "Cannot use private member {} on a non-object",
+        vm.description(name.description())
+    );
+    let msg = JsString::new(vm, msg);
+    Err(JsValue::new(JsTypeError::new(vm, msg, None)))
+}
+
+/// Array index a computed member key stands for, only numbers are looked at so the key never
+/// has to be interned.
+fn array_index(key: JsValue) -> Option<u32> {
+    if !key.is_number() {
+        return None;
+    }
+    is_array_index(&key.number())
+}
+
+/// Writes the instruction at `pc`, its source line and the top of the operand stack to the stderr
+/// sink when [Options::trace_execution](crate::vm::Options::trace_execution) is set.
+unsafe fn trace_op(vm: &mut VirtualMachine, bcode: Gc<ByteCode>, pc: *const u8, op: Op) {
+    let offset = pc as usize - bcode.code_start as usize;
+    let line = bcode.line_at(offset as u32);
+    let top = if vm.stack > vm.stack_start {
+        format!("{}", vm.stack.sub(1).read())
+    } else {
+        "-".to_owned()
+    };
+    let _ = writeln!(
+        vm.stderr(),
+        "{:04} {:?} line {} top {}",
+        offset,
+        op,
+        line,
+        top
+    );
+}
+
 unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<JsValue, JsValue> {
     //let mut pc = (*frame).code;
-    if LOG {
-        println!("enter frame {:p}", frame);
-    }
     let ctx = vm.space().new_local_context();
     let bcode = (*frame).bcode.unwrap();
     let mut pc = (*frame).code;
+    let trace = vm.options().trace_execution;
     loop {
         let op = std::mem::transmute::<_, Op>(pc.cast::<u8>().read_unaligned());
-        pc = pc.add(1);
-        if LOG {
-            println!("{:?}", op);
+        if trace {
+            trace_op(vm, bcode, pc, op);
         }
+        pc = pc.add(1);
         (*frame).code = pc;
         match op {
             Op::OP_DROP => {
@@ -75,6 +124,9 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
                 let this = vm.get_this();
                 vm.upush(this);
             }
+            Op::OP_PUSH_NEW_TARGET => {
+                vm.upush((*frame).new_target);
+            }
             Op::OP_PUSH_TRUE => {
                 vm.upush(JsValue::new(true));
             }
@@ -142,15 +194,23 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
                 // middle path
                 if v1.is_number() && v2.is_number() {
                     let x = v1.number();
-                    let y = v1.number();
+                    let y = v2.number();
                     vm.upush(JsValue::new(x + y));
                     /* everything other down there is slow path */
                 } else if (v1.is_cell() && v1.as_cell().is::<JsString>())
                     || (v2.is_cell() && v2.as_cell().is::<JsString>())
                 {
-                    let s1 = v1.to_string(vm)?;
-                    let s2 = v2.to_string(vm)?;
-                    let res = JsString::new(vm, format!("{}{}", s1, s2));
+                    let v2 = ctx.new_local(v2);
+                    let s1 = ctx.new_local(v1.to_js_string(vm)?);
+                    let s2 = v2.to_js_string(vm)?;
+                    // concatenating the empty string keeps the other operand as it is.
+                    let res = if s1.as_str().is_empty() {
+                        s2
+                    } else if s2.as_str().is_empty() {
+                        *s1
+                    } else {
+                        JsString::new(vm, format!("{}{}", s1.as_str(), s2.as_str()))
+                    };
                     vm.upush(JsValue::new(res));
                 } else {
                     let v1 = v1.to_number(vm)?;
@@ -195,13 +255,7 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
                 let v1 = vm.upop();
                 let v1 = v1.to_number(vm)?;
                 let v2 = v2.to_number(vm)?;
-                let result = v1 / v2;
-                let val = if result as i32 as f64 == result {
-                    JsValue::new(result as i32)
-                } else {
-                    JsValue::new(result)
-                };
-                vm.upush(val);
+                vm.upush(JsValue::new_number(v1 / v2));
             }
             Op::OP_REM => {
                 // this opcode is special. We have int32 value for optimizing math
@@ -211,96 +265,49 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
                 let v1 = vm.upop();
                 let v1 = v1.to_number(vm)?;
                 let v2 = v2.to_number(vm)?;
-                let result = v1 % v2;
-                let val = if result as i32 as f64 == result {
-                    JsValue::new(result as i32)
-                } else {
-                    JsValue::new(result)
-                };
+                vm.upush(JsValue::new_number(v1 % v2));
             }
             Op::OP_LSHIFT => {
                 let v2 = vm.upop();
                 let v1 = vm.upop();
-
-                if v1.is_int32() && v2.is_int32() {
-                    let v1 = v1.as_int32();
-                    let v2 = v2.as_int32();
-                    let res = v1 << v2;
-                    vm.upush(JsValue::new(res));
-                } else {
-                    let v1 = v1.to_number(vm)? as i32;
-                    let v2 = v2.to_number(vm)? as i32;
-                    vm.upush(JsValue::new(v1 << v2));
-                }
+                let v1 = v1.to_int32(vm)?;
+                let v2 = v2.to_uint32(vm)?;
+                vm.upush(JsValue::new(v1.wrapping_shl(v2)));
             }
             Op::OP_RSHIFT => {
                 let v2 = vm.upop();
                 let v1 = vm.upop();
-
-                if v1.is_int32() && v2.is_int32() {
-                    let v1 = v1.as_int32();
-                    let v2 = v2.as_int32() as u32;
-                    let res = v1 >> v2;
-                    vm.upush(JsValue::new(res));
-                } else {
-                    let v1 = v1.to_number(vm)? as i32;
-                    let v2 = v2.to_number(vm)? as i32 as u32;
-                    vm.upush(JsValue::new(v1 >> v2));
-                }
+                let v1 = v1.to_int32(vm)?;
+                let v2 = v2.to_uint32(vm)?;
+                vm.upush(JsValue::new(v1.wrapping_shr(v2)));
             }
             Op::OP_URSHIFT => {
                 let v2 = vm.upop();
                 let v1 = vm.upop();
-
-                if v1.is_int32() && v2.is_int32() {
-                    let v1 = v1.as_int32() as u32;
-                    let v2 = v2.as_int32() as u32;
-                    let res = v1 >> v2;
-                    vm.upush(JsValue::new(res));
-                } else {
-                    let v1 = v1.to_number(vm)? as i32 as u32;
-                    let v2 = v2.to_number(vm)? as i32 as u32;
-                    vm.upush(JsValue::new(v1 >> v2));
-                }
+                let v1 = v1.to_uint32(vm)?;
+                let v2 = v2.to_uint32(vm)?;
+                vm.upush(JsValue::new(v1.wrapping_shr(v2) as f64));
             }
             Op::OP_OR => {
                 let v2 = vm.upop();
                 let v1 = vm.upop();
-                if v1.is_int32() && v2.is_int32() {
-                    let v1 = v1.as_int32();
-                    let v2 = v2.as_int32();
-                    vm.upush(JsValue::new(v1 | v2));
-                } else {
-                    let v1 = v1.to_number(vm)?;
-                    let v2 = v2.to_number(vm)?;
-                    vm.upush(JsValue::new(v1 as i32 | v2 as i32));
-                }
+                let v1 = v1.to_int32(vm)?;
+                let v2 = v2.to_int32(vm)?;
+                vm.upush(JsValue::new(v1 | v2));
             }
             Op::OP_AND => {
                 let v2 = vm.upop();
                 let v1 = vm.upop();
-                if v1.is_int32() && v2.is_int32() {
-                    let v1 = v1.as_int32();
-                    let v2 = v2.as_int32();
-                    vm.upush(JsValue::new(v1 & v2));
-                } else {
-                    let v1 = v1.to_number(vm)?;
-                    let v2 = v2.to_number(vm)?;
-                    vm.upush(JsValue::new(v1 as i32 & v2 as i32));
-                }
+                let v1 = v1.to_int32(vm)?;
+                let v2 = v2.to_int32(vm)?;
+                vm.upush(JsValue::new(v1 & v2));
             }
             Op::OP_XOR => {
                 let v2 = vm.upop();
                 let v1 = vm.upop();
-                if v1.is_int32() && v2.is_int32() {
-                    let v1 = v1.as_int32();
-                    let v2 = v2.as_int32();
-                    vm.upush(JsValue::new(v1 ^ v2));
-                } else {
-                    let v1 = v1.to_number(vm)?;
-                    let v2 = v2.to_number(vm)?;
-                    vm.upush(JsValue::new(v1 as i32 ^ v2 as i32));
-                }
+                let v1 = v1.to_int32(vm)?;
+                let v2 = v2.to_int32(vm)?;
+                vm.upush(JsValue::new(v1 ^ v2));
             }
 
             Op::OP_LT => {
@@ -390,6 +397,10 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
                 let offset = pc.cast::<i32>().read_unaligned();
                 pc = pc.add(4);
                 pc = pc.offset(offset as _);
+                // Loops jump backwards, checking there keeps them interruptible.
+                if offset < 0 {
+                    vm.poll_interrupt()?;
+                }
             }
             Op::OP_JMP_FALSE => {
                 let offset = pc.cast::<i32>().read_unaligned();
@@ -425,13 +436,128 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
                     bcode,
                 )?;
             }
+            Op::OP_GET_LOCAL => {
+                let ix = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                vm.upush(*(*frame).locals.get_unchecked(ix as usize));
+            }
+            Op::OP_SET_LOCAL => {
+                let val = vm.upop();
+                let ix = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                *(*frame).locals.get_unchecked_mut(ix as usize) = val;
+            }
+            Op::OP_FOR_IN_SETUP => {
+                let obj = vm.upop();
+                vm.upush(obj);
+                let names = if obj.is_object() {
+                    obj.as_object().for_in_keys(vm)?
+                } else {
+                    vec![]
+                };
+                let mut keys = JsArray::new(vm, 0);
+                // keep the array alive while the key strings are allocated.
+                vm.upush(JsValue::new(keys));
+                for (i, name) in names.iter().enumerate() {
+                    let desc = vm.description(*name);
+                    let key = JsString::new(vm, desc);
+                    keys.put(vm, Symbol::Indexed(i as _), JsValue::new(key), false)?;
+                }
+                vm.upush(JsValue::new(0));
+            }
+            Op::OP_DECL_PRIVATE_NAME => {
+                let ix = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                let name = bcode.names[ix as usize];
+                let private = ctx.new_local(JsPrivateName::new(vm, name));
+                let mut scope = (*frame).scope.as_object();
+                scope.define_own_property(
+                    vm,
+                    name,
+                    &*DataDescriptor::new(JsValue::new(*private), NONE),
+                    false,
+                )?;
+            }
+            Op::OP_INIT_PRIVATE_METHOD => {
+                let mut name = vm.upop().as_cell().downcast::<JsPrivateName>().unwrap();
+                let method = vm.upop();
+                name.set_method(method);
+            }
+            Op::OP_ADD_PRIVATE => {
+                let name = vm.upop().as_cell().downcast::<JsPrivateName>().unwrap();
+                let value = vm.upop();
+                let obj = vm.upop();
+                let mut obj = private_target(vm, obj, name)?;
+                obj.add_private(vm, name, value)?;
+            }
+            Op::OP_GET_PRIVATE => {
+                let name = vm.upop().as_cell().downcast::<JsPrivateName>().unwrap();
+                let obj = vm.upop();
+                let obj = private_target(vm, obj, name)?;
+                let value = obj.get_private(vm, name)?;
+                vm.upush(value);
+            }
+            Op::OP_SET_PRIVATE => {
+                let name = vm.upop().as_cell().downcast::<JsPrivateName>().unwrap();
+                let obj = vm.upop();
+                let mut obj = private_target(vm, obj, name)?;
+                let value = vm.upop();
+                obj.set_private(vm, name, value)?;
+            }
+            Op::OP_DEFINE_METHOD => {
+                let method = ctx.new_local(vm.upop());
+                let key = vm.upop();
+                let mut obj = vm.upop().as_object();
+                vm.upush(JsValue::new(obj));
+                let sym = key.to_symbol(vm)?;
+                obj.define_own_property(vm, sym, &*DataDescriptor::new(*method, W | C), false)?;
+            }
+            Op::OP_FOR_IN_NEXT => {
+                let offset = pc.cast::<i32>().read_unaligned();
+                pc = pc.add(4);
+                let mut index = vm.upop().as_int32() as u32;
+                let keys = vm.upop().as_object();
+                let obj = vm.upop();
+                vm.upush(obj);
+                vm.upush(JsValue::new(keys));
+                let mut next = None;
+                while index < keys.elements.length() {
+                    let key = keys.get(vm, Symbol::Indexed(index))?;
+                    index += 1;
+                    let sym = key.to_symbol(vm)?;
+                    if obj.as_object().has_property(vm, sym) {
+                        next = Some(key);
+                        break;
+                    }
+                }
+                vm.upush(JsValue::new(index));
+                match next {
+                    Some(key) => vm.upush(key),
+                    None => pc = pc.offset(offset as _),
+                }
+            }
             Op::OP_DECL_VAR => {
                 let name = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
                 let name = bcode.names[name as usize];
-                Env {
+                let mut env = Env {
                     record: (*frame).scope.as_object(),
+                };
+                // `var` and function declarations may repeat, the first one creates the binding.
+                if !env.has_own_variable(vm, name) {
+                    env.declare_variable(vm, name, true)?;
+                }
+            }
+            Op::OP_DECL_GLOBAL_VAR => {
+                let name = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                let name = bcode.names[name as usize];
+                let mut env = Env {
+                    record: vm.global_object(),
+                };
+                if !env.has_own_variable(vm, name) {
+                    env.declare_variable(vm, name, true)?;
                 }
-                .declare_variable(vm, name, true)?;
             }
             Op::OP_DECL_IMMUTABLE => {
                 let name = pc.cast::<u32>().read_unaligned();
@@ -443,21 +569,54 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
             }
             Op::OP_RET => {
                 let val = vm.upop();
-                if LOG {
-                    println!("leave frame {:p}", frame);
-                }
                 return Ok(val);
             }
-            Op::OP_TRY_PUSH_CATCH => {
+            Op::OP_TRY_PUSH_CATCH | Op::OP_TRY_PUSH_FINALLY => {
                 let offset = pc.cast::<i32>().read_unaligned();
                 pc = pc.add(4);
-                let to = pc.offset(offset as _);
-                (*frame).try_stack.push(to);
+                (*frame).try_stack.push(TryHandler {
+                    target: pc.offset(offset as _),
+                    stack: vm.stack,
+                    scope: (*frame).scope,
+                    finally: op == Op::OP_TRY_PUSH_FINALLY,
+                });
             }
             Op::OP_TRY_POP => {
                 (*frame).try_stack.pop();
             }
-            Op::OP_ENTER_CATCH => {}
+            Op::OP_ENTER_CATCH => {
+                let name = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                let name = bcode.names[name as usize];
+                let error = ctx.new_local(vm.upop());
+                let scope = (*frame).scope;
+                let structure = Structure::new_indexed(
+                    vm,
+                    if scope.is_object() {
+                        Some(scope.as_object())
+                    } else {
+                        None
+                    },
+                    false,
+                );
+                let mut record = ctx.new_local(JsObject::new(
+                    vm,
+                    structure,
+                    JsObject::get_class(),
+                    ObjectTag::Ordinary,
+                ));
+                let _ = record.define_own_property(
+                    vm,
+                    name,
+                    &*DataDescriptor::new(*error, W | E | C),
+                    false,
+                );
+                (*frame).scope = JsValue::new(*record);
+            }
+            Op::OP_EXIT_CATCH => {
+                let scope = (*frame).scope.as_object();
+                (*frame).scope = JsValue::new(scope.prototype().unwrap());
+            }
             Op::OP_THROW => {
                 // exception is handled in upper function.
                 let v1 = vm.upop();
@@ -494,6 +653,9 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
                     return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
                 }
                 args.ctor_call = is_ctor;
+                if is_ctor {
+                    args.new_target = v1;
+                }
                 let mut obj = v1.as_object();
                 //let f = obj.as_function_mut();
 
@@ -517,17 +679,15 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
             }
             Op::OP_GET => {
                 let obj = vm.upop();
-                let name = vm.upop();
-                let sym = name.to_symbol(vm)?;
-                let val = vm.get_(obj, sym)?;
+                let key = vm.upop();
+                let val = vm.get_by_val(obj, key)?;
                 vm.upush(val);
             }
             Op::OP_SET => {
                 let obj = vm.upop();
-                let name = vm.upop();
+                let key = vm.upop();
                 let val = vm.upop();
-                let sym = name.to_symbol(vm)?;
-                vm.put_(obj, sym, val, bcode.strict)?;
+                vm.put_by_val(obj, key, val, bcode.strict)?;
             }
             Op::OP_SET_PROP => {
                 let ix = pc.cast::<u32>().read_unaligned();
@@ -549,6 +709,116 @@ unsafe fn eval_bcode(vm: &mut VirtualMachine, frame: *mut FrameBase) -> Result<J
                 let val = vm.get_prop(obj, name, fix, bcode.strict, bcode)?;
                 vm.upush(val);
             }
+            Op::OP_GET_PROP_FOR_UPDATE => {
+                let ix = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                let fix = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                let name = bcode.names[ix as usize];
+                let obj = vm.upop();
+                let val = vm.get_prop_for_update(obj, name, fix, bcode)?;
+                vm.upush(obj);
+                vm.upush(val);
+            }
+            Op::OP_UPDATE_PROP => {
+                let ix = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                let fix = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                let name = bcode.names[ix as usize];
+                let val = vm.upop();
+                let obj = vm.upop();
+                vm.update_prop(obj, name, val, fix, bcode.strict, bcode)?;
+            }
+            Op::OP_CREATE_OBJ => {
+                let structure = vm.global_data().object_structure.unwrap();
+                let obj = JsObject::new(vm, structure, JsObject::get_class(), ObjectTag::Ordinary);
+                vm.upush(JsValue::new(obj));
+            }
+            Op::OP_INIT_PROP => {
+                let val = vm.upop();
+                let key = vm.upop();
+                let mut obj = vm.upop().as_object();
+                vm.upush(JsValue::new(obj));
+                let sym = key.to_symbol(vm)?;
+                obj.define_own_property(vm, sym, &*DataDescriptor::new(val, W | E | C), false)?;
+            }
+            Op::OP_CREATE_LITERAL_OBJ => {
+                let ix = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                let structure = bcode.structures[ix as usize];
+                let count = structure.get_slots_size();
+                let mut obj =
+                    JsObject::new(vm, structure, JsObject::get_class(), ObjectTag::Ordinary);
+                for n in (0..count).rev() {
+                    *obj.direct_mut(n) = vm.upop();
+                }
+                vm.upush(JsValue::new(obj));
+            }
+            Op::OP_NEW_REGEXP => {
+                let flags = vm.upop().as_string();
+                let pattern = vm.upop().as_string();
+                let regexp = JsRegExp::new(vm, pattern.as_str(), flags.as_str())?;
+                vm.upush(JsValue::new(regexp));
+            }
+            Op::OP_DEBUGGER => {
+                let line = pc.cast::<u32>().read_unaligned();
+                pc = pc.add(4);
+                vm.debugger(line);
+            }
+            Op::OP_CREATE_ARR => {
+                let arr = JsArray::new(vm, 0);
+                vm.upush(JsValue::new(arr));
+            }
+            Op::OP_ARRAY_PUSH => {
+                let val = vm.upop();
+                // array stays on the stack so it is kept alive.
+                let mut arr = vm.upop().as_object();
+                vm.upush(JsValue::new(arr));
+                let index = arr.elements.length();
+                if val.is_empty() {
+                    arr.set_length(vm, index + 1, true)?;
+                } else {
+                    arr.put(vm, Symbol::Indexed(index), val, true)?;
+                }
+            }
+            Op::OP_ARRAY_SPREAD => {
+                let lctx = vm.space().new_local_context();
+                let val = lctx.new_local(vm.upop());
+                let mut arr = vm.upop().as_object();
+                vm.upush(JsValue::new(arr));
+                let mut index = arr.elements.length();
+                iterate_or_arraylike(vm, *val, &mut |vm, val| {
+                    arr.put(vm, Symbol::Indexed(index), val, true)?;
+                    index += 1;
+                    Ok(())
+                })?;
+            }
+            Op::OP_OBJECT_SPREAD => {
+                let lctx = vm.space().new_local_context();
+                let source = lctx.new_local(vm.upop());
+                // the literal stays on the stack so it is kept alive.
+                let target = vm.upop().as_object();
+                vm.upush(JsValue::new(target));
+                let result = vm.spread_object(target, *source)?;
+                vm.upop();
+                vm.upush(JsValue::new(result));
+            }
+            Op::OP_DELETE => {
+                let name = vm.upop();
+                let obj = vm.upop();
+                let sym = name.to_symbol(vm)?;
+                let res = vm.delete_(obj, sym, bcode.strict)?;
+                vm.upush(JsValue::new(res));
+            }
+            Op::OP_DELETE_VAR => {
+                let name = vm.upop();
+                let sym = name.to_symbol(vm)?;
+                // strict code never gets here, see `Compiler::syntax_error`.
+                // declared bindings are never deletable, unresolvable references are.
+                let found = (*frame).scope.as_object().has_property(vm, sym);
+                vm.upush(JsValue::new(!found));
+            }
 
             _ => todo!("unimplemented or unknown opcode {:?}", op),
         }
@@ -561,9 +831,15 @@ unsafe fn eval_internal(
     pc: *mut u8,
     this: JsValue,
     scope: Gc<JsObject>,
+    new_target: JsValue,
+    locals: Vec<JsValue>,
 ) -> Result<JsValue, JsValue> {
-    let mut frame = vm.init_call_frame_bcode(bcode, JsValue::new(scope), this, pc, false);
+    vm.poll_interrupt()?;
+    let is_ctor = !new_target.is_undefined();
+    let mut frame = vm.init_call_frame_bcode(bcode, JsValue::new(scope), this, pc, is_ctor);
     (*frame).code = bcode.code_start;
+    (*frame).new_target = new_target;
+    (*frame).locals = locals;
     loop {
         match eval_bcode(vm, frame) {
             Ok(val) => {
@@ -571,19 +847,38 @@ unsafe fn eval_internal(
                 vm.frame = frame.prev;
                 return Ok(val);
             }
-            Err(e) => match (*frame).try_stack.pop() {
-                Some(addr) => {
-                    (*frame).code = addr as *mut u8;
+            Err(e) => match vm.pop_try_handler(frame) {
+                Some(handler) => {
+                    (*frame).code = handler.target as *mut u8;
+                    (*frame).scope = handler.scope;
+                    vm.stack = handler.stack;
                     vm.upush(e);
                     continue;
                 }
-                None => return Err(e),
+                None => {
+                    let frame = Box::from_raw(frame);
+                    vm.frame = frame.prev;
+                    return Err(e);
+                }
             },
         }
     }
 }
 
 impl VirtualMachine {
+    /// Pops the innermost handler of `frame` the exception being thrown stops at. While an
+    /// interrupt unwinds only `finally` blocks run, an abort runs none.
+    unsafe fn pop_try_handler(&mut self, frame: *mut FrameBase) -> Option<TryHandler> {
+        while let Some(handler) = (*frame).try_stack.pop() {
+            match self.interrupt_level {
+                0 => return Some(handler),
+                1 if handler.finally => return Some(handler),
+                _ => {}
+            }
+        }
+        None
+    }
+
     #[allow(clippy::explicit_counter_loop)]
     pub(crate) fn perform_vm_call(
         &mut self,
@@ -593,6 +888,17 @@ impl VirtualMachine {
     ) -> Result<JsValue, JsValue> {
         unsafe {
             let f = func;
+            let ctx = self.space().new_local_context();
+            let rest = match f.code.rest {
+                Some(_) => {
+                    let values = (f.code.params.len()..args_.size())
+                        .map(|i| args_.at(i))
+                        .collect::<Vec<_>>();
+                    JsValue::new(*self.new_array(&ctx, &values))
+                }
+                None => JsValue::undefined(),
+            };
+            let rest = ctx.new_local(rest);
             let scope = env.as_object();
             let mut nscope = JsObject::new(
                 self,
@@ -603,21 +909,51 @@ impl VirtualMachine {
             let mut i = 0;
 
             for p in f.code.params.iter() {
-                let _ = nscope
-                    .put(self, *p, args_[i], false)
-                    .unwrap_or_else(|_| panic!());
+                // parameters resolved to slots are stored in the frame below.
+                if !f.code.local_names.contains(p) {
+                    let _ = nscope
+                        .put(self, *p, args_.at(i), false)
+                        .unwrap_or_else(|_| panic!());
+                }
 
                 i += 1;
             }
+            if let Some(name) = f.code.rest {
+                if !f.code.local_names.contains(&name) {
+                    let _ = nscope
+                        .put(self, name, *rest, false)
+                        .unwrap_or_else(|_| panic!());
+                }
+            }
+            let locals = f
+                .code
+                .local_names
+                .iter()
+                .map(|name| match f.code.params.iter().rposition(|p| p == name) {
+                    Some(ix) => args_.at(ix),
+                    None if f.code.rest == Some(*name) => *rest,
+                    None => JsValue::undefined(),
+                })
+                .collect();
 
-            let args = JsArguments::new(self, nscope, &f.code.params);
+            // strict code and non-simple parameter lists get an unmapped `arguments`.
+            let mapped = !f.code.strict && f.code.simple_params;
+            let args = JsArguments::new(self, nscope, &f.code.params, args_, mapped);
             let _ = nscope.put(self, Symbol::arguments(), JsValue::new(args), false);
             let mut slot = Slot::new();
             let _slot = nscope
                 .get_slot(self, Symbol::arguments(), &mut slot)
                 .unwrap_or_else(|_| panic!());
 
-            eval_internal(self, f.code, f.code.code_start, args_.this, nscope)
+            eval_internal(
+                self,
+                f.code,
+                f.code.code_start,
+                args_.this,
+                nscope,
+                args_.new_target,
+                locals,
+            )
         }
     }
     fn bcode_get_var(
@@ -627,6 +963,9 @@ impl VirtualMachine {
         feedback: u32,
         mut bcode: Gc<ByteCode>,
     ) -> Result<JsValue, JsValue> {
+        if self.options().disable_ics {
+            return Env { record: scope }.get_variable(self, name);
+        }
         match &bcode.feedback[feedback as usize] {
             TypeFeedBack::Generic => return Env { record: scope }.get_variable(self, name),
             TypeFeedBack::Structure(structure, offset, count) => {
@@ -690,6 +1029,10 @@ impl VirtualMachine {
         strict: bool,
         mut bcode: Gc<ByteCode>,
     ) -> Result<(), JsValue> {
+        if self.options().disable_ics {
+            Env { record: scope }.set_variable(self, name, val, strict)?;
+            return Ok(());
+        }
         match &bcode.feedback[feedback as usize] {
             TypeFeedBack::Generic => {
                 Env { record: scope }.set_variable(self, name, val, strict)?;
@@ -748,6 +1091,8 @@ impl VirtualMachine {
             this_obj: JsValue::undefined(),
             thrown_val: JsValue::undefined(),
             callee: JsValue::undefined(),
+            new_target: JsValue::undefined(),
+            locals: vec![],
         });
 
         let p = Box::into_raw(frame);
@@ -773,6 +1118,49 @@ impl VirtualMachine {
         frame
     }
 
+    /// Own properties of string primitives are read-only, writing them is ignored in sloppy code
+    /// and throws in strict code. Returns whether `name` is such a property.
+    fn reject_string_write(
+        &mut self,
+        obj: JsValue,
+        name: Symbol,
+        strict: bool,
+    ) -> Result<bool, JsValue> {
+        if !obj.is_string() || !obj.as_string().has_own_property(name) {
+            return Ok(false);
+        }
+        if strict {
+            let desc = self.description(name);
+            let msg = JsString::new(
+                self,
+                format!("Cannot assign to read only property '{}' of string", desc),
+            );
+            return Err(JsValue::new(JsTypeError::new(self, msg, None)));
+        }
+        Ok(true)
+    }
+
+    /// `[[Set]]` on a primitive, setters on its prototype see the primitive as `this` and data
+    /// properties can't be created.
+    fn put_primitive(
+        &mut self,
+        obj: JsValue,
+        name: Symbol,
+        val: JsValue,
+        strict: bool,
+    ) -> Result<(), JsValue> {
+        let proto = obj.get_primitive_proto(self);
+        if !proto.put_with_receiver(self, name, val, obj)? && strict {
+            let desc = self.description(name);
+            let msg = JsString::new(
+                self,
+                format!("Cannot create property '{}' on a primitive", desc),
+            );
+            return Err(JsValue::new(JsTypeError::new(self, msg, None)));
+        }
+        Ok(())
+    }
+
     fn put_(
         &mut self,
         obj: JsValue,
@@ -780,21 +1168,141 @@ impl VirtualMachine {
         val: JsValue,
         strict: bool,
     ) -> Result<(), JsValue> {
-        let mut obj = if obj.is_object() {
-            obj.as_object()
+        if self.reject_string_write(obj, name, strict)? {
+            return Ok(());
+        }
+        if !obj.is_object() {
+            return self.put_primitive(obj, name, val, strict);
+        }
+        obj.as_object().put(self, name, val, strict)
+    }
+
+    /// section 7.3.25 CopyDataProperties(target, source, excludedItems) for `{...source}`,
+    /// returns the object that replaces `target`.
+    fn spread_object(
+        &mut self,
+        target: Gc<JsObject>,
+        source: JsValue,
+    ) -> Result<Gc<JsObject>, JsValue> {
+        if !source.is_object() && !source.is_string() {
+            return Ok(target);
+        }
+        let ctx = self.space().new_local_context();
+        let mut target = ctx.new_local(target);
+        if source.is_string() {
+            for i in 0..source.as_string().utf16_len() {
+                let val = source.get_slot(self, Symbol::Indexed(i), &mut Slot::new())?;
+                let desc = DataDescriptor::new(val, W | E | C);
+                target.define_own_property(self, Symbol::Indexed(i), &*desc, true)?;
+            }
+            return Ok(*target);
+        }
+        let source = ctx.new_local(source.as_object());
+        if let Some(structure) = self.spread_structure(*target, *source) {
+            let mut copy =
+                JsObject::new(self, structure, JsObject::get_class(), ObjectTag::Ordinary);
+            for n in 0..structure.get_slots_size() {
+                *copy.direct_mut(n) = *source.direct(n);
+            }
+            self.structure_stats_mut().spread_structure_shares += 1;
+            return Ok(copy);
+        }
+        let keys = source.own_enumerable_keys(self)?;
+        for key in keys {
+            let val = source.get(self, key)?;
+            target.define_own_property(self, key, &*DataDescriptor::new(val, W | E | C), true)?;
+        }
+        Ok(*target)
+    }
+
+    /// Structure `{...source}` can take over when `target` has no properties yet: `source` is a
+    /// plain object inheriting from `Object.prototype` with a shared structure, no elements and
+    /// only writable, enumerable and configurable data properties, which are exactly the ones
+    /// the spread would define. Accessors and proxies have to be read one property at a time.
+    fn spread_structure(
+        &mut self,
+        target: Gc<JsObject>,
+        source: Gc<JsObject>,
+    ) -> Option<Gc<Structure>> {
+        let object_structure = self.global_data().object_structure.unwrap();
+        if !Gc::ptr_eq(target.structure(), object_structure) || target.elements.length() != 0 {
+            return None;
+        }
+        if source.tag() != ObjectTag::Ordinary
+            || !std::ptr::eq(source.class(), JsObject::get_class())
+            || source.elements.length() != 0
+        {
+            return None;
+        }
+        let mut structure = source.structure();
+        if structure.is_unique() || structure.prototype() != self.global_data().object_prototype {
+            return None;
+        }
+        structure.allocate_table_if_needed(self);
+        let plain = structure.table().map_or(true, |table| {
+            table.values().all(|entry| {
+                entry.attrs.is_simple_data()
+                    && entry.attrs.is_enumerable()
+                    && entry.attrs.is_configurable()
+            })
+        });
+        if plain {
+            Some(structure)
         } else {
-            obj.get_primitive_proto(self)
-        };
-        obj.put(self, name, val, strict)
+            None
+        }
+    }
+
+    fn delete_(&mut self, obj: JsValue, name: Symbol, strict: bool) -> Result<bool, JsValue> {
+        if obj.is_object() {
+            return obj.as_object().delete(self, name, strict);
+        }
+        if obj.is_undefined_or_null() {
+            let desc = self.description(name);
+            let msg = JsString::new(
+                self,
+                format!("Cannot delete property '{}' of undefined or null", desc),
+            );
+            return Err(JsValue::new(JsTypeError::new(self, msg, None)));
+        }
+        // primitives have no configurable own properties to remove.
+        Ok(true)
     }
 
     fn get_(&mut self, obj: JsValue, name: Symbol) -> Result<JsValue, JsValue> {
-        let mut obj = if obj.is_object() {
-            obj.as_object()
-        } else {
-            obj.get_primitive_proto(self)
-        };
-        obj.get(self, name)
+        let mut slot = Slot::new();
+        obj.get_slot(self, name, &mut slot)
+    }
+
+    /// `obj[key]`, array index keys are first looked up in the dense elements of `obj`.
+    fn get_by_val(&mut self, obj: JsValue, key: JsValue) -> Result<JsValue, JsValue> {
+        if obj.is_object() {
+            let obj = obj.as_object();
+            if let Some(val) = array_index(key).and_then(|ix| obj.get_dense_element(ix)) {
+                return Ok(val);
+            }
+        }
+        let name = key.to_symbol(self)?;
+        self.get_(obj, name)
+    }
+
+    /// `obj[key] = val`, overwrites an existing dense element of `obj` in place.
+    fn put_by_val(
+        &mut self,
+        obj: JsValue,
+        key: JsValue,
+        val: JsValue,
+        strict: bool,
+    ) -> Result<(), JsValue> {
+        if obj.is_object() {
+            if let Some(ix) = array_index(key) {
+                if obj.as_object().put_dense_element(ix, val) {
+                    return Ok(());
+                }
+            }
+        }
+        let name = key.to_symbol(self)?;
+        self.put_(obj, name, val, strict)
     }
 
     fn get_prop(
@@ -805,6 +1313,10 @@ impl VirtualMachine {
         strict: bool,
         mut bcode: Gc<ByteCode>,
     ) -> Result<JsValue, JsValue> {
+        if self.options().disable_ics {
+            let mut slot = Slot::new();
+            return obj.get_slot(self, name, &mut slot);
+        }
         match &bcode.feedback[feedback as usize] {
             TypeFeedBack::Generic => {
                 let mut slot = Slot::new();
@@ -830,17 +1342,22 @@ impl VirtualMachine {
                 let structure = *structure;
                 let offset = *offset;
                 let count = *count;
-                let obj = if obj.is_object() {
+                let base = if obj.is_object() {
                     obj.as_object()
                 } else {
                     obj.get_primitive_proto(self)
                 };
-                if let Some(hit) = self.try_cache(structure, obj) {
+                if let Some(hit) = self.try_cache(structure, base) {
+                    self.ic_stats_mut().hits += 1;
                     return Ok(*hit.direct(offset as _));
                 } else {
+                    self.ic_stats_mut().misses += 1;
+                    // misses go through the value so that own properties of string primitives
+                    // are not looked up on String.prototype.
                     if count == 64 {
                         bcode.feedback[feedback as usize] = TypeFeedBack::Generic;
-                        return obj.get(self, name);
+                        let mut slot = Slot::new();
+                        return obj.get_slot(self, name, &mut slot);
                     } else {
                         let mut slot = Slot::new();
                         let val = obj.get_slot(self, name, &mut slot)?;
@@ -871,11 +1388,16 @@ impl VirtualMachine {
         strict: bool,
         mut bcode: Gc<ByteCode>,
     ) -> Result<(), JsValue> {
-        let mut obj = if obj.is_object() {
-            obj.as_object()
-        } else {
-            obj.get_primitive_proto(self)
-        };
+        if self.reject_string_write(obj, name, strict)? {
+            return Ok(());
+        }
+        if !obj.is_object() {
+            return self.put_primitive(obj, name, val, strict);
+        }
+        let mut obj = obj.as_object();
+        if self.options().disable_ics {
+            return obj.put(self, name, val, strict);
+        }
         match &bcode.feedback[feedback as usize] {
             TypeFeedBack::Generic => obj.put(self, name, val, strict),
             TypeFeedBack::None => {
@@ -900,15 +1422,17 @@ impl VirtualMachine {
                 let count = *count;
 
                 if let Some(hit) = self.try_cache(structure, obj) {
+                    self.ic_stats_mut().hits += 1;
                     *obj.direct_mut(offset as _) = val;
                     return Ok(());
                 } else {
+                    self.ic_stats_mut().misses += 1;
                     if count == 64 {
                         bcode.feedback[feedback as usize] = TypeFeedBack::Generic;
                         return obj.put(self, name, val, strict);
                     } else {
                         let mut slot = Slot::new();
-                        let val = obj.get_slot(self, name, &mut slot)?;
+                        obj.put_slot(self, name, val, &mut slot, strict)?;
                         if slot.is_store_cacheable() {
                             bcode.feedback[feedback as usize] = TypeFeedBack::Structure(
                                 slot.base()
@@ -920,12 +1444,847 @@ impl VirtualMachine {
                                 count + 1,
                             );
                         }
-
-                        obj.put(self, name, val, strict)
+                        Ok(())
                     }
                 }
             }
             _ => unreachable!(),
         }
     }
+
+    /// Reads `obj.name` for a compound assignment `obj.name op= rhs`. When `obj` has the
+    /// structure cached in the feedback slot the own data property is read through that slot,
+    /// the [OP_UPDATE_PROP](Op::OP_UPDATE_PROP) writing the result back shares the slot and
+    /// does not look the property up again.
+    fn get_prop_for_update(
+        &mut self,
+        obj: JsValue,
+        name: Symbol,
+        feedback: u32,
+        mut bcode: Gc<ByteCode>,
+    ) -> Result<JsValue, JsValue> {
+        if obj.is_object() && !self.options().disable_ics {
+            if let TypeFeedBack::Structure(structure, offset, misses) =
+                bcode.feedback[feedback as usize]
+            {
+                let base = obj.as_object();
+                if Gc::ptr_eq(base.structure(), structure) {
+                    self.ic_stats_mut().hits += 1;
+                    return Ok(*base.direct(offset as _));
+                }
+                self.ic_stats_mut().misses += 1;
+                bcode.feedback[feedback as usize] = if misses == 64 {
+                    TypeFeedBack::Generic
+                } else {
+                    TypeFeedBack::Structure(structure, offset, misses + 1)
+                };
+            }
+        }
+        let mut slot = Slot::new();
+        obj.get_slot(self, name, &mut slot)
+    }
+
+    /// Writes the result of a compound assignment back to `obj.name`, straight to the slot
+    /// cached by [Self::get_prop_for_update] when `obj` still has its structure. Otherwise it is
+    /// a regular put, which is what accessors and proxies always take.
+    fn update_prop(
+        &mut self,
+        obj: JsValue,
+        name: Symbol,
+        val: JsValue,
+        feedback: u32,
+        strict: bool,
+        mut bcode: Gc<ByteCode>,
+    ) -> Result<(), JsValue> {
+        let mut cache = obj.is_object() && !self.options().disable_ics;
+        let mut misses = 0;
+        if cache {
+            match bcode.feedback[feedback as usize] {
+                TypeFeedBack::Structure(structure, offset, count) => {
+                    let mut base = obj.as_object();
+                    // evaluating and converting the operands can run code that reshapes `obj`.
+                    if Gc::ptr_eq(base.structure(), structure) {
+                        *base.direct_mut(offset as _) = val;
+                        return Ok(());
+                    }
+                    misses = count;
+                }
+                TypeFeedBack::Generic => cache = false,
+                _ => (),
+            }
+        }
+
+        if self.reject_string_write(obj, name, strict)? {
+            return Ok(());
+        }
+        if !obj.is_object() {
+            return self.put_primitive(obj, name, val, strict);
+        }
+        let mut base = obj.as_object();
+        let mut slot = Slot::new();
+        base.put_slot(self, name, val, &mut slot, strict)?;
+        if cache && slot.is_store_cacheable() {
+            bcode.feedback[feedback as usize] =
+                TypeFeedBack::Structure(base.structure(), slot.offset(), misses);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bytecode::TypeFeedBack,
+        heap::cell::Gc,
+        runtime::error::{JsSyntaxError, JsTypeError},
+        vm::{BufferedError, Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_binary_operand_order() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "sub = 7 - 2;
+             div = 8 / 2;
+             cat = 'p' + 1;
+             lt = 1 < 2;
+             le = 2 <= 1;
+             gt = 1 > 2;
+             ge = 2 >= 1;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get("sub").number(), 5.0);
+        assert_eq!(get("div").number(), 4.0);
+        assert_eq!(get("cat").as_string().as_str(), "p1");
+        assert!(get("lt").as_boolean());
+        assert!(!get("le").as_boolean());
+        assert!(!get("gt").as_boolean());
+        assert!(get("ge").as_boolean());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_strict_delete_var() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval("'use strict'; var x = 1; delete x;");
+        match res {
+            Err(e) => assert!(std::ptr::eq(
+                e.as_object().class(),
+                JsSyntaxError::get_class()
+            )),
+            Ok(_) => panic!("expected SyntaxError"),
+        }
+        // an early error, the script does not run even though the `delete` is never reached.
+        let res = vm.eval("'use strict'; ran = 1; if (false) delete x;");
+        match res {
+            Err(e) => assert!(std::ptr::eq(
+                e.as_object().class(),
+                JsSyntaxError::get_class()
+            )),
+            Ok(_) => panic!("expected SyntaxError"),
+        }
+        let res = vm.eval("function f() { 'use strict'; if (false) delete x; } ran = 1;");
+        assert!(res.is_err());
+        let global = vm.global_object();
+        let ran = vm.intern("ran");
+        assert!(!global.has_property(&mut vm, ran));
+        let res = vm.eval("var y = 1; delete y;");
+        assert!(res.is_ok());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_unsupported_syntax() {
+        let mut vm = VirtualMachine::new(Options::default());
+        for src in [
+            "ran = 1; f = () => 1;",
+            "ran = 1; for (;;) {}",
+            "ran = 1; var [a] = [1];",
+            "function f() { ran = 1; return 1 ? 2 : 3; }",
+            "ran = 1; x = { get y() { return 1; } };",
+        ]
+        .iter()
+        {
+            match vm.eval(src) {
+                Err(e) => assert!(std::ptr::eq(
+                    e.as_object().class(),
+                    JsSyntaxError::get_class()
+                )),
+                Ok(_) => panic!("{} compiled", src),
+            }
+        }
+        let global = vm.global_object();
+        let ran = vm.intern("ran");
+        assert!(!global.has_property(&mut vm, ran));
+        // calls of computed members used to be reported as unreachable.
+        let res = vm.eval(
+            "var o = { f: function () { return this.v; }, v: 3 };
+             k = 'f';
+             r = o['f']() + o[k]();",
+        );
+        assert!(res.is_ok());
+        let r = vm.get(global, "r").unwrap_or_else(|_| panic!());
+        assert_eq!(r.as_int32(), 6);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_local_slots() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function sum(a, b) {
+                 var c = a + b;
+                 var d = c * 2;
+                 return d + c + a;
+             }
+             y = 100;
+             function shadow(x) {
+                 var y = x + 1;
+                 return y;
+             }
+             function nested(x) {
+                 function inner(x) {
+                     var y = x * 10;
+                     return y;
+                 }
+                 return inner(x + 1) + x;
+             }
+             s = sum(1, 2);
+             t = shadow(1);
+             u = nested(1);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get =
+            |vm: &mut VirtualMachine, name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get(&mut vm, "s").as_int32(), 10);
+        assert_eq!(get(&mut vm, "t").as_int32(), 2);
+        assert_eq!(get(&mut vm, "y").as_int32(), 100);
+        assert_eq!(get(&mut vm, "u").as_int32(), 21);
+
+        let disassemble = |vm: &mut VirtualMachine, name: &str| {
+            let f = get(vm, name).as_object();
+            let mut out = String::new();
+            f.as_function()
+                .as_vm()
+                .code
+                .display_to(&mut out)
+                .unwrap_or_else(|_| panic!());
+            out
+        };
+        let count = |out: &str, op: &str| out.lines().filter(|line| line.contains(op)).count();
+        // every variable access in `sum` is a slot access.
+        let sum = disassemble(&mut vm, "sum");
+        assert_eq!(count(&sum, "get_var"), 0);
+        assert_eq!(count(&sum, "set_var"), 0);
+        assert_eq!(count(&sum, "get_local"), 6);
+        assert_eq!(count(&sum, "set_local"), 2);
+        // `x` is referenced from `inner` so it stays in the scope object.
+        let nested = disassemble(&mut vm, "nested");
+        assert_eq!(count(&nested, "get_var"), 2);
+        assert_eq!(count(&nested, "get_local"), 1);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_local_slots_with_default_parameters() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function f(a, g = function () { return a; }) {
+                 a = 2;
+                 return g();
+             }
+             function h(a, n = arguments.length) {
+                 var b = a + n;
+                 return b;
+             }
+             r = f(1);
+             s = h(1);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        // the closure sees the assignment made in the body.
+        assert_eq!(get("r").as_int32(), 2);
+        assert_eq!(get("s").as_int32(), 2);
+        // `arguments` in a default value disables slots for the whole function.
+        let mut out = String::new();
+        get("h")
+            .as_object()
+            .as_function()
+            .as_vm()
+            .code
+            .display_to(&mut out)
+            .unwrap_or_else(|_| panic!());
+        assert!(!out.contains("get_local"));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_disable_ics() {
+        let mut vm = VirtualMachine::new(Options::builder().disable_ics(true).build());
+        // the load in `getx` is first served from `F.prototype`, then `o` gets its own `x`
+        // which has to shadow it.
+        let res = vm.eval(
+            "function F() {}
+             F.prototype.x = 1;
+             o = new F();
+             function getx(obj) { return obj.x; }
+             a = getx(o);
+             o.x = 2;
+             b = getx(o);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get =
+            |vm: &mut VirtualMachine, name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get(&mut vm, "a").as_int32(), 1);
+        assert_eq!(get(&mut vm, "b").as_int32(), 2);
+        let getx = get(&mut vm, "getx").as_object();
+        let code = getx.as_function().as_vm().code;
+        assert!(code
+            .feedback
+            .iter()
+            .all(|feedback| matches!(feedback, TypeFeedBack::None)));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_compound_assignment() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function bump(o, i) {
+                 if (i === 100) {
+                     return o;
+                 }
+                 o.x += 1;
+                 return bump(o, i + 1);
+             }
+             function reassign(o, i) {
+                 if (i === 100) {
+                     return o;
+                 }
+                 o.x = o.x + 1;
+                 return reassign(o, i + 1);
+             }
+             data = { x: 1 };
+             other = { x: 1 };",
+        );
+        assert!(res.is_ok());
+        // the first update fills the feedback slot, every later one is a single hit.
+        let before = vm.ic_stats();
+        assert!(vm.eval("bump(data, 0);").is_ok());
+        let after = vm.ic_stats();
+        assert_eq!(after.hits - before.hits, 99);
+        assert_eq!(after.misses, before.misses);
+        // a load followed by a store looks the property up twice.
+        let before = vm.ic_stats();
+        assert!(vm.eval("reassign(other, 0);").is_ok());
+        let after = vm.ic_stats();
+        assert_eq!(after.hits - before.hits, 198);
+
+        let res = vm.eval(
+            "stored = 5;
+             acc = {};
+             Object.defineProperty(acc, 'x', {
+                 get: function () { return stored; },
+                 set: function (v) { stored = v * 10; }
+             });
+             function bumpAccessor(o, i) {
+                 if (i === 3) {
+                     return o;
+                 }
+                 o.x += 1;
+                 return bumpAccessor(o, i + 1);
+             }
+             bumpAccessor(acc, 0);
+             o = { s: 'a', n: 7 };
+             o.s += 'b';
+             o.n -= 2;
+             o.n *= 3;
+             o.n %= 4;
+             o.n <<= 3;
+             v = 10;
+             v -= 3;
+             v /= 2;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |obj, name: &str| vm.get(obj, name).unwrap_or_else(|_| panic!());
+        let data = get(global, "data").as_object();
+        assert_eq!(get(data, "x").as_int32(), 101);
+        assert_eq!(get(global, "stored").as_int32(), 6110);
+        let o = get(global, "o").as_object();
+        assert_eq!(get(o, "s").as_string().as_str(), "ab");
+        assert_eq!(get(o, "n").as_int32(), 24);
+        assert_eq!(get(global, "v").number(), 3.5);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_compound_assignment_semantics() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "log = '';
+             target = {};
+             Object.defineProperty(target, 'x', {
+                 get: function () { log += 'g'; return 1; },
+                 set: function (v) { log += 's'; }
+             });
+             function obj() { log += 'o'; return target; }
+             function rhs() { log += 'r'; return 1; }
+             obj().x += rhs();
+             n = { a: 4294967297, s: 1, u: 0 - 1, d: 0, r: 0 - 4 };
+             n.a |= 0;
+             n.s <<= 33;
+             n.u >>>= 0;
+             n.d /= 0 - 1;
+             n.r %= 2;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let log = vm.get(global, "log").unwrap_or_else(|_| panic!());
+        assert_eq!(log.as_string().as_str(), "ogrs");
+        let n = vm.get(global, "n").unwrap_or_else(|_| panic!()).as_object();
+        let mut get = |name: &str| vm.get(n, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get("a").as_int32(), 1);
+        assert_eq!(get("s").as_int32(), 2);
+        assert_eq!(get("u").number(), 4294967295.0);
+        for &name in ["d", "r"].iter() {
+            let val = get(name);
+            assert!(val.is_double() && val.as_double() == 0.0);
+            assert!(val.as_double().is_sign_negative());
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_for_in_enumeration_cache() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "o = { p1: { fa: 1, fb: 2 }, p2: { fa: 3, fb: 4 }, p3: { fa: 5, fb: 6 } };
+             keys = '';
+             for (var k in o) {}
+             for (var k in o.p1) { keys = keys + k; }",
+        );
+        assert!(res.is_ok());
+        let before = vm.structure_stats();
+        let res = vm.eval(
+            "sum = 0;
+             for (var k in o) {
+                 for (var j in o[k]) { sum = sum + o[k][j]; }
+             }
+             d = { a: 1, b: 2, c: 3 };
+             seen = '';
+             for (var k in d) { seen = seen + k; delete d.c; }",
+        );
+        assert!(res.is_ok());
+        let after = vm.structure_stats();
+        // every shape was enumerated before, `d` turns unique once `c` is deleted.
+        assert_eq!(
+            after.enumeration_cache_misses,
+            before.enumeration_cache_misses + 1
+        );
+        assert!(after.enumeration_cache_hits >= before.enumeration_cache_hits + 4);
+
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get("keys").as_string().as_str(), "fafb");
+        assert_eq!(get("sum").as_int32(), 21);
+        assert_eq!(get("seen").as_string().as_str(), "ab");
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_literal_structure_shared() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let before = vm.structure_stats();
+        let res = vm.eval(
+            "function make(list, i) {
+                 if (i === 50) {
+                     return list;
+                 }
+                 list[i] = { a: i, b: 'x', c: null, 'd': [i], a2: i + 1 };
+                 return make(list, i + 1);
+             }
+             list = make([], 0);
+             other = { a: 1, b: 2, c: 3, d: 4, a2: 5 };
+             dup = { a: 1, a: 2 };
+             last = list[49];
+             out = '' + last.a + last.b + last.c + last.d[0] + last.a2 + Object.keys(last).join();",
+        );
+        assert!(res.is_ok());
+        let after = vm.structure_stats();
+        // both literals have the same keys, `dup` repeats a key and goes through transitions.
+        assert_eq!(after.literal_structures, before.literal_structures + 1);
+
+        let global = vm.global_object();
+        let list = vm.get(global, "list").unwrap_or_else(|_| panic!());
+        let first = vm.get(list.as_object(), 0u32).unwrap_or_else(|_| panic!());
+        let structure = first.as_object().structure();
+        for i in 1..50u32 {
+            let obj = vm.get(list.as_object(), i).unwrap_or_else(|_| panic!());
+            assert!(Gc::ptr_eq(obj.as_object().structure(), structure));
+        }
+        let other = vm.get(global, "other").unwrap_or_else(|_| panic!());
+        assert!(Gc::ptr_eq(other.as_object().structure(), structure));
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "49xnull4950a,b,c,d,a2");
+        let dup = vm.get(global, "dup").unwrap_or_else(|_| panic!());
+        let a = vm.get(dup.as_object(), "a").unwrap_or_else(|_| panic!());
+        assert_eq!(a.as_int32(), 2);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_private_class_members() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "class Counter {
+                 #count = 0;
+                 step = 1;
+                 #bump() { this.#count = this.#count + this.step; }
+                 inc() { this.#bump(); return this.#count; }
+                 static peek(o) { return o.#count; }
+             }
+             c = new Counter();
+             c.inc();
+             n = c.inc();
+             keys = '';
+             for (var k in c) { keys = keys + k; }
+             names = Object.getOwnPropertyNames(c).length;
+             hidden = c['#count'];",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get("n").as_int32(), 2);
+        assert_eq!(get("keys").as_string().as_str(), "step");
+        assert_eq!(get("names").as_int32(), 1);
+        assert!(get("hidden").is_undefined());
+
+        let res = vm.eval("Counter.peek({});");
+        match res {
+            Err(e) => assert!(std::ptr::eq(
+                e.as_object().class(),
+                JsTypeError::get_class()
+            )),
+            Ok(_) => panic!("expected TypeError"),
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_unsupported_class_elements() {
+        let mut vm = VirtualMachine::new(Options::default());
+        for src in [
+            "ran = 1; class A extends Object {}",
+            "ran = 1; class B { get x() { return 1; } }",
+            "ran = 1; class C { static set x(v) {} }",
+            "ran = 1; class D { get #x() { return 1; } }",
+        ]
+        .iter()
+        {
+            match vm.eval(src) {
+                Err(e) => assert!(std::ptr::eq(
+                    e.as_object().class(),
+                    JsSyntaxError::get_class()
+                )),
+                Ok(_) => panic!("{} compiled", src),
+            }
+        }
+        let global = vm.global_object();
+        let ran = vm.intern("ran");
+        assert!(!global.has_property(&mut vm, ran));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_computed_member() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "arr = [1, 2, 3];
+             i = 1;
+             first = arr[i];
+             arr[i] = 5;
+             arr[1.5 + 0.5] = 6;
+             o = { ab: 7, 1: 'one' };
+             named = o['a' + 'b'];
+             numeric = o[1] + o['1'];
+             holes = [];
+             holes[2] = 3;
+             Object.prototype[1] = 'proto';
+             hole = holes[1];
+             p = [1, 2];
+             Object.preventExtensions(p);
+             p[0] = 3;
+             p[5] = 4;
+             s = 'xyz'[i];",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get("first").as_int32(), 2);
+        let arr = get("arr").as_object();
+        assert_eq!(arr.get_dense_element(1).map(|val| val.number()), Some(5.0));
+        assert_eq!(arr.get_dense_element(2).map(|val| val.number()), Some(6.0));
+        assert_eq!(get("named").as_int32(), 7);
+        assert_eq!(get("numeric").as_string().as_str(), "oneone");
+        // holes fall back to the prototype chain.
+        assert!(get("holes").as_object().get_dense_element(1).is_none());
+        assert_eq!(get("hole").as_string().as_str(), "proto");
+        // non-extensible objects keep their elements sparse and take the generic path.
+        let p = get("p").as_object();
+        assert!(p.get_dense_element(0).is_none());
+        assert_eq!(p.elements.length(), 2);
+        assert_eq!(get("s").as_string().as_str(), "y");
+        assert_eq!(vm.get(p, 0u32).unwrap_or_else(|_| panic!()).as_int32(), 3);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_hoisting() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "early = before();
+             function before() { return 'hoisted'; }
+             undef = later;
+             var later = 1;
+
+             beforeDecl = shadowed;
+             var shadowed;
+             afterDecl = shadowed;
+             function shadowed() {}
+
+             var assigned = 1;
+             function assigned() {}
+
+             function scoped() {
+                 var out = [f(), v];
+                 var v = 1;
+                 function f() { return 'inner'; }
+                 return out.join();
+             }
+             scopedOut = scoped();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get("early").as_string().as_str(), "hoisted");
+        assert!(get("undef").is_undefined());
+        assert_eq!(get("later").as_int32(), 1);
+        // a `var` without initializer does not reset the function of the same name.
+        assert!(get("beforeDecl").is_callable());
+        assert!(get("afterDecl").is_callable());
+        // the assignment runs after the function was bound and is not undone by the declaration.
+        assert_eq!(get("assigned").as_int32(), 1);
+        assert_eq!(get("scopedOut").as_string().as_str(), "inner,");
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_function_length_and_name() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function two(a, b) {}
+             function rest(a, ...more) { return more; }
+             const g = function () {};
+             h = function () {};
+             named = function inner() {};
+             o = { m: function () {} };
+             class K { constructor(a, b, c) {} }
+             square = function (x) { return x * x; };
+             out = [
+                 two.length, two.name, rest.length, g.name, g.length, h.name, named.name,
+                 o.m.name, K.length, K.name, (function (x) {}).name,
+                 Function.prototype.length, Function.prototype.name
+             ].join();
+             gathered = rest(1, 2, 3).join();
+             none = rest(1).length;
+             sq = '' + square(4);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(
+            get("out").as_string().as_str(),
+            "2,two,1,g,0,h,inner,m,3,K,,0,"
+        );
+        assert_eq!(get("gathered").as_string().as_str(), "2,3");
+        assert_eq!(get("none").as_int32(), 0);
+        assert_eq!(get("sq").as_string().as_str(), "16");
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_catch_without_binding() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function fail() { throw 'boom'; }
+             log = '';
+             try {
+                 x = 1 + fail();
+                 log = log + 'unreachable;';
+             } catch {
+                 log = log + 'caught;';
+             }
+             log = log + 'after;';
+             function run() {
+                 try { fail(); } catch { log = log + 'inner;'; } finally { log = log + 'finally;'; }
+                 try { log = log + 'try;'; } catch { log = log + 'never;'; } finally { log = log + 'done;'; }
+                 return 'ran';
+             }
+             result = run();
+             try {
+                 try { fail(); } finally { log = log + 'rethrow;'; }
+             } catch (e) {
+                 log = log + e;
+             }",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(
+            get("log").as_string().as_str(),
+            "caught;after;inner;finally;try;done;rethrow;boom"
+        );
+        assert_eq!(get("result").as_string().as_str(), "ran");
+
+        let res = vm.eval("try { fail(); } finally { log = 'cleanup'; }");
+        assert!(res.is_err());
+        let log = vm.get(global, "log").unwrap_or_else(|_| panic!());
+        assert_eq!(log.as_string().as_str(), "cleanup");
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_trace_execution() {
+        let mut vm = VirtualMachine::new(Options::builder().trace_execution(true).build());
+        let stderr = BufferedError::default();
+        vm.set_stderr(Box::new(stderr.clone()));
+        let res = vm.eval("x = 1 + 2;\ny = x;");
+        assert!(res.is_ok());
+        let trace = stderr.to_string();
+        let ops: Vec<&str> = trace
+            .lines()
+            .filter_map(|line| line.split(' ').nth(1))
+            .collect();
+        let expected = [
+            "OP_PUSH_INT",
+            "OP_PUSH_INT",
+            "OP_ADD",
+            "OP_SET_VAR",
+            "OP_GET_VAR",
+            "OP_SET_VAR",
+        ];
+        let mut rest = &ops[..];
+        for op in expected.iter() {
+            let at = rest
+                .iter()
+                .position(|traced| traced == op)
+                .unwrap_or_else(|| panic!("{} missing in\n{}", op, trace));
+            rest = &rest[at + 1..];
+        }
+        assert!(trace.contains("OP_ADD line 1 top 2"), "{}", trace);
+        assert!(trace.contains("OP_GET_VAR line 2"), "{}", trace);
+        VirtualMachineRef::dispose(vm);
+
+        let mut vm = VirtualMachine::new(Options::default());
+        let stderr = BufferedError::default();
+        vm.set_stderr(Box::new(stderr.clone()));
+        assert!(vm.eval("x = 1 + 2;").is_ok());
+        assert_eq!(stderr.to_string(), "");
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_object_spread() {
+        // keep 50 properties below the dictionary threshold so the source structure is shared.
+        let mut vm = VirtualMachine::new(Options::builder().dictionary_threshold(64).build());
+        let res = vm.eval(
+            "function fill(o, i) {
+                 if (i === 50) {
+                     return o;
+                 }
+                 o['k' + i] = i;
+                 return fill(o, i + 1);
+             }
+             src = fill({}, 0);
+             copy = {...src};",
+        );
+        assert!(res.is_ok());
+        assert_eq!(vm.structure_stats().spread_structure_shares, 1);
+        let global = vm.global_object();
+        let src = vm
+            .get(global, "src")
+            .unwrap_or_else(|_| panic!())
+            .as_object();
+        let copy = vm
+            .get(global, "copy")
+            .unwrap_or_else(|_| panic!())
+            .as_object();
+        assert!(Gc::ptr_eq(src.structure(), copy.structure()));
+
+        let res = vm.eval(
+            "function readOnly() { return 'got'; }
+             withGetter = { a: 1 };
+             Object.defineProperty(withGetter, 'g', { get: readOnly, enumerable: true });
+             Object.defineProperty(withGetter, 'hidden', { value: 1 });
+             slow = {...withGetter};
+             mixed = { first: 0, ...src, k0: 'last' };
+             copy.k1 = 'changed';
+             out = [copy.k0, copy.k1, src.k1, copy.k49, Object.keys(copy).length, slow.a, slow.g,
+                    Object.hasOwn(slow, 'hidden'), mixed.k0, mixed.k10, Object.keys(mixed).length,
+                    Object.keys({...'ab', ...null}).join(' ')].join();",
+        );
+        assert!(res.is_ok());
+        assert_eq!(vm.structure_stats().spread_structure_shares, 1);
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(
+            out.as_string().as_str(),
+            "0,changed,1,49,50,1,got,false,last,10,51,0 1"
+        );
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_non_canonical_nan() {
+        let mut vm = VirtualMachine::new(Options::default());
+        // 0x7ff00000_00000001 is a signaling NaN, 0xffff0000_00000001 has the top bits that tag
+        // int32 immediates set.
+        let res = vm.eval(
+            "function check(high) {
+                 var buffer = new ArrayBuffer(8);
+                 var words = new Uint32Array(buffer);
+                 words[0] = 1;
+                 words[1] = high;
+                 var n = new Float64Array(buffer)[0];
+                 return [n, n + 1, 1 + n, n + 0.5, n - 1, n * 2, n / 2, 2 / n, n % 2, 2 % n,
+                         Math.abs(n), Math.sqrt(n), Math.max(n, 1), Math.pow(n, 1), Math.floor(n)];
+             }
+             signaling = check(2146435072);
+             tagged = check(4294901760);
+             mixed = 1.5 + 2;
+             remainders = [7 % 3, (0 - 7) % 2, 5.5 % 2, 4 % (0 - 2), 1 % 0].join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        for name in ["signaling", "tagged"].iter() {
+            let results = vm.get(global, name).unwrap_or_else(|_| panic!());
+            let results = results.as_object();
+            for i in 0..15u32 {
+                let val = vm.get(results, i).unwrap_or_else(|_| panic!());
+                assert!(val.is_number() && !val.is_cell() && !val.is_int32());
+                assert!(val.number().is_nan(), "{}[{}]", name, i);
+            }
+        }
+        let mixed = vm.get(global, "mixed").unwrap_or_else(|_| panic!());
+        assert_eq!(mixed.number(), 3.5);
+        // the result takes the sign of the dividend.
+        let remainders = vm.get(global, "remainders").unwrap_or_else(|_| panic!());
+        assert_eq!(remainders.as_string().as_str(), "1,-1,1.5,0,NaN");
+        VirtualMachineRef::dispose(vm);
+    }
 }
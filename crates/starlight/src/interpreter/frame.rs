@@ -13,6 +13,20 @@ pub enum BlockType {
     Switch,
 }
 
+/// Handler of a `try` statement pushed by `OP_TRY_PUSH_CATCH` or `OP_TRY_PUSH_FINALLY`.
+#[derive(Clone, Copy)]
+pub struct TryHandler {
+    /// Start of the `catch` block, or of the `finally` block that rethrows.
+    pub target: *const u8,
+    /// Top of the operand stack when the `try` statement was entered.
+    pub stack: *mut JsValue,
+    /// Scope of the `try` statement. It encloses the scope the error is thrown from, so it
+    /// is kept alive through the frame scope.
+    pub scope: JsValue,
+    /// Whether `target` is a `finally` block, the only handlers an interrupt unwinds through.
+    pub finally: bool,
+}
+
 use starlight_derive::Trace;
 #[repr(C)]
 #[derive(Trace)]
@@ -29,13 +43,17 @@ pub struct FrameBase {
     pub stack_size: usize,
     pub scope: JsValue,
     #[unsafe_ignore_trace]
-    pub try_stack: Vec<*const u8>,
+    pub try_stack: Vec<TryHandler>,
     pub this_obj: JsValue,
     pub thrown_val: JsValue,
     pub bcode: Option<Gc<ByteCode>>,
     #[unsafe_ignore_trace]
     pub code: *mut u8,
     pub callee: JsValue,
+    /// Value of `new.target`, `undefined` when the frame was not entered through `[[Construct]]`.
+    pub new_target: JsValue,
+    /// Frame slots of the bindings listed in `ByteCode::local_names`.
+    pub locals: Vec<JsValue>,
 }
 
 impl Cell for FrameBase {}
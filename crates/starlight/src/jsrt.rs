@@ -1,17 +1,50 @@
 use crate::{
-    runtime::{arguments::Arguments, function::JsNativeFunction, value::JsValue},
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        function::{JsAPI, JsNativeFunction},
+        object::JsObject,
+        property_descriptor::DataDescriptor,
+        symbol::Symbol,
+        value::JsValue,
+    },
     vm::VirtualMachine,
 };
+use std::io::Write;
 
+pub mod array;
+pub mod atomics;
+pub mod console;
+pub mod date;
 pub mod error;
+pub mod escape;
+pub mod eval;
+pub mod finalization_registry;
+pub mod function;
+pub mod json;
+pub mod map;
+pub mod math;
+pub mod microtask;
+pub mod number;
+pub mod object;
+pub mod performance;
+pub mod proxy;
+pub mod reflect;
+pub mod regexp;
+pub mod string;
+pub mod structured_clone;
+pub mod symbol;
+pub mod typed_array;
 
 pub fn print(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut line = String::new();
     for ix in 0..args.size() {
         let val = args[ix];
-        let s = val.to_string(vm)?;
-        print!("{}", s);
+        line.push_str(&val.to_string(vm)?);
     }
-    println!();
+    line.push('\n');
+    let _ = vm.stdout().write_all(line.as_bytes());
 
     Ok(JsValue::undefined())
 }
@@ -21,5 +54,96 @@ pub fn jsrt_init(vm: &mut VirtualMachine) {
     let mut global = ctx.new_local(vm.global_object());
     let name = vm.intern("print");
     let print = ctx.new_local(JsNativeFunction::new(vm, name, print, 0));
-    assert!(global.put(vm, name, JsValue::new(*print), false).is_ok());
+    let _ = global.define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*print), W | C),
+        false,
+    );
+    let name = vm.intern("globalThis");
+    let _ = global.define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*global), W | C),
+        false,
+    );
+    define_method(vm, *global, "queueMicrotask", microtask::queue_microtask, 1);
+    define_method(vm, *global, "eval", eval::global_eval, 1);
+    define_method(vm, *global, "escape", escape::escape, 1);
+    define_method(vm, *global, "unescape", escape::unescape, 1);
+    define_method(
+        vm,
+        *global,
+        "structuredClone",
+        structured_clone::structured_clone,
+        1,
+    );
+
+    object::init_object(vm);
+    function::init_function(vm);
+    array::init_array(vm);
+    string::init_string(vm);
+    number::init_number(vm);
+    regexp::init_regexp(vm);
+    map::init_map(vm);
+    date::init_date(vm);
+    typed_array::init_typed_array(vm);
+    finalization_registry::init_finalization_registry(vm);
+    math::init_math(vm);
+    atomics::init_atomics(vm);
+    json::init_json(vm);
+    symbol::init_symbol(vm);
+    performance::init_performance(vm);
+    reflect::init_reflect(vm);
+    proxy::init_proxy(vm);
+    console::init_console(vm);
+}
+
+/// Define builtin method `name` on `obj`. Builtin methods are writable and configurable but not enumerable.
+pub(crate) fn define_method(
+    vm: &mut VirtualMachine,
+    mut obj: Gc<JsObject>,
+    name: &str,
+    f: JsAPI,
+    arity: u32,
+) {
+    let name = vm.intern(name);
+    let func = JsNativeFunction::new(vm, name, f, arity);
+    let _ = obj.define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(func), W | C),
+        false,
+    );
+}
+
+/// Defines `alias` on `proto` as the same function object as the existing method `name`.
+pub(crate) fn define_alias(
+    vm: &mut VirtualMachine,
+    mut proto: Gc<JsObject>,
+    alias: Symbol,
+    name: &str,
+) {
+    let name = vm.intern(name);
+    let method = proto.get(vm, name).unwrap_or_else(|_| JsValue::undefined());
+    let _ = proto.define_own_property(vm, alias, &*DataDescriptor::new(method, W | C), false);
+}
+
+/// section 7.4.7 CreateIterResultObject(value, done)
+pub(crate) fn iter_result(
+    vm: &mut VirtualMachine,
+    value: JsValue,
+    done: bool,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let value = ctx.new_local(value);
+    let mut result = vm.new_object(&ctx);
+    result.put(vm, Symbol::value(), *value, true)?;
+    result.put(vm, Symbol::done(), JsValue::new(done), true)?;
+    Ok(JsValue::new(*result))
+}
+
+/// `[Symbol.iterator]` of builtin iterators, they are their own iterables.
+pub(crate) fn return_this(_vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(args.this)
 }
@@ -0,0 +1,1325 @@
+use std::cmp::Ordering;
+
+use super::{define_alias, define_method, iter_result, return_this};
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        array::{JsArray, JsArrayIterator},
+        attributes::*,
+        error::{JsRangeError, JsTypeError},
+        function::JsNativeFunction,
+        iterator::iterate_or_arraylike,
+        map::IterationKind,
+        object::{JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// section 15.4.2 The Array Constructor
+pub fn array_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.size() == 1 && args[0].is_number() {
+        let len = args[0].number();
+        if len < 0.0 || len > u32::MAX as f64 || len.trunc() != len {
+            let msg = JsString::new(vm, "Invalid array length");
+            return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+        }
+        return Ok(JsValue::new(JsArray::new(vm, len as u32)));
+    }
+    let ctx = vm.space().new_local_context();
+    let mut arr = ctx.new_local(JsArray::new(vm, 0));
+    for i in 0..args.size() {
+        arr.put(vm, Symbol::Indexed(i as _), args[i], true)?;
+    }
+    Ok(JsValue::new(*arr))
+}
+
+/// section 22.1.2.1 Array.from(items [, mapfn [, thisArg]])
+pub fn array_from(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mapfn = args.at(1);
+    if !mapfn.is_undefined() && !mapfn.is_callable() {
+        let msg = JsString::new(vm, "Array.from: mapper is not a function");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let this_arg = args.at(2);
+    let ctx = vm.space().new_local_context();
+    let mut arr = ctx.new_local(JsArray::new(vm, 0));
+    let mut index = 0;
+    iterate_or_arraylike(vm, args.at(0), &mut |vm, val| {
+        let val = if mapfn.is_callable() {
+            let ctx = vm.space().new_local_context();
+            let mut margs = ctx.new_local(Arguments::new(vm, this_arg, 2));
+            margs[0] = val;
+            margs[1] = JsValue::new(index as i32);
+            mapfn.as_object().as_function_mut().call(vm, &mut margs)?
+        } else {
+            val
+        };
+        arr.put(vm, Symbol::Indexed(index), val, true)?;
+        index += 1;
+        Ok(())
+    })?;
+    Ok(JsValue::new(*arr))
+}
+
+/// section 22.1.2.2 Array.isArray(arg)
+pub fn array_is_array(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let val = args.at(0);
+    Ok(JsValue::new(
+        val.is_object() && val.as_object().tag() == ObjectTag::Array,
+    ))
+}
+
+fn this_object(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    name: &str,
+) -> Result<Gc<JsObject>, JsValue> {
+    if !this.is_object() {
+        let msg = JsString::new(vm, format!("Array.prototype.{} called on non-object", name));
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(this.as_object())
+}
+
+/// `ToUint32(obj.length)`
+fn length_of(vm: &mut VirtualMachine, obj: Gc<JsObject>) -> Result<u32, JsValue> {
+    let len = obj.get(vm, Symbol::length())?.to_number(vm)?;
+    if !len.is_finite() {
+        return Ok(0);
+    }
+    Ok(len.trunc().rem_euclid(4294967296.0) as u32)
+}
+
+/// section 15.4.4.11 SortCompare, `undefined` and holes never get here.
+fn sort_compare(
+    vm: &mut VirtualMachine,
+    comparefn: JsValue,
+    x: JsValue,
+    y: JsValue,
+) -> Result<Ordering, JsValue> {
+    if comparefn.is_undefined() {
+        let x = x.to_string(vm)?;
+        let y = y.to_string(vm)?;
+        // Strings compare by UTF-16 code units, not by code points.
+        return Ok(x.encode_utf16().cmp(y.encode_utf16()));
+    }
+    let ctx = vm.space().new_local_context();
+    let mut args = ctx.new_local(Arguments::new(vm, JsValue::undefined(), 2));
+    args[0] = x;
+    args[1] = y;
+    let res = comparefn
+        .as_object()
+        .as_function_mut()
+        .call(vm, &mut args)?
+        .to_number(vm)?;
+    Ok(if res < 0.0 {
+        Ordering::Less
+    } else if res > 0.0 {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    })
+}
+
+/// Stable merge sort. `items` holds every value at all times, so a throwing comparator leaves a
+/// permutation of the input behind.
+fn merge_sort(
+    vm: &mut VirtualMachine,
+    comparefn: JsValue,
+    items: &mut [JsValue],
+    buf: &mut Vec<JsValue>,
+) -> Result<(), JsValue> {
+    let len = items.len();
+    if len < 2 {
+        return Ok(());
+    }
+    let mid = len / 2;
+    merge_sort(vm, comparefn, &mut items[..mid], buf)?;
+    merge_sort(vm, comparefn, &mut items[mid..], buf)?;
+    buf.clear();
+    let (mut i, mut j) = (0, mid);
+    while i < mid && j < len {
+        // Take from the right run only when strictly smaller to keep equal elements in order.
+        if sort_compare(vm, comparefn, items[j], items[i])? == Ordering::Less {
+            buf.push(items[j]);
+            j += 1;
+        } else {
+            buf.push(items[i]);
+            i += 1;
+        }
+    }
+    buf.extend_from_slice(&items[i..mid]);
+    buf.extend_from_slice(&items[j..len]);
+    items.copy_from_slice(buf);
+    Ok(())
+}
+
+/// section 15.4.4.11 Array.prototype.sort(comparefn)
+pub fn array_sort(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let comparefn = args.at(0);
+    if !comparefn.is_undefined() && !comparefn.is_callable() {
+        let msg = JsString::new(
+            vm,
+            "The comparison function must be either a function or undefined",
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(this_object(vm, args.this, "sort")?);
+    let len = length_of(vm, *obj)?;
+
+    let mut items = ctx.new_local(Vec::new());
+    let mut undefined_count = 0;
+    for i in 0..len {
+        if !obj.has_property(vm, Symbol::Indexed(i)) {
+            continue;
+        }
+        let val = obj.get(vm, Symbol::Indexed(i))?;
+        if val.is_undefined() {
+            undefined_count += 1;
+        } else {
+            items.push(val);
+        }
+    }
+
+    // The array is only written once sorting succeeded, an exception leaves it untouched.
+    let mut buf = ctx.new_local(Vec::with_capacity(items.len()));
+    merge_sort(vm, comparefn, &mut items, &mut buf)?;
+
+    let mut index = 0;
+    for &val in items.iter() {
+        obj.put(vm, Symbol::Indexed(index), val, true)?;
+        index += 1;
+    }
+    for _ in 0..undefined_count {
+        obj.put(vm, Symbol::Indexed(index), JsValue::undefined(), true)?;
+        index += 1;
+    }
+    while index < len {
+        obj.delete(vm, Symbol::Indexed(index), true)?;
+        index += 1;
+    }
+    Ok(JsValue::new(*obj))
+}
+
+fn callback_argument(
+    vm: &mut VirtualMachine,
+    val: JsValue,
+    name: &str,
+) -> Result<JsValue, JsValue> {
+    if !val.is_callable() {
+        let msg = JsString::new(
+            vm,
+            format!("Array.prototype.{}: callback is not a function", name),
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(val)
+}
+
+/// Calls `callback` with the usual `(element, index, array)` triple.
+fn call_callback(
+    vm: &mut VirtualMachine,
+    callback: JsValue,
+    this_arg: JsValue,
+    val: JsValue,
+    index: u32,
+    obj: Gc<JsObject>,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut args = ctx.new_local(Arguments::new(vm, this_arg, 3));
+    args[0] = val;
+    args[1] = JsValue::from(index);
+    args[2] = JsValue::new(obj);
+    callback.as_object().as_function_mut().call(vm, &mut args)
+}
+
+/// Shared loop of `some` and `every`, stops at the first callback result whose truthiness is
+/// `stop_on`. Holes are skipped.
+fn array_test(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+    name: &str,
+    stop_on: bool,
+) -> Result<bool, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, name)?);
+    let len = length_of(vm, *obj)?;
+    let callback = callback_argument(vm, args.at(0), name)?;
+    let this_arg = args.at(1);
+    for i in 0..len {
+        if !obj.has_property(vm, Symbol::Indexed(i)) {
+            continue;
+        }
+        let val = obj.get(vm, Symbol::Indexed(i))?;
+        if call_callback(vm, callback, this_arg, val, i, *obj)?.to_boolean() == stop_on {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// section 15.4.4.17 Array.prototype.some(callbackfn [, thisArg])
+pub fn array_some(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(array_test(vm, args, "some", true)?))
+}
+
+/// section 15.4.4.16 Array.prototype.every(callbackfn [, thisArg])
+pub fn array_every(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(!array_test(vm, args, "every", false)?))
+}
+
+/// Shared loop of `find` and `findIndex`. Unlike `some`, holes are visited as `undefined`.
+fn array_find_entry(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+    name: &str,
+) -> Result<Option<(u32, JsValue)>, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, name)?);
+    let len = length_of(vm, *obj)?;
+    let predicate = callback_argument(vm, args.at(0), name)?;
+    let this_arg = args.at(1);
+    for i in 0..len {
+        let val = ctx.new_local(obj.get(vm, Symbol::Indexed(i))?);
+        if call_callback(vm, predicate, this_arg, *val, i, *obj)?.to_boolean() {
+            return Ok(Some((i, *val)));
+        }
+    }
+    Ok(None)
+}
+
+/// section 22.1.3.8 Array.prototype.find(predicate [, thisArg])
+pub fn array_find(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(match array_find_entry(vm, args, "find")? {
+        Some((_, val)) => val,
+        None => JsValue::undefined(),
+    })
+}
+
+/// section 22.1.3.9 Array.prototype.findIndex(predicate [, thisArg])
+pub fn array_find_index(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(match array_find_entry(vm, args, "findIndex")? {
+        Some((index, _)) => JsValue::from(index),
+        None => JsValue::new(-1),
+    })
+}
+
+/// Shared loop of `reduce` and `reduceRight`, `right` walks from the last index down. Holes are
+/// skipped, also while looking for the element that seeds the accumulator when no initial
+/// value was passed.
+fn array_fold(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+    name: &str,
+    right: bool,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, name)?);
+    let len = length_of(vm, *obj)?;
+    let callback = callback_argument(vm, args.at(0), name)?;
+    let mut indices: Box<dyn Iterator<Item = u32>> = if right {
+        Box::new((0..len).rev())
+    } else {
+        Box::new(0..len)
+    };
+    let mut acc = ctx.new_local(args.at(1));
+    if args.size() < 2 {
+        match indices.find(|&i| obj.has_property(vm, Symbol::Indexed(i))) {
+            Some(i) => *acc = obj.get(vm, Symbol::Indexed(i))?,
+            None => {
+                let msg = JsString::new(
+                    vm,
+                    format!(
+                        "Array.prototype.{} of empty array with no initial value",
+                        name
+                    ),
+                );
+                return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+            }
+        }
+    }
+    for i in indices {
+        if !obj.has_property(vm, Symbol::Indexed(i)) {
+            continue;
+        }
+        let val = obj.get(vm, Symbol::Indexed(i))?;
+        let ctx = vm.space().new_local_context();
+        let mut call = ctx.new_local(Arguments::new(vm, JsValue::undefined(), 4));
+        call[0] = *acc;
+        call[1] = val;
+        call[2] = JsValue::from(i);
+        call[3] = JsValue::new(*obj);
+        *acc = callback.as_object().as_function_mut().call(vm, &mut call)?;
+    }
+    Ok(*acc)
+}
+
+/// section 22.1.3.19 Array.prototype.reduce(callbackfn [, initialValue])
+pub fn array_reduce(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    array_fold(vm, args, "reduce", false)
+}
+
+/// section 22.1.3.20 Array.prototype.reduceRight(callbackfn [, initialValue])
+pub fn array_reduce_right(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    array_fold(vm, args, "reduceRight", true)
+}
+
+/// section 23.1.3.13.1 FlattenIntoArray, returns the next free index of `target`.
+#[allow(clippy::too_many_arguments)]
+fn flatten_into_array(
+    vm: &mut VirtualMachine,
+    target: Gc<JsObject>,
+    source: Gc<JsObject>,
+    source_len: u32,
+    start: u32,
+    depth: f64,
+    mapper: JsValue,
+    this_arg: JsValue,
+) -> Result<u32, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut target = ctx.new_local(target);
+    let source = ctx.new_local(source);
+    let mut index = start;
+    for i in 0..source_len {
+        if !source.has_property(vm, Symbol::Indexed(i)) {
+            continue;
+        }
+        let mut element = source.get(vm, Symbol::Indexed(i))?;
+        if !mapper.is_undefined() {
+            element = call_callback(vm, mapper, this_arg, element, i, *source)?;
+        }
+        let element = ctx.new_local(element);
+        if depth > 0.0 && element.is_object() && element.as_object().tag() == ObjectTag::Array {
+            let inner = element.as_object();
+            let len = length_of(vm, inner)?;
+            index = flatten_into_array(
+                vm,
+                *target,
+                inner,
+                len,
+                index,
+                depth - 1.0,
+                JsValue::undefined(),
+                JsValue::undefined(),
+            )?;
+        } else {
+            target.put(vm, Symbol::Indexed(index), *element, true)?;
+            index += 1;
+        }
+    }
+    Ok(index)
+}
+
+/// section 23.1.3.13 Array.prototype.flat([depth])
+pub fn array_flat(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "flat")?);
+    let len = length_of(vm, *obj)?;
+    let depth = if args.at(0).is_undefined() {
+        1.0
+    } else {
+        let depth = args.at(0).to_number(vm)?;
+        if depth.is_nan() {
+            0.0
+        } else {
+            depth.trunc()
+        }
+    };
+    let arr = ctx.new_local(JsArray::new(vm, 0));
+    flatten_into_array(
+        vm,
+        *arr,
+        *obj,
+        len,
+        0,
+        depth,
+        JsValue::undefined(),
+        JsValue::undefined(),
+    )?;
+    Ok(JsValue::new(*arr))
+}
+
+/// section 23.1.3.14 Array.prototype.flatMap(mapperFunction [, thisArg])
+pub fn array_flat_map(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "flatMap")?);
+    let len = length_of(vm, *obj)?;
+    let mapper = callback_argument(vm, args.at(0), "flatMap")?;
+    let arr = ctx.new_local(JsArray::new(vm, 0));
+    flatten_into_array(vm, *arr, *obj, len, 0, 1.0, mapper, args.at(1))?;
+    Ok(JsValue::new(*arr))
+}
+
+/// section 15.4.4.5 Array.prototype.join(separator)
+pub fn array_join(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "join")?);
+    let len = length_of(vm, *obj)?;
+    let separator = if args.at(0).is_undefined() {
+        ",".to_owned()
+    } else {
+        args.at(0).to_string(vm)?
+    };
+    let mut result = String::new();
+    for i in 0..len {
+        if i != 0 {
+            result.push_str(&separator);
+        }
+        let val = obj.get(vm, Symbol::Indexed(i))?;
+        if !val.is_undefined_or_null() {
+            result.push_str(&val.to_string(vm)?);
+        }
+    }
+    Ok(JsValue::new(JsString::new(vm, result)))
+}
+
+/// section 15.4.4.8 Array.prototype.reverse()
+pub fn array_reverse(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(this_object(vm, args.this, "reverse")?);
+    let len = length_of(vm, *obj)?;
+    let middle = len / 2;
+    for lower in 0..middle {
+        let upper = len - lower - 1;
+        let lower_exists = obj.has_property(vm, Symbol::Indexed(lower));
+        let lower_value = ctx.new_local(obj.get(vm, Symbol::Indexed(lower))?);
+        let upper_exists = obj.has_property(vm, Symbol::Indexed(upper));
+        let upper_value = obj.get(vm, Symbol::Indexed(upper))?;
+        if upper_exists {
+            obj.put(vm, Symbol::Indexed(lower), upper_value, true)?;
+        } else if lower_exists {
+            obj.delete(vm, Symbol::Indexed(lower), true)?;
+        }
+        if lower_exists {
+            obj.put(vm, Symbol::Indexed(upper), *lower_value, true)?;
+        } else if upper_exists {
+            obj.delete(vm, Symbol::Indexed(upper), true)?;
+        }
+    }
+    Ok(JsValue::new(*obj))
+}
+
+/// section 15.4.4.4 Array.prototype.concat([item1 [, item2 [, ...]]])
+pub fn array_concat(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let this = ctx.new_local(this_object(vm, args.this, "concat")?);
+    let mut arr = ctx.new_local(JsArray::new(vm, 0));
+    let mut n = 0;
+    for i in 0..=args.size() {
+        let item = if i == 0 {
+            JsValue::new(*this)
+        } else {
+            args[i - 1]
+        };
+        if item.is_object() && item.as_object().tag() == ObjectTag::Array {
+            let item = item.as_object();
+            let len = length_of(vm, item)?;
+            for k in 0..len {
+                // holes are kept, only the final length accounts for them.
+                if item.has_property(vm, Symbol::Indexed(k)) {
+                    let val = item.get(vm, Symbol::Indexed(k))?;
+                    arr.put(vm, Symbol::Indexed(n), val, true)?;
+                }
+                n += 1;
+            }
+        } else {
+            arr.put(vm, Symbol::Indexed(n), item, true)?;
+            n += 1;
+        }
+    }
+    arr.put(vm, Symbol::length(), JsValue::from(n), true)?;
+    Ok(JsValue::new(*arr))
+}
+
+/// Converts a relative index argument as used by `fill`, `copyWithin`, `slice`, `indexOf` and
+/// `includes` to an absolute index clamped to `0..=len`. `undefined` gives `default`.
+fn relative_index(
+    vm: &mut VirtualMachine,
+    val: JsValue,
+    len: u32,
+    default: u32,
+) -> Result<u32, JsValue> {
+    if val.is_undefined() {
+        return Ok(default);
+    }
+    let n = val.to_number(vm)?;
+    let n = if n.is_nan() { 0.0 } else { n.trunc() };
+    Ok(if n < 0.0 {
+        (len as f64 + n).max(0.0) as u32
+    } else {
+        n.min(len as f64) as u32
+    })
+}
+
+/// section 22.1.3.6 Array.prototype.fill(value [, start [, end]])
+pub fn array_fill(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(this_object(vm, args.this, "fill")?);
+    let len = length_of(vm, *obj)?;
+    let start = relative_index(vm, args.at(1), len, 0)?;
+    let end = relative_index(vm, args.at(2), len, len)?;
+    for i in start..end {
+        obj.put(vm, Symbol::Indexed(i), args.at(0), true)?;
+    }
+    Ok(JsValue::new(*obj))
+}
+
+/// section 23.1.3.28 Array.prototype.slice(start, end)
+pub fn array_slice(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "slice")?);
+    let len = length_of(vm, *obj)?;
+    let start = relative_index(vm, args.at(0), len, 0)?;
+    let end = relative_index(vm, args.at(1), len, len)?;
+    let mut arr = ctx.new_local(JsArray::new(vm, 0));
+    let mut n = 0;
+    for k in start..end.max(start) {
+        // holes are kept, only the final length accounts for them.
+        if obj.has_property(vm, Symbol::Indexed(k)) {
+            let val = obj.get(vm, Symbol::Indexed(k))?;
+            arr.put(vm, Symbol::Indexed(n), val, true)?;
+        }
+        n += 1;
+    }
+    arr.put(vm, Symbol::length(), JsValue::from(n), true)?;
+    Ok(JsValue::new(*arr))
+}
+
+/// section 22.1.3.3 Array.prototype.copyWithin(target, start [, end])
+pub fn array_copy_within(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(this_object(vm, args.this, "copyWithin")?);
+    let len = length_of(vm, *obj)?;
+    let to = relative_index(vm, args.at(0), len, 0)?;
+    let from = relative_index(vm, args.at(1), len, 0)?;
+    let fin = relative_index(vm, args.at(2), len, len)?;
+    let count = fin.saturating_sub(from).min(len - to);
+    // Copy backwards when the ranges overlap with the target after the source, so elements are
+    // read before they get overwritten.
+    let backwards = from < to && to < from + count;
+    for k in 0..count {
+        let offset = if backwards { count - k - 1 } else { k };
+        let (from, to) = (from + offset, to + offset);
+        if obj.has_property(vm, Symbol::Indexed(from)) {
+            let val = obj.get(vm, Symbol::Indexed(from))?;
+            obj.put(vm, Symbol::Indexed(to), val, true)?;
+        } else {
+            obj.delete(vm, Symbol::Indexed(to), true)?;
+        }
+    }
+    Ok(JsValue::new(*obj))
+}
+
+/// section 15.4.4.14 Array.prototype.indexOf(searchElement [, fromIndex]), holes are skipped and
+/// `NaN` is never found.
+pub fn array_index_of(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "indexOf")?);
+    let len = length_of(vm, *obj)?;
+    let start = relative_index(vm, args.at(1), len, 0)?;
+    for k in start..len {
+        if obj.has_property(vm, Symbol::Indexed(k))
+            && obj.get(vm, Symbol::Indexed(k))?.strict_equal(args.at(0))
+        {
+            return Ok(JsValue::new(k));
+        }
+    }
+    Ok(JsValue::new(-1))
+}
+
+/// section 15.4.4.15 Array.prototype.lastIndexOf(searchElement [, fromIndex])
+pub fn array_last_index_of(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "lastIndexOf")?);
+    let len = length_of(vm, *obj)?;
+    if len == 0 {
+        return Ok(JsValue::new(-1));
+    }
+    let n = if args.size() > 1 {
+        let n = args.at(1).to_number(vm)?;
+        if n.is_nan() {
+            0.0
+        } else {
+            n.trunc()
+        }
+    } else {
+        len as f64 - 1.0
+    };
+    let start = if n < 0.0 {
+        len as f64 + n
+    } else {
+        n.min(len as f64 - 1.0)
+    };
+    if start < 0.0 {
+        return Ok(JsValue::new(-1));
+    }
+    for k in (0..=start as u32).rev() {
+        if obj.has_property(vm, Symbol::Indexed(k))
+            && obj.get(vm, Symbol::Indexed(k))?.strict_equal(args.at(0))
+        {
+            return Ok(JsValue::new(k));
+        }
+    }
+    Ok(JsValue::new(-1))
+}
+
+/// section 22.1.3.11 Array.prototype.includes(searchElement [, fromIndex]), holes read as
+/// `undefined` and `NaN` is found.
+pub fn array_includes(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "includes")?);
+    let len = length_of(vm, *obj)?;
+    let start = relative_index(vm, args.at(1), len, 0)?;
+    for k in start..len {
+        let val = obj.get(vm, Symbol::Indexed(k))?;
+        if JsValue::same_value_zero(val, args.at(0)) {
+            return Ok(JsValue::new(true));
+        }
+    }
+    Ok(JsValue::new(false))
+}
+
+/// Reads elements `0..len` of `obj`, holes read as `undefined` like in every method that copies
+/// into a new array instead of changing the receiver.
+fn read_elements(
+    vm: &mut VirtualMachine,
+    obj: Gc<JsObject>,
+    len: u32,
+    values: &mut Vec<JsValue>,
+) -> Result<(), JsValue> {
+    for i in 0..len {
+        let val = obj.get(vm, Symbol::Indexed(i))?;
+        values.push(val);
+    }
+    Ok(())
+}
+
+/// section 23.1.3.33 Array.prototype.toReversed()
+pub fn array_to_reversed(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "toReversed")?);
+    let len = length_of(vm, *obj)?;
+    let mut values = ctx.new_local(Vec::with_capacity(len as usize));
+    read_elements(vm, *obj, len, &mut values)?;
+    values.reverse();
+    let arr = vm.new_array(&ctx, &values);
+    Ok(JsValue::new(*arr))
+}
+
+/// section 23.1.3.34 Array.prototype.toSorted(comparefn)
+pub fn array_to_sorted(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let comparefn = args.at(0);
+    if !comparefn.is_undefined() && !comparefn.is_callable() {
+        let msg = JsString::new(
+            vm,
+            "The comparison function must be either a function or undefined",
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "toSorted")?);
+    let len = length_of(vm, *obj)?;
+    let mut values = ctx.new_local(Vec::with_capacity(len as usize));
+    read_elements(vm, *obj, len, &mut values)?;
+    // Holes were read as `undefined`, both go after every other value like in `sort`.
+    values.retain(|val| !val.is_undefined());
+    let mut buf = ctx.new_local(Vec::with_capacity(values.len()));
+    merge_sort(vm, comparefn, &mut values, &mut buf)?;
+    values.resize(len as usize, JsValue::undefined());
+    let arr = vm.new_array(&ctx, &values);
+    Ok(JsValue::new(*arr))
+}
+
+/// section 23.1.3.35 Array.prototype.toSpliced(start, skipCount, ...items)
+pub fn array_to_spliced(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "toSpliced")?);
+    let len = length_of(vm, *obj)?;
+    let start = relative_index(vm, args.at(0), len, 0)?;
+    let skip = match args.size() {
+        0 => 0,
+        1 => len - start,
+        _ => {
+            let n = args[1].to_number(vm)?;
+            let n = if n.is_nan() { 0.0 } else { n.trunc() };
+            n.max(0.0).min((len - start) as f64) as u32
+        }
+    };
+    let inserted = args.size().saturating_sub(2);
+    let new_len = len as u64 - skip as u64 + inserted as u64;
+    if new_len > u32::MAX as u64 {
+        let msg = JsString::new(vm, "Invalid array length");
+        return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+    }
+    let mut values = ctx.new_local(Vec::with_capacity(new_len as usize));
+    read_elements(vm, *obj, start, &mut values)?;
+    for i in 0..inserted {
+        values.push(args[i + 2]);
+    }
+    for i in start + skip..len {
+        let val = obj.get(vm, Symbol::Indexed(i))?;
+        values.push(val);
+    }
+    let arr = vm.new_array(&ctx, &values);
+    Ok(JsValue::new(*arr))
+}
+
+/// section 23.1.3.39 Array.prototype.with(index, value)
+pub fn array_with(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(this_object(vm, args.this, "with")?);
+    let len = length_of(vm, *obj)?;
+    let n = args.at(0).to_number(vm)?;
+    let n = if n.is_nan() { 0.0 } else { n.trunc() };
+    let index = if n < 0.0 { len as f64 + n } else { n };
+    if index < 0.0 || index >= len as f64 {
+        let msg = JsString::new(vm, "Array.prototype.with: index out of range");
+        return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+    }
+    let mut values = ctx.new_local(Vec::with_capacity(len as usize));
+    read_elements(vm, *obj, len, &mut values)?;
+    values[index as usize] = args.at(1);
+    let arr = vm.new_array(&ctx, &values);
+    Ok(JsValue::new(*arr))
+}
+
+fn create_array_iterator(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+    name: &str,
+    kind: IterationKind,
+) -> Result<JsValue, JsValue> {
+    let obj = this_object(vm, args.this, name)?;
+    Ok(JsValue::new(JsArrayIterator::new(vm, obj, kind)))
+}
+
+/// section 23.1.3.1 Array.prototype.at(index)
+pub fn array_at(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let obj = this_object(vm, args.this, "at")?;
+    let len = length_of(vm, obj)? as f64;
+    let n = args.at(0).to_number(vm)?;
+    let n = if n.is_nan() { 0.0 } else { n.trunc() };
+    let k = if n < 0.0 { len + n } else { n };
+    if k < 0.0 || k >= len {
+        return Ok(JsValue::undefined());
+    }
+    obj.get(vm, Symbol::Indexed(k as u32))
+}
+
+/// section 22.1.3.13 Array.prototype.keys()
+pub fn array_keys(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    create_array_iterator(vm, args, "keys", IterationKind::Key)
+}
+
+/// section 22.1.3.29 Array.prototype.values()
+pub fn array_values(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    create_array_iterator(vm, args, "values", IterationKind::Value)
+}
+
+/// section 22.1.3.4 Array.prototype.entries()
+pub fn array_entries(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    create_array_iterator(vm, args, "entries", IterationKind::KeyValue)
+}
+
+/// section 22.1.5.2.1 %ArrayIteratorPrototype%.next(), holes are not skipped.
+pub fn array_iterator_next(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    if !this.is_object() || this.as_object().tag() != ObjectTag::ArrayIterator {
+        let msg = JsString::new(vm, "next method called on incompatible receiver");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let mut iter = ctx.new_local(this.as_object());
+    if iter.as_array_iterator().done {
+        return iter_result(vm, JsValue::undefined(), true);
+    }
+    let array = ctx.new_local(iter.as_array_iterator().array);
+    let index = iter.as_array_iterator().position;
+    if index >= length_of(vm, *array)? {
+        iter.as_array_iterator_mut().done = true;
+        return iter_result(vm, JsValue::undefined(), true);
+    }
+    iter.as_array_iterator_mut().position = index + 1;
+    let key = JsValue::from(index);
+    match iter.as_array_iterator().kind {
+        IterationKind::Key => iter_result(vm, key, false),
+        IterationKind::Value => {
+            let value = array.get(vm, Symbol::Indexed(index))?;
+            iter_result(vm, value, false)
+        }
+        IterationKind::KeyValue => {
+            let value = array.get(vm, Symbol::Indexed(index))?;
+            let entry = vm.new_array(&ctx, &[key, value]);
+            iter_result(vm, JsValue::new(*entry), false)
+        }
+    }
+}
+
+pub(crate) fn init_array(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let mut proto = ctx.new_local(vm.global_data().array_prototype.unwrap());
+    let name = vm.intern("Array");
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        array_constructor,
+        1,
+    ));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(*proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    define_method(vm, *ctor, "from", array_from, 1);
+    define_method(vm, *ctor, "isArray", array_is_array, 1);
+    define_method(vm, *proto, "sort", array_sort, 1);
+    define_method(vm, *proto, "some", array_some, 1);
+    define_method(vm, *proto, "every", array_every, 1);
+    define_method(vm, *proto, "find", array_find, 1);
+    define_method(vm, *proto, "findIndex", array_find_index, 1);
+    define_method(vm, *proto, "reduce", array_reduce, 1);
+    define_method(vm, *proto, "reduceRight", array_reduce_right, 1);
+    define_method(vm, *proto, "flat", array_flat, 0);
+    define_method(vm, *proto, "flatMap", array_flat_map, 1);
+    define_method(vm, *proto, "join", array_join, 1);
+    define_method(vm, *proto, "reverse", array_reverse, 0);
+    define_method(vm, *proto, "concat", array_concat, 1);
+    define_method(vm, *proto, "fill", array_fill, 1);
+    define_method(vm, *proto, "copyWithin", array_copy_within, 2);
+    define_method(vm, *proto, "slice", array_slice, 2);
+    define_method(vm, *proto, "at", array_at, 1);
+    define_method(vm, *proto, "indexOf", array_index_of, 1);
+    define_method(vm, *proto, "lastIndexOf", array_last_index_of, 1);
+    define_method(vm, *proto, "includes", array_includes, 1);
+    define_method(vm, *proto, "toReversed", array_to_reversed, 0);
+    define_method(vm, *proto, "toSorted", array_to_sorted, 1);
+    define_method(vm, *proto, "toSpliced", array_to_spliced, 2);
+    define_method(vm, *proto, "with", array_with, 2);
+    define_method(vm, *proto, "keys", array_keys, 0);
+    define_method(vm, *proto, "values", array_values, 0);
+    define_method(vm, *proto, "entries", array_entries, 0);
+    define_alias(vm, *proto, Symbol::iterator(), "values");
+
+    let structure = vm.global_data().array_iterator_structure.unwrap();
+    let iter_proto = ctx.new_local(structure.prototype().unwrap());
+    define_method(vm, *iter_proto, "next", array_iterator_next, 0);
+    let iterator = JsNativeFunction::new(vm, Symbol::iterator(), return_this, 0);
+    let _ = iter_proto.define_own_property(
+        vm,
+        Symbol::iterator(),
+        &*DataDescriptor::new(JsValue::new(iterator), W | C),
+        false,
+    );
+
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        runtime::{error::JsTypeError, symbol::Symbol, value::JsValue},
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    fn elements(vm: &mut VirtualMachine, name: &str) -> Vec<JsValue> {
+        let global = vm.global_object();
+        let arr = vm.get(global, name).unwrap_or_else(|_| panic!());
+        let arr = arr.as_object();
+        let len = vm.get(arr, "length").unwrap_or_else(|_| panic!());
+        (0..len.as_int32() as u32)
+            .map(|i| vm.get(arr, i).unwrap_or_else(|_| panic!()))
+            .collect()
+    }
+
+    fn ints(vm: &mut VirtualMachine, name: &str) -> Vec<i32> {
+        elements(vm, name).iter().map(|x| x.as_int32()).collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function numeric(a, b) { return a - b; }
+             function byKey(a, b) { return a.k - b.k; }
+             function u() {}
+             a = [10, 2, 1].sort();
+             b = [10, 2, 1, 33, 4].sort(numeric);
+             c = [{ k: 1, v: 0 }, { k: 0, v: 1 }, { k: 1, v: 2 }, { k: 0, v: 3 }].sort(byKey);
+             d = [3, , u(), 1].sort();",
+        );
+        assert!(res.is_ok());
+        assert_eq!(ints(&mut vm, "a"), [1, 10, 2]);
+        assert_eq!(ints(&mut vm, "b"), [1, 2, 4, 10, 33]);
+
+        let order = elements(&mut vm, "c")
+            .iter()
+            .map(|x| {
+                vm.get(x.as_object(), "v")
+                    .unwrap_or_else(|_| panic!())
+                    .as_int32()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(order, [1, 3, 0, 2]);
+
+        let d = elements(&mut vm, "d");
+        assert_eq!(d.len(), 4);
+        assert_eq!(d[0].as_int32(), 1);
+        assert_eq!(d[1].as_int32(), 3);
+        assert!(d[2].is_undefined());
+        let global = vm.global_object();
+        let d = vm.get(global, "d").unwrap_or_else(|_| panic!()).as_object();
+        assert!(!d.has_own_property(&mut vm, Symbol::Indexed(3)));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_sort_comparator_throws() {
+        let mut vm = VirtualMachine::new(Options::default());
+        assert!(vm.eval("a = [3, 1, 2];").is_ok());
+        assert!(vm
+            .eval("function bad(x, y) { return x.y.z; } a.sort(bad);")
+            .is_err());
+        assert_eq!(ints(&mut vm, "a"), [3, 1, 2]);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_higher_order() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "calls = 0;
+             function big(x) { calls = calls + 1; return x > 1; }
+             function neg(x) { return x < 0; }
+             function pair(x) { return [x, [x * 10]]; }
+             a = [0, 5, 7, 9];
+             some = a.some(big);
+             every = a.every(big);
+             found = a.find(big);
+             idx = a.findIndex(big);
+             missing = a.findIndex(neg);
+             flat = [1, [2, [3, [4, [5]]]], , 6].flat(1 / 0);
+             one = [1, [2, [3]]].flat();
+             mapped = [1, 2].flatMap(pair);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let some = vm.get(global, "some").unwrap_or_else(|_| panic!());
+        assert!(some.to_boolean());
+        let every = vm.get(global, "every").unwrap_or_else(|_| panic!());
+        assert!(every.is_boolean() && !every.to_boolean());
+        // `some` stops at 5, `every` at 0, `find` and `findIndex` at 5 again.
+        let calls = vm.get(global, "calls").unwrap_or_else(|_| panic!());
+        assert_eq!(calls.as_int32(), 2 + 1 + 2 + 2);
+        let found = vm.get(global, "found").unwrap_or_else(|_| panic!());
+        assert_eq!(found.as_int32(), 5);
+        let idx = vm.get(global, "idx").unwrap_or_else(|_| panic!());
+        assert_eq!(idx.as_int32(), 1);
+        let missing = vm.get(global, "missing").unwrap_or_else(|_| panic!());
+        assert_eq!(missing.as_int32(), -1);
+
+        assert_eq!(ints(&mut vm, "flat"), [1, 2, 3, 4, 5, 6]);
+        let one = elements(&mut vm, "one");
+        assert_eq!(one.len(), 3);
+        assert_eq!(one[1].as_int32(), 2);
+        assert!(one[2].is_object());
+        let mapped = elements(&mut vm, "mapped");
+        assert_eq!(mapped.len(), 4);
+        assert_eq!(mapped[0].as_int32(), 1);
+        assert!(mapped[1].is_object());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_reduce() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function collect(acc, value, index) {
+                 acc[acc.length] = value;
+                 indices[indices.length] = index;
+                 return acc;
+             }
+             function pair(acc, value) { return [acc, value]; }
+             function sum(acc, value) { return acc + value; }
+             function same(acc, value, index, array) { return array === source; }
+             source = [1, 2, 3];
+             indices = [];
+             right = source.reduceRight(collect, []);
+             paired = source.reduceRight(pair);
+             seed = paired[0][0];
+             left = [1, , 3].reduce(collect, []);
+             total = source.reduce(sum);
+             seeded = [, , 5, , ].reduceRight(sum);
+             empty = [].reduce(sum, 7);
+             array = source.reduce(same, 0);",
+        );
+        assert!(res.is_ok());
+        assert_eq!(ints(&mut vm, "right"), [3, 2, 1]);
+        assert_eq!(ints(&mut vm, "left"), [1, 3]);
+        assert_eq!(ints(&mut vm, "indices"), [2, 1, 0, 0, 2]);
+        // the last element seeds the accumulator of `reduceRight`.
+        assert_eq!(elements(&mut vm, "paired")[1].as_int32(), 1);
+        let global = vm.global_object();
+        let seed = vm.get(global, "seed").unwrap_or_else(|_| panic!());
+        assert_eq!(seed.as_int32(), 3);
+        let total = vm.get(global, "total").unwrap_or_else(|_| panic!());
+        assert_eq!(total.as_int32(), 6);
+        let seeded = vm.get(global, "seeded").unwrap_or_else(|_| panic!());
+        assert_eq!(seeded.as_int32(), 5);
+        let empty = vm.get(global, "empty").unwrap_or_else(|_| panic!());
+        assert_eq!(empty.as_int32(), 7);
+        let array = vm.get(global, "array").unwrap_or_else(|_| panic!());
+        assert!(array.to_boolean());
+
+        for source in &["[].reduce(sum);", "[, , ].reduceRight(sum);"] {
+            match vm.eval(source) {
+                Err(e) => assert!(std::ptr::eq(
+                    e.as_object().class(),
+                    JsTypeError::get_class()
+                )),
+                Ok(_) => panic!("expected TypeError from {}", source),
+            }
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_iterators_visit_holes() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = [1, , 3, , ];
+             entries = Array.from(a.entries());
+             keys = Array.from(a.keys());
+             values = Array.from(a.values());
+             alias = Array.prototype[Symbol.iterator] === Array.prototype.values;
+             it = a.keys();
+             own = it[Symbol.iterator]() === it;",
+        );
+        assert!(res.is_ok());
+        assert_eq!(ints(&mut vm, "keys"), [0, 1, 2, 3]);
+        let values = elements(&mut vm, "values");
+        assert_eq!(values.len(), 4);
+        assert!(values[1].is_undefined() && values[3].is_undefined());
+        assert_eq!(values[2].as_int32(), 3);
+
+        let entries = elements(&mut vm, "entries");
+        assert_eq!(entries.len(), 4);
+        for (i, entry) in entries.iter().enumerate() {
+            let entry = entry.as_object();
+            let index = vm.get(entry, 0u32).unwrap_or_else(|_| panic!());
+            let value = vm.get(entry, 1u32).unwrap_or_else(|_| panic!());
+            assert_eq!(index.as_int32(), i as i32);
+            assert!(entry.has_own_property(&mut vm, Symbol::Indexed(1)));
+            assert_eq!(value.is_undefined(), i % 2 == 1);
+        }
+
+        let global = vm.global_object();
+        for name in &["alias", "own"] {
+            let val = vm.get(global, *name).unwrap_or_else(|_| panic!());
+            assert!(val.as_boolean());
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_join_reverse_concat() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function u() {}
+             a = [1, , u(), null, 'x'].join();
+             b = [1, 2, 3].join(' - ');
+             c = [].join();
+             d = [1, 2, 3, 4].reverse();
+             e = [1, , 3, 4].reverse();
+             f = [1, 2].concat(3, [4, [5]], 'six');
+             g = [1, , 3].concat([4]);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get = |vm: &mut VirtualMachine, name: &str| {
+            let val = vm.get(global, name).unwrap_or_else(|_| panic!());
+            val.as_string().as_str().to_owned()
+        };
+        assert_eq!(get(&mut vm, "a"), "1,,,,x");
+        assert_eq!(get(&mut vm, "b"), "1 - 2 - 3");
+        assert_eq!(get(&mut vm, "c"), "");
+        assert_eq!(ints(&mut vm, "d"), [4, 3, 2, 1]);
+        let e = vm.get(global, "e").unwrap_or_else(|_| panic!()).as_object();
+        assert!(!e.has_own_property(&mut vm, Symbol::Indexed(2)));
+        assert_eq!(vm.get(e, 0u32).unwrap_or_else(|_| panic!()).as_int32(), 4);
+
+        let f = elements(&mut vm, "f");
+        assert_eq!(f.len(), 6);
+        assert_eq!(f[3].as_int32(), 4);
+        assert!(f[4].is_object());
+        assert_eq!(f[5].as_string().as_str(), "six");
+        let g = vm.get(global, "g").unwrap_or_else(|_| panic!()).as_object();
+        assert_eq!(elements(&mut vm, "g").len(), 4);
+        assert!(!g.has_own_property(&mut vm, Symbol::Indexed(1)));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_fill_copy_within() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = [1, 2, 3, 4, 5].fill(0, 1, 0 - 1);
+             b = [1, 2, 3].fill(7);
+             c = [1, 2, 3, 4, 5].copyWithin(1, 0, 3);
+             d = [1, 2, 3, 4, 5].copyWithin(0, 2);
+             e = [1, 2, 3, 4, 5].copyWithin(0 - 2, 0);",
+        );
+        assert!(res.is_ok());
+        assert_eq!(ints(&mut vm, "a"), [1, 0, 0, 0, 5]);
+        assert_eq!(ints(&mut vm, "b"), [7, 7, 7]);
+        assert_eq!(ints(&mut vm, "c"), [1, 1, 2, 3, 5]);
+        assert_eq!(ints(&mut vm, "d"), [3, 4, 5, 4, 5]);
+        assert_eq!(ints(&mut vm, "e"), [1, 2, 3, 1, 2]);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_slice() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = [1, 2, 3, 4, 5];
+             b = a.slice(1, 0 - 1);
+             b[0] = 9;
+             c = a.slice(0 - 2);
+             d = a.slice(0 - 100, 100);
+             e = a.slice(3, 1);
+             f = [1, , 3].slice(1);",
+        );
+        assert!(res.is_ok());
+        assert_eq!(ints(&mut vm, "a"), [1, 2, 3, 4, 5]);
+        assert_eq!(ints(&mut vm, "b"), [9, 3, 4]);
+        assert_eq!(ints(&mut vm, "c"), [4, 5]);
+        assert_eq!(ints(&mut vm, "d"), [1, 2, 3, 4, 5]);
+        assert!(elements(&mut vm, "e").is_empty());
+        let global = vm.global_object();
+        let f = vm.get(global, "f").unwrap_or_else(|_| panic!()).as_object();
+        assert_eq!(elements(&mut vm, "f").len(), 2);
+        assert!(!f.has_own_property(&mut vm, Symbol::Indexed(0)));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "var nothing;
+             nan = 0 / 0;
+             a = [1, 2, 3, 2, 1];
+             holes = [];
+             holes[2] = 'c';
+             out = [
+                 [nan].includes(nan), [nan].indexOf(nan), [nan].lastIndexOf(nan),
+                 a.indexOf(2), a.indexOf(2, 2), a.indexOf(2, 0 - 2), a.indexOf(1, 0 - 10),
+                 a.indexOf(2, 5), a.indexOf('2'), a.lastIndexOf(2), a.lastIndexOf(2, 0 - 3),
+                 a.lastIndexOf(1, 0 - 6), a.lastIndexOf(1, 10), a.includes(3, 0 - 2),
+                 a.includes(3, 0 - 3), a.includes(1, 5), [0].includes(0 - 0),
+                 holes.includes(nothing), holes.indexOf(nothing), holes.indexOf('c'),
+                 holes.lastIndexOf(nothing), [].lastIndexOf(nothing)
+             ].join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(
+            out.as_string().as_str(),
+            "true,-1,-1,1,3,3,0,-1,-1,3,1,-1,4,false,true,false,true,true,-1,2,-1,-1"
+        );
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_at() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = [1, 2, 3];
+             holes = [];
+             holes[2] = 'c';
+             out = [a.at(0), a.at(0 - 1), a.at(1.7), a.at('2'), holes.at(0 - 1)].join();
+             past = a.at(3);
+             before = a.at(0 - 4);
+             hole = holes.at(0);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "1,3,2,3,c");
+        for name in ["past", "before", "hole"].iter() {
+            assert!(vm
+                .get(global, *name)
+                .unwrap_or_else(|_| panic!())
+                .is_undefined());
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_change_array_by_copy() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function numeric(a, b) { return a - b; }
+             function u() {}
+             a = [3, 1, 2];
+             sorted = a.toSorted();
+             sortedBy = [10, 2, 1].toSorted(numeric);
+             undefinedLast = [2, u(), 1].toSorted();
+             reversed = a.toReversed();
+             spliced = a.toSpliced(1, 1, 7, 8);
+             tail = a.toSpliced(0 - 1);
+             inserted = a.toSpliced(3, 0, 4);
+             replaced = a.with(0 - 1, 9);
+             holes = [];
+             holes[1] = 5;
+             filled = holes.with(1, 6);
+             same = a.join() === '3,1,2';
+             holesKept = holes.length === 2;
+             mutatedSort = [3, 1, 2].sort().join() === sorted.join();
+             mutatedReverse = [3, 1, 2].reverse().join() === reversed.join();",
+        );
+        assert!(res.is_ok());
+        assert_eq!(ints(&mut vm, "a"), [3, 1, 2]);
+        assert_eq!(ints(&mut vm, "sorted"), [1, 2, 3]);
+        assert_eq!(ints(&mut vm, "sortedBy"), [1, 2, 10]);
+        assert_eq!(ints(&mut vm, "reversed"), [2, 1, 3]);
+        assert_eq!(ints(&mut vm, "spliced"), [3, 7, 8, 2]);
+        assert_eq!(ints(&mut vm, "tail"), [3, 1]);
+        assert_eq!(ints(&mut vm, "inserted"), [3, 1, 2, 4]);
+        assert_eq!(ints(&mut vm, "replaced"), [3, 1, 9]);
+        let last = elements(&mut vm, "undefinedLast");
+        assert_eq!(last.len(), 3);
+        assert!(last[2].is_undefined());
+        let filled = elements(&mut vm, "filled");
+        assert!(filled[0].is_undefined());
+        assert_eq!(filled[1].as_int32(), 6);
+        let global = vm.global_object();
+        for name in ["same", "holesKept", "mutatedSort", "mutatedReverse"].iter() {
+            let value = vm.get(global, *name).unwrap_or_else(|_| panic!());
+            assert!(value.to_boolean(), "{}", name);
+        }
+
+        assert!(vm.eval("a.with(3, 0);").is_err());
+        assert!(vm.eval("a.with(0 - 4, 0);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,194 @@
+//! `Atomics` for the single agent of the VM. Nothing else can observe a view while an operation
+//! runs, so plain reads and writes are sequentially consistent.
+use super::{
+    define_method,
+    typed_array::{range_error, to_index, type_error},
+};
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        object::{JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        string::JsString,
+        symbol::Symbol,
+        typed_array::TypedArrayKind,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// section 25.4.2.1 ValidateIntegerTypedArray(typedArray)
+fn validate_integer_typed_array(
+    vm: &mut VirtualMachine,
+    value: JsValue,
+) -> Result<Gc<JsObject>, JsValue> {
+    let kind = if value.is_object() {
+        TypedArrayKind::from_tag(value.as_object().tag())
+    } else {
+        None
+    };
+    match kind {
+        Some(TypedArrayKind::Uint8Clamped)
+        | Some(TypedArrayKind::Float32)
+        | Some(TypedArrayKind::Float64)
+        | None => Err(type_error(
+            vm,
+            "Atomics operations need an integer typed array",
+        )),
+        Some(_) => {
+            let array = value.as_object();
+            if array
+                .as_typed_array()
+                .buffer()
+                .as_array_buffer()
+                .is_detached()
+            {
+                return Err(type_error(
+                    vm,
+                    "Atomics operation on a detached ArrayBuffer",
+                ));
+            }
+            Ok(array)
+        }
+    }
+}
+
+/// section 25.4.2.2 ValidateAtomicAccess(typedArray, requestIndex)
+fn validate_atomic_access(
+    vm: &mut VirtualMachine,
+    array: Gc<JsObject>,
+    index: JsValue,
+) -> Result<usize, JsValue> {
+    let index = to_index(vm, index, "atomic access index")?;
+    if index >= array.as_typed_array().length() {
+        return Err(range_error(vm, "Invalid atomic access index"));
+    }
+    Ok(index)
+}
+
+/// section 7.1.5 ToIntegerOrInfinity(argument)
+fn to_integer_or_infinity(vm: &mut VirtualMachine, value: JsValue) -> Result<f64, JsValue> {
+    let n = value.to_number(vm)?;
+    Ok(if n.is_nan() || n == 0.0 {
+        0.0
+    } else {
+        n.trunc()
+    })
+}
+
+/// Reads the element at `index` after the arguments were converted, converting them can detach
+/// the buffer.
+fn element(vm: &mut VirtualMachine, array: Gc<JsObject>, index: usize) -> Result<f64, JsValue> {
+    match array.as_typed_array().get(index) {
+        Some(value) => Ok(value.number()),
+        None => Err(type_error(
+            vm,
+            "Atomics operation on a detached ArrayBuffer",
+        )),
+    }
+}
+
+/// section 25.4.4 Atomics.add(typedArray, index, value)
+pub fn atomics_add(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let array = validate_integer_typed_array(vm, args.at(0))?;
+    let index = validate_atomic_access(vm, array, args.at(1))?;
+    let value = to_integer_or_infinity(vm, args.at(2))?;
+    let old = element(vm, array, index)?;
+    // only the low 32 bits of `value` reach the element, reducing it first keeps the sum exact.
+    let value = if value.is_finite() {
+        value.rem_euclid(4294967296.0)
+    } else {
+        0.0
+    };
+    array.as_typed_array().set(index, old + value);
+    Ok(JsValue::new(old))
+}
+
+/// section 25.4.9 Atomics.load(typedArray, index)
+pub fn atomics_load(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let array = validate_integer_typed_array(vm, args.at(0))?;
+    let index = validate_atomic_access(vm, array, args.at(1))?;
+    Ok(JsValue::new(element(vm, array, index)?))
+}
+
+/// section 25.4.11 Atomics.store(typedArray, index, value)
+pub fn atomics_store(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let array = validate_integer_typed_array(vm, args.at(0))?;
+    let index = validate_atomic_access(vm, array, args.at(1))?;
+    let value = to_integer_or_infinity(vm, args.at(2))?;
+    element(vm, array, index)?;
+    array.as_typed_array().set(index, value);
+    Ok(JsValue::new(value))
+}
+
+pub(crate) fn init_atomics(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().object_structure.unwrap();
+    let mut atomics = ctx.new_local(JsObject::new(
+        vm,
+        structure,
+        JsObject::get_class(),
+        ObjectTag::Ordinary,
+    ));
+    define_method(vm, *atomics, "add", atomics_add, 3);
+    define_method(vm, *atomics, "load", atomics_load, 2);
+    define_method(vm, *atomics, "store", atomics_store, 3);
+    let tag = JsString::new(vm, "Atomics");
+    let _ = atomics.define_own_property(
+        vm,
+        Symbol::to_string_tag(),
+        &*DataDescriptor::new(JsValue::new(tag), C),
+        false,
+    );
+
+    let name = vm.intern("Atomics");
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*atomics), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_atomics_on_shared_buffer() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "buffer = new SharedArrayBuffer(8);
+             ints = new Int32Array(buffer);
+             first = Atomics.add(ints, 1, 5);
+             second = Atomics.add(ints, 1, 2);
+             stored = Atomics.store(ints, 0, 7.9);
+             bytes = new Uint8Array(4);
+             bytes[0] = 250;
+             wrapped = Atomics.add(bytes, 0, 10);
+             out = '' + first + ',' + second + ',' + ints[1] + ',' + stored + ','
+                 + Atomics.load(ints, 0) + ',' + wrapped + ',' + bytes[0] + ','
+                 + buffer.byteLength + ',' + buffer.slice(4).byteLength;
+             cloned = structuredClone(buffer) === buffer;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "0,5,7,7,7,250,4,8,4");
+        let cloned = vm.get(global, "cloned").unwrap_or_else(|_| panic!());
+        assert!(cloned.to_boolean());
+
+        assert!(vm
+            .eval("structuredClone(buffer, { transfer: [buffer] });")
+            .is_err());
+        assert!(vm
+            .eval("ArrayBuffer.prototype.slice.call(buffer, 0);")
+            .is_err());
+        assert!(vm.eval("Atomics.add(new Float64Array(1), 0, 1);").is_err());
+        assert!(vm.eval("Atomics.load(ints, 2);").is_err());
+        assert!(vm.eval("SharedArrayBuffer(1);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
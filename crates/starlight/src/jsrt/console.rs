@@ -0,0 +1,258 @@
+use super::define_method;
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        object::{EnumerationMode, JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+use std::io::Write;
+
+/// Arguments separated by spaces, strings as they are and everything else inspected.
+fn format_args(vm: &mut VirtualMachine, args: &Arguments) -> String {
+    let mut line = String::new();
+    for ix in 0..args.size() {
+        if ix != 0 {
+            line.push(' ');
+        }
+        let val = args[ix];
+        if val.is_string() {
+            line.push_str(val.as_string().as_str());
+        } else {
+            line.push_str(&vm.inspect(val));
+        }
+    }
+    line.push('\n');
+    line
+}
+
+/// `console.log(...args)`, also used for `console.info` and `console.debug`.
+pub fn console_log(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let line = format_args(vm, args);
+    let _ = vm.stdout().write_all(line.as_bytes());
+    Ok(JsValue::undefined())
+}
+
+/// `console.error(...args)`, also used for `console.warn`.
+pub fn console_error(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let line = format_args(vm, args);
+    let _ = vm.stderr().write_all(line.as_bytes());
+    Ok(JsValue::undefined())
+}
+
+/// `console.dir(obj, options)`, prints `obj` inspected down to `options.depth` levels of
+/// nesting, 2 by default. A `null` depth has no limit.
+pub fn console_dir(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut depth = Some(2);
+    let options = args.at(1);
+    if options.is_object() {
+        let val = vm.get(options.as_object(), "depth")?;
+        if val.is_null() {
+            depth = None;
+        } else if !val.is_undefined() {
+            let n = val.to_number(vm)?;
+            depth = if n == f64::INFINITY {
+                None
+            } else {
+                Some(n.max(0.0) as u32)
+            };
+        }
+    }
+    let mut line = vm.inspect_to_depth(args.at(0), depth);
+    line.push('\n');
+    let _ = vm.stdout().write_all(line.as_bytes());
+    Ok(JsValue::undefined())
+}
+
+/// Own enumerable keys of `obj`.
+fn own_keys(vm: &mut VirtualMachine, obj: Gc<JsObject>) -> Vec<Symbol> {
+    let mut keys = vec![];
+    obj.get_own_property_names(vm, &mut |name, _| keys.push(name), EnumerationMode::Default);
+    keys
+}
+
+/// `console.table(data)`, prints the own enumerable properties of `data` as the rows of an
+/// ASCII table. The columns are the keys of the rows that are objects in the order they are
+/// first seen, other rows show their value in a `Values` column. Anything but an object is
+/// printed like `console.log` does.
+pub fn console_table(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let data = args.at(0);
+    if !data.is_object() || data.as_object().is_callable() {
+        return console_log(vm, args);
+    }
+    let ctx = vm.space().new_local_context();
+    let data = ctx.new_local(data.as_object());
+    let mut columns: Vec<Symbol> = vec![];
+    let mut has_values = false;
+    // index, cells of the keys of the row and the value of a row that is not an object.
+    let mut rows = vec![];
+    for index in own_keys(vm, *data) {
+        let val = data.get(vm, index)?;
+        let mut cells = vec![];
+        let mut value = String::new();
+        if val.is_object() && !val.as_object().is_callable() {
+            let row = ctx.new_local(val.as_object());
+            for key in own_keys(vm, *row) {
+                if !columns.contains(&key) {
+                    columns.push(key);
+                }
+                let cell = row.get(vm, key)?;
+                cells.push((key, vm.inspect(cell)));
+            }
+        } else {
+            has_values = true;
+            value = vm.inspect(val);
+        }
+        rows.push((vm.description(index), cells, value));
+    }
+
+    let mut header = vec!["(index)".to_string()];
+    header.extend(columns.iter().map(|column| vm.description(*column)));
+    if has_values {
+        header.push("Values".to_string());
+    }
+    let mut lines = vec![header];
+    for (index, cells, value) in rows {
+        let mut line = vec![index];
+        for column in columns.iter() {
+            let cell = cells.iter().find(|(key, _)| key == column);
+            line.push(cell.map(|(_, cell)| cell.clone()).unwrap_or_default());
+        }
+        if has_values {
+            line.push(value);
+        }
+        lines.push(line);
+    }
+    let widths = (0..lines[0].len())
+        .map(|i| {
+            lines
+                .iter()
+                .map(|line| line[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let mut rule = String::from("+");
+    for width in widths.iter() {
+        rule.push_str(&"-".repeat(width + 2));
+        rule.push('+');
+    }
+    rule.push('\n');
+
+    let mut out = rule.clone();
+    for (i, line) in lines.iter().enumerate() {
+        out.push('|');
+        for (cell, width) in line.iter().zip(widths.iter()) {
+            out.push_str(&format!(" {:<1$} |", cell, width));
+        }
+        out.push('\n');
+        if i == 0 {
+            out.push_str(&rule);
+        }
+    }
+    out.push_str(&rule);
+    let _ = vm.stdout().write_all(out.as_bytes());
+    Ok(JsValue::undefined())
+}
+
+pub(crate) fn init_console(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().object_structure.unwrap();
+    let console = ctx.new_local(JsObject::new(
+        vm,
+        structure,
+        JsObject::get_class(),
+        ObjectTag::Ordinary,
+    ));
+    define_method(vm, *console, "log", console_log, 0);
+    define_method(vm, *console, "info", console_log, 0);
+    define_method(vm, *console, "debug", console_log, 0);
+    define_method(vm, *console, "error", console_error, 0);
+    define_method(vm, *console, "warn", console_error, 0);
+    define_method(vm, *console, "dir", console_dir, 0);
+    define_method(vm, *console, "table", console_table, 1);
+    let name = vm.intern("console");
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*console), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{BufferedError, Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_console_sinks() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let stdout = BufferedError::default();
+        let stderr = BufferedError::default();
+        vm.set_stdout(Box::new(stdout.clone()));
+        vm.set_stderr(Box::new(stderr.clone()));
+        assert!(vm
+            .eval(
+                "print('p');
+                 console.log('a', 1, [true]);
+                 console.error('oops', 'b');
+                 console.warn('w');"
+            )
+            .is_ok());
+        assert_eq!(stdout.to_string(), "p\na 1 [true]\n");
+        assert_eq!(stderr.to_string(), "oops b\nw\n");
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_console_dir_depth() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let stdout = BufferedError::default();
+        vm.set_stdout(Box::new(stdout.clone()));
+        assert!(vm
+            .eval(
+                "o = { a: { b: { c: { d: 1 } } }, e: [1, [2, [3]]] };
+                 console.dir(o);
+                 console.dir(o, { depth: 0 });
+                 console.dir(o, { depth: null });
+                 c = { name: 'c' };
+                 c.self = c;
+                 console.dir(c, { depth: null });"
+            )
+            .is_ok());
+        assert_eq!(
+            stdout.to_string(),
+            "{ a: { b: { c: [Object] } }, e: [1, [2, [Array]]] }\n\
+             { a: [Object], e: [Array] }\n\
+             { a: { b: { c: { d: 1 } } }, e: [1, [2, [3]]] }\n\
+             { name: \"c\", self: [Circular] }\n"
+        );
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_console_table() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let stdout = BufferedError::default();
+        vm.set_stdout(Box::new(stdout.clone()));
+        assert!(vm
+            .eval("console.table([{ a: 1, b: 'x' }, { a: 22, c: true }, 3]);")
+            .is_ok());
+        assert_eq!(
+            stdout.to_string(),
+            "+---------+----+-----+------+--------+\n\
+             | (index) | a  | b   | c    | Values |\n\
+             +---------+----+-----+------+--------+\n\
+             | 0       | 1  | \"x\" |      |        |\n\
+             | 1       | 22 |     | true |        |\n\
+             | 2       |    |     |      | 3      |\n\
+             +---------+----+-----+------+--------+\n"
+        );
+        VirtualMachineRef::dispose(vm);
+    }
+}
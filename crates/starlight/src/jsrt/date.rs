@@ -0,0 +1,273 @@
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::define_method;
+use crate::{
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        date::*,
+        error::{JsRangeError, JsTypeError},
+        function::JsNativeFunction,
+        object::{JsHint, ObjectTag},
+        property_descriptor::DataDescriptor,
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn current_time() -> f64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_millis() as f64,
+        Err(before) => -(before.duration().as_millis() as f64),
+    }
+}
+
+/// There is no wall clock without host bindings, the epoch is reported.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn current_time() -> f64 {
+    0.0
+}
+
+/// section 21.4.1.22 thisTimeValue(value)
+fn this_time_value(vm: &mut VirtualMachine, this: JsValue, method: &str) -> Result<f64, JsValue> {
+    if !this.is_object() || this.as_object().tag() != ObjectTag::Date {
+        let msg = JsString::new(
+            vm,
+            format!("Date.prototype.{} called on incompatible receiver", method),
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(this.as_object().as_date().time())
+}
+
+/// The time value of `year, month[, date[, hours[, minutes[, seconds[, ms]]]]]` as passed to
+/// `Date.UTC` and the `Date` constructor.
+fn time_from_fields(vm: &mut VirtualMachine, args: &Arguments) -> Result<f64, JsValue> {
+    let defaults = [f64::NAN, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+    let mut fields = defaults;
+    for (ix, field) in fields.iter_mut().enumerate() {
+        if ix < args.size() {
+            *field = args.at(ix).to_number(vm)?;
+        }
+    }
+    let mut year = fields[0];
+    if !year.is_nan() {
+        let integer = year.trunc();
+        if (0.0..=99.0).contains(&integer) {
+            year = 1900.0 + integer;
+        }
+    }
+    let day = make_day(year, fields[1], fields[2]);
+    let time = make_time(fields[3], fields[4], fields[5], fields[6]);
+    Ok(make_date(day, time))
+}
+
+/// section 21.4.2.1 Date(...values)
+pub fn date_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if !args.ctor_call {
+        let string = JsString::new(vm, to_date_string(current_time()));
+        return Ok(JsValue::new(string));
+    }
+    let time = match args.size() {
+        0 => current_time(),
+        1 => {
+            let value = args.at(0);
+            if value.is_object() && value.as_object().tag() == ObjectTag::Date {
+                value.as_object().as_date().time()
+            } else {
+                let value = value.to_primitive(vm, JsHint::None)?;
+                if value.is_string() {
+                    parse_date(value.as_string().as_str())
+                } else {
+                    value.to_number(vm)?
+                }
+            }
+        }
+        _ => time_from_fields(vm, args)?,
+    };
+    Ok(JsValue::new(JsDate::new(vm, time_clip(time))))
+}
+
+/// section 21.4.3.1 Date.now()
+pub fn date_now(_vm: &mut VirtualMachine, _args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(current_time()))
+}
+
+/// section 21.4.3.2 Date.parse(string)
+pub fn date_parse(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = args.at(0).to_string(vm)?;
+    Ok(JsValue::new(parse_date(&string)))
+}
+
+/// section 21.4.3.4 Date.UTC(year[, month[, date[, hours[, minutes[, seconds[, ms]]]]]])
+pub fn date_utc(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(time_clip(time_from_fields(vm, args)?)))
+}
+
+/// section 21.4.4.10 Date.prototype.getTime()
+pub fn date_get_time(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(this_time_value(vm, args.this, "getTime")?))
+}
+
+/// section 21.4.4.44 Date.prototype.valueOf()
+pub fn date_value_of(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(this_time_value(vm, args.this, "valueOf")?))
+}
+
+/// section 21.4.4.36 Date.prototype.toISOString()
+pub fn date_to_iso_string(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let time = this_time_value(vm, args.this, "toISOString")?;
+    if time.is_nan() {
+        let msg = JsString::new(vm, "Invalid time value");
+        return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+    }
+    Ok(JsValue::new(JsString::new(vm, to_iso_string(time))))
+}
+
+/// section 21.4.4.41 Date.prototype.toString()
+pub fn date_to_string(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let time = this_time_value(vm, args.this, "toString")?;
+    Ok(JsValue::new(JsString::new(vm, to_date_string(time))))
+}
+
+/// section 21.4.4.37 Date.prototype.toJSON(key)
+pub fn date_to_json(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if !args.this.is_object() {
+        let msg = JsString::new(vm, "Date.prototype.toJSON called on non-object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(args.this.as_object());
+    let time = args.this.to_primitive(vm, JsHint::Number)?;
+    if time.is_number() && !time.number().is_finite() {
+        return Ok(JsValue::null());
+    }
+    let name = vm.intern("toISOString");
+    let to_iso_string = obj.get(vm, name)?;
+    if !to_iso_string.is_callable() {
+        let msg = JsString::new(vm, "Date.prototype.toJSON: toISOString is not a function");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let mut args = ctx.new_local(Arguments::new(vm, JsValue::new(*obj), 0));
+    to_iso_string
+        .as_object()
+        .as_function_mut()
+        .call(vm, &mut args)
+}
+
+pub(crate) fn init_date(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().date_structure.unwrap();
+    let mut proto = ctx.new_local(structure.prototype().unwrap());
+    let name = vm.intern("Date");
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        date_constructor,
+        7,
+    ));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(*proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    define_method(vm, *ctor, "now", date_now, 0);
+    define_method(vm, *ctor, "parse", date_parse, 1);
+    define_method(vm, *ctor, "UTC", date_utc, 7);
+    define_method(vm, *proto, "getTime", date_get_time, 0);
+    define_method(vm, *proto, "valueOf", date_value_of, 0);
+    define_method(vm, *proto, "toISOString", date_to_iso_string, 0);
+    define_method(vm, *proto, "toString", date_to_string, 0);
+    define_method(vm, *proto, "toJSON", date_to_json, 1);
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_to_json() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = JSON.stringify({ d: new Date(0), e: new Date(2020, 1, 29, 12, 30) });
+             b = JSON.stringify({ d: new Date('x') });
+             function toJSON(key) { return key + '!'; }
+             c = JSON.stringify({ n: 1, o: { toJSON: toJSON } });
+             d = JSON.stringify({ d: new Date(0), f: toJSON, r: /a/g });
+             e = new Date('2000-01-01T00:00:00.000Z').getTime();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let expected = [
+            (
+                "a",
+                r#"{"d":"1970-01-01T00:00:00.000Z","e":"2020-02-29T12:30:00.000Z"}"#,
+            ),
+            ("b", r#"{"d":null}"#),
+            ("c", r#"{"n":1,"o":"o!"}"#),
+            ("d", r#"{"d":"1970-01-01T00:00:00.000Z","r":{}}"#),
+        ];
+        for (name, json) in expected.iter() {
+            let value = vm.get(global, name).unwrap_or_else(|_| panic!());
+            assert_eq!(value.as_string().as_str(), *json);
+        }
+        let time = vm.get(global, "e").unwrap_or_else(|_| panic!());
+        assert_eq!(
+            time.to_number(&mut vm).unwrap_or_else(|_| panic!()),
+            946684800000.0
+        );
+
+        assert!(vm.eval("new Date('x').toISOString();").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_parse_and_components() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "zulu = new Date('2020-02-29T12:00:00Z').toISOString();
+             offset = new Date('2020-02-29T12:00:00+02:00').toISOString();
+             bare = new Date('2020-02-29').toISOString();
+             rfc = Date.parse('Sat, 29 Feb 2020 12:00:00 GMT') === Date.parse('2020-02-29T12:00Z');
+             invalid = new Date('not a date').toString();
+             invalidTime = '' + new Date('2020-13-01').getTime();
+             overflow = new Date(2020, 13, 35).toISOString();
+             hours = new Date(Date.UTC(2020, 0, 1, 25, 0 - 30)).toISOString();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let expected = [
+            ("zulu", "2020-02-29T12:00:00.000Z"),
+            ("offset", "2020-02-29T10:00:00.000Z"),
+            ("bare", "2020-02-29T00:00:00.000Z"),
+            ("invalid", "Invalid Date"),
+            ("invalidTime", "NaN"),
+            ("overflow", "2021-03-07T00:00:00.000Z"),
+            ("hours", "2020-01-02T00:30:00.000Z"),
+        ];
+        for (name, out) in expected.iter() {
+            let value = vm.get(global, name).unwrap_or_else(|_| panic!());
+            assert_eq!(value.as_string().as_str(), *out, "{}", name);
+        }
+        let rfc = vm.get(global, "rfc").unwrap_or_else(|_| panic!());
+        assert!(rfc.to_boolean());
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    runtime::{arguments::Arguments, string::JsString, value::JsValue},
+    vm::VirtualMachine,
+};
+
+/// Characters `escape` leaves alone, besides ASCII letters and digits.
+const UNESCAPED: &[u8] = b"@*_+-./";
+
+fn hex_digit(unit: u16) -> Option<u16> {
+    std::char::from_u32(unit as u32)
+        .and_then(|c| c.to_digit(16))
+        .map(|d| d as u16)
+}
+
+/// section B.2.1.1 escape(string)
+pub fn escape(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let s = args.at(0).to_string(vm)?;
+    let mut res = String::with_capacity(s.len());
+    for unit in s.encode_utf16() {
+        let byte = unit as u8;
+        if unit < 128 && (byte.is_ascii_alphanumeric() || UNESCAPED.contains(&byte)) {
+            res.push(byte as char);
+        } else if unit < 256 {
+            res.push_str(&format!("%{:02X}", unit));
+        } else {
+            res.push_str(&format!("%u{:04X}", unit));
+        }
+    }
+    Ok(JsValue::new(JsString::new(vm, res)))
+}
+
+/// section B.2.1.2 unescape(string)
+pub fn unescape(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let s = args.at(0).to_string(vm)?;
+    let units = s.encode_utf16().collect::<Vec<u16>>();
+    let hex = |from: usize, count: usize| -> Option<u16> {
+        let digits = units.get(from..from + count)?;
+        digits
+            .iter()
+            .try_fold(0, |acc, &unit| Some(acc * 16 + hex_digit(unit)?))
+    };
+    let mut res = Vec::with_capacity(units.len());
+    let mut k = 0;
+    while k < units.len() {
+        let unit = units[k];
+        if unit == b'%' as u16 {
+            if units.get(k + 1) == Some(&(b'u' as u16)) {
+                if let Some(decoded) = hex(k + 2, 4) {
+                    res.push(decoded);
+                    k += 6;
+                    continue;
+                }
+            } else if let Some(decoded) = hex(k + 1, 2) {
+                res.push(decoded);
+                k += 3;
+                continue;
+            }
+        }
+        res.push(unit);
+        k += 1;
+    }
+    // lone surrogates have no UTF-8 form and are replaced like in `fromCharCode`.
+    let res = String::from_utf16_lossy(&res);
+    Ok(JsValue::new(JsString::new(vm, res)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_escape_round_trip() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "ascii = escape('abc 123@*_+-./!~');
+             asciiBack = unescape(ascii);
+             latin = escape('\\u00e9');
+             euro = escape('x\\u20ac');
+             euroBack = unescape(euro);
+             broken = unescape('%zz%u12%4');",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get = |vm: &mut VirtualMachine, name: &str| {
+            let val = vm.get(global, name).unwrap_or_else(|_| panic!());
+            val.as_string().as_str().to_owned()
+        };
+        assert_eq!(get(&mut vm, "ascii"), "abc%20123@*_+-./%21%7E");
+        assert_eq!(get(&mut vm, "asciiBack"), "abc 123@*_+-./!~");
+        assert_eq!(get(&mut vm, "latin"), "%E9");
+        assert_eq!(get(&mut vm, "euro"), "x%u20AC");
+        assert_eq!(get(&mut vm, "euroBack"), "x\u{20ac}");
+        assert_eq!(get(&mut vm, "broken"), "%zz%u12%4");
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    runtime::{arguments::Arguments, error::JsEvalError, string::JsString, value::JsValue},
+    vm::VirtualMachine,
+};
+
+/// Throws an `EvalError` when runtime code generation is disabled with
+/// [Options::allow_eval](crate::vm::Options::allow_eval), `what` names the caller.
+pub(crate) fn ensure_eval_allowed(vm: &mut VirtualMachine, what: &str) -> Result<(), JsValue> {
+    if vm.options().allow_eval {
+        return Ok(());
+    }
+    let msg = JsString::new(
+        vm,
+        format!("{}: code generation from strings is disabled", what),
+    );
+    Err(JsValue::new(JsEvalError::new(vm, msg, None)))
+}
+
+/// section 18.2.1 eval(x), every call is an indirect eval: the code runs as global code and
+/// does not see the variables of the caller.
+pub fn global_eval(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    ensure_eval_allowed(vm, "eval")?;
+    let source = args.at(0);
+    if !source.is_string() {
+        return Ok(source);
+    }
+    let ctx = vm.space().new_local_context();
+    let code = vm.compile_script(source.as_string().as_str(), "<eval>")?;
+    let code = ctx.new_local(code);
+    vm.run_script(*code)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        runtime::error::{JsEvalError, JsSyntaxError},
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_eval() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function twice(x) { return x + x; }
+             three = eval('1 + 2');
+             same = eval(5);
+             eval('var declared = twice(4);');
+             out = [three, same, declared].join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "3,5,8");
+        match vm.eval("eval('(');") {
+            Err(e) => assert!(std::ptr::eq(
+                e.as_object().class(),
+                JsSyntaxError::get_class()
+            )),
+            Ok(_) => panic!("expected SyntaxError"),
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_eval_disallowed() {
+        let mut vm = VirtualMachine::new(Options::builder().allow_eval(false).build());
+        match vm.eval("eval('1');") {
+            Err(e) => assert!(std::ptr::eq(
+                e.as_object().class(),
+                JsEvalError::get_class()
+            )),
+            Ok(_) => panic!("expected EvalError"),
+        }
+        let res = vm.eval(
+            "function add(a, b) { return a + b; }
+             sum = add(1, 2);
+             same = eval;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let sum = vm.get(global, "sum").unwrap_or_else(|_| panic!());
+        assert_eq!(sum.as_int32(), 3);
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,196 @@
+use super::define_method;
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        error::JsTypeError,
+        finalization_registry::JsFinalizationRegistry,
+        function::JsNativeFunction,
+        object::{JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+fn type_error(vm: &mut VirtualMachine, msg: &str) -> JsValue {
+    let msg = JsString::new(vm, msg);
+    JsValue::new(JsTypeError::new(vm, msg, None))
+}
+
+fn this_registry(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    method: &str,
+) -> Result<Gc<JsObject>, JsValue> {
+    if !this.is_object() || this.as_object().tag() != ObjectTag::FinalizationRegistry {
+        let msg = format!(
+            "FinalizationRegistry.prototype.{} called on incompatible receiver",
+            method
+        );
+        return Err(type_error(vm, &msg));
+    }
+    Ok(this.as_object())
+}
+
+/// section 26.2.1.1 FinalizationRegistry(cleanupCallback)
+pub fn finalization_registry_constructor(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if !args.ctor_call {
+        return Err(type_error(
+            vm,
+            "Constructor FinalizationRegistry requires 'new'",
+        ));
+    }
+    let cleanup = args.at(0);
+    if !cleanup.is_callable() {
+        return Err(type_error(
+            vm,
+            "FinalizationRegistry: cleanup must be callable",
+        ));
+    }
+    Ok(JsValue::new(JsFinalizationRegistry::new(vm, cleanup)))
+}
+
+/// section 26.2.3.2 FinalizationRegistry.prototype.register(target, heldValue[, unregisterToken])
+pub fn finalization_registry_register(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut registry = this_registry(vm, args.this, "register")?;
+    let target = args.at(0);
+    if !target.is_object() {
+        return Err(type_error(
+            vm,
+            "FinalizationRegistry.prototype.register: invalid target",
+        ));
+    }
+    let held = args.at(1);
+    if held.is_object() && Gc::ptr_eq(held.as_object(), target.as_object()) {
+        return Err(type_error(
+            vm,
+            "FinalizationRegistry.prototype.register: target and holdings must not be same",
+        ));
+    }
+    let token = args.at(2);
+    let token = if token.is_object() {
+        Some(token.as_object())
+    } else if token.is_undefined() {
+        None
+    } else {
+        return Err(type_error(
+            vm,
+            "FinalizationRegistry.prototype.register: invalid unregister token",
+        ));
+    };
+    registry
+        .as_finalization_registry_mut()
+        .register(vm, target.as_object(), held, token);
+    Ok(JsValue::undefined())
+}
+
+/// section 26.2.3.3 FinalizationRegistry.prototype.unregister(unregisterToken)
+pub fn finalization_registry_unregister(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut registry = this_registry(vm, args.this, "unregister")?;
+    let token = args.at(0);
+    if !token.is_object() {
+        return Err(type_error(
+            vm,
+            "FinalizationRegistry.prototype.unregister: invalid unregister token",
+        ));
+    }
+    Ok(JsValue::new(
+        registry
+            .as_finalization_registry_mut()
+            .unregister(token.as_object()),
+    ))
+}
+
+pub(crate) fn init_finalization_registry(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().finalization_registry_structure.unwrap();
+    let mut proto = ctx.new_local(structure.prototype().unwrap());
+    let name = vm.intern("FinalizationRegistry");
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        finalization_registry_constructor,
+        1,
+    ));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(*proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    define_method(vm, *proto, "register", finalization_registry_register, 2);
+    define_method(
+        vm,
+        *proto,
+        "unregister",
+        finalization_registry_unregister,
+        1,
+    );
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_cleanup_after_collection() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "calls = '';
+             function cleanup(held) { calls = calls + held + ';'; }
+             r = new FinalizationRegistry(cleanup);
+             kept = {};
+             function register() {
+                 var token = {};
+                 r.register({}, 'dropped');
+                 r.register(kept, 'kept', kept);
+                 r.register({}, 'unregistered', token);
+                 removed = r.unregister(token);
+             }
+             register();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let removed = vm.get(global, "removed").unwrap_or_else(|_| panic!());
+        assert!(removed.to_boolean());
+
+        vm.space().gc();
+        vm.run_microtasks();
+        let calls = vm.get(global, "calls").unwrap_or_else(|_| panic!());
+        assert_eq!(calls.as_string().as_str(), "dropped;");
+
+        // every cleanup runs once.
+        vm.space().gc();
+        vm.run_microtasks();
+        let calls = vm.get(global, "calls").unwrap_or_else(|_| panic!());
+        assert_eq!(calls.as_string().as_str(), "dropped;");
+
+        assert!(vm.eval("r.register(1, 'x');").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,182 @@
+use super::{define_method, eval::ensure_eval_allowed};
+use crate::{
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        error::{JsSyntaxError, JsTypeError},
+        function::JsNativeFunction,
+        iterator::arguments_from_list,
+        property_descriptor::DataDescriptor,
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+use swc_ecmascript::ast::{Expr, Stmt};
+
+/// section 19.2.1.1 Function(p1, p2, ..., pn, body), the function is created in the global
+/// scope.
+pub fn function_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    ensure_eval_allowed(vm, "Function")?;
+    let argc = args.size();
+    let mut params = Vec::with_capacity(argc.saturating_sub(1));
+    for i in 0..argc.saturating_sub(1) {
+        params.push(args[i].to_string(vm)?);
+    }
+    let body = if argc == 0 {
+        String::new()
+    } else {
+        args[argc - 1].to_string(vm)?
+    };
+    let source = format!(
+        "(function anonymous({}\n) {{\n{}\n}})",
+        params.join(","),
+        body
+    );
+    let (script, file) = vm.parse_script(&source, "<function>")?;
+    // parameters or a body closing the function early leave more than the function expression.
+    let single = match &script.body[..] {
+        [Stmt::Expr(stmt)] => match &*stmt.expr {
+            Expr::Paren(paren) => matches!(&*paren.expr, Expr::Fn(_)),
+            _ => false,
+        },
+        _ => false,
+    };
+    if !single {
+        let msg = JsString::new(
+            vm,
+            "Malformed parameters or body of the Function constructor",
+        );
+        return Err(JsValue::new(JsSyntaxError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let code = ctx.new_local(vm.compile_parsed(&script, file)?);
+    vm.run_script(*code)
+}
+
+/// section 15.3.4.3 Function.prototype.apply(thisArg, argArray)
+pub fn function_apply(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let func = args.this;
+    if !func.is_callable() {
+        let msg = JsString::new(vm, "Function.prototype.apply was called on non-function");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let arr = args.at(1);
+    let fargs = if arr.is_undefined_or_null() {
+        Arguments::new(vm, args.at(0), 0)
+    } else {
+        arguments_from_list(vm, args.at(0), arr)?
+    };
+    let mut fargs = ctx.new_local(fargs);
+    func.as_object().as_function_mut().call(vm, &mut fargs)
+}
+
+/// section 15.3.4.4 Function.prototype.call(thisArg [, arg1 [, arg2, ...]])
+pub fn function_call(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let func = args.this;
+    if !func.is_callable() {
+        let msg = JsString::new(vm, "Function.prototype.call was called on non-function");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let argc = if args.size() == 0 { 0 } else { args.size() - 1 };
+    let mut fargs = ctx.new_local(Arguments::new(vm, args.at(0), argc));
+    for i in 0..argc {
+        fargs[i] = args[i + 1];
+    }
+    func.as_object().as_function_mut().call(vm, &mut fargs)
+}
+
+pub(crate) fn init_function(vm: &mut VirtualMachine) {
+    let mut proto = vm.global_data().function_prototype.unwrap();
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::length(),
+        &*DataDescriptor::new(JsValue::new(0), C),
+        false,
+    );
+    let name = JsValue::new(JsString::new(vm, ""));
+    let _ = proto.define_own_property(vm, Symbol::name(), &*DataDescriptor::new(name, C), false);
+    define_method(vm, proto, "apply", function_apply, 2);
+    define_method(vm, proto, "call", function_call, 1);
+
+    let ctx = vm.space().new_local_context();
+    let name = vm.intern("Function");
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        function_constructor,
+        1,
+    ));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        runtime::error::{JsEvalError, JsSyntaxError},
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_function_constructor() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "add = new Function('a', 'b', 'return a + b');
+             joined = Function('a, b', 'c', 'return a + b + c');
+             strictThis = new Function(\"'use strict'; return this;\")();
+             empty = new Function();
+             out = [add(1, 2), add.length, add.name, joined(1, 2, 3), joined.length, strictThis,
+                    empty()].join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "3,2,anonymous,6,3,,");
+
+        for src in [
+            "new Function('return (');",
+            "new Function('}); (function() {');",
+        ]
+        .iter()
+        {
+            match vm.eval(src) {
+                Err(e) => assert!(std::ptr::eq(
+                    e.as_object().class(),
+                    JsSyntaxError::get_class()
+                )),
+                Ok(_) => panic!("expected SyntaxError"),
+            }
+        }
+        VirtualMachineRef::dispose(vm);
+
+        let mut vm = VirtualMachine::new(Options::builder().allow_eval(false).build());
+        match vm.eval("new Function('return 1');") {
+            Err(e) => assert!(std::ptr::eq(
+                e.as_object().class(),
+                JsEvalError::get_class()
+            )),
+            Ok(_) => panic!("expected EvalError"),
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+}
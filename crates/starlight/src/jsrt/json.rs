@@ -0,0 +1,322 @@
+use super::define_method;
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        error::JsTypeError,
+        object::{EnumerationMode, JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// section 24.5.2.2 QuoteJSONString(value)
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// State of a single `JSON.stringify` call.
+struct Stringifier {
+    replacer: Option<JsValue>,
+    /// Keys selected by an array replacer, in the order they are serialized.
+    property_list: Option<Vec<Symbol>>,
+    gap: String,
+    indent: String,
+    /// Objects being serialized, to detect cycles.
+    stack: Vec<Gc<JsObject>>,
+}
+
+impl Stringifier {
+    /// section 24.5.2.1 SerializeJSONProperty, `None` stands for `undefined`.
+    fn serialize_property(
+        &mut self,
+        vm: &mut VirtualMachine,
+        holder: Gc<JsObject>,
+        key: Symbol,
+    ) -> Result<Option<String>, JsValue> {
+        let ctx = vm.space().new_local_context();
+        let mut value = holder.get(vm, key)?;
+        if value.is_object() {
+            let to_json = vm.intern("toJSON");
+            let to_json = value.as_object().get(vm, to_json)?;
+            if to_json.is_callable() {
+                let key = JsString::new(vm, key.as_string());
+                let mut args = ctx.new_local(Arguments::new(vm, value, 1));
+                args[0] = JsValue::new(key);
+                value = to_json.as_object().as_function_mut().call(vm, &mut args)?;
+            }
+        }
+        if let Some(replacer) = self.replacer {
+            let key = JsString::new(vm, key.as_string());
+            let mut args = ctx.new_local(Arguments::new(vm, JsValue::new(holder), 2));
+            args[0] = JsValue::new(key);
+            args[1] = value;
+            value = replacer.as_object().as_function_mut().call(vm, &mut args)?;
+        }
+
+        if value.is_null() {
+            Ok(Some("null".to_owned()))
+        } else if value.is_boolean() {
+            Ok(Some(value.as_boolean().to_string()))
+        } else if value.is_string() {
+            Ok(Some(quote(value.as_string().as_str())))
+        } else if value.is_number() {
+            if value.number().is_finite() {
+                Ok(Some(value.to_string(vm)?))
+            } else {
+                Ok(Some("null".to_owned()))
+            }
+        } else if value.is_object() && !value.is_callable() {
+            let obj = ctx.new_local(value.as_object());
+            if obj.tag() == ObjectTag::Array {
+                self.serialize_array(vm, *obj).map(Some)
+            } else {
+                self.serialize_object(vm, *obj).map(Some)
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Pushes `obj` on the stack, throwing a TypeError for a cyclic structure.
+    fn enter(&mut self, vm: &mut VirtualMachine, obj: Gc<JsObject>) -> Result<String, JsValue> {
+        if self.stack.iter().any(|x| Gc::ptr_eq(*x, obj)) {
+            let msg = JsString::new(vm, "JSON.stringify: cannot serialize cyclic structures");
+            return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+        }
+        self.stack.push(obj);
+        let stepback = self.indent.clone();
+        self.indent.push_str(&self.gap);
+        Ok(stepback)
+    }
+
+    fn leave(&mut self, stepback: String) {
+        self.stack.pop();
+        self.indent = stepback;
+    }
+
+    /// Wraps serialized members in `open` and `close`, one per line when there is a gap.
+    fn join(&self, partial: &[String], stepback: &str, open: char, close: char) -> String {
+        if partial.is_empty() {
+            format!("{}{}", open, close)
+        } else if self.gap.is_empty() {
+            format!("{}{}{}", open, partial.join(","), close)
+        } else {
+            let separator = format!(",\n{}", self.indent);
+            format!(
+                "{}\n{}{}\n{}{}",
+                open,
+                self.indent,
+                partial.join(&separator),
+                stepback,
+                close
+            )
+        }
+    }
+
+    /// section 24.5.2.4 SerializeJSONObject(value)
+    fn serialize_object(
+        &mut self,
+        vm: &mut VirtualMachine,
+        obj: Gc<JsObject>,
+    ) -> Result<String, JsValue> {
+        let stepback = self.enter(vm, obj)?;
+        let keys = match self.property_list {
+            Some(ref list) => list.clone(),
+            None => {
+                let mut keys = vec![];
+                obj.get_own_property_names(
+                    vm,
+                    &mut |name, _| keys.push(name),
+                    EnumerationMode::Default,
+                );
+                keys
+            }
+        };
+        let mut partial = vec![];
+        for key in keys {
+            if let Some(value) = self.serialize_property(vm, obj, key)? {
+                let separator = if self.gap.is_empty() { ":" } else { ": " };
+                partial.push(format!("{}{}{}", quote(&key.as_string()), separator, value));
+            }
+        }
+        let result = self.join(&partial, &stepback, '{', '}');
+        self.leave(stepback);
+        Ok(result)
+    }
+
+    /// section 24.5.2.5 SerializeJSONArray(value)
+    fn serialize_array(
+        &mut self,
+        vm: &mut VirtualMachine,
+        obj: Gc<JsObject>,
+    ) -> Result<String, JsValue> {
+        let stepback = self.enter(vm, obj)?;
+        let len = obj.get(vm, Symbol::length())?.to_number(vm)?;
+        let len = if len.is_finite() && len > 0.0 {
+            len.trunc() as u32
+        } else {
+            0
+        };
+        let mut partial = Vec::with_capacity(len as usize);
+        for index in 0..len {
+            let value = self.serialize_property(vm, obj, Symbol::Indexed(index))?;
+            partial.push(value.unwrap_or_else(|| "null".to_owned()));
+        }
+        let result = self.join(&partial, &stepback, '[', ']');
+        self.leave(stepback);
+        Ok(result)
+    }
+}
+
+/// Keys selected by an array `replacer`: strings and numbers converted to strings, first
+/// occurrence wins.
+fn replacer_property_list(
+    vm: &mut VirtualMachine,
+    replacer: Gc<JsObject>,
+) -> Result<Vec<Symbol>, JsValue> {
+    let len = replacer.get(vm, Symbol::length())?.to_number(vm)?;
+    let len = if len.is_finite() && len > 0.0 {
+        len.trunc() as u32
+    } else {
+        0
+    };
+    let mut list = vec![];
+    for index in 0..len {
+        let item = replacer.get(vm, Symbol::Indexed(index))?;
+        if !item.is_string() && !item.is_number() {
+            continue;
+        }
+        let name = item.to_string(vm)?;
+        let key = vm.intern(name);
+        if !list.contains(&key) {
+            list.push(key);
+        }
+    }
+    Ok(list)
+}
+
+/// section 24.5.2 JSON.stringify(value [, replacer [, space]])
+pub fn json_stringify(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let replacer = args.at(1);
+    let mut stringifier = Stringifier {
+        replacer: None,
+        property_list: None,
+        gap: String::new(),
+        indent: String::new(),
+        stack: vec![],
+    };
+    if replacer.is_callable() {
+        stringifier.replacer = Some(replacer);
+    } else if replacer.is_object() && replacer.as_object().tag() == ObjectTag::Array {
+        stringifier.property_list = Some(replacer_property_list(vm, replacer.as_object())?);
+    }
+
+    let space = args.at(2);
+    if space.is_number() {
+        let n = space.number();
+        let n = if n.is_nan() { 0.0 } else { n.min(10.0) };
+        if n >= 1.0 {
+            stringifier.gap = " ".repeat(n as usize);
+        }
+    } else if space.is_string() {
+        stringifier.gap = space.as_string().as_str().chars().take(10).collect();
+    }
+
+    let structure = vm.global_data().object_structure.unwrap();
+    let mut wrapper = ctx.new_local(JsObject::new(
+        vm,
+        structure,
+        JsObject::get_class(),
+        ObjectTag::Ordinary,
+    ));
+    let empty = vm.intern("");
+    wrapper.put(vm, empty, args.at(0), false)?;
+    match stringifier.serialize_property(vm, *wrapper, empty)? {
+        Some(result) => Ok(JsValue::new(JsString::new(vm, result))),
+        None => Ok(JsValue::undefined()),
+    }
+}
+
+pub(crate) fn init_json(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().object_structure.unwrap();
+    let json = ctx.new_local(JsObject::new(
+        vm,
+        structure,
+        JsObject::get_class(),
+        ObjectTag::Ordinary,
+    ));
+    define_method(vm, *json, "stringify", json_stringify, 3);
+    let name = vm.intern("JSON");
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*json), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    fn eval_string(vm: &mut VirtualMachine, src: &str) -> String {
+        assert!(vm.eval(&format!("result = {};", src)).is_ok());
+        let global = vm.global_object();
+        let result = vm.get(global, "result").unwrap_or_else(|_| panic!());
+        result.as_string().as_str().to_owned()
+    }
+
+    #[test]
+    fn test_stringify_replacer_array() {
+        let mut vm = VirtualMachine::new(Options::default());
+        assert!(vm
+            .eval(
+                "function u() {}
+                 o = { a: 1, b: 'x', c: { b: 2, a: [1, { a: 3, z: 4 }], d: 5 }, 1: true };"
+            )
+            .is_ok());
+        assert_eq!(
+            eval_string(&mut vm, "JSON.stringify(o)"),
+            r#"{"1":true,"a":1,"b":"x","c":{"b":2,"a":[1,{"a":3,"z":4}],"d":5}}"#
+        );
+        // keys come out in replacer order, nested objects are filtered by the same list and
+        // array elements are not.
+        assert_eq!(
+            eval_string(&mut vm, "JSON.stringify(o, ['c', 'a', 'c', 1, 'missing'])"),
+            r#"{"c":{"a":[1,{"a":3}]},"a":1,"1":true}"#
+        );
+        assert_eq!(
+            eval_string(&mut vm, "JSON.stringify(o, ['b'], 2)"),
+            "{\n  \"b\": \"x\"\n}"
+        );
+        assert_eq!(
+            eval_string(&mut vm, "JSON.stringify([u(), 'a\\n'], null, '--')"),
+            "[\n--null,\n--\"a\\n\"\n]"
+        );
+        assert!(vm.eval("o.c.self = o; JSON.stringify(o);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
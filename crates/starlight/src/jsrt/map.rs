@@ -0,0 +1,493 @@
+use super::{define_alias, define_method, iter_result, return_this};
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        array::JsArray,
+        attributes::*,
+        error::JsTypeError,
+        function::{JsAPI, JsNativeFunction},
+        iterator::{group_by, iterate_or_arraylike},
+        map::{IterationKind, JsMap, JsMapIterator, JsSet},
+        object::{JsObject, ObjectTag},
+        property_descriptor::{AccessorDescriptor, DataDescriptor},
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+fn this_collection(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    tag: ObjectTag,
+    method: &str,
+) -> Result<Gc<JsObject>, JsValue> {
+    if !this.is_object() || this.as_object().tag() != tag {
+        let class = if tag == ObjectTag::Set { "Set" } else { "Map" };
+        let msg = JsString::new(
+            vm,
+            format!(
+                "Method {}.prototype.{} called on incompatible receiver",
+                class, method
+            ),
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(this.as_object())
+}
+
+fn require_new(vm: &mut VirtualMachine, args: &Arguments, class: &str) -> Result<(), JsValue> {
+    if !args.ctor_call {
+        let msg = JsString::new(vm, format!("Constructor {} requires 'new'", class));
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(())
+}
+
+/// section 23.1.1.1 Map([iterable])
+pub fn map_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    require_new(vm, args, "Map")?;
+    let ctx = vm.space().new_local_context();
+    let mut map = ctx.new_local(JsMap::new(vm));
+    let iterable = args.at(0);
+    if !iterable.is_undefined_or_null() {
+        iterate_or_arraylike(vm, iterable, &mut |vm, entry| {
+            if !entry.is_object() {
+                let msg = JsString::new(vm, "Iterator value is not an entry object");
+                return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+            }
+            let ctx = vm.space().new_local_context();
+            let entry = ctx.new_local(entry.as_object());
+            let key = ctx.new_local(entry.get(vm, Symbol::Indexed(0))?);
+            let value = entry.get(vm, Symbol::Indexed(1))?;
+            map.as_map_mut().set(*key, value);
+            Ok(())
+        })?;
+    }
+    Ok(JsValue::new(*map))
+}
+
+/// section 24.1.2.1 Map.groupBy(items, callbackfn)
+pub fn map_group_by(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut map = ctx.new_local(JsMap::new(vm));
+    group_by(vm, args.at(0), args.at(1), &mut |vm, key| {
+        // JsMap::set stores `-0` as `+0` so no key coercion is needed here.
+        if let Some(group) = map.as_map().get(key) {
+            return Ok(group.as_object());
+        }
+        let group = JsArray::new(vm, 0);
+        map.as_map_mut().set(key, JsValue::new(group));
+        Ok(group)
+    })?;
+    Ok(JsValue::new(*map))
+}
+
+/// section 23.1.3.6 Map.prototype.get(key)
+pub fn map_get(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_collection(vm, args.this, ObjectTag::Map, "get")?;
+    Ok(map
+        .as_map()
+        .get(args.at(0))
+        .unwrap_or_else(JsValue::undefined))
+}
+
+/// section 23.1.3.9 Map.prototype.set(key, value)
+pub fn map_set(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut map = this_collection(vm, args.this, ObjectTag::Map, "set")?;
+    map.as_map_mut().set(args.at(0), args.at(1));
+    Ok(args.this)
+}
+
+/// section 23.1.3.7 Map.prototype.has(key)
+pub fn map_has(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_collection(vm, args.this, ObjectTag::Map, "has")?;
+    Ok(JsValue::new(map.as_map().has(args.at(0))))
+}
+
+/// section 23.1.3.3 Map.prototype.delete(key)
+pub fn map_delete(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut map = this_collection(vm, args.this, ObjectTag::Map, "delete")?;
+    Ok(JsValue::new(map.as_map_mut().delete(args.at(0))))
+}
+
+/// section 23.1.3.1 Map.prototype.clear()
+pub fn map_clear(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut map = this_collection(vm, args.this, ObjectTag::Map, "clear")?;
+    map.as_map_mut().clear();
+    Ok(JsValue::undefined())
+}
+
+/// section 23.1.3.10 get Map.prototype.size
+pub fn map_size(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_collection(vm, args.this, ObjectTag::Map, "size")?;
+    Ok(JsValue::from(map.as_map().size() as u32))
+}
+
+/// Calls `callback` for every entry of `collection`, entries added by the callback are
+/// visited and deleted ones are skipped.
+fn for_each(
+    vm: &mut VirtualMachine,
+    collection: Gc<JsObject>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let callback = args.at(0);
+    if !callback.is_callable() {
+        let msg = JsString::new(vm, "forEach callback is not a function");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let collection = ctx.new_local(collection);
+    let mut position = 0;
+    while let Some((ix, key, value)) = collection.as_map().next_entry(position) {
+        position = ix + 1;
+        let mut cargs = ctx.new_local(Arguments::new(vm, args.at(1), 3));
+        cargs[0] = value;
+        cargs[1] = key;
+        cargs[2] = JsValue::new(*collection);
+        callback
+            .as_object()
+            .as_function_mut()
+            .call(vm, &mut cargs)?;
+    }
+    Ok(JsValue::undefined())
+}
+
+/// section 23.1.3.5 Map.prototype.forEach(callbackfn [, thisArg])
+pub fn map_for_each(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_collection(vm, args.this, ObjectTag::Map, "forEach")?;
+    for_each(vm, map, args)
+}
+
+/// section 23.1.3.4 Map.prototype.entries()
+pub fn map_entries(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_collection(vm, args.this, ObjectTag::Map, "entries")?;
+    Ok(JsValue::new(JsMapIterator::new(
+        vm,
+        map,
+        IterationKind::KeyValue,
+    )))
+}
+
+/// section 23.1.3.8 Map.prototype.keys()
+pub fn map_keys(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_collection(vm, args.this, ObjectTag::Map, "keys")?;
+    Ok(JsValue::new(JsMapIterator::new(
+        vm,
+        map,
+        IterationKind::Key,
+    )))
+}
+
+/// section 23.1.3.11 Map.prototype.values()
+pub fn map_values(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_collection(vm, args.this, ObjectTag::Map, "values")?;
+    Ok(JsValue::new(JsMapIterator::new(
+        vm,
+        map,
+        IterationKind::Value,
+    )))
+}
+
+/// section 23.2.1.1 Set([iterable])
+pub fn set_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    require_new(vm, args, "Set")?;
+    let ctx = vm.space().new_local_context();
+    let mut set = ctx.new_local(JsSet::new(vm));
+    let iterable = args.at(0);
+    if !iterable.is_undefined_or_null() {
+        iterate_or_arraylike(vm, iterable, &mut |_vm, value| {
+            set.as_map_mut().set(value, value);
+            Ok(())
+        })?;
+    }
+    Ok(JsValue::new(*set))
+}
+
+/// section 23.2.3.1 Set.prototype.add(value)
+pub fn set_add(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut set = this_collection(vm, args.this, ObjectTag::Set, "add")?;
+    set.as_map_mut().set(args.at(0), args.at(0));
+    Ok(args.this)
+}
+
+/// section 23.2.3.7 Set.prototype.has(value)
+pub fn set_has(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let set = this_collection(vm, args.this, ObjectTag::Set, "has")?;
+    Ok(JsValue::new(set.as_map().has(args.at(0))))
+}
+
+/// section 23.2.3.4 Set.prototype.delete(value)
+pub fn set_delete(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut set = this_collection(vm, args.this, ObjectTag::Set, "delete")?;
+    Ok(JsValue::new(set.as_map_mut().delete(args.at(0))))
+}
+
+/// section 23.2.3.2 Set.prototype.clear()
+pub fn set_clear(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut set = this_collection(vm, args.this, ObjectTag::Set, "clear")?;
+    set.as_map_mut().clear();
+    Ok(JsValue::undefined())
+}
+
+/// section 23.2.3.9 get Set.prototype.size
+pub fn set_size(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let set = this_collection(vm, args.this, ObjectTag::Set, "size")?;
+    Ok(JsValue::from(set.as_map().size() as u32))
+}
+
+/// section 23.2.3.6 Set.prototype.forEach(callbackfn [, thisArg])
+pub fn set_for_each(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let set = this_collection(vm, args.this, ObjectTag::Set, "forEach")?;
+    for_each(vm, set, args)
+}
+
+/// section 23.2.3.5 Set.prototype.entries()
+pub fn set_entries(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let set = this_collection(vm, args.this, ObjectTag::Set, "entries")?;
+    Ok(JsValue::new(JsMapIterator::new(
+        vm,
+        set,
+        IterationKind::KeyValue,
+    )))
+}
+
+/// section 23.2.3.10 Set.prototype.values()
+pub fn set_values(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let set = this_collection(vm, args.this, ObjectTag::Set, "values")?;
+    Ok(JsValue::new(JsMapIterator::new(
+        vm,
+        set,
+        IterationKind::Value,
+    )))
+}
+
+/// section 23.1.5.2.1 %MapIteratorPrototype%.next() and 23.2.5.2.1 %SetIteratorPrototype%.next()
+pub fn map_iterator_next(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    if !this.is_object()
+        || !matches!(
+            this.as_object().tag(),
+            ObjectTag::MapIterator | ObjectTag::SetIterator
+        )
+    {
+        let msg = JsString::new(vm, "next method called on incompatible receiver");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let mut iter = ctx.new_local(this.as_object());
+    if iter.as_map_iterator().done {
+        return iter_result(vm, JsValue::undefined(), true);
+    }
+    let map = iter.as_map_iterator().map;
+    let (ix, key, value) = match map.as_map().next_entry(iter.as_map_iterator().position) {
+        Some(entry) => entry,
+        None => {
+            iter.as_map_iterator_mut().done = true;
+            return iter_result(vm, JsValue::undefined(), true);
+        }
+    };
+    iter.as_map_iterator_mut().position = ix + 1;
+    match iter.as_map_iterator().kind {
+        IterationKind::Key => iter_result(vm, key, false),
+        IterationKind::Value => iter_result(vm, value, false),
+        IterationKind::KeyValue => {
+            let entry = vm.new_array(&ctx, &[key, value]);
+            iter_result(vm, JsValue::new(*entry), false)
+        }
+    }
+}
+
+fn define_size_getter(vm: &mut VirtualMachine, mut proto: Gc<JsObject>, f: JsAPI) {
+    let name = vm.intern("size");
+    let getter = JsNativeFunction::new(vm, name, f, 0);
+    let _ = proto.define_own_property(
+        vm,
+        name,
+        &*AccessorDescriptor::new(JsValue::new(getter), JsValue::undefined(), C),
+        false,
+    );
+}
+
+fn init_collection(
+    vm: &mut VirtualMachine,
+    name: &str,
+    constructor: JsAPI,
+    mut proto: Gc<JsObject>,
+) -> Gc<JsObject> {
+    let ctx = vm.space().new_local_context();
+    let name = vm.intern(name);
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(vm, name, constructor, 0));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    *ctor
+}
+
+fn init_iterator_prototype(vm: &mut VirtualMachine, mut proto: Gc<JsObject>) {
+    define_method(vm, proto, "next", map_iterator_next, 0);
+    let iterator = JsNativeFunction::new(vm, Symbol::iterator(), return_this, 0);
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::iterator(),
+        &*DataDescriptor::new(JsValue::new(iterator), W | C),
+        false,
+    );
+}
+
+pub(crate) fn init_map(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().map_structure.unwrap();
+    let proto = ctx.new_local(structure.prototype().unwrap());
+    let ctor = init_collection(vm, "Map", map_constructor, *proto);
+    define_method(vm, ctor, "groupBy", map_group_by, 2);
+    define_method(vm, *proto, "get", map_get, 1);
+    define_method(vm, *proto, "set", map_set, 2);
+    define_method(vm, *proto, "has", map_has, 1);
+    define_method(vm, *proto, "delete", map_delete, 1);
+    define_method(vm, *proto, "clear", map_clear, 0);
+    define_method(vm, *proto, "forEach", map_for_each, 1);
+    define_method(vm, *proto, "entries", map_entries, 0);
+    define_method(vm, *proto, "keys", map_keys, 0);
+    define_method(vm, *proto, "values", map_values, 0);
+    define_size_getter(vm, *proto, map_size);
+    define_alias(vm, *proto, Symbol::iterator(), "entries");
+
+    let structure = vm.global_data().set_structure.unwrap();
+    let proto = ctx.new_local(structure.prototype().unwrap());
+    init_collection(vm, "Set", set_constructor, *proto);
+    define_method(vm, *proto, "add", set_add, 1);
+    define_method(vm, *proto, "has", set_has, 1);
+    define_method(vm, *proto, "delete", set_delete, 1);
+    define_method(vm, *proto, "clear", set_clear, 0);
+    define_method(vm, *proto, "forEach", set_for_each, 1);
+    define_method(vm, *proto, "entries", set_entries, 0);
+    define_method(vm, *proto, "values", set_values, 0);
+    define_size_getter(vm, *proto, set_size);
+    let keys = vm.intern("keys");
+    define_alias(vm, *proto, keys, "values");
+    define_alias(vm, *proto, Symbol::iterator(), "values");
+
+    let structure = vm.global_data().map_iterator_structure.unwrap();
+    init_iterator_prototype(vm, structure.prototype().unwrap());
+    let structure = vm.global_data().set_iterator_structure.unwrap();
+    init_iterator_prototype(vm, structure.prototype().unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_mutation_during_iteration() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "m = new Map([[1, 'a'], [2, 'b'], [3, 'c']]);
+             seen = '';
+             function visit(value, key, map) {
+                 seen = seen + key + value + ',';
+                 if (key === 1) {
+                     map.delete(2);
+                     map.set(4, 'd');
+                 }
+             }
+             m.forEach(visit);
+             it = m.entries();
+             first = it.next().value;
+             m.delete(3);
+             m.set(5, 'e');
+             second = it.next().value;
+             third = it.next().value;
+             last = it.next();
+             keys = [...m.keys()];
+             s = new Set([1, 2, 2, 3]);
+             s.delete(1);
+             s.add(1);
+             values = [...s];
+             size = s.size;
+             keysAlias = Set.prototype.keys === Set.prototype.values;
+             iteratorAlias = Map.prototype[Symbol.iterator] === Map.prototype.entries;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get =
+            |vm: &mut VirtualMachine, name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        let join = |vm: &mut VirtualMachine, name: &str, len: u32| {
+            let val = get(vm, name);
+            let mut out = vec![];
+            for i in 0..len {
+                let elem = vm.get(val.as_object(), i).unwrap_or_else(|_| panic!());
+                out.push(elem.to_string(vm).unwrap_or_else(|_| panic!()));
+            }
+            out.join(",")
+        };
+        let seen = get(&mut vm, "seen");
+        assert_eq!(seen.as_string().as_str(), "1a,3c,4d,");
+        assert_eq!(join(&mut vm, "first", 2), "1,a");
+        assert_eq!(join(&mut vm, "second", 2), "4,d");
+        assert_eq!(join(&mut vm, "third", 2), "5,e");
+        let last = get(&mut vm, "last").as_object();
+        assert!(vm
+            .get(last, "done")
+            .unwrap_or_else(|_| panic!())
+            .to_boolean());
+        assert_eq!(join(&mut vm, "keys", 3), "1,4,5");
+        assert_eq!(join(&mut vm, "values", 3), "2,3,1");
+        assert_eq!(get(&mut vm, "size").as_int32(), 3);
+        assert!(get(&mut vm, "keysAlias").to_boolean());
+        assert!(get(&mut vm, "iteratorAlias").to_boolean());
+
+        assert!(vm.eval("Map();").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = { name: 'a' };
+             b = { name: 'b' };
+             items = [{ k: b, v: 1 }, { k: a, v: 2 }, { k: b, v: 3 }];
+             function owner(item) { return item.k; }
+             m = Map.groupBy(items, owner);
+             keys = [...m.keys()];
+             firstIsB = keys[0] === b;
+             ga = m.get(a);
+             gb = m.get(b);
+             second = gb[1].v;
+             size = m.size;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get =
+            |vm: &mut VirtualMachine, name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert!(get(&mut vm, "firstIsB").to_boolean());
+        assert_eq!(get(&mut vm, "size").as_int32(), 2);
+        assert_eq!(get(&mut vm, "second").as_int32(), 3);
+        for (name, len) in &[("ga", 1), ("gb", 2)] {
+            let group = get(&mut vm, name).as_object();
+            let length = vm.get(group, "length").unwrap_or_else(|_| panic!());
+            assert_eq!(length.as_int32(), *len);
+        }
+
+        assert!(vm.eval("Map.groupBy(null, owner);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
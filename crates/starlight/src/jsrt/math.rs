@@ -0,0 +1,291 @@
+use super::define_method;
+use crate::{
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        object::{JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// Defines `Math.$name(x)` as `$f` applied to ToNumber(x).
+macro_rules! math_unary {
+    ($($name: ident => $f: expr),*) => {
+        $(
+            pub fn $name(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+                let x = args.at(0).to_number(vm)?;
+                let f: fn(f64) -> f64 = $f;
+                Ok(JsValue::new_number(f(x)))
+            }
+        )*
+    };
+}
+
+math_unary! {
+    math_abs => f64::abs,
+    math_acos => f64::acos,
+    math_acosh => f64::acosh,
+    math_asin => f64::asin,
+    math_asinh => |x| if x == 0.0 { x } else { x.asinh() },
+    math_atan => f64::atan,
+    math_atanh => |x| if x == 0.0 { x } else { x.atanh() },
+    math_cbrt => f64::cbrt,
+    math_ceil => f64::ceil,
+    math_cos => f64::cos,
+    math_cosh => f64::cosh,
+    math_exp => f64::exp,
+    math_expm1 => |x| if x == 0.0 { x } else { x.exp_m1() },
+    math_floor => f64::floor,
+    math_fround => |x| x as f32 as f64,
+    math_log => f64::ln,
+    math_log1p => |x| if x == 0.0 { x } else { x.ln_1p() },
+    math_log10 => f64::log10,
+    math_log2 => f64::log2,
+    math_sin => f64::sin,
+    math_sinh => |x| if x == 0.0 { x } else { x.sinh() },
+    math_sqrt => f64::sqrt,
+    math_tan => f64::tan,
+    math_tanh => |x| if x == 0.0 { x } else { x.tanh() },
+    math_trunc => f64::trunc,
+    // `Math.round(-2.5)` is `-2`, halves round towards `+Infinity` and `-0.5 <= x < 0` gives `-0`.
+    math_round => |x| {
+        if !x.is_finite() || x == 0.0 {
+            x
+        } else if x < 0.0 && x >= -0.5 {
+            -0.0
+        } else {
+            let r = x.floor();
+            if x - r >= 0.5 {
+                r + 1.0
+            } else {
+                r
+            }
+        }
+    },
+    math_sign => |x| if x.is_nan() || x == 0.0 { x } else { x.signum() }
+}
+
+/// section 20.2.2.8 Math.atan2(y, x)
+pub fn math_atan2(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let y = args.at(0).to_number(vm)?;
+    let x = args.at(1).to_number(vm)?;
+    Ok(JsValue::new_number(y.atan2(x)))
+}
+
+/// section 20.2.2.11 Math.clz32(x)
+pub fn math_clz32(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let n = args.at(0).to_uint32(vm)?;
+    Ok(JsValue::new(n.leading_zeros() as i32))
+}
+
+/// section 20.2.2.18 Math.hypot(...args)
+pub fn math_hypot(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut values = Vec::with_capacity(args.size());
+    for ix in 0..args.size() {
+        values.push(args[ix].to_number(vm)?.abs());
+    }
+    // every argument is coerced first, and an infinity wins over NaN.
+    if values.iter().any(|x| x.is_infinite()) {
+        return Ok(JsValue::new_double(f64::INFINITY));
+    }
+    if values.iter().any(|x| x.is_nan()) {
+        return Ok(JsValue::new_double(f64::NAN));
+    }
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    if max == 0.0 {
+        return Ok(JsValue::new(0));
+    }
+    // scale by the largest value to avoid overflow of the squares.
+    let sum: f64 = values.iter().map(|x| (x / max) * (x / max)).sum();
+    Ok(JsValue::new_number(sum.sqrt() * max))
+}
+
+/// section 20.2.2.19 Math.imul(x, y)
+pub fn math_imul(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let a = args.at(0).to_uint32(vm)?;
+    let b = args.at(1).to_uint32(vm)?;
+    Ok(JsValue::new(a.wrapping_mul(b) as i32))
+}
+
+fn min_max(vm: &mut VirtualMachine, args: &Arguments, max: bool) -> Result<JsValue, JsValue> {
+    let mut result = if max {
+        f64::NEG_INFINITY
+    } else {
+        f64::INFINITY
+    };
+    for ix in 0..args.size() {
+        let n = args[ix].to_number(vm)?;
+        if result.is_nan() {
+            continue;
+        }
+        // `+0` is larger than `-0` here, which `f64` comparison does not tell apart.
+        let take = if n.is_nan() {
+            true
+        } else if n == 0.0 && result == 0.0 {
+            n.is_sign_negative() != max
+        } else if max {
+            n > result
+        } else {
+            n < result
+        };
+        if take {
+            result = n;
+        }
+    }
+    Ok(JsValue::new_number(result))
+}
+
+/// section 20.2.2.24 Math.max(...args)
+pub fn math_max(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    min_max(vm, args, true)
+}
+
+/// section 20.2.2.25 Math.min(...args)
+pub fn math_min(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    min_max(vm, args, false)
+}
+
+/// section 20.2.2.26 Math.pow(base, exponent)
+pub fn math_pow(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let base = args.at(0).to_number(vm)?;
+    let exponent = args.at(1).to_number(vm)?;
+    // unlike `powf`, `1 ** NaN` and `(-1) ** Infinity` are NaN.
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return Ok(JsValue::new_double(f64::NAN));
+    }
+    Ok(JsValue::new_number(base.powf(exponent)))
+}
+
+pub(crate) fn init_math(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().object_structure.unwrap();
+    let mut math = ctx.new_local(JsObject::new(
+        vm,
+        structure,
+        JsObject::get_class(),
+        ObjectTag::Ordinary,
+    ));
+    let constants = [
+        ("E", std::f64::consts::E),
+        ("LN10", std::f64::consts::LN_10),
+        ("LN2", std::f64::consts::LN_2),
+        ("LOG10E", std::f64::consts::LOG10_E),
+        ("LOG2E", std::f64::consts::LOG2_E),
+        ("PI", std::f64::consts::PI),
+        ("SQRT1_2", std::f64::consts::FRAC_1_SQRT_2),
+        ("SQRT2", std::f64::consts::SQRT_2),
+    ];
+    for (name, value) in constants.iter() {
+        let name = vm.intern(name);
+        let _ = math.define_own_property(
+            vm,
+            name,
+            &*DataDescriptor::new(JsValue::new_double(*value), NONE),
+            false,
+        );
+    }
+
+    define_method(vm, *math, "abs", math_abs, 1);
+    define_method(vm, *math, "acos", math_acos, 1);
+    define_method(vm, *math, "acosh", math_acosh, 1);
+    define_method(vm, *math, "asin", math_asin, 1);
+    define_method(vm, *math, "asinh", math_asinh, 1);
+    define_method(vm, *math, "atan", math_atan, 1);
+    define_method(vm, *math, "atanh", math_atanh, 1);
+    define_method(vm, *math, "atan2", math_atan2, 2);
+    define_method(vm, *math, "cbrt", math_cbrt, 1);
+    define_method(vm, *math, "ceil", math_ceil, 1);
+    define_method(vm, *math, "clz32", math_clz32, 1);
+    define_method(vm, *math, "cos", math_cos, 1);
+    define_method(vm, *math, "cosh", math_cosh, 1);
+    define_method(vm, *math, "exp", math_exp, 1);
+    define_method(vm, *math, "expm1", math_expm1, 1);
+    define_method(vm, *math, "floor", math_floor, 1);
+    define_method(vm, *math, "fround", math_fround, 1);
+    define_method(vm, *math, "hypot", math_hypot, 2);
+    define_method(vm, *math, "imul", math_imul, 2);
+    define_method(vm, *math, "log", math_log, 1);
+    define_method(vm, *math, "log1p", math_log1p, 1);
+    define_method(vm, *math, "log10", math_log10, 1);
+    define_method(vm, *math, "log2", math_log2, 1);
+    define_method(vm, *math, "max", math_max, 2);
+    define_method(vm, *math, "min", math_min, 2);
+    define_method(vm, *math, "pow", math_pow, 2);
+    define_method(vm, *math, "round", math_round, 1);
+    define_method(vm, *math, "sign", math_sign, 1);
+    define_method(vm, *math, "sin", math_sin, 1);
+    define_method(vm, *math, "sinh", math_sinh, 1);
+    define_method(vm, *math, "sqrt", math_sqrt, 1);
+    define_method(vm, *math, "tan", math_tan, 1);
+    define_method(vm, *math, "tanh", math_tanh, 1);
+    define_method(vm, *math, "trunc", math_trunc, 1);
+
+    let name = vm.intern("Math");
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*math), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    fn eval_number(vm: &mut VirtualMachine, src: &str) -> f64 {
+        assert!(vm.eval(&format!("result = {};", src)).is_ok());
+        let global = vm.global_object();
+        vm.get(global, "result")
+            .unwrap_or_else(|_| panic!())
+            .number()
+    }
+
+    #[test]
+    fn test_math_edge_cases() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let pi = std::f64::consts::PI;
+        // there are no unary minus or `NaN` globals yet, `nz` is `-0`.
+        assert!(vm
+            .eval("nz = Math.round(0 - 0.25); nan = 0 / 0; inf = 1 / 0;")
+            .is_ok());
+        assert!(eval_number(&mut vm, "nz").is_sign_negative());
+
+        // atan2 keeps the quadrant of (x, y) and the sign of zeros.
+        assert_eq!(eval_number(&mut vm, "Math.atan2(1, 1)"), pi / 4.0);
+        assert_eq!(eval_number(&mut vm, "Math.atan2(1, 0 - 1)"), 3.0 * pi / 4.0);
+        assert_eq!(
+            eval_number(&mut vm, "Math.atan2(0 - 1, 0 - 1)"),
+            -3.0 * pi / 4.0
+        );
+        assert_eq!(eval_number(&mut vm, "Math.atan2(0 - 1, 1)"), -pi / 4.0);
+        assert_eq!(eval_number(&mut vm, "Math.atan2(0, nz)"), pi);
+        assert!(eval_number(&mut vm, "Math.atan2(nz, 1)").is_sign_negative());
+
+        assert_eq!(eval_number(&mut vm, "Math.clz32(0)"), 32.0);
+        assert_eq!(eval_number(&mut vm, "Math.clz32(1)"), 31.0);
+        assert_eq!(eval_number(&mut vm, "Math.clz32(0 - 1)"), 0.0);
+        assert_eq!(eval_number(&mut vm, "Math.clz32(2147483648)"), 0.0);
+        assert_eq!(eval_number(&mut vm, "Math.clz32(4294967296)"), 32.0);
+        assert_eq!(eval_number(&mut vm, "Math.clz32(0.5)"), 32.0);
+        assert_eq!(eval_number(&mut vm, "Math.clz32(nan)"), 32.0);
+
+        assert_eq!(eval_number(&mut vm, "Math.imul(2, 4)"), 8.0);
+        assert_eq!(eval_number(&mut vm, "Math.imul(0xffffffff, 5)"), -5.0);
+        assert_eq!(eval_number(&mut vm, "Math.imul(0x7fffffff, 2)"), -2.0);
+        assert_eq!(eval_number(&mut vm, "Math.imul(65536, 65536)"), 0.0);
+
+        assert_eq!(eval_number(&mut vm, "Math.fround(5.5)"), 5.5);
+        assert_eq!(eval_number(&mut vm, "Math.fround(5.05)"), 5.050000190734863);
+        assert_eq!(eval_number(&mut vm, "Math.cbrt(0 - 27)"), -3.0);
+        assert_eq!(eval_number(&mut vm, "Math.hypot(3, 4)"), 5.0);
+        assert_eq!(eval_number(&mut vm, "Math.hypot(nan, inf)"), f64::INFINITY);
+        assert_eq!(eval_number(&mut vm, "Math.hypot()"), 0.0);
+        assert_eq!(eval_number(&mut vm, "Math.round(0 - 2.5)"), -2.0);
+        assert_eq!(eval_number(&mut vm, "Math.max()"), f64::NEG_INFINITY);
+        assert!(eval_number(&mut vm, "Math.min(0, nz)").is_sign_negative());
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{
+    runtime::{arguments::Arguments, error::JsTypeError, string::JsString, value::JsValue},
+    vm::VirtualMachine,
+};
+
+/// `queueMicrotask(callback)`, `callback` runs at the next microtask checkpoint.
+pub fn queue_microtask(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let callback = args.at(0);
+    if !callback.is_callable() {
+        let msg = JsString::new(vm, "queueMicrotask: callback is not a function");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    vm.enqueue_microtask(callback);
+    Ok(JsValue::undefined())
+}
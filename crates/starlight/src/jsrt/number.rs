@@ -0,0 +1,312 @@
+use super::define_method;
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        error::{JsRangeError, JsTypeError},
+        function::JsNativeFunction,
+        number::{number_to_radix_string, number_to_string},
+        number_format::NumberFormat,
+        object::JsObject,
+        property_descriptor::DataDescriptor,
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// section 15.7.1.1 Number([value])
+pub fn number_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.size() == 0 {
+        return Ok(JsValue::new(0));
+    }
+    Ok(JsValue::new_number(args[0].to_number(vm)?))
+}
+
+fn this_number_value(vm: &mut VirtualMachine, this: JsValue, name: &str) -> Result<f64, JsValue> {
+    if !this.is_number() {
+        let msg = JsString::new(
+            vm,
+            format!("Number.prototype.{} requires that 'this' be a Number", name),
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(this.number())
+}
+
+/// GetNumberOption from ECMA-402, `None` when the option is absent.
+fn digits_option(
+    vm: &mut VirtualMachine,
+    options: Option<Gc<JsObject>>,
+    name: &str,
+    min: usize,
+    max: usize,
+) -> Result<Option<usize>, JsValue> {
+    let options = match options {
+        Some(options) => options,
+        None => return Ok(None),
+    };
+    let key = vm.intern(name);
+    let val = options.get(vm, key)?;
+    if val.is_undefined() {
+        return Ok(None);
+    }
+    let n = val.to_number(vm)?;
+    if n.is_nan() || n < min as f64 || n > max as f64 {
+        let msg = JsString::new(vm, format!("{} value is out of range.", name));
+        return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+    }
+    Ok(Some(n.floor() as usize))
+}
+
+/// section 15.7.4.2 Number.prototype.toString([radix])
+pub fn number_to_string_method(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let x = this_number_value(vm, args.this, "toString")?;
+    let radix = if args.at(0).is_undefined() {
+        10.0
+    } else {
+        args.at(0).to_number(vm)?.trunc()
+    };
+    if !(2.0..=36.0).contains(&radix) {
+        let msg = JsString::new(vm, "toString() radix must be between 2 and 36");
+        return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+    }
+    let s = if radix == 10.0 {
+        number_to_string(x)
+    } else {
+        number_to_radix_string(x, radix as u32)
+    };
+    Ok(JsValue::new(JsString::new(vm, s)))
+}
+
+/// StrWhiteSpaceChar, white space and line terminators.
+fn is_str_whitespace(c: char) -> bool {
+    (c.is_whitespace() && c != '\u{85}') || c == '\u{feff}'
+}
+
+/// section 15.1.2.2 parseInt(string, radix)
+pub fn parse_int(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let input = args.at(0).to_string(vm)?;
+    let radix = args.at(1).to_int32(vm)?;
+    let mut s = input.trim_start_matches(is_str_whitespace);
+    let negative = s.starts_with('-');
+    if s.starts_with('-') || s.starts_with('+') {
+        s = &s[1..];
+    }
+    // radix 0 is the same as no radix at all, the prefix decides.
+    let mut strip_prefix = true;
+    let mut radix = match radix {
+        0 => 10,
+        2..=36 => {
+            strip_prefix = radix == 16;
+            radix as u32
+        }
+        _ => return Ok(JsValue::new_number(f64::NAN)),
+    };
+    if strip_prefix && (s.starts_with("0x") || s.starts_with("0X")) {
+        s = &s[2..];
+        radix = 16;
+    }
+    let end = s.find(|c: char| !c.is_digit(radix)).unwrap_or(s.len());
+    let digits = &s[..end];
+    if digits.is_empty() {
+        return Ok(JsValue::new_number(f64::NAN));
+    }
+    let value = if radix == 10 {
+        // correctly rounded, even past 20 significant digits.
+        digits.parse::<f64>().unwrap()
+    } else {
+        digits.chars().fold(0.0, |acc, c| {
+            acc * radix as f64 + c.to_digit(radix).unwrap() as f64
+        })
+    };
+    Ok(JsValue::new_number(if negative { -value } else { value }))
+}
+
+/// section 15.7.4.3 Number.prototype.toLocaleString([locales [, options]])
+///
+/// Only grouping and fraction digit options are understood, see `runtime::number_format`.
+pub fn number_to_locale_string(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let x = this_number_value(vm, args.this, "toLocaleString")?;
+    let locale = if args.at(0).is_undefined() {
+        "en-US".to_owned()
+    } else {
+        args.at(0).to_string(vm)?
+    };
+    let mut format = NumberFormat::new(&locale);
+
+    let ctx = vm.space().new_local_context();
+    let options = match args.at(1) {
+        val if val.is_undefined() => None,
+        val if val.is_object() => Some(*ctx.new_local(val.as_object())),
+        val if val.is_null() => {
+            let msg = JsString::new(vm, "Cannot convert null to object");
+            return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+        }
+        // Primitives have no own options, everything comes from the defaults.
+        _ => None,
+    };
+    if let Some(options) = options {
+        let key = vm.intern("useGrouping");
+        let grouping = options.get(vm, key)?;
+        if !grouping.is_undefined() {
+            format.use_grouping = grouping.to_boolean();
+        }
+    }
+    let min = digits_option(vm, options, "minimumFractionDigits", 0, 20)?;
+    let max = digits_option(vm, options, "maximumFractionDigits", 0, 20)?;
+    let min = min.unwrap_or(0);
+    let max = match max {
+        Some(max) if max < min => {
+            let msg = JsString::new(vm, "maximumFractionDigits value is out of range.");
+            return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+        }
+        Some(max) => max,
+        None => min.max(format.maximum_fraction_digits),
+    };
+    format.minimum_fraction_digits = min;
+    format.maximum_fraction_digits = max;
+    Ok(JsValue::new(JsString::new(vm, format.format(x))))
+}
+
+pub(crate) fn init_number(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let mut proto = ctx.new_local(vm.global_data().number_prototype.unwrap());
+    let name = vm.intern("Number");
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        number_constructor,
+        1,
+    ));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(*proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    define_method(vm, *proto, "toString", number_to_string_method, 1);
+    define_method(vm, *proto, "toLocaleString", number_to_locale_string, 0);
+
+    let mut global = ctx.new_local(vm.global_object());
+    let _ = global.define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    // Number.parseInt is the same function object as the global parseInt.
+    let name = vm.intern("parseInt");
+    let func = ctx.new_local(JsNativeFunction::new(vm, name, parse_int, 2));
+    for mut target in vec![*global, *ctor] {
+        let _ = target.define_own_property(
+            vm,
+            name,
+            &*DataDescriptor::new(JsValue::new(*func), W | C),
+            false,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    fn string(vm: &mut VirtualMachine, name: &str) -> String {
+        let global = vm.global_object();
+        let val = vm.get(global, name).unwrap_or_else(|_| panic!());
+        val.as_string().as_str().to_owned()
+    }
+
+    #[test]
+    fn test_to_locale_string() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = (1234567).toLocaleString();
+             b = (1.005).toLocaleString('en-US', { maximumFractionDigits: 2 });
+             c = (5).toLocaleString('en-US', { minimumFractionDigits: 2 });
+             d = (1234567.891).toLocaleString('de-DE');
+             e = (1234567).toLocaleString('en-US', { useGrouping: false });",
+        );
+        assert!(res.is_ok());
+        assert_eq!(string(&mut vm, "a"), "1,234,567");
+        assert_eq!(string(&mut vm, "b"), "1.01");
+        assert_eq!(string(&mut vm, "c"), "5.00");
+        assert_eq!(string(&mut vm, "d"), "1.234.567,891");
+        assert_eq!(string(&mut vm, "e"), "1234567");
+
+        assert!(vm
+            .eval("(1).toLocaleString('en', { minimumFractionDigits: 21 });")
+            .is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_to_string() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = (0.1).toString();
+             b = (1e21).toString();
+             c = (0 / (0 - 1)).toString();
+             d = (1.5e300).toString();
+             e = (1e-7).toString();
+             f = '' + 123e-20;
+             g = (255).toString(16);
+             h = [0.5, 1 / 0, 0 - 1 / 0, 0 / 0, 123456789012345680000].join();",
+        );
+        assert!(res.is_ok());
+        assert_eq!(string(&mut vm, "a"), "0.1");
+        assert_eq!(string(&mut vm, "b"), "1e+21");
+        assert_eq!(string(&mut vm, "c"), "0");
+        assert_eq!(string(&mut vm, "d"), "1.5e+300");
+        assert_eq!(string(&mut vm, "e"), "1e-7");
+        assert_eq!(string(&mut vm, "f"), "1.23e-18");
+        assert_eq!(string(&mut vm, "g"), "ff");
+        assert_eq!(
+            string(&mut vm, "h"),
+            "0.5,Infinity,-Infinity,NaN,123456789012345680000"
+        );
+
+        assert!(vm.eval("(1).toString(37);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_radix() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = [(0.5).toString(2), (255.5).toString(16), (0 - 2.25).toString(2)].join();
+             b = [parseInt('ff', 16), parseInt('0xff', 16), parseInt('0xff'),
+                  parseInt('0x1f', 0), parseInt('  -0x10'), parseInt('+12px'),
+                  parseInt('0x10', 10), parseInt('0x10', 8), parseInt('11', 2),
+                  parseInt('z', 36), parseInt('-17', 0)].join();
+             c = [parseInt('1', 1), parseInt('1', 37), parseInt('1', 0 - 1), parseInt(''),
+                  parseInt('-'), parseInt('0x'), parseInt('2', 2)].join();
+             d = '' + 1 / parseInt('-0');
+             e = Number.parseInt === parseInt;",
+        );
+        assert!(res.is_ok());
+        assert_eq!(string(&mut vm, "a"), "0.1,ff.8,-10.01");
+        assert_eq!(string(&mut vm, "b"), "255,255,255,31,-16,12,0,0,3,35,-17");
+        assert_eq!(string(&mut vm, "c"), "NaN,NaN,NaN,NaN,NaN,NaN,NaN");
+        assert_eq!(string(&mut vm, "d"), "-Infinity");
+        let global = vm.global_object();
+        let e = vm.get(global, "e").unwrap_or_else(|_| panic!());
+        assert!(e.to_boolean());
+        VirtualMachineRef::dispose(vm);
+    }
+}
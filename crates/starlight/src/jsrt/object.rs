@@ -0,0 +1,798 @@
+use super::define_method;
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        array::JsArray,
+        attributes::*,
+        error::JsTypeError,
+        function::JsNativeFunction,
+        iterator::{group_by, iterate_or_arraylike},
+        object::{JsObject, ObjectTag},
+        property_descriptor::{DataDescriptor, PropertyDescriptor},
+        slot::Slot,
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+fn object_argument(vm: &mut VirtualMachine, val: JsValue) -> Result<Gc<JsObject>, JsValue> {
+    if !val.is_object() {
+        let msg = JsString::new(vm, "Object.* called on non-object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(val.as_object())
+}
+
+fn new_object(vm: &mut VirtualMachine) -> Gc<JsObject> {
+    let structure = vm.global_data().object_structure.unwrap();
+    JsObject::new(vm, structure, JsObject::get_class(), ObjectTag::Ordinary)
+}
+
+/// section 8.10.4 FromPropertyDescriptor
+pub(crate) fn from_property_descriptor(
+    vm: &mut VirtualMachine,
+    desc: &PropertyDescriptor,
+) -> Result<Gc<JsObject>, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(new_object(vm));
+    if desc.is_accessor() {
+        obj.put(vm, Symbol::get(), desc.getter(), true)?;
+        obj.put(vm, Symbol::set(), desc.setter(), true)?;
+    } else {
+        obj.put(vm, Symbol::value(), desc.value(), true)?;
+        obj.put(
+            vm,
+            Symbol::writable(),
+            JsValue::new(desc.is_writable()),
+            true,
+        )?;
+    }
+    obj.put(
+        vm,
+        Symbol::enumerable(),
+        JsValue::new(desc.is_enumerable()),
+        true,
+    )?;
+    obj.put(
+        vm,
+        Symbol::configurable(),
+        JsValue::new(desc.is_configurable()),
+        true,
+    )?;
+    Ok(*obj)
+}
+
+/// section 8.10.5 ToPropertyDescriptor
+pub(crate) fn to_property_descriptor(
+    vm: &mut VirtualMachine,
+    val: JsValue,
+) -> Result<PropertyDescriptor, JsValue> {
+    if !val.is_object() {
+        let msg = JsString::new(vm, "Property description must be an object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(val.as_object());
+    let mut attrs = NONE;
+    macro_rules! flag {
+        ($name: ident, $set: expr, $undef: expr) => {
+            if obj.has_property(vm, Symbol::$name()) {
+                if obj.get(vm, Symbol::$name())?.to_boolean() {
+                    attrs |= $set;
+                }
+            } else {
+                attrs |= $undef;
+            }
+        };
+    }
+    flag!(enumerable, E, UNDEF_ENUMERABLE);
+    flag!(configurable, C, UNDEF_CONFIGURABLE);
+
+    let has_get = obj.has_property(vm, Symbol::get());
+    let has_set = obj.has_property(vm, Symbol::set());
+    if has_get || has_set {
+        let getter = if has_get {
+            obj.get(vm, Symbol::get())?
+        } else {
+            JsValue::undefined()
+        };
+        let setter = if has_set {
+            obj.get(vm, Symbol::set())?
+        } else {
+            JsValue::undefined()
+        };
+        if (!getter.is_undefined() && !getter.is_callable())
+            || (!setter.is_undefined() && !setter.is_callable())
+        {
+            let msg = JsString::new(vm, "Getter and setter must be functions");
+            return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+        }
+        if obj.has_property(vm, Symbol::value()) || obj.has_property(vm, Symbol::writable()) {
+            let msg = JsString::new(
+                vm,
+                "Invalid property descriptor. Cannot both specify accessors and a value or writable attribute",
+            );
+            return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+        }
+        if !has_get {
+            attrs |= UNDEF_GETTER;
+        }
+        if !has_set {
+            attrs |= UNDEF_SETTER;
+        }
+        return Ok(PropertyDescriptor::accessor_descriptor(
+            getter, setter, attrs,
+        ));
+    }
+
+    flag!(writable, W, UNDEF_WRITABLE);
+    if obj.has_property(vm, Symbol::value()) {
+        let value = obj.get(vm, Symbol::value())?;
+        Ok(PropertyDescriptor::data_descriptor(value, attrs))
+    } else {
+        Ok(PropertyDescriptor::data_descriptor(
+            JsValue::undefined(),
+            attrs | UNDEF_VALUE,
+        ))
+    }
+}
+
+/// section 15.2.1 The Object Constructor Called as a Function
+pub fn object_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let val = args.at(0);
+    if val.is_object() {
+        return Ok(val);
+    }
+    Ok(JsValue::new(new_object(vm)))
+}
+
+/// section 15.2.3.6 Object.defineProperty(O, P, Attributes)
+pub fn object_define_property(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(object_argument(vm, args.at(0))?);
+    let name = args.at(1).to_symbol(vm)?;
+    let desc = to_property_descriptor(vm, args.at(2))?;
+    obj.define_own_property(vm, name, &desc, true)?;
+    Ok(JsValue::new(*obj))
+}
+
+/// section 15.2.3.7 steps 3-6 of Object.defineProperties(O, Properties), every descriptor is
+/// read and validated before the first one is defined. Symbol keys are defined too.
+fn define_properties(
+    vm: &mut VirtualMachine,
+    obj: Gc<JsObject>,
+    props: JsValue,
+) -> Result<(), JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(obj);
+    let props = ctx.new_local(object_argument(vm, props)?);
+    let names = props.own_enumerable_keys(vm)?;
+    let mut descriptors = Vec::with_capacity(names.len());
+    for name in names {
+        let desc = props.get(vm, name)?;
+        ctx.new_local(desc);
+        let desc = to_property_descriptor(vm, desc)?;
+        // values of the descriptor are only reachable from here until they are defined
+        if desc.is_accessor() {
+            ctx.new_local(desc.getter());
+            ctx.new_local(desc.setter());
+        } else {
+            ctx.new_local(desc.value());
+        }
+        descriptors.push((name, desc));
+    }
+    for (name, desc) in descriptors.iter() {
+        obj.define_own_property(vm, *name, desc, true)?;
+    }
+    Ok(())
+}
+
+/// section 15.2.3.7 Object.defineProperties(O, Properties)
+pub fn object_define_properties(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(object_argument(vm, args.at(0))?);
+    define_properties(vm, *obj, args.at(1))?;
+    Ok(JsValue::new(*obj))
+}
+
+/// section 15.2.3.5 Object.create(O [, Properties])
+pub fn object_create(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let proto = args.at(0);
+    let proto = if proto.is_object() {
+        Some(proto.as_object())
+    } else if proto.is_null() {
+        None
+    } else {
+        let msg = JsString::new(vm, "Object prototype may only be an Object or null");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    };
+    let ctx = vm.space().new_local_context();
+    let structure = vm.root_structure(proto);
+    let obj = ctx.new_local(JsObject::new(
+        vm,
+        structure,
+        JsObject::get_class(),
+        ObjectTag::Ordinary,
+    ));
+    if !args.at(1).is_undefined() {
+        define_properties(vm, *obj, args.at(1))?;
+    }
+    Ok(JsValue::new(*obj))
+}
+
+/// Array of the string values of `names`.
+fn names_array(vm: &mut VirtualMachine, names: Vec<Symbol>) -> JsValue {
+    let ctx = vm.space().new_local_context();
+    let mut values = Vec::with_capacity(names.len());
+    for name in names {
+        let s = match name {
+            Symbol::Indexed(index) if index <= i32::MAX as u32 => vm.int_string(index as i32),
+            _ => JsString::new(vm, name.as_string()),
+        };
+        let s = ctx.new_local(s);
+        values.push(JsValue::new(*s));
+    }
+    let arr = vm.new_array(&ctx, &values);
+    JsValue::new(*arr)
+}
+
+/// section 15.2.3.4 Object.getOwnPropertyNames(O)
+pub fn object_get_own_property_names(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(object_argument(vm, args.at(0))?);
+    let names = obj.own_property_keys(vm)?;
+    Ok(names_array(vm, names))
+}
+
+/// section 15.2.3.14 Object.keys(O)
+pub fn object_keys(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(object_argument(vm, args.at(0))?);
+    let names = obj.own_enumerable_keys(vm)?;
+    Ok(names_array(vm, names))
+}
+
+/// section 19.1.2.9 Object.getOwnPropertyDescriptors(O)
+pub fn object_get_own_property_descriptors(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(object_argument(vm, args.at(0))?);
+    let mut result = ctx.new_local(new_object(vm));
+    for name in obj.own_property_keys(vm)? {
+        let mut slot = Slot::new();
+        if !obj.get_own_property_slot(vm, name, &mut slot) {
+            continue;
+        }
+        let desc = from_property_descriptor(vm, &slot.to_descriptor())?;
+        result.put(vm, name, JsValue::new(desc), true)?;
+    }
+    Ok(JsValue::new(*result))
+}
+
+/// section 20.1.2.13 Object.hasOwn(O, P)
+pub fn object_has_own(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let target = args.at(0);
+    if target.is_undefined_or_null() {
+        let msg = JsString::new(vm, "Cannot convert undefined or null to object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let key = args.at(1).to_symbol(vm)?;
+    let found = if target.is_object() {
+        target.as_object().has_own_property(vm, key)
+    } else if target.is_string() {
+        target.as_string().has_own_property(key)
+    } else {
+        // the wrapper objects of other primitives have no own properties.
+        false
+    };
+    Ok(JsValue::new(found))
+}
+
+/// section 15.2.3.10 Object.preventExtensions(O)
+pub fn object_prevent_extensions(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut obj = object_argument(vm, args.at(0))?;
+    obj.prevent_extensions(vm);
+    Ok(JsValue::new(obj))
+}
+
+/// section 15.2.3.13 Object.isExtensible(O)
+pub fn object_is_extensible(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let obj = object_argument(vm, args.at(0))?;
+    Ok(JsValue::new(obj.is_extensible()))
+}
+
+/// section 19.1.2.7 Object.fromEntries(iterable)
+pub fn object_from_entries(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let iterable = args.at(0);
+    if iterable.is_undefined_or_null() {
+        let msg = JsString::new(vm, "Object.fromEntries requires an iterable argument");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(new_object(vm));
+    iterate_or_arraylike(vm, iterable, &mut |vm, entry| {
+        if !entry.is_object() {
+            let msg = JsString::new(vm, "Iterator value is not an entry object");
+            return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+        }
+        let entry = entry.as_object();
+        let key = entry.get(vm, Symbol::Indexed(0))?;
+        let ctx = vm.space().new_local_context();
+        let value = ctx.new_local(entry.get(vm, Symbol::Indexed(1))?);
+        let key = key.to_symbol(vm)?;
+        // CreateDataPropertyOrThrow, properties are added in order so the object goes through
+        // the usual structure transitions.
+        obj.define_own_property(vm, key, &*DataDescriptor::new(*value, W | E | C), true)?;
+        Ok(())
+    })?;
+    Ok(JsValue::new(*obj))
+}
+
+/// section 20.1.2.12 Object.groupBy(items, callbackfn)
+pub fn object_group_by(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut obj = ctx.new_local(JsObject::new_empty(vm));
+    group_by(vm, args.at(0), args.at(1), &mut |vm, key| {
+        let key = key.to_symbol(vm)?;
+        let group = obj.get(vm, key)?;
+        if group.is_object() {
+            return Ok(group.as_object());
+        }
+        let group = JsArray::new(vm, 0);
+        obj.define_own_property(
+            vm,
+            key,
+            &*DataDescriptor::new(JsValue::new(group), W | E | C),
+            true,
+        )?;
+        Ok(group)
+    })?;
+    Ok(JsValue::new(*obj))
+}
+
+/// Tag of `[object Tag]` for objects without a string `[Symbol.toStringTag]`.
+fn builtin_tag(obj: Gc<JsObject>) -> &'static str {
+    match obj.tag() {
+        ObjectTag::Array => "Array",
+        ObjectTag::NormalArguments | ObjectTag::StrictArguments => "Arguments",
+        ObjectTag::Regex => "RegExp",
+        _ if obj.is_callable() => "Function",
+        // every error class is named `Error`.
+        _ if obj.class().name == "Error" => "Error",
+        _ if obj.class().name == "Date" => "Date",
+        _ => "Object",
+    }
+}
+
+/// section 19.1.3.6 Object.prototype.toString()
+pub fn object_to_string(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    let builtin = if this.is_undefined() {
+        return Ok(JsValue::new(JsString::new(vm, "[object Undefined]")));
+    } else if this.is_null() {
+        return Ok(JsValue::new(JsString::new(vm, "[object Null]")));
+    } else if this.is_object() {
+        builtin_tag(this.as_object())
+    } else if this.is_string() {
+        "String"
+    } else if this.is_number() {
+        "Number"
+    } else if this.is_boolean() {
+        "Boolean"
+    } else {
+        "Symbol"
+    };
+    let tag = this.get_slot(vm, Symbol::to_string_tag(), &mut Slot::new())?;
+    let result = if tag.is_string() {
+        format!("[object {}]", tag.as_string().as_str())
+    } else {
+        format!("[object {}]", builtin)
+    };
+    Ok(JsValue::new(JsString::new(vm, result)))
+}
+
+pub(crate) fn init_object(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let mut proto = ctx.new_local(vm.global_data().get_object_prototype());
+    let name = vm.intern("Object");
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        object_constructor,
+        1,
+    ));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(*proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    define_method(vm, *ctor, "defineProperty", object_define_property, 3);
+    define_method(vm, *ctor, "defineProperties", object_define_properties, 2);
+    define_method(vm, *ctor, "keys", object_keys, 1);
+    define_method(vm, *ctor, "create", object_create, 2);
+    define_method(
+        vm,
+        *ctor,
+        "getOwnPropertyNames",
+        object_get_own_property_names,
+        1,
+    );
+    define_method(
+        vm,
+        *ctor,
+        "getOwnPropertyDescriptors",
+        object_get_own_property_descriptors,
+        1,
+    );
+    define_method(vm, *ctor, "preventExtensions", object_prevent_extensions, 1);
+    define_method(vm, *ctor, "isExtensible", object_is_extensible, 1);
+    define_method(vm, *ctor, "hasOwn", object_has_own, 2);
+    define_method(vm, *ctor, "fromEntries", object_from_entries, 1);
+    define_method(vm, *ctor, "groupBy", object_group_by, 2);
+    define_method(vm, *proto, "toString", object_to_string, 0);
+
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        heap::cell::Gc,
+        runtime::{error::JsTypeError, symbol::Symbol, value::JsValue},
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    fn names(vm: &mut VirtualMachine, arr: JsValue) -> Vec<String> {
+        let arr = arr.as_object();
+        let len = vm.get(arr, Symbol::length()).unwrap_or_else(|_| panic!());
+        (0..len.as_int32() as u32)
+            .map(|i| {
+                let val = vm.get(arr, i).unwrap_or_else(|_| panic!());
+                val.as_string().as_str().to_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_get_own_property_names() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "o = Object();
+             o.b = 1;
+             o[2] = 2;
+             o.a = 3;
+             o[0] = 4;
+             Object.defineProperty(o, 'hidden', { value: 5, writable: true });
+             n = Object.getOwnPropertyNames(o);
+             d = Object.getOwnPropertyDescriptors(o);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let n = vm.get(global, "n").unwrap_or_else(|_| panic!());
+        assert_eq!(names(&mut vm, n), ["0", "2", "b", "a", "hidden"]);
+
+        let d = vm.get(global, "d").unwrap_or_else(|_| panic!());
+        let hidden = vm.get(d.as_object(), "hidden").unwrap_or_else(|_| panic!());
+        let hidden = hidden.as_object();
+        let e = vm.get(hidden, "enumerable").unwrap_or_else(|_| panic!());
+        assert!(e.is_boolean() && !e.to_boolean());
+        let w = vm.get(hidden, "writable").unwrap_or_else(|_| panic!());
+        assert!(w.to_boolean());
+        let v = vm.get(hidden, "value").unwrap_or_else(|_| panic!());
+        assert_eq!(v.as_int32(), 5);
+
+        let b = vm.get(d.as_object(), "b").unwrap_or_else(|_| panic!());
+        let e = vm
+            .get(b.as_object(), "enumerable")
+            .unwrap_or_else(|_| panic!());
+        assert!(e.to_boolean());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_own_property_names_after_delete() {
+        let mut vm = VirtualMachine::new(Options::default());
+        // `d` takes over the slot of `a` but is still the newest key.
+        let res = vm.eval(
+            "o = { a: 1, b: 2, c: 3 };
+             delete o.a;
+             o.d = 1;
+             n = Object.getOwnPropertyNames(o);
+             k = Object.keys(o);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        for &name in ["n", "k"].iter() {
+            let keys = vm.get(global, name).unwrap_or_else(|_| panic!());
+            assert_eq!(names(&mut vm, keys), ["b", "c", "d"]);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_define_properties() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function getB() { return this.a + 1; }
+             function setC(v) { stored = v; }
+             s = Symbol('s');
+             o = {};
+             r = Object.defineProperties(o, {
+                 a: { value: 1, writable: true, enumerable: true },
+                 b: { get: getB },
+                 c: { set: setC, enumerable: true },
+                 [s]: { value: 'sym' }
+             });
+             o.c = 5;
+             out = [r === o, o.a, o.b, o[s], stored, Object.keys(o).join('|')].join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "true,1,2,sym,5,a|c");
+
+        // nothing is defined when one of the descriptors is invalid.
+        assert!(vm.eval("p = {};").is_ok());
+        let res = vm.eval(
+            "Object.defineProperties(p, { ok: { value: 1 }, bad: { value: 2, get: getB } });",
+        );
+        match res {
+            Err(e) => assert!(std::ptr::eq(
+                e.as_object().class(),
+                JsTypeError::get_class()
+            )),
+            Ok(_) => panic!("expected TypeError"),
+        }
+        let p = vm.get(global, "p").unwrap_or_else(|_| panic!()).as_object();
+        let ok = vm.intern("ok");
+        assert!(!p.has_own_property(&mut vm, ok));
+        assert!(vm
+            .eval("Object.defineProperties(p, { w: { writable: true, set: setC } });")
+            .is_err());
+        assert!(vm.eval("Object.defineProperties(1, {});").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_prevent_extensions() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "o = Object();
+             o.a = 1;
+             before = Object.isExtensible(o);
+             r = Object.preventExtensions(o);
+             after = Object.isExtensible(o);
+             o.b = 2;
+             o.a = 3;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let before = vm.get(global, "before").unwrap_or_else(|_| panic!());
+        assert!(before.to_boolean());
+        let after = vm.get(global, "after").unwrap_or_else(|_| panic!());
+        assert!(after.is_boolean() && !after.to_boolean());
+        let o = vm.get(global, "o").unwrap_or_else(|_| panic!());
+        let r = vm.get(global, "r").unwrap_or_else(|_| panic!());
+        assert!(Gc::ptr_eq(o.as_object(), r.as_object()));
+        let b = vm.get(o.as_object(), "b").unwrap_or_else(|_| panic!());
+        assert!(b.is_undefined());
+        let a = vm.get(o.as_object(), "a").unwrap_or_else(|_| panic!());
+        assert_eq!(a.as_int32(), 3);
+
+        assert!(vm.eval("'use strict'; o.a = 5;").is_ok());
+        assert!(vm.eval("'use strict'; o.c = 4;").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_from_entries() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "o = Object.fromEntries([['a', 1], [2, 'two'], [Symbol.iterator, 3], ['a', 4]]);
+             i = 0;
+             function next() {
+                 i = i + 1;
+                 if (i > 2) {
+                     return { done: true };
+                 }
+                 return { done: false, value: ['k' + i, i * 10] };
+             }
+             function entries() { return { next: next }; }
+             iterable = { [Symbol.iterator]: entries };
+             p = Object.fromEntries(iterable);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let o = vm.get(global, "o").unwrap_or_else(|_| panic!()).as_object();
+        let a = vm.get(o, "a").unwrap_or_else(|_| panic!());
+        assert_eq!(a.as_int32(), 4);
+        let two = vm.get(o, 2u32).unwrap_or_else(|_| panic!());
+        assert_eq!(two.as_string().as_str(), "two");
+        let sym = vm.get(o, Symbol::iterator()).unwrap_or_else(|_| panic!());
+        assert_eq!(sym.as_int32(), 3);
+
+        let p = vm.get(global, "p").unwrap_or_else(|_| panic!()).as_object();
+        let k2 = vm.get(p, "k2").unwrap_or_else(|_| panic!());
+        assert_eq!(k2.as_int32(), 20);
+
+        assert!(vm.eval("Object.fromEntries([1]);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_to_string_tags() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function u() {}
+             function g() { return ts.call(arguments); }
+             ts = Object.prototype.toString;
+             widget = { [Symbol.toStringTag]: 'Widget' };
+             tags = [
+                 ts.call([1]), ts.call(null), ts.call(u()), ts.call(u), ts.call(new TypeError('x')),
+                 g(), ts.call(1), ts.call('s'), ts.call({}), ts.call(widget),
+                 ts.call({ [Symbol.toStringTag]: 5 }), '' + widget
+             ];",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let tags = vm.get(global, "tags").unwrap_or_else(|_| panic!());
+        assert_eq!(
+            names(&mut vm, tags),
+            [
+                "[object Array]",
+                "[object Null]",
+                "[object Undefined]",
+                "[object Function]",
+                "[object Error]",
+                "[object Arguments]",
+                "[object Number]",
+                "[object String]",
+                "[object Object]",
+                "[object Widget]",
+                "[object Object]",
+                "[object Widget]"
+            ]
+        );
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "indices = '';
+             function parity(n, i) {
+                 indices = indices + i;
+                 if (Math.floor(n / 2) * 2 === n) return 'even';
+                 return 'odd';
+             }
+             g = Object.groupBy([1, 2, 3, 4, 5], parity);
+             keys = Object.getOwnPropertyNames(g);
+             fromSet = Object.groupBy(new Set([4, 7]), parity);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let indices = vm.get(global, "indices").unwrap_or_else(|_| panic!());
+        assert_eq!(indices.as_string().as_str(), "0123401");
+        let keys = vm.get(global, "keys").unwrap_or_else(|_| panic!());
+        assert_eq!(names(&mut vm, keys), ["odd", "even"]);
+        let g = vm.get(global, "g").unwrap_or_else(|_| panic!()).as_object();
+        assert!(g.prototype().is_none());
+        for (key, expected) in &[("odd", &[1, 3, 5][..]), ("even", &[2, 4][..])] {
+            let group = vm.get(g, *key).unwrap_or_else(|_| panic!()).as_object();
+            let len = vm.get(group, Symbol::length()).unwrap_or_else(|_| panic!());
+            assert_eq!(len.as_int32() as usize, expected.len());
+            for (i, n) in expected.iter().enumerate() {
+                let val = vm.get(group, i as u32).unwrap_or_else(|_| panic!());
+                assert_eq!(val.as_int32(), *n);
+            }
+        }
+        let from_set = vm.get(global, "fromSet").unwrap_or_else(|_| panic!());
+        let odd = vm.get(from_set.as_object(), "odd");
+        let first = vm.get(odd.unwrap_or_else(|_| panic!()).as_object(), 0u32);
+        assert_eq!(first.unwrap_or_else(|_| panic!()).as_int32(), 7);
+
+        assert!(vm.eval("Object.groupBy([1], 1);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_has_own() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function first(n) { return 'all'; }
+             bare = Object.groupBy([1], first);
+             o = { a: 1, 2: 'two' };
+             Object.defineProperty(o, 'hidden', { value: 0 });
+             out = [
+                 Object.hasOwn(bare, 'all'),
+                 Object.hasOwn(bare, 'toString'),
+                 Object.hasOwn(o, 'a'),
+                 Object.hasOwn(o, 2),
+                 Object.hasOwn(o, '2'),
+                 Object.hasOwn(o, 'hidden'),
+                 Object.hasOwn(o, 'toString'),
+                 Object.hasOwn(o, 'b'),
+                 Object.hasOwn('abc', 'length'),
+                 Object.hasOwn('abc', 1),
+                 Object.hasOwn('abc', 3),
+                 Object.hasOwn(1, 'toString')
+             ].join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(
+            out.as_string().as_str(),
+            "true,false,true,true,true,true,false,false,true,true,false,false"
+        );
+        assert!(vm.eval("Object.hasOwn(null, 'a');").is_err());
+        assert!(vm.eval("Object.hasOwn(undefined, 'a');").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+    #[test]
+    fn test_root_structures_shared() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = {};
+             b = {};
+             proto = { greet: 'hi' };
+             c = Object.create(proto);
+             d = Object.create(proto);
+             bare = Object.create(null);
+             described = Object.create(proto, { x: { value: 1, enumerable: true }, y: { value: 2 } });
+             out = [described.x, described.y, described.greet, Object.keys(described)].join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let id = |vm: &mut VirtualMachine, name: &str| {
+            let obj = vm.get(global, name).unwrap_or_else(|_| panic!());
+            obj.as_object().structure().id()
+        };
+        assert_eq!(id(&mut vm, "a"), id(&mut vm, "b"));
+        assert_eq!(id(&mut vm, "c"), id(&mut vm, "d"));
+        assert_ne!(id(&mut vm, "a"), id(&mut vm, "c"));
+        let bare = vm.get(global, "bare").unwrap_or_else(|_| panic!());
+        assert!(bare.as_object().prototype().is_none());
+
+        let res = vm.eval("a.x = 1; b.x = 2; c.x = 3; d.x = 4;");
+        assert!(res.is_ok());
+        assert_eq!(id(&mut vm, "a"), id(&mut vm, "b"));
+        assert_eq!(id(&mut vm, "c"), id(&mut vm, "d"));
+
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "1,2,hi,x");
+        assert!(vm.eval("Object.create(1);").is_err());
+        assert!(vm.eval("Object.create({}, null);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
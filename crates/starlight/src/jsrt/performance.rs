@@ -0,0 +1,75 @@
+use super::define_method;
+use crate::{
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        object::{JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// `performance.now()`, fractional milliseconds from a monotonic clock started with the VM.
+pub fn performance_now(vm: &mut VirtualMachine, _args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(vm.now()))
+}
+
+pub(crate) fn init_performance(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().object_structure.unwrap();
+    let performance = ctx.new_local(JsObject::new(
+        vm,
+        structure,
+        JsObject::get_class(),
+        ObjectTag::Ordinary,
+    ));
+    define_method(vm, *performance, "now", performance_now, 0);
+    let name = vm.intern("performance");
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*performance), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    fn eval_times(vm: &mut VirtualMachine) -> (f64, f64) {
+        assert!(vm
+            .eval("a = performance.now(); b = performance.now();")
+            .is_ok());
+        let global = vm.global_object();
+        let a = vm.get(global, "a").unwrap_or_else(|_| panic!());
+        let b = vm.get(global, "b").unwrap_or_else(|_| panic!());
+        (a.number(), b.number())
+    }
+
+    #[test]
+    fn test_performance_now() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let (a, b) = eval_times(&mut vm);
+        assert!(a >= 0.0 && a <= b);
+        VirtualMachineRef::dispose(vm);
+
+        let ticks = Rc::new(Cell::new(0.0));
+        let clock = ticks.clone();
+        let opts = Options::builder()
+            .clock(move || {
+                clock.set(clock.get() + 1.5);
+                clock.get()
+            })
+            .build();
+        let mut vm = VirtualMachine::new(opts);
+        let (a, b) = eval_times(&mut vm);
+        assert_eq!(a, 1.5);
+        assert_eq!(b, 3.0);
+        assert_eq!(ticks.get(), 3.0);
+        VirtualMachineRef::dispose(vm);
+    }
+}
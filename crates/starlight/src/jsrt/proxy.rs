@@ -0,0 +1,130 @@
+use crate::{
+    runtime::{
+        arguments::Arguments, attributes::*, error::JsTypeError, function::JsNativeFunction,
+        property_descriptor::DataDescriptor, proxy::JsProxy, string::JsString, value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// section 26.2.1.1 Proxy(target, handler)
+pub fn proxy_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if !args.ctor_call {
+        let msg = JsString::new(vm, "Constructor Proxy requires 'new'");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let (target, handler) = (args.at(0), args.at(1));
+    if !target.is_object() || !handler.is_object() {
+        let msg = JsString::new(
+            vm,
+            "Cannot create proxy with a non-object as target or handler",
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(JsValue::new(JsProxy::new(
+        vm,
+        target.as_object(),
+        handler.as_object(),
+    )))
+}
+
+pub(crate) fn init_proxy(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let name = vm.intern("Proxy");
+    let ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        proxy_constructor,
+        2,
+    ));
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_own_keys_trap() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "target = { a: 1, b: 2 };
+             function listKeys(t) { return ['a', 'b', 'extra']; }
+             function describe(t, key) {
+                 return { value: 0, enumerable: key !== 'b', configurable: true };
+             }
+             function read(t, key, receiver) {
+                 if (key === 'extra') {
+                     return 'synthetic';
+                 }
+                 return t[key];
+             }
+             handler = { ownKeys: listKeys, getOwnPropertyDescriptor: describe, get: read };
+             p = new Proxy(target, handler);
+             own = Reflect.ownKeys(p).join();
+             keys = Object.keys(p).join();
+             names = Object.getOwnPropertyNames(p).join();
+             visited = '';
+             for (var key in p) { visited = visited + key + '=' + p[key] + ';'; }
+
+             plain = new Proxy({ x: 1 }, {});
+             plainKeys = Object.keys(plain).join() + ',' + Reflect.ownKeys(plain).join();
+
+             frozen = {};
+             Object.defineProperty(frozen, 'fixed', { value: 1, enumerable: true });
+             function fixedAndMore() { return ['fixed', 'more']; }
+             listed = new Proxy(frozen, { ownKeys: fixedAndMore });
+             listedKeys = Reflect.ownKeys(listed).join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let expected = [
+            ("own", "a,b,extra"),
+            ("keys", "a,extra"),
+            ("names", "a,b,extra"),
+            ("visited", "a=1;extra=synthetic;"),
+            ("plainKeys", "x,x"),
+            ("listedKeys", "fixed,more"),
+        ];
+        for (name, out) in expected.iter() {
+            let value = vm.get(global, name).unwrap_or_else(|_| panic!());
+            assert_eq!(value.as_string().as_str(), *out);
+        }
+
+        // duplicate keys
+        let res = vm.eval(
+            "function twice() { return ['a', 'a']; }
+             Reflect.ownKeys(new Proxy({}, { ownKeys: twice }));",
+        );
+        assert!(res.is_err());
+        // keys that are neither strings nor symbols
+        let res = vm.eval(
+            "function numbers() { return [1]; }
+             Object.keys(new Proxy({}, { ownKeys: numbers }));",
+        );
+        assert!(res.is_err());
+        // a non-configurable key of the target is left out
+        let res = vm.eval(
+            "function onlyMore() { return ['more']; }
+             hidden = new Proxy(frozen, { ownKeys: onlyMore });
+             for (var key in hidden) {}",
+        );
+        assert!(res.is_err());
+        let res = vm.eval("Object.keys(hidden);");
+        assert!(res.is_err());
+        // a non-extensible target must report exactly its keys
+        let res = vm.eval(
+            "sealed = { s: 1 };
+             Object.preventExtensions(sealed);
+             function withExtra() { return ['s', 'x']; }
+             Reflect.ownKeys(new Proxy(sealed, { ownKeys: withExtra }));",
+        );
+        assert!(res.is_err());
+        assert!(vm.eval("Proxy({}, {});").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,273 @@
+use super::define_method;
+use crate::{
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        error::JsTypeError,
+        iterator::arguments_from_list,
+        object::{JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        string::JsString,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// section 26.1.1 Reflect.apply(target, thisArgument, argumentsList)
+pub fn reflect_apply(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let target = args.at(0);
+    if !target.is_callable() {
+        let msg = JsString::new(vm, "Reflect.apply: target is not a function");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let list = args.at(2);
+    if !list.is_object() {
+        let msg = JsString::new(vm, "Reflect.apply: argumentsList is not an object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let fargs = arguments_from_list(vm, args.at(1), list)?;
+    let mut fargs = ctx.new_local(fargs);
+    target.as_object().as_function_mut().call(vm, &mut fargs)
+}
+
+/// section 26.1.2 Reflect.construct(target, argumentsList [, newTarget])
+pub fn reflect_construct(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let target = args.at(0);
+    if !target.is_constructor() {
+        let msg = JsString::new(vm, "Reflect.construct: target is not a constructor");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let new_target = if args.size() > 2 { args[2] } else { target };
+    if !new_target.is_constructor() {
+        let msg = JsString::new(vm, "Reflect.construct: newTarget is not a constructor");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let list = args.at(1);
+    if !list.is_object() {
+        let msg = JsString::new(vm, "Reflect.construct: argumentsList is not an object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let cargs = arguments_from_list(vm, JsValue::undefined(), list)?;
+    let mut cargs = ctx.new_local(cargs);
+    cargs.new_target = new_target;
+    let structure = new_target.as_object().func_construct_map(vm).ok();
+    target
+        .as_object()
+        .as_function_mut()
+        .construct(vm, &mut cargs, structure)
+}
+
+/// section 26.1.6 Reflect.get(target, propertyKey [, receiver])
+pub fn reflect_get(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let target = args.at(0);
+    if !target.is_object() {
+        let msg = JsString::new(vm, "Reflect.get: target is not an object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let key = args.at(1).to_symbol(vm)?;
+    let receiver = if args.size() > 2 { args[2] } else { target };
+    target.as_object().get_with_receiver(vm, key, receiver)
+}
+
+/// section 26.1.13 Reflect.set(target, propertyKey, V [, receiver])
+pub fn reflect_set(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let target = args.at(0);
+    if !target.is_object() {
+        let msg = JsString::new(vm, "Reflect.set: target is not an object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let key = args.at(1).to_symbol(vm)?;
+    let receiver = if args.size() > 3 { args[3] } else { target };
+    let done = target
+        .as_object()
+        .put_with_receiver(vm, key, args.at(2), receiver)?;
+    Ok(JsValue::new(done))
+}
+
+/// section 26.1.11 Reflect.ownKeys(target)
+pub fn reflect_own_keys(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let target = args.at(0);
+    if !target.is_object() {
+        let msg = JsString::new(vm, "Reflect.ownKeys: target is not an object");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let target = ctx.new_local(target.as_object());
+    let keys = target.own_property_keys(vm)?;
+    let mut values = ctx.new_local(Vec::with_capacity(keys.len()));
+    for key in keys {
+        let value = vm.key_value(key);
+        values.push(value);
+    }
+    let arr = vm.new_array(&ctx, &values);
+    Ok(JsValue::new(*arr))
+}
+
+pub(crate) fn init_reflect(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().object_structure.unwrap();
+    let reflect = ctx.new_local(JsObject::new(
+        vm,
+        structure,
+        JsObject::get_class(),
+        ObjectTag::Ordinary,
+    ));
+    define_method(vm, *reflect, "apply", reflect_apply, 3);
+    define_method(vm, *reflect, "construct", reflect_construct, 2);
+    define_method(vm, *reflect, "get", reflect_get, 2);
+    define_method(vm, *reflect, "set", reflect_set, 3);
+    define_method(vm, *reflect, "ownKeys", reflect_own_keys, 1);
+    let name = vm.intern("Reflect");
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*reflect), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        heap::cell::Gc,
+        runtime::error::JsTypeError,
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_reflect_construct_new_target() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function A(x) { this.x = x; this.t = new.target; }
+             function B() {}
+             B.prototype.marker = 7;
+             o = Reflect.construct(A, [3], B);
+             p = new A(4);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let o = vm.get(global, "o").unwrap_or_else(|_| panic!()).as_object();
+        let p = vm.get(global, "p").unwrap_or_else(|_| panic!()).as_object();
+        let a = vm.get(global, "A").unwrap_or_else(|_| panic!()).as_object();
+        let b = vm.get(global, "B").unwrap_or_else(|_| panic!()).as_object();
+        let b_proto = vm
+            .get(b, "prototype")
+            .unwrap_or_else(|_| panic!())
+            .as_object();
+
+        assert!(Gc::ptr_eq(o.prototype().unwrap(), b_proto));
+        assert_eq!(
+            vm.get(o, "marker").unwrap_or_else(|_| panic!()).as_int32(),
+            7
+        );
+        assert_eq!(vm.get(o, "x").unwrap_or_else(|_| panic!()).as_int32(), 3);
+        let t = vm.get(o, "t").unwrap_or_else(|_| panic!()).as_object();
+        assert!(Gc::ptr_eq(t, b));
+        let t = vm.get(p, "t").unwrap_or_else(|_| panic!()).as_object();
+        assert!(Gc::ptr_eq(t, a));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_reflect_construct_requires_constructors() {
+        let mut vm = VirtualMachine::new(Options::default());
+        for src in [
+            "Reflect.construct(String.fromCharCode, []);",
+            "Reflect.construct(function () {}, [], String.fromCharCode);",
+        ]
+        .iter()
+        {
+            match vm.eval(src) {
+                Err(e) => assert!(std::ptr::eq(
+                    e.as_object().class(),
+                    JsTypeError::get_class()
+                )),
+                Ok(_) => panic!("{} did not throw", src),
+            }
+        }
+        let res = vm.eval("o = Reflect.construct(Object, []); a = Reflect.construct(Array, []);");
+        assert!(res.is_ok());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_apply_copies_arrays() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function count() { return arguments.length + arguments[9999]; }
+             function sum(a, b, c) { return a + b + c; }
+             function ownValues() { return this.values(); }
+             big = new Array(10000).fill(1);
+             observed = new Array(10000).fill(2);
+             observed[Symbol.iterator] = ownValues;
+             like = { length: 3 };
+             like[0] = 1;
+             like[1] = 2;
+             like[2] = 3;
+             fromLike = Reflect.apply(sum, null, like) + sum.apply(null, like);",
+        );
+        assert!(res.is_ok());
+        let before = vm.space().allocation_count();
+        assert!(vm.eval("fast = Reflect.apply(count, null, big);").is_ok());
+        let fast = vm.space().allocation_count() - before;
+        let before = vm.space().allocation_count();
+        assert!(vm.eval("slow = count.apply(null, observed);").is_ok());
+        let slow = vm.space().allocation_count() - before;
+        // the overridden iterator still runs and allocates a result object per element
+        assert!(fast < 1000, "{} allocations", fast);
+        assert!(slow > 10000, "{} allocations", slow);
+
+        let global = vm.global_object();
+        let expected = [("fast", 10001), ("slow", 10002), ("fromLike", 12)];
+        for (name, out) in expected.iter() {
+            let value = vm.get(global, name).unwrap_or_else(|_| panic!());
+            assert_eq!(
+                value.to_number(&mut vm).unwrap_or_else(|_| panic!()),
+                *out as f64
+            );
+        }
+
+        assert!(vm.eval("Reflect.apply(count, null, 1);").is_err());
+        assert!(vm.eval("Reflect.apply({}, null, []);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_accessor_receiver() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function Base() {}
+             function readBacking() { return this.backing; }
+             function writeBacking(v) { this.backing = v + '!'; }
+             Object.defineProperty(Base.prototype, 'value', { get: readBacking, set: writeBacking });
+             Base.prototype.backing = 'proto';
+             d = new Base();
+             d.backing = 'derived';
+             viaGet = d.value;
+             d.value = 'set';
+             custom = { backing: 'custom' };
+             viaReflect = Reflect.get(d, 'value', custom);
+             setResult = Reflect.set(d, 'value', 'reflected', custom);
+             target = {};
+             receiver = {};
+             dataSet = Reflect.set(target, 'x', 1, receiver);
+             ro = {};
+             Object.defineProperty(ro, 'fixed', { value: 1 });
+             roSet = Reflect.set(ro, 'fixed', 2);
+             out = [viaGet, d.backing, Base.prototype.backing, viaReflect, setResult,
+                    custom.backing, dataSet, Object.hasOwn(target, 'x'), receiver.x, roSet,
+                    ro.fixed, Reflect.get(d, 'value')].join();",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(
+            out.as_string().as_str(),
+            "derived,set!,proto,custom,true,reflected!,true,false,1,false,1,set!"
+        );
+        assert!(vm.eval("Reflect.get(1, 'x');").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
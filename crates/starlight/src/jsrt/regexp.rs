@@ -0,0 +1,306 @@
+use super::{define_method, iter_result, return_this};
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        array::JsArray,
+        attributes::*,
+        error::JsTypeError,
+        function::JsNativeFunction,
+        object::{JsObject, ObjectTag},
+        property_descriptor::DataDescriptor,
+        regexp::JsRegExp,
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+fn this_regexp(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    name: &str,
+) -> Result<Gc<JsObject>, JsValue> {
+    if !this.is_object() || this.as_object().tag() != ObjectTag::Regex {
+        let msg = JsString::new(
+            vm,
+            format!("RegExp.prototype.{} requires that 'this' be a RegExp", name),
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(this.as_object())
+}
+
+/// section 7.1.15 ToLength
+pub(crate) fn to_length(vm: &mut VirtualMachine, val: JsValue) -> Result<usize, JsValue> {
+    let n = val.to_number(vm)?;
+    if n.is_nan() || n <= 0.0 {
+        return Ok(0);
+    }
+    Ok(n.min(9007199254740991.0) as usize)
+}
+
+/// section 21.2.5.2.2 RegExpBuiltinExec(R, S)
+pub(crate) fn regexp_builtin_exec(
+    vm: &mut VirtualMachine,
+    regexp: Gc<JsObject>,
+    s: Gc<JsString>,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut regexp = ctx.new_local(regexp);
+    let s = ctx.new_local(s);
+    let last_index = regexp.get(vm, Symbol::lastIndex())?;
+    let last_index = to_length(vm, last_index)?;
+    let flags = regexp.as_regexp().regex.flags();
+    // lastIndex is only read and updated by global or sticky regular expressions.
+    let uses_last_index = flags.global || flags.sticky;
+    let input = s.as_str().encode_utf16().collect::<Vec<u16>>();
+    let start = if uses_last_index { last_index } else { 0 };
+
+    let caps = if start > input.len() {
+        None
+    } else {
+        regexp.as_regexp().regex.exec(&input, start)
+    };
+    let caps = match caps {
+        Some(caps) => caps,
+        None => {
+            if uses_last_index {
+                regexp.put(vm, Symbol::lastIndex(), JsValue::new(0), true)?;
+            }
+            return Ok(JsValue::null());
+        }
+    };
+    let (begin, end) = caps[0].unwrap();
+    if uses_last_index {
+        regexp.put(vm, Symbol::lastIndex(), JsValue::from(end as u32), true)?;
+    }
+
+    let mut arr = ctx.new_local(JsArray::new(vm, 0));
+    for (i, cap) in caps.iter().enumerate() {
+        let val = match cap {
+            Some((s, e)) => {
+                JsValue::new(JsString::new(vm, String::from_utf16_lossy(&input[*s..*e])))
+            }
+            None => JsValue::undefined(),
+        };
+        arr.put(vm, Symbol::Indexed(i as u32), val, true)?;
+    }
+    arr.put(vm, Symbol::index(), JsValue::from(begin as u32), true)?;
+    arr.put(vm, Symbol::input(), JsValue::new(*s), true)?;
+
+    let names = regexp.as_regexp().regex.group_names().to_vec();
+    let groups = if names.is_empty() {
+        JsValue::undefined()
+    } else {
+        let mut groups = vm.new_object(&ctx);
+        for (name, index) in names {
+            let val = arr.get(vm, Symbol::Indexed(index as u32))?;
+            let name = vm.intern(name);
+            groups.put(vm, name, val, true)?;
+        }
+        JsValue::new(*groups)
+    };
+    let name = vm.intern("groups");
+    arr.put(vm, name, groups, true)?;
+    Ok(JsValue::new(*arr))
+}
+
+/// section 21.2.3.1 RegExp(pattern, flags)
+pub fn regexp_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let pattern = args.at(0);
+    let flags = args.at(1);
+    let (pattern, default_flags) =
+        if pattern.is_object() && pattern.as_object().tag() == ObjectTag::Regex {
+            let regexp = pattern.as_object();
+            let regexp = regexp.as_regexp();
+            (regexp.source.clone(), regexp.regex.flags().to_string())
+        } else if pattern.is_undefined() {
+            (String::new(), String::new())
+        } else {
+            (pattern.to_string(vm)?, String::new())
+        };
+    let flags = if flags.is_undefined() {
+        default_flags
+    } else {
+        flags.to_string(vm)?
+    };
+    Ok(JsValue::new(JsRegExp::new(vm, &pattern, &flags)?))
+}
+
+/// section 21.2.5.2 RegExp.prototype.exec(string)
+pub fn regexp_exec(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let regexp = ctx.new_local(this_regexp(vm, args.this, "exec")?);
+    let s = args.at(0).to_string(vm)?;
+    let s = JsString::new(vm, s);
+    regexp_builtin_exec(vm, *regexp, s)
+}
+
+/// section 21.2.5.13 RegExp.prototype.test(S)
+pub fn regexp_test(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let regexp = ctx.new_local(this_regexp(vm, args.this, "test")?);
+    let s = args.at(0).to_string(vm)?;
+    let s = JsString::new(vm, s);
+    Ok(JsValue::new(
+        !regexp_builtin_exec(vm, *regexp, s)?.is_null(),
+    ))
+}
+
+/// section 21.2.7.1.1 %RegExpStringIteratorPrototype%.next()
+pub fn regexp_string_iterator_next(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    if !this.is_object() || this.as_object().tag() != ObjectTag::RegExpStringIterator {
+        let msg = JsString::new(vm, "next method called on incompatible receiver");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let ctx = vm.space().new_local_context();
+    let mut iter = ctx.new_local(this.as_object());
+    if iter.as_regexp_string_iterator().done {
+        return iter_result(vm, JsValue::undefined(), true);
+    }
+    let mut regexp = ctx.new_local(iter.as_regexp_string_iterator().regexp);
+    let string = iter.as_regexp_string_iterator().string;
+    let m = ctx.new_local(regexp_builtin_exec(vm, *regexp, string)?);
+    if m.is_null() {
+        iter.as_regexp_string_iterator_mut().done = true;
+        return iter_result(vm, JsValue::undefined(), true);
+    }
+    if !iter.as_regexp_string_iterator().global {
+        iter.as_regexp_string_iterator_mut().done = true;
+        return iter_result(vm, *m, false);
+    }
+    let matched = m.as_object().get(vm, Symbol::Indexed(0))?.to_string(vm)?;
+    if matched.is_empty() {
+        // Step past empty matches or the iterator would never advance.
+        let last_index = regexp.get(vm, Symbol::lastIndex())?;
+        let last_index = to_length(vm, last_index)?;
+        regexp.put(
+            vm,
+            Symbol::lastIndex(),
+            JsValue::new_number(last_index as f64 + 1.0),
+            true,
+        )?;
+    }
+    iter_result(vm, *m, false)
+}
+
+pub(crate) fn init_regexp(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let mut proto = ctx.new_local(vm.global_data().regexp_prototype.unwrap());
+    let name = vm.intern("RegExp");
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        regexp_constructor,
+        2,
+    ));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(*proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    define_method(vm, *proto, "exec", regexp_exec, 1);
+    define_method(vm, *proto, "test", regexp_test, 1);
+
+    let structure = vm.global_data().regexp_string_iterator_structure.unwrap();
+    let mut iter_proto = ctx.new_local(structure.prototype().unwrap());
+    define_method(vm, *iter_proto, "next", regexp_string_iterator_next, 0);
+    let iterator = JsNativeFunction::new(vm, Symbol::iterator(), return_this, 0);
+    let _ = iter_proto.define_own_property(
+        vm,
+        Symbol::iterator(),
+        &*DataDescriptor::new(JsValue::new(iterator), W | C),
+        false,
+    );
+
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_exec() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "re = /(\\d+)-(x)?/g;
+             m = re.exec('a 12- 34-x');
+             last = re.lastIndex;
+             n = re.exec('a 12- 34-x');
+             none = re.exec('a 12- 34-x');
+             t = RegExp('^ab', 'i').test('ABC');",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let m = vm.get(global, "m").unwrap_or_else(|_| panic!()).as_object();
+        let whole = vm.get(m, 0u32).unwrap_or_else(|_| panic!());
+        assert_eq!(whole.as_string().as_str(), "12-");
+        assert!(vm.get(m, 2u32).unwrap_or_else(|_| panic!()).is_undefined());
+        let index = vm.get(m, "index").unwrap_or_else(|_| panic!());
+        assert_eq!(index.as_int32(), 2);
+        let last = vm.get(global, "last").unwrap_or_else(|_| panic!());
+        assert_eq!(last.as_int32(), 5);
+        let n = vm.get(global, "n").unwrap_or_else(|_| panic!()).as_object();
+        let x = vm.get(n, 2u32).unwrap_or_else(|_| panic!());
+        assert_eq!(x.as_string().as_str(), "x");
+        assert!(vm
+            .get(global, "none")
+            .unwrap_or_else(|_| panic!())
+            .is_null());
+        assert!(vm
+            .get(global, "t")
+            .unwrap_or_else(|_| panic!())
+            .to_boolean());
+
+        assert!(vm.eval("RegExp('(');").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_sticky_and_unicode() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "re = /foo/y;
+             re.lastIndex = 3;
+             hit = re.test('barfoo');
+             after = re.lastIndex;
+             re.lastIndex = 1;
+             miss = re.exec('barfoo');
+             reset = re.lastIndex;
+             flags = re.sticky + ',' + /a/gu.flags + ',' + /a/u.unicode;
+             astral = /^.$/u.test('😀');
+             units = /^.$/.test('😀');",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert!(get("hit").to_boolean());
+        assert_eq!(get("after").as_int32(), 6);
+        // a sticky match doesn't scan forward from lastIndex.
+        assert!(get("miss").is_null());
+        assert_eq!(get("reset").as_int32(), 0);
+        assert_eq!(get("flags").as_string().as_str(), "true,gu,true");
+        assert!(get("astral").to_boolean());
+        assert!(!get("units").to_boolean());
+        VirtualMachineRef::dispose(vm);
+    }
+}
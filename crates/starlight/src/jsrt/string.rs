@@ -0,0 +1,725 @@
+use super::{define_method, symbol::symbol_descriptive_string};
+use crate::{
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        collator::{Collator, Sensitivity},
+        error::{JsRangeError, JsTypeError},
+        function::JsNativeFunction,
+        normalize::{normalize, NormalizationForm},
+        object::ObjectTag,
+        property_descriptor::DataDescriptor,
+        regexp::{Captures, JsRegExp, JsRegExpStringIterator},
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// section 7.1.8 ToUint16
+fn to_uint16(n: f64) -> u16 {
+    if !n.is_finite() {
+        return 0;
+    }
+    n.trunc().rem_euclid(65536.0) as u16
+}
+
+/// section 15.5.1.1 String([value])
+pub fn string_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.size() == 0 {
+        return Ok(JsValue::new(JsString::new(vm, "")));
+    }
+    // the one conversion of a symbol to a string that does not throw.
+    if args[0].is_symbol() && !args.ctor_call {
+        let s = symbol_descriptive_string(vm, args[0].as_symbol());
+        return Ok(JsValue::new(JsString::new(vm, s)));
+    }
+    Ok(JsValue::new(args[0].to_js_string(vm)?))
+}
+
+/// section 15.5.3.2 String.fromCharCode([char0 [, char1 [, … ]]])
+pub fn string_from_char_code(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut units = Vec::with_capacity(args.size());
+    for i in 0..args.size() {
+        units.push(to_uint16(args[i].to_number(vm)?));
+    }
+    let s = String::from_utf16_lossy(&units);
+    let mut chars = s.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(JsValue::new(vm.char_string(c)));
+    }
+    Ok(JsValue::new(JsString::new(vm, s)))
+}
+
+/// section 21.1.2.2 String.fromCodePoint(...codePoints)
+pub fn string_from_code_point(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut s = String::with_capacity(args.size());
+    for i in 0..args.size() {
+        let n = args[i].to_number(vm)?;
+        let c = if n.trunc() == n && (0.0..=0x10ffff as f64).contains(&n) {
+            // lone surrogates have no UTF-8 form and are replaced like in `fromCharCode`.
+            Some(std::char::from_u32(n as u32).unwrap_or('\u{fffd}'))
+        } else {
+            None
+        };
+        match c {
+            Some(c) => s.push(c),
+            None => {
+                let msg = JsString::new(vm, format!("Invalid code point {}", n));
+                return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+            }
+        }
+    }
+    let mut chars = s.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(JsValue::new(vm.char_string(c)));
+    }
+    Ok(JsValue::new(JsString::new(vm, s)))
+}
+
+/// section 21.1.3.12 String.prototype.normalize([form])
+pub fn string_normalize(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.this.is_undefined_or_null() {
+        let msg = JsString::new(vm, "String.prototype.normalize called on null or undefined");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let s = args.this.to_string(vm)?;
+    let form = if args.at(0).is_undefined() {
+        NormalizationForm::Nfc
+    } else {
+        let name = args[0].to_string(vm)?;
+        match NormalizationForm::from_name(&name) {
+            Some(form) => form,
+            None => {
+                let msg = JsString::new(
+                    vm,
+                    "The normalization form should be one of NFC, NFD, NFKC, NFKD.",
+                );
+                return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+            }
+        }
+    };
+    Ok(JsValue::new(JsString::new(vm, normalize(&s, form))))
+}
+
+/// section 21.1.3.10 String.prototype.localeCompare(that [, locales [, options]])
+///
+/// `locales` is accepted but ignored, `options` supports `numeric` and `sensitivity` as in
+/// `Intl.Collator`.
+pub fn string_locale_compare(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if args.this.is_undefined_or_null() {
+        let msg = JsString::new(
+            vm,
+            "String.prototype.localeCompare called on null or undefined",
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let s = args.this.to_string(vm)?;
+    let that = args.at(0).to_string(vm)?;
+    let mut collator = Collator::default();
+    let options = args.at(2);
+    if options.is_object() {
+        let options = options.as_object();
+        let numeric = vm.intern("numeric");
+        let numeric = options.get(vm, numeric)?;
+        if !numeric.is_undefined() {
+            collator.numeric = numeric.to_boolean();
+        }
+        let sensitivity = vm.intern("sensitivity");
+        let sensitivity = options.get(vm, sensitivity)?;
+        if !sensitivity.is_undefined() {
+            let name = sensitivity.to_string(vm)?;
+            collator.sensitivity = match Sensitivity::from_name(&name) {
+                Some(sensitivity) => sensitivity,
+                None => {
+                    let msg = JsString::new(
+                        vm,
+                        format!("Value {} out of range for option sensitivity", name),
+                    );
+                    return Err(JsValue::new(JsRangeError::new(vm, msg, None)));
+                }
+            };
+        }
+    }
+    Ok(JsValue::new(collator.compare(&s, &that) as i32))
+}
+
+/// section 21.1.3.12 String.prototype.matchAll(regexp)
+pub fn string_match_all(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.this.is_undefined_or_null() {
+        let msg = JsString::new(vm, "String.prototype.matchAll called on null or undefined");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let s = args.this.to_string(vm)?;
+    let ctx = vm.space().new_local_context();
+    let s = ctx.new_local(JsString::new(vm, s));
+    let arg = args.at(0);
+    let mut regexp = if arg.is_object() && arg.as_object().tag() == ObjectTag::Regex {
+        let original = ctx.new_local(arg.as_object());
+        let flags = original.as_regexp().regex.flags();
+        if !flags.global {
+            let msg = JsString::new(
+                vm,
+                "String.prototype.matchAll called with a non-global RegExp argument",
+            );
+            return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+        }
+        let source = original.as_regexp().source.clone();
+        let mut regexp = ctx.new_local(JsRegExp::new(vm, &source, &flags.to_string())?);
+        let last_index = original.get(vm, Symbol::lastIndex())?;
+        regexp.put(vm, Symbol::lastIndex(), last_index, true)?;
+        regexp
+    } else {
+        let pattern = if arg.is_undefined() {
+            String::new()
+        } else {
+            arg.to_string(vm)?
+        };
+        ctx.new_local(JsRegExp::new(vm, &pattern, "g")?)
+    };
+    let global = regexp.as_regexp().regex.flags().global;
+    Ok(JsValue::new(JsRegExpStringIterator::new(
+        vm, *regexp, *s, global,
+    )))
+}
+
+fn find_utf16(haystack: &[u16], needle: &[u16], from: usize) -> Option<usize> {
+    if from + needle.len() > haystack.len() {
+        return None;
+    }
+    (from..=haystack.len() - needle.len()).find(|&i| &haystack[i..i + needle.len()] == needle)
+}
+
+fn is_digit(c: u16) -> bool {
+    (b'0' as u16..=b'9' as u16).contains(&c)
+}
+
+/// section 21.1.3.17.1 GetSubstitution(matched, str, position, captures, namedCaptures, replacement)
+///
+/// `captures[0]` is the whole match, `names` maps group names to capture indices.
+fn get_substitution(
+    input: &[u16],
+    captures: &Captures,
+    names: &[(String, usize)],
+    replacement: &[u16],
+) -> Vec<u16> {
+    let (position, end) = captures[0].unwrap();
+    let m = captures.len() - 1;
+    let push_capture = |result: &mut Vec<u16>, n: usize| {
+        if let Some((start, end)) = captures[n] {
+            result.extend_from_slice(&input[start..end]);
+        }
+    };
+    let dollar = b'$' as u16;
+    let mut result = Vec::with_capacity(replacement.len());
+    let mut i = 0;
+    while i < replacement.len() {
+        let c = replacement[i];
+        if c != dollar || i + 1 == replacement.len() {
+            result.push(c);
+            i += 1;
+            continue;
+        }
+        let next = replacement[i + 1];
+        match next as u8 {
+            b'$' if next < 128 => {
+                result.push(dollar);
+                i += 2;
+            }
+            b'&' if next < 128 => {
+                result.extend_from_slice(&input[position..end]);
+                i += 2;
+            }
+            b'`' if next < 128 => {
+                result.extend_from_slice(&input[..position]);
+                i += 2;
+            }
+            b'\'' if next < 128 => {
+                result.extend_from_slice(&input[end..]);
+                i += 2;
+            }
+            _ if is_digit(next) => {
+                let d1 = (next - b'0' as u16) as usize;
+                let two = replacement
+                    .get(i + 2)
+                    .filter(|&&c| is_digit(c))
+                    .map(|&c| d1 * 10 + (c - b'0' as u16) as usize);
+                match two {
+                    Some(n) if n >= 1 && n <= m => {
+                        push_capture(&mut result, n);
+                        i += 3;
+                    }
+                    _ if d1 >= 1 && d1 <= m => {
+                        push_capture(&mut result, d1);
+                        i += 2;
+                    }
+                    _ => {
+                        result.push(dollar);
+                        i += 1;
+                    }
+                }
+            }
+            b'<' if next < 128 && !names.is_empty() => {
+                let close = replacement[i + 2..]
+                    .iter()
+                    .position(|&c| c == b'>' as u16)
+                    .map(|p| p + i + 2);
+                match close {
+                    Some(close) => {
+                        let name = String::from_utf16_lossy(&replacement[i + 2..close]);
+                        if let Some(&(_, n)) = names.iter().find(|(group, _)| *group == name) {
+                            push_capture(&mut result, n);
+                        }
+                        i = close + 1;
+                    }
+                    None => {
+                        result.push(dollar);
+                        i += 1;
+                    }
+                }
+            }
+            _ => {
+                result.push(dollar);
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+/// Calls a replacer function with `(matched, p1, ..., pn, position, string [, groups])`.
+fn call_replacer(
+    vm: &mut VirtualMachine,
+    replacer: JsValue,
+    input: &[u16],
+    string: JsValue,
+    captures: &Captures,
+    names: &[(String, usize)],
+) -> Result<Vec<u16>, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let nargs = captures.len() + 2 + if names.is_empty() { 0 } else { 1 };
+    let mut args = ctx.new_local(Arguments::new(vm, JsValue::undefined(), nargs));
+    for (i, capture) in captures.iter().enumerate() {
+        args[i] = match capture {
+            Some((start, end)) => JsValue::new(JsString::new(
+                vm,
+                String::from_utf16_lossy(&input[*start..*end]),
+            )),
+            None => JsValue::undefined(),
+        };
+    }
+    args[captures.len()] = JsValue::from(captures[0].unwrap().0 as u32);
+    args[captures.len() + 1] = string;
+    if !names.is_empty() {
+        let mut groups = vm.new_object(&ctx);
+        for (name, n) in names {
+            let val = args[*n];
+            let name = vm.intern(name.as_str());
+            groups.put(vm, name, val, true)?;
+        }
+        args[captures.len() + 2] = JsValue::new(*groups);
+    }
+    let res = replacer.as_object().as_function_mut().call(vm, &mut args)?;
+    Ok(res.to_string(vm)?.encode_utf16().collect())
+}
+
+/// section 22.1.3.1 String.prototype.at(index)
+pub fn string_at(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.this.is_undefined_or_null() {
+        let msg = JsString::new(vm, "String.prototype.at called on null or undefined");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let s = args.this.to_string(vm)?;
+    let n = args.at(0).to_number(vm)?;
+    let n = if n.is_nan() { 0.0 } else { n.trunc() };
+    let len = s.utf16_len() as f64;
+    let k = if n < 0.0 { len + n } else { n };
+    if k < 0.0 || k >= len {
+        return Ok(JsValue::undefined());
+    }
+    let unit = s.code_unit_at(k as u32).unwrap();
+    // halves of a surrogate pair can't be represented on their own.
+    let c = std::char::from_u32(unit as u32).unwrap_or('\u{fffd}');
+    Ok(JsValue::new(vm.char_string(c)))
+}
+
+/// section 21.1.3.18 String.prototype.replaceAll(searchValue, replaceValue)
+pub fn string_replace_all(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.this.is_undefined_or_null() {
+        let msg = JsString::new(
+            vm,
+            "String.prototype.replaceAll called on null or undefined",
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let s = args.this.to_string(vm)?;
+    let ctx = vm.space().new_local_context();
+    let string = ctx.new_local(JsValue::new(JsString::new(vm, &s)));
+    let input = s.encode_utf16().collect::<Vec<u16>>();
+    let search = args.at(0);
+
+    let mut matches: Vec<Captures> = vec![];
+    let mut names = vec![];
+    if search.is_object() && search.as_object().tag() == ObjectTag::Regex {
+        let mut regexp = ctx.new_local(search.as_object());
+        let regex = &regexp.as_regexp().regex;
+        if !regex.flags().global {
+            let msg = JsString::new(vm, "replaceAll must be called with a global RegExp");
+            return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+        }
+        names = regex.group_names().to_vec();
+        let mut pos = 0;
+        while pos <= input.len() {
+            let captures = match regex.exec(&input, pos) {
+                Some(captures) => captures,
+                None => break,
+            };
+            let (start, end) = captures[0].unwrap();
+            // An empty match must still move forward.
+            pos = if end == start { end + 1 } else { end };
+            matches.push(captures);
+        }
+        regexp.put(vm, Symbol::lastIndex(), JsValue::new(0), true)?;
+    } else {
+        let search = if search.is_undefined() {
+            "undefined".to_owned()
+        } else {
+            search.to_string(vm)?
+        };
+        let search = search.encode_utf16().collect::<Vec<u16>>();
+        let advance = search.len().max(1);
+        let mut pos = find_utf16(&input, &search, 0);
+        while let Some(p) = pos {
+            matches.push(vec![Some((p, p + search.len()))]);
+            pos = find_utf16(&input, &search, p + advance);
+        }
+    }
+
+    let replace = args.at(1);
+    let template = if replace.is_callable() {
+        None
+    } else {
+        Some(replace.to_string(vm)?.encode_utf16().collect::<Vec<u16>>())
+    };
+    let mut result = Vec::with_capacity(input.len());
+    let mut last = 0;
+    for captures in matches.iter() {
+        let (start, end) = captures[0].unwrap();
+        result.extend_from_slice(&input[last..start]);
+        match template {
+            Some(ref template) => {
+                result.extend(get_substitution(&input, captures, &names, template));
+            }
+            None => {
+                result.extend(call_replacer(
+                    vm, replace, &input, *string, captures, &names,
+                )?);
+            }
+        }
+        last = end;
+    }
+    result.extend_from_slice(&input[last..]);
+    Ok(JsValue::new(JsString::new(
+        vm,
+        String::from_utf16_lossy(&result),
+    )))
+}
+
+pub(crate) fn init_string(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let mut proto = ctx.new_local(vm.global_data().string_prototype.unwrap());
+    let name = vm.intern("String");
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        string_constructor,
+        1,
+    ));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(*proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    define_method(vm, *ctor, "fromCharCode", string_from_char_code, 1);
+    define_method(vm, *ctor, "fromCodePoint", string_from_code_point, 1);
+    define_method(vm, *proto, "normalize", string_normalize, 0);
+    define_method(vm, *proto, "localeCompare", string_locale_compare, 1);
+    define_method(vm, *proto, "matchAll", string_match_all, 1);
+    define_method(vm, *proto, "replaceAll", string_replace_all, 2);
+    define_method(vm, *proto, "at", string_at, 1);
+
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        heap::cell::Gc,
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_single_char_cache() {
+        let mut vm = VirtualMachine::new(Options::default());
+        assert!(vm
+            .eval("a = String.fromCharCode(65); b = String.fromCharCode(65);")
+            .is_ok());
+        let global = vm.global_object();
+        let a = vm.get(global, "a").unwrap_or_else(|_| panic!());
+        let b = vm.get(global, "b").unwrap_or_else(|_| panic!());
+        assert_eq!(a.as_string().as_str(), "A");
+        assert!(Gc::ptr_eq(a.as_string(), b.as_string()));
+
+        let before = vm.space().allocation_count();
+        for _ in 0..100 {
+            let s = vm.char_string('A');
+            assert!(Gc::ptr_eq(s, a.as_string()));
+            let s = vm.int_string(42);
+            assert_eq!(s.as_str(), "42");
+        }
+        // only the first `int_string(42)` allocates.
+        assert_eq!(vm.space().allocation_count(), before + 1);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_int_string_conversions() {
+        let mut vm = VirtualMachine::new(Options::default());
+        assert!(vm
+            .eval(
+                "a = String(12);
+                 b = '' + 12;
+                 c = 12 + '';
+                 d = Object.keys([5, 6])[1];
+                 e = 'x' + 12;"
+            )
+            .is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        let (a, b, c, d, e) = (get("a"), get("b"), get("c"), get("d"), get("e"));
+        assert_eq!(a.as_string().as_str(), "12");
+        assert!(Gc::ptr_eq(a.as_string(), b.as_string()));
+        assert!(Gc::ptr_eq(a.as_string(), c.as_string()));
+        assert_eq!(e.as_string().as_str(), "x12");
+        let one = vm.int_string(1);
+        assert!(Gc::ptr_eq(d.as_string(), one));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_code_points_and_normalize() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = String.fromCodePoint(0x1F600, 65);
+             b = String.fromCharCode(0xD83D, 0xDE00, 0x10041);
+             c = 'e\\u0301'.normalize();
+             d = 'e\\u0301'.normalize('NFD');",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get = |vm: &mut VirtualMachine, name: &str| {
+            let val = vm.get(global, name).unwrap_or_else(|_| panic!());
+            val.as_string().as_str().to_owned()
+        };
+        assert_eq!(get(&mut vm, "a"), "\u{1f600}A");
+        assert_eq!(get(&mut vm, "b"), "\u{1f600}A");
+        assert_eq!(get(&mut vm, "c"), "\u{e9}");
+        assert_eq!(get(&mut vm, "d"), "e\u{301}");
+        assert!(vm.eval("String.fromCodePoint(0x110000);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_locale_compare() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "a = 'a10'.localeCompare('a2');
+             b = 'a10'.localeCompare('a2', 'en', { numeric: true });
+             c = 'Stra\\u00dfe'.localeCompare('stra\\u00dfe', 'en', { sensitivity: 'base' });
+             d = 'a'.localeCompare('A');
+             e = 'r\\u00e9sum\\u00e9'.localeCompare('RESUME', 'en', { sensitivity: 'accent' });
+             function numeric(x, y) {
+                 return x.localeCompare(y, 'en', { numeric: true });
+             }
+             f = ['b', 'a10', 'A', 'a2', 'a'].sort(numeric).join(' ');",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get =
+            |vm: &mut VirtualMachine, name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get(&mut vm, "a").as_int32(), -1);
+        assert_eq!(get(&mut vm, "b").as_int32(), 1);
+        assert_eq!(get(&mut vm, "c").as_int32(), 0);
+        assert_eq!(get(&mut vm, "d").as_int32(), -1);
+        assert_eq!(get(&mut vm, "e").as_int32(), 1);
+        assert_eq!(get(&mut vm, "f").as_string().as_str(), "a A a2 a10 b");
+        assert!(vm
+            .eval("'a'.localeCompare('b', 'en', { sensitivity: 'loud' });")
+            .is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_match_all() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "re = /(?<year>\\d{4})-(?<month>\\d{2})/g;
+             it = '2020-01 and 2021-12'.matchAll(re);
+             first = it.next();
+             second = it.next();
+             third = it.next();
+             year = first.value.groups.year;
+             month = second.value[2];
+             index = second.value.index;
+             digits = [...'a1b2'.matchAll(/\\d/g)];
+             empty = [...'abc'.matchAll('')];",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let year = vm.get(global, "year").unwrap_or_else(|_| panic!());
+        assert_eq!(year.as_string().as_str(), "2020");
+        let month = vm.get(global, "month").unwrap_or_else(|_| panic!());
+        assert_eq!(month.as_string().as_str(), "12");
+        let index = vm.get(global, "index").unwrap_or_else(|_| panic!());
+        assert_eq!(index.as_int32(), 12);
+        let third = vm.get(global, "third").unwrap_or_else(|_| panic!());
+        let done = vm
+            .get(third.as_object(), "done")
+            .unwrap_or_else(|_| panic!());
+        assert!(done.to_boolean());
+        let digits = vm.get(global, "digits").unwrap_or_else(|_| panic!());
+        let len = vm
+            .get(digits.as_object(), "length")
+            .unwrap_or_else(|_| panic!());
+        assert_eq!(len.as_int32(), 2);
+        let empty = vm.get(global, "empty").unwrap_or_else(|_| panic!());
+        let len = vm
+            .get(empty.as_object(), "length")
+            .unwrap_or_else(|_| panic!());
+        assert_eq!(len.as_int32(), 4);
+        // the original regexp is cloned, its lastIndex is left alone.
+        let re = vm.get(global, "re").unwrap_or_else(|_| panic!());
+        let last = vm
+            .get(re.as_object(), "lastIndex")
+            .unwrap_or_else(|_| panic!());
+        assert_eq!(last.as_int32(), 0);
+
+        assert!(vm.eval("'abc'.matchAll(/b/);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function withOffset(m, offset) { return m + offset; }
+             function wrap(m, digit, offset, s) { return '[' + digit + offset + s.length + ']'; }
+             a = 'aaaa'.replaceAll('aa', 'b');
+             b = 'x-y-z'.replaceAll('-', '$&$&');
+             c = 'ab'.replaceAll('', '-');
+             d = 'abab'.replaceAll('b', withOffset);
+             e = 'a1b2'.replaceAll(/(\\d)/g, wrap);
+             f = '2020-01 2021-12'.replaceAll(/(?<y>\\d+)-(\\d+)/g, '$2/$<y>');
+             g = 'abc'.replaceAll('b', '$`$\\'$$$1');",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get = |vm: &mut VirtualMachine, name: &str| {
+            let val = vm.get(global, name).unwrap_or_else(|_| panic!());
+            val.as_string().as_str().to_owned()
+        };
+        assert_eq!(get(&mut vm, "a"), "bb");
+        assert_eq!(get(&mut vm, "b"), "x--y--z");
+        assert_eq!(get(&mut vm, "c"), "-a-b-");
+        assert_eq!(get(&mut vm, "d"), "ab1ab3");
+        assert_eq!(get(&mut vm, "e"), "a[114]b[234]");
+        assert_eq!(get(&mut vm, "f"), "01/2020 12/2021");
+        assert_eq!(get(&mut vm, "g"), "aac$$1c");
+
+        assert!(vm.eval("'abc'.replaceAll(/b/, 'x');").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_index_and_length() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "s = 'abc';
+             i = 1;
+             a = s[i];
+             b = 'abc'[2];
+             c = s[3];
+             len = s.length;
+             wide = '\\u00e9\\u{1F600}'.length;
+             s[0] = 'x';
+             s.length = 1;
+             first = s[0];
+             after = s.length;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let get =
+            |vm: &mut VirtualMachine, name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert_eq!(get(&mut vm, "a").as_string().as_str(), "b");
+        assert_eq!(get(&mut vm, "b").as_string().as_str(), "c");
+        assert!(get(&mut vm, "c").is_undefined());
+        assert_eq!(get(&mut vm, "len").as_int32(), 3);
+        assert_eq!(get(&mut vm, "wide").as_int32(), 3);
+        assert_eq!(get(&mut vm, "first").as_string().as_str(), "a");
+        assert_eq!(get(&mut vm, "after").as_int32(), 3);
+        VirtualMachineRef::dispose(vm);
+
+        let mut vm = VirtualMachine::new(Options::builder().strict(true).build());
+        assert!(vm.eval("var t = 'abc'; t.length = 1;").is_err());
+        assert!(vm.eval("var t = 'abc'; t[1] = 'x';").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_at() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "out = ['abc'.at(0), 'abc'.at(0 - 1), 'abc'.at(1.5), '\u{e9}x'.at(0 - 1)].join();
+             past = 'abc'.at(3);
+             before = 'abc'.at(0 - 4);
+             empty = ''.at(0);",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "a,c,b,x");
+        for name in ["past", "before", "empty"].iter() {
+            assert!(vm
+                .get(global, *name)
+                .unwrap_or_else(|_| panic!())
+                .is_undefined());
+        }
+        // a code unit, not a code point.
+        assert!(vm.eval("half = '\u{1F600}'.at(0);").is_ok());
+        let half = vm.get(global, "half").unwrap_or_else(|_| panic!());
+        assert_eq!(half.as_string().as_str(), "\u{fffd}");
+        VirtualMachineRef::dispose(vm);
+    }
+}
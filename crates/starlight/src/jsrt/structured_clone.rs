@@ -0,0 +1,255 @@
+//! `structuredClone(value, { transfer })`
+//!
+//! Serialization and deserialization happen in a single pass since the clone never leaves the
+//! VM. Buffers in the transfer list are detached up front and their bytes are moved to the
+//! buffers of the clone, if cloning throws the bytes are moved back.
+use std::collections::HashMap;
+
+use crate::{
+    heap::{
+        cell::Gc,
+        context::{Local, LocalContext},
+    },
+    runtime::{
+        arguments::Arguments,
+        array::JsArray,
+        array_buffer::JsArrayBuffer,
+        date::JsDate,
+        error::JsTypeError,
+        iterator::iterate_or_arraylike,
+        object::{EnumerationMode, JsObject, ObjectTag},
+        string::JsString,
+        typed_array::JsTypedArray,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// There is no `DOMException` so a `DataCloneError` is reported as a `TypeError`.
+fn data_clone_error(vm: &mut VirtualMachine, msg: &str) -> JsValue {
+    let msg = JsString::new(vm, msg);
+    JsValue::new(JsTypeError::new(vm, msg, None))
+}
+
+struct Cloner<'a> {
+    ctx: &'a LocalContext<'a>,
+    /// Objects cloned so far and their clones, shared and cyclic references stay that way in
+    /// the clone. Clones are kept alive through their locals.
+    memory: HashMap<Gc<JsObject>, Local<'a, Gc<JsObject>>>,
+}
+
+impl<'a> Cloner<'a> {
+    fn remember(&mut self, source: Gc<JsObject>, clone: Local<'a, Gc<JsObject>>) -> Gc<JsObject> {
+        let obj = *clone;
+        self.memory.insert(source, clone);
+        obj
+    }
+
+    fn clone_value(&mut self, vm: &mut VirtualMachine, value: JsValue) -> Result<JsValue, JsValue> {
+        if !value.is_object() {
+            if value.is_symbol() {
+                return Err(data_clone_error(vm, "Symbol() could not be cloned"));
+            }
+            return Ok(value);
+        }
+        let obj = value.as_object();
+        if let Some(clone) = self.memory.get(&obj) {
+            return Ok(JsValue::new(**clone));
+        }
+        let clone = match obj.tag() {
+            // there is a single agent, sharing the buffer is sharing the object.
+            ObjectTag::ArrayBuffer if obj.as_array_buffer().is_shared() => obj,
+            ObjectTag::ArrayBuffer => {
+                let buffer = obj.as_array_buffer();
+                if buffer.is_detached() {
+                    return Err(data_clone_error(
+                        vm,
+                        "A detached ArrayBuffer could not be cloned",
+                    ));
+                }
+                let data = buffer.data().to_vec();
+                let clone = self.ctx.new_local(JsArrayBuffer::new(vm, data));
+                self.remember(obj, clone)
+            }
+            _ if obj.is_typed_array() => {
+                let view = obj.as_typed_array();
+                let (kind, offset, length) = (view.kind(), view.byte_offset(), view.length());
+                if view.buffer().as_array_buffer().is_detached() {
+                    return Err(data_clone_error(
+                        vm,
+                        "A view of a detached ArrayBuffer could not be cloned",
+                    ));
+                }
+                // views of the same buffer keep sharing one buffer in the clone.
+                let buffer = self
+                    .clone_value(vm, JsValue::new(view.buffer()))?
+                    .as_object();
+                let clone = JsTypedArray::new(vm, kind, buffer, offset, length);
+                let clone = self.ctx.new_local(clone);
+                self.remember(obj, clone)
+            }
+            ObjectTag::Date => {
+                let clone = self.ctx.new_local(JsDate::new(vm, obj.as_date().time()));
+                self.remember(obj, clone)
+            }
+            ObjectTag::Array => {
+                let length = obj.elements.length();
+                let clone = self.ctx.new_local(JsArray::new(vm, length));
+                let clone = self.remember(obj, clone);
+                self.clone_properties(vm, obj, clone)?;
+                clone
+            }
+            ObjectTag::Ordinary if !obj.is_callable() => {
+                let clone = vm.new_object(self.ctx);
+                let clone = self.remember(obj, clone);
+                self.clone_properties(vm, obj, clone)?;
+                clone
+            }
+            _ => {
+                let msg = format!("#<{}> could not be cloned", obj.class().name);
+                return Err(data_clone_error(vm, &msg));
+            }
+        };
+        Ok(JsValue::new(clone))
+    }
+
+    /// Clones own enumerable properties of `source` into `target`.
+    fn clone_properties(
+        &mut self,
+        vm: &mut VirtualMachine,
+        source: Gc<JsObject>,
+        mut target: Gc<JsObject>,
+    ) -> Result<(), JsValue> {
+        let mut keys = vec![];
+        source.get_own_property_names(vm, &mut |name, _| keys.push(name), EnumerationMode::Default);
+        for key in keys {
+            let value = source.get(vm, key)?;
+            let value = self.clone_value(vm, value)?;
+            target.put(vm, key, value, true)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `transfer` option of `structuredClone`, every entry must be a distinct attached
+/// `ArrayBuffer`.
+fn transfer_list(vm: &mut VirtualMachine, options: JsValue) -> Result<Vec<Gc<JsObject>>, JsValue> {
+    if !options.is_object() {
+        return Ok(vec![]);
+    }
+    let name = vm.intern("transfer");
+    let transfer = options.as_object().get(vm, name)?;
+    if transfer.is_undefined() {
+        return Ok(vec![]);
+    }
+    let mut list = vec![];
+    iterate_or_arraylike(vm, transfer, &mut |vm, value| {
+        if !value.is_object() || value.as_object().tag() != ObjectTag::ArrayBuffer {
+            return Err(data_clone_error(vm, "Value not transferable"));
+        }
+        let buffer = value.as_object();
+        if buffer.as_array_buffer().is_shared() {
+            return Err(data_clone_error(
+                vm,
+                "A SharedArrayBuffer could not be transferred",
+            ));
+        }
+        if buffer.as_array_buffer().is_detached() {
+            return Err(data_clone_error(
+                vm,
+                "An ArrayBuffer is detached and could not be cloned",
+            ));
+        }
+        if list.iter().any(|other| Gc::ptr_eq(*other, buffer)) {
+            return Err(data_clone_error(
+                vm,
+                "Duplicate ArrayBuffer in the transfer list",
+            ));
+        }
+        list.push(buffer);
+        Ok(())
+    })?;
+    Ok(list)
+}
+
+/// `structuredClone(value[, options])`
+pub fn structured_clone(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let transfer = transfer_list(vm, args.at(1))?;
+    let ctx = vm.space().new_local_context();
+    let mut cloner = Cloner {
+        ctx: &ctx,
+        memory: HashMap::new(),
+    };
+    for &buffer in transfer.iter() {
+        let mut source = buffer;
+        let data = source.as_array_buffer_mut().detach();
+        let target = ctx.new_local(JsArrayBuffer::new(vm, data));
+        cloner.memory.insert(buffer, target);
+    }
+    match cloner.clone_value(vm, args.at(0)) {
+        Ok(clone) => Ok(clone),
+        Err(error) => {
+            for &buffer in transfer.iter() {
+                let mut source = buffer;
+                let mut target = *cloner.memory[&buffer];
+                let data = target.as_array_buffer_mut().detach();
+                source.as_array_buffer_mut().attach(data);
+            }
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_transfer() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "buffer = new ArrayBuffer(4);
+             view = new Uint8Array(buffer);
+             view[0] = 1; view[3] = 4;
+             moved = structuredClone({ v: view, b: buffer }, { transfer: [buffer] });
+             movedOut = '' + buffer.detached + ',' + buffer.byteLength + ',' + view.length + ','
+                 + moved.b.detached + ',' + (moved.v.buffer === moved.b) + ','
+                 + moved.v[0] + ',' + moved.v[3];
+
+             source = new Uint8Array([5, 6, 7]);
+             copy = structuredClone(source);
+             copy[0] = 9;
+             copyOut = '' + source[0] + ',' + copy[0] + ',' + copy[2] + ','
+                 + source.buffer.detached + ',' + (copy.buffer === source.buffer);
+
+             kept = new ArrayBuffer(2);
+             function notCloneable() {}",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let expected = [
+            ("movedOut", "true,0,0,false,true,1,4"),
+            ("copyOut", "5,9,7,false,false"),
+        ];
+        for (name, out) in expected.iter() {
+            let value = vm.get(global, name).unwrap_or_else(|_| panic!());
+            assert_eq!(value.as_string().as_str(), *out);
+        }
+
+        // a failed clone gives transferred buffers their bytes back.
+        let res = vm.eval("structuredClone({ f: notCloneable }, { transfer: [kept] });");
+        assert!(res.is_err());
+        assert!(vm
+            .eval("keptOut = '' + kept.detached + ',' + kept.byteLength;")
+            .is_ok());
+        let value = vm.get(global, "keptOut").unwrap_or_else(|_| panic!());
+        assert_eq!(value.as_string().as_str(), "false,2");
+
+        assert!(vm.eval("new Uint8Array(buffer);").is_err());
+        assert!(vm.eval("buffer.slice(0);").is_err());
+        assert!(vm
+            .eval("structuredClone(1, { transfer: [buffer] });")
+            .is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,175 @@
+use super::define_method;
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        attributes::*,
+        error::JsTypeError,
+        function::JsNativeFunction,
+        property_descriptor::{AccessorDescriptor, DataDescriptor},
+        string::JsString,
+        symbol::{JsSymbol, Symbol},
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// section 19.4.1.1 Symbol([description])
+pub fn symbol_constructor(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.ctor_call {
+        let msg = JsString::new(vm, "Symbol is not a constructor");
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    let description = if args.at(0).is_undefined() {
+        None
+    } else {
+        Some(args.at(0).to_string(vm)?)
+    };
+    Ok(JsValue::new(JsSymbol::new_unique(
+        vm,
+        description.as_deref(),
+    )))
+}
+
+/// section 19.4.3.2.1 SymbolDescriptiveString(sym)
+pub(crate) fn symbol_descriptive_string(vm: &VirtualMachine, sym: Gc<JsSymbol>) -> String {
+    format!("Symbol({})", sym.description(vm).unwrap_or_default())
+}
+
+/// section 19.4.3 thisSymbolValue(value)
+fn this_symbol_value(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    method: &str,
+) -> Result<Gc<JsSymbol>, JsValue> {
+    if !this.is_symbol() {
+        let msg = JsString::new(
+            vm,
+            format!(
+                "Symbol.prototype.{} requires that 'this' be a Symbol",
+                method
+            ),
+        );
+        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+    }
+    Ok(this.as_symbol())
+}
+
+/// section 19.4.3.2 get Symbol.prototype.description
+pub fn symbol_description(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let sym = this_symbol_value(vm, args.this, "description")?;
+    Ok(match sym.description(vm) {
+        Some(description) => JsValue::new(JsString::new(vm, description)),
+        None => JsValue::undefined(),
+    })
+}
+
+/// section 19.4.3.3 Symbol.prototype.toString()
+pub fn symbol_to_string(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let sym = this_symbol_value(vm, args.this, "toString")?;
+    let s = symbol_descriptive_string(vm, sym);
+    Ok(JsValue::new(JsString::new(vm, s)))
+}
+
+pub(crate) fn init_symbol(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let mut proto = ctx.new_local(vm.global_data().symbol_prototype.unwrap());
+    let name = vm.intern("Symbol");
+    let mut sym = ctx.new_local(JsNativeFunction::new_constructor(
+        vm,
+        name,
+        symbol_constructor,
+        0,
+    ));
+    let _ = sym.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(*proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*sym), W | C),
+        false,
+    );
+    let iterator = JsSymbol::new(vm, Symbol::iterator());
+    let name = vm.intern("iterator");
+    let _ = sym.define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(iterator), NONE),
+        false,
+    );
+    let to_string_tag = JsSymbol::new(vm, Symbol::to_string_tag());
+    let name = vm.intern("toStringTag");
+    let _ = sym.define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(to_string_tag), NONE),
+        false,
+    );
+
+    let name = vm.intern("description");
+    let getter = JsNativeFunction::new(vm, name, symbol_description, 0);
+    let _ = proto.define_own_property(
+        vm,
+        name,
+        &*AccessorDescriptor::new(JsValue::new(getter), JsValue::undefined(), C),
+        false,
+    );
+    define_method(vm, *proto, "toString", symbol_to_string, 0);
+
+    let name = vm.intern("Symbol");
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*sym), W | C),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        runtime::error::JsTypeError,
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_symbol_description() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "var nothing;
+             x = Symbol('x');
+             described = x.description === 'x';
+             undescribed = Symbol().description === nothing;
+             empty = Symbol('').description === '';
+             unique = Symbol('x') === x;
+             out = x.toString() + ',' + Symbol().toString() + ',' + String(Symbol('y'));",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let mut get = |name: &str| vm.get(global, name).unwrap_or_else(|_| panic!());
+        assert!(get("described").as_boolean());
+        assert!(get("undescribed").as_boolean());
+        assert!(get("empty").as_boolean());
+        assert!(!get("unique").as_boolean());
+        assert_eq!(
+            get("out").as_string().as_str(),
+            "Symbol(x),Symbol(),Symbol(y)"
+        );
+
+        // only explicit conversions turn a symbol into a string.
+        for source in &["'' + Symbol();", "'a' + Symbol('b');", "new Symbol();"] {
+            match vm.eval(source) {
+                Err(e) => assert!(std::ptr::eq(
+                    e.as_object().class(),
+                    JsTypeError::get_class()
+                )),
+                Ok(_) => panic!("expected TypeError from {}", source),
+            }
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+}
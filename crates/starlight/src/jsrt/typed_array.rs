@@ -0,0 +1,618 @@
+use super::define_method;
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        arguments::Arguments,
+        array_buffer::JsArrayBuffer,
+        attributes::*,
+        error::{JsRangeError, JsTypeError},
+        function::{JsAPI, JsNativeFunction},
+        iterator::iterate_or_arraylike,
+        object::{JsObject, ObjectTag},
+        property_descriptor::{AccessorDescriptor, DataDescriptor},
+        string::JsString,
+        symbol::Symbol,
+        typed_array::{JsTypedArray, TypedArrayKind},
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// Largest buffer that can be allocated.
+const MAX_BYTE_LENGTH: usize = i32::MAX as usize;
+
+pub(crate) fn type_error(vm: &mut VirtualMachine, msg: &str) -> JsValue {
+    let msg = JsString::new(vm, msg);
+    JsValue::new(JsTypeError::new(vm, msg, None))
+}
+
+pub(crate) fn range_error(vm: &mut VirtualMachine, msg: &str) -> JsValue {
+    let msg = JsString::new(vm, msg);
+    JsValue::new(JsRangeError::new(vm, msg, None))
+}
+
+/// section 7.1.22 ToIndex(value)
+pub(crate) fn to_index(
+    vm: &mut VirtualMachine,
+    value: JsValue,
+    what: &str,
+) -> Result<usize, JsValue> {
+    if value.is_undefined() {
+        return Ok(0);
+    }
+    let n = value.to_number(vm)?;
+    let integer = if n.is_nan() { 0.0 } else { n.trunc() };
+    if !(0.0..=MAX_BYTE_LENGTH as f64).contains(&integer) {
+        return Err(range_error(vm, &format!("Invalid {}: {}", what, n)));
+    }
+    Ok(integer as usize)
+}
+
+/// Resolves a relative `start`/`end` argument against `len`, `default` is used for
+/// `undefined`.
+fn relative_index(
+    vm: &mut VirtualMachine,
+    value: JsValue,
+    len: usize,
+    default: usize,
+) -> Result<usize, JsValue> {
+    if value.is_undefined() {
+        return Ok(default);
+    }
+    let n = value.to_number(vm)?;
+    let n = if n.is_nan() { 0.0 } else { n.trunc() };
+    Ok(if n < 0.0 {
+        (len as f64 + n).max(0.0) as usize
+    } else {
+        n.min(len as f64) as usize
+    })
+}
+
+fn this_array_buffer(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    method: &str,
+) -> Result<Gc<JsObject>, JsValue> {
+    if !this.is_object()
+        || this.as_object().tag() != ObjectTag::ArrayBuffer
+        || this.as_object().as_array_buffer().is_shared()
+    {
+        let msg = format!(
+            "ArrayBuffer.prototype.{} called on incompatible receiver",
+            method
+        );
+        return Err(type_error(vm, &msg));
+    }
+    Ok(this.as_object())
+}
+
+fn this_shared_array_buffer(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    method: &str,
+) -> Result<Gc<JsObject>, JsValue> {
+    if !this.is_object()
+        || this.as_object().tag() != ObjectTag::ArrayBuffer
+        || !this.as_object().as_array_buffer().is_shared()
+    {
+        let msg = format!(
+            "SharedArrayBuffer.prototype.{} called on incompatible receiver",
+            method
+        );
+        return Err(type_error(vm, &msg));
+    }
+    Ok(this.as_object())
+}
+
+fn this_typed_array(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    method: &str,
+) -> Result<Gc<JsObject>, JsValue> {
+    if !this.is_object() || !this.as_object().is_typed_array() {
+        let msg = format!(
+            "TypedArray.prototype.{} called on incompatible receiver",
+            method
+        );
+        return Err(type_error(vm, &msg));
+    }
+    Ok(this.as_object())
+}
+
+/// section 25.1.3.1 ArrayBuffer(length)
+pub fn array_buffer_constructor(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if !args.ctor_call {
+        return Err(type_error(vm, "Constructor ArrayBuffer requires 'new'"));
+    }
+    let length = to_index(vm, args.at(0), "array buffer length")?;
+    Ok(JsValue::new(JsArrayBuffer::new(vm, vec![0; length])))
+}
+
+/// section 25.1.4.1 ArrayBuffer.isView(arg)
+pub fn array_buffer_is_view(
+    _vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let arg = args.at(0);
+    Ok(JsValue::new(
+        arg.is_object() && arg.as_object().is_typed_array(),
+    ))
+}
+
+/// section 25.1.5.1 get ArrayBuffer.prototype.byteLength
+pub fn array_buffer_byte_length(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let buffer = this_array_buffer(vm, args.this, "byteLength")?;
+    Ok(JsValue::new(buffer.as_array_buffer().byte_length() as f64))
+}
+
+/// get ArrayBuffer.prototype.detached
+pub fn array_buffer_detached(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let buffer = this_array_buffer(vm, args.this, "detached")?;
+    Ok(JsValue::new(buffer.as_array_buffer().is_detached()))
+}
+
+/// section 25.1.5.3 ArrayBuffer.prototype.slice(start, end)
+pub fn array_buffer_slice(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let buffer = ctx.new_local(this_array_buffer(vm, args.this, "slice")?);
+    if buffer.as_array_buffer().is_detached() {
+        return Err(type_error(
+            vm,
+            "ArrayBuffer.prototype.slice called on a detached ArrayBuffer",
+        ));
+    }
+    let len = buffer.as_array_buffer().byte_length();
+    let start = relative_index(vm, args.at(0), len, 0)?;
+    let end = relative_index(vm, args.at(1), len, len)?;
+    // `start` and `end` conversions can detach the buffer.
+    let data = buffer.as_array_buffer().data();
+    let bytes = if start < end && end <= data.len() {
+        data[start..end].to_vec()
+    } else if start < end {
+        return Err(type_error(
+            vm,
+            "ArrayBuffer.prototype.slice called on a detached ArrayBuffer",
+        ));
+    } else {
+        vec![]
+    };
+    Ok(JsValue::new(JsArrayBuffer::new(vm, bytes)))
+}
+
+/// section 25.2.2.1 SharedArrayBuffer(length)
+pub fn shared_array_buffer_constructor(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if !args.ctor_call {
+        return Err(type_error(
+            vm,
+            "Constructor SharedArrayBuffer requires 'new'",
+        ));
+    }
+    let length = to_index(vm, args.at(0), "array buffer length")?;
+    Ok(JsValue::new(JsArrayBuffer::new_shared(vm, vec![0; length])))
+}
+
+/// section 25.2.4.1 get SharedArrayBuffer.prototype.byteLength
+pub fn shared_array_buffer_byte_length(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let buffer = this_shared_array_buffer(vm, args.this, "byteLength")?;
+    Ok(JsValue::new(buffer.as_array_buffer().byte_length() as f64))
+}
+
+/// section 25.2.4.3 SharedArrayBuffer.prototype.slice(start, end)
+pub fn shared_array_buffer_slice(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let buffer = ctx.new_local(this_shared_array_buffer(vm, args.this, "slice")?);
+    let len = buffer.as_array_buffer().byte_length();
+    let start = relative_index(vm, args.at(0), len, 0)?;
+    let end = relative_index(vm, args.at(1), len, len)?;
+    let bytes = if start < end {
+        buffer.as_array_buffer().data()[start..end].to_vec()
+    } else {
+        vec![]
+    };
+    Ok(JsValue::new(JsArrayBuffer::new_shared(vm, bytes)))
+}
+
+/// section 23.2.5.1 TypedArray(...args)
+fn typed_array_constructor(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+    kind: TypedArrayKind,
+) -> Result<JsValue, JsValue> {
+    if !args.ctor_call {
+        let msg = format!("Constructor {} requires 'new'", kind.name());
+        return Err(type_error(vm, &msg));
+    }
+    let ctx = vm.space().new_local_context();
+    let size = kind.element_size();
+    let first = args.at(0);
+    if !first.is_object() {
+        let length = to_index(vm, first, "typed array length")?;
+        if length > MAX_BYTE_LENGTH / size {
+            return Err(range_error(vm, "Array buffer allocation failed"));
+        }
+        let buffer = ctx.new_local(JsArrayBuffer::new(vm, vec![0; length * size]));
+        return Ok(JsValue::new(JsTypedArray::new(
+            vm, kind, *buffer, 0, length,
+        )));
+    }
+
+    let source = ctx.new_local(first.as_object());
+    if source.tag() == ObjectTag::ArrayBuffer {
+        // section 23.2.5.1.3 InitializeTypedArrayFromArrayBuffer
+        let offset = to_index(vm, args.at(1), "typed array offset")?;
+        if offset % size != 0 {
+            let msg = format!(
+                "start offset of {} should be a multiple of {}",
+                kind.name(),
+                size
+            );
+            return Err(range_error(vm, &msg));
+        }
+        let length = if args.at(2).is_undefined() {
+            None
+        } else {
+            Some(to_index(vm, args.at(2), "typed array length")?)
+        };
+        let buffer = source.as_array_buffer();
+        if buffer.is_detached() {
+            return Err(type_error(
+                vm,
+                "Cannot construct a typed array on a detached ArrayBuffer",
+            ));
+        }
+        let byte_length = buffer.byte_length();
+        let length = match length {
+            Some(length) if offset + length * size <= byte_length => length,
+            None if byte_length % size != 0 => {
+                let msg = format!(
+                    "byte length of {} should be a multiple of {}",
+                    kind.name(),
+                    size
+                );
+                return Err(range_error(vm, &msg));
+            }
+            None if offset <= byte_length => (byte_length - offset) / size,
+            Some(length) => {
+                let msg = format!("Invalid typed array length: {}", length);
+                return Err(range_error(vm, &msg));
+            }
+            None => {
+                let msg = format!(
+                    "Start offset {} is outside the bounds of the buffer",
+                    offset
+                );
+                return Err(range_error(vm, &msg));
+            }
+        };
+        return Ok(JsValue::new(JsTypedArray::new(
+            vm, kind, *source, offset, length,
+        )));
+    }
+
+    let mut values = vec![];
+    if source.is_typed_array() {
+        // section 23.2.5.1.2 InitializeTypedArrayFromTypedArray
+        let view = source.as_typed_array();
+        if view.buffer().as_array_buffer().is_detached() {
+            return Err(type_error(
+                vm,
+                "Cannot construct a typed array from a detached ArrayBuffer",
+            ));
+        }
+        for index in 0..view.length() {
+            values.push(view.get(index).unwrap().number());
+        }
+    } else {
+        iterate_or_arraylike(vm, *source, &mut |vm, value| {
+            values.push(value.to_number(vm)?);
+            Ok(())
+        })?;
+    }
+    if values.len() > MAX_BYTE_LENGTH / size {
+        return Err(range_error(vm, "Array buffer allocation failed"));
+    }
+    let buffer = ctx.new_local(JsArrayBuffer::new(vm, vec![0; values.len() * size]));
+    let array = JsTypedArray::new(vm, kind, *buffer, 0, values.len());
+    for (index, value) in values.into_iter().enumerate() {
+        array.as_typed_array().set(index, value);
+    }
+    Ok(JsValue::new(array))
+}
+
+macro_rules! typed_array_constructors {
+    ($($kind: ident: $name: ident;)*) => {
+        $(
+            fn $name(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+                typed_array_constructor(vm, args, TypedArrayKind::$kind)
+            }
+        )*
+
+        fn constructor_of(kind: TypedArrayKind) -> JsAPI {
+            match kind {
+                $(TypedArrayKind::$kind => $name,)*
+            }
+        }
+    };
+}
+
+typed_array_constructors! {
+    Int8: int8_array_constructor;
+    Uint8: uint8_array_constructor;
+    Uint8Clamped: uint8_clamped_array_constructor;
+    Int16: int16_array_constructor;
+    Uint16: uint16_array_constructor;
+    Int32: int32_array_constructor;
+    Uint32: uint32_array_constructor;
+    Float32: float32_array_constructor;
+    Float64: float64_array_constructor;
+}
+
+/// section 23.2.3.1 get %TypedArray%.prototype.buffer
+pub fn typed_array_buffer(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let array = this_typed_array(vm, args.this, "buffer")?;
+    Ok(JsValue::new(array.as_typed_array().buffer()))
+}
+
+/// section 23.2.3.2 get %TypedArray%.prototype.byteLength
+pub fn typed_array_byte_length(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let array = this_typed_array(vm, args.this, "byteLength")?;
+    Ok(JsValue::new(array.as_typed_array().byte_length() as f64))
+}
+
+/// section 23.2.3.3 get %TypedArray%.prototype.byteOffset
+pub fn typed_array_byte_offset(
+    vm: &mut VirtualMachine,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let array = this_typed_array(vm, args.this, "byteOffset")?;
+    Ok(JsValue::new(array.as_typed_array().byte_offset() as f64))
+}
+
+/// section 23.2.3.19 get %TypedArray%.prototype.length
+pub fn typed_array_length(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let array = this_typed_array(vm, args.this, "length")?;
+    Ok(JsValue::new(array.as_typed_array().length() as f64))
+}
+
+/// section 23.2.3.25 %TypedArray%.prototype.slice(start, end), copies the elements into a new
+/// buffer.
+pub fn typed_array_slice(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let array = ctx.new_local(this_typed_array(vm, args.this, "slice")?);
+    let len = array.as_typed_array().length();
+    let start = relative_index(vm, args.at(0), len, 0)?;
+    let end = relative_index(vm, args.at(1), len, len)?;
+    let count = end.saturating_sub(start);
+    // `start` and `end` conversions can detach the buffer.
+    let view = array.as_typed_array();
+    if count > 0 && view.buffer().as_array_buffer().is_detached() {
+        return Err(type_error(
+            vm,
+            "TypedArray.prototype.slice called on a detached ArrayBuffer",
+        ));
+    }
+    let kind = view.kind();
+    let size = kind.element_size();
+    let from = view.byte_offset() + start * size;
+    let bytes = view.buffer().as_array_buffer().data()[from..from + count * size].to_vec();
+    let buffer = ctx.new_local(JsArrayBuffer::new(vm, bytes));
+    Ok(JsValue::new(JsTypedArray::new(vm, kind, *buffer, 0, count)))
+}
+
+/// section 23.2.3.28 %TypedArray%.prototype.subarray(begin, end), the result is a view of the
+/// same buffer.
+pub fn typed_array_subarray(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let array = ctx.new_local(this_typed_array(vm, args.this, "subarray")?);
+    let len = array.as_typed_array().length();
+    let begin = relative_index(vm, args.at(0), len, 0)?;
+    let end = relative_index(vm, args.at(1), len, len)?;
+    let view = array.as_typed_array();
+    if view.buffer().as_array_buffer().is_detached() {
+        return Err(type_error(
+            vm,
+            "TypedArray.prototype.subarray called on a detached ArrayBuffer",
+        ));
+    }
+    let kind = view.kind();
+    let byte_offset = view.byte_offset() + begin * kind.element_size();
+    Ok(JsValue::new(JsTypedArray::new(
+        vm,
+        kind,
+        view.buffer(),
+        byte_offset,
+        end.saturating_sub(begin),
+    )))
+}
+
+fn define_getter(vm: &mut VirtualMachine, mut proto: Gc<JsObject>, name: &str, f: JsAPI) {
+    let name = vm.intern(name);
+    let getter = JsNativeFunction::new(vm, name, f, 0);
+    let _ = proto.define_own_property(
+        vm,
+        name,
+        &*AccessorDescriptor::new(JsValue::new(getter), JsValue::undefined(), C),
+        false,
+    );
+}
+
+/// Defines a global constructor `name` with `proto` as its prototype.
+fn define_constructor(
+    vm: &mut VirtualMachine,
+    name: &str,
+    f: JsAPI,
+    arity: u32,
+    mut proto: Gc<JsObject>,
+) -> Gc<JsObject> {
+    let ctx = vm.space().new_local_context();
+    let name = vm.intern(name);
+    let mut ctor = ctx.new_local(JsNativeFunction::new_constructor(vm, name, f, arity));
+    let _ = ctor.define_own_property(
+        vm,
+        Symbol::prototype(),
+        &*DataDescriptor::new(JsValue::new(proto), NONE),
+        false,
+    );
+    let _ = proto.define_own_property(
+        vm,
+        Symbol::constructor(),
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    let _ = vm.global_object().define_own_property(
+        vm,
+        name,
+        &*DataDescriptor::new(JsValue::new(*ctor), W | C),
+        false,
+    );
+    *ctor
+}
+
+pub(crate) fn init_typed_array(vm: &mut VirtualMachine) {
+    let ctx = vm.space().new_local_context();
+    let structure = vm.global_data().array_buffer_structure.unwrap();
+    let proto = ctx.new_local(structure.prototype().unwrap());
+    let ctor = ctx.new_local(define_constructor(
+        vm,
+        "ArrayBuffer",
+        array_buffer_constructor,
+        1,
+        *proto,
+    ));
+    define_method(vm, *ctor, "isView", array_buffer_is_view, 1);
+    define_getter(vm, *proto, "byteLength", array_buffer_byte_length);
+    define_getter(vm, *proto, "detached", array_buffer_detached);
+    define_method(vm, *proto, "slice", array_buffer_slice, 2);
+
+    let structure = vm.global_data().shared_array_buffer_structure.unwrap();
+    let proto = ctx.new_local(structure.prototype().unwrap());
+    define_constructor(
+        vm,
+        "SharedArrayBuffer",
+        shared_array_buffer_constructor,
+        1,
+        *proto,
+    );
+    define_getter(vm, *proto, "byteLength", shared_array_buffer_byte_length);
+    define_method(vm, *proto, "slice", shared_array_buffer_slice, 2);
+
+    let typed_array_proto = ctx.new_local(vm.global_data().typed_array_prototype.unwrap());
+    define_getter(vm, *typed_array_proto, "buffer", typed_array_buffer);
+    define_getter(
+        vm,
+        *typed_array_proto,
+        "byteLength",
+        typed_array_byte_length,
+    );
+    define_getter(
+        vm,
+        *typed_array_proto,
+        "byteOffset",
+        typed_array_byte_offset,
+    );
+    define_getter(vm, *typed_array_proto, "length", typed_array_length);
+    define_method(vm, *typed_array_proto, "slice", typed_array_slice, 2);
+    define_method(vm, *typed_array_proto, "subarray", typed_array_subarray, 2);
+
+    let bytes_per_element = vm.intern("BYTES_PER_ELEMENT");
+    for &kind in TypedArrayKind::ALL {
+        let structure = vm.global_data().typed_array_structures[kind as usize];
+        let mut proto = ctx.new_local(structure.prototype().unwrap());
+        let mut ctor = ctx.new_local(define_constructor(
+            vm,
+            kind.name(),
+            constructor_of(kind),
+            3,
+            *proto,
+        ));
+        let size = JsValue::new(kind.element_size() as i32);
+        let _ = ctor.define_own_property(
+            vm,
+            bytes_per_element,
+            &*DataDescriptor::new(size, NONE),
+            false,
+        );
+        let _ = proto.define_own_property(
+            vm,
+            bytes_per_element,
+            &*DataDescriptor::new(size, NONE),
+            false,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_views() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "buffer = new ArrayBuffer(8);
+             bytes = new Uint8Array(buffer);
+             words = new Uint16Array(buffer, 2, 2);
+             words[0] = 258;
+             words[5] = 1;
+             bytes[7] = 300;
+             copy = new Int8Array([1, 200, 3]);
+             sliced = new Uint8Array(buffer.slice(2, 4));
+             out = '' + bytes[2] + ',' + bytes[3] + ',' + bytes[7] + ',' + copy[1] + ','
+                 + sliced.length + ',' + sliced[0] + ',' + words.byteOffset + ',' + bytes[8];",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "2,1,44,-56,2,2,2,undefined");
+
+        assert!(vm.eval("new Uint16Array(buffer, 1);").is_err());
+        assert!(vm.eval("Uint8Array(1);").is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_subarray_and_slice() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "parent = new Int16Array([1, 2, 3, 4, 5, 6]);
+             sub = parent.subarray(1, 0 - 1);
+             sub[0] = 20;
+             parent[4] = 50;
+             copy = parent.slice(0 - 3);
+             copy[0] = 40;
+             out = '' + parent[1] + ',' + sub[3] + ',' + sub.length + ',' + sub.byteOffset + ','
+                 + (sub.buffer === parent.buffer) + ',' + parent[3] + ',' + copy[1] + ','
+                 + (copy.buffer === parent.buffer);
+             clamped = '' + parent.subarray(0 - 100, 100).length + ','
+                 + parent.slice(4, 2).length + ',' + parent.subarray(3).byteOffset;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "20,50,4,2,true,4,50,false");
+        let clamped = vm.get(global, "clamped").unwrap_or_else(|_| panic!());
+        assert_eq!(clamped.as_string().as_str(), "6,0,6");
+        VirtualMachineRef::dispose(vm);
+    }
+}
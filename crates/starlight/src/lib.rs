@@ -32,6 +32,8 @@ pub mod heap;
 pub mod interpreter;
 pub mod jsrt;
 pub mod runtime;
+#[cfg(feature = "serde-bridge")]
+pub mod serde_bridge;
 pub mod symbol_table;
 pub mod utils;
 pub mod vm;
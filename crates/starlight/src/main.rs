@@ -1,34 +1,113 @@
+use starlight::runtime::value::JsValue;
 use starlight::vm::VirtualMachine;
-use starlight::{
-    jsrt::jsrt_init,
-    vm::{Options, VirtualMachineRef},
-};
+use starlight::vm::{Options, VirtualMachineRef};
+use std::{fs, path::PathBuf, process, time::Instant};
 use structopt::StructOpt;
 
-fn main() {
-    let mut vm = VirtualMachine::new(Options::from_args());
-    jsrt_init(&mut vm);
-    let res = vm.eval(
-        r#"
+#[derive(StructOpt)]
+struct Cli {
+    #[structopt(flatten)]
+    options: Options,
+    #[structopt(
+        parse(from_os_str),
+        help = "Script to run, a small built-in script runs when none is given."
+    )]
+    file: Option<PathBuf>,
+    #[structopt(
+        long,
+        help = "Untimed runs of the script before the timed ones of --repeat.",
+        default_value = "0"
+    )]
+    warmup: usize,
+    #[structopt(
+        long,
+        help = "Run the script this many times in the same VM and print time and allocation statistics."
+    )]
+    repeat: Option<usize>,
+}
+
+const DEFAULT_SCRIPT: &str = r#"
 function foo(x,y) {
     arguments[0] = 5
     return x + y
-} 
+}
 
 print(foo(4,3))
 
-        "#,
+        "#;
+
+/// Minimum, median and maximum of `values`.
+fn summary<T: Ord + Copy>(values: &mut [T]) -> (T, T, T) {
+    values.sort_unstable();
+    (
+        values[0],
+        values[values.len() / 2],
+        values[values.len() - 1],
+    )
+}
+
+fn bench(
+    vm: &mut VirtualMachine,
+    source: &str,
+    warmup: usize,
+    repeat: usize,
+) -> Result<(), JsValue> {
+    for _ in 0..warmup {
+        vm.eval(source)?;
+    }
+    let collections = vm.gc_stats().collections;
+    let mut times = Vec::with_capacity(repeat);
+    let mut allocations = Vec::with_capacity(repeat);
+    for _ in 0..repeat {
+        let before = vm.space().allocation_count();
+        let start = Instant::now();
+        vm.eval(source)?;
+        times.push(start.elapsed());
+        allocations.push(vm.space().allocation_count() - before);
+    }
+    let ms = |time: std::time::Duration| time.as_secs_f64() * 1000.0;
+    let (min, median, max) = summary(&mut times);
+    println!("runs: {} (warmup {})", repeat, warmup);
+    println!(
+        "time: min {:.3}ms median {:.3}ms max {:.3}ms",
+        ms(min),
+        ms(median),
+        ms(max)
     );
-    match res {
-        Ok(_) => {
-            println!("done");
-        }
-        Err(e) => {
-            println!(
-                "{}",
-                e.to_string(&mut vm).unwrap_or_else(|_| "shit".to_string())
-            );
-        }
+    let (min, median, max) = summary(&mut allocations);
+    println!("allocations: min {} median {} max {}", min, median, max);
+    println!("collections: {}", vm.gc_stats().collections - collections);
+    Ok(())
+}
+
+fn main() {
+    let cli = Cli::from_args();
+    let source = match cli.file {
+        Some(ref path) => fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("cannot read {}: {}", path.display(), e);
+            process::exit(1);
+        }),
+        None => DEFAULT_SCRIPT.to_owned(),
+    };
+    if cli.repeat == Some(0) {
+        eprintln!("--repeat needs at least one run");
+        process::exit(1);
+    }
+    let (warmup, repeat) = (cli.warmup, cli.repeat);
+    let mut vm = VirtualMachine::new(cli.options);
+    let res = match repeat {
+        Some(repeat) => bench(&mut vm, &source, warmup, repeat),
+        None => vm.eval(&source).map(|_| println!("done")),
+    };
+    let failed = res.is_err();
+    if let Err(e) = res {
+        println!(
+            "{}",
+            e.to_string(&mut vm).unwrap_or_else(|_| "shit".to_string())
+        );
     }
     VirtualMachineRef::dispose(vm);
+    if failed {
+        process::exit(1);
+    }
 }
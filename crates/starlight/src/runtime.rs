@@ -5,20 +5,33 @@ pub mod method_table;
 pub mod accessor;
 pub mod arguments;
 pub mod array;
+pub mod array_buffer;
 pub mod attributes;
+pub mod collator;
+pub mod date;
 pub mod env;
 pub mod error;
+pub mod finalization_registry;
 pub mod function;
 pub mod gc_array;
 pub mod global;
 pub mod indexed_elements;
+pub mod iterator;
 pub mod js_arguments;
+pub mod map;
+pub mod normalize;
+pub mod number;
+pub mod number_format;
 pub mod object;
+pub mod private_name;
 pub mod property_descriptor;
+pub mod proxy;
 pub mod ref_ptr;
+pub mod regexp;
 pub mod slot;
 pub mod storage;
 pub mod string;
 pub mod structure;
 pub mod symbol;
+pub mod typed_array;
 pub mod value;
@@ -35,7 +35,7 @@ impl Accessor {
 impl Cell for Accessor {}
 
 unsafe impl Trace for Accessor {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.setter.trace(tracer);
         self.getter.trace(tracer);
     }
@@ -11,18 +11,29 @@ pub struct Arguments {
     pub this: JsValue,
     pub values: Gc<GcArray<JsValue>>,
     pub ctor_call: bool,
+    /// `new.target` of a `[[Construct]]` call, `undefined` for ordinary calls.
+    pub new_target: JsValue,
 }
 
 impl Arguments {
     pub fn size(&self) -> usize {
         self.values.len()
     }
+    /// Returns argument at `index` or `undefined` if it was not passed.
+    pub fn at(&self, index: usize) -> JsValue {
+        if index < self.size() {
+            self.values[index]
+        } else {
+            JsValue::undefined()
+        }
+    }
     pub fn new(vm: &mut VirtualMachine, this: JsValue, size: usize) -> Self {
         let arr = GcArray::new(vm.space(), size, JsValue::undefined());
         Self {
             this,
             values: arr,
             ctor_call: false,
+            new_target: JsValue::undefined(),
         }
     }
 }
@@ -42,9 +53,10 @@ impl IndexMut<usize> for Arguments {
 
 impl Cell for Arguments {}
 unsafe impl Trace for Arguments {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.this.trace(tracer);
         self.values.trace(tracer);
+        self.new_target.trace(tracer);
     }
 }
 
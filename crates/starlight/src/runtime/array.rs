@@ -1,13 +1,22 @@
+use super::map::IterationKind;
 use super::{
     attributes::*, method_table::*, object::*, property_descriptor::*, slot::*, symbol::*, value::*,
 };
 use super::{error::JsTypeError, indexed_elements::MAX_VECTOR_SIZE, string::JsString};
 use crate::{heap::cell::*, vm::*};
+use std::mem::ManuallyDrop;
 
 pub struct JsArray;
 #[allow(non_snake_case)]
 impl JsArray {
     define_jsclass!(JsArray, Array);
+    /// Create a new array object of length `n` inheriting from `Array.prototype`.
+    pub fn new(vm: &mut VirtualMachine, n: u32) -> Gc<JsObject> {
+        let structure = vm.global_data().array_structure.unwrap();
+        let mut arr = JsObject::new(vm, structure, JsArray::get_class(), ObjectTag::Array);
+        arr.elements.set_length(n);
+        arr
+    }
     pub fn GetPropertyNamesMethod(
         obj: Gc<JsObject>,
         vm: &mut VirtualMachine,
@@ -67,10 +76,26 @@ impl JsArray {
         collector: &mut dyn FnMut(Symbol, u32),
         mode: EnumerationMode,
     ) {
-        if mode == EnumerationMode::IncludeNotEnumerable {
+        if mode != EnumerationMode::IncludeNotEnumerable {
+            return JsObject::GetOwnPropertyNamesMethod(obj, vm, collector, mode);
+        }
+        // `length` goes after the indices and before other string keys.
+        let mut length_reported = false;
+        JsObject::GetOwnPropertyNamesMethod(
+            obj,
+            vm,
+            &mut |sym, offset| {
+                if !length_reported && !matches!(sym, Symbol::Indexed(_)) {
+                    length_reported = true;
+                    collector(Symbol::length(), 0);
+                }
+                collector(sym, offset);
+            },
+            mode,
+        );
+        if !length_reported {
             collector(Symbol::length(), 0);
         }
-        JsObject::GetOwnPropertyNamesMethod(obj, vm, collector, mode)
     }
 
     pub fn DeleteNonIndexedMethod(
@@ -81,7 +106,8 @@ impl JsArray {
     ) -> Result<bool, JsValue> {
         if name == Symbol::length() {
             if throwable {
-                todo!();
+                let msg = JsString::new(vm, "Cannot delete non-configurable property 'length'");
+                return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
             }
             return Ok(false);
         }
@@ -265,7 +291,7 @@ impl Gc<JsObject> {
         Ok(succ)
     }
 
-    fn set_length(
+    pub(crate) fn set_length(
         &mut self,
         ctx: &mut VirtualMachine,
         len: u32,
@@ -334,7 +360,7 @@ impl Gc<JsObject> {
         for it in props.iter().rev() {
             let sym = it.0;
             match sym {
-                Symbol::Indexed(index) => {
+                Symbol::Indexed(index) if index >= len => {
                     if !self.delete_indexed_internal(ctx, index, false)? {
                         self.elements.set_length(index + 1);
                         if throwable {
@@ -351,3 +377,36 @@ impl Gc<JsObject> {
         Ok(true)
     }
 }
+
+/// Iterator returned by `keys`, `values` and `entries` of `Array.prototype`.
+pub struct JsArrayIterator {
+    /// Iterated object, any array-like. `length` is read again on every step.
+    pub array: Gc<JsObject>,
+    pub position: u32,
+    pub kind: IterationKind,
+    pub done: bool,
+}
+
+impl JsArrayIterator {
+    define_jsclass!(JsObject, Iterator);
+
+    pub fn new(vm: &mut VirtualMachine, array: Gc<JsObject>, kind: IterationKind) -> Gc<JsObject> {
+        let structure = vm.global_data().array_iterator_structure.unwrap();
+        let obj = JsObject::new(vm, structure, Self::get_class(), ObjectTag::ArrayIterator);
+        unsafe {
+            *obj.data::<JsArrayIterator>() = ManuallyDrop::new(JsArrayIterator {
+                array,
+                position: 0,
+                kind,
+                done: false,
+            });
+        }
+        obj
+    }
+}
+
+unsafe impl Trace for JsArrayIterator {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.array.trace(tracer);
+    }
+}
@@ -0,0 +1,97 @@
+//! Byte storage of `ArrayBuffer` and `SharedArrayBuffer` objects.
+//!
+//! There is a single agent so shared buffers only differ in that they are never detached or
+//! transferred.
+use std::mem::ManuallyDrop;
+
+use super::{
+    method_table::*,
+    object::{JsObject, ObjectTag},
+};
+use crate::{
+    heap::cell::{Gc, Trace},
+    vm::VirtualMachine,
+};
+
+pub struct JsArrayBuffer {
+    /// `None` once the buffer was detached.
+    data: Option<Vec<u8>>,
+    shared: bool,
+}
+
+struct SharedArrayBuffer;
+impl SharedArrayBuffer {
+    define_jsclass_with_symbol!(JsObject, SharedArrayBuffer, ArrayBuffer);
+}
+
+impl JsArrayBuffer {
+    define_jsclass_with_symbol!(JsObject, ArrayBuffer, ArrayBuffer);
+
+    /// Creates a buffer owning `data`.
+    pub fn new(vm: &mut VirtualMachine, data: Vec<u8>) -> Gc<JsObject> {
+        let structure = vm.global_data().array_buffer_structure.unwrap();
+        let obj = JsObject::new(vm, structure, Self::get_class(), ObjectTag::ArrayBuffer);
+        unsafe {
+            *obj.data::<JsArrayBuffer>() = ManuallyDrop::new(JsArrayBuffer {
+                data: Some(data),
+                shared: false,
+            });
+        }
+        obj
+    }
+
+    /// Creates a `SharedArrayBuffer` owning `data`.
+    pub fn new_shared(vm: &mut VirtualMachine, data: Vec<u8>) -> Gc<JsObject> {
+        let structure = vm.global_data().shared_array_buffer_structure.unwrap();
+        let obj = JsObject::new(
+            vm,
+            structure,
+            SharedArrayBuffer::get_class(),
+            ObjectTag::ArrayBuffer,
+        );
+        unsafe {
+            *obj.data::<JsArrayBuffer>() = ManuallyDrop::new(JsArrayBuffer {
+                data: Some(data),
+                shared: true,
+            });
+        }
+        obj
+    }
+
+    /// section 25.1.2.5 IsSharedArrayBuffer(obj)
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// section 25.1.2.2 IsDetachedBuffer(arrayBuffer)
+    pub fn is_detached(&self) -> bool {
+        self.data.is_none()
+    }
+
+    pub fn byte_length(&self) -> usize {
+        self.data().len()
+    }
+
+    /// The bytes of the buffer, empty once it was detached.
+    pub fn data(&self) -> &[u8] {
+        self.data.as_deref().unwrap_or(&[])
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.data.as_deref_mut().unwrap_or(&mut [])
+    }
+
+    /// section 25.1.2.3 DetachArrayBuffer(arrayBuffer), returns the bytes that were held so they
+    /// can be moved to another buffer without copying. Shared buffers can not be detached.
+    pub fn detach(&mut self) -> Vec<u8> {
+        assert!(!self.shared, "a SharedArrayBuffer can not be detached");
+        self.data.take().unwrap_or_default()
+    }
+
+    /// Gives a buffer created as the target of a transfer the bytes of the detached source.
+    pub fn attach(&mut self, data: Vec<u8>) {
+        self.data = Some(data);
+    }
+}
+
+unsafe impl Trace for JsArrayBuffer {}
@@ -228,6 +228,62 @@ pub fn create_accessor(mut attrs: AttrExternal) -> AttrSafe {
     AttrSafe::new(attrs.raw)
 }
 
+/// Fluent builder for [AttrSafe].
+///
+/// ```ignore
+/// let attrs = Attributes::data().writable(true).enumerable(false).configurable(false).freeze();
+/// assert!(attrs.is_writable() && !attrs.is_enumerable());
+/// ```
+///
+/// Bits that are not set explicitly default to `false`. Accessor attributes never carry the writable bit.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Attributes {
+    attrs: AttrExternal,
+    accessor: bool,
+}
+
+impl Attributes {
+    pub fn data() -> Self {
+        Self {
+            attrs: AttrExternal::new(None),
+            accessor: false,
+        }
+    }
+
+    pub fn accessor() -> Self {
+        Self {
+            attrs: AttrExternal::new(None),
+            accessor: true,
+        }
+    }
+
+    /// Has no effect on accessor attributes.
+    pub fn writable(mut self, val: bool) -> Self {
+        if !self.accessor {
+            self.attrs.set_writable(val);
+        }
+        self
+    }
+
+    pub fn enumerable(mut self, val: bool) -> Self {
+        self.attrs.set_enumerable(val);
+        self
+    }
+
+    pub fn configurable(mut self, val: bool) -> Self {
+        self.attrs.set_configurable(val);
+        self
+    }
+
+    pub fn freeze(self) -> AttrSafe {
+        if self.accessor {
+            create_accessor(self.attrs)
+        } else {
+            create_data(self.attrs)
+        }
+    }
+}
+
 pub fn object_data() -> AttrSafe {
     create_data(AttrExternal::new(Some(
         WRITABLE | ENUMERABLE | CONFIGURABLE,
@@ -257,3 +313,40 @@ impl DerefMut for AttrSafe {
         &mut self.attributes
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributes_builder() {
+        for raw in 0..8u32 {
+            let (w, e, c) = (raw & W != 0, raw & E != 0, raw & C != 0);
+            let attrs = Attributes::data()
+                .writable(w)
+                .enumerable(e)
+                .configurable(c)
+                .freeze();
+            assert!(attrs.is_data() && !attrs.is_accessor());
+            assert!(!attrs.is_not_found());
+            assert_eq!(attrs.is_writable(), w);
+            assert_eq!(attrs.is_enumerable(), e);
+            assert_eq!(attrs.is_configurable(), c);
+            assert_eq!(attrs.raw(), DATA | raw);
+            assert!(attrs == create_data(AttrExternal::new(Some(raw))));
+
+            let attrs = Attributes::accessor()
+                .writable(w)
+                .enumerable(e)
+                .configurable(c)
+                .freeze();
+            assert!(attrs.is_accessor() && !attrs.is_data());
+            assert!(!attrs.is_not_found());
+            assert!(!attrs.is_writable());
+            assert_eq!(attrs.is_enumerable(), e);
+            assert_eq!(attrs.is_configurable(), c);
+            assert_eq!(attrs.raw(), ACCESSOR | (raw & BOTH));
+        }
+        assert!(AttrSafe::not_found().is_not_found());
+    }
+}
@@ -0,0 +1,170 @@
+//! String collation used by `String.prototype.localeCompare`.
+//!
+//! This is not the Unicode Collation Algorithm: strings are compared on three levels, base
+//! characters first, then accents and then case, which is enough to sort words the way users
+//! expect. Locales are not taken into account.
+use std::cmp::Ordering;
+
+use super::normalize::{combining_class, normalize, NormalizationForm};
+
+/// Which differences between strings make them compare unequal, as in `Intl.Collator`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sensitivity {
+    /// Only base letters differ: `a ≠ b`, `a = á`, `a = A`.
+    Base,
+    /// Base letters or accents differ: `a ≠ á`, `a = A`.
+    Accent,
+    /// Base letters or case differ: `a = á`, `a ≠ A`.
+    Case,
+    /// Any difference: `a ≠ á`, `a ≠ A`.
+    Variant,
+}
+
+impl Sensitivity {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "base" => Some(Self::Base),
+            "accent" => Some(Self::Accent),
+            "case" => Some(Self::Case),
+            "variant" => Some(Self::Variant),
+            _ => None,
+        }
+    }
+}
+
+/// Primary weight of a collation element, variants are ordered as they sort.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Primary {
+    /// Whitespace, punctuation and symbols.
+    Symbol(char),
+    /// Run of digits under numeric collation: number of significant digits, then the digits.
+    Number(usize, String),
+    Digit(char),
+    /// Letters and everything else, lowercased.
+    Letter(char),
+}
+
+struct Element {
+    primary: Primary,
+    /// Combining marks following the base character.
+    accents: Vec<char>,
+    upper: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Collator {
+    /// Compare runs of digits by their numeric value, so that `"a2" < "a10"`.
+    pub numeric: bool,
+    pub sensitivity: Sensitivity,
+}
+
+impl Default for Collator {
+    fn default() -> Self {
+        Self {
+            numeric: false,
+            sensitivity: Sensitivity::Variant,
+        }
+    }
+}
+
+impl Collator {
+    fn elements(&self, s: &str) -> Vec<Element> {
+        let decomposed = normalize(s, NormalizationForm::Nfd);
+        let mut elements: Vec<Element> = vec![];
+        let mut chars = decomposed.chars().peekable();
+        while let Some(c) = chars.next() {
+            if combining_class(c) != 0 {
+                if let Some(last) = elements.last_mut() {
+                    last.accents.push(c);
+                    continue;
+                }
+            }
+            let primary = if self.numeric && c.is_ascii_digit() {
+                let mut digits = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !next.is_ascii_digit() {
+                        break;
+                    }
+                    digits.push(next);
+                    chars.next();
+                }
+                let significant = digits.trim_start_matches('0').to_owned();
+                Primary::Number(significant.len(), significant)
+            } else if c.is_ascii_digit() {
+                Primary::Digit(c)
+            } else if c.is_whitespace() || c.is_ascii_punctuation() || !c.is_alphanumeric() {
+                Primary::Symbol(c)
+            } else {
+                Primary::Letter(c.to_lowercase().next().unwrap_or(c))
+            };
+            elements.push(Element {
+                primary,
+                accents: vec![],
+                upper: c.is_uppercase(),
+            });
+        }
+        elements
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let a = self.elements(a);
+        let b = self.elements(b);
+        let primary = a
+            .iter()
+            .map(|e| &e.primary)
+            .cmp(b.iter().map(|e| &e.primary));
+        if primary != Ordering::Equal {
+            return primary;
+        }
+        if matches!(self.sensitivity, Sensitivity::Accent | Sensitivity::Variant) {
+            let accents = a
+                .iter()
+                .map(|e| &e.accents)
+                .cmp(b.iter().map(|e| &e.accents));
+            if accents != Ordering::Equal {
+                return accents;
+            }
+        }
+        if matches!(self.sensitivity, Sensitivity::Case | Sensitivity::Variant) {
+            // lowercase sorts first.
+            return a.iter().map(|e| e.upper).cmp(b.iter().map(|e| e.upper));
+        }
+        Ordering::Equal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collation_levels() {
+        let collator = Collator::default();
+        assert_eq!(collator.compare("a", "b"), Ordering::Less);
+        assert_eq!(collator.compare("a", "A"), Ordering::Less);
+        assert_eq!(collator.compare("B", "a"), Ordering::Greater);
+        assert_eq!(collator.compare("e", "\u{e9}"), Ordering::Less);
+        assert_eq!(collator.compare("\u{e9}", "e\u{301}"), Ordering::Equal);
+        assert_eq!(collator.compare("a10", "a2"), Ordering::Less);
+        assert_eq!(collator.compare("a b", "ab"), Ordering::Less);
+
+        let numeric = Collator {
+            numeric: true,
+            ..Collator::default()
+        };
+        assert_eq!(numeric.compare("a10", "a2"), Ordering::Greater);
+        assert_eq!(numeric.compare("a010", "a10"), Ordering::Equal);
+
+        let base = Collator {
+            numeric: false,
+            sensitivity: Sensitivity::Base,
+        };
+        assert_eq!(base.compare("Resume", "r\u{e9}sum\u{e9}"), Ordering::Equal);
+        let accent = Collator {
+            numeric: false,
+            sensitivity: Sensitivity::Accent,
+        };
+        assert_eq!(accent.compare("a", "A"), Ordering::Equal);
+        assert_eq!(accent.compare("a", "\u{e1}"), Ordering::Less);
+    }
+}
@@ -0,0 +1,575 @@
+//! Time values of `Date` objects.
+//!
+//! A time value is a number of milliseconds since the epoch, NaN for an invalid date. There is
+//! no time zone support, local time is UTC.
+use std::mem::ManuallyDrop;
+
+use super::{
+    method_table::*,
+    object::{JsObject, ObjectTag},
+};
+use crate::{
+    heap::cell::{Gc, Trace},
+    vm::VirtualMachine,
+};
+
+pub const MS_PER_DAY: f64 = 86_400_000.0;
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+pub struct JsDate {
+    time: f64,
+}
+
+impl JsDate {
+    define_jsclass_with_symbol!(JsObject, Date, Date);
+
+    /// Creates a date with time value `time`, which must already be clipped.
+    pub fn new(vm: &mut VirtualMachine, time: f64) -> Gc<JsObject> {
+        let structure = vm.global_data().date_structure.unwrap();
+        let obj = JsObject::new(vm, structure, Self::get_class(), ObjectTag::Date);
+        unsafe {
+            *obj.data::<JsDate>() = ManuallyDrop::new(JsDate { time });
+        }
+        obj
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+}
+
+unsafe impl Trace for JsDate {}
+
+/// Calendar fields of a time value.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DateFields {
+    pub year: i64,
+    /// 1 to 12.
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub millisecond: u32,
+    /// 0 is Sunday.
+    pub weekday: u32,
+}
+
+/// Days from the epoch to the proleptic Gregorian date `year-month-day`.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let year_of_era = year - era * 400;
+    let month_from_march = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Inverse of [days_from_civil].
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = (if days >= 0 { days } else { days - 146096 }) / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month as u32, day)
+}
+
+/// Splits the finite time value `time` into calendar fields.
+pub fn date_fields(time: f64) -> DateFields {
+    debug_assert!(time.is_finite());
+    let day = (time / MS_PER_DAY).floor();
+    let mut ms = (time - day * MS_PER_DAY) as u32;
+    let (year, month, date) = civil_from_days(day as i64);
+    let millisecond = ms % 1000;
+    ms /= 1000;
+    let second = ms % 60;
+    ms /= 60;
+    DateFields {
+        year,
+        month,
+        day: date,
+        hour: ms / 60,
+        minute: ms % 60,
+        second,
+        millisecond,
+        // the epoch was a Thursday.
+        weekday: (day as i64 + 4).rem_euclid(7) as u32,
+    }
+}
+
+/// section 21.4.1.27 MakeTime(hour, min, sec, ms)
+pub fn make_time(hour: f64, min: f64, sec: f64, ms: f64) -> f64 {
+    if !(hour.is_finite() && min.is_finite() && sec.is_finite() && ms.is_finite()) {
+        return f64::NAN;
+    }
+    hour.trunc() * 3_600_000.0 + min.trunc() * 60_000.0 + sec.trunc() * 1000.0 + ms.trunc()
+}
+
+/// section 21.4.1.28 MakeDay(year, month, date)
+pub fn make_day(year: f64, month: f64, date: f64) -> f64 {
+    if !(year.is_finite() && month.is_finite() && date.is_finite()) {
+        return f64::NAN;
+    }
+    let year = year.trunc() + (month.trunc() / 12.0).floor();
+    // far outside of the time value range, also keeps the conversion below from overflowing.
+    if year.abs() > 400_000.0 {
+        return f64::NAN;
+    }
+    let month = month.trunc().rem_euclid(12.0) as u32 + 1;
+    days_from_civil(year as i64, month, 1) as f64 + date.trunc() - 1.0
+}
+
+/// section 21.4.1.29 MakeDate(day, time)
+pub fn make_date(day: f64, time: f64) -> f64 {
+    let tv = day * MS_PER_DAY + time;
+    if tv.is_finite() {
+        tv
+    } else {
+        f64::NAN
+    }
+}
+
+/// section 21.4.1.31 TimeClip(time)
+pub fn time_clip(time: f64) -> f64 {
+    if !time.is_finite() || time.abs() > 8.64e15 {
+        return f64::NAN;
+    }
+    // adding +0 turns -0 into +0.
+    time.trunc() + 0.0
+}
+
+/// section 21.4.1.32 Date Time String Format, the time value is expected to be valid.
+pub fn to_iso_string(time: f64) -> String {
+    let fields = date_fields(time);
+    let year = if (0..=9999).contains(&fields.year) {
+        format!("{:04}", fields.year)
+    } else {
+        let sign = if fields.year < 0 { '-' } else { '+' };
+        format!("{}{:06}", sign, fields.year.abs())
+    };
+    format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        fields.month,
+        fields.day,
+        fields.hour,
+        fields.minute,
+        fields.second,
+        fields.millisecond
+    )
+}
+
+/// section 21.4.4.41.4 ToDateString(tv)
+pub fn to_date_string(time: f64) -> String {
+    if time.is_nan() {
+        return "Invalid Date".to_owned();
+    }
+    let fields = date_fields(time);
+    let sign = if fields.year < 0 { "-" } else { "" };
+    format!(
+        "{} {} {:02} {}{:04} {:02}:{:02}:{:02} GMT+0000 (Coordinated Universal Time)",
+        WEEKDAYS[fields.weekday as usize],
+        MONTHS[fields.month as usize - 1],
+        fields.day,
+        sign,
+        fields.year.abs(),
+        fields.hour,
+        fields.minute,
+        fields.second
+    )
+}
+
+fn parse_digits(bytes: &[u8], pos: &mut usize, count: usize) -> Option<i64> {
+    let digits = bytes.get(*pos..*pos + count)?;
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    *pos += count;
+    Some(
+        digits
+            .iter()
+            .fold(0, |acc, digit| acc * 10 + (digit - b'0') as i64),
+    )
+}
+
+fn eat(bytes: &[u8], pos: &mut usize, c: u8) -> bool {
+    if bytes.get(*pos) == Some(&c) {
+        *pos += 1;
+        true
+    } else {
+        false
+    }
+}
+
+fn parse_iso(s: &str) -> Option<f64> {
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    let year = match *bytes.first()? {
+        b'+' | b'-' => {
+            let negative = bytes[0] == b'-';
+            pos += 1;
+            let year = parse_digits(bytes, &mut pos, 6)?;
+            if negative && year == 0 {
+                // -000000 is not a valid year.
+                return None;
+            }
+            if negative {
+                -year
+            } else {
+                year
+            }
+        }
+        _ => parse_digits(bytes, &mut pos, 4)?,
+    };
+    let mut month = 1;
+    let mut day = 1;
+    if eat(bytes, &mut pos, b'-') {
+        month = parse_digits(bytes, &mut pos, 2)?;
+        if eat(bytes, &mut pos, b'-') {
+            day = parse_digits(bytes, &mut pos, 2)?;
+        }
+    }
+    if !(1..=12).contains(&month)
+        || day < 1
+        || civil_from_days(days_from_civil(year, month as u32, day as u32))
+            != (year, month as u32, day as u32)
+    {
+        return None;
+    }
+
+    let (mut hour, mut minute, mut second, mut ms) = (0, 0, 0, 0);
+    let mut offset = 0;
+    if eat(bytes, &mut pos, b'T') {
+        hour = parse_digits(bytes, &mut pos, 2)?;
+        if !eat(bytes, &mut pos, b':') {
+            return None;
+        }
+        minute = parse_digits(bytes, &mut pos, 2)?;
+        if eat(bytes, &mut pos, b':') {
+            second = parse_digits(bytes, &mut pos, 2)?;
+            if eat(bytes, &mut pos, b'.') {
+                ms = parse_digits(bytes, &mut pos, 3)?;
+            }
+        }
+        if minute > 59 || second > 59 || hour > 24 || (hour == 24 && minute + second + ms != 0) {
+            return None;
+        }
+        match bytes.get(pos).copied() {
+            Some(b'Z') => pos += 1,
+            Some(sign @ b'+') | Some(sign @ b'-') => {
+                pos += 1;
+                let offset_hour = parse_digits(bytes, &mut pos, 2)?;
+                if !eat(bytes, &mut pos, b':') {
+                    return None;
+                }
+                let offset_minute = parse_digits(bytes, &mut pos, 2)?;
+                if offset_hour > 23 || offset_minute > 59 {
+                    return None;
+                }
+                offset = (offset_hour * 60 + offset_minute) * 60_000;
+                if sign == b'-' {
+                    offset = -offset;
+                }
+            }
+            _ => (),
+        }
+    }
+    if pos != bytes.len() {
+        return None;
+    }
+    let day = make_day(year as f64, (month - 1) as f64, day as f64);
+    let time = make_time(hour as f64, minute as f64, second as f64, ms as f64);
+    Some(make_date(day, time) - offset as f64)
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+const WEEKDAY_NAMES: [&str; 7] = [
+    "sunday",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+];
+
+/// Index of `word` in `names` when it is one of them or an abbreviation of at least three letters.
+fn name_index(names: &[&str], word: &str) -> Option<usize> {
+    if word.len() < 3 {
+        return None;
+    }
+    names.iter().position(|name| name.starts_with(word))
+}
+
+/// Offset in minutes of the time zone names RFC 2822 knows.
+fn zone_offset(word: &str) -> Option<i64> {
+    Some(match word {
+        "z" | "ut" | "utc" | "gmt" => 0,
+        "edt" => -4 * 60,
+        "est" | "cdt" => -5 * 60,
+        "cst" | "mdt" => -6 * 60,
+        "mst" | "pdt" => -7 * 60,
+        "pst" => -8 * 60,
+        _ => return None,
+    })
+}
+
+/// Reads a run of at most 9 digits, returns the value and the number of digits.
+fn parse_number(bytes: &[u8], pos: &mut usize) -> Option<(i64, usize)> {
+    let count = bytes[*pos..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if count == 0 || count > 9 {
+        return None;
+    }
+    parse_digits(bytes, pos, count).map(|n| (n, count))
+}
+
+/// Strings that are not in the date time string format, as printed by `toString`, by
+/// `toUTCString` and found in RFC 2822 headers, e.g. `Sat, 01 Jan 2000 10:00:00 +0100`,
+/// `January 1, 2000 1:30 PM` or `1/1/2000`.
+///
+/// Tokens may come in any order: month and weekday names, `day`, `year`, `month/day/year`,
+/// `year/month/day`, `hour:minute[:second[.fraction]]`, `AM`/`PM`, zone names and numeric
+/// offsets following the time. Parenthesized comments are skipped. Two digit years are 19xx from
+/// 50 on and 20xx below.
+fn parse_legacy(s: &str) -> Option<f64> {
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    let (mut year, mut month, mut day) = (None, None, None);
+    let mut year_digits = 0;
+    let mut time = None;
+    let mut offset = 0;
+    let mut pm = None;
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        if c.is_ascii_whitespace() || c == b',' {
+            pos += 1;
+        } else if c == b'(' {
+            pos += bytes[pos..].iter().position(|&c| c == b')')? + 1;
+        } else if c.is_ascii_alphabetic() {
+            let start = pos;
+            while pos < bytes.len() && bytes[pos].is_ascii_alphabetic() {
+                pos += 1;
+            }
+            let word = s[start..pos].to_ascii_lowercase();
+            if let Some(ix) = name_index(&MONTH_NAMES, &word) {
+                if month.replace(ix as i64 + 1).is_some() {
+                    return None;
+                }
+            } else if word == "am" || word == "pm" {
+                pm = Some(word == "pm");
+            } else if let Some(zone) = zone_offset(&word) {
+                offset = zone;
+            } else if word != "t" && name_index(&WEEKDAY_NAMES, &word).is_none() {
+                return None;
+            }
+        } else if (c == b'+' || c == b'-') && time.is_some() {
+            pos += 1;
+            let (n, digits) = parse_number(bytes, &mut pos)?;
+            let minutes = match digits {
+                4 => n / 100 * 60 + n % 100,
+                1 | 2 if eat(bytes, &mut pos, b':') => n * 60 + parse_digits(bytes, &mut pos, 2)?,
+                1 | 2 => n * 60,
+                _ => return None,
+            };
+            offset = if c == b'-' { -minutes } else { minutes };
+        } else if c.is_ascii_digit() {
+            let (n, digits) = parse_number(bytes, &mut pos)?;
+            let separator = bytes.get(pos).copied();
+            let numeric_date = (separator == Some(b'/') || separator == Some(b'-'))
+                && bytes.get(pos + 1).map_or(false, u8::is_ascii_digit);
+            if eat(bytes, &mut pos, b':') {
+                let minute = parse_number(bytes, &mut pos)?.0;
+                let (mut second, mut ms) = (0, 0);
+                if eat(bytes, &mut pos, b':') {
+                    second = parse_number(bytes, &mut pos)?.0;
+                    if eat(bytes, &mut pos, b'.') {
+                        let start = pos;
+                        let fraction = parse_number(bytes, &mut pos)?.1;
+                        // milliseconds are the first three digits of the fraction.
+                        ms = bytes[start..start + fraction.min(3)]
+                            .iter()
+                            .fold(0, |acc, digit| acc * 10 + (digit - b'0') as i64)
+                            * 10i64.pow(3 - fraction.min(3) as u32);
+                    }
+                }
+                if time.replace((n, minute, second, ms)).is_some() {
+                    return None;
+                }
+            } else if numeric_date {
+                pos += 1;
+                let second = parse_number(bytes, &mut pos)?.0;
+                if !eat(bytes, &mut pos, separator.unwrap()) || month.is_some() || day.is_some() {
+                    return None;
+                }
+                let (third, third_digits) = parse_number(bytes, &mut pos)?;
+                let (y, m, d, y_digits) = if digits >= 3 {
+                    (n, second, third, digits)
+                } else {
+                    (third, n, second, third_digits)
+                };
+                if year.replace(y).is_some() {
+                    return None;
+                }
+                year_digits = y_digits;
+                month = Some(m);
+                day = Some(d);
+            } else if digits <= 2 && day.is_none() {
+                day = Some(n);
+            } else {
+                if year.replace(n).is_some() {
+                    return None;
+                }
+                year_digits = digits;
+            }
+        } else if c == b'-' || c == b'/' || c == b'.' {
+            pos += 1;
+        } else {
+            return None;
+        }
+    }
+
+    let (year, month, day) = (year?, month?, day.unwrap_or(1));
+    let year = match year_digits {
+        1 | 2 if year < 50 => year + 2000,
+        1 | 2 => year + 1900,
+        _ => year,
+    };
+    let (mut hour, minute, second, ms) = time.unwrap_or((0, 0, 0, 0));
+    if let Some(pm) = pm {
+        if !(1..=12).contains(&hour) {
+            return None;
+        }
+        hour = hour % 12 + if pm { 12 } else { 0 };
+    }
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 24
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+    let day = make_day(year as f64, (month - 1) as f64, day as f64);
+    let time = make_time(hour as f64, minute as f64, second as f64, ms as f64);
+    Some(make_date(day, time) - (offset * 60_000) as f64)
+}
+
+/// section 21.4.3.2 Date.parse(string)
+///
+/// Strings in the date time string format are parsed as the spec requires, other strings with
+/// the implementation specific heuristics of [parse_legacy]. Anything else is NaN. Times
+/// without an offset are local time, which is UTC.
+pub fn parse_date(s: &str) -> f64 {
+    let s = s.trim();
+    parse_iso(s)
+        .or_else(|| parse_legacy(s))
+        .map_or(f64::NAN, time_clip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iso_round_trip() {
+        let cases: &[(f64, &str)] = &[
+            (0.0, "1970-01-01T00:00:00.000Z"),
+            (951782400000.0, "2000-02-29T00:00:00.000Z"),
+            (-1.0, "1969-12-31T23:59:59.999Z"),
+            (-62198755200000.0, "-000001-01-01T00:00:00.000Z"),
+            (8.64e15, "+275760-09-13T00:00:00.000Z"),
+        ];
+        for (time, iso) in cases {
+            assert_eq!(to_iso_string(*time), *iso);
+            assert_eq!(parse_date(iso), *time);
+        }
+        assert_eq!(parse_date("2000-03"), 951868800000.0);
+        assert_eq!(parse_date("1970-01-01T01:00+01:00"), 0.0);
+        assert!(parse_date("2001-02-29").is_nan());
+        assert!(parse_date("-000000-01-01").is_nan());
+        assert!(parse_date("1970-01-01T00:60").is_nan());
+        assert_eq!(
+            to_date_string(0.0),
+            "Thu Jan 01 1970 00:00:00 GMT+0000 (Coordinated Universal Time)"
+        );
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        let y2k = 946684800000.0;
+        let cases: &[(&str, f64)] = &[
+            ("2000-01-01T00:00:00Z", y2k),
+            ("2000-01-01T00:00:00", y2k),
+            ("2000-01-01T01:30:00+01:30", y2k),
+            ("1999-12-31T19:00:00.000-05:00", y2k),
+            ("2000-01-01", y2k),
+            ("2000-01-01T00:00:00.5Z", y2k + 500.0),
+            ("2000-01-01 10:00", y2k + 36e5 * 10.0),
+            ("Sat, 01 Jan 2000 00:00:00 GMT", y2k),
+            ("Sat, 01 Jan 2000 01:00:00 +0100", y2k),
+            ("Fri, 31 Dec 1999 19:00:00 EST", y2k),
+            (
+                "Sat Jan 01 2000 00:00:00 GMT+0000 (Coordinated Universal Time)",
+                y2k,
+            ),
+            ("January 1, 2000", y2k),
+            ("1 jan 2000", y2k),
+            ("Jan 1 00", y2k),
+            ("1/1/2000", y2k),
+            ("2000/01/01 00:00", y2k),
+            ("Jan 1 2000 12:00 AM", y2k),
+            ("Jan 1 2000 1:30 PM", y2k + 36e5 * 13.5),
+            ("Jan 2000", y2k),
+        ];
+        for (s, time) in cases {
+            assert_eq!(parse_date(s), *time, "{}", s);
+        }
+        for time in [0.0, y2k + 123_456_000.0, -86_400_000.0 * 400.0].iter() {
+            assert_eq!(parse_date(&to_date_string(*time)), *time);
+        }
+        let invalid = [
+            "",
+            "x",
+            "Foo 1 2000",
+            "Jan 1 2000 25:00",
+            "13/01/2000",
+            "Jan Feb 2000",
+            "1 2",
+            "Jan 1 2000 13:00 PM",
+            "2000-01-01T00:00:00Zjunk",
+        ];
+        for s in invalid.iter() {
+            assert!(parse_date(s).is_nan(), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Backing storage of `FinalizationRegistry` objects.
+//!
+//! Targets and unregister tokens are held through [WeakGc] so registering an object never keeps
+//! it alive. Held values are strong until their target is collected. Collected targets are
+//! looked for at every microtask checkpoint, see [VirtualMachine::run_microtasks].
+use std::mem::ManuallyDrop;
+
+use super::{
+    method_table::*,
+    object::{JsObject, ObjectTag},
+    value::JsValue,
+};
+use crate::{
+    gc::weak::WeakGc,
+    heap::cell::{Gc, Trace, Tracer},
+    vm::VirtualMachine,
+};
+
+struct RegistryCell {
+    target: WeakGc<JsObject>,
+    held: JsValue,
+    token: Option<WeakGc<JsObject>>,
+}
+
+pub struct JsFinalizationRegistry {
+    cleanup: JsValue,
+    cells: Vec<RegistryCell>,
+}
+
+impl JsFinalizationRegistry {
+    define_jsclass_with_symbol!(JsObject, FinalizationRegistry, Object);
+
+    /// Creates a registry calling `cleanup` with the held value of every collected target.
+    pub fn new(vm: &mut VirtualMachine, cleanup: JsValue) -> Gc<JsObject> {
+        let structure = vm.global_data().finalization_registry_structure.unwrap();
+        let obj = JsObject::new(
+            vm,
+            structure,
+            Self::get_class(),
+            ObjectTag::FinalizationRegistry,
+        );
+        unsafe {
+            *obj.data::<JsFinalizationRegistry>() = ManuallyDrop::new(JsFinalizationRegistry {
+                cleanup,
+                cells: vec![],
+            });
+        }
+        vm.add_finalization_registry(obj);
+        obj
+    }
+
+    pub fn cleanup(&self) -> JsValue {
+        self.cleanup
+    }
+
+    pub fn register(
+        &mut self,
+        vm: &mut VirtualMachine,
+        target: Gc<JsObject>,
+        held: JsValue,
+        token: Option<Gc<JsObject>>,
+    ) {
+        let target = vm.space().new_weak(target);
+        let token = token.map(|token| vm.space().new_weak(token));
+        self.cells.push(RegistryCell {
+            target,
+            held,
+            token,
+        });
+    }
+
+    /// Removes every cell registered with `token`, returns whether there was any.
+    pub fn unregister(&mut self, token: Gc<JsObject>) -> bool {
+        let before = self.cells.len();
+        self.cells.retain(
+            |cell| match cell.token.as_ref().and_then(|token| token.upgrade()) {
+                Some(cell_token) => !Gc::ptr_eq(cell_token, token),
+                None => true,
+            },
+        );
+        self.cells.len() != before
+    }
+
+    /// Removes the cells whose target was collected and returns their held values in
+    /// registration order.
+    pub fn take_collected(&mut self) -> Vec<JsValue> {
+        let mut held = vec![];
+        self.cells.retain(|cell| {
+            if cell.target.upgrade().is_some() {
+                true
+            } else {
+                held.push(cell.held);
+                false
+            }
+        });
+        held
+    }
+}
+
+unsafe impl Trace for JsFinalizationRegistry {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.cleanup.trace(tracer);
+        for cell in self.cells.iter_mut() {
+            cell.held.trace(tracer);
+        }
+    }
+}
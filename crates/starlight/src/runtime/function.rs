@@ -27,6 +27,14 @@ impl JsFunction {
             FuncType::User(ref x) => x.code.strict,
         }
     }
+    /// Whether the function has a `[[Construct]]` method. Native functions only have one when
+    /// created by [JsNativeFunction::new_constructor].
+    pub fn is_constructor(&self) -> bool {
+        match self.ty {
+            FuncType::Native(ref x) => x.constructor,
+            FuncType::User(_) => true,
+        }
+    }
     pub fn as_native(&self) -> &JsNativeFunction {
         match self.ty {
             FuncType::Native(ref x) => x,
@@ -53,6 +61,9 @@ impl JsFunction {
         }
     }
 
+    /// `[[Construct]]`, `structure` should come from `args.new_target` so that the new object
+    /// inherits from `new_target.prototype`. If the function returns an object it is the result,
+    /// otherwise the freshly allocated `this` is.
     pub fn construct(
         &mut self,
         vm: &mut VirtualMachine,
@@ -62,7 +73,11 @@ impl JsFunction {
         let structure = structure.unwrap_or_else(|| Structure::new_unique_indexed(vm, None, false));
         let obj = JsObject::new(vm, structure, JsObject::get_class(), ObjectTag::Ordinary);
         args.this = JsValue::new(obj);
-        let _ = self.call(vm, args)?;
+        args.ctor_call = true;
+        let result = self.call(vm, args)?;
+        if result.is_object() {
+            return Ok(result);
+        }
         Ok(args.this)
     }
 
@@ -276,12 +291,30 @@ pub type JsAPI = fn(vm: &mut VirtualMachine, arguments: &Arguments) -> Result<Js
 #[allow(dead_code)]
 pub struct JsNativeFunction {
     func: JsAPI,
+    constructor: bool,
 }
 
 impl JsNativeFunction {
+    /// Like [JsNativeFunction::new] but the function is a constructor, see
+    /// [JsFunction::is_constructor].
+    pub fn new_constructor(
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        f: JsAPI,
+        n: u32,
+    ) -> Gc<JsObject> {
+        let mut func = Self::new(vm, name, f, n);
+        func.as_function_mut().as_native_mut().constructor = true;
+        func
+    }
+
     pub fn new(ctx: &mut VirtualMachine, name: Symbol, f: JsAPI, n: u32) -> Gc<JsObject> {
         let vm = ctx;
-        let mut func = JsFunction::new(vm, FuncType::Native(JsNativeFunction { func: f }), false);
+        let native = JsNativeFunction {
+            func: f,
+            constructor: false,
+        };
+        let mut func = JsFunction::new(vm, FuncType::Native(native), false);
         let l = Symbol::length();
 
         let _ = func.define_own_property(
@@ -309,7 +342,10 @@ impl JsNativeFunction {
         let mut func = JsFunction::new_with_struct(
             vm,
             s,
-            FuncType::Native(JsNativeFunction { func: f }),
+            FuncType::Native(JsNativeFunction {
+                func: f,
+                constructor: false,
+            }),
             false,
         );
         let l = Symbol::length();
@@ -330,10 +366,13 @@ impl JsNativeFunction {
 }
 
 unsafe impl Trace for JsFunction {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.construct_struct.trace(tracer);
         match self.ty {
-            FuncType::User(ref x) => x.code.trace(tracer),
+            FuncType::User(ref mut x) => {
+                x.code.trace(tracer);
+                x.scope.trace(tracer);
+            }
             _ => (),
         }
     }
@@ -354,6 +393,15 @@ impl JsVMFunction {
         };
 
         let mut this = ctx.new_local(JsFunction::new(vm, FuncType::User(f), false));
+        let _ = this.define_own_property(
+            vm,
+            Symbol::length(),
+            &*DataDescriptor::new(JsValue::new(code.length as i32), C),
+            false,
+        );
+        let name = vm.description(code.name);
+        let name = JsValue::new(JsString::new(vm, &name));
+        let _ = this.define_own_property(vm, Symbol::name(), &*DataDescriptor::new(name, C), false);
         let mut proto = ctx.new_local(JsObject::new_empty(vm));
 
         let _ = proto.define_own_property(
@@ -391,17 +439,12 @@ unsafe { Gc::from_raw(self) };
         let mut slot = Slot::new();
         let proto = Symbol::prototype();
         let res = Self::GetNonIndexedSlotMethod(obj, vm, proto, &mut slot)?;
-        let structure = unsafe {
-            Structure::new_indexed(
-                vm,
-                if res.is_cell() && res.as_cell().is::<JsObject>() {
-                    Some(res.as_cell().downcast_unchecked())
-                } else {
-                    Some(vm.global_data().get_object_prototype())
-                },
-                false,
-            )
+        let proto = if res.is_cell() && res.as_cell().is::<JsObject>() {
+            unsafe { res.as_cell().downcast_unchecked() }
+        } else {
+            vm.global_data().get_object_prototype()
         };
+        let structure = vm.root_structure(Some(proto));
         if slot.is_load_cacheable()
             && slot
                 .base()
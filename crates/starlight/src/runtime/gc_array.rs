@@ -8,7 +8,7 @@ fn clp2(number: usize) -> usize {
     x + 1
 }
 use std::{
-    mem::{size_of, MaybeUninit},
+    mem::size_of,
     ops::{Index, IndexMut},
     usize,
 };
@@ -22,11 +22,14 @@ use crate::{
     vm::VirtualMachine,
 };
 
+/// Number of elements [GcArray::trace_chunk] traces at once.
+pub const TRACE_CHUNK: usize = 1024;
+
+/// Fixed-length array stored inline in its cell, elements follow the `len` field.
 #[repr(C)]
 pub struct GcArray<T: Cell> {
-    /*len: u32,
-    data: MaybeUninit<T>,*/
-    data: Box<[T]>,
+    len: usize,
+    data: [T; 0],
 }
 impl<T: Cell> GcArray<T> {
     pub fn begin(&self) -> *mut T {
@@ -38,39 +41,62 @@ impl<T: Cell> GcArray<T> {
     }
 
     pub fn end(&self) -> *mut T {
-        unsafe { self.begin().add(self.data.len() as _) }
+        unsafe { self.begin().add(self.len) }
     }
-    /*pub fn new(vm: &mut JsVirtualMachine, len: usize) -> Handle<Self>
-    where
-        T: Default,
-    {
-        //  println!("alloc");
-        let val = Self { len, data: [] };
-        let mut cell = allocate_cell(vm, len * size_of::<T>() + size_of::<GcArray<T>>(), val);
-        for i in 0..cell.len() {
-            cell[i] = T::default();
-        }
-        cell
-    }*/
 
     pub fn new(space: &mut Space, size: usize, init: T) -> Gc<Self>
     where
         T: Clone,
     {
         let val = Self {
-            data: vec![init; size].into_boxed_slice(),
+            len: size,
+            data: [],
         };
-        space.alloc(val)
+        let cell = space.alloc_varlen(val, size, size_of::<T>());
+        for i in 0..size {
+            unsafe {
+                cell.begin().add(i).write(init.clone());
+            }
+        }
+        cell
     }
     pub fn len(&self) -> usize {
-        self.data.len() as _
+        self.len
+    }
+
+    /// Traces at most [TRACE_CHUNK] elements starting at `start` and returns where the next chunk
+    /// starts, `None` once the whole array was traced. Arrays of elements without GC pointers
+    /// are done right away. This lets a marker interleave huge arrays with other work instead of
+    /// walking them in one go.
+    pub fn trace_chunk(&mut self, start: usize, tracer: &mut dyn Tracer) -> Option<usize> {
+        if !T::needs_trace() {
+            return None;
+        }
+        let end = self.len.min(start.saturating_add(TRACE_CHUNK));
+        for value in self.as_mut()[start.min(end)..end].iter_mut() {
+            value.trace(tracer);
+        }
+        if end < self.len {
+            Some(end)
+        } else {
+            None
+        }
     }
 }
 
 unsafe impl<T: Cell> Trace for GcArray<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
-        for i in 0..self.len() {
-            self[i].trace(tracer);
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        let mut next = Some(0);
+        while let Some(start) = next {
+            next = self.trace_chunk(start, tracer);
+        }
+    }
+}
+
+impl<T: Cell> Drop for GcArray<T> {
+    fn drop(&mut self) {
+        unsafe {
+            std::ptr::drop_in_place(self.as_mut());
         }
     }
 }
@@ -81,41 +107,39 @@ impl<T: Cell> serde::Serialize for GcArray<T> {
     where
         S: serde::Serializer,
     {
-        let mut x = serializer.serialize_struct("GcArray")?;
-        x.serialize_field("len", self.len)?;
-        x.serialize_field("data", unsafe {
-            std::slice::from_raw_parts(self.data.as_ptr(), self.len)
-        })?;
+        let mut x = serializer.serialize_struct("GcArray", 2)?;
+        x.serialize_field("len", &self.len)?;
+        x.serialize_field("data", self.as_ref())?;
         x.end()
     }
 }
 impl<T: Cell> Cell for GcArray<T> {
     fn compute_size(&self) -> usize {
-        /*(self.data.len() as usize * size_of::<T>()) +*/
-        size_of::<Self>()
+        size_of::<Self>() + self.len * size_of::<T>()
     }
 }
 impl<T: Cell> Index<usize> for GcArray<T> {
     type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
-        unsafe { &*self.data.as_ptr().add(index) }
+        assert!(index < self.len, "index {} out of bounds", index);
+        unsafe { &*self.begin().add(index) }
     }
 }
 impl<T: Cell> IndexMut<usize> for GcArray<T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        unsafe { &mut *self.data.as_mut_ptr().add(index) }
+        assert!(index < self.len, "index {} out of bounds", index);
+        unsafe { &mut *self.begin().add(index) }
     }
 }
 
 impl<T: Cell> AsRef<[T]> for GcArray<T> {
     fn as_ref(&self) -> &[T] {
-        &self.data
-        //unsafe { std::slice::from_raw_parts(self.data.as_ptr(), self.len as usize) }
+        unsafe { std::slice::from_raw_parts(self.begin(), self.len) }
     }
 }
 impl<T: Cell> AsMut<[T]> for GcArray<T> {
     fn as_mut(&mut self) -> &mut [T] {
-        &mut self.data //unsafe { std::slice::from_raw_parts_mut(self.data.as_ptr() as *mut T, self.len as usize) }
+        unsafe { std::slice::from_raw_parts_mut(self.begin(), self.len) }
     }
 }
 
@@ -123,7 +147,7 @@ impl<T: Cell> AsMut<[T]> for GcArray<T> {
 struct RawVec<T: Cell> {
     cap: u32,
     len: u32,
-    data: MaybeUninit<T>,
+    data: [T; 0],
 }
 
 impl<T: Cell> RawVec<T> {
@@ -131,15 +155,15 @@ impl<T: Cell> RawVec<T> {
         let val = Self {
             len: 0,
             cap: len as _,
-            data: MaybeUninit::uninit(),
+            data: [],
         };
-        vm.space().allocate(val)
+        vm.space().alloc_varlen(val, len, size_of::<T>())
     }
 }
 
 impl<T: Cell> Cell for RawVec<T> {
     fn compute_size(&self) -> usize {
-        (self.cap as usize * size_of::<T>()) + size_of::<Self>()
+        size_of::<Self>() + self.cap as usize * size_of::<T>()
     }
 }
 
@@ -153,14 +177,14 @@ impl<T: Cell> serde::Serialize for RawVec<T> {
         x.serialize_field("len", &self.len)?;
         x.serialize_field("cap", &self.cap)?;
         x.serialize_field("data", unsafe {
-            std::slice::from_raw_parts(self.data.as_ptr(), self.len)
+            std::slice::from_raw_parts(self.data.as_ptr(), self.len as usize)
         })?;
         x.end()
     }
 }
 
 unsafe impl<T: Cell> Trace for RawVec<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         for i in 0..self.len {
             unsafe {
                 (*self.data.as_ptr().add(i as _)).trace(tracer);
@@ -332,7 +356,7 @@ impl<T: Cell> serde::Serialize for GcVec<T> {
 impl<T: Cell> Cell for GcVec<T> {}
 
 impl<T: Cell> Trace for GcVec<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         for i in 0..self.len() {
             self[i].trace(tracer);
         }
@@ -429,7 +453,87 @@ impl<T: Cell> IndexMut<usize> for GcVec<T> {
 
 impl<T: Cell> Cell for GcVec<T> {}
 unsafe impl<T: Trace + Cell> Trace for GcVec<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.data.trace(tracer);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        heap::cell::Header,
+        runtime::{object::JsObject, value::JsValue},
+        vm::{Options, VirtualMachineRef},
+    };
+
+    struct CountingTracer {
+        traced: Vec<*mut Header>,
+    }
+
+    impl Tracer for CountingTracer {
+        fn trace(&mut self, header: *mut Header) {
+            self.traced.push(header);
+        }
+    }
+
+    #[test]
+    fn test_varlen_array() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut arr = ctx.new_local(GcArray::new(vm.space(), 100, JsValue::undefined()));
+            assert_eq!(arr.len(), 100);
+            assert_eq!(
+                (**arr).compute_size(),
+                size_of::<GcArray<JsValue>>() + 100 * size_of::<JsValue>()
+            );
+            for i in 0..100 {
+                let obj = JsObject::new_empty(&mut vm);
+                arr[i] = JsValue::new(obj);
+            }
+            let mut tracer = CountingTracer { traced: vec![] };
+            (**arr).trace(&mut tracer);
+            assert_eq!(tracer.traced.len(), 100);
+
+            // the last element has to survive a collection as well as the first one.
+            vm.space().gc();
+            assert!(arr[0].is_object());
+            assert!(arr[99].is_object());
+            assert!(arr.as_ref().iter().all(|value| value.is_object()));
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_trace_chunks() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut bytes = ctx.new_local(GcArray::new(vm.space(), 100_000, 0u8));
+            let mut tracer = CountingTracer { traced: vec![] };
+            assert_eq!(bytes.trace_chunk(0, &mut tracer), None);
+            (**bytes).trace(&mut tracer);
+            assert!(tracer.traced.is_empty());
+
+            let n = 3 * TRACE_CHUNK + 10;
+            let mut arr = ctx.new_local(GcArray::new(vm.space(), n, JsValue::undefined()));
+            for i in 0..n {
+                let obj = JsObject::new_empty(&mut vm);
+                arr[i] = JsValue::new(obj);
+            }
+            let mut chunks = 0;
+            let mut next = Some(0);
+            while let Some(start) = next {
+                next = arr.trace_chunk(start, &mut tracer);
+                chunks += 1;
+            }
+            assert_eq!(chunks, 4);
+            assert_eq!(tracer.traced.len(), n);
+
+            vm.space().gc();
+            assert!(arr.as_ref().iter().all(|value| value.is_object()));
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -125,8 +125,12 @@ impl JsGlobal {
         collector: &mut dyn FnMut(Symbol, u32),
         mode: EnumerationMode,
     ) {
-        for it in obj.as_global().sym_map.iter() {
-            collector(*it.0, *it.1);
+        let include = mode == EnumerationMode::IncludeNotEnumerable;
+        let global = obj.as_global();
+        for (&name, &offset) in global.sym_map.iter() {
+            if include || global.point_at(offset).attributes().is_enumerable() {
+                collector(name, offset);
+            }
         }
         JsObject::GetOwnPropertyNamesMethod(obj, vm, collector, mode)
     }
@@ -247,8 +251,11 @@ impl JsGlobal {
 }
 
 unsafe impl Trace for JsGlobal {
-    fn trace(&self, tracer: &mut dyn Tracer) {
-        for var in self.variables.iter() {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        for &name in self.sym_map.keys() {
+            tracer.trace_symbol(name);
+        }
+        for var in self.variables.iter_mut() {
             var.trace(tracer);
         }
     }
@@ -283,4 +290,29 @@ mod tests {
 
         VirtualMachineRef::dispose(vm);
     }
+
+    #[test]
+    fn test_builtins_not_enumerable() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "keys = Object.keys(globalThis).length;
+             names = Object.getOwnPropertyNames(globalThis).join();
+             pi = globalThis.Math.PI;
+             isSelf = globalThis.globalThis === globalThis;",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let keys = vm.get(global, "keys").unwrap_or_else(|_| panic!());
+        assert_eq!(keys.number(), 0.0);
+        let names = vm.get(global, "names").unwrap_or_else(|_| panic!());
+        let names = names.as_string().as_str().split(',').collect::<Vec<_>>();
+        for name in ["Math", "JSON", "print", "globalThis", "Object"].iter() {
+            assert!(names.contains(name), "{}", name);
+        }
+        let is_self = vm.get(global, "isSelf").unwrap_or_else(|_| panic!());
+        assert!(is_self.to_boolean());
+        let pi = vm.get(global, "pi").unwrap_or_else(|_| panic!());
+        assert_eq!(pi.number(), std::f64::consts::PI);
+        VirtualMachineRef::dispose(vm);
+    }
 }
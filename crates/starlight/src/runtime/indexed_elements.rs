@@ -109,9 +109,9 @@ impl serde::Serialize for IndexedElements {
 
 impl Cell for IndexedElements {}
 unsafe impl Trace for IndexedElements {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.map.trace(tracer);
-        for item in self.vector.iter() {
+        for item in self.vector.iter_mut() {
             item.trace(tracer);
         }
     }
@@ -131,7 +131,7 @@ impl serde::Serialize for StoredSlot {
 }
 impl Cell for StoredSlot {}
 unsafe impl Trace for StoredSlot {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.value.trace(tracer);
     }
 }
@@ -0,0 +1,265 @@
+//! Iteration helpers shared by builtins that consume iterables or array-like objects.
+use super::{
+    arguments::Arguments,
+    error::JsTypeError,
+    object::{JsObject, ObjectTag},
+    string::JsString,
+    symbol::Symbol,
+    value::JsValue,
+};
+use crate::{heap::cell::Gc, vm::VirtualMachine};
+
+fn type_error(vm: &mut VirtualMachine, msg: &str) -> JsValue {
+    let msg = JsString::new(vm, msg);
+    JsValue::new(JsTypeError::new(vm, msg, None))
+}
+
+fn call(vm: &mut VirtualMachine, func: JsValue, this: JsValue) -> Result<JsValue, JsValue> {
+    let ctx = vm.space().new_local_context();
+    let mut args = ctx.new_local(Arguments::new(vm, this, 0));
+    func.as_object().as_function_mut().call(vm, &mut args)
+}
+
+/// Feeds every element of `val` to `f`.
+///
+/// If `val` has a callable `[Symbol.iterator]` method the iterator protocol is used, otherwise
+/// `val` is read as an array-like object through `length` and indexed gets. Every builtin that
+/// accepts iterables (`Array.from`, spread, `Function.prototype.apply`) goes through this function
+/// so they can't diverge in behavior.
+pub fn iterate_or_arraylike(
+    vm: &mut VirtualMachine,
+    val: JsValue,
+    f: &mut dyn FnMut(&mut VirtualMachine, JsValue) -> Result<(), JsValue>,
+) -> Result<(), JsValue> {
+    if val.is_string() {
+        let s = val.as_string().as_str().to_owned();
+        for c in s.chars() {
+            let c = vm.char_string(c);
+            f(vm, JsValue::new(c))?;
+        }
+        return Ok(());
+    }
+    if !val.is_object() {
+        return Err(type_error(vm, "value is not iterable or array-like"));
+    }
+    let ctx = vm.space().new_local_context();
+    let obj = ctx.new_local(val.as_object());
+    let method = obj.get(vm, Symbol::iterator())?;
+    if method.is_callable() {
+        let iterator = call(vm, method, val)?;
+        if !iterator.is_object() {
+            return Err(type_error(
+                vm,
+                "Result of the Symbol.iterator method is not an object",
+            ));
+        }
+        let iterator = ctx.new_local(iterator.as_object());
+        let next = ctx.new_local(iterator.get(vm, Symbol::next())?);
+        if !next.is_callable() {
+            return Err(type_error(vm, "iterator.next is not a function"));
+        }
+        loop {
+            let result = call(vm, *next, JsValue::new(*iterator))?;
+            if !result.is_object() {
+                return Err(type_error(vm, "Iterator result is not an object"));
+            }
+            let result = result.as_object();
+            if result.get(vm, Symbol::done())?.to_boolean() {
+                return Ok(());
+            }
+            let value = result.get(vm, Symbol::value())?;
+            if let Err(e) = f(vm, value) {
+                close_iterator(vm, *iterator);
+                return Err(e);
+            }
+        }
+    }
+    if !method.is_undefined_or_null() {
+        return Err(type_error(vm, "Symbol.iterator is not a function"));
+    }
+
+    let len = obj.get(vm, Symbol::length())?.to_number(vm)?;
+    let len = if len.is_nan() || len <= 0.0 {
+        0
+    } else {
+        len.min(u32::MAX as f64) as u32
+    };
+    for i in 0..len {
+        let val = obj.get(vm, Symbol::Indexed(i))?;
+        f(vm, val)?;
+    }
+    Ok(())
+}
+
+/// section 7.3.19 CreateListFromArrayLike(obj)
+///
+/// Arguments for a call of `Function.prototype.apply`, `Reflect.apply` or `Reflect.construct`
+/// with the elements of `list`. A dense array without holes whose `[Symbol.iterator]` is still the
+/// initial `Array.prototype.values` can't observe how it is read, its elements are copied into the
+/// argument area directly. Anything else goes through [iterate_or_arraylike].
+pub fn arguments_from_list(
+    vm: &mut VirtualMachine,
+    this: JsValue,
+    list: JsValue,
+) -> Result<Arguments, JsValue> {
+    if list.is_object() && list.as_object().tag() == ObjectTag::Array {
+        let obj = list.as_object();
+        let len = obj.elements.length() as usize;
+        let packed = obj.elements.dense()
+            && obj.elements.vector.len() >= len
+            && obj.elements.vector[..len].iter().all(|val| !val.is_empty());
+        if packed {
+            let method = obj.get(vm, Symbol::iterator())?;
+            let intrinsic = match vm.global_data().array_values {
+                Some(values) => method.is_object() && Gc::ptr_eq(method.as_object(), values),
+                None => false,
+            };
+            // the getter of an accessor `[Symbol.iterator]` may have changed the array
+            if intrinsic && obj.elements.vector.len() >= len {
+                let mut args = Arguments::new(vm, this, len);
+                args.values
+                    .as_mut()
+                    .copy_from_slice(&obj.elements.vector[..len]);
+                return Ok(args);
+            }
+        }
+    }
+
+    let ctx = vm.space().new_local_context();
+    let this = ctx.new_local(this);
+    let mut values = ctx.new_local(Vec::new());
+    iterate_or_arraylike(vm, list, &mut |_, val| {
+        values.push(val);
+        Ok(())
+    })?;
+    let mut args = Arguments::new(vm, *this, values.len());
+    for (i, val) in values.iter().enumerate() {
+        args[i] = *val;
+    }
+    Ok(args)
+}
+
+/// section 7.3.35 GroupBy(items, callbackfn, keyCoercion)
+///
+/// Calls `callback` with every element of `items` and its index and appends the element to the
+/// array `group` returns for the resulting key. `group` applies the key coercion and creates
+/// missing groups, so groups and their elements keep the order they were first seen in.
+pub fn group_by(
+    vm: &mut VirtualMachine,
+    items: JsValue,
+    callback: JsValue,
+    group: &mut dyn FnMut(&mut VirtualMachine, JsValue) -> Result<Gc<JsObject>, JsValue>,
+) -> Result<(), JsValue> {
+    if items.is_undefined_or_null() {
+        return Err(type_error(vm, "groupBy called on null or undefined"));
+    }
+    if !callback.is_callable() {
+        return Err(type_error(vm, "groupBy callback is not a function"));
+    }
+    let mut k = 0u32;
+    iterate_or_arraylike(vm, items, &mut |vm, value| {
+        let ctx = vm.space().new_local_context();
+        let value = ctx.new_local(value);
+        let mut args = ctx.new_local(Arguments::new(vm, JsValue::undefined(), 2));
+        args[0] = *value;
+        args[1] = JsValue::from(k);
+        let key = callback.as_object().as_function_mut().call(vm, &mut args)?;
+        let mut target = group(vm, key)?;
+        let len = target.elements.length();
+        target.put(vm, Symbol::Indexed(len), *value, true)?;
+        k += 1;
+        Ok(())
+    })
+}
+
+/// IteratorClose, errors thrown by `return` are ignored since the original completion wins.
+fn close_iterator(vm: &mut VirtualMachine, iterator: Gc<JsObject>) {
+    let ret = vm.intern("return");
+    if let Ok(method) = iterator.get(vm, ret) {
+        if method.is_callable() {
+            let _ = call(vm, method, JsValue::new(iterator));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        runtime::{
+            arguments::Arguments, attributes::object_data, function::JsNativeFunction,
+            symbol::Symbol, value::JsValue,
+        },
+        vm::{Options, VirtualMachine, VirtualMachineRef},
+    };
+
+    fn next(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+        let this = args.this.as_object();
+        let i = vm.get(this, "i")?.as_int32();
+        let ctx = vm.space().new_local_context();
+        let result = vm.new_object(&ctx);
+        if i < 3 {
+            vm.put(this, "i", JsValue::new(i + 1), object_data())?;
+            vm.put(
+                *result,
+                Symbol::value(),
+                JsValue::new(i * 10),
+                object_data(),
+            )?;
+            vm.put(*result, Symbol::done(), JsValue::new(false), object_data())?;
+        } else {
+            vm.put(*result, Symbol::done(), JsValue::new(true), object_data())?;
+        }
+        Ok(JsValue::new(*result))
+    }
+
+    fn iterator(vm: &mut VirtualMachine, _args: &Arguments) -> Result<JsValue, JsValue> {
+        let ctx = vm.space().new_local_context();
+        let iter = vm.new_object(&ctx);
+        let f = JsNativeFunction::new(vm, Symbol::next(), next, 0);
+        vm.put(*iter, Symbol::next(), JsValue::new(f), object_data())?;
+        vm.put(*iter, "i", JsValue::new(0), object_data())?;
+        Ok(JsValue::new(*iter))
+    }
+
+    fn collect(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+        let ctx = vm.space().new_local_context();
+        let values = (0..args.size()).map(|i| args[i]).collect::<Vec<_>>();
+        let arr = vm.new_array(&ctx, &values);
+        Ok(JsValue::new(*arr))
+    }
+
+    #[test]
+    fn test_iterable_protocols_agree() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let global = vm.global_object();
+            let it = vm.new_object(&ctx);
+            let f = JsNativeFunction::new(&mut vm, Symbol::iterator(), iterator, 0);
+            assert!(vm
+                .put(*it, Symbol::iterator(), JsValue::new(f), object_data())
+                .is_ok());
+            assert!(vm
+                .put(global, "it", JsValue::new(*it), object_data())
+                .is_ok());
+            let name = vm.intern("collect");
+            let f = JsNativeFunction::new(&mut vm, name, collect, 0);
+            assert!(vm.put(global, name, JsValue::new(f), object_data()).is_ok());
+
+            assert!(vm
+                .eval("a = [...it]; b = Array.from(it); c = collect.apply(null, it);")
+                .is_ok());
+            for name in ["a", "b", "c"].iter() {
+                let arr = vm.get(global, *name).unwrap_or_else(|_| panic!());
+                let arr = arr.as_object();
+                let len = vm.get(arr, Symbol::length()).unwrap_or_else(|_| panic!());
+                assert_eq!(len.as_int32(), 3);
+                for i in 0..3u32 {
+                    let val = vm.get(arr, i).unwrap_or_else(|_| panic!());
+                    assert_eq!(val.as_int32(), i as i32 * 10);
+                }
+            }
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -1,5 +1,6 @@
 use super::{
-    attributes::{create_data, AttrExternal},
+    arguments::Arguments,
+    attributes::{create_data, AttrExternal, UNDEF_VALUE},
     env::Env,
     method_table::*,
     object::*,
@@ -17,8 +18,11 @@ use super::{
     symbol::Symbol,
 };
 
+/// section 10.6 Arguments Object
+///
+/// `mapping` holds the parameter an index is mapped to, or `DUMMY_SYMBOL` once the mapping is
+/// broken. Mapped indices read and write the binding of the parameter in `env`.
 pub struct JsArguments {
-    // TODO: Better alternative?
     pub mapping: Box<[Symbol]>,
     pub env: Gc<JsObject>,
 }
@@ -48,6 +52,28 @@ impl JsArguments {
         _slot: &mut Slot,
         throwable: bool,
     ) -> Result<bool, JsValue> {
+        let arg = obj.as_arguments();
+        let mapped = arg.mapping.get(index as usize).copied();
+        // a mapped index that becomes read-only keeps the current value of its parameter.
+        let mut frozen = DataDescriptor { parent: *desc };
+        let desc = match mapped {
+            Some(mapped)
+                if mapped != DUMMY_SYMBOL
+                    && desc.is_data()
+                    && desc.is_value_absent()
+                    && !desc.is_writable_absent()
+                    && !desc.is_writable() =>
+            {
+                let val = arg
+                    .env
+                    .get(vm, mapped)
+                    .unwrap_or_else(|_| JsValue::undefined());
+                frozen.attrs = AttrExternal::new(Some(desc.attrs.raw & !UNDEF_VALUE));
+                frozen.set_value(val);
+                &*frozen
+            }
+            _ => desc,
+        };
         match obj.define_own_indexed_property_internal(vm, index, desc, throwable) {
             Ok(false) | Err(_) => {
                 if throwable {
@@ -151,12 +177,17 @@ impl JsArguments {
     }
 
     pub fn DeleteIndexedMethod(
-        obj: Gc<JsObject>,
+        mut obj: Gc<JsObject>,
         vm: &mut VirtualMachine,
         index: u32,
         throwable: bool,
     ) -> Result<bool, JsValue> {
-        JsObject::DeleteIndexedMethod(obj, vm, index, throwable)
+        let deleted = JsObject::DeleteIndexedMethod(obj, vm, index, throwable)?;
+        let arg = obj.as_arguments_mut();
+        if deleted && arg.mapping.len() > index as usize {
+            arg.mapping[index as usize] = DUMMY_SYMBOL;
+        }
+        Ok(deleted)
     }
 
     pub fn GetNonIndexedSlotMethod(
@@ -241,7 +272,18 @@ impl JsArguments {
     ) -> bool {
         JsObject::GetIndexedPropertySlotMethod(obj, vm, index, slot)
     }
-    pub fn new(vm: &mut VirtualMachine, env: Gc<JsObject>, params: &[Symbol]) -> Gc<JsObject> {
+    /// section 10.6 CreateArgumentsObject
+    ///
+    /// Holds the values of `args`. When `mapped` the indices below both the argument count and
+    /// the parameter count alias the parameters bound in `env`, a name listed twice aliases its
+    /// last index only.
+    pub fn new(
+        vm: &mut VirtualMachine,
+        env: Gc<JsObject>,
+        params: &[Symbol],
+        args: &Arguments,
+        mapped: bool,
+    ) -> Gc<JsObject> {
         let struct_ = vm.global_data().normal_arguments_structure.unwrap();
         let mut obj = JsObject::new(
             vm,
@@ -249,39 +291,89 @@ impl JsArguments {
             JsArguments::get_class(),
             ObjectTag::NormalArguments,
         );
-        //let s = Structure::new_unique_indexed(vm, None, true);
 
-        let args = JsArguments {
+        let data = JsArguments {
             mapping: vec![].into_boxed_slice(),
             env,
         };
-        *obj.data::<JsArguments>() = ManuallyDrop::new(args);
+        *obj.data::<JsArguments>() = ManuallyDrop::new(data);
         use super::attributes::*;
-        let mut mapping = Vec::with_capacity(params.len());
-        for (i, param) in params.iter().enumerate() {
+        for i in 0..args.size() {
             let mut slot = Slot::new();
             let _ = obj.define_own_indexed_property_slot(
                 vm,
                 i as _,
                 &*DataDescriptor::new(
-                    JsValue::new(2),
+                    args.at(i),
                     create_data(AttrExternal::new(Some(W | C | E))).raw(),
                 ),
                 &mut slot,
                 false,
             );
-
-            mapping.push(*param);
-
-            //let _ = obj.put(vm, Symbol::Indexed(i as _), JsValue::undefined(), false);
         }
-        obj.as_arguments_mut().mapping = mapping.into_boxed_slice();
+        let _ = obj.define_own_property(
+            vm,
+            Symbol::length(),
+            &*DataDescriptor::new(JsValue::new(args.size() as u32), W | C),
+            false,
+        );
+        if mapped {
+            let mut mapping = vec![DUMMY_SYMBOL; params.len().min(args.size())];
+            for i in (0..mapping.len()).rev() {
+                if !params[i + 1..].contains(&params[i]) {
+                    mapping[i] = params[i];
+                }
+            }
+            obj.as_arguments_mut().mapping = mapping.into_boxed_slice();
+        }
         obj
     }
 }
 
 unsafe impl Trace for JsArguments {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        for name in self.mapping.iter_mut() {
+            name.trace(tracer);
+        }
         self.env.trace(tracer);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_mapped_arguments() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function write(x, y) { arguments[0] = 5; return x + y; }
+             function read(x) { x = 10; return arguments[0]; }
+             function missing(x, y) { y = 7; return arguments.length + ',' + arguments[1]; }
+             function twice(x, x) { arguments[0] = 5; return x; }
+             function deleted(x) { delete arguments[0]; arguments[0] = 4; return x; }
+             mapped = [write(4, 3), read(1), missing(1), twice(1, 2), deleted(3)].join();
+
+             function strictWrite(x, y) { 'use strict'; arguments[0] = 5; return x + y; }
+             function strictRead(x) { 'use strict'; x = 10; return arguments[0]; }
+             strict = [strictWrite(4, 3), strictRead(1)].join();
+
+             function withDefault(x, y = 3) { arguments[0] = 5; return x + y; }
+             function readDefault(x, y = 3) { x = 10; return arguments[0] + ',' + y; }
+             function withRest(x, ...more) { x = 9; return arguments[0] + ',' + more.length; }
+             unmapped = [withDefault(4), readDefault(1), withRest(1, 2, 3)].join(';');",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let expected = [
+            ("mapped", "8,10,1,undefined,2,3"),
+            ("strict", "7,1"),
+            ("unmapped", "7;1,3;1,2"),
+        ];
+        for (name, out) in expected.iter() {
+            let value = vm.get(global, name).unwrap_or_else(|_| panic!());
+            assert_eq!(value.as_string().as_str(), *out);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,255 @@
+//! Backing storage of `Map` and `Set` objects and their iterators.
+//!
+//! Entries are kept in insertion order. Deleting an entry leaves a hole behind so that live
+//! iterators, which only remember a position, keep visiting entries added after they were
+//! created and skip the deleted ones.
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    mem::ManuallyDrop,
+};
+
+use super::{
+    class::Class,
+    method_table::*,
+    object::{JsObject, ObjectTag},
+    structure::Structure,
+    value::JsValue,
+};
+use crate::{
+    heap::cell::{Gc, Trace, Tracer},
+    vm::VirtualMachine,
+};
+
+/// Key of the index, hashed and compared under SameValueZero.
+#[derive(Clone, Copy)]
+struct MapKey(JsValue);
+
+impl PartialEq for MapKey {
+    fn eq(&self, other: &Self) -> bool {
+        JsValue::same_value_zero(self.0, other.0)
+    }
+}
+
+impl Eq for MapKey {}
+
+impl Hash for MapKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.same_value_zero_hash(state)
+    }
+}
+
+pub struct JsMap {
+    entries: Vec<Option<(JsValue, JsValue)>>,
+    index: HashMap<MapKey, usize>,
+    size: usize,
+}
+
+impl JsMap {
+    define_jsclass_with_symbol!(JsObject, Map, Object);
+
+    pub fn new(vm: &mut VirtualMachine) -> Gc<JsObject> {
+        let structure = vm.global_data().map_structure.unwrap();
+        Self::with_tag(vm, structure, Self::get_class(), ObjectTag::Map)
+    }
+
+    fn with_tag(
+        vm: &mut VirtualMachine,
+        structure: Gc<Structure>,
+        class: &'static Class,
+        tag: ObjectTag,
+    ) -> Gc<JsObject> {
+        let obj = JsObject::new(vm, structure, class, tag);
+        unsafe {
+            *obj.data::<JsMap>() = ManuallyDrop::new(JsMap {
+                entries: vec![],
+                index: HashMap::new(),
+                size: 0,
+            });
+        }
+        obj
+    }
+
+    /// Number of live entries.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, key: JsValue) -> Option<JsValue> {
+        let ix = *self.index.get(&MapKey(key))?;
+        self.entries[ix].map(|(_, value)| value)
+    }
+
+    pub fn has(&self, key: JsValue) -> bool {
+        self.index.contains_key(&MapKey(key))
+    }
+
+    /// Inserts or updates `key`. Updating keeps the position of the existing entry.
+    pub fn set(&mut self, key: JsValue, value: JsValue) {
+        let hashed = MapKey(key);
+        if let Some(&ix) = self.index.get(&hashed) {
+            if let Some(entry) = self.entries[ix].as_mut() {
+                entry.1 = value;
+            }
+            return;
+        }
+        // `-0` is stored as `+0`, see step 5 of Map.prototype.set.
+        let key = if key.is_number() && key.number() == 0.0 {
+            JsValue::new(0)
+        } else {
+            key
+        };
+        self.index.insert(hashed, self.entries.len());
+        self.entries.push(Some((key, value)));
+        self.size += 1;
+    }
+
+    pub fn delete(&mut self, key: JsValue) -> bool {
+        match self.index.remove(&MapKey(key)) {
+            Some(ix) => {
+                self.entries[ix] = None;
+                self.size -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for entry in self.entries.iter_mut() {
+            *entry = None;
+        }
+        self.index.clear();
+        self.size = 0;
+    }
+
+    /// Rebuilds the index from the entries. Objects and symbols are hashed by address, so this
+    /// is needed after [VirtualMachine::compact_heap] moved them.
+    pub(crate) fn rehash(&mut self) {
+        self.index = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, entry)| entry.map(|(key, _)| (MapKey(key), ix)))
+            .collect();
+    }
+
+    /// First live entry at or after `position` together with its position.
+    pub fn next_entry(&self, position: usize) -> Option<(usize, JsValue, JsValue)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .skip(position)
+            .find_map(|(ix, entry)| entry.map(|(key, value)| (ix, key, value)))
+    }
+}
+
+unsafe impl Trace for JsMap {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        for (key, value) in self.entries.iter_mut().flatten() {
+            key.trace(tracer);
+            value.trace(tracer);
+        }
+    }
+}
+
+/// `Set` objects share the storage of `Map`, values are stored as keys.
+pub struct JsSet;
+
+impl JsSet {
+    define_jsclass!(JsObject, Set);
+
+    pub fn new(vm: &mut VirtualMachine) -> Gc<JsObject> {
+        let structure = vm.global_data().set_structure.unwrap();
+        JsMap::with_tag(vm, structure, Self::get_class(), ObjectTag::Set)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IterationKind {
+    Key,
+    Value,
+    KeyValue,
+}
+
+/// Iterator returned by `entries`, `keys` and `values` of `Map` and `Set`.
+pub struct JsMapIterator {
+    pub map: Gc<JsObject>,
+    /// Position of the next entry to look at.
+    pub position: usize,
+    pub kind: IterationKind,
+    pub done: bool,
+}
+
+impl JsMapIterator {
+    define_jsclass!(JsObject, Iterator);
+
+    /// Creates an iterator over `map`, a `Map` or `Set` object.
+    pub fn new(vm: &mut VirtualMachine, map: Gc<JsObject>, kind: IterationKind) -> Gc<JsObject> {
+        let (structure, tag) = if map.tag() == ObjectTag::Set {
+            (
+                vm.global_data().set_iterator_structure.unwrap(),
+                ObjectTag::SetIterator,
+            )
+        } else {
+            (
+                vm.global_data().map_iterator_structure.unwrap(),
+                ObjectTag::MapIterator,
+            )
+        };
+        let obj = JsObject::new(vm, structure, Self::get_class(), tag);
+        unsafe {
+            *obj.data::<JsMapIterator>() = ManuallyDrop::new(JsMapIterator {
+                map,
+                position: 0,
+                kind,
+                done: false,
+            });
+        }
+        obj
+    }
+}
+
+unsafe impl Trace for JsMapIterator {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.map.trace(tracer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        runtime::string::JsString,
+        vm::{Options, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_same_value_zero_keys() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let ctx = vm.space().new_local_context();
+        let mut map = ctx.new_local(JsMap::new(&mut vm));
+        let map = map.as_map_mut();
+        map.set(JsValue::new(1), JsValue::new(1));
+        map.set(JsValue::new_double(1.0), JsValue::new(2));
+        map.set(JsValue::new_double(-0.0), JsValue::new(3));
+        map.set(JsValue::new_double(f64::NAN), JsValue::new(4));
+        let s = JsString::new(&mut vm, "a");
+        map.set(JsValue::new(s), JsValue::new(5));
+        let s = JsString::new(&mut vm, "a");
+        assert_eq!(map.size(), 4);
+        assert_eq!(map.get(JsValue::new(1)).unwrap().as_int32(), 2);
+        assert_eq!(map.get(JsValue::new(0)).unwrap().as_int32(), 3);
+        assert_eq!(
+            map.get(JsValue::new_double(-f64::NAN)).unwrap().as_int32(),
+            4
+        );
+        assert_eq!(map.get(JsValue::new(s)).unwrap().as_int32(), 5);
+        assert!(map.delete(JsValue::new(0)));
+        assert!(!map.has(JsValue::new_double(-0.0)));
+        let (ix, key, _) = map.next_entry(1).unwrap();
+        assert_eq!(ix, 2);
+        assert!(key.number().is_nan());
+        VirtualMachineRef::dispose(vm);
+    }
+}
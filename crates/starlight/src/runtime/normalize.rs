@@ -0,0 +1,1556 @@
+//! Unicode normalization (UAX #15) used by `String.prototype.normalize`.
+//!
+//! Decomposition data only covers Latin, Greek, Cyrillic, general punctuation and the Latin
+//! ligatures, Hangul syllables and fullwidth ASCII are handled algorithmically. Characters
+//! outside of these blocks are left untouched.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl NormalizationForm {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "NFC" => Some(Self::Nfc),
+            "NFD" => Some(Self::Nfd),
+            "NFKC" => Some(Self::Nfkc),
+            "NFKD" => Some(Self::Nfkd),
+            _ => None,
+        }
+    }
+
+    fn is_compat(self) -> bool {
+        matches!(self, Self::Nfkc | Self::Nfkd)
+    }
+
+    fn is_composed(self) -> bool {
+        matches!(self, Self::Nfc | Self::Nfkc)
+    }
+}
+
+const S_BASE: u32 = 0xac00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11a7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const N_COUNT: u32 = V_COUNT * T_COUNT;
+const S_COUNT: u32 = L_COUNT * N_COUNT;
+
+/// Canonical combining class of `c`, `0` for starters.
+pub(crate) fn combining_class(c: char) -> u8 {
+    COMBINING_CLASS
+        .binary_search_by_key(&c, |&(ch, _)| ch)
+        .map(|ix| COMBINING_CLASS[ix].1)
+        .unwrap_or(0)
+}
+
+fn decompose_char(c: char, compat: bool, out: &mut Vec<char>) {
+    let code = c as u32;
+    if (S_BASE..S_BASE + S_COUNT).contains(&code) {
+        let index = code - S_BASE;
+        out.push(std::char::from_u32(L_BASE + index / N_COUNT).unwrap());
+        out.push(std::char::from_u32(V_BASE + (index % N_COUNT) / T_COUNT).unwrap());
+        if index % T_COUNT != 0 {
+            out.push(std::char::from_u32(T_BASE + index % T_COUNT).unwrap());
+        }
+        return;
+    }
+    if let Ok(ix) = CANONICAL.binary_search_by_key(&c, |&(ch, _, _)| ch) {
+        let (_, first, second) = CANONICAL[ix];
+        decompose_char(first, compat, out);
+        if second != '\0' {
+            decompose_char(second, compat, out);
+        }
+        return;
+    }
+    if compat {
+        if (0xff01..=0xff5e).contains(&code) {
+            out.push(std::char::from_u32(code - 0xfee0).unwrap());
+            return;
+        }
+        if let Ok(ix) = COMPATIBILITY.binary_search_by_key(&c, |&(ch, _)| ch) {
+            for &ch in COMPATIBILITY[ix].1 {
+                decompose_char(ch, compat, out);
+            }
+            return;
+        }
+    }
+    out.push(c);
+}
+
+fn compose_pair(first: char, second: char) -> Option<char> {
+    let (f, s) = (first as u32, second as u32);
+    if (L_BASE..L_BASE + L_COUNT).contains(&f) && (V_BASE..V_BASE + V_COUNT).contains(&s) {
+        let lv = S_BASE + ((f - L_BASE) * V_COUNT + (s - V_BASE)) * T_COUNT;
+        return std::char::from_u32(lv);
+    }
+    if (S_BASE..S_BASE + S_COUNT).contains(&f)
+        && (f - S_BASE) % T_COUNT == 0
+        && (T_BASE + 1..T_BASE + T_COUNT).contains(&s)
+    {
+        return std::char::from_u32(f + s - T_BASE);
+    }
+    COMPOSITION
+        .binary_search_by_key(&(first, second), |&(a, b, _)| (a, b))
+        .ok()
+        .map(|ix| COMPOSITION[ix].2)
+}
+
+fn compose(chars: &mut Vec<char>) {
+    if chars.is_empty() {
+        return;
+    }
+    let mut result = Vec::with_capacity(chars.len());
+    let mut starter: Option<usize> = None;
+    let mut last_class = 0u8;
+    for &c in chars.iter() {
+        let class = combining_class(c);
+        if let Some(ix) = starter {
+            // a character is blocked from the starter if a character of the same or higher
+            // class, or another starter, sits in between.
+            let blocked = result.len() - 1 != ix && (last_class == 0 || last_class >= class);
+            if !blocked {
+                if let Some(composed) = compose_pair(result[ix], c) {
+                    result[ix] = composed;
+                    continue;
+                }
+            }
+        }
+        if class == 0 {
+            starter = Some(result.len());
+        }
+        last_class = class;
+        result.push(c);
+    }
+    *chars = result;
+}
+
+/// Normalizes `s` to `form`.
+pub fn normalize(s: &str, form: NormalizationForm) -> String {
+    let mut chars = Vec::with_capacity(s.len());
+    for c in s.chars() {
+        decompose_char(c, form.is_compat(), &mut chars);
+    }
+    // canonical ordering: stable sort every run of non-starters by combining class.
+    let mut start = 0;
+    while start < chars.len() {
+        if combining_class(chars[start]) == 0 {
+            start += 1;
+            continue;
+        }
+        let mut end = start;
+        while end < chars.len() && combining_class(chars[end]) != 0 {
+            end += 1;
+        }
+        chars[start..end].sort_by_key(|&c| combining_class(c));
+        start = end;
+    }
+    if form.is_composed() {
+        compose(&mut chars);
+    }
+    chars.into_iter().collect()
+}
+
+/// Canonical decompositions `(composed, first, second)`, `second` is `'\0'` for singletons.
+static CANONICAL: &[(char, char, char)] = &[
+    ('\u{00c0}', '\u{0041}', '\u{0300}'),
+    ('\u{00c1}', '\u{0041}', '\u{0301}'),
+    ('\u{00c2}', '\u{0041}', '\u{0302}'),
+    ('\u{00c3}', '\u{0041}', '\u{0303}'),
+    ('\u{00c4}', '\u{0041}', '\u{0308}'),
+    ('\u{00c5}', '\u{0041}', '\u{030a}'),
+    ('\u{00c7}', '\u{0043}', '\u{0327}'),
+    ('\u{00c8}', '\u{0045}', '\u{0300}'),
+    ('\u{00c9}', '\u{0045}', '\u{0301}'),
+    ('\u{00ca}', '\u{0045}', '\u{0302}'),
+    ('\u{00cb}', '\u{0045}', '\u{0308}'),
+    ('\u{00cc}', '\u{0049}', '\u{0300}'),
+    ('\u{00cd}', '\u{0049}', '\u{0301}'),
+    ('\u{00ce}', '\u{0049}', '\u{0302}'),
+    ('\u{00cf}', '\u{0049}', '\u{0308}'),
+    ('\u{00d1}', '\u{004e}', '\u{0303}'),
+    ('\u{00d2}', '\u{004f}', '\u{0300}'),
+    ('\u{00d3}', '\u{004f}', '\u{0301}'),
+    ('\u{00d4}', '\u{004f}', '\u{0302}'),
+    ('\u{00d5}', '\u{004f}', '\u{0303}'),
+    ('\u{00d6}', '\u{004f}', '\u{0308}'),
+    ('\u{00d9}', '\u{0055}', '\u{0300}'),
+    ('\u{00da}', '\u{0055}', '\u{0301}'),
+    ('\u{00db}', '\u{0055}', '\u{0302}'),
+    ('\u{00dc}', '\u{0055}', '\u{0308}'),
+    ('\u{00dd}', '\u{0059}', '\u{0301}'),
+    ('\u{00e0}', '\u{0061}', '\u{0300}'),
+    ('\u{00e1}', '\u{0061}', '\u{0301}'),
+    ('\u{00e2}', '\u{0061}', '\u{0302}'),
+    ('\u{00e3}', '\u{0061}', '\u{0303}'),
+    ('\u{00e4}', '\u{0061}', '\u{0308}'),
+    ('\u{00e5}', '\u{0061}', '\u{030a}'),
+    ('\u{00e7}', '\u{0063}', '\u{0327}'),
+    ('\u{00e8}', '\u{0065}', '\u{0300}'),
+    ('\u{00e9}', '\u{0065}', '\u{0301}'),
+    ('\u{00ea}', '\u{0065}', '\u{0302}'),
+    ('\u{00eb}', '\u{0065}', '\u{0308}'),
+    ('\u{00ec}', '\u{0069}', '\u{0300}'),
+    ('\u{00ed}', '\u{0069}', '\u{0301}'),
+    ('\u{00ee}', '\u{0069}', '\u{0302}'),
+    ('\u{00ef}', '\u{0069}', '\u{0308}'),
+    ('\u{00f1}', '\u{006e}', '\u{0303}'),
+    ('\u{00f2}', '\u{006f}', '\u{0300}'),
+    ('\u{00f3}', '\u{006f}', '\u{0301}'),
+    ('\u{00f4}', '\u{006f}', '\u{0302}'),
+    ('\u{00f5}', '\u{006f}', '\u{0303}'),
+    ('\u{00f6}', '\u{006f}', '\u{0308}'),
+    ('\u{00f9}', '\u{0075}', '\u{0300}'),
+    ('\u{00fa}', '\u{0075}', '\u{0301}'),
+    ('\u{00fb}', '\u{0075}', '\u{0302}'),
+    ('\u{00fc}', '\u{0075}', '\u{0308}'),
+    ('\u{00fd}', '\u{0079}', '\u{0301}'),
+    ('\u{00ff}', '\u{0079}', '\u{0308}'),
+    ('\u{0100}', '\u{0041}', '\u{0304}'),
+    ('\u{0101}', '\u{0061}', '\u{0304}'),
+    ('\u{0102}', '\u{0041}', '\u{0306}'),
+    ('\u{0103}', '\u{0061}', '\u{0306}'),
+    ('\u{0104}', '\u{0041}', '\u{0328}'),
+    ('\u{0105}', '\u{0061}', '\u{0328}'),
+    ('\u{0106}', '\u{0043}', '\u{0301}'),
+    ('\u{0107}', '\u{0063}', '\u{0301}'),
+    ('\u{0108}', '\u{0043}', '\u{0302}'),
+    ('\u{0109}', '\u{0063}', '\u{0302}'),
+    ('\u{010a}', '\u{0043}', '\u{0307}'),
+    ('\u{010b}', '\u{0063}', '\u{0307}'),
+    ('\u{010c}', '\u{0043}', '\u{030c}'),
+    ('\u{010d}', '\u{0063}', '\u{030c}'),
+    ('\u{010e}', '\u{0044}', '\u{030c}'),
+    ('\u{010f}', '\u{0064}', '\u{030c}'),
+    ('\u{0112}', '\u{0045}', '\u{0304}'),
+    ('\u{0113}', '\u{0065}', '\u{0304}'),
+    ('\u{0114}', '\u{0045}', '\u{0306}'),
+    ('\u{0115}', '\u{0065}', '\u{0306}'),
+    ('\u{0116}', '\u{0045}', '\u{0307}'),
+    ('\u{0117}', '\u{0065}', '\u{0307}'),
+    ('\u{0118}', '\u{0045}', '\u{0328}'),
+    ('\u{0119}', '\u{0065}', '\u{0328}'),
+    ('\u{011a}', '\u{0045}', '\u{030c}'),
+    ('\u{011b}', '\u{0065}', '\u{030c}'),
+    ('\u{011c}', '\u{0047}', '\u{0302}'),
+    ('\u{011d}', '\u{0067}', '\u{0302}'),
+    ('\u{011e}', '\u{0047}', '\u{0306}'),
+    ('\u{011f}', '\u{0067}', '\u{0306}'),
+    ('\u{0120}', '\u{0047}', '\u{0307}'),
+    ('\u{0121}', '\u{0067}', '\u{0307}'),
+    ('\u{0122}', '\u{0047}', '\u{0327}'),
+    ('\u{0123}', '\u{0067}', '\u{0327}'),
+    ('\u{0124}', '\u{0048}', '\u{0302}'),
+    ('\u{0125}', '\u{0068}', '\u{0302}'),
+    ('\u{0128}', '\u{0049}', '\u{0303}'),
+    ('\u{0129}', '\u{0069}', '\u{0303}'),
+    ('\u{012a}', '\u{0049}', '\u{0304}'),
+    ('\u{012b}', '\u{0069}', '\u{0304}'),
+    ('\u{012c}', '\u{0049}', '\u{0306}'),
+    ('\u{012d}', '\u{0069}', '\u{0306}'),
+    ('\u{012e}', '\u{0049}', '\u{0328}'),
+    ('\u{012f}', '\u{0069}', '\u{0328}'),
+    ('\u{0130}', '\u{0049}', '\u{0307}'),
+    ('\u{0134}', '\u{004a}', '\u{0302}'),
+    ('\u{0135}', '\u{006a}', '\u{0302}'),
+    ('\u{0136}', '\u{004b}', '\u{0327}'),
+    ('\u{0137}', '\u{006b}', '\u{0327}'),
+    ('\u{0139}', '\u{004c}', '\u{0301}'),
+    ('\u{013a}', '\u{006c}', '\u{0301}'),
+    ('\u{013b}', '\u{004c}', '\u{0327}'),
+    ('\u{013c}', '\u{006c}', '\u{0327}'),
+    ('\u{013d}', '\u{004c}', '\u{030c}'),
+    ('\u{013e}', '\u{006c}', '\u{030c}'),
+    ('\u{0143}', '\u{004e}', '\u{0301}'),
+    ('\u{0144}', '\u{006e}', '\u{0301}'),
+    ('\u{0145}', '\u{004e}', '\u{0327}'),
+    ('\u{0146}', '\u{006e}', '\u{0327}'),
+    ('\u{0147}', '\u{004e}', '\u{030c}'),
+    ('\u{0148}', '\u{006e}', '\u{030c}'),
+    ('\u{014c}', '\u{004f}', '\u{0304}'),
+    ('\u{014d}', '\u{006f}', '\u{0304}'),
+    ('\u{014e}', '\u{004f}', '\u{0306}'),
+    ('\u{014f}', '\u{006f}', '\u{0306}'),
+    ('\u{0150}', '\u{004f}', '\u{030b}'),
+    ('\u{0151}', '\u{006f}', '\u{030b}'),
+    ('\u{0154}', '\u{0052}', '\u{0301}'),
+    ('\u{0155}', '\u{0072}', '\u{0301}'),
+    ('\u{0156}', '\u{0052}', '\u{0327}'),
+    ('\u{0157}', '\u{0072}', '\u{0327}'),
+    ('\u{0158}', '\u{0052}', '\u{030c}'),
+    ('\u{0159}', '\u{0072}', '\u{030c}'),
+    ('\u{015a}', '\u{0053}', '\u{0301}'),
+    ('\u{015b}', '\u{0073}', '\u{0301}'),
+    ('\u{015c}', '\u{0053}', '\u{0302}'),
+    ('\u{015d}', '\u{0073}', '\u{0302}'),
+    ('\u{015e}', '\u{0053}', '\u{0327}'),
+    ('\u{015f}', '\u{0073}', '\u{0327}'),
+    ('\u{0160}', '\u{0053}', '\u{030c}'),
+    ('\u{0161}', '\u{0073}', '\u{030c}'),
+    ('\u{0162}', '\u{0054}', '\u{0327}'),
+    ('\u{0163}', '\u{0074}', '\u{0327}'),
+    ('\u{0164}', '\u{0054}', '\u{030c}'),
+    ('\u{0165}', '\u{0074}', '\u{030c}'),
+    ('\u{0168}', '\u{0055}', '\u{0303}'),
+    ('\u{0169}', '\u{0075}', '\u{0303}'),
+    ('\u{016a}', '\u{0055}', '\u{0304}'),
+    ('\u{016b}', '\u{0075}', '\u{0304}'),
+    ('\u{016c}', '\u{0055}', '\u{0306}'),
+    ('\u{016d}', '\u{0075}', '\u{0306}'),
+    ('\u{016e}', '\u{0055}', '\u{030a}'),
+    ('\u{016f}', '\u{0075}', '\u{030a}'),
+    ('\u{0170}', '\u{0055}', '\u{030b}'),
+    ('\u{0171}', '\u{0075}', '\u{030b}'),
+    ('\u{0172}', '\u{0055}', '\u{0328}'),
+    ('\u{0173}', '\u{0075}', '\u{0328}'),
+    ('\u{0174}', '\u{0057}', '\u{0302}'),
+    ('\u{0175}', '\u{0077}', '\u{0302}'),
+    ('\u{0176}', '\u{0059}', '\u{0302}'),
+    ('\u{0177}', '\u{0079}', '\u{0302}'),
+    ('\u{0178}', '\u{0059}', '\u{0308}'),
+    ('\u{0179}', '\u{005a}', '\u{0301}'),
+    ('\u{017a}', '\u{007a}', '\u{0301}'),
+    ('\u{017b}', '\u{005a}', '\u{0307}'),
+    ('\u{017c}', '\u{007a}', '\u{0307}'),
+    ('\u{017d}', '\u{005a}', '\u{030c}'),
+    ('\u{017e}', '\u{007a}', '\u{030c}'),
+    ('\u{01a0}', '\u{004f}', '\u{031b}'),
+    ('\u{01a1}', '\u{006f}', '\u{031b}'),
+    ('\u{01af}', '\u{0055}', '\u{031b}'),
+    ('\u{01b0}', '\u{0075}', '\u{031b}'),
+    ('\u{01cd}', '\u{0041}', '\u{030c}'),
+    ('\u{01ce}', '\u{0061}', '\u{030c}'),
+    ('\u{01cf}', '\u{0049}', '\u{030c}'),
+    ('\u{01d0}', '\u{0069}', '\u{030c}'),
+    ('\u{01d1}', '\u{004f}', '\u{030c}'),
+    ('\u{01d2}', '\u{006f}', '\u{030c}'),
+    ('\u{01d3}', '\u{0055}', '\u{030c}'),
+    ('\u{01d4}', '\u{0075}', '\u{030c}'),
+    ('\u{01d5}', '\u{00dc}', '\u{0304}'),
+    ('\u{01d6}', '\u{00fc}', '\u{0304}'),
+    ('\u{01d7}', '\u{00dc}', '\u{0301}'),
+    ('\u{01d8}', '\u{00fc}', '\u{0301}'),
+    ('\u{01d9}', '\u{00dc}', '\u{030c}'),
+    ('\u{01da}', '\u{00fc}', '\u{030c}'),
+    ('\u{01db}', '\u{00dc}', '\u{0300}'),
+    ('\u{01dc}', '\u{00fc}', '\u{0300}'),
+    ('\u{01de}', '\u{00c4}', '\u{0304}'),
+    ('\u{01df}', '\u{00e4}', '\u{0304}'),
+    ('\u{01e0}', '\u{0226}', '\u{0304}'),
+    ('\u{01e1}', '\u{0227}', '\u{0304}'),
+    ('\u{01e2}', '\u{00c6}', '\u{0304}'),
+    ('\u{01e3}', '\u{00e6}', '\u{0304}'),
+    ('\u{01e6}', '\u{0047}', '\u{030c}'),
+    ('\u{01e7}', '\u{0067}', '\u{030c}'),
+    ('\u{01e8}', '\u{004b}', '\u{030c}'),
+    ('\u{01e9}', '\u{006b}', '\u{030c}'),
+    ('\u{01ea}', '\u{004f}', '\u{0328}'),
+    ('\u{01eb}', '\u{006f}', '\u{0328}'),
+    ('\u{01ec}', '\u{01ea}', '\u{0304}'),
+    ('\u{01ed}', '\u{01eb}', '\u{0304}'),
+    ('\u{01ee}', '\u{01b7}', '\u{030c}'),
+    ('\u{01ef}', '\u{0292}', '\u{030c}'),
+    ('\u{01f0}', '\u{006a}', '\u{030c}'),
+    ('\u{01f4}', '\u{0047}', '\u{0301}'),
+    ('\u{01f5}', '\u{0067}', '\u{0301}'),
+    ('\u{01f8}', '\u{004e}', '\u{0300}'),
+    ('\u{01f9}', '\u{006e}', '\u{0300}'),
+    ('\u{01fa}', '\u{00c5}', '\u{0301}'),
+    ('\u{01fb}', '\u{00e5}', '\u{0301}'),
+    ('\u{01fc}', '\u{00c6}', '\u{0301}'),
+    ('\u{01fd}', '\u{00e6}', '\u{0301}'),
+    ('\u{01fe}', '\u{00d8}', '\u{0301}'),
+    ('\u{01ff}', '\u{00f8}', '\u{0301}'),
+    ('\u{0200}', '\u{0041}', '\u{030f}'),
+    ('\u{0201}', '\u{0061}', '\u{030f}'),
+    ('\u{0202}', '\u{0041}', '\u{0311}'),
+    ('\u{0203}', '\u{0061}', '\u{0311}'),
+    ('\u{0204}', '\u{0045}', '\u{030f}'),
+    ('\u{0205}', '\u{0065}', '\u{030f}'),
+    ('\u{0206}', '\u{0045}', '\u{0311}'),
+    ('\u{0207}', '\u{0065}', '\u{0311}'),
+    ('\u{0208}', '\u{0049}', '\u{030f}'),
+    ('\u{0209}', '\u{0069}', '\u{030f}'),
+    ('\u{020a}', '\u{0049}', '\u{0311}'),
+    ('\u{020b}', '\u{0069}', '\u{0311}'),
+    ('\u{020c}', '\u{004f}', '\u{030f}'),
+    ('\u{020d}', '\u{006f}', '\u{030f}'),
+    ('\u{020e}', '\u{004f}', '\u{0311}'),
+    ('\u{020f}', '\u{006f}', '\u{0311}'),
+    ('\u{0210}', '\u{0052}', '\u{030f}'),
+    ('\u{0211}', '\u{0072}', '\u{030f}'),
+    ('\u{0212}', '\u{0052}', '\u{0311}'),
+    ('\u{0213}', '\u{0072}', '\u{0311}'),
+    ('\u{0214}', '\u{0055}', '\u{030f}'),
+    ('\u{0215}', '\u{0075}', '\u{030f}'),
+    ('\u{0216}', '\u{0055}', '\u{0311}'),
+    ('\u{0217}', '\u{0075}', '\u{0311}'),
+    ('\u{0218}', '\u{0053}', '\u{0326}'),
+    ('\u{0219}', '\u{0073}', '\u{0326}'),
+    ('\u{021a}', '\u{0054}', '\u{0326}'),
+    ('\u{021b}', '\u{0074}', '\u{0326}'),
+    ('\u{021e}', '\u{0048}', '\u{030c}'),
+    ('\u{021f}', '\u{0068}', '\u{030c}'),
+    ('\u{0226}', '\u{0041}', '\u{0307}'),
+    ('\u{0227}', '\u{0061}', '\u{0307}'),
+    ('\u{0228}', '\u{0045}', '\u{0327}'),
+    ('\u{0229}', '\u{0065}', '\u{0327}'),
+    ('\u{022a}', '\u{00d6}', '\u{0304}'),
+    ('\u{022b}', '\u{00f6}', '\u{0304}'),
+    ('\u{022c}', '\u{00d5}', '\u{0304}'),
+    ('\u{022d}', '\u{00f5}', '\u{0304}'),
+    ('\u{022e}', '\u{004f}', '\u{0307}'),
+    ('\u{022f}', '\u{006f}', '\u{0307}'),
+    ('\u{0230}', '\u{022e}', '\u{0304}'),
+    ('\u{0231}', '\u{022f}', '\u{0304}'),
+    ('\u{0232}', '\u{0059}', '\u{0304}'),
+    ('\u{0233}', '\u{0079}', '\u{0304}'),
+    ('\u{0340}', '\u{0300}', '\0'),
+    ('\u{0341}', '\u{0301}', '\0'),
+    ('\u{0343}', '\u{0313}', '\0'),
+    ('\u{0344}', '\u{0308}', '\u{0301}'),
+    ('\u{0374}', '\u{02b9}', '\0'),
+    ('\u{037e}', '\u{003b}', '\0'),
+    ('\u{0385}', '\u{00a8}', '\u{0301}'),
+    ('\u{0386}', '\u{0391}', '\u{0301}'),
+    ('\u{0387}', '\u{00b7}', '\0'),
+    ('\u{0388}', '\u{0395}', '\u{0301}'),
+    ('\u{0389}', '\u{0397}', '\u{0301}'),
+    ('\u{038a}', '\u{0399}', '\u{0301}'),
+    ('\u{038c}', '\u{039f}', '\u{0301}'),
+    ('\u{038e}', '\u{03a5}', '\u{0301}'),
+    ('\u{038f}', '\u{03a9}', '\u{0301}'),
+    ('\u{0390}', '\u{03ca}', '\u{0301}'),
+    ('\u{03aa}', '\u{0399}', '\u{0308}'),
+    ('\u{03ab}', '\u{03a5}', '\u{0308}'),
+    ('\u{03ac}', '\u{03b1}', '\u{0301}'),
+    ('\u{03ad}', '\u{03b5}', '\u{0301}'),
+    ('\u{03ae}', '\u{03b7}', '\u{0301}'),
+    ('\u{03af}', '\u{03b9}', '\u{0301}'),
+    ('\u{03b0}', '\u{03cb}', '\u{0301}'),
+    ('\u{03ca}', '\u{03b9}', '\u{0308}'),
+    ('\u{03cb}', '\u{03c5}', '\u{0308}'),
+    ('\u{03cc}', '\u{03bf}', '\u{0301}'),
+    ('\u{03cd}', '\u{03c5}', '\u{0301}'),
+    ('\u{03ce}', '\u{03c9}', '\u{0301}'),
+    ('\u{03d3}', '\u{03d2}', '\u{0301}'),
+    ('\u{03d4}', '\u{03d2}', '\u{0308}'),
+    ('\u{0400}', '\u{0415}', '\u{0300}'),
+    ('\u{0401}', '\u{0415}', '\u{0308}'),
+    ('\u{0403}', '\u{0413}', '\u{0301}'),
+    ('\u{0407}', '\u{0406}', '\u{0308}'),
+    ('\u{040c}', '\u{041a}', '\u{0301}'),
+    ('\u{040d}', '\u{0418}', '\u{0300}'),
+    ('\u{040e}', '\u{0423}', '\u{0306}'),
+    ('\u{0419}', '\u{0418}', '\u{0306}'),
+    ('\u{0439}', '\u{0438}', '\u{0306}'),
+    ('\u{0450}', '\u{0435}', '\u{0300}'),
+    ('\u{0451}', '\u{0435}', '\u{0308}'),
+    ('\u{0453}', '\u{0433}', '\u{0301}'),
+    ('\u{0457}', '\u{0456}', '\u{0308}'),
+    ('\u{045c}', '\u{043a}', '\u{0301}'),
+    ('\u{045d}', '\u{0438}', '\u{0300}'),
+    ('\u{045e}', '\u{0443}', '\u{0306}'),
+    ('\u{0476}', '\u{0474}', '\u{030f}'),
+    ('\u{0477}', '\u{0475}', '\u{030f}'),
+    ('\u{04c1}', '\u{0416}', '\u{0306}'),
+    ('\u{04c2}', '\u{0436}', '\u{0306}'),
+    ('\u{04d0}', '\u{0410}', '\u{0306}'),
+    ('\u{04d1}', '\u{0430}', '\u{0306}'),
+    ('\u{04d2}', '\u{0410}', '\u{0308}'),
+    ('\u{04d3}', '\u{0430}', '\u{0308}'),
+    ('\u{04d6}', '\u{0415}', '\u{0306}'),
+    ('\u{04d7}', '\u{0435}', '\u{0306}'),
+    ('\u{04da}', '\u{04d8}', '\u{0308}'),
+    ('\u{04db}', '\u{04d9}', '\u{0308}'),
+    ('\u{04dc}', '\u{0416}', '\u{0308}'),
+    ('\u{04dd}', '\u{0436}', '\u{0308}'),
+    ('\u{04de}', '\u{0417}', '\u{0308}'),
+    ('\u{04df}', '\u{0437}', '\u{0308}'),
+    ('\u{04e2}', '\u{0418}', '\u{0304}'),
+    ('\u{04e3}', '\u{0438}', '\u{0304}'),
+    ('\u{04e4}', '\u{0418}', '\u{0308}'),
+    ('\u{04e5}', '\u{0438}', '\u{0308}'),
+    ('\u{04e6}', '\u{041e}', '\u{0308}'),
+    ('\u{04e7}', '\u{043e}', '\u{0308}'),
+    ('\u{04ea}', '\u{04e8}', '\u{0308}'),
+    ('\u{04eb}', '\u{04e9}', '\u{0308}'),
+    ('\u{04ec}', '\u{042d}', '\u{0308}'),
+    ('\u{04ed}', '\u{044d}', '\u{0308}'),
+    ('\u{04ee}', '\u{0423}', '\u{0304}'),
+    ('\u{04ef}', '\u{0443}', '\u{0304}'),
+    ('\u{04f0}', '\u{0423}', '\u{0308}'),
+    ('\u{04f1}', '\u{0443}', '\u{0308}'),
+    ('\u{04f2}', '\u{0423}', '\u{030b}'),
+    ('\u{04f3}', '\u{0443}', '\u{030b}'),
+    ('\u{04f4}', '\u{0427}', '\u{0308}'),
+    ('\u{04f5}', '\u{0447}', '\u{0308}'),
+    ('\u{04f8}', '\u{042b}', '\u{0308}'),
+    ('\u{04f9}', '\u{044b}', '\u{0308}'),
+    ('\u{1e00}', '\u{0041}', '\u{0325}'),
+    ('\u{1e01}', '\u{0061}', '\u{0325}'),
+    ('\u{1e02}', '\u{0042}', '\u{0307}'),
+    ('\u{1e03}', '\u{0062}', '\u{0307}'),
+    ('\u{1e04}', '\u{0042}', '\u{0323}'),
+    ('\u{1e05}', '\u{0062}', '\u{0323}'),
+    ('\u{1e06}', '\u{0042}', '\u{0331}'),
+    ('\u{1e07}', '\u{0062}', '\u{0331}'),
+    ('\u{1e08}', '\u{00c7}', '\u{0301}'),
+    ('\u{1e09}', '\u{00e7}', '\u{0301}'),
+    ('\u{1e0a}', '\u{0044}', '\u{0307}'),
+    ('\u{1e0b}', '\u{0064}', '\u{0307}'),
+    ('\u{1e0c}', '\u{0044}', '\u{0323}'),
+    ('\u{1e0d}', '\u{0064}', '\u{0323}'),
+    ('\u{1e0e}', '\u{0044}', '\u{0331}'),
+    ('\u{1e0f}', '\u{0064}', '\u{0331}'),
+    ('\u{1e10}', '\u{0044}', '\u{0327}'),
+    ('\u{1e11}', '\u{0064}', '\u{0327}'),
+    ('\u{1e12}', '\u{0044}', '\u{032d}'),
+    ('\u{1e13}', '\u{0064}', '\u{032d}'),
+    ('\u{1e14}', '\u{0112}', '\u{0300}'),
+    ('\u{1e15}', '\u{0113}', '\u{0300}'),
+    ('\u{1e16}', '\u{0112}', '\u{0301}'),
+    ('\u{1e17}', '\u{0113}', '\u{0301}'),
+    ('\u{1e18}', '\u{0045}', '\u{032d}'),
+    ('\u{1e19}', '\u{0065}', '\u{032d}'),
+    ('\u{1e1a}', '\u{0045}', '\u{0330}'),
+    ('\u{1e1b}', '\u{0065}', '\u{0330}'),
+    ('\u{1e1c}', '\u{0228}', '\u{0306}'),
+    ('\u{1e1d}', '\u{0229}', '\u{0306}'),
+    ('\u{1e1e}', '\u{0046}', '\u{0307}'),
+    ('\u{1e1f}', '\u{0066}', '\u{0307}'),
+    ('\u{1e20}', '\u{0047}', '\u{0304}'),
+    ('\u{1e21}', '\u{0067}', '\u{0304}'),
+    ('\u{1e22}', '\u{0048}', '\u{0307}'),
+    ('\u{1e23}', '\u{0068}', '\u{0307}'),
+    ('\u{1e24}', '\u{0048}', '\u{0323}'),
+    ('\u{1e25}', '\u{0068}', '\u{0323}'),
+    ('\u{1e26}', '\u{0048}', '\u{0308}'),
+    ('\u{1e27}', '\u{0068}', '\u{0308}'),
+    ('\u{1e28}', '\u{0048}', '\u{0327}'),
+    ('\u{1e29}', '\u{0068}', '\u{0327}'),
+    ('\u{1e2a}', '\u{0048}', '\u{032e}'),
+    ('\u{1e2b}', '\u{0068}', '\u{032e}'),
+    ('\u{1e2c}', '\u{0049}', '\u{0330}'),
+    ('\u{1e2d}', '\u{0069}', '\u{0330}'),
+    ('\u{1e2e}', '\u{00cf}', '\u{0301}'),
+    ('\u{1e2f}', '\u{00ef}', '\u{0301}'),
+    ('\u{1e30}', '\u{004b}', '\u{0301}'),
+    ('\u{1e31}', '\u{006b}', '\u{0301}'),
+    ('\u{1e32}', '\u{004b}', '\u{0323}'),
+    ('\u{1e33}', '\u{006b}', '\u{0323}'),
+    ('\u{1e34}', '\u{004b}', '\u{0331}'),
+    ('\u{1e35}', '\u{006b}', '\u{0331}'),
+    ('\u{1e36}', '\u{004c}', '\u{0323}'),
+    ('\u{1e37}', '\u{006c}', '\u{0323}'),
+    ('\u{1e38}', '\u{1e36}', '\u{0304}'),
+    ('\u{1e39}', '\u{1e37}', '\u{0304}'),
+    ('\u{1e3a}', '\u{004c}', '\u{0331}'),
+    ('\u{1e3b}', '\u{006c}', '\u{0331}'),
+    ('\u{1e3c}', '\u{004c}', '\u{032d}'),
+    ('\u{1e3d}', '\u{006c}', '\u{032d}'),
+    ('\u{1e3e}', '\u{004d}', '\u{0301}'),
+    ('\u{1e3f}', '\u{006d}', '\u{0301}'),
+    ('\u{1e40}', '\u{004d}', '\u{0307}'),
+    ('\u{1e41}', '\u{006d}', '\u{0307}'),
+    ('\u{1e42}', '\u{004d}', '\u{0323}'),
+    ('\u{1e43}', '\u{006d}', '\u{0323}'),
+    ('\u{1e44}', '\u{004e}', '\u{0307}'),
+    ('\u{1e45}', '\u{006e}', '\u{0307}'),
+    ('\u{1e46}', '\u{004e}', '\u{0323}'),
+    ('\u{1e47}', '\u{006e}', '\u{0323}'),
+    ('\u{1e48}', '\u{004e}', '\u{0331}'),
+    ('\u{1e49}', '\u{006e}', '\u{0331}'),
+    ('\u{1e4a}', '\u{004e}', '\u{032d}'),
+    ('\u{1e4b}', '\u{006e}', '\u{032d}'),
+    ('\u{1e4c}', '\u{00d5}', '\u{0301}'),
+    ('\u{1e4d}', '\u{00f5}', '\u{0301}'),
+    ('\u{1e4e}', '\u{00d5}', '\u{0308}'),
+    ('\u{1e4f}', '\u{00f5}', '\u{0308}'),
+    ('\u{1e50}', '\u{014c}', '\u{0300}'),
+    ('\u{1e51}', '\u{014d}', '\u{0300}'),
+    ('\u{1e52}', '\u{014c}', '\u{0301}'),
+    ('\u{1e53}', '\u{014d}', '\u{0301}'),
+    ('\u{1e54}', '\u{0050}', '\u{0301}'),
+    ('\u{1e55}', '\u{0070}', '\u{0301}'),
+    ('\u{1e56}', '\u{0050}', '\u{0307}'),
+    ('\u{1e57}', '\u{0070}', '\u{0307}'),
+    ('\u{1e58}', '\u{0052}', '\u{0307}'),
+    ('\u{1e59}', '\u{0072}', '\u{0307}'),
+    ('\u{1e5a}', '\u{0052}', '\u{0323}'),
+    ('\u{1e5b}', '\u{0072}', '\u{0323}'),
+    ('\u{1e5c}', '\u{1e5a}', '\u{0304}'),
+    ('\u{1e5d}', '\u{1e5b}', '\u{0304}'),
+    ('\u{1e5e}', '\u{0052}', '\u{0331}'),
+    ('\u{1e5f}', '\u{0072}', '\u{0331}'),
+    ('\u{1e60}', '\u{0053}', '\u{0307}'),
+    ('\u{1e61}', '\u{0073}', '\u{0307}'),
+    ('\u{1e62}', '\u{0053}', '\u{0323}'),
+    ('\u{1e63}', '\u{0073}', '\u{0323}'),
+    ('\u{1e64}', '\u{015a}', '\u{0307}'),
+    ('\u{1e65}', '\u{015b}', '\u{0307}'),
+    ('\u{1e66}', '\u{0160}', '\u{0307}'),
+    ('\u{1e67}', '\u{0161}', '\u{0307}'),
+    ('\u{1e68}', '\u{1e62}', '\u{0307}'),
+    ('\u{1e69}', '\u{1e63}', '\u{0307}'),
+    ('\u{1e6a}', '\u{0054}', '\u{0307}'),
+    ('\u{1e6b}', '\u{0074}', '\u{0307}'),
+    ('\u{1e6c}', '\u{0054}', '\u{0323}'),
+    ('\u{1e6d}', '\u{0074}', '\u{0323}'),
+    ('\u{1e6e}', '\u{0054}', '\u{0331}'),
+    ('\u{1e6f}', '\u{0074}', '\u{0331}'),
+    ('\u{1e70}', '\u{0054}', '\u{032d}'),
+    ('\u{1e71}', '\u{0074}', '\u{032d}'),
+    ('\u{1e72}', '\u{0055}', '\u{0324}'),
+    ('\u{1e73}', '\u{0075}', '\u{0324}'),
+    ('\u{1e74}', '\u{0055}', '\u{0330}'),
+    ('\u{1e75}', '\u{0075}', '\u{0330}'),
+    ('\u{1e76}', '\u{0055}', '\u{032d}'),
+    ('\u{1e77}', '\u{0075}', '\u{032d}'),
+    ('\u{1e78}', '\u{0168}', '\u{0301}'),
+    ('\u{1e79}', '\u{0169}', '\u{0301}'),
+    ('\u{1e7a}', '\u{016a}', '\u{0308}'),
+    ('\u{1e7b}', '\u{016b}', '\u{0308}'),
+    ('\u{1e7c}', '\u{0056}', '\u{0303}'),
+    ('\u{1e7d}', '\u{0076}', '\u{0303}'),
+    ('\u{1e7e}', '\u{0056}', '\u{0323}'),
+    ('\u{1e7f}', '\u{0076}', '\u{0323}'),
+    ('\u{1e80}', '\u{0057}', '\u{0300}'),
+    ('\u{1e81}', '\u{0077}', '\u{0300}'),
+    ('\u{1e82}', '\u{0057}', '\u{0301}'),
+    ('\u{1e83}', '\u{0077}', '\u{0301}'),
+    ('\u{1e84}', '\u{0057}', '\u{0308}'),
+    ('\u{1e85}', '\u{0077}', '\u{0308}'),
+    ('\u{1e86}', '\u{0057}', '\u{0307}'),
+    ('\u{1e87}', '\u{0077}', '\u{0307}'),
+    ('\u{1e88}', '\u{0057}', '\u{0323}'),
+    ('\u{1e89}', '\u{0077}', '\u{0323}'),
+    ('\u{1e8a}', '\u{0058}', '\u{0307}'),
+    ('\u{1e8b}', '\u{0078}', '\u{0307}'),
+    ('\u{1e8c}', '\u{0058}', '\u{0308}'),
+    ('\u{1e8d}', '\u{0078}', '\u{0308}'),
+    ('\u{1e8e}', '\u{0059}', '\u{0307}'),
+    ('\u{1e8f}', '\u{0079}', '\u{0307}'),
+    ('\u{1e90}', '\u{005a}', '\u{0302}'),
+    ('\u{1e91}', '\u{007a}', '\u{0302}'),
+    ('\u{1e92}', '\u{005a}', '\u{0323}'),
+    ('\u{1e93}', '\u{007a}', '\u{0323}'),
+    ('\u{1e94}', '\u{005a}', '\u{0331}'),
+    ('\u{1e95}', '\u{007a}', '\u{0331}'),
+    ('\u{1e96}', '\u{0068}', '\u{0331}'),
+    ('\u{1e97}', '\u{0074}', '\u{0308}'),
+    ('\u{1e98}', '\u{0077}', '\u{030a}'),
+    ('\u{1e99}', '\u{0079}', '\u{030a}'),
+    ('\u{1e9b}', '\u{017f}', '\u{0307}'),
+    ('\u{1ea0}', '\u{0041}', '\u{0323}'),
+    ('\u{1ea1}', '\u{0061}', '\u{0323}'),
+    ('\u{1ea2}', '\u{0041}', '\u{0309}'),
+    ('\u{1ea3}', '\u{0061}', '\u{0309}'),
+    ('\u{1ea4}', '\u{00c2}', '\u{0301}'),
+    ('\u{1ea5}', '\u{00e2}', '\u{0301}'),
+    ('\u{1ea6}', '\u{00c2}', '\u{0300}'),
+    ('\u{1ea7}', '\u{00e2}', '\u{0300}'),
+    ('\u{1ea8}', '\u{00c2}', '\u{0309}'),
+    ('\u{1ea9}', '\u{00e2}', '\u{0309}'),
+    ('\u{1eaa}', '\u{00c2}', '\u{0303}'),
+    ('\u{1eab}', '\u{00e2}', '\u{0303}'),
+    ('\u{1eac}', '\u{1ea0}', '\u{0302}'),
+    ('\u{1ead}', '\u{1ea1}', '\u{0302}'),
+    ('\u{1eae}', '\u{0102}', '\u{0301}'),
+    ('\u{1eaf}', '\u{0103}', '\u{0301}'),
+    ('\u{1eb0}', '\u{0102}', '\u{0300}'),
+    ('\u{1eb1}', '\u{0103}', '\u{0300}'),
+    ('\u{1eb2}', '\u{0102}', '\u{0309}'),
+    ('\u{1eb3}', '\u{0103}', '\u{0309}'),
+    ('\u{1eb4}', '\u{0102}', '\u{0303}'),
+    ('\u{1eb5}', '\u{0103}', '\u{0303}'),
+    ('\u{1eb6}', '\u{1ea0}', '\u{0306}'),
+    ('\u{1eb7}', '\u{1ea1}', '\u{0306}'),
+    ('\u{1eb8}', '\u{0045}', '\u{0323}'),
+    ('\u{1eb9}', '\u{0065}', '\u{0323}'),
+    ('\u{1eba}', '\u{0045}', '\u{0309}'),
+    ('\u{1ebb}', '\u{0065}', '\u{0309}'),
+    ('\u{1ebc}', '\u{0045}', '\u{0303}'),
+    ('\u{1ebd}', '\u{0065}', '\u{0303}'),
+    ('\u{1ebe}', '\u{00ca}', '\u{0301}'),
+    ('\u{1ebf}', '\u{00ea}', '\u{0301}'),
+    ('\u{1ec0}', '\u{00ca}', '\u{0300}'),
+    ('\u{1ec1}', '\u{00ea}', '\u{0300}'),
+    ('\u{1ec2}', '\u{00ca}', '\u{0309}'),
+    ('\u{1ec3}', '\u{00ea}', '\u{0309}'),
+    ('\u{1ec4}', '\u{00ca}', '\u{0303}'),
+    ('\u{1ec5}', '\u{00ea}', '\u{0303}'),
+    ('\u{1ec6}', '\u{1eb8}', '\u{0302}'),
+    ('\u{1ec7}', '\u{1eb9}', '\u{0302}'),
+    ('\u{1ec8}', '\u{0049}', '\u{0309}'),
+    ('\u{1ec9}', '\u{0069}', '\u{0309}'),
+    ('\u{1eca}', '\u{0049}', '\u{0323}'),
+    ('\u{1ecb}', '\u{0069}', '\u{0323}'),
+    ('\u{1ecc}', '\u{004f}', '\u{0323}'),
+    ('\u{1ecd}', '\u{006f}', '\u{0323}'),
+    ('\u{1ece}', '\u{004f}', '\u{0309}'),
+    ('\u{1ecf}', '\u{006f}', '\u{0309}'),
+    ('\u{1ed0}', '\u{00d4}', '\u{0301}'),
+    ('\u{1ed1}', '\u{00f4}', '\u{0301}'),
+    ('\u{1ed2}', '\u{00d4}', '\u{0300}'),
+    ('\u{1ed3}', '\u{00f4}', '\u{0300}'),
+    ('\u{1ed4}', '\u{00d4}', '\u{0309}'),
+    ('\u{1ed5}', '\u{00f4}', '\u{0309}'),
+    ('\u{1ed6}', '\u{00d4}', '\u{0303}'),
+    ('\u{1ed7}', '\u{00f4}', '\u{0303}'),
+    ('\u{1ed8}', '\u{1ecc}', '\u{0302}'),
+    ('\u{1ed9}', '\u{1ecd}', '\u{0302}'),
+    ('\u{1eda}', '\u{01a0}', '\u{0301}'),
+    ('\u{1edb}', '\u{01a1}', '\u{0301}'),
+    ('\u{1edc}', '\u{01a0}', '\u{0300}'),
+    ('\u{1edd}', '\u{01a1}', '\u{0300}'),
+    ('\u{1ede}', '\u{01a0}', '\u{0309}'),
+    ('\u{1edf}', '\u{01a1}', '\u{0309}'),
+    ('\u{1ee0}', '\u{01a0}', '\u{0303}'),
+    ('\u{1ee1}', '\u{01a1}', '\u{0303}'),
+    ('\u{1ee2}', '\u{01a0}', '\u{0323}'),
+    ('\u{1ee3}', '\u{01a1}', '\u{0323}'),
+    ('\u{1ee4}', '\u{0055}', '\u{0323}'),
+    ('\u{1ee5}', '\u{0075}', '\u{0323}'),
+    ('\u{1ee6}', '\u{0055}', '\u{0309}'),
+    ('\u{1ee7}', '\u{0075}', '\u{0309}'),
+    ('\u{1ee8}', '\u{01af}', '\u{0301}'),
+    ('\u{1ee9}', '\u{01b0}', '\u{0301}'),
+    ('\u{1eea}', '\u{01af}', '\u{0300}'),
+    ('\u{1eeb}', '\u{01b0}', '\u{0300}'),
+    ('\u{1eec}', '\u{01af}', '\u{0309}'),
+    ('\u{1eed}', '\u{01b0}', '\u{0309}'),
+    ('\u{1eee}', '\u{01af}', '\u{0303}'),
+    ('\u{1eef}', '\u{01b0}', '\u{0303}'),
+    ('\u{1ef0}', '\u{01af}', '\u{0323}'),
+    ('\u{1ef1}', '\u{01b0}', '\u{0323}'),
+    ('\u{1ef2}', '\u{0059}', '\u{0300}'),
+    ('\u{1ef3}', '\u{0079}', '\u{0300}'),
+    ('\u{1ef4}', '\u{0059}', '\u{0323}'),
+    ('\u{1ef5}', '\u{0079}', '\u{0323}'),
+    ('\u{1ef6}', '\u{0059}', '\u{0309}'),
+    ('\u{1ef7}', '\u{0079}', '\u{0309}'),
+    ('\u{1ef8}', '\u{0059}', '\u{0303}'),
+    ('\u{1ef9}', '\u{0079}', '\u{0303}'),
+    ('\u{2000}', '\u{2002}', '\0'),
+    ('\u{2001}', '\u{2003}', '\0'),
+];
+
+/// Compatibility decompositions.
+static COMPATIBILITY: &[(char, &[char])] = &[
+    ('\u{00a0}', &['\u{0020}']),
+    ('\u{00a8}', &['\u{0020}', '\u{0308}']),
+    ('\u{00aa}', &['\u{0061}']),
+    ('\u{00af}', &['\u{0020}', '\u{0304}']),
+    ('\u{00b2}', &['\u{0032}']),
+    ('\u{00b3}', &['\u{0033}']),
+    ('\u{00b4}', &['\u{0020}', '\u{0301}']),
+    ('\u{00b5}', &['\u{03bc}']),
+    ('\u{00b8}', &['\u{0020}', '\u{0327}']),
+    ('\u{00b9}', &['\u{0031}']),
+    ('\u{00ba}', &['\u{006f}']),
+    ('\u{00bc}', &['\u{0031}', '\u{2044}', '\u{0034}']),
+    ('\u{00bd}', &['\u{0031}', '\u{2044}', '\u{0032}']),
+    ('\u{00be}', &['\u{0033}', '\u{2044}', '\u{0034}']),
+    ('\u{0132}', &['\u{0049}', '\u{004a}']),
+    ('\u{0133}', &['\u{0069}', '\u{006a}']),
+    ('\u{013f}', &['\u{004c}', '\u{00b7}']),
+    ('\u{0140}', &['\u{006c}', '\u{00b7}']),
+    ('\u{0149}', &['\u{02bc}', '\u{006e}']),
+    ('\u{017f}', &['\u{0073}']),
+    ('\u{01c4}', &['\u{0044}', '\u{017d}']),
+    ('\u{01c5}', &['\u{0044}', '\u{017e}']),
+    ('\u{01c6}', &['\u{0064}', '\u{017e}']),
+    ('\u{01c7}', &['\u{004c}', '\u{004a}']),
+    ('\u{01c8}', &['\u{004c}', '\u{006a}']),
+    ('\u{01c9}', &['\u{006c}', '\u{006a}']),
+    ('\u{01ca}', &['\u{004e}', '\u{004a}']),
+    ('\u{01cb}', &['\u{004e}', '\u{006a}']),
+    ('\u{01cc}', &['\u{006e}', '\u{006a}']),
+    ('\u{01f1}', &['\u{0044}', '\u{005a}']),
+    ('\u{01f2}', &['\u{0044}', '\u{007a}']),
+    ('\u{01f3}', &['\u{0064}', '\u{007a}']),
+    ('\u{037a}', &['\u{0020}', '\u{0345}']),
+    ('\u{0384}', &['\u{0020}', '\u{0301}']),
+    ('\u{03d0}', &['\u{03b2}']),
+    ('\u{03d1}', &['\u{03b8}']),
+    ('\u{03d2}', &['\u{03a5}']),
+    ('\u{03d5}', &['\u{03c6}']),
+    ('\u{03d6}', &['\u{03c0}']),
+    ('\u{03f0}', &['\u{03ba}']),
+    ('\u{03f1}', &['\u{03c1}']),
+    ('\u{03f2}', &['\u{03c2}']),
+    ('\u{03f4}', &['\u{0398}']),
+    ('\u{03f5}', &['\u{03b5}']),
+    ('\u{03f9}', &['\u{03a3}']),
+    ('\u{1e9a}', &['\u{0061}', '\u{02be}']),
+    ('\u{2002}', &['\u{0020}']),
+    ('\u{2003}', &['\u{0020}']),
+    ('\u{2004}', &['\u{0020}']),
+    ('\u{2005}', &['\u{0020}']),
+    ('\u{2006}', &['\u{0020}']),
+    ('\u{2007}', &['\u{0020}']),
+    ('\u{2008}', &['\u{0020}']),
+    ('\u{2009}', &['\u{0020}']),
+    ('\u{200a}', &['\u{0020}']),
+    ('\u{2011}', &['\u{2010}']),
+    ('\u{2017}', &['\u{0020}', '\u{0333}']),
+    ('\u{2024}', &['\u{002e}']),
+    ('\u{2025}', &['\u{002e}', '\u{002e}']),
+    ('\u{2026}', &['\u{002e}', '\u{002e}', '\u{002e}']),
+    ('\u{202f}', &['\u{0020}']),
+    ('\u{2033}', &['\u{2032}', '\u{2032}']),
+    ('\u{2034}', &['\u{2032}', '\u{2032}', '\u{2032}']),
+    ('\u{2036}', &['\u{2035}', '\u{2035}']),
+    ('\u{2037}', &['\u{2035}', '\u{2035}', '\u{2035}']),
+    ('\u{203c}', &['\u{0021}', '\u{0021}']),
+    ('\u{203e}', &['\u{0020}', '\u{0305}']),
+    ('\u{2047}', &['\u{003f}', '\u{003f}']),
+    ('\u{2048}', &['\u{003f}', '\u{0021}']),
+    ('\u{2049}', &['\u{0021}', '\u{003f}']),
+    (
+        '\u{2057}',
+        &['\u{2032}', '\u{2032}', '\u{2032}', '\u{2032}'],
+    ),
+    ('\u{205f}', &['\u{0020}']),
+    ('\u{fb00}', &['\u{0066}', '\u{0066}']),
+    ('\u{fb01}', &['\u{0066}', '\u{0069}']),
+    ('\u{fb02}', &['\u{0066}', '\u{006c}']),
+    ('\u{fb03}', &['\u{0066}', '\u{0066}', '\u{0069}']),
+    ('\u{fb04}', &['\u{0066}', '\u{0066}', '\u{006c}']),
+    ('\u{fb05}', &['\u{017f}', '\u{0074}']),
+    ('\u{fb06}', &['\u{0073}', '\u{0074}']),
+];
+
+/// Canonical combining classes of the combining marks above.
+static COMBINING_CLASS: &[(char, u8)] = &[
+    ('\u{0300}', 230),
+    ('\u{0301}', 230),
+    ('\u{0302}', 230),
+    ('\u{0303}', 230),
+    ('\u{0304}', 230),
+    ('\u{0305}', 230),
+    ('\u{0306}', 230),
+    ('\u{0307}', 230),
+    ('\u{0308}', 230),
+    ('\u{0309}', 230),
+    ('\u{030a}', 230),
+    ('\u{030b}', 230),
+    ('\u{030c}', 230),
+    ('\u{030d}', 230),
+    ('\u{030e}', 230),
+    ('\u{030f}', 230),
+    ('\u{0310}', 230),
+    ('\u{0311}', 230),
+    ('\u{0312}', 230),
+    ('\u{0313}', 230),
+    ('\u{0314}', 230),
+    ('\u{0315}', 232),
+    ('\u{0316}', 220),
+    ('\u{0317}', 220),
+    ('\u{0318}', 220),
+    ('\u{0319}', 220),
+    ('\u{031a}', 232),
+    ('\u{031b}', 216),
+    ('\u{031c}', 220),
+    ('\u{031d}', 220),
+    ('\u{031e}', 220),
+    ('\u{031f}', 220),
+    ('\u{0320}', 220),
+    ('\u{0321}', 202),
+    ('\u{0322}', 202),
+    ('\u{0323}', 220),
+    ('\u{0324}', 220),
+    ('\u{0325}', 220),
+    ('\u{0326}', 220),
+    ('\u{0327}', 202),
+    ('\u{0328}', 202),
+    ('\u{0329}', 220),
+    ('\u{032a}', 220),
+    ('\u{032b}', 220),
+    ('\u{032c}', 220),
+    ('\u{032d}', 220),
+    ('\u{032e}', 220),
+    ('\u{032f}', 220),
+    ('\u{0330}', 220),
+    ('\u{0331}', 220),
+    ('\u{0332}', 220),
+    ('\u{0333}', 220),
+    ('\u{0334}', 1),
+    ('\u{0335}', 1),
+    ('\u{0336}', 1),
+    ('\u{0337}', 1),
+    ('\u{0338}', 1),
+    ('\u{0339}', 220),
+    ('\u{033a}', 220),
+    ('\u{033b}', 220),
+    ('\u{033c}', 220),
+    ('\u{033d}', 230),
+    ('\u{033e}', 230),
+    ('\u{033f}', 230),
+    ('\u{0340}', 230),
+    ('\u{0341}', 230),
+    ('\u{0342}', 230),
+    ('\u{0343}', 230),
+    ('\u{0344}', 230),
+    ('\u{0345}', 240),
+    ('\u{0346}', 230),
+    ('\u{0347}', 220),
+    ('\u{0348}', 220),
+    ('\u{0349}', 220),
+    ('\u{034a}', 230),
+    ('\u{034b}', 230),
+    ('\u{034c}', 230),
+    ('\u{034d}', 220),
+    ('\u{034e}', 220),
+    ('\u{0350}', 230),
+    ('\u{0351}', 230),
+    ('\u{0352}', 230),
+    ('\u{0353}', 220),
+    ('\u{0354}', 220),
+    ('\u{0355}', 220),
+    ('\u{0356}', 220),
+    ('\u{0357}', 230),
+    ('\u{0358}', 232),
+    ('\u{0359}', 220),
+    ('\u{035a}', 220),
+    ('\u{035b}', 230),
+    ('\u{035c}', 233),
+    ('\u{035d}', 234),
+    ('\u{035e}', 234),
+    ('\u{035f}', 233),
+    ('\u{0360}', 234),
+    ('\u{0361}', 234),
+    ('\u{0362}', 233),
+    ('\u{0363}', 230),
+    ('\u{0364}', 230),
+    ('\u{0365}', 230),
+    ('\u{0366}', 230),
+    ('\u{0367}', 230),
+    ('\u{0368}', 230),
+    ('\u{0369}', 230),
+    ('\u{036a}', 230),
+    ('\u{036b}', 230),
+    ('\u{036c}', 230),
+    ('\u{036d}', 230),
+    ('\u{036e}', 230),
+    ('\u{036f}', 230),
+    ('\u{0483}', 230),
+    ('\u{0484}', 230),
+    ('\u{0485}', 230),
+    ('\u{0486}', 230),
+    ('\u{0487}', 230),
+];
+
+/// Primary composites `(first, second, composed)` sorted by `(first, second)`.
+static COMPOSITION: &[(char, char, char)] = &[
+    ('\u{0041}', '\u{0300}', '\u{00c0}'),
+    ('\u{0041}', '\u{0301}', '\u{00c1}'),
+    ('\u{0041}', '\u{0302}', '\u{00c2}'),
+    ('\u{0041}', '\u{0303}', '\u{00c3}'),
+    ('\u{0041}', '\u{0304}', '\u{0100}'),
+    ('\u{0041}', '\u{0306}', '\u{0102}'),
+    ('\u{0041}', '\u{0307}', '\u{0226}'),
+    ('\u{0041}', '\u{0308}', '\u{00c4}'),
+    ('\u{0041}', '\u{0309}', '\u{1ea2}'),
+    ('\u{0041}', '\u{030a}', '\u{00c5}'),
+    ('\u{0041}', '\u{030c}', '\u{01cd}'),
+    ('\u{0041}', '\u{030f}', '\u{0200}'),
+    ('\u{0041}', '\u{0311}', '\u{0202}'),
+    ('\u{0041}', '\u{0323}', '\u{1ea0}'),
+    ('\u{0041}', '\u{0325}', '\u{1e00}'),
+    ('\u{0041}', '\u{0328}', '\u{0104}'),
+    ('\u{0042}', '\u{0307}', '\u{1e02}'),
+    ('\u{0042}', '\u{0323}', '\u{1e04}'),
+    ('\u{0042}', '\u{0331}', '\u{1e06}'),
+    ('\u{0043}', '\u{0301}', '\u{0106}'),
+    ('\u{0043}', '\u{0302}', '\u{0108}'),
+    ('\u{0043}', '\u{0307}', '\u{010a}'),
+    ('\u{0043}', '\u{030c}', '\u{010c}'),
+    ('\u{0043}', '\u{0327}', '\u{00c7}'),
+    ('\u{0044}', '\u{0307}', '\u{1e0a}'),
+    ('\u{0044}', '\u{030c}', '\u{010e}'),
+    ('\u{0044}', '\u{0323}', '\u{1e0c}'),
+    ('\u{0044}', '\u{0327}', '\u{1e10}'),
+    ('\u{0044}', '\u{032d}', '\u{1e12}'),
+    ('\u{0044}', '\u{0331}', '\u{1e0e}'),
+    ('\u{0045}', '\u{0300}', '\u{00c8}'),
+    ('\u{0045}', '\u{0301}', '\u{00c9}'),
+    ('\u{0045}', '\u{0302}', '\u{00ca}'),
+    ('\u{0045}', '\u{0303}', '\u{1ebc}'),
+    ('\u{0045}', '\u{0304}', '\u{0112}'),
+    ('\u{0045}', '\u{0306}', '\u{0114}'),
+    ('\u{0045}', '\u{0307}', '\u{0116}'),
+    ('\u{0045}', '\u{0308}', '\u{00cb}'),
+    ('\u{0045}', '\u{0309}', '\u{1eba}'),
+    ('\u{0045}', '\u{030c}', '\u{011a}'),
+    ('\u{0045}', '\u{030f}', '\u{0204}'),
+    ('\u{0045}', '\u{0311}', '\u{0206}'),
+    ('\u{0045}', '\u{0323}', '\u{1eb8}'),
+    ('\u{0045}', '\u{0327}', '\u{0228}'),
+    ('\u{0045}', '\u{0328}', '\u{0118}'),
+    ('\u{0045}', '\u{032d}', '\u{1e18}'),
+    ('\u{0045}', '\u{0330}', '\u{1e1a}'),
+    ('\u{0046}', '\u{0307}', '\u{1e1e}'),
+    ('\u{0047}', '\u{0301}', '\u{01f4}'),
+    ('\u{0047}', '\u{0302}', '\u{011c}'),
+    ('\u{0047}', '\u{0304}', '\u{1e20}'),
+    ('\u{0047}', '\u{0306}', '\u{011e}'),
+    ('\u{0047}', '\u{0307}', '\u{0120}'),
+    ('\u{0047}', '\u{030c}', '\u{01e6}'),
+    ('\u{0047}', '\u{0327}', '\u{0122}'),
+    ('\u{0048}', '\u{0302}', '\u{0124}'),
+    ('\u{0048}', '\u{0307}', '\u{1e22}'),
+    ('\u{0048}', '\u{0308}', '\u{1e26}'),
+    ('\u{0048}', '\u{030c}', '\u{021e}'),
+    ('\u{0048}', '\u{0323}', '\u{1e24}'),
+    ('\u{0048}', '\u{0327}', '\u{1e28}'),
+    ('\u{0048}', '\u{032e}', '\u{1e2a}'),
+    ('\u{0049}', '\u{0300}', '\u{00cc}'),
+    ('\u{0049}', '\u{0301}', '\u{00cd}'),
+    ('\u{0049}', '\u{0302}', '\u{00ce}'),
+    ('\u{0049}', '\u{0303}', '\u{0128}'),
+    ('\u{0049}', '\u{0304}', '\u{012a}'),
+    ('\u{0049}', '\u{0306}', '\u{012c}'),
+    ('\u{0049}', '\u{0307}', '\u{0130}'),
+    ('\u{0049}', '\u{0308}', '\u{00cf}'),
+    ('\u{0049}', '\u{0309}', '\u{1ec8}'),
+    ('\u{0049}', '\u{030c}', '\u{01cf}'),
+    ('\u{0049}', '\u{030f}', '\u{0208}'),
+    ('\u{0049}', '\u{0311}', '\u{020a}'),
+    ('\u{0049}', '\u{0323}', '\u{1eca}'),
+    ('\u{0049}', '\u{0328}', '\u{012e}'),
+    ('\u{0049}', '\u{0330}', '\u{1e2c}'),
+    ('\u{004a}', '\u{0302}', '\u{0134}'),
+    ('\u{004b}', '\u{0301}', '\u{1e30}'),
+    ('\u{004b}', '\u{030c}', '\u{01e8}'),
+    ('\u{004b}', '\u{0323}', '\u{1e32}'),
+    ('\u{004b}', '\u{0327}', '\u{0136}'),
+    ('\u{004b}', '\u{0331}', '\u{1e34}'),
+    ('\u{004c}', '\u{0301}', '\u{0139}'),
+    ('\u{004c}', '\u{030c}', '\u{013d}'),
+    ('\u{004c}', '\u{0323}', '\u{1e36}'),
+    ('\u{004c}', '\u{0327}', '\u{013b}'),
+    ('\u{004c}', '\u{032d}', '\u{1e3c}'),
+    ('\u{004c}', '\u{0331}', '\u{1e3a}'),
+    ('\u{004d}', '\u{0301}', '\u{1e3e}'),
+    ('\u{004d}', '\u{0307}', '\u{1e40}'),
+    ('\u{004d}', '\u{0323}', '\u{1e42}'),
+    ('\u{004e}', '\u{0300}', '\u{01f8}'),
+    ('\u{004e}', '\u{0301}', '\u{0143}'),
+    ('\u{004e}', '\u{0303}', '\u{00d1}'),
+    ('\u{004e}', '\u{0307}', '\u{1e44}'),
+    ('\u{004e}', '\u{030c}', '\u{0147}'),
+    ('\u{004e}', '\u{0323}', '\u{1e46}'),
+    ('\u{004e}', '\u{0327}', '\u{0145}'),
+    ('\u{004e}', '\u{032d}', '\u{1e4a}'),
+    ('\u{004e}', '\u{0331}', '\u{1e48}'),
+    ('\u{004f}', '\u{0300}', '\u{00d2}'),
+    ('\u{004f}', '\u{0301}', '\u{00d3}'),
+    ('\u{004f}', '\u{0302}', '\u{00d4}'),
+    ('\u{004f}', '\u{0303}', '\u{00d5}'),
+    ('\u{004f}', '\u{0304}', '\u{014c}'),
+    ('\u{004f}', '\u{0306}', '\u{014e}'),
+    ('\u{004f}', '\u{0307}', '\u{022e}'),
+    ('\u{004f}', '\u{0308}', '\u{00d6}'),
+    ('\u{004f}', '\u{0309}', '\u{1ece}'),
+    ('\u{004f}', '\u{030b}', '\u{0150}'),
+    ('\u{004f}', '\u{030c}', '\u{01d1}'),
+    ('\u{004f}', '\u{030f}', '\u{020c}'),
+    ('\u{004f}', '\u{0311}', '\u{020e}'),
+    ('\u{004f}', '\u{031b}', '\u{01a0}'),
+    ('\u{004f}', '\u{0323}', '\u{1ecc}'),
+    ('\u{004f}', '\u{0328}', '\u{01ea}'),
+    ('\u{0050}', '\u{0301}', '\u{1e54}'),
+    ('\u{0050}', '\u{0307}', '\u{1e56}'),
+    ('\u{0052}', '\u{0301}', '\u{0154}'),
+    ('\u{0052}', '\u{0307}', '\u{1e58}'),
+    ('\u{0052}', '\u{030c}', '\u{0158}'),
+    ('\u{0052}', '\u{030f}', '\u{0210}'),
+    ('\u{0052}', '\u{0311}', '\u{0212}'),
+    ('\u{0052}', '\u{0323}', '\u{1e5a}'),
+    ('\u{0052}', '\u{0327}', '\u{0156}'),
+    ('\u{0052}', '\u{0331}', '\u{1e5e}'),
+    ('\u{0053}', '\u{0301}', '\u{015a}'),
+    ('\u{0053}', '\u{0302}', '\u{015c}'),
+    ('\u{0053}', '\u{0307}', '\u{1e60}'),
+    ('\u{0053}', '\u{030c}', '\u{0160}'),
+    ('\u{0053}', '\u{0323}', '\u{1e62}'),
+    ('\u{0053}', '\u{0326}', '\u{0218}'),
+    ('\u{0053}', '\u{0327}', '\u{015e}'),
+    ('\u{0054}', '\u{0307}', '\u{1e6a}'),
+    ('\u{0054}', '\u{030c}', '\u{0164}'),
+    ('\u{0054}', '\u{0323}', '\u{1e6c}'),
+    ('\u{0054}', '\u{0326}', '\u{021a}'),
+    ('\u{0054}', '\u{0327}', '\u{0162}'),
+    ('\u{0054}', '\u{032d}', '\u{1e70}'),
+    ('\u{0054}', '\u{0331}', '\u{1e6e}'),
+    ('\u{0055}', '\u{0300}', '\u{00d9}'),
+    ('\u{0055}', '\u{0301}', '\u{00da}'),
+    ('\u{0055}', '\u{0302}', '\u{00db}'),
+    ('\u{0055}', '\u{0303}', '\u{0168}'),
+    ('\u{0055}', '\u{0304}', '\u{016a}'),
+    ('\u{0055}', '\u{0306}', '\u{016c}'),
+    ('\u{0055}', '\u{0308}', '\u{00dc}'),
+    ('\u{0055}', '\u{0309}', '\u{1ee6}'),
+    ('\u{0055}', '\u{030a}', '\u{016e}'),
+    ('\u{0055}', '\u{030b}', '\u{0170}'),
+    ('\u{0055}', '\u{030c}', '\u{01d3}'),
+    ('\u{0055}', '\u{030f}', '\u{0214}'),
+    ('\u{0055}', '\u{0311}', '\u{0216}'),
+    ('\u{0055}', '\u{031b}', '\u{01af}'),
+    ('\u{0055}', '\u{0323}', '\u{1ee4}'),
+    ('\u{0055}', '\u{0324}', '\u{1e72}'),
+    ('\u{0055}', '\u{0328}', '\u{0172}'),
+    ('\u{0055}', '\u{032d}', '\u{1e76}'),
+    ('\u{0055}', '\u{0330}', '\u{1e74}'),
+    ('\u{0056}', '\u{0303}', '\u{1e7c}'),
+    ('\u{0056}', '\u{0323}', '\u{1e7e}'),
+    ('\u{0057}', '\u{0300}', '\u{1e80}'),
+    ('\u{0057}', '\u{0301}', '\u{1e82}'),
+    ('\u{0057}', '\u{0302}', '\u{0174}'),
+    ('\u{0057}', '\u{0307}', '\u{1e86}'),
+    ('\u{0057}', '\u{0308}', '\u{1e84}'),
+    ('\u{0057}', '\u{0323}', '\u{1e88}'),
+    ('\u{0058}', '\u{0307}', '\u{1e8a}'),
+    ('\u{0058}', '\u{0308}', '\u{1e8c}'),
+    ('\u{0059}', '\u{0300}', '\u{1ef2}'),
+    ('\u{0059}', '\u{0301}', '\u{00dd}'),
+    ('\u{0059}', '\u{0302}', '\u{0176}'),
+    ('\u{0059}', '\u{0303}', '\u{1ef8}'),
+    ('\u{0059}', '\u{0304}', '\u{0232}'),
+    ('\u{0059}', '\u{0307}', '\u{1e8e}'),
+    ('\u{0059}', '\u{0308}', '\u{0178}'),
+    ('\u{0059}', '\u{0309}', '\u{1ef6}'),
+    ('\u{0059}', '\u{0323}', '\u{1ef4}'),
+    ('\u{005a}', '\u{0301}', '\u{0179}'),
+    ('\u{005a}', '\u{0302}', '\u{1e90}'),
+    ('\u{005a}', '\u{0307}', '\u{017b}'),
+    ('\u{005a}', '\u{030c}', '\u{017d}'),
+    ('\u{005a}', '\u{0323}', '\u{1e92}'),
+    ('\u{005a}', '\u{0331}', '\u{1e94}'),
+    ('\u{0061}', '\u{0300}', '\u{00e0}'),
+    ('\u{0061}', '\u{0301}', '\u{00e1}'),
+    ('\u{0061}', '\u{0302}', '\u{00e2}'),
+    ('\u{0061}', '\u{0303}', '\u{00e3}'),
+    ('\u{0061}', '\u{0304}', '\u{0101}'),
+    ('\u{0061}', '\u{0306}', '\u{0103}'),
+    ('\u{0061}', '\u{0307}', '\u{0227}'),
+    ('\u{0061}', '\u{0308}', '\u{00e4}'),
+    ('\u{0061}', '\u{0309}', '\u{1ea3}'),
+    ('\u{0061}', '\u{030a}', '\u{00e5}'),
+    ('\u{0061}', '\u{030c}', '\u{01ce}'),
+    ('\u{0061}', '\u{030f}', '\u{0201}'),
+    ('\u{0061}', '\u{0311}', '\u{0203}'),
+    ('\u{0061}', '\u{0323}', '\u{1ea1}'),
+    ('\u{0061}', '\u{0325}', '\u{1e01}'),
+    ('\u{0061}', '\u{0328}', '\u{0105}'),
+    ('\u{0062}', '\u{0307}', '\u{1e03}'),
+    ('\u{0062}', '\u{0323}', '\u{1e05}'),
+    ('\u{0062}', '\u{0331}', '\u{1e07}'),
+    ('\u{0063}', '\u{0301}', '\u{0107}'),
+    ('\u{0063}', '\u{0302}', '\u{0109}'),
+    ('\u{0063}', '\u{0307}', '\u{010b}'),
+    ('\u{0063}', '\u{030c}', '\u{010d}'),
+    ('\u{0063}', '\u{0327}', '\u{00e7}'),
+    ('\u{0064}', '\u{0307}', '\u{1e0b}'),
+    ('\u{0064}', '\u{030c}', '\u{010f}'),
+    ('\u{0064}', '\u{0323}', '\u{1e0d}'),
+    ('\u{0064}', '\u{0327}', '\u{1e11}'),
+    ('\u{0064}', '\u{032d}', '\u{1e13}'),
+    ('\u{0064}', '\u{0331}', '\u{1e0f}'),
+    ('\u{0065}', '\u{0300}', '\u{00e8}'),
+    ('\u{0065}', '\u{0301}', '\u{00e9}'),
+    ('\u{0065}', '\u{0302}', '\u{00ea}'),
+    ('\u{0065}', '\u{0303}', '\u{1ebd}'),
+    ('\u{0065}', '\u{0304}', '\u{0113}'),
+    ('\u{0065}', '\u{0306}', '\u{0115}'),
+    ('\u{0065}', '\u{0307}', '\u{0117}'),
+    ('\u{0065}', '\u{0308}', '\u{00eb}'),
+    ('\u{0065}', '\u{0309}', '\u{1ebb}'),
+    ('\u{0065}', '\u{030c}', '\u{011b}'),
+    ('\u{0065}', '\u{030f}', '\u{0205}'),
+    ('\u{0065}', '\u{0311}', '\u{0207}'),
+    ('\u{0065}', '\u{0323}', '\u{1eb9}'),
+    ('\u{0065}', '\u{0327}', '\u{0229}'),
+    ('\u{0065}', '\u{0328}', '\u{0119}'),
+    ('\u{0065}', '\u{032d}', '\u{1e19}'),
+    ('\u{0065}', '\u{0330}', '\u{1e1b}'),
+    ('\u{0066}', '\u{0307}', '\u{1e1f}'),
+    ('\u{0067}', '\u{0301}', '\u{01f5}'),
+    ('\u{0067}', '\u{0302}', '\u{011d}'),
+    ('\u{0067}', '\u{0304}', '\u{1e21}'),
+    ('\u{0067}', '\u{0306}', '\u{011f}'),
+    ('\u{0067}', '\u{0307}', '\u{0121}'),
+    ('\u{0067}', '\u{030c}', '\u{01e7}'),
+    ('\u{0067}', '\u{0327}', '\u{0123}'),
+    ('\u{0068}', '\u{0302}', '\u{0125}'),
+    ('\u{0068}', '\u{0307}', '\u{1e23}'),
+    ('\u{0068}', '\u{0308}', '\u{1e27}'),
+    ('\u{0068}', '\u{030c}', '\u{021f}'),
+    ('\u{0068}', '\u{0323}', '\u{1e25}'),
+    ('\u{0068}', '\u{0327}', '\u{1e29}'),
+    ('\u{0068}', '\u{032e}', '\u{1e2b}'),
+    ('\u{0068}', '\u{0331}', '\u{1e96}'),
+    ('\u{0069}', '\u{0300}', '\u{00ec}'),
+    ('\u{0069}', '\u{0301}', '\u{00ed}'),
+    ('\u{0069}', '\u{0302}', '\u{00ee}'),
+    ('\u{0069}', '\u{0303}', '\u{0129}'),
+    ('\u{0069}', '\u{0304}', '\u{012b}'),
+    ('\u{0069}', '\u{0306}', '\u{012d}'),
+    ('\u{0069}', '\u{0308}', '\u{00ef}'),
+    ('\u{0069}', '\u{0309}', '\u{1ec9}'),
+    ('\u{0069}', '\u{030c}', '\u{01d0}'),
+    ('\u{0069}', '\u{030f}', '\u{0209}'),
+    ('\u{0069}', '\u{0311}', '\u{020b}'),
+    ('\u{0069}', '\u{0323}', '\u{1ecb}'),
+    ('\u{0069}', '\u{0328}', '\u{012f}'),
+    ('\u{0069}', '\u{0330}', '\u{1e2d}'),
+    ('\u{006a}', '\u{0302}', '\u{0135}'),
+    ('\u{006a}', '\u{030c}', '\u{01f0}'),
+    ('\u{006b}', '\u{0301}', '\u{1e31}'),
+    ('\u{006b}', '\u{030c}', '\u{01e9}'),
+    ('\u{006b}', '\u{0323}', '\u{1e33}'),
+    ('\u{006b}', '\u{0327}', '\u{0137}'),
+    ('\u{006b}', '\u{0331}', '\u{1e35}'),
+    ('\u{006c}', '\u{0301}', '\u{013a}'),
+    ('\u{006c}', '\u{030c}', '\u{013e}'),
+    ('\u{006c}', '\u{0323}', '\u{1e37}'),
+    ('\u{006c}', '\u{0327}', '\u{013c}'),
+    ('\u{006c}', '\u{032d}', '\u{1e3d}'),
+    ('\u{006c}', '\u{0331}', '\u{1e3b}'),
+    ('\u{006d}', '\u{0301}', '\u{1e3f}'),
+    ('\u{006d}', '\u{0307}', '\u{1e41}'),
+    ('\u{006d}', '\u{0323}', '\u{1e43}'),
+    ('\u{006e}', '\u{0300}', '\u{01f9}'),
+    ('\u{006e}', '\u{0301}', '\u{0144}'),
+    ('\u{006e}', '\u{0303}', '\u{00f1}'),
+    ('\u{006e}', '\u{0307}', '\u{1e45}'),
+    ('\u{006e}', '\u{030c}', '\u{0148}'),
+    ('\u{006e}', '\u{0323}', '\u{1e47}'),
+    ('\u{006e}', '\u{0327}', '\u{0146}'),
+    ('\u{006e}', '\u{032d}', '\u{1e4b}'),
+    ('\u{006e}', '\u{0331}', '\u{1e49}'),
+    ('\u{006f}', '\u{0300}', '\u{00f2}'),
+    ('\u{006f}', '\u{0301}', '\u{00f3}'),
+    ('\u{006f}', '\u{0302}', '\u{00f4}'),
+    ('\u{006f}', '\u{0303}', '\u{00f5}'),
+    ('\u{006f}', '\u{0304}', '\u{014d}'),
+    ('\u{006f}', '\u{0306}', '\u{014f}'),
+    ('\u{006f}', '\u{0307}', '\u{022f}'),
+    ('\u{006f}', '\u{0308}', '\u{00f6}'),
+    ('\u{006f}', '\u{0309}', '\u{1ecf}'),
+    ('\u{006f}', '\u{030b}', '\u{0151}'),
+    ('\u{006f}', '\u{030c}', '\u{01d2}'),
+    ('\u{006f}', '\u{030f}', '\u{020d}'),
+    ('\u{006f}', '\u{0311}', '\u{020f}'),
+    ('\u{006f}', '\u{031b}', '\u{01a1}'),
+    ('\u{006f}', '\u{0323}', '\u{1ecd}'),
+    ('\u{006f}', '\u{0328}', '\u{01eb}'),
+    ('\u{0070}', '\u{0301}', '\u{1e55}'),
+    ('\u{0070}', '\u{0307}', '\u{1e57}'),
+    ('\u{0072}', '\u{0301}', '\u{0155}'),
+    ('\u{0072}', '\u{0307}', '\u{1e59}'),
+    ('\u{0072}', '\u{030c}', '\u{0159}'),
+    ('\u{0072}', '\u{030f}', '\u{0211}'),
+    ('\u{0072}', '\u{0311}', '\u{0213}'),
+    ('\u{0072}', '\u{0323}', '\u{1e5b}'),
+    ('\u{0072}', '\u{0327}', '\u{0157}'),
+    ('\u{0072}', '\u{0331}', '\u{1e5f}'),
+    ('\u{0073}', '\u{0301}', '\u{015b}'),
+    ('\u{0073}', '\u{0302}', '\u{015d}'),
+    ('\u{0073}', '\u{0307}', '\u{1e61}'),
+    ('\u{0073}', '\u{030c}', '\u{0161}'),
+    ('\u{0073}', '\u{0323}', '\u{1e63}'),
+    ('\u{0073}', '\u{0326}', '\u{0219}'),
+    ('\u{0073}', '\u{0327}', '\u{015f}'),
+    ('\u{0074}', '\u{0307}', '\u{1e6b}'),
+    ('\u{0074}', '\u{0308}', '\u{1e97}'),
+    ('\u{0074}', '\u{030c}', '\u{0165}'),
+    ('\u{0074}', '\u{0323}', '\u{1e6d}'),
+    ('\u{0074}', '\u{0326}', '\u{021b}'),
+    ('\u{0074}', '\u{0327}', '\u{0163}'),
+    ('\u{0074}', '\u{032d}', '\u{1e71}'),
+    ('\u{0074}', '\u{0331}', '\u{1e6f}'),
+    ('\u{0075}', '\u{0300}', '\u{00f9}'),
+    ('\u{0075}', '\u{0301}', '\u{00fa}'),
+    ('\u{0075}', '\u{0302}', '\u{00fb}'),
+    ('\u{0075}', '\u{0303}', '\u{0169}'),
+    ('\u{0075}', '\u{0304}', '\u{016b}'),
+    ('\u{0075}', '\u{0306}', '\u{016d}'),
+    ('\u{0075}', '\u{0308}', '\u{00fc}'),
+    ('\u{0075}', '\u{0309}', '\u{1ee7}'),
+    ('\u{0075}', '\u{030a}', '\u{016f}'),
+    ('\u{0075}', '\u{030b}', '\u{0171}'),
+    ('\u{0075}', '\u{030c}', '\u{01d4}'),
+    ('\u{0075}', '\u{030f}', '\u{0215}'),
+    ('\u{0075}', '\u{0311}', '\u{0217}'),
+    ('\u{0075}', '\u{031b}', '\u{01b0}'),
+    ('\u{0075}', '\u{0323}', '\u{1ee5}'),
+    ('\u{0075}', '\u{0324}', '\u{1e73}'),
+    ('\u{0075}', '\u{0328}', '\u{0173}'),
+    ('\u{0075}', '\u{032d}', '\u{1e77}'),
+    ('\u{0075}', '\u{0330}', '\u{1e75}'),
+    ('\u{0076}', '\u{0303}', '\u{1e7d}'),
+    ('\u{0076}', '\u{0323}', '\u{1e7f}'),
+    ('\u{0077}', '\u{0300}', '\u{1e81}'),
+    ('\u{0077}', '\u{0301}', '\u{1e83}'),
+    ('\u{0077}', '\u{0302}', '\u{0175}'),
+    ('\u{0077}', '\u{0307}', '\u{1e87}'),
+    ('\u{0077}', '\u{0308}', '\u{1e85}'),
+    ('\u{0077}', '\u{030a}', '\u{1e98}'),
+    ('\u{0077}', '\u{0323}', '\u{1e89}'),
+    ('\u{0078}', '\u{0307}', '\u{1e8b}'),
+    ('\u{0078}', '\u{0308}', '\u{1e8d}'),
+    ('\u{0079}', '\u{0300}', '\u{1ef3}'),
+    ('\u{0079}', '\u{0301}', '\u{00fd}'),
+    ('\u{0079}', '\u{0302}', '\u{0177}'),
+    ('\u{0079}', '\u{0303}', '\u{1ef9}'),
+    ('\u{0079}', '\u{0304}', '\u{0233}'),
+    ('\u{0079}', '\u{0307}', '\u{1e8f}'),
+    ('\u{0079}', '\u{0308}', '\u{00ff}'),
+    ('\u{0079}', '\u{0309}', '\u{1ef7}'),
+    ('\u{0079}', '\u{030a}', '\u{1e99}'),
+    ('\u{0079}', '\u{0323}', '\u{1ef5}'),
+    ('\u{007a}', '\u{0301}', '\u{017a}'),
+    ('\u{007a}', '\u{0302}', '\u{1e91}'),
+    ('\u{007a}', '\u{0307}', '\u{017c}'),
+    ('\u{007a}', '\u{030c}', '\u{017e}'),
+    ('\u{007a}', '\u{0323}', '\u{1e93}'),
+    ('\u{007a}', '\u{0331}', '\u{1e95}'),
+    ('\u{00a8}', '\u{0301}', '\u{0385}'),
+    ('\u{00c2}', '\u{0300}', '\u{1ea6}'),
+    ('\u{00c2}', '\u{0301}', '\u{1ea4}'),
+    ('\u{00c2}', '\u{0303}', '\u{1eaa}'),
+    ('\u{00c2}', '\u{0309}', '\u{1ea8}'),
+    ('\u{00c4}', '\u{0304}', '\u{01de}'),
+    ('\u{00c5}', '\u{0301}', '\u{01fa}'),
+    ('\u{00c6}', '\u{0301}', '\u{01fc}'),
+    ('\u{00c6}', '\u{0304}', '\u{01e2}'),
+    ('\u{00c7}', '\u{0301}', '\u{1e08}'),
+    ('\u{00ca}', '\u{0300}', '\u{1ec0}'),
+    ('\u{00ca}', '\u{0301}', '\u{1ebe}'),
+    ('\u{00ca}', '\u{0303}', '\u{1ec4}'),
+    ('\u{00ca}', '\u{0309}', '\u{1ec2}'),
+    ('\u{00cf}', '\u{0301}', '\u{1e2e}'),
+    ('\u{00d4}', '\u{0300}', '\u{1ed2}'),
+    ('\u{00d4}', '\u{0301}', '\u{1ed0}'),
+    ('\u{00d4}', '\u{0303}', '\u{1ed6}'),
+    ('\u{00d4}', '\u{0309}', '\u{1ed4}'),
+    ('\u{00d5}', '\u{0301}', '\u{1e4c}'),
+    ('\u{00d5}', '\u{0304}', '\u{022c}'),
+    ('\u{00d5}', '\u{0308}', '\u{1e4e}'),
+    ('\u{00d6}', '\u{0304}', '\u{022a}'),
+    ('\u{00d8}', '\u{0301}', '\u{01fe}'),
+    ('\u{00dc}', '\u{0300}', '\u{01db}'),
+    ('\u{00dc}', '\u{0301}', '\u{01d7}'),
+    ('\u{00dc}', '\u{0304}', '\u{01d5}'),
+    ('\u{00dc}', '\u{030c}', '\u{01d9}'),
+    ('\u{00e2}', '\u{0300}', '\u{1ea7}'),
+    ('\u{00e2}', '\u{0301}', '\u{1ea5}'),
+    ('\u{00e2}', '\u{0303}', '\u{1eab}'),
+    ('\u{00e2}', '\u{0309}', '\u{1ea9}'),
+    ('\u{00e4}', '\u{0304}', '\u{01df}'),
+    ('\u{00e5}', '\u{0301}', '\u{01fb}'),
+    ('\u{00e6}', '\u{0301}', '\u{01fd}'),
+    ('\u{00e6}', '\u{0304}', '\u{01e3}'),
+    ('\u{00e7}', '\u{0301}', '\u{1e09}'),
+    ('\u{00ea}', '\u{0300}', '\u{1ec1}'),
+    ('\u{00ea}', '\u{0301}', '\u{1ebf}'),
+    ('\u{00ea}', '\u{0303}', '\u{1ec5}'),
+    ('\u{00ea}', '\u{0309}', '\u{1ec3}'),
+    ('\u{00ef}', '\u{0301}', '\u{1e2f}'),
+    ('\u{00f4}', '\u{0300}', '\u{1ed3}'),
+    ('\u{00f4}', '\u{0301}', '\u{1ed1}'),
+    ('\u{00f4}', '\u{0303}', '\u{1ed7}'),
+    ('\u{00f4}', '\u{0309}', '\u{1ed5}'),
+    ('\u{00f5}', '\u{0301}', '\u{1e4d}'),
+    ('\u{00f5}', '\u{0304}', '\u{022d}'),
+    ('\u{00f5}', '\u{0308}', '\u{1e4f}'),
+    ('\u{00f6}', '\u{0304}', '\u{022b}'),
+    ('\u{00f8}', '\u{0301}', '\u{01ff}'),
+    ('\u{00fc}', '\u{0300}', '\u{01dc}'),
+    ('\u{00fc}', '\u{0301}', '\u{01d8}'),
+    ('\u{00fc}', '\u{0304}', '\u{01d6}'),
+    ('\u{00fc}', '\u{030c}', '\u{01da}'),
+    ('\u{0102}', '\u{0300}', '\u{1eb0}'),
+    ('\u{0102}', '\u{0301}', '\u{1eae}'),
+    ('\u{0102}', '\u{0303}', '\u{1eb4}'),
+    ('\u{0102}', '\u{0309}', '\u{1eb2}'),
+    ('\u{0103}', '\u{0300}', '\u{1eb1}'),
+    ('\u{0103}', '\u{0301}', '\u{1eaf}'),
+    ('\u{0103}', '\u{0303}', '\u{1eb5}'),
+    ('\u{0103}', '\u{0309}', '\u{1eb3}'),
+    ('\u{0112}', '\u{0300}', '\u{1e14}'),
+    ('\u{0112}', '\u{0301}', '\u{1e16}'),
+    ('\u{0113}', '\u{0300}', '\u{1e15}'),
+    ('\u{0113}', '\u{0301}', '\u{1e17}'),
+    ('\u{014c}', '\u{0300}', '\u{1e50}'),
+    ('\u{014c}', '\u{0301}', '\u{1e52}'),
+    ('\u{014d}', '\u{0300}', '\u{1e51}'),
+    ('\u{014d}', '\u{0301}', '\u{1e53}'),
+    ('\u{015a}', '\u{0307}', '\u{1e64}'),
+    ('\u{015b}', '\u{0307}', '\u{1e65}'),
+    ('\u{0160}', '\u{0307}', '\u{1e66}'),
+    ('\u{0161}', '\u{0307}', '\u{1e67}'),
+    ('\u{0168}', '\u{0301}', '\u{1e78}'),
+    ('\u{0169}', '\u{0301}', '\u{1e79}'),
+    ('\u{016a}', '\u{0308}', '\u{1e7a}'),
+    ('\u{016b}', '\u{0308}', '\u{1e7b}'),
+    ('\u{017f}', '\u{0307}', '\u{1e9b}'),
+    ('\u{01a0}', '\u{0300}', '\u{1edc}'),
+    ('\u{01a0}', '\u{0301}', '\u{1eda}'),
+    ('\u{01a0}', '\u{0303}', '\u{1ee0}'),
+    ('\u{01a0}', '\u{0309}', '\u{1ede}'),
+    ('\u{01a0}', '\u{0323}', '\u{1ee2}'),
+    ('\u{01a1}', '\u{0300}', '\u{1edd}'),
+    ('\u{01a1}', '\u{0301}', '\u{1edb}'),
+    ('\u{01a1}', '\u{0303}', '\u{1ee1}'),
+    ('\u{01a1}', '\u{0309}', '\u{1edf}'),
+    ('\u{01a1}', '\u{0323}', '\u{1ee3}'),
+    ('\u{01af}', '\u{0300}', '\u{1eea}'),
+    ('\u{01af}', '\u{0301}', '\u{1ee8}'),
+    ('\u{01af}', '\u{0303}', '\u{1eee}'),
+    ('\u{01af}', '\u{0309}', '\u{1eec}'),
+    ('\u{01af}', '\u{0323}', '\u{1ef0}'),
+    ('\u{01b0}', '\u{0300}', '\u{1eeb}'),
+    ('\u{01b0}', '\u{0301}', '\u{1ee9}'),
+    ('\u{01b0}', '\u{0303}', '\u{1eef}'),
+    ('\u{01b0}', '\u{0309}', '\u{1eed}'),
+    ('\u{01b0}', '\u{0323}', '\u{1ef1}'),
+    ('\u{01b7}', '\u{030c}', '\u{01ee}'),
+    ('\u{01ea}', '\u{0304}', '\u{01ec}'),
+    ('\u{01eb}', '\u{0304}', '\u{01ed}'),
+    ('\u{0226}', '\u{0304}', '\u{01e0}'),
+    ('\u{0227}', '\u{0304}', '\u{01e1}'),
+    ('\u{0228}', '\u{0306}', '\u{1e1c}'),
+    ('\u{0229}', '\u{0306}', '\u{1e1d}'),
+    ('\u{022e}', '\u{0304}', '\u{0230}'),
+    ('\u{022f}', '\u{0304}', '\u{0231}'),
+    ('\u{0292}', '\u{030c}', '\u{01ef}'),
+    ('\u{0391}', '\u{0301}', '\u{0386}'),
+    ('\u{0395}', '\u{0301}', '\u{0388}'),
+    ('\u{0397}', '\u{0301}', '\u{0389}'),
+    ('\u{0399}', '\u{0301}', '\u{038a}'),
+    ('\u{0399}', '\u{0308}', '\u{03aa}'),
+    ('\u{039f}', '\u{0301}', '\u{038c}'),
+    ('\u{03a5}', '\u{0301}', '\u{038e}'),
+    ('\u{03a5}', '\u{0308}', '\u{03ab}'),
+    ('\u{03a9}', '\u{0301}', '\u{038f}'),
+    ('\u{03b1}', '\u{0301}', '\u{03ac}'),
+    ('\u{03b5}', '\u{0301}', '\u{03ad}'),
+    ('\u{03b7}', '\u{0301}', '\u{03ae}'),
+    ('\u{03b9}', '\u{0301}', '\u{03af}'),
+    ('\u{03b9}', '\u{0308}', '\u{03ca}'),
+    ('\u{03bf}', '\u{0301}', '\u{03cc}'),
+    ('\u{03c5}', '\u{0301}', '\u{03cd}'),
+    ('\u{03c5}', '\u{0308}', '\u{03cb}'),
+    ('\u{03c9}', '\u{0301}', '\u{03ce}'),
+    ('\u{03ca}', '\u{0301}', '\u{0390}'),
+    ('\u{03cb}', '\u{0301}', '\u{03b0}'),
+    ('\u{03d2}', '\u{0301}', '\u{03d3}'),
+    ('\u{03d2}', '\u{0308}', '\u{03d4}'),
+    ('\u{0406}', '\u{0308}', '\u{0407}'),
+    ('\u{0410}', '\u{0306}', '\u{04d0}'),
+    ('\u{0410}', '\u{0308}', '\u{04d2}'),
+    ('\u{0413}', '\u{0301}', '\u{0403}'),
+    ('\u{0415}', '\u{0300}', '\u{0400}'),
+    ('\u{0415}', '\u{0306}', '\u{04d6}'),
+    ('\u{0415}', '\u{0308}', '\u{0401}'),
+    ('\u{0416}', '\u{0306}', '\u{04c1}'),
+    ('\u{0416}', '\u{0308}', '\u{04dc}'),
+    ('\u{0417}', '\u{0308}', '\u{04de}'),
+    ('\u{0418}', '\u{0300}', '\u{040d}'),
+    ('\u{0418}', '\u{0304}', '\u{04e2}'),
+    ('\u{0418}', '\u{0306}', '\u{0419}'),
+    ('\u{0418}', '\u{0308}', '\u{04e4}'),
+    ('\u{041a}', '\u{0301}', '\u{040c}'),
+    ('\u{041e}', '\u{0308}', '\u{04e6}'),
+    ('\u{0423}', '\u{0304}', '\u{04ee}'),
+    ('\u{0423}', '\u{0306}', '\u{040e}'),
+    ('\u{0423}', '\u{0308}', '\u{04f0}'),
+    ('\u{0423}', '\u{030b}', '\u{04f2}'),
+    ('\u{0427}', '\u{0308}', '\u{04f4}'),
+    ('\u{042b}', '\u{0308}', '\u{04f8}'),
+    ('\u{042d}', '\u{0308}', '\u{04ec}'),
+    ('\u{0430}', '\u{0306}', '\u{04d1}'),
+    ('\u{0430}', '\u{0308}', '\u{04d3}'),
+    ('\u{0433}', '\u{0301}', '\u{0453}'),
+    ('\u{0435}', '\u{0300}', '\u{0450}'),
+    ('\u{0435}', '\u{0306}', '\u{04d7}'),
+    ('\u{0435}', '\u{0308}', '\u{0451}'),
+    ('\u{0436}', '\u{0306}', '\u{04c2}'),
+    ('\u{0436}', '\u{0308}', '\u{04dd}'),
+    ('\u{0437}', '\u{0308}', '\u{04df}'),
+    ('\u{0438}', '\u{0300}', '\u{045d}'),
+    ('\u{0438}', '\u{0304}', '\u{04e3}'),
+    ('\u{0438}', '\u{0306}', '\u{0439}'),
+    ('\u{0438}', '\u{0308}', '\u{04e5}'),
+    ('\u{043a}', '\u{0301}', '\u{045c}'),
+    ('\u{043e}', '\u{0308}', '\u{04e7}'),
+    ('\u{0443}', '\u{0304}', '\u{04ef}'),
+    ('\u{0443}', '\u{0306}', '\u{045e}'),
+    ('\u{0443}', '\u{0308}', '\u{04f1}'),
+    ('\u{0443}', '\u{030b}', '\u{04f3}'),
+    ('\u{0447}', '\u{0308}', '\u{04f5}'),
+    ('\u{044b}', '\u{0308}', '\u{04f9}'),
+    ('\u{044d}', '\u{0308}', '\u{04ed}'),
+    ('\u{0456}', '\u{0308}', '\u{0457}'),
+    ('\u{0474}', '\u{030f}', '\u{0476}'),
+    ('\u{0475}', '\u{030f}', '\u{0477}'),
+    ('\u{04d8}', '\u{0308}', '\u{04da}'),
+    ('\u{04d9}', '\u{0308}', '\u{04db}'),
+    ('\u{04e8}', '\u{0308}', '\u{04ea}'),
+    ('\u{04e9}', '\u{0308}', '\u{04eb}'),
+    ('\u{1e36}', '\u{0304}', '\u{1e38}'),
+    ('\u{1e37}', '\u{0304}', '\u{1e39}'),
+    ('\u{1e5a}', '\u{0304}', '\u{1e5c}'),
+    ('\u{1e5b}', '\u{0304}', '\u{1e5d}'),
+    ('\u{1e62}', '\u{0307}', '\u{1e68}'),
+    ('\u{1e63}', '\u{0307}', '\u{1e69}'),
+    ('\u{1ea0}', '\u{0302}', '\u{1eac}'),
+    ('\u{1ea0}', '\u{0306}', '\u{1eb6}'),
+    ('\u{1ea1}', '\u{0302}', '\u{1ead}'),
+    ('\u{1ea1}', '\u{0306}', '\u{1eb7}'),
+    ('\u{1eb8}', '\u{0302}', '\u{1ec6}'),
+    ('\u{1eb9}', '\u{0302}', '\u{1ec7}'),
+    ('\u{1ecc}', '\u{0302}', '\u{1ed8}'),
+    ('\u{1ecd}', '\u{0302}', '\u{1ed9}'),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("e\u{301}", NormalizationForm::Nfc), "\u{e9}");
+        assert_eq!(normalize("\u{e9}", NormalizationForm::Nfd), "e\u{301}");
+        // dot below (220) sorts before dot above (230).
+        assert_eq!(
+            normalize("q\u{307}\u{323}", NormalizationForm::Nfd),
+            "q\u{323}\u{307}"
+        );
+        assert_eq!(
+            normalize("\u{1e0b}\u{323}", NormalizationForm::Nfc),
+            "\u{1e0d}\u{307}"
+        );
+        assert_eq!(normalize("\u{fb01}", NormalizationForm::Nfkc), "fi");
+        assert_eq!(normalize("\u{fb01}", NormalizationForm::Nfc), "\u{fb01}");
+        assert_eq!(
+            normalize("\u{d55c}", NormalizationForm::Nfd),
+            "\u{1112}\u{1161}\u{11ab}"
+        );
+        assert_eq!(
+            normalize("\u{1112}\u{1161}\u{11ab}", NormalizationForm::Nfc),
+            "\u{d55c}"
+        );
+        assert_eq!(normalize("\u{ff21}", NormalizationForm::Nfkd), "A");
+    }
+}
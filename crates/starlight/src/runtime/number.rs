@@ -0,0 +1,180 @@
+//! Number to string conversions.
+//!
+//! Decimal conversion relies on the shortest round-trip digits produced by the standard library
+//! float formatting and lays them out as specified by `Number::toString`.
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// section 7.1.12.1 NumberToString(m)
+pub fn number_to_string(m: f64) -> String {
+    if m.is_nan() {
+        return "NaN".to_owned();
+    }
+    if m == 0.0 {
+        // both +0 and -0.
+        return "0".to_owned();
+    }
+    if m < 0.0 {
+        return format!("-{}", number_to_string(-m));
+    }
+    if m.is_infinite() {
+        return "Infinity".to_owned();
+    }
+    // `{:e}` produces the shortest digits that round-trip, as `d.ddde[-]x`.
+    let formatted = format!("{:e}", m);
+    let e = formatted.find('e').unwrap();
+    let digits = formatted[..e].replace('.', "");
+    let k = digits.len() as i32;
+    let n = formatted[e + 1..].parse::<i32>().unwrap() + 1;
+
+    if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let exponent = (n - 1).abs();
+        if k == 1 {
+            format!("{}e{}{}", digits, sign, exponent)
+        } else {
+            format!("{}.{}e{}{}", &digits[..1], &digits[1..], sign, exponent)
+        }
+    }
+}
+
+/// Upper bound of fraction digits, `Number.MIN_VALUE` in binary needs 1074 of them.
+const MAX_FRACTION_DIGITS: usize = 1100;
+
+/// `Number.prototype.toString(radix)` for radix other than 10. Fraction digits are produced
+/// until the value is unambiguous, the same way V8 does it.
+pub fn number_to_radix_string(value: f64, radix: u32) -> String {
+    debug_assert!((2..=36).contains(&radix));
+    if value.is_nan() {
+        return "NaN".to_owned();
+    }
+    if value == 0.0 {
+        return "0".to_owned();
+    }
+    if value < 0.0 {
+        return format!("-{}", number_to_radix_string(-value, radix));
+    }
+    if value.is_infinite() {
+        return "Infinity".to_owned();
+    }
+    let radix_f = radix as f64;
+    let mut integer = value.trunc();
+    let mut fraction = value - integer;
+    // half the distance to the next double, digits below it don't change the value.
+    let next = f64::from_bits(value.to_bits() + 1);
+    let mut delta = (0.5 * (next - value)).max(f64::from_bits(1));
+
+    let mut fraction_digits: Vec<u8> = vec![];
+    if fraction >= delta {
+        loop {
+            fraction *= radix_f;
+            delta *= radix_f;
+            let digit = fraction as usize;
+            fraction_digits.push(DIGITS[digit]);
+            fraction -= digit as f64;
+            if (fraction > 0.5 || (fraction == 0.5 && digit & 1 == 1)) && fraction + delta > 1.0 {
+                // round up, carrying into the integer part when every digit overflows.
+                loop {
+                    match fraction_digits.pop() {
+                        None => {
+                            integer += 1.0;
+                            break;
+                        }
+                        Some(c) => {
+                            let digit = DIGITS.iter().position(|&d| d == c).unwrap();
+                            if digit + 1 < radix as usize {
+                                fraction_digits.push(DIGITS[digit + 1]);
+                                break;
+                            }
+                        }
+                    }
+                }
+                break;
+            }
+            if fraction < delta || fraction_digits.len() >= MAX_FRACTION_DIGITS {
+                break;
+            }
+        }
+    }
+
+    let mut integer_digits: Vec<u8> = vec![];
+    // past 2^53 the low digits are not representable, V8 prints them as zeros.
+    while integer / radix_f >= 9007199254740992.0 {
+        integer /= radix_f;
+        integer_digits.push(b'0');
+    }
+    loop {
+        let remainder = integer % radix_f;
+        integer_digits.push(DIGITS[remainder as usize]);
+        integer = (integer - remainder) / radix_f;
+        if integer <= 0.0 {
+            break;
+        }
+    }
+    integer_digits.reverse();
+
+    let mut result = String::from_utf8(integer_digits).unwrap();
+    if !fraction_digits.is_empty() {
+        result.push('.');
+        result.push_str(std::str::from_utf8(&fraction_digits).unwrap());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_to_string() {
+        let cases: &[(f64, &str)] = &[
+            (0.1, "0.1"),
+            (-0.0, "0"),
+            (f64::INFINITY, "Infinity"),
+            (f64::NEG_INFINITY, "-Infinity"),
+            (f64::NAN, "NaN"),
+            (1e21, "1e+21"),
+            (1e20, "100000000000000000000"),
+            (123456789012345680000.0, "123456789012345680000"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (-1.5, "-1.5"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (1.23e-18, "1.23e-18"),
+            (5e-324, "5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+            (9007199254740992.0, "9007199254740992"),
+            (1.5e300, "1.5e+300"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(number_to_string(*value), *expected);
+        }
+    }
+
+    #[test]
+    fn test_number_to_radix_string() {
+        assert_eq!(number_to_radix_string(255.0, 16), "ff");
+        assert_eq!(number_to_radix_string(-255.0, 36), "-73");
+        assert_eq!(number_to_radix_string(0.5, 2), "0.1");
+        assert_eq!(
+            number_to_radix_string(0.1, 2),
+            "0.0001100110011001100110011001100110011001100110011001101"
+        );
+        assert_eq!(number_to_radix_string(3.75, 16), "3.c");
+        assert_eq!(number_to_radix_string(255.5, 16), "ff.8");
+        assert_eq!(number_to_radix_string(-0.25, 2), "-0.01");
+        let min = number_to_radix_string(5e-324, 2);
+        assert_eq!(min, format!("0.{}1", "0".repeat(1073)));
+        assert!(number_to_radix_string(0.1, 3).len() < MAX_FRACTION_DIGITS);
+        assert_eq!(
+            number_to_radix_string(2f64.powi(60), 2),
+            format!("1{}", "0".repeat(60))
+        );
+    }
+}
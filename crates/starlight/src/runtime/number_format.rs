@@ -0,0 +1,201 @@
+//! Locale aware number formatting used by `Number.prototype.toLocaleString`.
+//!
+//! This is not an `Intl` implementation: only grouping and fraction digits are supported and the
+//! locale data is a small table keyed by language. Unknown locales fall back to `en-US`.
+
+/// Separators of a locale.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LocaleData {
+    pub tag: &'static str,
+    pub group: &'static str,
+    pub decimal: &'static str,
+    /// Integer part must have at least this many digits before grouping kicks in.
+    pub min_grouping_digits: usize,
+}
+
+const LOCALES: &[(&str, LocaleData)] = &[
+    (
+        "en",
+        LocaleData {
+            tag: "en-US",
+            group: ",",
+            decimal: ".",
+            min_grouping_digits: 1,
+        },
+    ),
+    (
+        "de",
+        LocaleData {
+            tag: "de-DE",
+            group: ".",
+            decimal: ",",
+            min_grouping_digits: 1,
+        },
+    ),
+    (
+        "fr",
+        LocaleData {
+            tag: "fr-FR",
+            group: "\u{202f}",
+            decimal: ",",
+            min_grouping_digits: 1,
+        },
+    ),
+    (
+        "es",
+        LocaleData {
+            tag: "es-ES",
+            group: ".",
+            decimal: ",",
+            min_grouping_digits: 2,
+        },
+    ),
+    (
+        "it",
+        LocaleData {
+            tag: "it-IT",
+            group: ".",
+            decimal: ",",
+            min_grouping_digits: 1,
+        },
+    ),
+];
+
+impl LocaleData {
+    /// Looks up `locale` by its language subtag, `en-US` is returned for anything unknown.
+    pub fn lookup(locale: &str) -> Self {
+        let language = locale.split(|c| c == '-' || c == '_').next().unwrap_or("");
+        LOCALES
+            .iter()
+            .find(|(lang, _)| lang.eq_ignore_ascii_case(language))
+            .unwrap_or(&LOCALES[0])
+            .1
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NumberFormat {
+    pub locale: LocaleData,
+    pub use_grouping: bool,
+    pub minimum_fraction_digits: usize,
+    pub maximum_fraction_digits: usize,
+}
+
+impl NumberFormat {
+    /// Same defaults as `Intl.NumberFormat`: grouping on, between 0 and 3 fraction digits.
+    pub fn new(locale: &str) -> Self {
+        Self {
+            locale: LocaleData::lookup(locale),
+            use_grouping: true,
+            minimum_fraction_digits: 0,
+            maximum_fraction_digits: 3,
+        }
+    }
+
+    pub fn format(&self, x: f64) -> String {
+        if x.is_nan() {
+            return "NaN".to_owned();
+        }
+        let sign = if x.is_sign_negative() { "-" } else { "" };
+        if x.is_infinite() {
+            return format!("{}\u{221e}", sign);
+        }
+        // `Display` for f64 prints the shortest representation that round-trips and never
+        // switches to exponent notation, rounding is then done on those decimal digits.
+        let repr = format!("{}", x.abs());
+        let (int, frac) = match repr.find('.') {
+            Some(dot) => (&repr[..dot], &repr[dot + 1..]),
+            None => (&repr[..], ""),
+        };
+        let mut digits = format!("{}{}", int, frac).into_bytes();
+        let mut int_len = int.len();
+        let max = self.maximum_fraction_digits;
+        if frac.len() > max {
+            let round_up = digits[int_len + max] >= b'5';
+            digits.truncate(int_len + max);
+            if round_up && !increment(&mut digits) {
+                digits.insert(0, b'1');
+                int_len += 1;
+            }
+        }
+        let mut frac_len = digits.len() - int_len;
+        while frac_len > self.minimum_fraction_digits && digits[int_len + frac_len - 1] == b'0' {
+            frac_len -= 1;
+        }
+        digits.truncate(int_len + frac_len);
+        digits.resize(int_len + self.minimum_fraction_digits.max(frac_len), b'0');
+
+        // Digits are ASCII so this can't fail.
+        let digits = String::from_utf8(digits).unwrap();
+        let (int, frac) = digits.split_at(int_len);
+        let mut result = sign.to_owned();
+        result.push_str(&self.group(int));
+        if !frac.is_empty() {
+            result.push_str(self.locale.decimal);
+            result.push_str(frac);
+        }
+        result
+    }
+
+    fn group(&self, int: &str) -> String {
+        if !self.use_grouping || int.len() < 4 + self.locale.min_grouping_digits - 1 {
+            return int.to_owned();
+        }
+        let mut result = String::with_capacity(int.len() * 2);
+        for (i, c) in int.chars().enumerate() {
+            if i != 0 && (int.len() - i) % 3 == 0 {
+                result.push_str(self.locale.group);
+            }
+            result.push(c);
+        }
+        result
+    }
+}
+
+/// Adds one to the decimal number in `digits`, returns false if the carry fell off the front.
+fn increment(digits: &mut [u8]) -> bool {
+    for d in digits.iter_mut().rev() {
+        if *d == b'9' {
+            *d = b'0';
+        } else {
+            *d += 1;
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(min: usize, max: usize) -> NumberFormat {
+        let mut fmt = NumberFormat::new("en-US");
+        fmt.minimum_fraction_digits = min;
+        fmt.maximum_fraction_digits = max;
+        fmt
+    }
+
+    #[test]
+    fn test_number_format() {
+        let en = NumberFormat::new("en-US");
+        assert_eq!(en.format(1234567.0), "1,234,567");
+        assert_eq!(en.format(-1234.5), "-1,234.5");
+        assert_eq!(en.format(999.0), "999");
+        assert_eq!(en.format(0.1 + 0.2), "0.3");
+        assert_eq!(en.format(f64::NAN), "NaN");
+        assert_eq!(en.format(f64::NEG_INFINITY), "-\u{221e}");
+
+        assert_eq!(digits(0, 2).format(1.005), "1.01");
+        assert_eq!(digits(0, 0).format(2.5), "3");
+        assert_eq!(digits(0, 2).format(9.999), "10");
+        assert_eq!(digits(0, 1).format(99999.96), "100,000");
+        assert_eq!(digits(2, 2).format(5.0), "5.00");
+        assert_eq!(digits(1, 3).format(0.25), "0.25");
+
+        assert_eq!(NumberFormat::new("de").format(1234567.891), "1.234.567,891");
+        assert_eq!(NumberFormat::new("es-ES").format(1234.0), "1234");
+        assert_eq!(NumberFormat::new("es-ES").format(12345.0), "12.345");
+        assert_eq!(NumberFormat::new("xx").locale.tag, "en-US");
+    }
+}
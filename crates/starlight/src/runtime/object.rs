@@ -2,19 +2,28 @@ use std::{mem::size_of, mem::ManuallyDrop};
 
 use super::{
     arguments::Arguments,
+    array::JsArrayIterator,
+    array_buffer::JsArrayBuffer,
     attributes::*,
     class::Class,
+    date::JsDate,
     error::JsTypeError,
+    finalization_registry::JsFinalizationRegistry,
     function::JsFunction,
     global::JsGlobal,
     indexed_elements::{IndexedElements, MAX_VECTOR_SIZE},
     js_arguments::JsArguments,
+    map::{JsMap, JsMapIterator},
+    private_name::JsPrivateName,
     property_descriptor::{DataDescriptor, PropertyDescriptor, StoredSlot},
+    proxy::JsProxy,
+    regexp::{JsRegExp, JsRegExpStringIterator},
     slot::*,
     storage::FixedStorage,
     string::JsString,
     structure::Structure,
     symbol::*,
+    typed_array::{JsTypedArray, TypedArrayKind},
 };
 use super::{method_table::MethodTable, value::JsValue};
 use crate::{
@@ -25,7 +34,7 @@ use crate::{
     },
     vm::*,
 };
-use std::collections::hash_map::Entry;
+use std::collections::{hash_map::Entry, HashSet};
 
 use wtf_rs::object_offsetof;
 
@@ -35,6 +44,10 @@ pub const OBJ_FLAG_EXTENSIBLE: u32 = 0x1;
 
 pub type ObjectSlots = FixedStorage<JsValue>;
 
+/// Property values live in two ranges of slot offsets: the first `inline_slots` offsets are
+/// stored in the object cell itself, right after the data of its tag, the remaining ones in
+/// the out-of-line `slots`. The number of inline slots is fixed when the object is allocated,
+/// see [Options::inline_slots].
 #[repr(C)]
 pub struct JsObject {
     tag: ObjectTag,
@@ -43,6 +56,9 @@ pub struct JsObject {
     slots: ObjectSlots,
     pub(crate) elements: IndexedElements,
     flags: u32,
+    inline_slots: u32,
+    /// Private fields and methods added by class constructors, see [JsPrivateName].
+    private_elements: Vec<(Gc<JsPrivateName>, JsValue)>,
 
     pub(crate) data_start: [ObjectData; 0],
 }
@@ -83,12 +99,45 @@ impl JsObject {
     pub fn flags_offsetof() -> usize {
         object_offsetof!(Self, flags)
     }
+    /// Number of slot offsets stored in the object cell.
+    pub fn inline_slots(&self) -> usize {
+        self.inline_slots as usize
+    }
+
+    /// Number of slots stored out-of-line.
+    pub fn out_of_line_slots(&self) -> usize {
+        self.slots.size()
+    }
+
+    fn inline_start(&self) -> *mut JsValue {
+        unsafe {
+            Address::from_ptr(self)
+                .offset(object_size_with_tag(self.tag))
+                .to_mut_ptr::<JsValue>()
+        }
+    }
+
     pub fn direct(&self, n: usize) -> &JsValue {
-        &self.slots[n]
+        if n < self.inline_slots() {
+            unsafe { &*self.inline_start().add(n) }
+        } else {
+            &self.slots[n - self.inline_slots()]
+        }
     }
 
     pub fn direct_mut(&mut self, n: usize) -> &mut JsValue {
-        &mut self.slots[n]
+        if n < self.inline_slots() {
+            unsafe { &mut *self.inline_start().add(n) }
+        } else {
+            let n = n - self.inline_slots();
+            &mut self.slots[n]
+        }
+    }
+
+    /// Makes room for `size` slot offsets, the ones past the inline slots go out-of-line.
+    fn resize_slots(&mut self, vm: &mut VirtualMachine, size: usize) {
+        let out_of_line = size.saturating_sub(self.inline_slots());
+        self.slots.resize(vm, out_of_line, JsValue::empty());
     }
 }
 
@@ -100,6 +149,25 @@ impl Drop for JsObject {
             ObjectTag::NormalArguments => unsafe {
                 ManuallyDrop::drop(&mut self.data::<JsArguments>())
             },
+            ObjectTag::Regex => unsafe { ManuallyDrop::drop(&mut self.data::<JsRegExp>()) },
+            ObjectTag::RegExpStringIterator => unsafe {
+                ManuallyDrop::drop(&mut self.data::<JsRegExpStringIterator>())
+            },
+            ObjectTag::Map | ObjectTag::Set => unsafe {
+                ManuallyDrop::drop(&mut self.data::<JsMap>())
+            },
+            ObjectTag::MapIterator | ObjectTag::SetIterator => unsafe {
+                ManuallyDrop::drop(&mut self.data::<JsMapIterator>())
+            },
+            ObjectTag::ArrayIterator => unsafe {
+                ManuallyDrop::drop(&mut self.data::<JsArrayIterator>())
+            },
+            ObjectTag::FinalizationRegistry => unsafe {
+                ManuallyDrop::drop(&mut self.data::<JsFinalizationRegistry>())
+            },
+            ObjectTag::ArrayBuffer => unsafe {
+                ManuallyDrop::drop(&mut self.data::<JsArrayBuffer>())
+            },
             _ => (),
         }
     }
@@ -111,6 +179,16 @@ pub union ObjectData {
     pub global: ManuallyDrop<JsGlobal>,
     pub function: ManuallyDrop<JsFunction>,
     pub arguments: ManuallyDrop<JsArguments>,
+    pub regexp: ManuallyDrop<JsRegExp>,
+    pub regexp_string_iterator: ManuallyDrop<JsRegExpStringIterator>,
+    pub map: ManuallyDrop<JsMap>,
+    pub map_iterator: ManuallyDrop<JsMapIterator>,
+    pub array_iterator: ManuallyDrop<JsArrayIterator>,
+    pub finalization_registry: ManuallyDrop<JsFinalizationRegistry>,
+    pub date: ManuallyDrop<JsDate>,
+    pub array_buffer: ManuallyDrop<JsArrayBuffer>,
+    pub typed_array: ManuallyDrop<JsTypedArray>,
+    pub proxy: ManuallyDrop<JsProxy>,
 }
 
 #[cfg(feature = "debug-snapshots")]
@@ -130,7 +208,7 @@ impl serde::Serialize for JsObject {
 }
 impl Cell for JsObject {
     fn compute_size(&self) -> usize {
-        object_size_with_tag(self.tag)
+        object_size_with_tag(self.tag) + self.inline_slots() * size_of::<JsValue>()
     }
     fn set_class_value(&mut self, _class: &'static Class) {
         self.class = _class;
@@ -147,20 +225,41 @@ impl Cell for JsObject {
     }
 }
 unsafe impl Trace for JsObject {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.structure.trace(tracer);
         self.elements.trace(tracer);
         self.slots.trace(tracer);
+        for n in 0..self.inline_slots() {
+            self.direct_mut(n).trace(tracer);
+        }
+        for (name, value) in self.private_elements.iter_mut() {
+            name.trace(tracer);
+            value.trace(tracer);
+        }
 
         match self.tag {
-            ObjectTag::Global => self.as_global().trace(tracer),
-            ObjectTag::Function => self.as_function().trace(tracer),
-            ObjectTag::NormalArguments => self.as_arguments().trace(tracer),
+            ObjectTag::Global => self.as_global_mut().trace(tracer),
+            ObjectTag::Function => self.as_function_mut().trace(tracer),
+            ObjectTag::NormalArguments => self.as_arguments_mut().trace(tracer),
+            ObjectTag::RegExpStringIterator => self.as_regexp_string_iterator_mut().trace(tracer),
+            ObjectTag::Map | ObjectTag::Set => self.as_map_mut().trace(tracer),
+            ObjectTag::MapIterator | ObjectTag::SetIterator => {
+                self.as_map_iterator_mut().trace(tracer)
+            }
+            ObjectTag::ArrayIterator => self.as_array_iterator_mut().trace(tracer),
+            ObjectTag::FinalizationRegistry => self.as_finalization_registry_mut().trace(tracer),
+            ObjectTag::Proxy => self.as_proxy_mut().trace(tracer),
+            _ if self.is_typed_array() => self.as_typed_array_mut().trace(tracer),
             _ => (),
         }
     }
 }
 
+fn private_error(vm: &mut VirtualMachine, msg: String) -> JsValue {
+    let msg = JsString::new(vm, msg);
+    JsValue::new(JsTypeError::new(vm, msg, None))
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum EnumerationMode {
     Default,
@@ -191,12 +290,107 @@ fn is_absent_descriptor(desc: &PropertyDescriptor) -> bool {
     true
 }
 
+/// Error for a `[[Set]]` rejected by `can_put`, `slot` is the one filled by the failed lookup.
+fn reject_put(vm: &mut VirtualMachine, name: Symbol, slot: &Slot) -> JsValue {
+    let desc = vm.description(name);
+    let msg = if slot.is_not_found() {
+        format!("Cannot add property '{}', object is not extensible", desc)
+    } else if slot.attributes().is_accessor() {
+        format!("Cannot set property '{}' which has only a getter", desc)
+    } else {
+        format!("Cannot assign to read only property '{}'", desc)
+    };
+    let msg = JsString::new(vm, msg);
+    JsValue::new(JsTypeError::new(vm, msg, None))
+}
+
 #[allow(non_snake_case)]
 impl JsObject {
     pub fn prototype(&self) -> Option<Gc<JsObject>> {
         self.structure.prototype()
     }
 
+    pub fn has_private(&self, name: Gc<JsPrivateName>) -> bool {
+        self.private_elements
+            .iter()
+            .any(|(key, _)| Gc::ptr_eq(*key, name))
+    }
+
+    /// Reads private element `name`, throws a TypeError if this object does not have it.
+    pub fn get_private(
+        &self,
+        vm: &mut VirtualMachine,
+        name: Gc<JsPrivateName>,
+    ) -> Result<JsValue, JsValue> {
+        match self
+            .private_elements
+            .iter()
+            .find(|(key, _)| Gc::ptr_eq(*key, name))
+        {
+            Some((_, value)) => Ok(name.method().unwrap_or(*value)),
+            None => {
+                let msg = format!(
+                    "Cannot read private member {} from an object whose class did not declare it",
+                    vm.description(name.description())
+                );
+                Err(private_error(vm, msg))
+            }
+        }
+    }
+
+    /// Writes private field `name`, throws a TypeError if this object does not have it or if it
+    /// is a method.
+    pub fn set_private(
+        &mut self,
+        vm: &mut VirtualMachine,
+        name: Gc<JsPrivateName>,
+        value: JsValue,
+    ) -> Result<(), JsValue> {
+        let desc = vm.description(name.description());
+        if name.method().is_some() {
+            return Err(private_error(
+                vm,
+                format!("Private method {} is not writable", desc),
+            ));
+        }
+        match self
+            .private_elements
+            .iter_mut()
+            .find(|(key, _)| Gc::ptr_eq(*key, name))
+        {
+            Some(entry) => {
+                entry.1 = value;
+                Ok(())
+            }
+            None => Err(private_error(
+                vm,
+                format!(
+                    "Cannot write private member {} to an object whose class did not declare it",
+                    desc
+                ),
+            )),
+        }
+    }
+
+    /// Adds private element `name` when a class constructor initializes this object. The value
+    /// of private methods is ignored, reads return the function stored with the name.
+    pub fn add_private(
+        &mut self,
+        vm: &mut VirtualMachine,
+        name: Gc<JsPrivateName>,
+        value: JsValue,
+    ) -> Result<(), JsValue> {
+        if self.has_private(name) {
+            let msg = format!(
+                "Cannot initialize {} twice on the same object",
+                vm.description(name.description())
+            );
+            return Err(private_error(vm, msg));
+        }
+        self.private_elements.push((name, value));
+        Ok(())
+    }
+
     pub fn GetNonIndexedPropertySlotMethod(
         mut obj: Gc<Self>,
         vm: &mut VirtualMachine,
@@ -244,7 +438,7 @@ impl JsObject {
     ) -> Result<(), JsValue> {
         if !obj.can_put(vm, name, slot) {
             if throwable {
-                todo!();
+                return Err(reject_put(vm, name, slot));
             }
 
             return Ok(());
@@ -331,7 +525,7 @@ impl JsObject {
             && obj.class.method_table.GetOwnIndexedPropertySlot as usize
                 == Self::GetOwnIndexedPropertySlotMethod as usize
             && (obj.prototype().is_none()
-                || obj.prototype().as_ref().unwrap().has_indexed_property())
+                || !obj.prototype().as_ref().unwrap().has_indexed_property())
         {
             slot.mark_put_result(PutResultType::IndexedOptimized, index);
             obj.define_own_indexe_value_dense_internal(vm, index, val, false);
@@ -340,7 +534,7 @@ impl JsObject {
         }
         if !obj.can_put_indexed(vm, index, slot) {
             if throwable {
-                todo!()
+                return Err(reject_put(vm, Symbol::Indexed(index), slot));
             }
             return Ok(());
         }
@@ -408,6 +602,22 @@ impl JsObject {
         (self.flags & OBJ_FLAG_EXTENSIBLE) != 0
     }
 
+    /// section 8.12 `[[PreventExtensions]]`
+    ///
+    /// Elements are moved to the sparse map since only the sparse path checks extensibility
+    /// before adding an index.
+    pub fn prevent_extensions(&mut self, vm: &mut VirtualMachine) {
+        if !self.is_extensible() {
+            return;
+        }
+        self.flags &= !OBJ_FLAG_EXTENSIBLE;
+        if self.elements.dense() {
+            self.elements.make_sparse(vm);
+        }
+        let s = self.structure.change_extensible_transition(vm);
+        self.set_structure(vm, s);
+    }
+
     pub fn set_callable(&mut self, val: bool) {
         if val {
             self.flags |= OBJ_FLAG_CALLABLE;
@@ -464,8 +674,7 @@ impl JsObject {
                             );
                             obj.set_structure(vm, new_struct);
                             let s = obj.structure;
-                            //   println!("resize to {} from {}", s.get_slots_size(), obj.slots.size());
-                            obj.slots.resize(vm, s.get_slots_size(), JsValue::empty());
+                            obj.resize_slots(vm, s.get_slots_size());
 
                             *obj.direct_mut(offset as _) = slot.value();
                             slot.mark_put_result(PutResultType::New, offset);
@@ -478,7 +687,12 @@ impl JsObject {
 
         if !obj.is_extensible() {
             if throwable {
-                todo!();
+                let msg = format!(
+                    "Cannot define property '{}', object is not extensible",
+                    vm.description(name)
+                );
+                let msg = JsString::new(vm, msg);
+                return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
             }
 
             return Ok(false);
@@ -493,7 +707,7 @@ impl JsObject {
 
         let s = obj.structure;
 
-        obj.slots.resize(vm, s.get_slots_size(), JsValue::empty());
+        obj.resize_slots(vm, s.get_slots_size());
         assert!(stored.value() == desc.value());
         *obj.direct_mut(offset as _) = stored.value();
         slot.mark_put_result(PutResultType::New, offset);
@@ -571,7 +785,12 @@ impl JsObject {
 
         if !slot.attributes().is_configurable() {
             if throwable {
-                todo!();
+                let desc = vm.description(name);
+                let msg = JsString::new(
+                    vm,
+                    format!("Cannot delete non-configurable property '{}'", desc),
+                );
+                return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
             }
             return Ok(false);
         }
@@ -595,7 +814,7 @@ impl JsObject {
     #[allow(clippy::unnecessary_unwrap)]
     pub fn delete_indexed_internal(
         &mut self,
-        _vm: &mut VirtualMachine,
+        vm: &mut VirtualMachine,
         index: u32,
         throwable: bool,
     ) -> Result<bool, JsValue> {
@@ -624,7 +843,11 @@ impl JsObject {
             Entry::Occupied(x) => {
                 if !x.get().attributes().is_configurable() {
                     if throwable {
-                        todo!();
+                        let msg = JsString::new(
+                            vm,
+                            format!("Cannot delete non-configurable property '{}'", index),
+                        );
+                        return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
                     }
                     return Ok(false);
                 }
@@ -654,28 +877,68 @@ impl JsObject {
 
         if !slot.attributes().is_configurable() {
             if throwable {
-                todo!();
+                let msg = JsString::new(
+                    vm,
+                    format!("Cannot delete non-configurable property '{}'", index),
+                );
+                return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
             }
             return Ok(false);
         }
 
         obj.delete_indexed_internal(vm, index, throwable)
     }
-    #[allow(unused_variables)]
     pub fn GetPropertyNamesMethod(
         obj: Gc<Self>,
         vm: &mut VirtualMachine,
         collector: &mut dyn FnMut(Symbol, u32),
         mode: EnumerationMode,
     ) {
+        obj.get_own_property_names(vm, collector, mode);
+        if let Some(proto) = obj.prototype() {
+            proto.get_property_names(vm, collector, mode);
+        }
     }
-    #[allow(unused_variables)]
+
+    /// Collects own property keys in spec order: integer indices in ascending order first,
+    /// then string keys in insertion order. Indexed keys are reported with `u32::MAX` offset.
     pub fn GetOwnPropertyNamesMethod(
-        obj: Gc<Self>,
+        mut obj: Gc<Self>,
         vm: &mut VirtualMachine,
         collector: &mut dyn FnMut(Symbol, u32),
         mode: EnumerationMode,
     ) {
+        let include = mode == EnumerationMode::IncludeNotEnumerable;
+        let mut indices = Vec::new();
+        if obj.elements.dense() {
+            for (index, val) in obj.elements.vector.iter().enumerate() {
+                if !val.is_empty() {
+                    indices.push(index as u32);
+                }
+            }
+        }
+        if let Some(map) = obj.elements.map {
+            for (index, slot) in map.iter() {
+                if include || slot.attributes().is_enumerable() {
+                    indices.push(*index);
+                }
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        for index in indices {
+            collector(Symbol::Indexed(index), u32::MAX);
+        }
+
+        if include {
+            for (sym, offset) in obj.structure.ordered_property_names(vm, true) {
+                collector(sym, offset);
+            }
+        } else {
+            for &(sym, offset) in obj.structure.enumeration_keys(vm).iter() {
+                collector(sym, offset);
+            }
+        }
     }
 
     /// 7.1.1 ToPrimitive
@@ -735,22 +998,33 @@ impl JsObject {
         class: &'static Class,
         tag: ObjectTag,
     ) -> Gc<Self> {
+        let inline_slots = vm.options().inline_slots.min(u32::MAX as usize);
+        let out_of_line = structure.get_slots_size().saturating_sub(inline_slots);
         let this = Self {
             structure,
             class,
 
-            slots: FixedStorage::with_capacity(vm, structure.get_slots_size(), JsValue::empty()),
+            slots: FixedStorage::with_capacity(vm, out_of_line, JsValue::empty()),
             data_start: [],
             elements: IndexedElements::new(vm),
             flags: OBJ_FLAG_EXTENSIBLE,
+            inline_slots: inline_slots as u32,
+            private_elements: vec![],
             tag,
         };
-        vm.space().alloc(this)
+        let obj = vm.space().alloc(this);
+        for n in 0..inline_slots {
+            unsafe { obj.inline_start().add(n).write(JsValue::empty()) };
+        }
+        obj
     }
 
     pub fn tag(&self) -> ObjectTag {
         self.tag
     }
+    pub fn class(&self) -> &'static Class {
+        self.class
+    }
     pub fn as_global(&self) -> &JsGlobal {
         assert_eq!(self.tag, ObjectTag::Global);
         unsafe { &*self.data::<JsGlobal>() }
@@ -776,6 +1050,94 @@ impl JsObject {
         assert_eq!(self.tag, ObjectTag::NormalArguments);
         unsafe { &mut *self.data::<JsArguments>() }
     }
+
+    pub fn as_regexp(&self) -> &JsRegExp {
+        assert_eq!(self.tag, ObjectTag::Regex);
+        unsafe { &*self.data::<JsRegExp>() }
+    }
+
+    pub fn as_regexp_string_iterator(&self) -> &JsRegExpStringIterator {
+        assert_eq!(self.tag, ObjectTag::RegExpStringIterator);
+        unsafe { &*self.data::<JsRegExpStringIterator>() }
+    }
+    pub fn as_regexp_string_iterator_mut(&mut self) -> &mut JsRegExpStringIterator {
+        assert_eq!(self.tag, ObjectTag::RegExpStringIterator);
+        unsafe { &mut *self.data::<JsRegExpStringIterator>() }
+    }
+
+    /// Storage of a `Map` or `Set` object.
+    pub fn as_map(&self) -> &JsMap {
+        assert!(self.tag == ObjectTag::Map || self.tag == ObjectTag::Set);
+        unsafe { &*self.data::<JsMap>() }
+    }
+    pub fn as_map_mut(&mut self) -> &mut JsMap {
+        assert!(self.tag == ObjectTag::Map || self.tag == ObjectTag::Set);
+        unsafe { &mut *self.data::<JsMap>() }
+    }
+
+    pub fn as_map_iterator(&self) -> &JsMapIterator {
+        assert!(self.tag == ObjectTag::MapIterator || self.tag == ObjectTag::SetIterator);
+        unsafe { &*self.data::<JsMapIterator>() }
+    }
+    pub fn as_map_iterator_mut(&mut self) -> &mut JsMapIterator {
+        assert!(self.tag == ObjectTag::MapIterator || self.tag == ObjectTag::SetIterator);
+        unsafe { &mut *self.data::<JsMapIterator>() }
+    }
+
+    pub fn as_array_iterator(&self) -> &JsArrayIterator {
+        assert_eq!(self.tag, ObjectTag::ArrayIterator);
+        unsafe { &*self.data::<JsArrayIterator>() }
+    }
+    pub fn as_array_iterator_mut(&mut self) -> &mut JsArrayIterator {
+        assert_eq!(self.tag, ObjectTag::ArrayIterator);
+        unsafe { &mut *self.data::<JsArrayIterator>() }
+    }
+
+    pub fn as_finalization_registry(&self) -> &JsFinalizationRegistry {
+        assert_eq!(self.tag, ObjectTag::FinalizationRegistry);
+        unsafe { &*self.data::<JsFinalizationRegistry>() }
+    }
+    pub fn as_finalization_registry_mut(&mut self) -> &mut JsFinalizationRegistry {
+        assert_eq!(self.tag, ObjectTag::FinalizationRegistry);
+        unsafe { &mut *self.data::<JsFinalizationRegistry>() }
+    }
+
+    pub fn as_date(&self) -> &JsDate {
+        assert_eq!(self.tag, ObjectTag::Date);
+        unsafe { &*self.data::<JsDate>() }
+    }
+
+    pub fn as_array_buffer(&self) -> &JsArrayBuffer {
+        assert_eq!(self.tag, ObjectTag::ArrayBuffer);
+        unsafe { &*self.data::<JsArrayBuffer>() }
+    }
+    pub fn as_array_buffer_mut(&mut self) -> &mut JsArrayBuffer {
+        assert_eq!(self.tag, ObjectTag::ArrayBuffer);
+        unsafe { &mut *self.data::<JsArrayBuffer>() }
+    }
+
+    pub fn is_typed_array(&self) -> bool {
+        TypedArrayKind::from_tag(self.tag).is_some()
+    }
+    pub fn as_typed_array(&self) -> &JsTypedArray {
+        assert!(self.is_typed_array());
+        unsafe { &*self.data::<JsTypedArray>() }
+    }
+
+    pub fn as_typed_array_mut(&mut self) -> &mut JsTypedArray {
+        assert!(self.is_typed_array());
+        unsafe { &mut *self.data::<JsTypedArray>() }
+    }
+
+    pub fn as_proxy(&self) -> &JsProxy {
+        assert_eq!(self.tag, ObjectTag::Proxy);
+        unsafe { &*self.data::<JsProxy>() }
+    }
+
+    pub fn as_proxy_mut(&mut self) -> &mut JsProxy {
+        assert_eq!(self.tag, ObjectTag::Proxy);
+        unsafe { &mut *self.data::<JsProxy>() }
+    }
 }
 
 impl Gc<JsObject> {
@@ -785,7 +1147,57 @@ impl Gc<JsObject> {
         collector: &mut dyn FnMut(Symbol, u32),
         mode: EnumerationMode,
     ) {
-        JsObject::GetOwnPropertyNamesMethod(*self, vm, collector, mode)
+        (self.class.method_table.GetOwnPropertyNames)(*self, vm, collector, mode)
+    }
+    /// section 9.1.11 [[OwnPropertyKeys]](), proxies run their `ownKeys` trap.
+    pub fn own_property_keys(&self, vm: &mut VirtualMachine) -> Result<Vec<Symbol>, JsValue> {
+        if self.tag == ObjectTag::Proxy {
+            return JsProxy::own_keys(vm, *self);
+        }
+        let mut keys = vec![];
+        self.get_own_property_names(
+            vm,
+            &mut |name, _| keys.push(name),
+            EnumerationMode::IncludeNotEnumerable,
+        );
+        Ok(keys)
+    }
+    /// Own enumerable keys as listed by `Object.keys`, proxies run their `ownKeys` trap.
+    pub fn own_enumerable_keys(&self, vm: &mut VirtualMachine) -> Result<Vec<Symbol>, JsValue> {
+        if self.tag == ObjectTag::Proxy {
+            return JsProxy::own_enumerable_keys(vm, *self);
+        }
+        let mut keys = vec![];
+        self.get_own_property_names(vm, &mut |name, _| keys.push(name), EnumerationMode::Default);
+        Ok(keys)
+    }
+    /// Keys visited by `for...in`: enumerable keys of the object followed by those of its
+    /// prototype chain, each reported once.
+    pub fn for_in_keys(&self, vm: &mut VirtualMachine) -> Result<Vec<Symbol>, JsValue> {
+        let mut keys = vec![];
+        let mut seen = HashSet::new();
+        let mut current = Some(*self);
+        while let Some(obj) = current {
+            for name in obj.own_enumerable_keys(vm)? {
+                if seen.insert(name) {
+                    keys.push(name);
+                }
+            }
+            current = if obj.tag == ObjectTag::Proxy {
+                obj.as_proxy().target().prototype()
+            } else {
+                obj.prototype()
+            };
+        }
+        Ok(keys)
+    }
+    pub fn get_property_names(
+        &self,
+        vm: &mut VirtualMachine,
+        collector: &mut dyn FnMut(Symbol, u32),
+        mode: EnumerationMode,
+    ) {
+        (self.class.method_table.GetPropertyNames)(*self, vm, collector, mode)
     }
     pub fn put_non_indexed_slot(
         &mut self,
@@ -869,6 +1281,43 @@ impl Gc<JsObject> {
             }
         }
     }
+    /// Own element `index` when it is held by the dense elements vector, dense elements are
+    /// always writable, enumerable and configurable data properties. `None` when the generic
+    /// lookup has to run.
+    pub fn get_dense_element(&self, index: u32) -> Option<JsValue> {
+        if !self.has_plain_elements() {
+            return None;
+        }
+        self.elements
+            .vector
+            .get(index as usize)
+            .copied()
+            .filter(|val| !val.is_empty())
+    }
+
+    /// Overwrites own element `index` when it is held by the dense elements vector. Returns
+    /// whether it did, the generic [JsObject::put] has to run otherwise.
+    pub fn put_dense_element(&mut self, index: u32, val: JsValue) -> bool {
+        if !self.has_plain_elements() || !self.elements.writable() {
+            return false;
+        }
+        match self.elements.vector.get_mut(index as usize) {
+            Some(element) if !element.is_empty() => {
+                *element = val;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the elements of this object are ordinary dense storage, other kinds of objects
+    /// override the indexed methods of their class.
+    fn has_plain_elements(&self) -> bool {
+        matches!(self.tag, ObjectTag::Ordinary | ObjectTag::Array)
+            && self.structure.is_indexed()
+            && self.elements.dense()
+    }
+
     pub fn get_non_indexed_property_slot(
         &self,
         vm: &mut VirtualMachine,
@@ -996,6 +1445,67 @@ impl Gc<JsObject> {
         self.put_slot(vm, name, val, &mut slot, throwable)
     }
 
+    /// section 9.1.8.1 OrdinaryGet(O, P, Receiver), accessors found on `self` or its prototypes
+    /// are called with `receiver` as `this`. Proxies ignore `receiver`.
+    pub fn get_with_receiver(
+        &self,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        receiver: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        if self.tag == ObjectTag::Proxy {
+            return self.get(vm, name);
+        }
+        let mut slot = Slot::new();
+        if self.get_property_slot(vm, name, &mut slot) {
+            return slot.get(vm, receiver);
+        }
+        Ok(JsValue::undefined())
+    }
+
+    /// section 9.1.9.2 OrdinarySetWithOwnDescriptor(O, P, V, Receiver, ownDesc), setters are
+    /// called with `receiver` as `this` and data properties are created on `receiver`. Returns
+    /// whether the assignment succeeded.
+    pub fn put_with_receiver(
+        &self,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        val: JsValue,
+        receiver: JsValue,
+    ) -> Result<bool, JsValue> {
+        let mut slot = Slot::new();
+        if self.get_property_slot(vm, name, &mut slot) {
+            if slot.attributes().is_accessor() {
+                let setter = slot.accessor().setter();
+                if !setter.is_callable() {
+                    return Ok(false);
+                }
+                let ctx = vm.space().new_local_context();
+                let mut args = ctx.new_local(Arguments::new(vm, receiver, 1));
+                args[0] = val;
+                setter.as_object().as_function_mut().call(vm, &mut args)?;
+                return Ok(true);
+            }
+            if !slot.attributes().is_writable() {
+                return Ok(false);
+            }
+        }
+        if !receiver.is_object() {
+            return Ok(false);
+        }
+        let mut receiver = receiver.as_object();
+        let mut own = Slot::new();
+        if receiver.get_own_property_slot(vm, name, &mut own) {
+            if own.attributes().is_accessor() || !own.attributes().is_writable() {
+                return Ok(false);
+            }
+            let desc =
+                DataDescriptor::new(val, UNDEF_ENUMERABLE | UNDEF_CONFIGURABLE | UNDEF_WRITABLE);
+            return receiver.define_own_property(vm, name, &*desc, false);
+        }
+        receiver.define_own_property(vm, name, &*DataDescriptor::new(val, W | C | E), false)
+    }
+
     pub fn can_put_non_indexed(
         &self,
         vm: &mut VirtualMachine,
@@ -1004,12 +1514,9 @@ impl Gc<JsObject> {
     ) -> bool {
         if self.get_non_indexed_property_slot(vm, name, slot) {
             if slot.attributes().is_accessor() {
-                if slot.attributes().is_accessor() {
-                    return slot.accessor().setter().is_cell()
-                        && !slot.accessor().setter().is_empty();
-                } else {
-                    return slot.attributes().is_writable();
-                }
+                return slot.accessor().setter().is_cell() && !slot.accessor().setter().is_empty();
+            } else {
+                return slot.attributes().is_writable();
             }
         }
         self.is_extensible()
@@ -1178,7 +1685,12 @@ impl Gc<JsObject> {
             }
             None if !self.is_extensible() => {
                 if throwable {
-                    todo!()
+                    let msg = format!(
+                        "Cannot define property '{}', object is not extensible",
+                        index
+                    );
+                    let msg = JsString::new(vm, msg);
+                    return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
                 }
                 Ok(false)
             }
@@ -1242,6 +1754,16 @@ pub fn object_size_with_tag(tag: ObjectTag) -> usize {
         ObjectTag::Global => size + size_of::<JsGlobal>(),
         ObjectTag::NormalArguments => size + size_of::<JsArguments>(),
         ObjectTag::Function => size + size_of::<JsFunction>(),
+        ObjectTag::Regex => size + size_of::<JsRegExp>(),
+        ObjectTag::RegExpStringIterator => size + size_of::<JsRegExpStringIterator>(),
+        ObjectTag::Map | ObjectTag::Set => size + size_of::<JsMap>(),
+        ObjectTag::MapIterator | ObjectTag::SetIterator => size + size_of::<JsMapIterator>(),
+        ObjectTag::ArrayIterator => size + size_of::<JsArrayIterator>(),
+        ObjectTag::FinalizationRegistry => size + size_of::<JsFinalizationRegistry>(),
+        ObjectTag::Date => size + size_of::<JsDate>(),
+        ObjectTag::ArrayBuffer => size + size_of::<JsArrayBuffer>(),
+        tag if TypedArrayKind::from_tag(tag).is_some() => size + size_of::<JsTypedArray>(),
+        ObjectTag::Proxy => size + size_of::<JsProxy>(),
         _ => size,
     }
 }
@@ -1258,6 +1780,7 @@ pub enum ObjectTag {
     Json,
     Function,
     Regex,
+    Date,
     ArrayBuffer,
     Int8Array,
     Uint8Array,
@@ -1279,16 +1802,18 @@ pub enum ObjectTag {
     ForInIterator,
     WeakMap,
     WeakSet,
+    FinalizationRegistry,
 
     NormalArguments,
     StrictArguments,
 
     Proxy,
+    RegExpStringIterator,
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::runtime::array::JsArray;
+    use crate::runtime::{array::JsArray, function::JsNativeFunction};
 
     use super::*;
     use wtf_rs::keep_on_stack;
@@ -1356,4 +1881,220 @@ mod tests {
             VirtualMachineRef::dispose(vm);
         }
     }
+
+    #[test]
+    fn test_delete() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let my_struct = Structure::new_indexed(&mut vm, None, true);
+            let mut obj = ctx.new_local(JsObject::new(
+                &mut vm,
+                my_struct,
+                JsObject::get_class(),
+                ObjectTag::Ordinary,
+            ));
+            let key = vm.intern("foo");
+            let key2 = vm.intern("bar");
+            assert!(obj.put(&mut vm, key, JsValue::new(42), false).is_ok());
+            assert!(obj
+                .define_own_property(
+                    &mut vm,
+                    key2,
+                    &*DataDescriptor::new(JsValue::new(1), W),
+                    false
+                )
+                .is_ok());
+
+            assert!(matches!(obj.delete(&mut vm, key, false), Ok(true)));
+            assert!(!obj.has_own_property(&mut vm, key));
+            // deleting an absent property succeeds
+            assert!(matches!(obj.delete(&mut vm, key, true), Ok(true)));
+
+            assert!(matches!(obj.delete(&mut vm, key2, false), Ok(false)));
+            assert!(obj.delete(&mut vm, key2, true).is_err());
+            assert!(obj.has_own_property(&mut vm, key2));
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_long_transition_chain() {
+        // without a property count limit the chain length still is, see MAX_TRANSITION_CHAIN.
+        let mut vm = VirtualMachine::new(Options::builder().dictionary_threshold(u32::MAX).build());
+        {
+            let ctx = vm.space().new_local_context();
+            let my_struct = Structure::new_indexed(&mut vm, None, false);
+            let mut obj = ctx.new_local(JsObject::new(
+                &mut vm,
+                my_struct,
+                JsObject::get_class(),
+                ObjectTag::Ordinary,
+            ));
+            let first = vm.intern("p0");
+            for i in 0..100_000 {
+                let key = vm.intern(format!("p{}", i));
+                assert!(obj.put(&mut vm, key, JsValue::new(i), false).is_ok());
+            }
+            let mut structure = obj.structure();
+            assert!(structure.is_unique());
+            structure.allocate_table(&mut vm);
+            assert!(structure.has_table());
+            let entry = structure.get(&mut vm, first);
+            assert!(!entry.is_not_found());
+            assert_eq!(obj.get(&mut vm, first).map(|x| x.as_int32()).ok(), Some(0));
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    fn new_child(vm: &mut VirtualMachine, proto: Gc<JsObject>) -> Gc<JsObject> {
+        let structure = Structure::new_indexed(vm, Some(proto), false);
+        JsObject::new(vm, structure, JsObject::get_class(), ObjectTag::Ordinary)
+    }
+
+    #[test]
+    fn test_put_inherited_readonly() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut proto = ctx.new_local(JsObject::new_empty(&mut vm));
+            let key = vm.intern("x");
+            assert!(proto
+                .define_own_property(
+                    &mut vm,
+                    key,
+                    &*DataDescriptor::new(JsValue::new(1), E),
+                    false
+                )
+                .is_ok());
+            let mut child = ctx.new_local(new_child(&mut vm, *proto));
+
+            let res = child.put(&mut vm, key, JsValue::new(2), true);
+            match res {
+                Err(e) => assert!(std::ptr::eq(
+                    e.as_object().class(),
+                    JsTypeError::get_class()
+                )),
+                Ok(_) => panic!("strict write to inherited read-only property must throw"),
+            }
+            assert!(child.put(&mut vm, key, JsValue::new(2), false).is_ok());
+            assert!(!child.has_own_property(&mut vm, key));
+            let val = child.get(&mut vm, key).unwrap_or_else(|_| panic!());
+            assert_eq!(val.as_int32(), 1);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    fn record_setter(vm: &mut VirtualMachine, args: &Arguments) -> Result<JsValue, JsValue> {
+        let mut this = args.this.as_object();
+        let key = vm.intern("seen");
+        this.put(vm, key, args[0], true)?;
+        Ok(JsValue::undefined())
+    }
+
+    #[test]
+    fn test_put_inherited_setter() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut proto = ctx.new_local(JsObject::new_empty(&mut vm));
+            let key = vm.intern("y");
+            let setter = ctx.new_local(JsNativeFunction::new(&mut vm, key, record_setter, 1));
+            let desc = PropertyDescriptor::accessor_setter(JsValue::new(*setter), C);
+            assert!(proto
+                .define_own_property(&mut vm, key, &desc, false)
+                .is_ok());
+            let mut child = ctx.new_local(new_child(&mut vm, *proto));
+
+            assert!(child.put(&mut vm, key, JsValue::new(42), true).is_ok());
+            assert!(!child.has_own_property(&mut vm, key));
+            let seen = vm.intern("seen");
+            assert!(child.has_own_property(&mut vm, seen));
+            let val = child.get(&mut vm, seen).unwrap_or_else(|_| panic!());
+            assert_eq!(val.as_int32(), 42);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_prevent_extensions() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut obj = ctx.new_local(JsObject::new_empty(&mut vm));
+            let old = vm.intern("old");
+            let new = vm.intern("new");
+            assert!(obj.put(&mut vm, old, JsValue::new(1), true).is_ok());
+            assert!(obj
+                .put(&mut vm, Symbol::Indexed(0), JsValue::new(1), true)
+                .is_ok());
+            obj.prevent_extensions(&mut vm);
+            assert!(!obj.is_extensible());
+
+            for &key in [new, Symbol::Indexed(1)].iter() {
+                match obj.put(&mut vm, key, JsValue::new(2), true) {
+                    Err(e) => assert!(std::ptr::eq(
+                        e.as_object().class(),
+                        JsTypeError::get_class()
+                    )),
+                    Ok(_) => panic!("strict add to non-extensible object must throw"),
+                }
+                assert!(obj.put(&mut vm, key, JsValue::new(2), false).is_ok());
+                assert!(!obj.has_own_property(&mut vm, key));
+                let desc = DataDescriptor::new(JsValue::new(2), W | E | C);
+                assert!(obj.define_own_property(&mut vm, key, &*desc, true).is_err());
+                assert!(matches!(
+                    obj.define_own_property(&mut vm, key, &*desc, false),
+                    Ok(false)
+                ));
+            }
+
+            // Existing properties stay writable.
+            assert!(obj.put(&mut vm, old, JsValue::new(3), true).is_ok());
+            assert!(obj
+                .put(&mut vm, Symbol::Indexed(0), JsValue::new(3), true)
+                .is_ok());
+            let val = obj
+                .get(&mut vm, Symbol::Indexed(0))
+                .unwrap_or_else(|_| panic!());
+            assert_eq!(val.as_int32(), 3);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_inline_slots() {
+        for &inline in [0, 4].iter() {
+            let mut vm = VirtualMachine::new(Options::builder().inline_slots(inline).build());
+            let res = vm.eval(
+                "small = { a: 'p' + 1, b: 'q' + 2 };
+                 large = { a: 1, b: 2, c: 3, d: 4, e: 5, f: 6, g: 'r' + 7 };
+                 function read(o) { return o.a + o.d + o.g; }
+                 small.c = 3;
+                 out = read(large) + ',' + read(large) + ',' + small.c;
+                 delete large.b;
+                 large.h = 8;",
+            );
+            assert!(res.is_ok());
+            let global = vm.global_object();
+            let small = vm.get(global, "small").unwrap_or_else(|_| panic!());
+            let small = small.as_object();
+            let large = vm.get(global, "large").unwrap_or_else(|_| panic!());
+            let large = large.as_object();
+            assert_eq!(small.inline_slots(), inline);
+            assert_eq!(small.out_of_line_slots(), 3usize.saturating_sub(inline));
+            let size = large.structure().get_slots_size();
+            assert!(large.out_of_line_slots() > 0);
+            assert_eq!(large.out_of_line_slots(), size - inline);
+
+            vm.space().gc();
+            let res = vm.eval("again = small.a + small.b + small.c + large.g + large.h;");
+            assert!(res.is_ok());
+            let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+            assert_eq!(out.as_string().as_str(), "5r7,5r7,3");
+            let again = vm.get(global, "again").unwrap_or_else(|_| panic!());
+            assert_eq!(again.as_string().as_str(), "p1q23r78");
+            VirtualMachineRef::dispose(vm);
+        }
+    }
 }
@@ -0,0 +1,118 @@
+//! Private names of class elements, `#x` in `class { #x = 1; }`.
+//!
+//! Each evaluation of a class body creates a fresh private name for every private element it
+//! declares. Objects store private elements keyed by the identity of the name, outside of their
+//! structure, so they are never visible to property enumeration or reflection.
+use super::{symbol::Symbol, value::JsValue};
+use crate::{
+    heap::cell::{Cell, Gc, Trace, Tracer},
+    vm::VirtualMachine,
+};
+
+pub struct JsPrivateName {
+    /// Name as written in the source, including the leading `#`.
+    description: Symbol,
+    /// Function of a private method, empty for private fields.
+    method: JsValue,
+}
+
+impl JsPrivateName {
+    pub fn new(vm: &mut VirtualMachine, description: Symbol) -> Gc<Self> {
+        vm.space().alloc(Self {
+            description,
+            method: JsValue::empty(),
+        })
+    }
+
+    pub fn description(&self) -> Symbol {
+        self.description
+    }
+
+    /// Function shared by every object with this private method, `None` for private fields.
+    pub fn method(&self) -> Option<JsValue> {
+        if self.method.is_empty() {
+            None
+        } else {
+            Some(self.method)
+        }
+    }
+
+    pub fn set_method(&mut self, method: JsValue) {
+        self.method = method;
+    }
+}
+
+unsafe impl Trace for JsPrivateName {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.description.trace(tracer);
+        self.method.trace(tracer);
+    }
+}
+impl Cell for JsPrivateName {}
+
+#[cfg(feature = "debug-snapshots")]
+impl serde::Serialize for JsPrivateName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut x = serializer.serialize_struct("JsPrivateName", 1)?;
+        x.serialize_field("description", &self.description)?;
+        x.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        runtime::{
+            error::JsTypeError,
+            object::{EnumerationMode, JsObject},
+        },
+        vm::{Options, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_private_elements() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut obj = ctx.new_local(JsObject::new_empty(&mut vm));
+            let sym = vm.intern("#x");
+            let x = ctx.new_local(JsPrivateName::new(&mut vm, sym));
+            // same description, but a different class.
+            let other = ctx.new_local(JsPrivateName::new(&mut vm, sym));
+            let is_type_error =
+                |err: JsValue| std::ptr::eq(err.as_object().class(), JsTypeError::get_class());
+
+            assert!(obj.add_private(&mut vm, *x, JsValue::new(1)).is_ok());
+            assert!(obj.has_private(*x));
+            assert!(!obj.has_private(*other));
+            assert!(is_type_error(
+                obj.add_private(&mut vm, *x, JsValue::new(1)).unwrap_err()
+            ));
+            assert!(is_type_error(obj.get_private(&mut vm, *other).unwrap_err()));
+            assert!(is_type_error(
+                obj.set_private(&mut vm, *other, JsValue::new(3))
+                    .unwrap_err()
+            ));
+            assert!(obj.set_private(&mut vm, *x, JsValue::new(2)).is_ok());
+            assert_eq!(
+                obj.get_private(&mut vm, *x)
+                    .unwrap_or_else(|_| panic!())
+                    .as_int32(),
+                2
+            );
+
+            let mut names = 0;
+            obj.get_own_property_names(
+                &mut vm,
+                &mut |_, _| names += 1,
+                EnumerationMode::IncludeNotEnumerable,
+            );
+            assert_eq!(names, 0);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+}
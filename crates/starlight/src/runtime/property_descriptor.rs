@@ -2,7 +2,7 @@ use crate::{heap::cell::Gc, vm::VirtualMachine};
 
 use super::error::*;
 use super::string::*;
-use super::{accessor::Accessor, attributes::*};
+use super::{accessor::Accessor, arguments::Arguments, attributes::*};
 use super::{attributes::AttrExternal, value::JsValue};
 use std::ops::{Deref, DerefMut};
 #[derive(Clone, Copy)]
@@ -54,7 +54,7 @@ impl PropertyDescriptor {
     pub fn accessor_setter(setter: JsValue, attrs: u32) -> Self {
         Self {
             attrs: AttrExternal::new(Some(
-                attrs | ACCESSOR | UNDEF_VALUE | UNDEF_SETTER | UNDEF_WRITABLE,
+                attrs | ACCESSOR | UNDEF_VALUE | UNDEF_GETTER | UNDEF_WRITABLE,
             )),
             value: PropertyLayout {
                 accessors: (JsValue::undefined(), setter),
@@ -259,7 +259,6 @@ impl StoredSlot {
             self.attributes.raw,
         )
     }
-    #[allow(unused_variables)]
     pub fn get(
         &self,
         context: &mut VirtualMachine,
@@ -269,7 +268,16 @@ impl StoredSlot {
             return Ok(self.value);
         }
         assert!(self.attributes.is_accessor());
-        todo!("Invoke accessor")
+        let getter = self.accessor().getter();
+        if !getter.is_callable() {
+            return Ok(JsValue::undefined());
+        }
+        let ctx = context.space().new_local_context();
+        let mut args = ctx.new_local(Arguments::new(context, this_binding, 0));
+        getter
+            .as_object()
+            .as_function_mut()
+            .call(context, &mut args)
     }
 
     pub fn accessor(&self) -> Gc<Accessor> {
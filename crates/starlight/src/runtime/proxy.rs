@@ -0,0 +1,494 @@
+//! Proxy exotic objects.
+//!
+//! The `get`, `set`, `deleteProperty` and `ownKeys` traps are supported, every other internal
+//! method is forwarded to the target. Slot lookups of the method table can not throw so they
+//! always forward to the target; enumeration that runs traps goes through
+//! [JsObject::own_property_keys] and [JsObject::own_enumerable_keys] instead.
+use std::mem::ManuallyDrop;
+
+use super::{
+    arguments::Arguments, error::JsTypeError, method_table::*, object::*, property_descriptor::*,
+    slot::*, string::JsString, symbol::*, value::*,
+};
+use crate::{heap::cell::*, vm::*};
+
+pub struct JsProxy {
+    target: Gc<JsObject>,
+    handler: Gc<JsObject>,
+}
+
+fn type_error(vm: &mut VirtualMachine, msg: String) -> JsValue {
+    let msg = JsString::new(vm, msg);
+    JsValue::new(JsTypeError::new(vm, msg, None))
+}
+
+#[allow(non_snake_case)]
+impl JsProxy {
+    define_jsclass_with_symbol!(JsProxy, Proxy, Object);
+
+    /// section 10.5.14 ProxyCreate(target, handler)
+    pub fn new(
+        vm: &mut VirtualMachine,
+        target: Gc<JsObject>,
+        handler: Gc<JsObject>,
+    ) -> Gc<JsObject> {
+        let structure = vm.global_data().proxy_structure.unwrap();
+        let obj = JsObject::new(vm, structure, Self::get_class(), ObjectTag::Proxy);
+        unsafe {
+            *obj.data::<JsProxy>() = ManuallyDrop::new(JsProxy { target, handler });
+        }
+        obj
+    }
+
+    pub fn target(&self) -> Gc<JsObject> {
+        self.target
+    }
+
+    pub fn handler(&self) -> Gc<JsObject> {
+        self.handler
+    }
+
+    /// section 7.3.10 GetMethod(handler, P), `None` when the handler does not define the trap.
+    fn trap(
+        vm: &mut VirtualMachine,
+        proxy: Gc<JsObject>,
+        name: &str,
+    ) -> Result<Option<Gc<JsObject>>, JsValue> {
+        let key = vm.intern(name);
+        let trap = proxy.as_proxy().handler.get(vm, key)?;
+        if trap.is_undefined_or_null() {
+            return Ok(None);
+        }
+        if !trap.is_callable() {
+            return Err(type_error(
+                vm,
+                format!("'{}' on proxy: trap is not a function", name),
+            ));
+        }
+        Ok(Some(trap.as_object()))
+    }
+
+    /// Calls `trap` with the handler as `this`, the target followed by `args` are passed.
+    fn call_trap(
+        vm: &mut VirtualMachine,
+        proxy: Gc<JsObject>,
+        mut trap: Gc<JsObject>,
+        key: Option<Symbol>,
+        args: &[JsValue],
+    ) -> Result<JsValue, JsValue> {
+        let ctx = vm.space().new_local_context();
+        let proxy = proxy.as_proxy();
+        let argc = 1 + key.is_some() as usize + args.len();
+        let mut cargs = ctx.new_local(Arguments::new(vm, JsValue::new(proxy.handler), argc));
+        cargs[0] = JsValue::new(proxy.target);
+        let mut ix = 1;
+        if let Some(key) = key {
+            cargs[ix] = vm.key_value(key);
+            ix += 1;
+        }
+        for arg in args {
+            cargs[ix] = *arg;
+            ix += 1;
+        }
+        trap.as_function_mut().call(vm, &mut cargs)
+    }
+
+    /// section 10.5.8 [[Get]](P, Receiver)
+    fn get(vm: &mut VirtualMachine, proxy: Gc<JsObject>, name: Symbol) -> Result<JsValue, JsValue> {
+        match Self::trap(vm, proxy, "get")? {
+            Some(trap) => Self::call_trap(vm, proxy, trap, Some(name), &[JsValue::new(proxy)]),
+            None => proxy.as_proxy().target.get(vm, name),
+        }
+    }
+
+    /// section 10.5.9 [[Set]](P, V, Receiver)
+    fn set(
+        vm: &mut VirtualMachine,
+        proxy: Gc<JsObject>,
+        name: Symbol,
+        val: JsValue,
+        throwable: bool,
+    ) -> Result<(), JsValue> {
+        let trap = match Self::trap(vm, proxy, "set")? {
+            Some(trap) => trap,
+            None => {
+                let mut target = proxy.as_proxy().target;
+                return target.put(vm, name, val, throwable);
+            }
+        };
+        let args = [val, JsValue::new(proxy)];
+        let ok = Self::call_trap(vm, proxy, trap, Some(name), &args)?.to_boolean();
+        if !ok && throwable {
+            let desc = vm.description(name);
+            return Err(type_error(
+                vm,
+                format!(
+                    "'set' on proxy: trap returned falsish for property '{}'",
+                    desc
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// section 10.5.10 [[Delete]](P)
+    fn delete(
+        vm: &mut VirtualMachine,
+        proxy: Gc<JsObject>,
+        name: Symbol,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        let trap = match Self::trap(vm, proxy, "deleteProperty")? {
+            Some(trap) => trap,
+            None => {
+                let mut target = proxy.as_proxy().target;
+                return target.delete(vm, name, throwable);
+            }
+        };
+        let ok = Self::call_trap(vm, proxy, trap, Some(name), &[])?.to_boolean();
+        if !ok && throwable {
+            let desc = vm.description(name);
+            return Err(type_error(
+                vm,
+                format!(
+                    "'deleteProperty' on proxy: trap returned falsish for property '{}'",
+                    desc
+                ),
+            ));
+        }
+        Ok(ok)
+    }
+
+    /// section 10.5.11 [[OwnPropertyKeys]]()
+    pub fn own_keys(vm: &mut VirtualMachine, proxy: Gc<JsObject>) -> Result<Vec<Symbol>, JsValue> {
+        let target = proxy.as_proxy().target;
+        let trap = match Self::trap(vm, proxy, "ownKeys")? {
+            Some(trap) => trap,
+            None => return target.own_property_keys(vm),
+        };
+        let result = Self::call_trap(vm, proxy, trap, None, &[])?;
+        if !result.is_object() {
+            return Err(type_error(
+                vm,
+                "'ownKeys' on proxy: trap returned a non-object".to_owned(),
+            ));
+        }
+        let ctx = vm.space().new_local_context();
+        let result = ctx.new_local(result.as_object());
+        // section 7.3.18 CreateListFromArrayLike(obj, « String, Symbol »)
+        let length = result.get(vm, Symbol::length())?.to_number(vm)?;
+        let length = if length.is_nan() || length < 0.0 {
+            0
+        } else {
+            length.min(u32::MAX as f64) as u32
+        };
+        let mut keys = vec![];
+        for ix in 0..length {
+            let key = result.get(vm, Symbol::Indexed(ix))?;
+            if !key.is_string() && !key.is_symbol() {
+                let msg = format!("'ownKeys' on proxy: {} is not a valid property name", key);
+                return Err(type_error(vm, msg));
+            }
+            let key = key.to_symbol(vm)?;
+            if keys.contains(&key) {
+                let desc = vm.description(key);
+                return Err(type_error(
+                    vm,
+                    format!(
+                        "'ownKeys' on proxy: trap returned duplicate entries ('{}')",
+                        desc
+                    ),
+                ));
+            }
+            keys.push(key);
+        }
+
+        let extensible = target.is_extensible();
+        let mut configurable = vec![];
+        let mut non_configurable = vec![];
+        for key in target.own_property_keys(vm)? {
+            let mut slot = Slot::new();
+            if target.get_own_property_slot(vm, key, &mut slot)
+                && !slot.attributes().is_configurable()
+            {
+                non_configurable.push(key);
+            } else {
+                configurable.push(key);
+            }
+        }
+        if extensible && non_configurable.is_empty() {
+            return Ok(keys);
+        }
+        let mut unchecked = keys.clone();
+        for key in non_configurable {
+            match unchecked.iter().position(|other| *other == key) {
+                Some(pos) => {
+                    unchecked.remove(pos);
+                }
+                None => {
+                    let desc = vm.description(key);
+                    return Err(type_error(
+                        vm,
+                        format!(
+                            "'ownKeys' on proxy: trap result did not include non-configurable \
+                             property '{}'",
+                            desc
+                        ),
+                    ));
+                }
+            }
+        }
+        if extensible {
+            return Ok(keys);
+        }
+        for key in configurable {
+            match unchecked.iter().position(|other| *other == key) {
+                Some(pos) => {
+                    unchecked.remove(pos);
+                }
+                None => {
+                    let desc = vm.description(key);
+                    return Err(type_error(
+                        vm,
+                        format!(
+                            "'ownKeys' on proxy: trap result did not include '{}' of a \
+                             non-extensible target",
+                            desc
+                        ),
+                    ));
+                }
+            }
+        }
+        if !unchecked.is_empty() {
+            return Err(type_error(
+                vm,
+                "'ownKeys' on proxy: trap returned extra keys for a non-extensible target"
+                    .to_owned(),
+            ));
+        }
+        Ok(keys)
+    }
+
+    /// Keys of [JsProxy::own_keys] whose own property is enumerable. Without a
+    /// `getOwnPropertyDescriptor` trap the property of the target is looked at, keys the
+    /// target does not have are skipped.
+    pub fn own_enumerable_keys(
+        vm: &mut VirtualMachine,
+        proxy: Gc<JsObject>,
+    ) -> Result<Vec<Symbol>, JsValue> {
+        let keys = Self::own_keys(vm, proxy)?;
+        let target = proxy.as_proxy().target;
+        let trap = Self::trap(vm, proxy, "getOwnPropertyDescriptor")?;
+        let mut enumerable = Vec::with_capacity(keys.len());
+        for key in keys {
+            let is_enumerable = match trap {
+                Some(trap) => {
+                    let desc = Self::call_trap(vm, proxy, trap, Some(key), &[])?;
+                    if desc.is_undefined() {
+                        false
+                    } else if !desc.is_object() {
+                        let desc = vm.description(key);
+                        return Err(type_error(
+                            vm,
+                            format!(
+                                "'getOwnPropertyDescriptor' on proxy: trap returned neither \
+                                 object nor undefined for property '{}'",
+                                desc
+                            ),
+                        ));
+                    } else {
+                        desc.as_object().get(vm, Symbol::enumerable())?.to_boolean()
+                    }
+                }
+                None => {
+                    let mut slot = Slot::new();
+                    target.get_own_property_slot(vm, key, &mut slot)
+                        && slot.attributes().is_enumerable()
+                }
+            };
+            if is_enumerable {
+                enumerable.push(key);
+            }
+        }
+        Ok(enumerable)
+    }
+
+    pub fn GetNonIndexedSlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> Result<JsValue, JsValue> {
+        slot.make_uncacheable();
+        Self::get(vm, obj, name)
+    }
+
+    pub fn GetIndexedSlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        slot: &mut Slot,
+    ) -> Result<JsValue, JsValue> {
+        slot.make_uncacheable();
+        Self::get(vm, obj, Symbol::Indexed(index))
+    }
+
+    pub fn GetNonIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> bool {
+        let found = obj
+            .as_proxy()
+            .target
+            .get_non_indexed_property_slot(vm, name, slot);
+        slot.make_uncacheable();
+        found
+    }
+
+    pub fn GetIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        slot: &mut Slot,
+    ) -> bool {
+        let found = obj
+            .as_proxy()
+            .target
+            .get_indexed_property_slot(vm, index, slot);
+        slot.make_uncacheable();
+        found
+    }
+
+    pub fn GetOwnNonIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> bool {
+        let found = obj
+            .as_proxy()
+            .target
+            .get_own_non_indexed_property_slot(vm, name, slot);
+        slot.make_uncacheable();
+        found
+    }
+
+    pub fn GetOwnIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        slot: &mut Slot,
+    ) -> bool {
+        let found = obj
+            .as_proxy()
+            .target
+            .get_own_indexed_property_slot(vm, index, slot);
+        slot.make_uncacheable();
+        found
+    }
+
+    pub fn PutNonIndexedSlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        val: JsValue,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<(), JsValue> {
+        slot.make_uncacheable();
+        Self::set(vm, obj, name, val, throwable)
+    }
+
+    pub fn PutIndexedSlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        val: JsValue,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<(), JsValue> {
+        slot.make_uncacheable();
+        Self::set(vm, obj, Symbol::Indexed(index), val, throwable)
+    }
+
+    pub fn DeleteNonIndexedMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        Self::delete(vm, obj, name, throwable)
+    }
+
+    pub fn DeleteIndexedMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        Self::delete(vm, obj, Symbol::Indexed(index), throwable)
+    }
+
+    pub fn DefineOwnNonIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        desc: &PropertyDescriptor,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        let mut target = obj.as_proxy().target;
+        target.define_own_non_indexed_property_slot(vm, name, desc, slot, throwable)
+    }
+
+    pub fn DefineOwnIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        desc: &PropertyDescriptor,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        let mut target = obj.as_proxy().target;
+        target.define_own_indexed_property_slot(vm, index, desc, slot, throwable)
+    }
+
+    pub fn GetPropertyNamesMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        collector: &mut dyn FnMut(Symbol, u32),
+        mode: EnumerationMode,
+    ) {
+        obj.as_proxy()
+            .target
+            .get_property_names(vm, collector, mode)
+    }
+
+    pub fn GetOwnPropertyNamesMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        collector: &mut dyn FnMut(Symbol, u32),
+        mode: EnumerationMode,
+    ) {
+        obj.as_proxy()
+            .target
+            .get_own_property_names(vm, collector, mode)
+    }
+
+    pub fn DefaultValueMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        hint: JsHint,
+    ) -> Result<JsValue, JsValue> {
+        JsObject::DefaultValueMethod(obj, vm, hint)
+    }
+}
+
+unsafe impl Trace for JsProxy {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.target.trace(tracer);
+        self.handler.trace(tracer);
+    }
+}
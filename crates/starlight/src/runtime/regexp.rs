@@ -0,0 +1,1202 @@
+//! Backtracking regular expression engine behind `RegExp` objects.
+//!
+//! Patterns are matched against UTF-16 code units like the spec requires for non-unicode
+//! patterns, with the `u` flag a surrogate pair is matched as one code point instead. Supported
+//! syntax is the ES2018 pattern grammar with Annex B leniency, except for lookbehind assertions
+//! and unicode property escapes.
+use std::{mem::ManuallyDrop, ops::Range};
+
+use super::{
+    attributes::*,
+    error::JsSyntaxError,
+    method_table::*,
+    object::{JsObject, ObjectTag},
+    property_descriptor::DataDescriptor,
+    string::JsString,
+    symbol::Symbol,
+    value::JsValue,
+};
+use crate::{
+    heap::cell::{Gc, Trace, Tracer},
+    vm::VirtualMachine,
+};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct RegExpFlags {
+    pub global: bool,
+    pub ignore_case: bool,
+    pub multiline: bool,
+    pub dot_all: bool,
+    pub unicode: bool,
+    /// Matches only start at `lastIndex` instead of scanning forward from it.
+    pub sticky: bool,
+}
+
+impl RegExpFlags {
+    pub fn parse(flags: &str) -> Result<Self, String> {
+        let mut result = Self::default();
+        for c in flags.chars() {
+            let flag = match c {
+                'g' => &mut result.global,
+                'i' => &mut result.ignore_case,
+                'm' => &mut result.multiline,
+                's' => &mut result.dot_all,
+                'u' => &mut result.unicode,
+                'y' => &mut result.sticky,
+                _ => return Err(format!("Invalid regular expression flags '{}'", flags)),
+            };
+            if *flag {
+                return Err(format!("Invalid regular expression flags '{}'", flags));
+            }
+            *flag = true;
+        }
+        Ok(result)
+    }
+}
+
+impl std::fmt::Display for RegExpFlags {
+    /// Flags in the canonical order used by `RegExp.prototype.flags`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for &(set, c) in [
+            (self.global, 'g'),
+            (self.ignore_case, 'i'),
+            (self.multiline, 'm'),
+            (self.dot_all, 's'),
+            (self.unicode, 'u'),
+            (self.sticky, 'y'),
+        ]
+        .iter()
+        {
+            if set {
+                write!(f, "{}", c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Start and end code unit index of every capture, index 0 is the whole match.
+pub type Captures = Vec<Option<(usize, usize)>>;
+
+#[derive(Clone, Debug)]
+struct CharClass {
+    ranges: Vec<(u32, u32)>,
+    /// Complemented sets coming from `\D`, `\W` and `\S` inside of a class.
+    excluded: Vec<&'static [(u32, u32)]>,
+    negated: bool,
+}
+
+impl CharClass {
+    fn new(ranges: &'static [(u32, u32)], negated: bool) -> Self {
+        Self {
+            ranges: ranges.to_vec(),
+            excluded: vec![],
+            negated,
+        }
+    }
+
+    fn contains_exact(&self, c: u32) -> bool {
+        in_ranges(&self.ranges, c) || self.excluded.iter().any(|set| !in_ranges(set, c))
+    }
+
+    fn matches(&self, c: u32, ignore_case: bool) -> bool {
+        let found = self.contains_exact(c)
+            || (ignore_case && case_variants(c).any(|v| self.contains_exact(v)));
+        found != self.negated
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Repeat {
+    node: Node,
+    min: u32,
+    max: Option<u32>,
+    greedy: bool,
+    /// Captures inside of the repeated atom, they are reset on every iteration.
+    captures: Range<usize>,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Empty,
+    Char(u32),
+    Any,
+    Class(Box<CharClass>),
+    LineStart,
+    LineEnd,
+    WordBoundary { negated: bool },
+    Group(Box<Node>, Option<usize>),
+    BackRef(usize),
+    Seq(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat(Box<Repeat>),
+    Look { node: Box<Node>, negated: bool },
+}
+
+impl Node {
+    /// Nodes that consume exactly one character, their repetitions are matched with a loop.
+    fn is_single_unit(&self) -> bool {
+        matches!(self, Node::Char(_) | Node::Any | Node::Class(_))
+    }
+}
+
+const DIGITS: &[(u32, u32)] = &[(0x30, 0x39)];
+const WORD: &[(u32, u32)] = &[(0x30, 0x39), (0x41, 0x5a), (0x5f, 0x5f), (0x61, 0x7a)];
+const SPACES: &[(u32, u32)] = &[
+    (0x09, 0x0d),
+    (0x20, 0x20),
+    (0xa0, 0xa0),
+    (0x1680, 0x1680),
+    (0x2000, 0x200a),
+    (0x2028, 0x2029),
+    (0x202f, 0x202f),
+    (0x205f, 0x205f),
+    (0x3000, 0x3000),
+    (0xfeff, 0xfeff),
+];
+
+fn in_ranges(ranges: &[(u32, u32)], c: u32) -> bool {
+    ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi)
+}
+
+fn is_line_terminator(c: u32) -> bool {
+    matches!(c, 0x0a | 0x0d | 0x2028 | 0x2029)
+}
+
+fn is_word(c: u16) -> bool {
+    in_ranges(WORD, c as u32)
+}
+
+fn is_lead_surrogate(c: u32) -> bool {
+    (0xd800..0xdc00).contains(&c)
+}
+
+fn is_trail_surrogate(c: u32) -> bool {
+    (0xdc00..0xe000).contains(&c)
+}
+
+fn code_point(lead: u32, trail: u32) -> u32 {
+    0x10000 + ((lead - 0xd800) << 10) + (trail - 0xdc00)
+}
+
+fn single_char(mut chars: impl Iterator<Item = char>) -> Option<u32> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if (c as u32) <= 0xffff => Some(c as u32),
+        _ => None,
+    }
+}
+
+/// section 21.2.2.8.2 Canonicalize for non-unicode patterns, characters outside of the BMP are
+/// left as they are.
+fn canonicalize(c: u32) -> u32 {
+    let ch = match char::from_u32(c) {
+        Some(ch) => ch,
+        None => return c,
+    };
+    match single_char(ch.to_uppercase()) {
+        // Don't map non-ASCII characters into ASCII, e.g. 'ſ' must not match 's'.
+        Some(upper) if !(c >= 128 && upper < 128) => upper,
+        _ => c,
+    }
+}
+
+fn case_variants(c: u32) -> impl Iterator<Item = u32> {
+    let ch = char::from_u32(c);
+    let lower = ch.and_then(|ch| single_char(ch.to_lowercase()));
+    let upper = ch.and_then(|ch| single_char(ch.to_uppercase()));
+    lower.into_iter().chain(upper)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Whether the `u` flag is set, characters outside of the BMP are single atoms then.
+    unicode: bool,
+    captures: usize,
+    total_captures: usize,
+    names: Vec<(String, usize)>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.chars.get(self.pos + n).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn looking_at(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    /// Counts capturing groups and collects their names before parsing so that forward
+    /// references like `\2(a)(b)` and `\k<x>(?<x>a)` resolve.
+    fn scan_groups(&mut self) -> Result<(), String> {
+        let mut i = 0;
+        let mut in_class = false;
+        while i < self.chars.len() {
+            match self.chars[i] {
+                '\\' => i += 1,
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '(' if !in_class => {
+                    if self.chars.get(i + 1) != Some(&'?') {
+                        self.total_captures += 1;
+                    } else if self.chars.get(i + 2) == Some(&'<')
+                        && !matches!(self.chars.get(i + 3), Some('=') | Some('!'))
+                    {
+                        self.total_captures += 1;
+                        let name = self.chars[i + 3..]
+                            .iter()
+                            .take_while(|&&c| c != '>')
+                            .collect::<String>();
+                        if self.names.iter().any(|(n, _)| *n == name) {
+                            return Err(format!("Duplicate capture group name '{}'", name));
+                        }
+                        self.names.push((name, self.total_captures));
+                    }
+                }
+                _ => (),
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn parse_disjunction(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.parse_alternative()?];
+        while self.eat('|') {
+            alternatives.push(self.parse_alternative()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alt(alternatives)
+        })
+    }
+
+    fn parse_alternative(&mut self) -> Result<Node, String> {
+        let mut terms = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            terms.push(self.parse_term()?);
+        }
+        Ok(match terms.len() {
+            0 => Node::Empty,
+            1 => terms.pop().unwrap(),
+            _ => Node::Seq(terms),
+        })
+    }
+
+    fn parse_term(&mut self) -> Result<Node, String> {
+        if self.eat('^') {
+            return Ok(Node::LineStart);
+        }
+        if self.eat('$') {
+            return Ok(Node::LineEnd);
+        }
+        if self.looking_at("\\b") || self.looking_at("\\B") {
+            self.pos += 2;
+            return Ok(Node::WordBoundary {
+                negated: self.chars[self.pos - 1] == 'B',
+            });
+        }
+        if self.looking_at("(?<=") || self.looking_at("(?<!") {
+            return Err("Lookbehind assertions are not supported".to_owned());
+        }
+        if self.looking_at("(?=") || self.looking_at("(?!") {
+            self.pos += 3;
+            let negated = self.chars[self.pos - 1] == '!';
+            let node = self.parse_disjunction()?;
+            if !self.eat(')') {
+                return Err("Unterminated group".to_owned());
+            }
+            return Ok(Node::Look {
+                node: Box::new(node),
+                negated,
+            });
+        }
+
+        let captures_before = self.captures;
+        let atom = self.parse_atom()?;
+        let (min, max) = match self.peek() {
+            Some('{') => match self.parse_braces() {
+                Some(bounds) => bounds,
+                None => return Ok(atom),
+            },
+            Some(c @ '*') | Some(c @ '+') | Some(c @ '?') => {
+                self.pos += 1;
+                match c {
+                    '*' => (0, None),
+                    '+' => (1, None),
+                    _ => (0, Some(1)),
+                }
+            }
+            _ => return Ok(atom),
+        };
+        if let Some(max) = max {
+            if max < min {
+                return Err("numbers out of order in {} quantifier".to_owned());
+            }
+        }
+        let greedy = !self.eat('?');
+        Ok(Node::Repeat(Box::new(Repeat {
+            node: atom,
+            min,
+            max,
+            greedy,
+            captures: captures_before + 1..self.captures + 1,
+        })))
+    }
+
+    fn parse_digits(&mut self) -> Option<u32> {
+        let start = self.pos;
+        let mut value = 0u32;
+        while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+            value = value.saturating_mul(10).saturating_add(d);
+            self.pos += 1;
+        }
+        if self.pos == start {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Parses `{n}`, `{n,}` or `{n,m}`. A brace that doesn't start a valid quantifier is left
+    /// alone and matched literally.
+    fn parse_braces(&mut self) -> Option<(u32, Option<u32>)> {
+        let start = self.pos;
+        self.pos += 1;
+        let result = (|| {
+            let min = self.parse_digits()?;
+            let max = if self.eat(',') {
+                self.parse_digits()
+            } else {
+                Some(min)
+            };
+            if self.eat('}') {
+                Some((min, max))
+            } else {
+                None
+            }
+        })();
+        if result.is_none() {
+            self.pos = start;
+        }
+        result
+    }
+
+    fn char_node(&self, c: char) -> Node {
+        let mut units = [0; 2];
+        let units = c.encode_utf16(&mut units);
+        if units.len() == 1 || self.unicode {
+            Node::Char(c as u32)
+        } else {
+            Node::Seq(units.iter().map(|&u| Node::Char(u as u32)).collect())
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let c = self.peek().unwrap();
+        self.pos += 1;
+        match c {
+            '.' => Ok(Node::Any),
+            '(' => {
+                let index = if self.eat('?') {
+                    if self.eat(':') {
+                        None
+                    } else if self.eat('<') {
+                        let name = self.parse_group_name()?;
+                        self.captures += 1;
+                        debug_assert!(self.names.contains(&(name, self.captures)));
+                        Some(self.captures)
+                    } else {
+                        return Err("Invalid group".to_owned());
+                    }
+                } else {
+                    self.captures += 1;
+                    Some(self.captures)
+                };
+                let node = self.parse_disjunction()?;
+                if !self.eat(')') {
+                    return Err("Unterminated group".to_owned());
+                }
+                Ok(Node::Group(Box::new(node), index))
+            }
+            ')' => Err("Unmatched ')'".to_owned()),
+            '*' | '+' | '?' => Err("Nothing to repeat".to_owned()),
+            '{' => {
+                self.pos -= 1;
+                if self.parse_braces().is_some() {
+                    return Err("Nothing to repeat".to_owned());
+                }
+                self.pos += 1;
+                Ok(Node::Char('{' as u32))
+            }
+            '[' => self.parse_class(),
+            '\\' => self.parse_atom_escape(),
+            c => Ok(self.char_node(c)),
+        }
+    }
+
+    fn parse_group_name(&mut self) -> Result<String, String> {
+        let mut name = String::new();
+        loop {
+            match self.peek() {
+                Some('>') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(c) if c == '$' || c == '_' || c.is_alphanumeric() => {
+                    name.push(c);
+                    self.pos += 1;
+                }
+                _ => return Err("Invalid capture group name".to_owned()),
+            }
+        }
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err("Invalid capture group name".to_owned());
+        }
+        Ok(name)
+    }
+
+    fn parse_atom_escape(&mut self) -> Result<Node, String> {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err("\\ at end of pattern".to_owned()),
+        };
+        if c.is_ascii_digit() && c != '0' {
+            let start = self.pos;
+            let n = self.parse_digits().unwrap() as usize;
+            if n <= self.total_captures {
+                return Ok(Node::BackRef(n));
+            }
+            // Annex B: not a back reference, read it as an octal escape or a literal digit.
+            self.pos = start;
+        }
+        if c == 'k' && !self.names.is_empty() {
+            self.pos += 1;
+            if !self.eat('<') {
+                return Err("Invalid named reference".to_owned());
+            }
+            let name = self.parse_group_name()?;
+            return match self.names.iter().find(|(n, _)| *n == name) {
+                Some(&(_, index)) => Ok(Node::BackRef(index)),
+                None => Err("Invalid named capture referenced".to_owned()),
+            };
+        }
+        match self.parse_class_escape()? {
+            ClassAtom::Unit(u) => Ok(Node::Char(u)),
+            ClassAtom::Set(ranges, negated) => {
+                Ok(Node::Class(Box::new(CharClass::new(ranges, negated))))
+            }
+        }
+    }
+
+    /// Escapes shared between atoms and classes, `self.pos` points after the backslash.
+    fn parse_class_escape(&mut self) -> Result<ClassAtom, String> {
+        let c = self.peek().unwrap();
+        self.pos += 1;
+        let unit = match c {
+            'd' => return Ok(ClassAtom::Set(DIGITS, false)),
+            'D' => return Ok(ClassAtom::Set(DIGITS, true)),
+            'w' => return Ok(ClassAtom::Set(WORD, false)),
+            'W' => return Ok(ClassAtom::Set(WORD, true)),
+            's' => return Ok(ClassAtom::Set(SPACES, false)),
+            'S' => return Ok(ClassAtom::Set(SPACES, true)),
+            't' => 0x09,
+            'n' => 0x0a,
+            'v' => 0x0b,
+            'f' => 0x0c,
+            'r' => 0x0d,
+            'c' => match self.peek() {
+                Some(l) if l.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    l as u32 % 32
+                }
+                _ => {
+                    // Annex B: `\c` without a control letter is a literal backslash.
+                    self.pos -= 1;
+                    '\\' as u32
+                }
+            },
+            '0'..='7' => {
+                self.pos -= 1;
+                let mut value = 0;
+                let mut len = 0;
+                while let Some(d) = self.peek().and_then(|c| c.to_digit(8)) {
+                    if len == 3 || value * 8 + d > 0o377 {
+                        break;
+                    }
+                    value = value * 8 + d;
+                    self.pos += 1;
+                    len += 1;
+                }
+                value
+            }
+            'x' => self.parse_hex(2).unwrap_or('x' as u32),
+            'u' => self.parse_unicode_escape()?,
+            c if self.unicode => c as u32,
+            c => {
+                let mut units = [0; 2];
+                let units = c.encode_utf16(&mut units);
+                if units.len() != 1 {
+                    return Err("Invalid escape".to_owned());
+                }
+                units[0] as u32
+            }
+        };
+        Ok(ClassAtom::Unit(unit))
+    }
+
+    fn parse_hex(&mut self, len: usize) -> Option<u32> {
+        let digits = self.chars.get(self.pos..self.pos + len)?;
+        let mut value = 0;
+        for c in digits {
+            value = value * 16 + c.to_digit(16)?;
+        }
+        self.pos += len;
+        Some(value)
+    }
+
+    /// `\uXXXX`, `self.pos` points after the `u`. Unicode patterns also take `\u{X...}` and
+    /// read a surrogate pair written as two escapes as one code point, a malformed escape is an
+    /// error there instead of a literal `u`.
+    fn parse_unicode_escape(&mut self) -> Result<u32, String> {
+        if !self.unicode {
+            return Ok(self.parse_hex(4).unwrap_or('u' as u32));
+        }
+        if self.eat('{') {
+            let mut value = 0u32;
+            let mut digits = 0;
+            while let Some(d) = self.peek().and_then(|c| c.to_digit(16)) {
+                value = value.saturating_mul(16).saturating_add(d);
+                digits += 1;
+                self.pos += 1;
+            }
+            if digits == 0 || value > 0x10ffff || !self.eat('}') {
+                return Err("Invalid Unicode escape".to_owned());
+            }
+            return Ok(value);
+        }
+        let unit = match self.parse_hex(4) {
+            Some(unit) => unit,
+            None => return Err("Invalid Unicode escape".to_owned()),
+        };
+        if is_lead_surrogate(unit) && self.looking_at("\\u") {
+            let save = self.pos;
+            self.pos += 2;
+            match self.parse_hex(4) {
+                Some(trail) if is_trail_surrogate(trail) => return Ok(code_point(unit, trail)),
+                _ => self.pos = save,
+            }
+        }
+        Ok(unit)
+    }
+
+    fn parse_class_atom(&mut self) -> Result<ClassAtom, String> {
+        match self.peek() {
+            None => Err("Unterminated character class".to_owned()),
+            Some('\\') => {
+                self.pos += 1;
+                match self.peek() {
+                    None => Err("\\ at end of pattern".to_owned()),
+                    Some('b') => {
+                        self.pos += 1;
+                        Ok(ClassAtom::Unit(0x08))
+                    }
+                    Some('-') => {
+                        self.pos += 1;
+                        Ok(ClassAtom::Unit('-' as u32))
+                    }
+                    _ => self.parse_class_escape(),
+                }
+            }
+            Some(c) => {
+                self.pos += 1;
+                let mut units = [0; 2];
+                let units = c.encode_utf16(&mut units);
+                if units.len() == 2 && !self.unicode {
+                    Ok(ClassAtom::Pair(units[0], units[1]))
+                } else {
+                    Ok(ClassAtom::Unit(c as u32))
+                }
+            }
+        }
+    }
+
+    fn parse_class(&mut self) -> Result<Node, String> {
+        let negated = self.eat('^');
+        let mut class = CharClass {
+            ranges: vec![],
+            excluded: vec![],
+            negated,
+        };
+        loop {
+            if self.eat(']') {
+                break;
+            }
+            let from = self.parse_class_atom()?;
+            if self.peek() == Some('-') && self.peek_at(1) != Some(']') && self.peek_at(1).is_some()
+            {
+                let save = self.pos;
+                self.pos += 1;
+                let to = self.parse_class_atom()?;
+                match (from, to) {
+                    (ClassAtom::Unit(lo), ClassAtom::Unit(hi)) => {
+                        if lo > hi {
+                            return Err("Range out of order in character class".to_owned());
+                        }
+                        class.ranges.push((lo, hi));
+                        continue;
+                    }
+                    // Annex B: a range with a class escape on either side is read literally.
+                    _ => self.pos = save,
+                }
+            }
+            match from {
+                ClassAtom::Unit(u) => class.ranges.push((u, u)),
+                ClassAtom::Pair(hi, lo) => {
+                    class.ranges.push((hi as u32, hi as u32));
+                    class.ranges.push((lo as u32, lo as u32));
+                }
+                ClassAtom::Set(set, false) => class.ranges.extend_from_slice(set),
+                ClassAtom::Set(set, true) => class.excluded.push(set),
+            }
+        }
+        Ok(Node::Class(Box::new(class)))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ClassAtom {
+    /// A code unit, or a code point in unicode patterns.
+    Unit(u32),
+    /// A character outside of the BMP in a non-unicode pattern, classes see its two surrogates
+    /// separately.
+    Pair(u16, u16),
+    Set(&'static [(u32, u32)], bool),
+}
+
+/// A compiled pattern.
+#[derive(Clone, Debug)]
+pub struct Regex {
+    flags: RegExpFlags,
+    node: Node,
+    captures: usize,
+    names: Vec<(String, usize)>,
+}
+
+impl Regex {
+    pub fn new(pattern: &str, flags: RegExpFlags) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            unicode: flags.unicode,
+            captures: 0,
+            total_captures: 0,
+            names: vec![],
+        };
+        parser.scan_groups()?;
+        let node = parser.parse_disjunction()?;
+        if parser.pos != parser.chars.len() {
+            return Err("Unmatched ')'".to_owned());
+        }
+        Ok(Self {
+            flags,
+            node,
+            captures: parser.captures,
+            names: parser.names,
+        })
+    }
+
+    pub fn flags(&self) -> RegExpFlags {
+        self.flags
+    }
+
+    /// Number of capturing groups, not counting the whole match.
+    pub fn capture_count(&self) -> usize {
+        self.captures
+    }
+
+    /// Named groups with their capture index, in pattern order.
+    pub fn group_names(&self) -> &[(String, usize)] {
+        &self.names
+    }
+
+    /// Tries to match at exactly `start`.
+    pub fn match_at(&self, input: &[u16], start: usize) -> Option<Captures> {
+        let mut caps = vec![None; self.captures + 1];
+        let matcher = Matcher {
+            input,
+            flags: self.flags,
+        };
+        let mut end = 0;
+        if matcher.node(&self.node, start, &mut caps, &mut |p, _| {
+            end = p;
+            true
+        }) {
+            caps[0] = Some((start, end));
+            Some(caps)
+        } else {
+            None
+        }
+    }
+
+    /// Finds the first match starting at or after `start`, sticky patterns only try `start`.
+    /// Unicode patterns don't try to match between the two halves of a surrogate pair.
+    pub fn exec(&self, input: &[u16], start: usize) -> Option<Captures> {
+        if self.flags.sticky {
+            return self.match_at(input, start);
+        }
+        let splits_pair = |i: usize| {
+            i > start
+                && i < input.len()
+                && is_lead_surrogate(input[i - 1] as u32)
+                && is_trail_surrogate(input[i] as u32)
+        };
+        (start..=input.len())
+            .filter(|&i| !self.flags.unicode || !splits_pair(i))
+            .find_map(|i| self.match_at(input, i))
+    }
+}
+
+struct Matcher<'a> {
+    input: &'a [u16],
+    flags: RegExpFlags,
+}
+
+type Cont<'k> = dyn FnMut(usize, &mut Captures) -> bool + 'k;
+
+impl<'a> Matcher<'a> {
+    /// Character at `pos` and the number of code units it takes. Only unicode patterns read a
+    /// surrogate pair as one character.
+    fn char_at(&self, pos: usize) -> Option<(u32, usize)> {
+        let c = *self.input.get(pos)? as u32;
+        if self.flags.unicode && is_lead_surrogate(c) {
+            if let Some(&trail) = self.input.get(pos + 1) {
+                if is_trail_surrogate(trail as u32) {
+                    return Some((code_point(c, trail as u32), 2));
+                }
+            }
+        }
+        Some((c, 1))
+    }
+
+    /// End of the single character matched by `node` at `pos`.
+    fn step(&self, node: &Node, pos: usize) -> Option<usize> {
+        let (c, len) = self.char_at(pos)?;
+        let matched = match node {
+            Node::Char(u) => {
+                *u == c || (self.flags.ignore_case && canonicalize(*u) == canonicalize(c))
+            }
+            Node::Any => self.flags.dot_all || !is_line_terminator(c),
+            Node::Class(class) => class.matches(c, self.flags.ignore_case),
+            _ => unreachable!(),
+        };
+        if matched {
+            Some(pos + len)
+        } else {
+            None
+        }
+    }
+
+    /// Matches `node` at `pos` and calls `k` with the end position. On failure `caps` is left as
+    /// it was on entry.
+    fn node(&self, node: &Node, pos: usize, caps: &mut Captures, k: &mut Cont) -> bool {
+        match node {
+            Node::Empty => k(pos, caps),
+            Node::Char(_) | Node::Any | Node::Class(_) => match self.step(node, pos) {
+                Some(end) => k(end, caps),
+                None => false,
+            },
+            Node::LineStart => {
+                let ok = pos == 0
+                    || (self.flags.multiline && is_line_terminator(self.input[pos - 1] as u32));
+                ok && k(pos, caps)
+            }
+            Node::LineEnd => {
+                let ok = pos == self.input.len()
+                    || (self.flags.multiline && is_line_terminator(self.input[pos] as u32));
+                ok && k(pos, caps)
+            }
+            Node::WordBoundary { negated } => {
+                let before = pos > 0 && is_word(self.input[pos - 1]);
+                let after = pos < self.input.len() && is_word(self.input[pos]);
+                ((before != after) != *negated) && k(pos, caps)
+            }
+            Node::Group(inner, None) => self.node(inner, pos, caps, k),
+            Node::Group(inner, Some(index)) => {
+                let index = *index;
+                self.node(inner, pos, caps, &mut |end, caps| {
+                    let old = caps[index];
+                    caps[index] = Some((pos, end));
+                    if k(end, caps) {
+                        true
+                    } else {
+                        caps[index] = old;
+                        false
+                    }
+                })
+            }
+            Node::BackRef(index) => match caps[*index] {
+                None => k(pos, caps),
+                Some((start, end)) => {
+                    let len = end - start;
+                    if pos + len > self.input.len() {
+                        return false;
+                    }
+                    let same = (0..len).all(|i| {
+                        let (a, b) = (self.input[start + i] as u32, self.input[pos + i] as u32);
+                        a == b || (self.flags.ignore_case && canonicalize(a) == canonicalize(b))
+                    });
+                    same && k(pos + len, caps)
+                }
+            },
+            Node::Seq(nodes) => self.seq(nodes, pos, caps, k),
+            Node::Alt(alternatives) => alternatives
+                .iter()
+                .any(|alternative| self.node(alternative, pos, caps, k)),
+            Node::Repeat(repeat) => {
+                if repeat.node.is_single_unit() {
+                    self.repeat_units(repeat, pos, caps, k)
+                } else {
+                    self.repeat(repeat, 0, pos, caps, k)
+                }
+            }
+            Node::Look { node, negated } => {
+                let mut inner = caps.clone();
+                let found = self.node(node, pos, &mut inner, &mut |_, _| true);
+                if *negated {
+                    !found && k(pos, caps)
+                } else if found {
+                    let saved = std::mem::replace(caps, inner);
+                    if k(pos, caps) {
+                        true
+                    } else {
+                        *caps = saved;
+                        false
+                    }
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn seq(&self, nodes: &[Node], pos: usize, caps: &mut Captures, k: &mut Cont) -> bool {
+        match nodes.split_first() {
+            None => k(pos, caps),
+            Some((first, rest)) => {
+                self.node(first, pos, caps, &mut |p, caps| self.seq(rest, p, caps, k))
+            }
+        }
+    }
+
+    /// Repetition of a single character matcher, done with a loop instead of recursion so that
+    /// `.*` over a long input doesn't exhaust the native stack. Characters of unicode patterns
+    /// can take two code units, so the end of every iteration is kept.
+    fn repeat_units(&self, repeat: &Repeat, pos: usize, caps: &mut Captures, k: &mut Cont) -> bool {
+        let max = repeat.max.map_or(usize::MAX, |max| max as usize);
+        let min = repeat.min as usize;
+        let mut ends = vec![pos];
+        while ends.len() <= max {
+            match self.step(&repeat.node, *ends.last().unwrap()) {
+                Some(end) => ends.push(end),
+                None => break,
+            }
+        }
+        if ends.len() <= min {
+            return false;
+        }
+        if repeat.greedy {
+            ends[min..].iter().rev().any(|&end| k(end, caps))
+        } else {
+            ends[min..].iter().any(|&end| k(end, caps))
+        }
+    }
+
+    fn repeat(
+        &self,
+        repeat: &Repeat,
+        count: u32,
+        pos: usize,
+        caps: &mut Captures,
+        k: &mut Cont,
+    ) -> bool {
+        if repeat.max == Some(count) {
+            return k(pos, caps);
+        }
+        if count < repeat.min {
+            return self.repeat_once(repeat, count, pos, caps, k);
+        }
+        if repeat.greedy {
+            self.repeat_once(repeat, count, pos, caps, k) || k(pos, caps)
+        } else {
+            k(pos, caps) || self.repeat_once(repeat, count, pos, caps, k)
+        }
+    }
+
+    fn repeat_once(
+        &self,
+        repeat: &Repeat,
+        count: u32,
+        pos: usize,
+        caps: &mut Captures,
+        k: &mut Cont,
+    ) -> bool {
+        let saved = caps[repeat.captures.clone()].to_vec();
+        for cap in caps[repeat.captures.clone()].iter_mut() {
+            *cap = None;
+        }
+        let matched = self.node(&repeat.node, pos, caps, &mut |end, caps| {
+            // An iteration past the minimum that matched nothing would loop forever.
+            if count >= repeat.min && end == pos {
+                return false;
+            }
+            self.repeat(repeat, count + 1, end, caps, k)
+        });
+        if !matched {
+            caps[repeat.captures.clone()].copy_from_slice(&saved);
+        }
+        matched
+    }
+}
+
+/// Internal data of `RegExp` instances.
+pub struct JsRegExp {
+    pub regex: Regex,
+    pub source: String,
+}
+
+impl JsRegExp {
+    define_jsclass!(JsObject, RegExp);
+
+    /// section 21.2.3.2.3 RegExpCreate, throws a `SyntaxError` for invalid patterns or flags.
+    pub fn new(
+        vm: &mut VirtualMachine,
+        pattern: &str,
+        flags: &str,
+    ) -> Result<Gc<JsObject>, JsValue> {
+        let regex = match RegExpFlags::parse(flags).and_then(|flags| Regex::new(pattern, flags)) {
+            Ok(regex) => regex,
+            Err(e) => {
+                let msg = JsString::new(
+                    vm,
+                    format!("Invalid regular expression: /{}/: {}", pattern, e),
+                );
+                return Err(JsValue::new(JsSyntaxError::new(vm, msg, None)));
+            }
+        };
+        let flags = regex.flags();
+        let structure = vm.global_data().regexp_structure.unwrap();
+        let mut obj = JsObject::new(vm, structure, Self::get_class(), ObjectTag::Regex);
+        unsafe {
+            *obj.data::<JsRegExp>() = ManuallyDrop::new(JsRegExp {
+                regex,
+                source: if pattern.is_empty() {
+                    "(?:)".to_owned()
+                } else {
+                    pattern.to_owned()
+                },
+            });
+        }
+
+        let ctx = vm.space().new_local_context();
+        let mut obj = ctx.new_local(obj);
+        let _ = obj.define_own_property(
+            vm,
+            Symbol::lastIndex(),
+            &*DataDescriptor::new(JsValue::new(0), W),
+            false,
+        );
+        let source = ctx.new_local(JsString::new(vm, &obj.as_regexp().source));
+        let flags_string = ctx.new_local(JsString::new(vm, flags.to_string()));
+        let props = [
+            ("source", JsValue::new(*source)),
+            ("flags", JsValue::new(*flags_string)),
+            ("global", JsValue::new(flags.global)),
+            ("ignoreCase", JsValue::new(flags.ignore_case)),
+            ("multiline", JsValue::new(flags.multiline)),
+            ("dotAll", JsValue::new(flags.dot_all)),
+            ("unicode", JsValue::new(flags.unicode)),
+            ("sticky", JsValue::new(flags.sticky)),
+        ];
+        for &(name, val) in props.iter() {
+            let name = vm.intern(name);
+            let _ = obj.define_own_property(vm, name, &*DataDescriptor::new(val, NONE), false);
+        }
+        Ok(*obj)
+    }
+}
+
+/// Iterator returned by `String.prototype.matchAll`.
+pub struct JsRegExpStringIterator {
+    pub regexp: Gc<JsObject>,
+    pub string: Gc<JsString>,
+    pub global: bool,
+    pub done: bool,
+}
+
+impl JsRegExpStringIterator {
+    define_jsclass!(JsObject, Iterator);
+
+    pub fn new(
+        vm: &mut VirtualMachine,
+        regexp: Gc<JsObject>,
+        string: Gc<JsString>,
+        global: bool,
+    ) -> Gc<JsObject> {
+        let structure = vm.global_data().regexp_string_iterator_structure.unwrap();
+        let obj = JsObject::new(
+            vm,
+            structure,
+            Self::get_class(),
+            ObjectTag::RegExpStringIterator,
+        );
+        unsafe {
+            *obj.data::<JsRegExpStringIterator>() = ManuallyDrop::new(JsRegExpStringIterator {
+                regexp,
+                string,
+                global,
+                done: false,
+            });
+        }
+        obj
+    }
+}
+
+unsafe impl Trace for JsRegExpStringIterator {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.regexp.trace(tracer);
+        self.string.trace(tracer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(pattern: &str, flags: &str, input: &str) -> Option<Vec<Option<String>>> {
+        let regex = Regex::new(pattern, RegExpFlags::parse(flags).unwrap()).unwrap();
+        let input = input.encode_utf16().collect::<Vec<_>>();
+        regex.exec(&input, 0).map(|caps| {
+            caps.iter()
+                .map(|cap| cap.map(|(s, e)| String::from_utf16_lossy(&input[s..e])))
+                .collect()
+        })
+    }
+
+    fn whole(pattern: &str, flags: &str, input: &str) -> Option<String> {
+        find(pattern, flags, input).and_then(|caps| caps[0].clone())
+    }
+
+    #[test]
+    fn test_regex_matching() {
+        assert_eq!(whole("b+", "", "abbbc").as_deref(), Some("bbb"));
+        assert_eq!(whole("b+?", "", "abbbc").as_deref(), Some("b"));
+        assert_eq!(whole("a.c", "", "xa\ncabc").as_deref(), Some("abc"));
+        assert_eq!(whole("a.c", "s", "xa\ncabc").as_deref(), Some("a\nc"));
+        assert_eq!(whole("^b", "", "a\nb"), None);
+        assert_eq!(whole("^b", "m", "a\nb").as_deref(), Some("b"));
+        assert_eq!(whole("\\bfoo\\b", "", "afoo foo").as_deref(), Some("foo"));
+        assert_eq!(whole("[^a-c\\d]+", "", "ab12xyz").as_deref(), Some("xyz"));
+        assert_eq!(whole("[\\D]+", "", "12ab3").as_deref(), Some("ab"));
+        assert_eq!(whole("HELLO", "i", "say hello").as_deref(), Some("hello"));
+        assert_eq!(whole("\\u0041\\x42", "", "zAB").as_deref(), Some("AB"));
+        assert_eq!(whole("a{2,3}", "", "aaaa").as_deref(), Some("aaa"));
+        assert_eq!(whole("a{,2}", "", "a{,2}").as_deref(), Some("a{,2}"));
+        assert_eq!(
+            whole("(a|ab)(c|bcd)(d*)", "", "abcd").as_deref(),
+            Some("abcd")
+        );
+        assert_eq!(whole("a(?=b)", "", "acab").as_deref(), Some("a"));
+        assert_eq!(whole("a(?!b)", "", "abac").as_deref(), Some("a"));
+        assert_eq!(
+            whole(".*", "", &"x".repeat(200_000)).map(|s| s.len()),
+            Some(200_000)
+        );
+
+        let caps = find("(\\w+)\\s(\\w+)\\s\\1", "", "hey you hey").unwrap();
+        assert_eq!(caps[2].as_deref(), Some("you"));
+        // Captures are reset on each iteration of a quantifier.
+        let caps = find("(z)((a+)?(b+)?(c))*", "", "zaacbbbcac").unwrap();
+        assert_eq!(caps[0].as_deref(), Some("zaacbbbcac"));
+        assert_eq!(caps[2].as_deref(), Some("ac"));
+        assert_eq!(caps[3].as_deref(), Some("a"));
+        assert_eq!(caps[4], None);
+        assert_eq!(caps[5].as_deref(), Some("c"));
+
+        let regex = Regex::new("(?<y>\\d{4})-(?<m>\\d\\d)-\\k<m>", RegExpFlags::default()).unwrap();
+        assert_eq!(regex.capture_count(), 2);
+        assert_eq!(
+            regex.group_names(),
+            &[("y".to_owned(), 1), ("m".to_owned(), 2)]
+        );
+        let input = "on 2020-10-10".encode_utf16().collect::<Vec<_>>();
+        assert_eq!(regex.exec(&input, 0).unwrap()[0], Some((3, 13)));
+    }
+
+    #[test]
+    fn test_regex_unicode() {
+        assert_eq!(whole("^.$", "u", "😀").as_deref(), Some("😀"));
+        assert_eq!(whole("^.$", "", "😀"), None);
+        assert_eq!(whole("😀+", "u", "a😀😀").as_deref(), Some("😀😀"));
+        assert_eq!(whole("[😀-😂]", "u", "x😁").as_deref(), Some("😁"));
+        assert_eq!(whole("^[^a]$", "u", "😀").as_deref(), Some("😀"));
+        assert_eq!(whole("\\u{1F600}", "u", "😀").as_deref(), Some("😀"));
+        assert_eq!(whole("\\uD83D\\uDE00", "u", "😀").as_deref(), Some("😀"));
+        // A lone surrogate doesn't match half of a pair.
+        assert_eq!(whole("\\uDE00", "u", "😀"), None);
+        let regex = Regex::new("\\uDE00", RegExpFlags::default()).unwrap();
+        let input = "😀".encode_utf16().collect::<Vec<_>>();
+        assert_eq!(regex.exec(&input, 0).unwrap()[0], Some((1, 2)));
+        assert!(Regex::new("\\u{110000}", RegExpFlags::parse("u").unwrap()).is_err());
+
+        let regex = Regex::new("foo", RegExpFlags::parse("y").unwrap()).unwrap();
+        let input = "barfoo".encode_utf16().collect::<Vec<_>>();
+        assert_eq!(regex.exec(&input, 3).unwrap()[0], Some((3, 6)));
+        assert!(regex.exec(&input, 1).is_none());
+    }
+
+    #[test]
+    fn test_regex_errors() {
+        for &pattern in [
+            "(",
+            "a)",
+            "*a",
+            "a{2,1}",
+            "[b-a]",
+            "(?<a>x)(?<a>y)",
+            "(?<=a)b",
+            "\\k<x>(?<y>a)",
+        ]
+        .iter()
+        {
+            assert!(
+                Regex::new(pattern, RegExpFlags::default()).is_err(),
+                "{}",
+                pattern
+            );
+        }
+        assert!(RegExpFlags::parse("gg").is_err());
+        assert!(RegExpFlags::parse("x").is_err());
+        assert_eq!(RegExpFlags::parse("smig").unwrap().to_string(), "gims");
+        assert_eq!(RegExpFlags::parse("yu").unwrap().to_string(), "uy");
+    }
+}
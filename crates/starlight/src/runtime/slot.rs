@@ -148,8 +148,8 @@ impl serde::Serialize for Slot {
 }
 impl Cell for Slot {}
 unsafe impl Trace for Slot {
-    fn trace(&self, tracer: &mut dyn Tracer) {
-        if let Some(ref obj) = self.base {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        if let Some(ref mut obj) = self.base {
             obj.trace(tracer);
         }
         self.value.trace(tracer);
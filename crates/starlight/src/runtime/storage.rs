@@ -88,11 +88,11 @@ impl<T: Cell + Copy> IndexMut<usize> for FixedStorage<T> {
 impl<T: Cell + Copy> Cell for FixedStorage<T> {}
 
 unsafe impl<T: Cell + Copy> Trace for FixedStorage<T> {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         /*for i in 0..self.data.len() {
             self.data[i].trace(tracer);
         }*/
-        self.data.iter().for_each(|x| x.trace(tracer));
+        self.data.iter_mut().for_each(|x| x.trace(tracer));
     }
 }
 
@@ -1,3 +1,4 @@
+use super::symbol::Symbol;
 use crate::{
     heap::cell::{Cell, Gc, Trace},
     vm::VirtualMachine,
@@ -46,6 +47,33 @@ impl JsString {
     pub fn len(&self) -> u32 {
         self.str.len() as _
     }
+
+    /// Length in UTF-16 code units, the `length` property seen from JS.
+    pub fn utf16_len(&self) -> u32 {
+        if self.str.is_ascii() {
+            self.len()
+        } else {
+            self.str.encode_utf16().count() as _
+        }
+    }
+
+    /// UTF-16 code unit at `index`, `None` past the end.
+    pub fn code_unit_at(&self, index: u32) -> Option<u16> {
+        if self.str.is_ascii() {
+            self.str.as_bytes().get(index as usize).map(|&b| b as u16)
+        } else {
+            self.str.encode_utf16().nth(index as usize)
+        }
+    }
+
+    /// Whether `name` is one of the read-only own properties of a string primitive, `length` or
+    /// an index below it.
+    pub fn has_own_property(&self, name: Symbol) -> bool {
+        match name {
+            Symbol::Indexed(ix) => ix < self.utf16_len(),
+            _ => name == Symbol::length(),
+        }
+    }
 }
 
 impl Cell for JsString {}
@@ -7,7 +7,7 @@ use crate::{
     heap::cell::{Cell, Gc, Trace, Tracer},
     vm::VirtualMachine,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, rc::Rc};
 use wtf_rs::unwrap_unchecked;
 
 /// In JavaScript programs, it's common to have multiple objects with the same property keys. Such objects
@@ -44,17 +44,49 @@ pub struct Structure {
     deleted: DeletedEntryHolder,
     added: (Symbol, MapEntry),
     previous: Option<Gc<Structure>>,
+    /// Structure whose transitions lead to this one. Unlike `previous` it is kept once the
+    /// property table is built, see [Structure::release_transition_chain].
+    parent: Option<Gc<Structure>>,
     prototype: Option<Gc<JsObject>>,
     calculated_size: u32,
+    /// Number of property additions along the transition chain that led to this structure.
     transit_count: u32,
+    /// Enumerable keys in insertion order, filled on first enumeration of a shared structure.
+    enumeration_cache: Option<Rc<[(Symbol, u32)]>>,
+    /// [MapEntry::index] of the next property added to this structure.
+    next_index: u32,
 }
 
 pub type StructureID = u32;
 
+/// Longest chain of shared structures. Objects adding more properties go to dictionary mode even
+/// when [Options::dictionary_threshold](crate::vm::Options::dictionary_threshold) is higher, so
+/// building a property table never walks a longer chain.
+pub const MAX_TRANSITION_CHAIN: u32 = 1024;
+
+/// Counters of the enumeration cache of structures, of the structures built ahead of time for
+/// object literals and of object spreads sharing structures, see
+/// [VirtualMachine::structure_stats].
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct StructureStats {
+    /// Enumerations that reused the key list cached on the structure.
+    pub enumeration_cache_hits: usize,
+    /// Enumerations that had to build the key list of a shared structure.
+    pub enumeration_cache_misses: usize,
+    /// Structures the compiler built for object literals with static keys, one per literal.
+    pub literal_structures: usize,
+    /// Object spreads that took over the structure of their source instead of adding each
+    /// property, see `OP_OBJECT_SPREAD`.
+    pub spread_structure_shares: usize,
+}
+
 #[derive(Copy, Clone)]
 pub struct MapEntry {
     pub offset: u32,
     pub attrs: AttrSafe,
+    /// Position of the property in insertion order. Offsets freed by deletes are reused, so
+    /// they do not give the order properties were added in.
+    pub index: u32,
 }
 
 impl MapEntry {
@@ -62,6 +94,7 @@ impl MapEntry {
         Self {
             offset: u32::MAX,
             attrs: AttrSafe::not_found(),
+            index: u32::MAX,
         }
     }
 
@@ -91,7 +124,11 @@ pub struct TransitionKey {
 }
 
 impl Cell for TransitionKey {}
-unsafe impl Trace for TransitionKey {}
+unsafe impl Trace for TransitionKey {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.name.trace(tracer);
+    }
+}
 
 #[cfg(feature = "debug-snapshots")]
 impl serde::Serialize for TransitionKey {
@@ -175,6 +212,36 @@ impl TransitionsTable {
         }
     }
 
+    /// Removes the transition adding `name` with `attrs`, if there is one.
+    pub fn remove(&mut self, name: Symbol, attrs: AttrSafe) {
+        let key = TransitionKey {
+            name,
+            attrs: attrs.raw(),
+        };
+        match self.var {
+            Transition::Table(Some(mut table)) => {
+                table.remove(&key);
+            }
+            Transition::Pair(key_, _) if key == key_ => self.var = Transition::None,
+            _ => (),
+        }
+    }
+
+    /// Checks for a transition other than the one adding `name` with `attrs`.
+    pub fn has_other(&self, name: Symbol, attrs: AttrSafe) -> bool {
+        let key = TransitionKey {
+            name,
+            attrs: attrs.raw(),
+        };
+        match self.var {
+            Transition::None => false,
+            Transition::Table(table) => table.map_or(false, |table| {
+                table.len() > 1 || (table.len() == 1 && !table.contains_key(&key))
+            }),
+            Transition::Pair(key_, _) => key != key_,
+        }
+    }
+
     pub fn find(&self, name: Symbol, attrs: AttrSafe) -> Option<Gc<Structure>> {
         let key = TransitionKey {
             name,
@@ -256,7 +323,7 @@ impl Transitions {
                 table.insert(self.u.pair.0, self.u.pair.1);
                 self.u.table = Some(table);
                 self.flags &= !MASK_HOLD_SINGLE;
-                self.flags &= MASK_HOLD_TABLE;
+                self.flags |= MASK_HOLD_TABLE;
             }
             if (self.flags & MASK_HOLD_TABLE) != 0 {
                 self.u.table.unwrap().insert(key, Some(map));
@@ -304,11 +371,29 @@ impl serde::Serialize for Structure {
         x.end()
     }
 }
+/// The active arm of the union is picked from the `MASK_HOLD_*` flags, reading the other one would
+/// reinterpret a `TransitionKey` as a pointer.
+unsafe impl Trace for Transitions {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        unsafe {
+            if (self.flags & MASK_HOLD_TABLE) != 0 {
+                self.u.table.trace(tracer);
+            } else if (self.flags & MASK_HOLD_SINGLE) != 0 {
+                self.u.pair.0.trace(tracer);
+                self.u.pair.1.trace(tracer);
+            }
+        }
+    }
+}
+
 unsafe impl Trace for TransitionsTable {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         match self.var {
-            Transition::Pair(_, x) => x.trace(tracer),
-            Transition::Table(table) => {
+            Transition::Pair(ref mut key, ref mut x) => {
+                key.trace(tracer);
+                x.trace(tracer);
+            }
+            Transition::Table(ref mut table) => {
                 table.trace(tracer);
             }
             _ => (),
@@ -317,17 +402,19 @@ unsafe impl Trace for TransitionsTable {
 }
 impl Cell for Structure {}
 unsafe impl Trace for Structure {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.transitions.trace(tracer);
         self.table.trace(tracer);
         self.prototype.trace(tracer);
         self.deleted.entry.trace(tracer);
-        match self.previous.as_ref() {
-            Some(x) => {
-                x.trace(tracer);
+        self.added.0.trace(tracer);
+        if let Some(ref keys) = self.enumeration_cache {
+            for &(key, _) in keys.iter() {
+                tracer.trace_symbol(key);
             }
-            _ => (),
         }
+        self.previous.trace(tracer);
+        self.parent.trace(tracer);
     }
 }
 
@@ -357,6 +444,7 @@ impl DeletedEntryHolder {
             offset,
         });
         self.entry = Some(entry);
+        self.size += 1;
     }
     pub fn pop(&mut self) -> u32 {
         let res = unwrap_unchecked(self.entry).offset;
@@ -376,13 +464,22 @@ impl DeletedEntryHolder {
 
 pub type TargetTable = HashMap<Symbol, MapEntry>;
 
+/// The [MapEntry::index] following the index of every entry in `table`.
+fn next_index(table: &TargetTable) -> u32 {
+    table
+        .values()
+        .map(|entry| entry.index + 1)
+        .max()
+        .unwrap_or(0)
+}
+
 pub struct DeletedEntry {
     prev: Option<Gc<DeletedEntry>>,
     offset: u32,
 }
 
 unsafe impl Trace for DeletedEntry {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         self.prev.trace(tracer)
     }
 }
@@ -425,41 +522,35 @@ impl Structure {
     pub fn has_table(&self) -> bool {
         self.table.is_some()
     }
+    /// Materializes the property table of this structure by walking the transition chain.
+    ///
+    /// The walk goes from the newest transition to the oldest one and stops at the first
+    /// structure that already owns a table. Entries seen first are the most recent ones, so older
+    /// entries never overwrite them; this keeps the walk iterative and memory bounded by the
+    /// number of properties rather than the length of the chain. Once the table is built the
+    /// chain is dropped, so no later lookup on this structure has to walk it again no matter how
+    /// long it was.
     pub fn allocate_table(&mut self, vm: &mut VirtualMachine) {
-        let mut stack = Vec::with_capacity(8);
-
+        let mut table = TargetTable::new();
         if self.is_adding_map() {
-            stack.push(self as *mut Self);
+            table.insert(self.added.0, self.added.1);
         }
 
         let mut current = self.previous;
-        loop {
-            match current {
-                Some(cur) => {
-                    if cur.has_table() {
-                        self.table =
-                            Some(vm.space().alloc((**cur.table.as_ref().unwrap()).clone()));
-                        break;
-                    } else {
-                        if cur.is_adding_map() {
-                            stack.push(&*cur as *const Self as *mut Self);
-                        }
-                    }
-                    current = cur.previous;
-                }
-                None => {
-                    self.table = Some(vm.space().alloc(HashMap::new()));
-                    break;
+        while let Some(cur) = current {
+            if let Some(base) = cur.table {
+                table.reserve(base.len());
+                for (key, entry) in base.iter() {
+                    table.entry(*key).or_insert(*entry);
                 }
+                break;
             }
-        }
-        assert!(self.table.is_some());
-        let mut table = self.table.unwrap();
-        unsafe {
-            for it in stack {
-                table.insert((*it).added.0, (*it).added.1);
+            if cur.is_adding_map() {
+                table.entry(cur.added.0).or_insert(cur.added.1);
             }
+            current = cur.previous;
         }
+        self.table = Some(vm.space().alloc(table));
         self.previous = None;
     }
 
@@ -477,19 +568,30 @@ impl Structure {
         self.transitions.is_indexed()
     }
 
+    /// Whether this structure belongs to a single object and is never shared through
+    /// transitions.
     pub fn is_unique(&self) -> bool {
         !self.transitions.is_enabled()
     }
 
+    /// Whether this unique structure was flattened, changes to it create a new unique structure
+    /// instead of modifying it in place.
+    pub fn is_flattened(&self) -> bool {
+        self.is_unique() && self.transitions.is_enabled_unique_transition()
+    }
+
+    /// Whether this structure can be used as a shape, i.e. its layout never changes. Shared
+    /// structures and flattened unique ones qualify, unique structures are changed in place.
     pub fn is_shaped(&self) -> bool {
-        // we can use this map id as shape or not
-        !self.is_unique() || self.transitions.is_enabled()
+        !self.is_unique() || self.is_flattened()
     }
 
     pub fn prototype(&self) -> Option<Gc<JsObject>> {
         self.prototype
     }
 
+    /// Stops in place changes of this structure if it is unique, see [Structure::is_flattened].
+    /// Shared structures are always shaped and stay as they are.
     pub fn flatten(&mut self) {
         if self.is_unique() {
             self.transitions.enable_unique_transition();
@@ -503,6 +605,90 @@ impl Structure {
             self.calculated_size as _
         }
     }
+
+    /// Checks that slot offsets, deleted slots and transitions of this structure agree with
+    /// each other, see [crate::gc::verify]. `slots_size` is the slot count the property table
+    /// was last sized for.
+    #[cfg(any(debug_assertions, feature = "verify-heap"))]
+    pub(crate) fn verify(&self, slots_size: usize) -> Result<(), String> {
+        if let Some(table) = self.table {
+            let mut offsets = std::collections::HashSet::new();
+            for entry in table.values() {
+                if entry.offset as usize >= slots_size {
+                    return Err(format!(
+                        "offset {} is out of {} slots",
+                        entry.offset, slots_size
+                    ));
+                }
+                if !offsets.insert(entry.offset) {
+                    return Err(format!("offset {} is used twice", entry.offset));
+                }
+            }
+        }
+        if self.is_adding_map() && self.added.1.offset as usize >= slots_size {
+            return Err(format!(
+                "added offset {} is out of {} slots",
+                self.added.1.offset, slots_size
+            ));
+        }
+
+        let mut deleted = 0;
+        let mut entry = self.deleted.entry;
+        while let Some(cur) = entry {
+            if cur.offset as usize >= slots_size {
+                return Err(format!(
+                    "deleted offset {} is out of {} slots",
+                    cur.offset, slots_size
+                ));
+            }
+            deleted += 1;
+            entry = cur.prev;
+        }
+        if deleted != self.deleted.size {
+            return Err(format!(
+                "{} deleted slots are recorded but {} are chained",
+                self.deleted.size, deleted
+            ));
+        }
+
+        if let Some(previous) = self.previous {
+            if previous.get_slots_size() > slots_size {
+                return Err(format!(
+                    "previous structure has {} slots, more than {}",
+                    previous.get_slots_size(),
+                    slots_size
+                ));
+            }
+        }
+        let check_transition = |key: &TransitionKey, target: Gc<Structure>| {
+            if let Some(previous) = target.previous {
+                if !std::ptr::eq(&*previous, self) {
+                    return Err("transition target does not point back".to_string());
+                }
+            }
+            if target.added.0 != key.name {
+                return Err("transition target adds another key".to_string());
+            }
+            match target.parent {
+                Some(parent) if std::ptr::eq(&*parent, self) => (),
+                _ => return Err("transition target does not know its parent".to_string()),
+            }
+            Ok(())
+        };
+        match self.transitions.var {
+            Transition::Pair(ref key, Some(target)) => check_transition(key, target)?,
+            Transition::Table(Some(table)) => {
+                for (key, target) in table.iter() {
+                    if let Some(target) = target {
+                        check_transition(key, *target)?;
+                    }
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
     fn ctor(vm: &mut VirtualMachine, previous: Gc<Self>, unique: bool) -> Gc<Self> {
         let mut this = vm.space().alloc(Self {
             prototype: previous.prototype,
@@ -514,16 +700,13 @@ impl Structure {
             },
             transitions: TransitionsTable::new(!unique, previous.transitions.is_indexed()),
             deleted: previous.deleted,
-            added: (
-                DUMMY_SYMBOL,
-                MapEntry {
-                    offset: u32::MAX,
-                    attrs: AttrSafe::not_found(),
-                },
-            ),
+            added: (DUMMY_SYMBOL, MapEntry::not_found()),
             id: 0,
             calculated_size: 0,
-            transit_count: 0,
+            transit_count: previous.transit_count,
+            enumeration_cache: None,
+            parent: None,
+            next_index: previous.next_index,
         });
         this.calculated_size = this.get_slots_size() as _;
         assert!(this.previous.is_some());
@@ -545,16 +728,13 @@ impl Structure {
                 entry: None,
                 size: 0,
             },
-            added: (
-                DUMMY_SYMBOL,
-                MapEntry {
-                    offset: u32::MAX,
-                    attrs: AttrSafe::not_found(),
-                },
-            ),
+            added: (DUMMY_SYMBOL, MapEntry::not_found()),
             id: 0,
             calculated_size: 0,
             transit_count: 0,
+            enumeration_cache: None,
+            parent: None,
+            next_index: 0,
         })
     }
     #[allow(dead_code)]
@@ -566,6 +746,7 @@ impl Structure {
         indexed: bool,
     ) -> Gc<Self> {
         let mut this = Self::ctor1(vm, prototype, unique, indexed);
+        this.next_index = table.map_or(0, |table| next_index(&table));
         this.table = table;
         this.calculated_size = this.get_slots_size() as _;
         this
@@ -583,16 +764,13 @@ impl Structure {
                 entry: None,
                 size: 0,
             },
-            added: (
-                DUMMY_SYMBOL,
-                MapEntry {
-                    offset: u32::MAX,
-                    attrs: AttrSafe::not_found(),
-                },
-            ),
+            added: (DUMMY_SYMBOL, MapEntry::not_found()),
             id: 0,
             calculated_size: 0,
             transit_count: 0,
+            enumeration_cache: None,
+            parent: None,
+            next_index: next_index(&table),
         });
         this.calculated_size = this.get_slots_size() as _;
         this
@@ -759,6 +937,45 @@ impl Gc<Structure> {
         }
     }
 
+    /// Own property keys with their slot offsets, in insertion order.
+    pub fn ordered_property_names(
+        &mut self,
+        vm: &mut VirtualMachine,
+        include: bool,
+    ) -> Vec<(Symbol, u32)> {
+        let mut keys = Vec::new();
+        if self.allocate_table_if_needed(vm) {
+            for (&sym, entry) in self.table.as_ref().unwrap().iter() {
+                if include || entry.attrs.is_enumerable() {
+                    keys.push((entry.index, sym, entry.offset));
+                }
+            }
+        }
+        keys.sort_unstable_by_key(|&(index, ..)| index);
+        keys.into_iter()
+            .map(|(_, sym, offset)| (sym, offset))
+            .collect()
+    }
+
+    /// Enumerable own property keys in insertion order.
+    ///
+    /// Shared structures never change their keys, any change transitions to another structure,
+    /// so the list is computed once and reused by every object with this shape. Unique
+    /// structures are modified in place and always recompute it.
+    pub fn enumeration_keys(&mut self, vm: &mut VirtualMachine) -> Rc<[(Symbol, u32)]> {
+        if self.is_unique() {
+            return self.ordered_property_names(vm, false).into();
+        }
+        if let Some(ref keys) = self.enumeration_cache {
+            vm.structure_stats_mut().enumeration_cache_hits += 1;
+            return keys.clone();
+        }
+        vm.structure_stats_mut().enumeration_cache_misses += 1;
+        let keys: Rc<[(Symbol, u32)]> = self.ordered_property_names(vm, false).into();
+        self.enumeration_cache = Some(keys.clone());
+        keys
+    }
+
     pub fn add_property_transition(
         &mut self,
         vm: &mut VirtualMachine,
@@ -769,6 +986,7 @@ impl Gc<Structure> {
         let mut entry = MapEntry {
             offset: 0,
             attrs: attributes,
+            index: 0,
         };
 
         if self.is_unique() {
@@ -790,6 +1008,8 @@ impl Gc<Structure> {
             } else {
                 entry.offset = self.get_slots_size() as _;
             }
+            entry.index = map.next_index;
+            map.next_index += 1;
             unwrap_unchecked(map.table.as_mut()).insert(name, entry);
             *offset = entry.offset;
             return map;
@@ -801,7 +1021,9 @@ impl Gc<Structure> {
 
             return map;
         }
-        if self.transit_count > 32 {
+        let transit_count = self.transit_count.saturating_add(1);
+        if transit_count >= vm.options().dictionary_threshold.min(MAX_TRANSITION_CHAIN) {
+            self.release_transition_chain();
             // stop transition
             let mut map = Structure::new_unique(
                 vm, // Heap::from_raw is safe here as there is no way to allocate JsObject not in the GC heap.
@@ -822,6 +1044,7 @@ impl Gc<Structure> {
                 MapEntry {
                     offset: slot,
                     attrs: attributes,
+                    index: self.next_index,
                 },
             );
             map.calculated_size = self.get_slots_size() as _;
@@ -831,11 +1054,14 @@ impl Gc<Structure> {
                 MapEntry {
                     offset: self.get_slots_size() as _,
                     attrs: attributes,
+                    index: self.next_index,
                 },
             );
             map.calculated_size = self.get_slots_size() as u32 + 1;
         }
-        map.transit_count += 1;
+        map.transit_count = transit_count;
+        map.next_index = self.next_index + 1;
+        map.parent = Some(*self);
         self.transitions.insert(vm, name, attributes, map);
         *offset = map.added.1.offset;
         assert!(map.get_slots_size() as u32 > map.added.1.offset);
@@ -843,6 +1069,29 @@ impl Gc<Structure> {
         map
     }
 
+    /// Removes the transitions leading to this structure, called when an object on it turns
+    /// into a dictionary. The walk goes towards the root and stops at the first structure that
+    /// has other transitions besides the one being walked, that structure and the link to it are
+    /// still shared with other shapes and are kept. Only the tail of the chain that led here
+    /// alone is no longer kept alive by transition tables, objects and inline caches using one
+    /// of these structures still keep it.
+    pub fn release_transition_chain(&mut self) {
+        let mut current = *self;
+        while let Some(mut parent) = current.parent {
+            if parent
+                .transitions
+                .has_other(current.added.0, current.added.1.attrs)
+            {
+                break;
+            }
+            parent
+                .transitions
+                .remove(current.added.0, current.added.1.attrs);
+            current.parent = None;
+            current = parent;
+        }
+    }
+
     pub fn get(&mut self, vm: &mut VirtualMachine, name: Symbol) -> MapEntry {
         if !self.has_table() {
             if self.previous.is_none() {
@@ -916,6 +1165,7 @@ impl StructureBuilder {
             MapEntry {
                 offset: index as _,
                 attrs: attributes,
+                index: self.table.len() as _,
             },
         );
     }
@@ -926,6 +1176,7 @@ impl StructureBuilder {
         let entry = MapEntry {
             offset: index as _,
             attrs: attributes,
+            index: index as _,
         };
         self.table.insert(symbol, entry);
         entry
@@ -942,3 +1193,228 @@ impl StructureBuilder {
             .unwrap_or_else(MapEntry::not_found)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gc::weak::WeakGc,
+        runtime::value::JsValue,
+        vm::{Options, VirtualMachineRef},
+    };
+
+    #[test]
+    fn test_transitions_survive_gc() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let mut transitions = ctx.new_local(Transitions::new(true, false));
+            let mut expected = vec![];
+            for i in 0..3 {
+                let proto = JsObject::new_empty(&mut vm);
+                let proto = ctx.new_local(proto);
+                let map = Structure::new_indexed(&mut vm, Some(*proto), false);
+                let name = vm.intern(format!("t{}", i));
+                transitions.insert(&mut vm, name, object_data(), map);
+                expected.push((name, proto));
+                // The first insert holds a single pair, later ones go through the table.
+                vm.space().gc();
+            }
+            // Reuse whatever the collector freed.
+            for _ in 0..1000 {
+                let _ = JsObject::new_empty(&mut vm);
+            }
+            vm.space().gc();
+
+            for (name, proto) in expected.iter() {
+                let map = transitions
+                    .find(*name, object_data())
+                    .unwrap_or_else(|| panic!());
+                assert!(Gc::ptr_eq(map.prototype().unwrap(), **proto));
+            }
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_projection_keeps_parent_alive() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let name = vm.intern("x");
+            let prototype = {
+                let inner = vm.space().new_local_context();
+                let mut proto = inner.new_local(JsObject::new_empty(&mut vm));
+                assert!(proto.put(&mut vm, name, JsValue::new(42), false).is_ok());
+                let map = Structure::new_indexed(&mut vm, Some(*proto), false);
+                // the structure is only reachable through the projection from here on.
+                Gc::map(map, &ctx, |map| &map.prototype)
+            };
+            for _ in 0..3 {
+                vm.space().gc();
+                for _ in 0..1000 {
+                    let _ = JsObject::new_empty(&mut vm);
+                }
+            }
+            let proto = prototype.unwrap_or_else(|| panic!());
+            assert!(Gc::ptr_eq(proto, prototype.parent().prototype().unwrap()));
+            assert_eq!(
+                proto
+                    .get(&mut vm, name)
+                    .unwrap_or_else(|_| panic!())
+                    .as_int32(),
+                42
+            );
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_dictionary_threshold() {
+        let mut vm = VirtualMachine::new(Options::builder().dictionary_threshold(4).build());
+        {
+            let ctx = vm.space().new_local_context();
+            for _ in 0..2 {
+                let mut obj = ctx.new_local(JsObject::new_empty(&mut vm));
+                for i in 0..6 {
+                    let name = vm.intern(format!("p{}", i));
+                    assert!(obj.put(&mut vm, name, JsValue::new(i), false).is_ok());
+                    assert_eq!(obj.structure().is_unique(), i + 1 >= 4);
+                }
+            }
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    /// Adds `count` keys to a new object that is dropped afterwards, returns its shared
+    /// structures. Runs in its own frame so the stack no longer refers to them once it returns.
+    #[inline(never)]
+    fn build_dictionary(vm: &mut VirtualMachine, count: u32) -> Vec<WeakGc<Structure>> {
+        let ctx = vm.space().new_local_context();
+        let mut obj = ctx.new_local(JsObject::new_empty(vm));
+        let mut chain = vec![];
+        for i in 0..count {
+            let name = vm.intern(format!("chain{}", i));
+            assert!(obj.put(vm, name, JsValue::new(i), false).is_ok());
+            if !obj.structure().is_unique() {
+                chain.push(vm.space().new_weak(obj.structure()));
+            }
+        }
+        assert!(obj.structure().is_unique());
+        chain
+    }
+
+    #[test]
+    fn test_dictionary_releases_chain() {
+        let mut vm = VirtualMachine::new(Options::builder().dictionary_threshold(8).build());
+        {
+            let ctx = vm.space().new_local_context();
+            // another shape branching off the empty object structure.
+            let mut branch = ctx.new_local(JsObject::new_empty(&mut vm));
+            let name = vm.intern("branch");
+            assert!(branch.put(&mut vm, name, JsValue::new(0), false).is_ok());
+            // an object that stays on a prefix of the chain keeps its own structure.
+            let mut short = ctx.new_local(JsObject::new_empty(&mut vm));
+            let name = vm.intern("chain0");
+            assert!(short.put(&mut vm, name, JsValue::new(0), false).is_ok());
+
+            let first = build_dictionary(&mut vm, 20);
+            assert_eq!(first.len(), 7);
+            assert!(Gc::ptr_eq(first[0].upgrade().unwrap(), short.structure()));
+            // the released chain is built again and goes to dictionary mode at the same key,
+            // the release stops at the root which has transitions for other shapes.
+            let second = build_dictionary(&mut vm, 20);
+            assert_eq!(second.len(), 7);
+            assert!(Gc::ptr_eq(second[0].upgrade().unwrap(), short.structure()));
+            vm.space().gc();
+            assert_eq!(vm.verify_heap(), Ok(()));
+            // objects adding the first key still share the structure of `short`.
+            let mut other = ctx.new_local(JsObject::new_empty(&mut vm));
+            assert!(other.put(&mut vm, name, JsValue::new(0), false).is_ok());
+            assert!(Gc::ptr_eq(other.structure(), short.structure()));
+            assert!(first[1..].iter().all(|s| s.upgrade().is_none()));
+            assert!(second[1..].iter().all(|s| s.upgrade().is_none()));
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_allocate_table_long_chain() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let root = Structure::new_indexed(&mut vm, None, false);
+            let mut tip = ctx.new_local(root);
+            let first = vm.intern("link0");
+            // built by hand, objects go to dictionary mode long before their chain is this long.
+            for i in 0..100_000u32 {
+                let name = vm.intern(format!("link{}", i));
+                let mut next = Structure::new(&mut vm, *tip);
+                next.added = (
+                    name,
+                    MapEntry {
+                        offset: i,
+                        attrs: object_data(),
+                        index: i,
+                    },
+                );
+                next.calculated_size = i + 1;
+                *tip = next;
+            }
+            // the older structures are only reachable through `previous` links.
+            vm.space().gc();
+            tip.allocate_table(&mut vm);
+            assert!(tip.has_table());
+            assert_eq!(tip.get_slots_size(), 100_000);
+            assert_eq!(tip.get(&mut vm, first).offset, 0);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_shaped_and_flattened() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let (x, y) = (vm.intern("x"), vm.intern("y"));
+            let mut a = ctx.new_local(JsObject::new_empty(&mut vm));
+            let mut b = ctx.new_local(JsObject::new_empty(&mut vm));
+            assert!(a.put(&mut vm, x, JsValue::new(1), false).is_ok());
+            assert!(a.put(&mut vm, y, JsValue::new(2), false).is_ok());
+            assert!(b.put(&mut vm, x, JsValue::new(1), false).is_ok());
+            assert!(b.put(&mut vm, y, JsValue::new(2), false).is_ok());
+            let shared = a.structure();
+            assert!(Gc::ptr_eq(shared, b.structure()));
+            assert!(!shared.is_unique());
+            assert!(shared.is_shaped());
+            assert!(!shared.is_flattened());
+
+            // deleting a property gives `b` a unique structure, changed in place from then on.
+            assert!(b.delete(&mut vm, x, false).is_ok());
+            let mut unique = b.structure();
+            assert!(unique.is_unique());
+            assert!(!unique.is_shaped());
+            assert!(!Gc::ptr_eq(unique, shared));
+            assert!(b.put(&mut vm, x, JsValue::new(3), false).is_ok());
+            assert!(Gc::ptr_eq(unique, b.structure()));
+
+            // once flattened it stays unique but later changes transition away from it.
+            unique.flatten();
+            assert!(unique.is_unique());
+            assert!(unique.is_flattened());
+            assert!(unique.is_shaped());
+            let z = vm.intern("z");
+            assert!(b.put(&mut vm, z, JsValue::new(4), false).is_ok());
+            assert!(!Gc::ptr_eq(unique, b.structure()));
+            assert!(b.structure().is_unique());
+            assert!(!b.structure().is_flattened());
+
+            // flattening a shared structure does nothing.
+            let mut shared = a.structure();
+            shared.flatten();
+            assert!(!shared.is_flattened());
+            assert!(shared.is_shaped());
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -1,10 +1,71 @@
 use std::hash::{Hash, Hasher};
 
-use crate::heap::{
-    cell::{Cell, Trace},
-    Allocator,
+use crate::{
+    heap::{
+        cell::{Cell, Gc, Trace, Tracer},
+        Allocator,
+    },
+    vm::VirtualMachine,
 };
 pub const DUMMY_SYMBOL: Symbol = Symbol::Key("");
+
+/// Largest array index, `2^32 - 1` is only valid as a length.
+pub const MAX_ARRAY_INDEX: u32 = u32::MAX - 1;
+
+/// Property keys that may stand for an array index, see [is_array_index].
+pub trait ArrayIndexKey {
+    fn array_index(&self) -> Option<u32>;
+}
+
+impl ArrayIndexKey for str {
+    fn array_index(&self) -> Option<u32> {
+        // only the canonical spelling: no sign, no leading zeros, no exponent.
+        if self.is_empty()
+            || self.len() > 10
+            || (self.len() > 1 && self.starts_with('0'))
+            || !self.bytes().all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let index = self.parse::<u64>().ok()?;
+        if index <= MAX_ARRAY_INDEX as u64 {
+            Some(index as u32)
+        } else {
+            None
+        }
+    }
+}
+
+impl ArrayIndexKey for Symbol {
+    fn array_index(&self) -> Option<u32> {
+        match *self {
+            Symbol::Indexed(index) if index <= MAX_ARRAY_INDEX => Some(index),
+            // the symbol table turns every array index into `Indexed`.
+            _ => None,
+        }
+    }
+}
+
+impl ArrayIndexKey for f64 {
+    fn array_index(&self) -> Option<u32> {
+        let n = *self;
+        // -0 is index 0 since its ToString is "0".
+        if n >= 0.0 && n <= MAX_ARRAY_INDEX as f64 && n.trunc() == n {
+            Some(n as u32)
+        } else {
+            None
+        }
+    }
+}
+
+/// section 6.1.7 The Object Type
+///
+/// An array index is a property key that is the canonical numeric string of an integer in
+/// `0..=2^32 - 2`. Numbers are checked as the key their ToString produces. Every place that
+/// decides whether a key goes to the elements of an object goes through this function.
+pub fn is_array_index<K: ArrayIndexKey + ?Sized>(key: &K) -> Option<u32> {
+    key.array_index()
+}
 /// Runtime symbol type.
 ///
 ///
@@ -18,6 +79,17 @@ pub enum Symbol {
     Key(&'static str),
 }
 impl Symbol {
+    /// Well-known `Symbol.iterator` key. Its name is not known to the interner so it never
+    /// collides with a string key spelled the same way.
+    pub fn iterator() -> Self {
+        static SYM: &str = "Symbol.iterator";
+        Self::Key(SYM)
+    }
+    /// Well-known `Symbol.toStringTag` key, read by `Object.prototype.toString`.
+    pub fn to_string_tag() -> Self {
+        static SYM: &str = "Symbol.toStringTag";
+        Self::Key(SYM)
+    }
     pub fn as_string(&self) -> String {
         match self {
             Self::Indexed(x) => x.to_string(),
@@ -62,24 +134,55 @@ impl serde::Serialize for Symbol {
     }
 }
 impl Cell for Symbol {}
-unsafe impl Trace for Symbol {}
+unsafe impl Trace for Symbol {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        tracer.trace_symbol(*self);
+    }
+}
 
 #[derive(Eq, PartialEq, Hash)]
 pub struct JsSymbol {
     sym: Symbol,
+    /// Whether the symbol has a description, `Symbol()` creates one without.
+    described: bool,
 }
 
 impl JsSymbol {
     pub fn new<A: Allocator<Self>>(allocator: &mut A, sym: Symbol) -> A::Result {
-        allocator.allocate(Self { sym })
+        allocator.allocate(Self {
+            sym,
+            described: true,
+        })
+    }
+
+    /// Creates the symbol of `Symbol(description)`, its key is not equal to any other key.
+    pub fn new_unique(vm: &mut VirtualMachine, description: Option<&str>) -> Gc<Self> {
+        let sym = vm.unique_symbol(description.unwrap_or(""));
+        vm.space().alloc(Self {
+            sym,
+            described: description.is_some(),
+        })
     }
 
     pub fn sym(&self) -> Symbol {
         self.sym
     }
+
+    /// The `description` of the symbol, `None` for symbols created without one.
+    pub fn description(&self, vm: &VirtualMachine) -> Option<String> {
+        if self.described {
+            Some(vm.description(self.sym))
+        } else {
+            None
+        }
+    }
 }
 
-unsafe impl Trace for JsSymbol {}
+unsafe impl Trace for JsSymbol {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.sym.trace(tracer);
+    }
+}
 impl Cell for JsSymbol {}
 
 #[cfg(feature = "debug-snapshots")]
@@ -176,3 +279,51 @@ macro_rules! def_syms {
 }
 
 default_symbols!(def_syms);
+
+macro_rules! def_sym_list {
+    ($($name:ident),*) => {
+        /// All well-known symbols, the interner resolves their names to these exact keys.
+        pub(crate) fn default_symbol_list() -> Vec<Symbol> {
+            vec![$(Symbol::$name()),*]
+        }
+    };
+}
+
+default_symbols!(def_sym_list);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+    #[test]
+    fn test_is_array_index() {
+        assert_eq!(is_array_index("0"), Some(0));
+        assert_eq!(is_array_index("4294967294"), Some(MAX_ARRAY_INDEX));
+        assert_eq!(is_array_index("4294967295"), None);
+        assert_eq!(is_array_index("01"), None);
+        assert_eq!(is_array_index("-1"), None);
+        assert_eq!(is_array_index("+1"), None);
+        assert_eq!(is_array_index("1e3"), None);
+        assert_eq!(is_array_index(""), None);
+        assert_eq!(is_array_index(&-0.0), Some(0));
+        assert_eq!(is_array_index(&4294967295.0), None);
+        assert_eq!(is_array_index(&1.5), None);
+        assert_eq!(is_array_index(&Symbol::Indexed(u32::MAX)), None);
+
+        let mut vm = VirtualMachine::new(Options::default());
+        assert!(vm.intern("7") == Symbol::Indexed(7));
+        assert!(matches!(vm.intern("01"), Symbol::Key(_)));
+        assert!(matches!(vm.intern("4294967295"), Symbol::Key(_)));
+        let res = vm.eval(
+            "o = {};
+             o['01'] = 'a'; o[1] = 'b'; o[0 - 1] = 'c'; o[4294967295] = 'd';
+             out = Object.keys(o).join() + ';' + o['1'] + o['-1'] + o['4294967295'];",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(out.as_string().as_str(), "1,01,-1,4294967295;bcd");
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -0,0 +1,458 @@
+//! Integer-indexed exotic objects viewing the bytes of an `ArrayBuffer`.
+//!
+//! Elements are not stored in the indexed elements of the object, every indexed access reads or
+//! writes the bytes of the viewed buffer. Once the buffer is detached the view has no elements.
+use std::mem::ManuallyDrop;
+
+use super::{
+    attributes::*, class::Class, error::JsTypeError, method_table::*, object::*,
+    property_descriptor::*, slot::*, string::JsString, symbol::*, value::*,
+};
+use crate::{heap::cell::*, vm::*};
+
+macro_rules! typed_array_kinds {
+    ($($kind: ident: $class: ident, $size: expr;)*) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum TypedArrayKind {
+            $($kind,)*
+        }
+
+        $(
+            struct $class;
+            impl $class {
+                define_jsclass_with_symbol!(JsTypedArray, $class, $class);
+            }
+        )*
+
+        impl TypedArrayKind {
+            pub const ALL: &'static [TypedArrayKind] = &[$(TypedArrayKind::$kind,)*];
+
+            /// Name of the constructor of this kind, `Uint8Array` for example.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(TypedArrayKind::$kind => stringify!($class),)*
+                }
+            }
+
+            pub fn element_size(self) -> usize {
+                match self {
+                    $(TypedArrayKind::$kind => $size,)*
+                }
+            }
+
+            pub fn tag(self) -> ObjectTag {
+                match self {
+                    $(TypedArrayKind::$kind => ObjectTag::$class,)*
+                }
+            }
+
+            pub fn from_tag(tag: ObjectTag) -> Option<Self> {
+                match tag {
+                    $(ObjectTag::$class => Some(TypedArrayKind::$kind),)*
+                    _ => None,
+                }
+            }
+
+            pub fn class(self) -> &'static Class {
+                match self {
+                    $(TypedArrayKind::$kind => $class::get_class(),)*
+                }
+            }
+        }
+    };
+}
+
+typed_array_kinds! {
+    Int8: Int8Array, 1;
+    Uint8: Uint8Array, 1;
+    Uint8Clamped: Uint8ClampedArray, 1;
+    Int16: Int16Array, 2;
+    Uint16: Uint16Array, 2;
+    Int32: Int32Array, 4;
+    Uint32: Uint32Array, 4;
+    Float32: Float32Array, 4;
+    Float64: Float64Array, 8;
+}
+
+/// Low 32 bits of the integer part of `n`, the common step of ToInt8 through ToUint32.
+fn integer_bits(n: f64) -> u32 {
+    if !n.is_finite() {
+        return 0;
+    }
+    n.trunc().rem_euclid(4294967296.0) as u32
+}
+
+/// section 7.1.12 ToUint8Clamp(argument)
+fn to_uint8_clamp(n: f64) -> u8 {
+    if n.is_nan() || n <= 0.0 {
+        return 0;
+    }
+    if n >= 255.0 {
+        return 255;
+    }
+    let floor = n.floor();
+    let rounded = if n - floor > 0.5 || (n - floor == 0.5 && floor % 2.0 != 0.0) {
+        floor + 1.0
+    } else {
+        floor
+    };
+    rounded as u8
+}
+
+impl TypedArrayKind {
+    /// section 25.1.2.10 RawBytesToNumeric(type, rawBytes, isLittleEndian), elements are always
+    /// little endian.
+    pub fn decode(self, bytes: &[u8]) -> JsValue {
+        macro_rules! read {
+            ($t: ty) => {{
+                let mut raw = [0; std::mem::size_of::<$t>()];
+                raw.copy_from_slice(bytes);
+                <$t>::from_le_bytes(raw)
+            }};
+        }
+        match self {
+            TypedArrayKind::Int8 => JsValue::new(read!(i8)),
+            TypedArrayKind::Uint8 | TypedArrayKind::Uint8Clamped => JsValue::new(read!(u8)),
+            TypedArrayKind::Int16 => JsValue::new(read!(i16)),
+            TypedArrayKind::Uint16 => JsValue::new(read!(u16)),
+            TypedArrayKind::Int32 => JsValue::new(read!(i32)),
+            TypedArrayKind::Uint32 => JsValue::new(read!(u32)),
+            TypedArrayKind::Float32 => JsValue::new(read!(f32) as f64),
+            TypedArrayKind::Float64 => JsValue::new(read!(f64)),
+        }
+    }
+
+    /// section 25.1.2.12 NumericToRawBytes(type, value, isLittleEndian)
+    pub fn encode(self, value: f64, bytes: &mut [u8]) {
+        let bits = integer_bits(value);
+        match self {
+            TypedArrayKind::Int8 | TypedArrayKind::Uint8 => bytes[0] = bits as u8,
+            TypedArrayKind::Uint8Clamped => bytes[0] = to_uint8_clamp(value),
+            TypedArrayKind::Int16 | TypedArrayKind::Uint16 => {
+                bytes.copy_from_slice(&(bits as u16).to_le_bytes())
+            }
+            TypedArrayKind::Int32 | TypedArrayKind::Uint32 => {
+                bytes.copy_from_slice(&bits.to_le_bytes())
+            }
+            TypedArrayKind::Float32 => bytes.copy_from_slice(&(value as f32).to_le_bytes()),
+            TypedArrayKind::Float64 => bytes.copy_from_slice(&value.to_le_bytes()),
+        }
+    }
+}
+
+pub struct JsTypedArray {
+    buffer: Gc<JsObject>,
+    kind: TypedArrayKind,
+    byte_offset: usize,
+    length: usize,
+}
+
+#[allow(non_snake_case)]
+impl JsTypedArray {
+    /// Creates a view of `length` elements of `buffer` starting at `byte_offset`, the range
+    /// must be inside of the buffer.
+    pub fn new(
+        vm: &mut VirtualMachine,
+        kind: TypedArrayKind,
+        buffer: Gc<JsObject>,
+        byte_offset: usize,
+        length: usize,
+    ) -> Gc<JsObject> {
+        debug_assert!(
+            byte_offset + length * kind.element_size() <= buffer.as_array_buffer().byte_length()
+        );
+        let structure = vm.global_data().typed_array_structures[kind as usize];
+        let obj = JsObject::new(vm, structure, kind.class(), kind.tag());
+        unsafe {
+            *obj.data::<JsTypedArray>() = ManuallyDrop::new(JsTypedArray {
+                buffer,
+                kind,
+                byte_offset,
+                length,
+            });
+        }
+        obj
+    }
+
+    pub fn buffer(&self) -> Gc<JsObject> {
+        self.buffer
+    }
+
+    pub fn kind(&self) -> TypedArrayKind {
+        self.kind
+    }
+
+    /// Number of elements, 0 once the buffer was detached.
+    pub fn length(&self) -> usize {
+        if self.buffer.as_array_buffer().is_detached() {
+            0
+        } else {
+            self.length
+        }
+    }
+
+    pub fn byte_length(&self) -> usize {
+        self.length() * self.kind.element_size()
+    }
+
+    pub fn byte_offset(&self) -> usize {
+        if self.buffer.as_array_buffer().is_detached() {
+            0
+        } else {
+            self.byte_offset
+        }
+    }
+
+    /// section 10.4.5.15 IntegerIndexedElementGet(O, index)
+    pub fn get(&self, index: usize) -> Option<JsValue> {
+        if index >= self.length() {
+            return None;
+        }
+        let size = self.kind.element_size();
+        let start = self.byte_offset + index * size;
+        let buffer = self.buffer.as_array_buffer();
+        Some(self.kind.decode(&buffer.data()[start..start + size]))
+    }
+
+    /// section 10.4.5.16 IntegerIndexedElementSet(O, index, value), writes outside of the view
+    /// are ignored.
+    pub fn set(&self, index: usize, value: f64) {
+        if index >= self.length() {
+            return;
+        }
+        let size = self.kind.element_size();
+        let start = self.byte_offset + index * size;
+        let mut buffer = self.buffer;
+        let data = buffer.as_array_buffer_mut().data_mut();
+        self.kind.encode(value, &mut data[start..start + size]);
+    }
+
+    fn reject(vm: &mut VirtualMachine, index: u32, throwable: bool) -> Result<bool, JsValue> {
+        if throwable {
+            let msg = JsString::new(
+                vm,
+                format!("Cannot redefine property '{}' of a typed array", index),
+            );
+            return Err(JsValue::new(JsTypeError::new(vm, msg, None)));
+        }
+        Ok(false)
+    }
+
+    pub fn GetPropertyNamesMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        collector: &mut dyn FnMut(Symbol, u32),
+        mode: EnumerationMode,
+    ) {
+        JsObject::GetPropertyNamesMethod(obj, vm, collector, mode)
+    }
+
+    pub fn DefaultValueMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        hint: JsHint,
+    ) -> Result<JsValue, JsValue> {
+        JsObject::DefaultValueMethod(obj, vm, hint)
+    }
+
+    /// section 10.4.5.3 [[DefineOwnProperty]](P, Desc)
+    pub fn DefineOwnIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        desc: &PropertyDescriptor,
+        _slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        if index as usize >= obj.as_typed_array().length()
+            || desc.is_accessor()
+            || (!desc.is_configurable_absent() && !desc.is_configurable())
+            || (!desc.is_enumerable_absent() && !desc.is_enumerable())
+            || (!desc.is_writable_absent() && !desc.is_writable())
+        {
+            return Self::reject(vm, index, throwable);
+        }
+        if !desc.is_value_absent() {
+            let value = desc.value().to_number(vm)?;
+            obj.as_typed_array().set(index as usize, value);
+        }
+        Ok(true)
+    }
+
+    /// section 10.4.5.1 [[GetOwnProperty]](P)
+    pub fn GetOwnIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        _vm: &mut VirtualMachine,
+        index: u32,
+        slot: &mut Slot,
+    ) -> bool {
+        match obj.as_typed_array().get(index as usize) {
+            Some(value) => {
+                slot.set_1(
+                    value,
+                    create_data(AttrExternal::new(Some(W | E | C))),
+                    Some(obj.as_dyn()),
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// section 10.4.5.5 [[Set]](P, V, Receiver)
+    pub fn PutIndexedSlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        val: JsValue,
+        _slot: &mut Slot,
+        _throwable: bool,
+    ) -> Result<(), JsValue> {
+        let value = val.to_number(vm)?;
+        obj.as_typed_array().set(index as usize, value);
+        Ok(())
+    }
+
+    pub fn PutNonIndexedSlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        val: JsValue,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<(), JsValue> {
+        JsObject::PutNonIndexedSlotMethod(obj, vm, name, val, slot, throwable)
+    }
+
+    /// section 10.4.5.7 [[OwnPropertyKeys]]()
+    pub fn GetOwnPropertyNamesMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        collector: &mut dyn FnMut(Symbol, u32),
+        mode: EnumerationMode,
+    ) {
+        for index in 0..obj.as_typed_array().length() {
+            collector(Symbol::Indexed(index as u32), u32::MAX);
+        }
+        JsObject::GetOwnPropertyNamesMethod(obj, vm, collector, mode)
+    }
+
+    pub fn DeleteNonIndexedMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        JsObject::DeleteNonIndexedMethod(obj, vm, name, throwable)
+    }
+
+    /// section 10.4.5.6 [[Delete]](P)
+    pub fn DeleteIndexedMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        if (index as usize) < obj.as_typed_array().length() {
+            return Self::reject(vm, index, throwable);
+        }
+        Ok(true)
+    }
+
+    pub fn GetNonIndexedSlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> Result<JsValue, JsValue> {
+        JsObject::GetNonIndexedSlotMethod(obj, vm, name, slot)
+    }
+
+    pub fn GetIndexedSlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        slot: &mut Slot,
+    ) -> Result<JsValue, JsValue> {
+        JsObject::GetIndexedSlotMethod(obj, vm, index, slot)
+    }
+
+    pub fn GetNonIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> bool {
+        JsObject::GetNonIndexedPropertySlotMethod(obj, vm, name, slot)
+    }
+
+    pub fn GetOwnNonIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> bool {
+        JsObject::GetOwnNonIndexedPropertySlotMethod(obj, vm, name, slot)
+    }
+
+    pub fn DefineOwnNonIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        name: Symbol,
+        desc: &PropertyDescriptor,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        JsObject::DefineOwnNonIndexedPropertySlotMethod(obj, vm, name, desc, slot, throwable)
+    }
+
+    /// Integer indices never reach the prototype chain, reads outside of the view are
+    /// `undefined`.
+    pub fn GetIndexedPropertySlotMethod(
+        obj: Gc<JsObject>,
+        vm: &mut VirtualMachine,
+        index: u32,
+        slot: &mut Slot,
+    ) -> bool {
+        obj.get_own_indexed_property_slot(vm, index, slot)
+    }
+}
+
+unsafe impl Trace for JsTypedArray {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
+        self.buffer.trace(tracer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_element_conversions() {
+        let mut bytes = [0; 8];
+        let cases: &[(TypedArrayKind, f64, f64)] = &[
+            (TypedArrayKind::Int8, 200.0, -56.0),
+            (TypedArrayKind::Uint8, -1.0, 255.0),
+            (TypedArrayKind::Uint8Clamped, 300.0, 255.0),
+            (TypedArrayKind::Uint8Clamped, 2.5, 2.0),
+            (TypedArrayKind::Uint8Clamped, 3.5, 4.0),
+            (TypedArrayKind::Int16, 40000.0, -25536.0),
+            (TypedArrayKind::Uint32, -1.0, 4294967295.0),
+            (TypedArrayKind::Int32, f64::NAN, 0.0),
+            (TypedArrayKind::Float32, 0.1, 0.1f32 as f64),
+            (TypedArrayKind::Float64, 0.1, 0.1),
+        ];
+        for &(kind, value, expected) in cases {
+            let bytes = &mut bytes[..kind.element_size()];
+            kind.encode(value, bytes);
+            assert_eq!(
+                kind.decode(bytes).number(),
+                expected,
+                "{:?} {}",
+                kind,
+                value
+            );
+        }
+    }
+}
@@ -1,19 +1,26 @@
-use super::{attributes::string_length, slot::*};
+use super::{
+    attributes::{string_indexed, string_length},
+    slot::*,
+};
 use crate::{
     heap::cell::{Cell, Gc, Trace, Tracer},
     vm::VirtualMachine,
 };
 
-use std::mem::transmute;
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
 use wtf_rs::{object_offsetof, pure_nan};
 pub const CMP_FALSE: i32 = 0;
 pub const CMP_TRUE: i32 = 1;
 pub const CMP_UNDEF: i32 = -1;
 use super::{
     error::JsTypeError,
-    object::{JsHint, JsObject},
+    number::number_to_string,
+    object::{JsHint, JsObject, ObjectTag},
     string::JsString,
-    symbol::{JsSymbol, Symbol},
+    symbol::{is_array_index, JsSymbol, Symbol},
 };
 
 #[derive(Clone, Copy)]
@@ -62,11 +69,10 @@ pub struct JsValue {
     u: EncodedValueDescriptor,
 }
 
-#[cfg(target_pointer_width = "64")]
 impl JsValue {
     /*
-     * On 64-bit platforms we use a NaN-encoded
-     * form for immediates.
+     * We use a NaN-encoded form for immediates. 32-bit targets such as wasm32 use the same
+     * encoding, their pointers simply leave the upper half of the payload zeroed.
      *
      * The encoding makes use of unused NaN space in the IEEE754 representation.  Any value
      * with the top 13 bits set represents a QNaN (with the sign bit set).  QNaN values
@@ -200,6 +206,64 @@ impl JsValue {
         x.into()
     }
 
+    /// Encodes `i` as an int32 immediate.
+    pub fn new_int(i: i32) -> Self {
+        Self {
+            u: EncodedValueDescriptor {
+                as_uint64: Self::NUMBER_TAG | i as u32 as u64,
+            },
+        }
+    }
+
+    /// Encodes `d` as a double even if it is integral. NaNs are canonicalized first since NaN
+    /// payloads with the top bits set would decode as integers or pointers.
+    pub fn new_double(d: f64) -> Self {
+        let d = if d.is_nan() { pure_nan::pure_nan() } else { d };
+        Self {
+            u: EncodedValueDescriptor {
+                as_uint64: d.to_bits().wrapping_add(Self::DOUBLE_ENCODE_OFFSET),
+            },
+        }
+    }
+
+    /// Encodes `d` as int32 if it is integral and not `-0.0`, as a double otherwise.
+    pub fn new_number(d: f64) -> Self {
+        if d as i32 as f64 == d && !(d == 0.0 && d.is_sign_negative()) {
+            Self::new_int(d as i32)
+        } else {
+            Self::new_double(d)
+        }
+    }
+
+    pub fn encode_bool(x: bool) -> Self {
+        if x {
+            Self::true_()
+        } else {
+            Self::false_()
+        }
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    pub fn encode_cell<T: Cell + ?Sized>(cell: Gc<T>) -> Self {
+        Self {
+            u: EncodedValueDescriptor { ptr: cell.as_dyn() },
+        }
+    }
+
+    /// The pointer only fills the low word, it is widened so the tag bits stay clear.
+    #[cfg(target_pointer_width = "32")]
+    pub fn encode_cell<T: Cell + ?Sized>(cell: Gc<T>) -> Self {
+        Self {
+            u: EncodedValueDescriptor {
+                as_uint64: cell.cell.as_ptr() as usize as u64,
+            },
+        }
+    }
+
+    pub fn encode_object(obj: Gc<JsObject>) -> Self {
+        Self::encode_cell(obj)
+    }
+
     pub fn is_empty(self) -> bool {
         self == Self::empty()
     }
@@ -252,7 +316,7 @@ impl JsValue {
     #[inline(always)]
     pub fn as_double(self) -> f64 {
         //  assert!(self.is_double());
-        unsafe { transmute(self.u.as_int64 - Self::DOUBLE_ENCODE_OFFSET as i64) }
+        unsafe { f64::from_bits(self.u.as_uint64.wrapping_sub(Self::DOUBLE_ENCODE_OFFSET)) }
     }
 
     pub fn is_number(self) -> bool {
@@ -262,7 +326,17 @@ impl JsValue {
     pub fn as_cell(self) -> Gc<dyn Cell> {
         // TODO(playX): we might want to insert is_empty check here too?
         assert!(self.is_cell());
-        unsafe { self.u.ptr }
+        #[cfg(target_pointer_width = "64")]
+        unsafe {
+            self.u.ptr
+        }
+        #[cfg(target_pointer_width = "32")]
+        unsafe {
+            Gc {
+                cell: std::ptr::NonNull::new_unchecked(self.u.as_uint64 as usize as *mut _),
+                marker: Default::default(),
+            }
+        }
     }
 }
 
@@ -311,6 +385,23 @@ impl JsValue {
             todo!()
         }
     }
+
+    /// section 7.1.5 ToInt32(argument)
+    pub fn to_int32(self, vm: &mut VirtualMachine) -> Result<i32, JsValue> {
+        Ok(self.to_uint32(vm)? as i32)
+    }
+
+    /// section 7.1.6 ToUint32(argument)
+    pub fn to_uint32(self, vm: &mut VirtualMachine) -> Result<u32, JsValue> {
+        if self.is_int32() {
+            return Ok(self.as_int32() as u32);
+        }
+        let n = self.to_number(vm)?;
+        if !n.is_finite() {
+            return Ok(0);
+        }
+        Ok(n.trunc().rem_euclid(4294967296.0) as u32)
+    }
     pub fn is_callable(self) -> bool {
         !self.is_empty()
             && self.is_cell()
@@ -320,6 +411,15 @@ impl JsValue {
                 .map(|x| x.is_callable())
                 .unwrap_or(false)
     }
+
+    /// section 7.2.4 IsConstructor(argument)
+    pub fn is_constructor(self) -> bool {
+        if !self.is_callable() {
+            return false;
+        }
+        let obj = self.as_object();
+        obj.tag() == ObjectTag::Function && obj.as_function().is_constructor()
+    }
     pub fn is_primitive(self) -> bool {
         self.is_number()
             || self.is_boolean()
@@ -332,7 +432,7 @@ impl JsValue {
             Ok(if self.is_int32() {
                 self.as_int32().to_string()
             } else {
-                self.as_double().to_string()
+                number_to_string(self.as_double())
             })
         } else if self.is_null() {
             Ok("null".to_owned())
@@ -359,6 +459,18 @@ impl JsValue {
             unreachable!()
         }
     }
+    /// Like [JsValue::to_string] but returns a string value. Strings are returned as they are and
+    /// small integers get the strings shared by [VirtualMachine::int_string].
+    pub fn to_js_string(self, vm: &mut VirtualMachine) -> Result<Gc<JsString>, JsValue> {
+        if self.is_int32() {
+            return Ok(vm.int_string(self.as_int32()));
+        }
+        if self.is_string() {
+            return Ok(self.as_string());
+        }
+        let s = self.to_string(vm)?;
+        Ok(JsString::new(vm, s))
+    }
     pub fn to_primitive(self, vm: &mut VirtualMachine, hint: JsHint) -> Result<Self, Self> {
         if self.is_cell() && self.as_cell().is::<JsObject>() {
             let mut obj = self.as_cell().downcast::<JsObject>().unwrap();
@@ -376,7 +488,7 @@ impl JsValue {
         unsafe { self.as_cell().downcast_unchecked() }
     }
     pub fn is_object(self) -> bool {
-        self.is_cell() && self.as_cell().is::<JsObject>()
+        self.is_cell() && !self.is_empty() && self.as_cell().is::<JsObject>()
     }
 
     pub fn is_symbol(self) -> bool {
@@ -420,12 +532,23 @@ impl JsValue {
                 let s = self.as_string();
                 if name == Symbol::length() {
                     slot.set_1(
-                        JsValue::new(s.len() as i32),
+                        JsValue::new(s.utf16_len() as i32),
                         string_length(),
                         Some(s.as_dyn()),
                     );
                     return Ok(slot.value());
                 }
+                // section 10.4.3.5 StringGetOwnProperty, indices past the end fall through to
+                // String.prototype.
+                if let Symbol::Indexed(ix) = name {
+                    if let Some(unit) = s.code_unit_at(ix) {
+                        // halves of a surrogate pair can't be represented on their own.
+                        let c = std::char::from_u32(unit as u32).unwrap_or('\u{fffd}');
+                        let c = vm.char_string(c);
+                        slot.set_1(JsValue::new(c), string_indexed(), Some(s.as_dyn()));
+                        return Ok(slot.value());
+                    }
+                }
             }
 
             let proto = self.get_primitive_proto(vm);
@@ -439,17 +562,17 @@ impl JsValue {
     pub fn to_symbol(self, vm: &mut VirtualMachine) -> Result<Symbol, JsValue> {
         if self.is_number() {
             if self.is_int32() {
-                if self.as_int32() as u32 as i32 == self.as_int32() {
+                if self.as_int32() >= 0 {
                     return Ok(Symbol::Indexed(self.as_int32() as _));
                 } else {
                     return Ok(vm.intern(self.as_int32().to_string()));
                 }
             } else {
                 let d = self.as_double();
-                if d as u32 as f64 == d {
-                    return Ok(Symbol::Indexed(d as u32));
+                if let Some(index) = is_array_index(&d) {
+                    return Ok(Symbol::Indexed(index));
                 } else {
-                    return Ok(vm.intern(d.to_string()));
+                    return Ok(vm.intern(number_to_string(d)));
                 }
             }
         }
@@ -634,6 +757,27 @@ impl JsValue {
     pub fn same_value_zero(lhs: Self, rhs: Self) -> bool {
         Self::same_value_impl(lhs, rhs, true)
     }
+
+    /// Hashes `self` so that values equal under [JsValue::same_value_zero] hash the same:
+    /// numbers by value with `-0` folded into `+0` and every NaN into one, strings by contents
+    /// and any other value by its bits.
+    pub fn same_value_zero_hash<H: Hasher>(self, state: &mut H) {
+        if self.is_number() {
+            let n = self.number();
+            let n = if n == 0.0 {
+                0.0
+            } else if n.is_nan() {
+                f64::NAN
+            } else {
+                n
+            };
+            n.to_bits().hash(state);
+        } else if self.is_string() {
+            self.as_string().as_str().hash(state);
+        } else {
+            unsafe { self.u.as_int64.hash(state) }
+        }
+    }
     pub fn is_any_int(self) -> bool {
         if self.is_int32() {
             return true;
@@ -724,33 +868,21 @@ impl JsValue {
 
 impl From<bool> for JsValue {
     fn from(x: bool) -> Self {
-        if x {
-            Self::true_()
-        } else {
-            Self::false_()
-        }
+        Self::encode_bool(x)
     }
 }
 
 impl<T: Cell + ?Sized> From<Gc<T>> for JsValue {
     fn from(x: Gc<T>) -> Self {
-        Self {
-            u: EncodedValueDescriptor { ptr: x.as_dyn() },
-        }
+        Self::encode_cell(x)
     }
 }
 
+/// Every arithmetic result reaches the value representation through here or
+/// [JsValue::new_double], so a NaN produced by an operation is always canonical.
 impl From<f64> for JsValue {
     fn from(d: f64) -> Self {
-        if d as i32 as f64 == d {
-            return Self::new(d as i32);
-        }
-        let int = unsafe { std::mem::transmute::<_, i64>(d) };
-        Self {
-            u: EncodedValueDescriptor {
-                as_int64: int + Self::DOUBLE_ENCODE_OFFSET as i64,
-            },
-        }
+        Self::new_number(d)
     }
 }
 
@@ -759,19 +891,24 @@ macro_rules! impl_from_int {
         $(
             impl From<$t> for JsValue {
                 fn from(x: $t) -> Self {
-                    let i = x as i32;
-                    Self {
-                        u: EncodedValueDescriptor {
-                            as_int64: (Self::NUMBER_TAG as i64) | i as i64
-                        }
-                    }
+                    Self::new_int(x as i32)
                 }
             }
         )*
     };
 }
 
-impl_from_int!(u8 i8 u16 i16 i32 u32);
+impl_from_int!(u8 i8 u16 i16 i32);
+
+impl From<u32> for JsValue {
+    fn from(x: u32) -> Self {
+        if x <= i32::MAX as u32 {
+            Self::new_int(x as i32)
+        } else {
+            Self::new_double(x as f64)
+        }
+    }
+}
 
 /*pub trait JsValueNew<T> {
     #[allow(unused_variables)]
@@ -807,6 +944,76 @@ impl PartialEq for JsValue {
     }
 }
 
+/// Short description for Rust-side debugging: the kind of the value and its contents.
+///
+/// Strings are printed as stored and objects only by class, so no `VirtualMachine` is needed.
+/// See [VirtualMachine::inspect] for a description that looks into objects.
+impl fmt::Debug for JsValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let val = *self;
+        if val.is_empty() {
+            f.write_str("Empty")
+        } else if val == Self::deleted() {
+            f.write_str("Deleted")
+        } else if val.is_undefined() {
+            f.write_str("Undefined")
+        } else if val.is_null() {
+            f.write_str("Null")
+        } else if val.is_boolean() {
+            write!(f, "Bool({})", val.as_boolean())
+        } else if val.is_int32() {
+            write!(f, "Int32({})", val.as_int32())
+        } else if val.is_number() {
+            write!(f, "Double({:?})", val.as_double())
+        } else if val.is_string() {
+            write!(f, "String({:?})", val.as_string().as_str())
+        } else if val.as_cell().is::<JsSymbol>() {
+            write!(f, "{}", val)
+        } else if val.as_cell().is::<JsObject>() {
+            write!(f, "Object({})", val)
+        } else {
+            write!(f, "Cell({})", val.as_cell().get_typename())
+        }
+    }
+}
+
+/// The value as JS would print it, except that objects are not converted through
+/// `toString` and doubles use Rust formatting.
+impl fmt::Display for JsValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let val = *self;
+        if val.is_empty() {
+            f.write_str("<empty>")
+        } else if val == Self::deleted() {
+            f.write_str("<deleted>")
+        } else if val.is_undefined() {
+            f.write_str("undefined")
+        } else if val.is_null() {
+            f.write_str("null")
+        } else if val.is_boolean() {
+            write!(f, "{}", val.as_boolean())
+        } else if val.is_int32() {
+            write!(f, "{}", val.as_int32())
+        } else if val.is_number() {
+            f.write_str(&number_to_string(val.as_double()))
+        } else {
+            let cell = val.as_cell();
+            if let Some(s) = cell.downcast::<JsString>() {
+                f.write_str(s.as_str())
+            } else if let Some(sym) = cell.downcast::<JsSymbol>() {
+                match sym.sym() {
+                    Symbol::Key(key) => write!(f, "Symbol({})", key),
+                    Symbol::Indexed(ix) => write!(f, "Symbol({})", ix),
+                }
+            } else if let Some(obj) = cell.downcast::<JsObject>() {
+                write!(f, "[object {}]", obj.class().name)
+            } else {
+                write!(f, "<{}>", cell.get_typename())
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -823,13 +1030,154 @@ mod tests {
         assert!(val.is_number() && val.is_double());
         assert_eq!(val.as_double(), 42.5);
     }
+
+    #[test]
+    fn test_encoding_round_trip() {
+        for &i in [0, 1, -1, i32::MIN, i32::MAX].iter() {
+            let val = JsValue::new_int(i);
+            assert!(val.is_int32() && val.is_number() && !val.is_double() && !val.is_cell());
+            assert_eq!(val.as_int32(), i);
+        }
+        for &d in [
+            0.5,
+            -1.5,
+            1e300,
+            f64::MIN_POSITIVE,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ]
+        .iter()
+        {
+            let val = JsValue::new_double(d);
+            assert!(val.is_double() && !val.is_int32() && !val.is_cell());
+            assert_eq!(val.as_double(), d);
+        }
+        assert!(JsValue::new_double(3.0).is_double());
+        assert!(JsValue::new(3.0).is_int32());
+
+        let big = JsValue::new(u32::MAX);
+        assert!(big.is_double());
+        assert_eq!(big.number(), u32::MAX as f64);
+
+        let neg_zero = JsValue::new(-0.0);
+        assert!(neg_zero.is_double());
+        assert!(neg_zero.as_double() == 0.0 && neg_zero.as_double().is_sign_negative());
+        let zero = JsValue::new(0.0);
+        assert!(zero.is_int32() && zero.as_int32() == 0);
+
+        for &bits in [
+            0x7ff8_0000_0000_0000u64,
+            0xffff_ffff_ffff_ffff,
+            0xfff8_0000_0000_0001,
+        ]
+        .iter()
+        {
+            let val = JsValue::new(f64::from_bits(bits));
+            assert!(val.is_double() && !val.is_cell() && !val.is_int32());
+            assert!(val.number().is_nan());
+        }
+
+        assert!(JsValue::encode_bool(true).is_true());
+        assert!(JsValue::encode_bool(false).is_false());
+        assert!(JsValue::encode_bool(false).is_boolean());
+        assert!(JsValue::null().is_null() && JsValue::null().is_undefined_or_null());
+        assert!(JsValue::undefined().is_undefined() && JsValue::undefined().is_undefined_or_null());
+        assert!(!JsValue::null().is_cell() && !JsValue::undefined().is_boolean());
+        assert!(!JsValue::empty().is_object());
+    }
+
+    #[test]
+    fn test_same_value_zero_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        fn hash(val: JsValue) -> u64 {
+            let mut state = DefaultHasher::new();
+            val.same_value_zero_hash(&mut state);
+            state.finish()
+        }
+        let zeros = [
+            JsValue::new(0),
+            JsValue::new_double(0.0),
+            JsValue::new_double(-0.0),
+        ];
+        for &zero in zeros.iter() {
+            assert!(JsValue::same_value_zero(zero, zeros[0]));
+            assert_eq!(hash(zero), hash(zeros[0]));
+        }
+        let nan = JsValue::new_double(f64::NAN);
+        let other_nan = JsValue::new_double(f64::from_bits(0xfff8_0000_0000_0001));
+        assert!(JsValue::same_value_zero(nan, other_nan));
+        assert_eq!(hash(nan), hash(other_nan));
+        assert_eq!(hash(JsValue::new(2)), hash(JsValue::new_double(2.0)));
+
+        let mut vm = VirtualMachine::new(crate::vm::Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let a = ctx.new_local(JsString::new(&mut vm, "key"));
+            let b = ctx.new_local(JsString::new(&mut vm, "key"));
+            let (a, b) = (JsValue::new(*a), JsValue::new(*b));
+            assert!(!Gc::ptr_eq(a.as_cell(), b.as_cell()));
+            assert!(JsValue::same_value_zero(a, b));
+            assert_eq!(hash(a), hash(b));
+            let obj = ctx.new_local(JsObject::new_empty(&mut vm));
+            let obj = JsValue::new(*obj);
+            assert_eq!(hash(obj), hash(obj));
+            assert!(!JsValue::same_value_zero(obj, a));
+        }
+        crate::vm::VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_encode_object() {
+        let mut vm = VirtualMachine::new(crate::vm::Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let obj = ctx.new_local(JsObject::new_empty(&mut vm));
+            let val = JsValue::encode_object(*obj);
+            assert!(val.is_cell() && val.is_object() && !val.is_number());
+            assert!(Gc::ptr_eq(val.as_object(), *obj));
+        }
+        crate::vm::VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_debug_format() {
+        assert_eq!(format!("{:?}", JsValue::new(42)), "Int32(42)");
+        assert_eq!(format!("{:?}", JsValue::new_double(1.5)), "Double(1.5)");
+        assert_eq!(format!("{:?}", JsValue::new(true)), "Bool(true)");
+        assert_eq!(format!("{:?}", JsValue::null()), "Null");
+        assert_eq!(format!("{:?}", JsValue::undefined()), "Undefined");
+        assert_eq!(
+            format!("{}", JsValue::new_double(f64::NEG_INFINITY)),
+            "-Infinity"
+        );
+
+        let mut vm = VirtualMachine::new(crate::vm::Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let obj = ctx.new_local(JsObject::new_empty(&mut vm));
+            assert_eq!(
+                format!("{:?}", JsValue::new(*obj)),
+                "Object([object Object])"
+            );
+            let arr = vm.new_array(&ctx, &[]);
+            assert_eq!(format!("{}", JsValue::new(*arr)), "[object Array]");
+            let s = JsString::new(&mut vm, "a\"b");
+            assert_eq!(format!("{:?}", JsValue::new(s)), "String(\"a\\\"b\")");
+        }
+        crate::vm::VirtualMachineRef::dispose(vm);
+    }
 }
 
 impl Cell for JsValue {}
 unsafe impl Trace for JsValue {
-    fn trace(&self, tracer: &mut dyn Tracer) {
+    fn trace(&mut self, tracer: &mut dyn Tracer) {
         if self.is_cell() && !self.is_empty() {
-            self.as_cell().trace(tracer);
+            let mut cell = self.as_cell();
+            tracer.trace_edge(&mut cell.cell);
+            if !Gc::ptr_eq(cell, self.as_cell()) {
+                // the compacting collector moved the cell, see [Tracer::trace_edge].
+                *self = Self::encode_cell(cell);
+            }
         }
     }
 }
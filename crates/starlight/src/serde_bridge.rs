@@ -0,0 +1,900 @@
+//! Conversion between [JsValue]s and Rust types implementing `serde`'s traits.
+//!
+//! Structs and maps become ordinary objects, sequences and tuples arrays, `None` and `()`
+//! `null`. Enums use serde's externally tagged form: a unit variant is its name, any other
+//! variant an object with the variant name as its only key.
+//!
+//! Numbers without a fractional part deserialize into integer types, other numbers only into
+//! floats. 64 bit integers beyond `Number.MAX_SAFE_INTEGER` are rejected instead of rounded.
+//! Objects that contain themselves are rejected as well.
+//!
+//! Only built with the `serde-bridge` feature.
+use std::fmt;
+
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor},
+    ser::{self, Serialize},
+};
+
+use crate::{
+    heap::cell::Gc,
+    runtime::{
+        array::JsArray,
+        object::{JsObject, ObjectTag},
+        string::JsString,
+        symbol::Symbol,
+        value::JsValue,
+    },
+    vm::VirtualMachine,
+};
+
+/// `Number.MAX_SAFE_INTEGER`, larger integers can't be told apart from their neighbours.
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+#[derive(Debug)]
+pub enum Error {
+    /// An exception thrown while reading a property.
+    Js(JsValue),
+    /// The value does not fit the Rust type or the Rust value has no JS counterpart.
+    Message(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Js(value) => write!(f, "exception thrown: {:?}", value),
+            Error::Message(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl From<JsValue> for Error {
+    fn from(value: JsValue) -> Self {
+        Error::Js(value)
+    }
+}
+
+/// Converts `value` into a JS value. The result is not rooted.
+pub fn to_js<T: Serialize + ?Sized>(vm: &mut VirtualMachine, value: &T) -> Result<JsValue, Error> {
+    let ctx = vm.space().new_local_context();
+    let mut roots = ctx.new_local(Vec::<JsValue>::new());
+    value.serialize(&mut Serializer {
+        vm,
+        roots: &mut roots,
+    })
+}
+
+/// Converts `value` into a `T`, getters of the objects it reads from are run.
+pub fn from_js<T: DeserializeOwned>(vm: &mut VirtualMachine, value: JsValue) -> Result<T, Error> {
+    let ctx = vm.space().new_local_context();
+    let mut roots = ctx.new_local(vec![value]);
+    T::deserialize(Deserializer {
+        vm,
+        roots: &mut roots,
+        ancestors: &mut vec![],
+        value,
+    })
+}
+
+fn safe_integer<T: fmt::Display>(n: T, number: f64) -> Result<JsValue, Error> {
+    if number.abs() > MAX_SAFE_INTEGER {
+        return Err(Error::Message(format!(
+            "{} can't be represented exactly as a number",
+            n
+        )));
+    }
+    Ok(JsValue::new(number))
+}
+
+struct Serializer<'a> {
+    vm: &'a mut VirtualMachine,
+    /// Cells created so far, they are only reachable from Rust until the conversion is done.
+    roots: &'a mut Vec<JsValue>,
+}
+
+impl Serializer<'_> {
+    fn keep(&mut self, value: JsValue) -> JsValue {
+        self.roots.push(value);
+        value
+    }
+
+    fn string(&mut self, s: &str) -> JsValue {
+        let s = JsString::new(self.vm, s);
+        self.keep(JsValue::new(s))
+    }
+
+    fn object(&mut self) -> Gc<JsObject> {
+        let structure = self.vm.global_data().object_structure.unwrap();
+        let obj = JsObject::new(
+            self.vm,
+            structure,
+            JsObject::get_class(),
+            ObjectTag::Ordinary,
+        );
+        self.keep(JsValue::new(obj));
+        obj
+    }
+
+    fn array(&mut self) -> Gc<JsObject> {
+        let arr = JsArray::new(self.vm, 0);
+        self.keep(JsValue::new(arr));
+        arr
+    }
+
+    /// `{ [variant]: value }`
+    fn tagged(&mut self, variant: &'static str, value: JsValue) -> Result<JsValue, Error> {
+        let mut obj = self.object();
+        let key = self.vm.intern(variant);
+        obj.put(self.vm, key, value, true)?;
+        Ok(JsValue::new(obj))
+    }
+}
+
+impl<'s, 'a> ser::Serializer for &'s mut Serializer<'a> {
+    type Ok = JsValue;
+    type Error = Error;
+    type SerializeSeq = SerializeArray<'s, 'a>;
+    type SerializeTuple = SerializeArray<'s, 'a>;
+    type SerializeTupleStruct = SerializeArray<'s, 'a>;
+    type SerializeTupleVariant = SerializeArray<'s, 'a>;
+    type SerializeMap = SerializeObject<'s, 'a>;
+    type SerializeStruct = SerializeObject<'s, 'a>;
+    type SerializeStructVariant = SerializeObject<'s, 'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v))
+    }
+    fn serialize_i8(self, v: i8) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v))
+    }
+    fn serialize_i16(self, v: i16) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v))
+    }
+    fn serialize_i32(self, v: i32) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v))
+    }
+    fn serialize_i64(self, v: i64) -> Result<JsValue, Error> {
+        safe_integer(v, v as f64)
+    }
+    fn serialize_u8(self, v: u8) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v))
+    }
+    fn serialize_u16(self, v: u16) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v))
+    }
+    fn serialize_u32(self, v: u32) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v))
+    }
+    fn serialize_u64(self, v: u64) -> Result<JsValue, Error> {
+        safe_integer(v, v as f64)
+    }
+    fn serialize_f32(self, v: f32) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v as f64))
+    }
+    fn serialize_f64(self, v: f64) -> Result<JsValue, Error> {
+        Ok(JsValue::new(v))
+    }
+    fn serialize_char(self, v: char) -> Result<JsValue, Error> {
+        let mut buf = [0; 4];
+        Ok(self.string(v.encode_utf8(&mut buf)))
+    }
+    fn serialize_str(self, v: &str) -> Result<JsValue, Error> {
+        Ok(self.string(v))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<JsValue, Error> {
+        use ser::SerializeSeq;
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            seq.serialize_element(byte)?;
+        }
+        seq.end()
+    }
+    fn serialize_none(self) -> Result<JsValue, Error> {
+        Ok(JsValue::null())
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<JsValue, Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<JsValue, Error> {
+        Ok(JsValue::null())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<JsValue, Error> {
+        Ok(JsValue::null())
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<JsValue, Error> {
+        Ok(self.string(variant))
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<JsValue, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<JsValue, Error> {
+        let value = value.serialize(&mut *self)?;
+        self.tagged(variant, value)
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SerializeArray<'s, 'a>, Error> {
+        Ok(SerializeArray {
+            array: self.array(),
+            ser: self,
+            len: 0,
+            variant: None,
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray<'s, 'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray<'s, 'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeArray<'s, 'a>, Error> {
+        Ok(SerializeArray {
+            array: self.array(),
+            ser: self,
+            len: 0,
+            variant: Some(variant),
+        })
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject<'s, 'a>, Error> {
+        Ok(SerializeObject {
+            object: self.object(),
+            ser: self,
+            key: None,
+            variant: None,
+        })
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject<'s, 'a>, Error> {
+        self.serialize_map(Some(len))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeObject<'s, 'a>, Error> {
+        Ok(SerializeObject {
+            object: self.object(),
+            ser: self,
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+struct SerializeArray<'s, 'a> {
+    ser: &'s mut Serializer<'a>,
+    array: Gc<JsObject>,
+    len: u32,
+    /// Variant the array is wrapped in by `end`, for tuple variants.
+    variant: Option<&'static str>,
+}
+
+impl SerializeArray<'_, '_> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let value = value.serialize(&mut *self.ser)?;
+        self.array
+            .put(self.ser.vm, Symbol::Indexed(self.len), value, true)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<JsValue, Error> {
+        let array = JsValue::new(self.array);
+        match self.variant {
+            Some(variant) => self.ser.tagged(variant, array),
+            None => Ok(array),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray<'_, '_> {
+    type Ok = JsValue;
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<JsValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray<'_, '_> {
+    type Ok = JsValue;
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<JsValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray<'_, '_> {
+    type Ok = JsValue;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<JsValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray<'_, '_> {
+    type Ok = JsValue;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<JsValue, Error> {
+        self.finish()
+    }
+}
+
+struct SerializeObject<'s, 'a> {
+    ser: &'s mut Serializer<'a>,
+    object: Gc<JsObject>,
+    /// Key passed to `serialize_key` whose value comes next.
+    key: Option<Symbol>,
+    /// Variant the object is wrapped in by `end`, for struct variants.
+    variant: Option<&'static str>,
+}
+
+impl SerializeObject<'_, '_> {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: Symbol, value: &T) -> Result<(), Error> {
+        let value = value.serialize(&mut *self.ser)?;
+        self.object.put(self.ser.vm, key, value, true)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<JsValue, Error> {
+        let object = JsValue::new(self.object);
+        match self.variant {
+            Some(variant) => self.ser.tagged(variant, object),
+            None => Ok(object),
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeObject<'_, '_> {
+    type Ok = JsValue;
+    type Error = Error;
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let key = key.serialize(&mut *self.ser)?;
+        if !key.is_string() && !key.is_number() {
+            return Err(Error::Message(format!(
+                "map key {:?} is neither a string nor a number",
+                key
+            )));
+        }
+        self.key = Some(key.to_symbol(self.ser.vm)?);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        self.insert(key, value)
+    }
+    fn end(self) -> Result<JsValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject<'_, '_> {
+    type Ok = JsValue;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let key = self.ser.vm.intern(key);
+        self.insert(key, value)
+    }
+    fn end(self) -> Result<JsValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject<'_, '_> {
+    type Ok = JsValue;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let key = self.ser.vm.intern(key);
+        self.insert(key, value)
+    }
+    fn end(self) -> Result<JsValue, Error> {
+        self.finish()
+    }
+}
+
+struct Deserializer<'a> {
+    vm: &'a mut VirtualMachine,
+    /// Values read from properties, getters may have created them.
+    roots: &'a mut Vec<JsValue>,
+    /// Objects whose properties are being deserialized, finding one of them again is a cycle.
+    ancestors: &'a mut Vec<Gc<JsObject>>,
+    value: JsValue,
+}
+
+impl<'a> Deserializer<'a> {
+    /// Deserializer of `value`, read from a property of the current value.
+    fn nested(
+        vm: &'a mut VirtualMachine,
+        roots: &'a mut Vec<JsValue>,
+        ancestors: &'a mut Vec<Gc<JsObject>>,
+        value: JsValue,
+    ) -> Self {
+        roots.push(value);
+        Self {
+            vm,
+            roots,
+            ancestors,
+            value,
+        }
+    }
+
+    /// Adds `obj` to the ancestors of the values deserialized next.
+    fn enter(&mut self, obj: Gc<JsObject>) -> Result<(), Error> {
+        if self
+            .ancestors
+            .iter()
+            .any(|&ancestor| Gc::ptr_eq(ancestor, obj))
+        {
+            return Err(Error::Message(
+                "cyclic object value can't be deserialized".to_owned(),
+            ));
+        }
+        self.ancestors.push(obj);
+        Ok(())
+    }
+
+    fn visit_object<'de, V: Visitor<'de>>(
+        &mut self,
+        obj: Gc<JsObject>,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if obj.tag() == ObjectTag::Array {
+            let len = obj.get(self.vm, Symbol::length())?.to_number(self.vm)? as u32;
+            return visitor.visit_seq(ArrayAccess {
+                vm: &mut *self.vm,
+                roots: &mut *self.roots,
+                ancestors: &mut *self.ancestors,
+                array: obj,
+                index: 0,
+                len,
+            });
+        }
+        let keys = obj.own_enumerable_keys(self.vm)?;
+        visitor.visit_map(ObjectAccess {
+            vm: &mut *self.vm,
+            roots: &mut *self.roots,
+            ancestors: &mut *self.ancestors,
+            object: obj,
+            keys: keys.into_iter(),
+            value: None,
+        })
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Deserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Error> {
+        let value = self.value;
+        if value.is_undefined_or_null() {
+            return visitor.visit_unit();
+        }
+        if value.is_boolean() {
+            return visitor.visit_bool(value.as_boolean());
+        }
+        if value.is_int32() {
+            return visitor.visit_i32(value.as_int32());
+        }
+        if value.is_number() {
+            let n = value.number();
+            let negative_zero = n == 0.0 && n.is_sign_negative();
+            if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER && !negative_zero {
+                return visitor.visit_i64(n as i64);
+            }
+            return visitor.visit_f64(n);
+        }
+        if value.is_string() {
+            return visitor.visit_string(value.as_string().as_str().to_owned());
+        }
+        if value.is_object() && !value.as_object().is_callable() {
+            let obj = value.as_object();
+            self.enter(obj)?;
+            let res = self.visit_object(obj, visitor);
+            self.ancestors.pop();
+            return res;
+        }
+        Err(Error::Message(format!(
+            "{:?} has no Rust counterpart",
+            value
+        )))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.value.is_undefined_or_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let value = self.value;
+        if value.is_string() {
+            let variant = value.as_string().as_str().to_owned();
+            return visitor.visit_enum(Variant {
+                variant,
+                value: Deserializer {
+                    value: JsValue::undefined(),
+                    ..self
+                },
+            });
+        }
+        if value.is_object() {
+            let obj = value.as_object();
+            let keys = obj.own_enumerable_keys(self.vm)?;
+            if keys.len() == 1 {
+                let key = keys[0];
+                let variant = self.vm.description(key);
+                let value = obj.get(self.vm, key)?;
+                self.enter(obj)?;
+                let res = visitor.visit_enum(Variant {
+                    variant,
+                    value: Deserializer::nested(self.vm, self.roots, self.ancestors, value),
+                });
+                self.ancestors.pop();
+                return res;
+            }
+        }
+        Err(Error::Message(format!(
+            "{:?} is neither a variant name nor an object with a single key",
+            value
+        )))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct ArrayAccess<'a> {
+    vm: &'a mut VirtualMachine,
+    roots: &'a mut Vec<JsValue>,
+    ancestors: &'a mut Vec<Gc<JsObject>>,
+    array: Gc<JsObject>,
+    index: u32,
+    len: u32,
+}
+
+impl<'de> de::SeqAccess<'de> for ArrayAccess<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let value = self.array.get(self.vm, Symbol::Indexed(self.index))?;
+        self.index += 1;
+        seed.deserialize(Deserializer::nested(
+            self.vm,
+            self.roots,
+            self.ancestors,
+            value,
+        ))
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index) as usize)
+    }
+}
+
+struct ObjectAccess<'a> {
+    vm: &'a mut VirtualMachine,
+    roots: &'a mut Vec<JsValue>,
+    ancestors: &'a mut Vec<Gc<JsObject>>,
+    object: Gc<JsObject>,
+    keys: std::vec::IntoIter<Symbol>,
+    /// Value of the key returned last.
+    value: Option<JsValue>,
+}
+
+impl<'de> de::MapAccess<'de> for ObjectAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let key = match self.keys.next() {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        self.value = Some(self.object.get(self.vm, key)?);
+        let key: de::value::StringDeserializer<Error> =
+            self.vm.description(key).into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer::nested(
+            self.vm,
+            self.roots,
+            self.ancestors,
+            value,
+        ))
+    }
+}
+
+/// Externally tagged enum variant, `value` is `undefined` for a unit variant given by name.
+struct Variant<'a> {
+    variant: String,
+    value: Deserializer<'a>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Variant<'a> {
+    type Error = Error;
+    type Variant = Deserializer<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Deserializer<'a>), Error> {
+        let variant: de::value::StringDeserializer<Error> = self.variant.into_deserializer();
+        Ok((seed.deserialize(variant)?, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::{Options, VirtualMachineRef};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point {
+        x: i32,
+        y: f64,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Segment(Point, Point),
+        Polygon { points: Vec<Point> },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Scene {
+        name: String,
+        origin: Point,
+        shapes: Vec<Shape>,
+        parent: Option<Box<Scene>>,
+        big: u64,
+        tags: HashMap<String, bool>,
+        pair: (u8, char),
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let mut tags = HashMap::new();
+        tags.insert("visible".to_owned(), true);
+        let scene = Scene {
+            name: "main".to_owned(),
+            origin: Point { x: -1, y: 0.5 },
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(2.0),
+                Shape::Segment(Point { x: 0, y: 0.0 }, Point { x: 1, y: 1.25 }),
+                Shape::Polygon {
+                    points: vec![Point { x: 3, y: -3.0 }],
+                },
+            ],
+            parent: Some(Box::new(Scene {
+                name: "root".to_owned(),
+                origin: Point { x: 0, y: 0.0 },
+                shapes: vec![],
+                parent: None,
+                big: 0,
+                tags: HashMap::new(),
+                pair: (0, 'a'),
+            })),
+            big: 1 << 40,
+            tags,
+            pair: (255, 'é'),
+        };
+        let value = to_js(&mut vm, &scene).unwrap_or_else(|_| panic!());
+        let mut global = vm.global_object();
+        let name = vm.intern("scene");
+        assert!(global.put(&mut vm, name, value, false).is_ok());
+        let res = vm.eval(
+            "out = [
+                 scene.name, scene.origin.x, scene.origin.y, scene.shapes[0],
+                 scene.shapes[1].Circle, scene.shapes[2].Segment[1].y,
+                 scene.shapes[3].Polygon.points[0].x, scene.parent.name,
+                 scene.parent.parent === null, scene.big === 1099511627776, scene.tags.visible,
+                 scene.pair.join()
+             ].join(';');
+             fromScript = {
+                 name: 'script',
+                 origin: { x: 4, y: 4 },
+                 shapes: ['Empty', { Circle: 1 }, { Polygon: { points: [{ x: 1, y: 2.5 }] } }],
+                 big: 4294967296,
+                 tags: { a: false },
+                 pair: [7, 'c']
+             };
+             fractional = { x: 1.5, y: 0 };",
+        );
+        assert!(res.is_ok());
+        let out = vm.get(global, "out").unwrap_or_else(|_| panic!());
+        assert_eq!(
+            out.as_string().as_str(),
+            "main;-1;0.5;Empty;2;1.25;3;root;true;true;true;255,é"
+        );
+
+        let back: Scene = from_js(&mut vm, value).unwrap_or_else(|_| panic!());
+        assert_eq!(back, scene);
+
+        let value = vm.get(global, "fromScript").unwrap_or_else(|_| panic!());
+        let script: Scene = from_js(&mut vm, value).unwrap_or_else(|_| panic!());
+        assert_eq!(script.origin, Point { x: 4, y: 4.0 });
+        assert_eq!(
+            script.shapes,
+            [
+                Shape::Empty,
+                Shape::Circle(1.0),
+                Shape::Polygon {
+                    points: vec![Point { x: 1, y: 2.5 }]
+                }
+            ]
+        );
+        assert!(script.parent.is_none());
+        assert_eq!(script.big, 1 << 32);
+        assert_eq!(script.tags.get("a"), Some(&false));
+        assert_eq!(script.pair, (7, 'c'));
+
+        // a fraction does not fit an integer field.
+        let value = vm.get(global, "fractional").unwrap_or_else(|_| panic!());
+        assert!(from_js::<Point>(&mut vm, value).is_err());
+        assert!(to_js(&mut vm, &u64::MAX).is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Node {
+        next: Option<Box<Node>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    enum Chain {
+        Link(Box<Chain>),
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "node = { next: { next: null } };
+             node.next.next = node;
+             chain = {};
+             chain.Link = chain;
+             point = { x: 1, y: 2 };
+             shared = [point, point];",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let node = vm.get(global, "node").unwrap_or_else(|_| panic!());
+        match from_js::<Node>(&mut vm, node) {
+            Err(Error::Message(msg)) => assert!(msg.contains("cyclic"), "{}", msg),
+            res => panic!("{:?}", res),
+        }
+        let chain = vm.get(global, "chain").unwrap_or_else(|_| panic!());
+        assert!(from_js::<Chain>(&mut vm, chain).is_err());
+        // an object found twice but not inside itself is no cycle.
+        let shared = vm.get(global, "shared").unwrap_or_else(|_| panic!());
+        let points: Vec<Point> = from_js(&mut vm, shared).unwrap_or_else(|_| panic!());
+        assert_eq!(points, [Point { x: 1, y: 2.0 }, Point { x: 1, y: 2.0 }]);
+        VirtualMachineRef::dispose(vm);
+    }
+}
@@ -1,24 +1,87 @@
 use std::collections::HashSet;
 
-use crate::runtime::symbol::Symbol;
+use crate::runtime::symbol::{default_symbol_list, is_array_index, Symbol};
 
 pub struct SymbolTable {
     set: HashSet<&'static str>,
+    /// Names of the well-known symbols. These are not owned by the table and must never be freed.
+    defaults: HashSet<&'static str>,
+    /// Symbols that are never freed because Rust code outside of the heap may hold them.
+    pinned: HashSet<*const u8>,
+    /// Keys of the symbols created by `Symbol(description)`. They are never looked up by name and
+    /// live as long as the table, every key has a buffer of its own so no two keys compare equal.
+    unique: Vec<String>,
 }
 
 impl SymbolTable {
     pub fn new() -> Self {
+        let mut defaults = HashSet::new();
+        for sym in default_symbol_list() {
+            if let Symbol::Key(key) = sym {
+                defaults.insert(key);
+            }
+        }
         Self {
             set: HashSet::new(),
+            defaults,
+            pinned: HashSet::new(),
+            unique: Vec::new(),
+        }
+    }
+
+    /// Number of symbols owned by the table, well-known symbols are not counted.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Keeps `sym` in the table for as long as the table lives.
+    pub fn pin(&mut self, sym: Symbol) {
+        if let Symbol::Key(key) = sym {
+            if self.set.contains(key) {
+                self.pinned.insert(key.as_ptr());
+            }
         }
     }
+
+    /// Frees every symbol that is neither pinned nor in `live`, the symbols held by live objects.
+    /// Symbols held anywhere else, like a Rust variable, must not be used afterwards.
+    pub fn sweep(&mut self, live: &HashSet<*const u8>) {
+        let pinned = &self.pinned;
+        self.set.retain(|key| {
+            if live.contains(&key.as_ptr()) || pinned.contains(&key.as_ptr()) {
+                return true;
+            }
+            unsafe {
+                String::from_raw_parts(key.as_ptr() as *mut u8, key.len(), key.len());
+            }
+            false
+        });
+    }
+    /// A key that is not equal to any other key, spelled `description`.
+    #[allow(clippy::transmute_ptr_to_ptr)]
+    pub fn unique(&mut self, description: &str) -> Symbol {
+        // an empty string does not allocate, its pointer would be shared.
+        let mut key = String::with_capacity(description.len().max(1));
+        key.push_str(description);
+        let s: &'static str = unsafe { std::mem::transmute::<&str, &'static str>(&key) };
+        self.unique.push(key);
+        Symbol::Key(s)
+    }
+
     #[allow(clippy::transmute_ptr_to_ptr)]
     pub fn lookup(&mut self, s: impl AsRef<str>) -> Symbol {
         let s = s.as_ref();
-        if let Ok(uint) = s.parse::<u32>() {
-            return Symbol::Indexed(uint);
+        if let Some(index) = is_array_index(s) {
+            return Symbol::Indexed(index);
         }
         let s: &'static str = unsafe { std::mem::transmute::<&str, &'static str>(&s) };
+        if let Some(val) = self.defaults.get(&s) {
+            return Symbol::Key(*val);
+        }
         if let Some(val) = self.set.get(&s) {
             Symbol::Key(*val)
         } else {
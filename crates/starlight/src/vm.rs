@@ -1,12 +1,22 @@
 use super::runtime::attributes::*;
-use std::{fmt::Display, io::Write, sync::RwLock};
-use std::{ops::DerefMut, ptr::null_mut};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::DerefMut,
+    ptr::null_mut,
+};
+use std::{
+    fmt::Display,
+    io::Write,
+    sync::{Arc, RwLock},
+    time::Instant,
+};
 use swc_common::{
     errors::{DiagnosticBuilder, Emitter, Handler},
     sync::Lrc,
 };
-use swc_common::{FileName, SourceMap};
-use swc_ecmascript::parser::*;
+use swc_common::{FileName, SourceFile, SourceMap};
+use swc_ecmascript::{ast::Script, parser::*};
 use wtf_rs::{object_offsetof, unwrap_unchecked};
 
 #[derive(Clone, Default)]
@@ -41,29 +51,40 @@ impl Emitter for MyEmiter {
         }
     }
 }
+#[cfg(any(debug_assertions, feature = "verify-heap"))]
+use crate::gc::verify::HeapError;
 use crate::{
+    bytecode::{ByteCode, IcStats},
     frontend::Compiler,
-    gc::space::Space,
+    gc::{
+        space::{GcStats, Space},
+        weak::WeakGc,
+    },
     heap::{
         cell::{Cell, Gc, Trace, Tracer},
         constraint::SimpleMarkingConstraint,
+        context::{Local, LocalContext},
+        handle::RootScope,
         Allocator,
     },
     interpreter::frame::FrameBase,
     jsrt::error::{
         error_constructor, error_to_string, eval_error_constructor, reference_error_constructor,
-        type_error_constructor,
+        syntax_error_constructor, type_error_constructor,
     },
+    jsrt::jsrt_init,
     runtime::{
         arguments::Arguments,
-        error::{JsError, JsEvalError, JsReferenceError, JsTypeError},
+        array::JsArray,
+        error::{JsError, JsEvalError, JsReferenceError, JsSyntaxError, JsTypeError},
         function::{JsNativeFunction, JsVMFunction},
         global::JsGlobal,
-        object::{JsObject, ObjectTag},
+        object::{EnumerationMode, JsObject, ObjectTag},
         property_descriptor::DataDescriptor,
         string::JsString,
-        structure::Structure,
-        symbol::Symbol,
+        structure::{Structure, StructureStats},
+        symbol::{JsSymbol, Symbol},
+        typed_array::TypedArrayKind,
         value::JsValue,
     },
     symbol_table::SymbolTable,
@@ -75,15 +96,170 @@ use structopt::*;
 pub struct Options {
     #[structopt(long, help = "Write barrier inline buffer size.", default_value = "32")]
     pub write_barrier_buffer_size: usize,
+    #[structopt(long, help = "Run a full collection before every allocation.")]
+    pub gc_stress: bool,
+    #[structopt(
+        long,
+        help = "Evict unreferenced symbols after a script once the symbol table holds more than this many."
+    )]
+    pub symbol_table_limit: Option<usize>,
+    #[structopt(
+        long,
+        help = "Evaluate all code in strict mode regardless of directives."
+    )]
+    pub strict: bool,
+    #[structopt(
+        long,
+        help = "Number of properties after which objects switch to a unique dictionary structure.",
+        default_value = "32"
+    )]
+    pub dictionary_threshold: u32,
+    #[structopt(
+        long,
+        help = "Disable inline caches, every property access does a full lookup."
+    )]
+    pub disable_ics: bool,
+    #[structopt(
+        long,
+        help = "Number of properties stored in the object itself before they spill to out-of-line storage.",
+        default_value = "4"
+    )]
+    pub inline_slots: usize,
+    #[structopt(
+        long,
+        help = "Log every executed opcode with its source line and the top of the stack to stderr."
+    )]
+    pub trace_execution: bool,
+    /// Whether `eval` and the `Function` constructor may compile code at runtime, when disabled
+    /// they throw an `EvalError`. Scripts passed to [VirtualMachine::eval] always run.
+    #[structopt(
+        long = "disallow-eval",
+        parse(from_flag = std::ops::Not::not),
+        help = "Make eval and the Function constructor throw an EvalError."
+    )]
+    pub allow_eval: bool,
+    /// Clock returning milliseconds used by `performance.now()`. When not set time elapsed
+    /// since VM creation is used, wasm32-unknown-unknown has no clock and reports 0.
+    #[structopt(skip)]
+    pub clock: Option<Box<dyn Fn() -> f64>>,
+    #[structopt(
+        long,
+        help = "Interrupt scripts running for longer than this many milliseconds, as measured by the clock."
+    )]
+    pub timeout: Option<u64>,
 }
 impl Default for Options {
     fn default() -> Self {
         Self {
             write_barrier_buffer_size: 32,
+            gc_stress: false,
+            symbol_table_limit: None,
+            strict: false,
+            dictionary_threshold: 32,
+            disable_ics: false,
+            inline_slots: 4,
+            trace_execution: false,
+            allow_eval: true,
+            clock: None,
+            timeout: None,
+        }
+    }
+}
+
+impl Options {
+    /// Start building VM options programmatically, without going through
+    /// `StructOpt` and process arguments.
+    pub fn builder() -> OptionsBuilder {
+        OptionsBuilder {
+            opts: Self::default(),
         }
     }
 }
 
+/// Builder for [Options], every option not set explicitly keeps its default value.
+pub struct OptionsBuilder {
+    opts: Options,
+}
+
+impl OptionsBuilder {
+    pub fn write_barrier_buffer_size(mut self, size: usize) -> Self {
+        self.opts.write_barrier_buffer_size = size;
+        self
+    }
+
+    pub fn gc_stress(mut self, enabled: bool) -> Self {
+        self.opts.gc_stress = enabled;
+        self
+    }
+
+    /// Once the symbol table holds more than `limit` symbols, symbols interned by scripts that
+    /// no live object holds are freed after [VirtualMachine::eval] returns. Symbols interned
+    /// while no script runs are never freed, native functions that keep a symbol past their call
+    /// have to store it in some heap object.
+    pub fn symbol_table_limit(mut self, limit: usize) -> Self {
+        self.opts.symbol_table_limit = Some(limit);
+        self
+    }
+
+    /// Treat every script and function as if it started with a `"use strict"` directive.
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.opts.strict = enabled;
+        self
+    }
+
+    /// Objects that reach `count` properties stop sharing structure transitions and get a
+    /// unique structure of their own. Counts above
+    /// [MAX_TRANSITION_CHAIN](crate::runtime::structure::MAX_TRANSITION_CHAIN) have no effect.
+    pub fn dictionary_threshold(mut self, count: u32) -> Self {
+        self.opts.dictionary_threshold = count;
+        self
+    }
+
+    /// Skip inline caches and type feedback so every property and variable access takes the
+    /// full lookup path, useful to tell whether a bug lives in the IC layer.
+    pub fn disable_ics(mut self, disabled: bool) -> Self {
+        self.opts.disable_ics = disabled;
+        self
+    }
+
+    /// Store the first `count` properties of every object in the object cell, properties past
+    /// them live in a separately allocated array.
+    pub fn inline_slots(mut self, count: usize) -> Self {
+        self.opts.inline_slots = count;
+        self
+    }
+
+    /// Write a line for every executed instruction to the stderr sink, see
+    /// [VirtualMachine::set_stderr].
+    pub fn trace_execution(mut self, enabled: bool) -> Self {
+        self.opts.trace_execution = enabled;
+        self
+    }
+
+    /// Allow `eval` and the `Function` constructor, untrusted scripts can be denied runtime code
+    /// generation by passing `false`.
+    pub fn allow_eval(mut self, allowed: bool) -> Self {
+        self.opts.allow_eval = allowed;
+        self
+    }
+
+    pub fn clock(mut self, clock: impl Fn() -> f64 + 'static) -> Self {
+        self.opts.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Interrupt [VirtualMachine::eval] once it ran for `ms` milliseconds, see
+    /// [InterruptHandle::interrupt]. Code still running `ms` milliseconds later is aborted.
+    pub fn timeout(mut self, ms: u64) -> Self {
+        self.opts.timeout = Some(ms);
+        self
+    }
+
+    pub fn build(self) -> Options {
+        self.opts
+    }
+}
+
 #[repr(C)]
 pub struct VirtualMachine {
     return_value: JsValue,
@@ -97,6 +273,62 @@ pub struct VirtualMachine {
     interner: SymbolTable,
     global_data: Box<GlobalData>,
     pub(crate) frame: *mut FrameBase,
+    options: Options,
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    start_time: Instant,
+    debugger: Option<Box<dyn FnMut(&mut VirtualMachine)>>,
+    debugger_line: Option<u32>,
+    microtasks: VecDeque<JsValue>,
+    /// Every `FinalizationRegistry` created so far, checked for collected targets at microtask
+    /// checkpoints. Registries are held weakly, a collected registry never calls back.
+    finalization_registries: Vec<WeakGc<JsObject>>,
+    uncaught_microtask: Option<Box<dyn FnMut(&mut VirtualMachine, JsValue, JsValue)>>,
+    /// Closures registered with [VirtualMachine::add_finalizer_on_drop], in registration order.
+    drop_finalizers: Vec<Box<dyn FnOnce()>>,
+    /// Handle stack of [RootScope]s, marked as roots.
+    pub(crate) handles: Vec<Gc<dyn Cell>>,
+    /// Number of [RootScope]s currently open.
+    pub(crate) root_scopes: usize,
+    structure_stats: StructureStats,
+    ic_stats: IcStats,
+    stdout: Box<dyn Write + Send>,
+    stderr: Box<dyn Write + Send>,
+    /// Interrupts requested through [InterruptHandle]s during the current [VirtualMachine::eval].
+    interrupts: Arc<AtomicU32>,
+    /// Number of requested interrupts already thrown, capped at 2. At 1 the interrupt error
+    /// unwinds through `finally` blocks only, at 2 it runs no handlers at all.
+    pub(crate) interrupt_level: u32,
+    /// Time [VirtualMachine::now] has to reach for the timeout to request the next interrupt.
+    deadline: Option<f64>,
+    interrupt_polls: u32,
+    /// Whether the outermost [VirtualMachine::eval] is running.
+    running: bool,
+    /// Size the symbol table has to exceed before it is swept again.
+    symbol_sweep_at: usize,
+}
+
+/// Requests a running [VirtualMachine] to stop from any thread, see
+/// [VirtualMachine::interrupt_handle].
+#[derive(Clone)]
+pub struct InterruptHandle(Arc<AtomicU32>);
+
+impl InterruptHandle {
+    /// The first interrupt throws an error at the next loop iteration or call. It cannot be
+    /// caught, but `finally` blocks still run on its way out. A second interrupt aborts
+    /// immediately without running them. Interrupts requested while no script runs are dropped.
+    pub fn interrupt(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Where [VirtualMachine::inspect] and [VirtualMachine::inspect_to_depth] stop descending
+/// into nested objects.
+#[derive(Clone, Copy)]
+enum Nesting {
+    /// Objects nested two levels deep are shown as `[object Class]`.
+    Brief,
+    /// Objects nested deeper than the limit are shown as `[Object]` or `[Array]`.
+    Limit(Option<u32>),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -126,6 +358,12 @@ pub trait IntoSymbol {
     fn into_symbol(self, vm: &mut VirtualMachine) -> Symbol;
 }
 
+impl IntoSymbol for Symbol {
+    fn into_symbol(self, _vm: &mut VirtualMachine) -> Symbol {
+        self
+    }
+}
+
 impl IntoSymbol for u32 {
     fn into_symbol(self, _vm: &mut VirtualMachine) -> Symbol {
         Symbol::Indexed(self)
@@ -142,62 +380,179 @@ impl IntoSymbol for String {
     }
 }
 
-struct OutBuf;
-
-impl std::fmt::Write for OutBuf {
-    fn write_str(&mut self, s: &str) -> std::fmt::Result {
-        print!("{}", s);
-        Ok(())
-    }
-}
-
 impl VirtualMachine {
+    /// Runs `script` as global code and returns the value of its trailing expression statement,
+    /// `undefined` when there is none.
     pub fn eval(&mut self, script: &str) -> Result<JsValue, JsValue> {
+        let outermost = self.frame.is_null();
+        if outermost {
+            // Interrupts requested while no script ran are dropped.
+            self.clear_interrupts();
+            self.deadline = self.options.timeout.map(|ms| self.now() + ms as f64);
+            self.running = true;
+        }
         let res = {
             let ctx = self.space().new_local_context();
-            let cm: Lrc<SourceMap> = Default::default();
-            let e = BufferedError::default();
-
-            let handler = Handler::with_emitter(true, false, Box::new(MyEmiter::default()));
-            // Real usage
-            // let fm = cm
-            //     .load_file(Path::new("test.js"))
-            //     .expect("failed to load test.js");
-            let fm = cm.new_source_file(FileName::Custom("<script>".into()), script.into());
-            let lexer = Lexer::new(
-                // We want to parse ecmascript
-                Syntax::Es(Default::default()),
-                // JscTarget defaults to es5
-                Default::default(),
-                StringInput::from(&*fm),
-                None,
-            );
+            match self.compile_script(script, "<script>") {
+                Ok(code) => {
+                    let code = ctx.new_local(code);
+                    self.run_script(*code)
+                }
+                Err(e) => Err(e),
+            }
+        };
+        // Microtask checkpoint, queued tasks run once the script finished.
+        let ctx = self.space().new_local_context();
+        let _ = ctx.new_local(match res {
+            Ok(val) | Err(val) => val,
+        });
+        if outermost {
+            self.clear_interrupts();
+        }
+        self.run_microtasks();
+        if outermost {
+            self.clear_interrupts();
+            self.deadline = None;
+            self.running = false;
+            self.evict_symbols();
+        }
+        res
+    }
 
-            let mut parser = Parser::new_from(lexer);
+    /// Handle that interrupts scripts run by this VM, it can be sent to other threads.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle(self.interrupts.clone())
+    }
 
-            for e in parser.take_errors() {
-                e.into_diagnostic(&handler).emit();
+    fn clear_interrupts(&mut self) {
+        self.interrupts.store(0, Ordering::Relaxed);
+        self.interrupt_level = 0;
+    }
+
+    /// Throws the interrupt error if an interrupt was requested since the last check or the
+    /// timeout expired. Called on backward jumps and function entry.
+    pub(crate) fn poll_interrupt(&mut self) -> Result<(), JsValue> {
+        if let (Some(deadline), Some(ms)) = (self.deadline, self.options.timeout) {
+            // Reading the clock on every poll would dominate tight loops.
+            self.interrupt_polls = self.interrupt_polls.wrapping_add(1);
+            if self.interrupt_polls % 1024 == 0 && self.now() >= deadline {
+                self.deadline = Some(deadline + ms as f64);
+                self.interrupts.fetch_add(1, Ordering::Relaxed);
             }
+        }
+        let requested = self.interrupts.load(Ordering::Relaxed).min(2);
+        if requested <= self.interrupt_level {
+            return Ok(());
+        }
+        self.interrupt_level = requested;
+        let msg = JsString::new(self, "Script execution interrupted");
+        Err(JsValue::new(JsError::new(self, msg, None)))
+    }
 
-            let script = match parser.parse_script() {
-                Ok(script) => script,
-                Err(e) => {
-                    todo!("throw error");
-                }
-            };
+    /// Parses and compiles `script` as global code, syntax errors are thrown as `SyntaxError`.
+    /// A trailing expression statement is the value of the script.
+    pub(crate) fn compile_script(
+        &mut self,
+        script: &str,
+        name: &str,
+    ) -> Result<Gc<ByteCode>, JsValue> {
+        let (script, file) = self.parse_script(script, name)?;
+        self.compile_parsed(&script, file)
+    }
+
+    /// Compiles a script returned by [VirtualMachine::parse_script] as global code, early errors
+    /// are thrown as `SyntaxError`.
+    pub(crate) fn compile_parsed(
+        &mut self,
+        script: &Script,
+        file: Lrc<SourceFile>,
+    ) -> Result<Gc<ByteCode>, JsValue> {
+        Compiler::compile_script(VirtualMachineRef(self), script, file).map_err(|error| {
+            let msg = JsString::new(self, error);
+            JsValue::new(JsSyntaxError::new(self, msg, None))
+        })
+    }
 
-            let code = ctx.new_local(Compiler::compile_script(VirtualMachineRef(self), &script));
+    /// Parses `script`, syntax errors are thrown as `SyntaxError`.
+    pub(crate) fn parse_script(
+        &mut self,
+        script: &str,
+        name: &str,
+    ) -> Result<(Script, Lrc<SourceFile>), JsValue> {
+        let cm: Lrc<SourceMap> = Default::default();
+        let errors = BufferedError::default();
+        let handler = Handler::with_emitter(true, false, Box::new(MyEmiter(errors.clone())));
+        let fm = cm.new_source_file(FileName::Custom(name.into()), script.into());
+        let lexer = Lexer::new(
+            // We want to parse ecmascript
+            Syntax::Es(EsConfig {
+                class_private_props: true,
+                class_private_methods: true,
+                class_props: true,
+                ..Default::default()
+            }),
+            // JscTarget defaults to es5
+            Default::default(),
+            StringInput::from(&*fm),
+            None,
+        );
 
-            code.display_to(&mut OutBuf).unwrap();
+        let mut parser = Parser::new_from(lexer);
 
-            let envs = Structure::new_indexed(self, Some(self.global_object()), false);
-            let env = JsObject::new(self, envs, JsObject::get_class(), ObjectTag::Ordinary);
-            let mut fun = ctx.new_local(JsVMFunction::new(self, *code, env));
-            let mut args = ctx.new_local(Arguments::new(self, JsValue::undefined(), 0));
-            fun.as_function_mut().call(self, &mut args)
+        for e in parser.take_errors() {
+            e.into_diagnostic(&handler).emit();
+        }
+
+        let script = match parser.parse_script() {
+            Ok(script) => script,
+            Err(e) => {
+                e.into_diagnostic(&handler).emit();
+                let msg = JsString::new(self, errors.to_string());
+                return Err(JsValue::new(JsSyntaxError::new(self, msg, None)));
+            }
         };
+        Ok((script, fm))
+    }
 
-        res
+    /// Runs global code compiled by [VirtualMachine::compile_script] in a fresh scope whose
+    /// parent is the global object.
+    pub(crate) fn run_script(&mut self, code: Gc<ByteCode>) -> Result<JsValue, JsValue> {
+        let ctx = self.space().new_local_context();
+        let envs = Structure::new_indexed(self, Some(self.global_object()), false);
+        let env = JsObject::new(self, envs, JsObject::get_class(), ObjectTag::Ordinary);
+        let mut fun = ctx.new_local(JsVMFunction::new(self, code, env));
+        let mut args = ctx.new_local(Arguments::new(self, JsValue::undefined(), 0));
+        fun.as_function_mut().call(self, &mut args)
+    }
+
+    /// Like [VirtualMachine::eval], but also returns everything the script printed instead of
+    /// writing it to the current output sink.
+    pub fn try_eval(&mut self, script: &str) -> (Result<JsValue, JsValue>, String) {
+        let output = BufferedError::default();
+        let previous = self.set_stdout(Box::new(output.clone()));
+        let res = self.eval(script);
+        self.set_stdout(previous);
+        (res, output.to_string())
+    }
+
+    /// Replaces the sink `print` and `console.log` write to and returns the previous one.
+    /// Output goes to the process stdout by default.
+    pub fn set_stdout(&mut self, stdout: Box<dyn Write + Send>) -> Box<dyn Write + Send> {
+        std::mem::replace(&mut self.stdout, stdout)
+    }
+
+    /// Replaces the sink for `console.error` and uncaught errors and returns the previous one.
+    /// Output goes to the process stderr by default.
+    pub fn set_stderr(&mut self, stderr: Box<dyn Write + Send>) -> Box<dyn Write + Send> {
+        std::mem::replace(&mut self.stderr, stderr)
+    }
+
+    pub fn stdout(&mut self) -> &mut dyn Write {
+        &mut *self.stdout
+    }
+
+    pub fn stderr(&mut self) -> &mut dyn Write {
+        &mut *self.stderr
     }
     pub fn description(&self, sym: Symbol) -> String {
         match sym {
@@ -206,6 +561,120 @@ impl VirtualMachine {
         }
     }
 
+    /// The value scripts see for property key `sym`: well-known symbol keys are symbols, every
+    /// other key is a string.
+    pub fn key_value(&mut self, sym: Symbol) -> JsValue {
+        if sym == Symbol::iterator() || sym == Symbol::to_string_tag() {
+            return JsValue::new(JsSymbol::new(self, sym));
+        }
+        if let Symbol::Indexed(index) = sym {
+            if index <= i32::MAX as u32 {
+                return JsValue::new(self.int_string(index as i32));
+            }
+        }
+        let desc = self.description(sym);
+        JsValue::new(JsString::new(self, desc))
+    }
+
+    /// Readable description of `value` for REPLs and debugging. Strings are quoted, arrays and
+    /// objects show their own enumerable properties; nesting deeper than two levels is cut off
+    /// as `[object Class]`. Getters are invoked while reading properties.
+    pub fn inspect(&mut self, value: JsValue) -> String {
+        let mut out = String::new();
+        self.inspect_into(&mut out, value, 0, Nesting::Brief, &mut vec![]);
+        out
+    }
+
+    /// Like [VirtualMachine::inspect] but objects nested deeper than `depth` levels are shown as
+    /// `[Object]` or `[Array]`, the way `console.dir` prints them. `None` has no limit, an
+    /// object containing itself is shown as `[Circular]` where it repeats.
+    pub fn inspect_to_depth(&mut self, value: JsValue, depth: Option<u32>) -> String {
+        let mut out = String::new();
+        self.inspect_into(&mut out, value, 0, Nesting::Limit(depth), &mut vec![]);
+        out
+    }
+
+    fn inspect_into(
+        &mut self,
+        out: &mut String,
+        value: JsValue,
+        depth: u32,
+        nesting: Nesting,
+        seen: &mut Vec<Gc<JsObject>>,
+    ) {
+        if value.is_empty() || value == JsValue::deleted() {
+            out.push_str(&format!("{}", value));
+            return;
+        }
+        if value.is_string() {
+            out.push_str(&format!("{:?}", value.as_string().as_str()));
+            return;
+        }
+        if !value.is_object() {
+            out.push_str(&format!("{}", value));
+            return;
+        }
+        let obj = value.as_object();
+        if obj.is_callable() {
+            out.push_str("[Function]");
+            return;
+        }
+        match nesting {
+            Nesting::Brief if depth >= 2 => {
+                out.push_str(&format!("{}", value));
+                return;
+            }
+            Nesting::Limit(Some(limit)) if depth > limit => {
+                out.push_str(if obj.tag() == ObjectTag::Array {
+                    "[Array]"
+                } else {
+                    "[Object]"
+                });
+                return;
+            }
+            _ => (),
+        }
+        if seen.iter().any(|outer| Gc::ptr_eq(*outer, obj)) {
+            out.push_str("[Circular]");
+            return;
+        }
+        let ctx = self.space().new_local_context();
+        let obj = ctx.new_local(obj);
+        let mut names = vec![];
+        obj.get_own_property_names(
+            self,
+            &mut |name, _| names.push(name),
+            EnumerationMode::Default,
+        );
+        if obj.tag() == ObjectTag::Array {
+            names.retain(|name| matches!(name, Symbol::Indexed(_)));
+            out.push('[');
+        } else if names.is_empty() {
+            out.push_str("{}");
+            return;
+        } else {
+            out.push_str("{ ");
+        }
+        seen.push(*obj);
+        for (i, name) in names.iter().enumerate() {
+            if i != 0 {
+                out.push_str(", ");
+            }
+            if obj.tag() != ObjectTag::Array {
+                out.push_str(&self.description(*name));
+                out.push_str(": ");
+            }
+            let val = obj.get(self, *name).unwrap_or_else(|e| e);
+            self.inspect_into(out, val, depth + 1, nesting, seen);
+        }
+        seen.pop();
+        out.push_str(if obj.tag() == ObjectTag::Array {
+            "]"
+        } else {
+            " }"
+        });
+    }
+
     pub(crate) fn try_cache(
         &mut self,
         s: Gc<Structure>,
@@ -225,7 +694,59 @@ impl VirtualMachine {
         None
     }
     pub fn intern(&mut self, val: impl IntoSymbol) -> Symbol {
-        val.into_symbol(self)
+        let sym = val.into_symbol(self);
+        // Outside of a script the symbol may end up anywhere in the embedder, see
+        // [VirtualMachine::evict_symbols].
+        if !self.running {
+            self.interner.pin(sym);
+        }
+        sym
+    }
+
+    /// Frees the symbols no live object holds once the symbol table outgrew
+    /// [Options::symbol_table_limit]. It only runs after the outermost script returned, when no
+    /// native frame is left that could hold symbols interned by the script.
+    fn evict_symbols(&mut self) {
+        let limit = match self.options.symbol_table_limit {
+            Some(limit) => limit,
+            None => return,
+        };
+        if self.interner.len() <= limit.max(self.symbol_sweep_at) {
+            return;
+        }
+        if let Some(live) = self.space.collect_symbols() {
+            self.interner.sweep(&live);
+            // a table of mostly live symbols is not swept again before it doubled.
+            self.symbol_sweep_at = self.interner.len() * 2;
+        }
+    }
+
+    /// A fresh key spelled `description` for `Symbol(description)`, see [SymbolTable::unique].
+    pub(crate) fn unique_symbol(&mut self, description: &str) -> Symbol {
+        self.interner.unique(description)
+    }
+
+    /// Number of symbols in the symbol table, not counting well-known symbols.
+    pub fn symbol_table_size(&self) -> usize {
+        self.interner.len()
+    }
+
+    /// Counters of the structure enumeration cache used by `for...in` and `Object.keys`.
+    pub fn structure_stats(&self) -> StructureStats {
+        self.structure_stats
+    }
+
+    pub(crate) fn structure_stats_mut(&mut self) -> &mut StructureStats {
+        &mut self.structure_stats
+    }
+
+    /// Counters of the inline caches of property loads, stores and compound assignments.
+    pub fn ic_stats(&self) -> IcStats {
+        self.ic_stats
+    }
+
+    pub(crate) fn ic_stats_mut(&mut self) -> &mut IcStats {
+        &mut self.ic_stats
     }
 
     pub fn push(&mut self, val: JsValue) {
@@ -263,6 +784,228 @@ impl VirtualMachine {
     pub fn global_data(&self) -> &GlobalData {
         &self.global_data
     }
+
+    pub(crate) fn global_data_mut(&mut self) -> &mut GlobalData {
+        &mut self.global_data
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// The empty structure objects inheriting from `prototype` start with. There is one per
+    /// prototype so objects created the same way share their structure and transitions.
+    pub fn root_structure(&mut self, prototype: Option<Gc<JsObject>>) -> Gc<Structure> {
+        let prototype = match prototype {
+            Some(prototype) => prototype,
+            None => return self.global_data.empty_object_struct.unwrap(),
+        };
+        if let Some(&structure) = self.global_data.root_structures.get(&prototype) {
+            return structure;
+        }
+        let structure = Structure::new_indexed(self, Some(prototype), false);
+        self.global_data
+            .root_structures
+            .insert(prototype, structure);
+        structure
+    }
+
+    /// String holding just `c`, strings for characters below [SMALL_STRING_CACHE_SIZE] are shared.
+    pub fn char_string(&mut self, c: char) -> Gc<JsString> {
+        let mut buf = [0; 4];
+        let s: &str = c.encode_utf8(&mut buf);
+        cached_string(self, |data| &mut data.char_strings, c as usize, s)
+    }
+
+    /// String form of `i`, strings for integers in `0..SMALL_STRING_CACHE_SIZE` are shared.
+    pub fn int_string(&mut self, i: i32) -> Gc<JsString> {
+        let ix = if i < 0 { usize::MAX } else { i as usize };
+        cached_string(self, |data| &mut data.int_strings, ix, i.to_string())
+    }
+
+    /// Current time in milliseconds as seen by `performance.now()`.
+    pub fn now(&self) -> f64 {
+        match self.options.clock {
+            Some(ref clock) => clock(),
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            None => self.start_time.elapsed().as_secs_f64() * 1000.0,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            None => 0.0,
+        }
+    }
+    /// Register `hook` to be called whenever a `debugger;` statement is executed, replacing
+    /// any previous hook. While the hook runs [VirtualMachine::debugger_line] and
+    /// [VirtualMachine::frame_locals] describe the paused statement.
+    pub fn on_debugger(&mut self, hook: Box<dyn FnMut(&mut VirtualMachine)>) {
+        self.debugger = Some(hook);
+    }
+
+    /// Line of the `debugger;` statement being handled, `None` outside of the debugger hook.
+    pub fn debugger_line(&self) -> Option<u32> {
+        self.debugger_line
+    }
+
+    /// Variables in scope of the current frame, innermost scope first. Globals are not included.
+    pub fn frame_locals(&mut self) -> Vec<(Symbol, JsValue)> {
+        let mut locals: Vec<(Symbol, JsValue)> = vec![];
+        if self.frame.is_null() {
+            return locals;
+        }
+        unsafe {
+            if let Some(bcode) = (*self.frame).bcode {
+                for (name, val) in bcode.local_names.iter().zip((*self.frame).locals.iter()) {
+                    locals.push((*name, *val));
+                }
+            }
+        }
+        let global = self.global_object();
+        let mut scope = unsafe { (*self.frame).scope };
+        while scope.is_object() && !Gc::ptr_eq(scope.as_object(), global) {
+            let record = scope.as_object();
+            let mut names = vec![];
+            record.get_own_property_names(
+                self,
+                &mut |name, _| names.push(name),
+                EnumerationMode::IncludeNotEnumerable,
+            );
+            for name in names {
+                // inner declarations shadow outer ones.
+                if locals.iter().any(|(local, _)| *local == name) {
+                    continue;
+                }
+                let val = record
+                    .get(self, name)
+                    .unwrap_or_else(|_| JsValue::undefined());
+                locals.push((name, val));
+            }
+            scope = match record.prototype() {
+                Some(proto) => JsValue::new(proto),
+                None => break,
+            };
+        }
+        locals
+    }
+
+    pub(crate) fn debugger(&mut self, line: u32) {
+        // the hook is taken out for the duration of the call so it can freely use the VM.
+        let mut hook = match self.debugger.take() {
+            Some(hook) => hook,
+            None => return,
+        };
+        self.debugger_line = Some(line);
+        hook(self);
+        self.debugger_line = None;
+        if self.debugger.is_none() {
+            self.debugger = Some(hook);
+        }
+    }
+
+    /// Append `task` to the microtask queue, it is called with no arguments on the next
+    /// checkpoint.
+    pub fn enqueue_microtask(&mut self, task: JsValue) {
+        self.microtasks.push_back(task);
+    }
+
+    /// Run queued microtasks until the queue is empty, including tasks queued while draining.
+    /// Errors thrown by a task go to the hook registered with
+    /// [VirtualMachine::on_uncaught_microtask] and do not stop the remaining tasks.
+    ///
+    /// Once the queue is empty `FinalizationRegistry` cleanup callbacks run for targets collected
+    /// since the last checkpoint, followed by any microtasks they queued.
+    pub fn run_microtasks(&mut self) {
+        loop {
+            while let Some(task) = self.microtasks.pop_front() {
+                let ctx = self.space().new_local_context();
+                let task = ctx.new_local(task);
+                let mut args = ctx.new_local(Arguments::new(self, JsValue::undefined(), 0));
+                let res = task.as_object().as_function_mut().call(self, &mut args);
+                if let Err(error) = res {
+                    self.report_uncaught_microtask(error, *task);
+                }
+            }
+            if !self.cleanup_finalization_registries() {
+                break;
+            }
+        }
+    }
+
+    pub(crate) fn add_finalization_registry(&mut self, registry: Gc<JsObject>) {
+        let registry = self.space.new_weak(registry);
+        self.finalization_registries.push(registry);
+    }
+
+    /// Calls the cleanup callback of live registries once per collected target, returns whether
+    /// any callback ran. Errors are reported like errors of microtasks.
+    fn cleanup_finalization_registries(&mut self) -> bool {
+        self.finalization_registries
+            .retain(|registry| registry.upgrade().is_some());
+        let ctx = self.space().new_local_context();
+        let mut jobs = vec![];
+        for registry in self.finalization_registries.iter() {
+            let mut registry = registry.upgrade().unwrap();
+            let cleanup = registry.as_finalization_registry().cleanup();
+            for held in registry.as_finalization_registry_mut().take_collected() {
+                jobs.push((ctx.new_local(cleanup), ctx.new_local(held)));
+            }
+        }
+        for (cleanup, held) in jobs.iter() {
+            let mut args = ctx.new_local(Arguments::new(self, JsValue::undefined(), 1));
+            args[0] = **held;
+            let res = cleanup.as_object().as_function_mut().call(self, &mut args);
+            if let Err(error) = res {
+                self.report_uncaught_microtask(error, **cleanup);
+            }
+        }
+        !jobs.is_empty()
+    }
+
+    /// Register `hook` to be called with the thrown value and the task function whenever a
+    /// microtask throws. Without a hook errors are printed to stderr.
+    pub fn on_uncaught_microtask(
+        &mut self,
+        hook: Box<dyn FnMut(&mut VirtualMachine, JsValue, JsValue)>,
+    ) {
+        self.uncaught_microtask = Some(hook);
+    }
+
+    /// Opens a scope rooting the values passed to [RootScope::root] until it is dropped, they
+    /// survive collections even when nothing else refers to them.
+    ///
+    /// # Safety
+    ///
+    /// The scope keeps a raw pointer to the VM, so it and every handle rooted through it must
+    /// be dropped before the VM is disposed. Scopes must be dropped in the reverse order they
+    /// were opened in, which is checked when they are dropped.
+    pub unsafe fn root_scope(&mut self) -> RootScope {
+        RootScope::new(self)
+    }
+
+    /// Register `finalizer` to be called when the VM is dropped, e.g. by
+    /// [VirtualMachineRef::dispose]. Finalizers run in reverse registration order before the
+    /// heap is released, so cells they hold pointers to are still valid, but no collection runs
+    /// after them and no cell gets swept on its own.
+    pub fn add_finalizer_on_drop(&mut self, finalizer: Box<dyn FnOnce()>) {
+        self.drop_finalizers.push(finalizer);
+    }
+
+    fn report_uncaught_microtask(&mut self, error: JsValue, task: JsValue) {
+        let mut hook = match self.uncaught_microtask.take() {
+            Some(hook) => hook,
+            None => {
+                let msg = match error.to_string(self) {
+                    Ok(msg) => msg,
+                    Err(_) => "<error>".to_owned(),
+                };
+                let _ = writeln!(self.stderr, "Uncaught (in microtask) {}", msg);
+                return;
+            }
+        };
+        hook(self, error, task);
+        if self.uncaught_microtask.is_none() {
+            self.uncaught_microtask = Some(hook);
+        }
+    }
+
     pub fn new(opts: Options) -> VirtualMachineRef {
         let space = Space::new();
         let stack = Vec::<JsValue>::with_capacity(16 * 1024);
@@ -283,18 +1026,50 @@ impl VirtualMachine {
             stack,
             stack_end,
             acc: JsValue::undefined(),
+            options: opts,
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            start_time: Instant::now(),
+            debugger: None,
+            debugger_line: None,
+            microtasks: VecDeque::new(),
+            finalization_registries: vec![],
+            uncaught_microtask: None,
+            drop_finalizers: vec![],
+            handles: vec![],
+            root_scopes: 0,
+            structure_stats: StructureStats::default(),
+            ic_stats: IcStats::default(),
+            stdout: Box::new(std::io::stdout()),
+            stderr: Box::new(std::io::stderr()),
+            interrupts: Arc::new(AtomicU32::new(0)),
+            interrupt_level: 0,
+            deadline: None,
+            interrupt_polls: 0,
+            running: false,
+            symbol_sweep_at: 0,
         })));
         let c = this;
         this.space.add_constraint(SimpleMarkingConstraint::new(
             "VM marking",
             move |tracer| unsafe {
-                let vm = c;
+                let vm = c.0;
                 (*vm).global_data.trace(tracer);
                 (*vm).global_object.trace(tracer);
                 (*vm).thrown_error.trace(tracer);
                 (*vm).return_value.trace(tracer);
+                for task in (*vm).microtasks.iter_mut() {
+                    task.trace(tracer);
+                }
+                for handle in (*vm).handles.iter_mut() {
+                    handle.trace(tracer);
+                }
+                let mut sp = (*vm).stack_start;
+                while sp < (*vm).stack {
+                    (*sp).trace(tracer);
+                    sp = sp.add(1);
+                }
                 unsafe {
-                    let mut current = (*vm).frame as *const FrameBase;
+                    let mut current = (*vm).frame;
                     while !current.is_null() {
                         (*current).trace(tracer);
                         current = (*current).prev;
@@ -307,12 +1082,90 @@ impl VirtualMachine {
         let s = this.global_data().empty_object_struct.unwrap();
         let proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
         this.global_data.object_prototype = Some(proto);
-        this.global_data.function_struct = Some(Structure::new_indexed(&mut this, None, false));
+        this.global_data.object_structure = Some(this.root_structure(Some(proto)));
+        let s = this.global_data().object_structure.unwrap();
+        let array_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.array_prototype = Some(array_proto);
+        this.global_data.array_structure =
+            Some(Structure::new_indexed(&mut this, Some(array_proto), true));
+        let string_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.string_prototype = Some(string_proto);
+        let number_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.number_prototype = Some(number_proto);
+        let symbol_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.symbol_prototype = Some(symbol_proto);
+        let regexp_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.regexp_prototype = Some(regexp_proto);
+        this.global_data.regexp_structure =
+            Some(Structure::new_indexed(&mut this, Some(regexp_proto), false));
+        let iter_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.regexp_string_iterator_structure =
+            Some(Structure::new_indexed(&mut this, Some(iter_proto), false));
+        let map_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.map_structure =
+            Some(Structure::new_indexed(&mut this, Some(map_proto), false));
+        let set_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.set_structure =
+            Some(Structure::new_indexed(&mut this, Some(set_proto), false));
+        let iter_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.map_iterator_structure =
+            Some(Structure::new_indexed(&mut this, Some(iter_proto), false));
+        let iter_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.set_iterator_structure =
+            Some(Structure::new_indexed(&mut this, Some(iter_proto), false));
+        let iter_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.array_iterator_structure =
+            Some(Structure::new_indexed(&mut this, Some(iter_proto), false));
+        let registry_proto =
+            JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.finalization_registry_structure = Some(Structure::new_indexed(
+            &mut this,
+            Some(registry_proto),
+            false,
+        ));
+        let date_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.date_structure =
+            Some(Structure::new_indexed(&mut this, Some(date_proto), false));
+        let buffer_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.array_buffer_structure =
+            Some(Structure::new_indexed(&mut this, Some(buffer_proto), false));
+        let shared_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.shared_array_buffer_structure =
+            Some(Structure::new_indexed(&mut this, Some(shared_proto), false));
+        let typed_array_proto =
+            JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.typed_array_prototype = Some(typed_array_proto);
+        let typed_array_struct = Structure::new_indexed(&mut this, Some(typed_array_proto), false);
+        for _ in TypedArrayKind::ALL {
+            let proto = JsObject::new(
+                &mut this,
+                typed_array_struct,
+                JsObject::get_class(),
+                ObjectTag::Ordinary,
+            );
+            let structure = Structure::new_indexed(&mut this, Some(proto), false);
+            this.global_data.typed_array_structures.push(structure);
+        }
+        this.global_data.proxy_structure = Some(Structure::new_indexed(&mut this, None, false));
+        let func_proto = JsObject::new(&mut this, s, JsObject::get_class(), ObjectTag::Ordinary);
+        this.global_data.function_prototype = Some(func_proto);
+        this.global_data.function_struct =
+            Some(Structure::new_indexed(&mut this, Some(func_proto), false));
         this.global_data.normal_arguments_structure =
             Some(Structure::new_indexed(&mut this, None, false));
         this.global_object = Some(JsGlobal::new(&mut this));
         this.init_error(proto);
-
+        jsrt_init(&mut this);
+        let mut array_proto = this.global_data().array_prototype.unwrap();
+        let values = array_proto.get(&mut this, Symbol::iterator());
+        this.global_data.array_values = values
+            .ok()
+            .filter(|values| values.is_object())
+            .map(|values| values.as_object());
+        // Builtins are set up before stress mode is enabled, bootstrapping keeps freshly created
+        // objects in plain locals until they are reachable from `global_data`.
+        let stress = this.options.gc_stress;
+        this.space.set_gc_stress(stress);
         this
     }
     pub fn global_object(&self) -> Gc<JsObject> {
@@ -322,6 +1175,66 @@ impl VirtualMachine {
         &mut self.space
     }
 
+    /// Collects garbage if enough was allocated since the last collection, see
+    /// [Space::collect_if_necessary]. Returns whether a collection ran.
+    ///
+    /// On wasm32 allocation never collects, embedders call this between evaluations instead.
+    pub fn collect_garbage_if_needed(&mut self) -> bool {
+        self.space.collect_if_necessary()
+    }
+
+    /// Collects garbage and packs the cells of partially filled heap blocks together, see
+    /// [Space::compact]. Returns the number of moved cells.
+    ///
+    /// `Gc`s in the heap, in [LocalContext]s, behind [RootScope] handles and in the VM itself
+    /// are updated.
+    ///
+    /// # Safety
+    ///
+    /// The native stack is not scanned, so a cell may move even though a `Gc` to it is still
+    /// held outside of the places above, such as a `Gc` copied out of a [Local] or a projection
+    /// returned by [Gc::map]. The caller must not use any of these after the call. Panics if
+    /// JavaScript code is running.
+    pub unsafe fn compact_heap(&mut self) -> usize {
+        assert!(
+            self.frame.is_null(),
+            "the heap can not be compacted while code runs"
+        );
+        let moved = self.space.compact();
+        if moved > 0 {
+            let mut maps = vec![];
+            self.space.for_each_cell(|cell| {
+                if let Some(object) = cell.downcast_ref::<JsObject>() {
+                    if object.tag() == ObjectTag::Map || object.tag() == ObjectTag::Set {
+                        maps.push(object as *const JsObject as *mut JsObject);
+                    }
+                }
+            });
+            for map in maps {
+                (*map).as_map_mut().rehash();
+            }
+        }
+        moved
+    }
+
+    pub fn gc_stats(&self) -> GcStats {
+        self.space.gc_stats()
+    }
+
+    /// Walks every cell in the heap, see [Space::for_each_cell]. `Cell::get_typename` and
+    /// `Cell::compute_size` give enough information to build a heap summary.
+    pub fn for_each_cell(&self, f: impl FnMut(&dyn Cell)) {
+        self.space.for_each_cell(f)
+    }
+
+    /// Checks that every `Gc` edge in the heap points to a live cell and that structures are
+    /// consistent, see [crate::gc::verify]. Meant for tests, call it right after
+    /// `vm.space().gc()` to catch edges the collector did not trace.
+    #[cfg(any(debug_assertions, feature = "verify-heap"))]
+    pub fn verify_heap(&self) -> Result<(), HeapError> {
+        self.space.verify()
+    }
+
     pub fn space_offset() -> usize {
         object_offsetof!(Self, space)
     }
@@ -356,10 +1269,11 @@ impl VirtualMachine {
         self.global_data.reference_error_structure =
             Some(Structure::new_indexed(self, None, false));
         self.global_data.type_error_structure = Some(Structure::new_indexed(self, None, false));
+        self.global_data.syntax_error_structure = Some(Structure::new_indexed(self, None, false));
         let structure = Structure::new_unique_with_proto(self, Some(obj_proto), false);
         let mut proto = JsObject::new(self, structure, JsError::get_class(), ObjectTag::Ordinary);
         let e = self.intern("Error");
-        let mut ctor = JsNativeFunction::new(self, e, error_constructor, 1);
+        let mut ctor = JsNativeFunction::new_constructor(self, e, error_constructor, 1);
         let _ = ctor.define_own_property(
             self,
             Symbol::prototype(),
@@ -420,7 +1334,8 @@ impl VirtualMachine {
                 .unwrap()
                 .change_prototype_with_no_transition(sub_proto);
             let sym = self.intern("EvalError");
-            let mut sub_ctor = JsNativeFunction::new(self, sym, eval_error_constructor, 1);
+            let mut sub_ctor =
+                JsNativeFunction::new_constructor(self, sym, eval_error_constructor, 1);
             let _ = sub_ctor.define_own_property(
                 self,
                 Symbol::prototype(),
@@ -482,7 +1397,8 @@ impl VirtualMachine {
                 .unwrap()
                 .change_prototype_with_no_transition(sub_proto);
             let sym = self.intern("TypeError");
-            let mut sub_ctor = JsNativeFunction::new(self, sym, type_error_constructor, 1);
+            let mut sub_ctor =
+                JsNativeFunction::new_constructor(self, sym, type_error_constructor, 1);
             let _ = sub_ctor.define_own_property(
                 self,
                 Symbol::prototype(),
@@ -548,7 +1464,8 @@ impl VirtualMachine {
                 .unwrap()
                 .change_prototype_with_no_transition(sub_proto);
             let sym = self.intern("ReferenceError");
-            let mut sub_ctor = JsNativeFunction::new(self, sym, reference_error_constructor, 1);
+            let mut sub_ctor =
+                JsNativeFunction::new_constructor(self, sym, reference_error_constructor, 1);
             let _ = sub_ctor.define_own_property(
                 self,
                 Symbol::prototype(),
@@ -596,6 +1513,121 @@ impl VirtualMachine {
 
             self.global_data.reference_error = Some(sub_proto);
         }
+
+        {
+            let structure = Structure::new_unique_with_proto(self, Some(proto), false);
+            let mut sub_proto = JsObject::new(
+                self,
+                structure,
+                JsSyntaxError::get_class(),
+                ObjectTag::Ordinary,
+            );
+
+            self.global_data
+                .syntax_error_structure
+                .unwrap()
+                .change_prototype_with_no_transition(sub_proto);
+            let sym = self.intern("SyntaxError");
+            let mut sub_ctor =
+                JsNativeFunction::new_constructor(self, sym, syntax_error_constructor, 1);
+            let _ = sub_ctor.define_own_property(
+                self,
+                Symbol::prototype(),
+                &*DataDescriptor::new(JsValue::new(sub_proto), NONE),
+                false,
+            );
+            let _ = sub_proto.define_own_property(
+                self,
+                Symbol::constructor(),
+                &*DataDescriptor::new(JsValue::new(sub_ctor), W | C),
+                false,
+            );
+
+            let n = self.intern("name");
+            let s = JsString::new(self, "SyntaxError");
+            let e = JsString::new(self, "");
+            let m = self.intern("message");
+            let _ = sub_proto.define_own_property(
+                self,
+                n,
+                &*DataDescriptor::new(JsValue::new(s), W | C),
+                false,
+            );
+
+            let _ = sub_proto.define_own_property(
+                self,
+                m,
+                &*DataDescriptor::new(JsValue::new(e), W | C),
+                false,
+            );
+            let to_str = JsNativeFunction::new(self, Symbol::toString(), error_to_string, 0);
+            let _ = sub_proto.define_own_property(
+                self,
+                Symbol::toString(),
+                &*DataDescriptor::new(JsValue::new(to_str), W | C),
+                false,
+            );
+
+            let _ = self.global_object().define_own_property(
+                self,
+                sym,
+                &*DataDescriptor::new(JsValue::new(sub_proto), W | C),
+                false,
+            );
+
+            self.global_data.syntax_error = Some(sub_proto);
+        }
+    }
+}
+
+/// Embedder API for building and inspecting objects from Rust without touching `Structure`s or `Slot`s directly.
+impl VirtualMachine {
+    /// Create a new ordinary object inheriting from `Object.prototype`, rooted in `ctx`.
+    pub fn new_object<'a>(&mut self, ctx: &'a LocalContext<'a>) -> Local<'a, Gc<JsObject>> {
+        let structure = self.global_data.object_structure.unwrap();
+        ctx.new_local(JsObject::new(
+            self,
+            structure,
+            JsObject::get_class(),
+            ObjectTag::Ordinary,
+        ))
+    }
+
+    /// Create a new array holding `elems`, rooted in `ctx`.
+    pub fn new_array<'a>(
+        &mut self,
+        ctx: &'a LocalContext<'a>,
+        elems: &[JsValue],
+    ) -> Local<'a, Gc<JsObject>> {
+        let mut arr = ctx.new_local(JsArray::new(self, 0));
+        for (i, elem) in elems.iter().enumerate() {
+            let _ = arr.put(self, Symbol::Indexed(i as _), *elem, false);
+        }
+        arr
+    }
+
+    /// Define own data property `name` on `obj` with the given attributes.
+    pub fn put(
+        &mut self,
+        mut obj: Gc<JsObject>,
+        name: impl IntoSymbol,
+        val: JsValue,
+        attrs: AttrSafe,
+    ) -> Result<(), JsValue> {
+        let name = self.intern(name);
+        obj.define_own_property(
+            self,
+            name,
+            &*DataDescriptor::new(val, attrs.raw() & (W | E | C)),
+            true,
+        )?;
+        Ok(())
+    }
+
+    /// Perform `obj[name]`, walking the prototype chain and invoking getters if necessary.
+    pub fn get(&mut self, obj: Gc<JsObject>, name: impl IntoSymbol) -> Result<JsValue, JsValue> {
+        let name = self.intern(name);
+        obj.get(self, name)
     }
 }
 
@@ -612,12 +1644,41 @@ use starlight_derive::Trace;
 pub struct GlobalData {
     pub(crate) normal_arguments_structure: Option<Gc<Structure>>,
     pub(crate) empty_object_struct: Option<Gc<Structure>>,
+    /// Root structure of `Object.prototype`, kept apart from [GlobalData::root_structures] so
+    /// object literals skip the lookup.
+    pub(crate) object_structure: Option<Gc<Structure>>,
+    pub(crate) array_structure: Option<Gc<Structure>>,
     pub(crate) function_struct: Option<Gc<Structure>>,
     pub(crate) object_prototype: Option<Gc<JsObject>>,
+    pub(crate) function_prototype: Option<Gc<JsObject>>,
+    pub(crate) array_prototype: Option<Gc<JsObject>>,
     pub(crate) number_prototype: Option<Gc<JsObject>>,
     pub(crate) string_prototype: Option<Gc<JsObject>>,
     pub(crate) boolean_prototype: Option<Gc<JsObject>>,
     pub(crate) symbol_prototype: Option<Gc<JsObject>>,
+    pub(crate) regexp_prototype: Option<Gc<JsObject>>,
+    pub(crate) regexp_structure: Option<Gc<Structure>>,
+    pub(crate) regexp_string_iterator_structure: Option<Gc<Structure>>,
+    pub(crate) map_structure: Option<Gc<Structure>>,
+    pub(crate) set_structure: Option<Gc<Structure>>,
+    pub(crate) map_iterator_structure: Option<Gc<Structure>>,
+    pub(crate) set_iterator_structure: Option<Gc<Structure>>,
+    pub(crate) array_iterator_structure: Option<Gc<Structure>>,
+    /// Structures of object literals with static keys by their key list, literals with the same
+    /// keys share them.
+    pub(crate) literal_structures: HashMap<Vec<Symbol>, Gc<Structure>>,
+    /// Empty root structures by prototype, see [VirtualMachine::root_structure].
+    pub(crate) root_structures: HashMap<Gc<JsObject>, Gc<Structure>>,
+    /// The initial `Array.prototype.values`, arrays still iterating with it can be read directly.
+    pub(crate) array_values: Option<Gc<JsObject>>,
+    pub(crate) finalization_registry_structure: Option<Gc<Structure>>,
+    pub(crate) date_structure: Option<Gc<Structure>>,
+    pub(crate) array_buffer_structure: Option<Gc<Structure>>,
+    pub(crate) shared_array_buffer_structure: Option<Gc<Structure>>,
+    pub(crate) typed_array_prototype: Option<Gc<JsObject>>,
+    /// Structures of typed arrays, indexed by [TypedArrayKind].
+    pub(crate) typed_array_structures: Vec<Gc<Structure>>,
+    pub(crate) proxy_structure: Option<Gc<Structure>>,
     pub(crate) error: Option<Gc<JsObject>>,
     pub(crate) type_error: Option<Gc<JsObject>>,
     pub(crate) reference_error: Option<Gc<JsObject>>,
@@ -633,6 +1694,35 @@ pub struct GlobalData {
     pub(crate) type_error_structure: Option<Gc<Structure>>,
     pub(crate) uri_error_structure: Option<Gc<Structure>>,
     pub(crate) eval_error_structure: Option<Gc<Structure>>,
+
+    /// Shared strings for characters below [SMALL_STRING_CACHE_SIZE], filled lazily.
+    pub(crate) char_strings: Vec<Option<Gc<JsString>>>,
+    /// Shared strings for integers in `0..SMALL_STRING_CACHE_SIZE`, filled lazily.
+    pub(crate) int_strings: Vec<Option<Gc<JsString>>>,
+}
+
+/// Single character strings and small integer strings below this value are cached.
+pub const SMALL_STRING_CACHE_SIZE: usize = 256;
+
+fn cached_string(
+    vm: &mut VirtualMachine,
+    cache: fn(&mut GlobalData) -> &mut Vec<Option<Gc<JsString>>>,
+    ix: usize,
+    s: impl AsRef<str>,
+) -> Gc<JsString> {
+    if ix >= SMALL_STRING_CACHE_SIZE {
+        return JsString::new(vm, s);
+    }
+    let entries = cache(&mut vm.global_data);
+    if entries.is_empty() {
+        entries.resize(SMALL_STRING_CACHE_SIZE, None);
+    }
+    if let Some(cached) = entries[ix] {
+        return cached;
+    }
+    let string = JsString::new(vm, s);
+    cache(&mut vm.global_data)[ix] = Some(string);
+    string
 }
 
 impl GlobalData {
@@ -647,8 +1737,365 @@ impl GlobalData {
 
 impl Drop for VirtualMachine {
     fn drop(&mut self) {
+        while let Some(finalizer) = self.drop_finalizers.pop() {
+            finalizer();
+        }
         unsafe {
             let _ = Vec::from_raw_parts(self.stack_start, 0, 16 * 1024);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_without_args() {
+        let opts = Options::builder().write_barrier_buffer_size(64).build();
+        assert_eq!(opts.write_barrier_buffer_size, 64);
+        assert_eq!(Options::default().write_barrier_buffer_size, 32);
+
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval("var x = 40 + 2;");
+        assert!(res.is_ok());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_finalizers_on_drop() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let ran = Rc::new(RefCell::new(vec![]));
+        let mut vm = VirtualMachine::new(Options::default());
+        for i in 0..3 {
+            let ran = ran.clone();
+            vm.add_finalizer_on_drop(Box::new(move || ran.borrow_mut().push(i)));
+        }
+        assert!(vm.eval("x = {};").is_ok());
+        assert!(ran.borrow().is_empty());
+        VirtualMachineRef::dispose(vm);
+        assert_eq!(*ran.borrow(), [2, 1, 0]);
+    }
+
+    #[test]
+    fn test_try_eval_captures_output() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let stdout = BufferedError::default();
+        vm.set_stdout(Box::new(stdout.clone()));
+        let (res, output) = vm.try_eval("print('hi');");
+        assert!(res.is_ok());
+        assert_eq!(output, "hi\n");
+        // the previous sink is back in place.
+        assert!(vm.eval("print('a', 1);").is_ok());
+        assert_eq!(stdout.to_string(), "a1\n");
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_interrupt_runs_finally_once() {
+        let mut vm = VirtualMachine::new(Options::builder().timeout(10).build());
+        let (res, output) = vm.try_eval("try { while (true) {} } finally { print('cleanup'); }");
+        match res {
+            Err(e) => assert!(std::ptr::eq(e.as_object().class(), JsError::get_class())),
+            Ok(_) => panic!(),
+        }
+        assert_eq!(output, "cleanup\n");
+        // catch does not stop the interrupt, a finally block that never ends is aborted.
+        let (res, output) = vm.try_eval(
+            "caught = false;
+             try {
+                 try { while (true) {} } catch (e) { caught = true; }
+             } finally {
+                 print('outer');
+                 while (true) {}
+             }
+             print('unreachable');",
+        );
+        assert!(res.is_err());
+        assert_eq!(output, "outer\n");
+        let global = vm.global_object();
+        let caught = vm.get(global, "caught").unwrap_or_else(|_| panic!());
+        assert!(!caught.to_boolean());
+        // the next script starts without pending interrupts.
+        assert!(vm.eval("try { done = 1; } catch (e) {}").is_ok());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_interrupt_handle() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let handle = vm.interrupt_handle();
+        let thread = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            handle.interrupt();
+        });
+        let res = vm.eval("function spin() { while (true) {} } spin();");
+        thread.join().unwrap();
+        assert!(res.is_err());
+        // an interrupt requested while no script runs does not stop the next one.
+        vm.interrupt_handle().interrupt();
+        assert!(vm
+            .eval("function loop() { i = 0; while (i < 10) { i += 1; } } loop();")
+            .is_ok());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_forced_strict_mode() {
+        let src = "function f() { inner = 1; }
+                   leaked = 1;";
+        let mut vm = VirtualMachine::new(Options::default());
+        assert!(vm.eval(src).is_ok());
+        assert!(vm.eval("f();").is_ok());
+        let global = vm.global_object();
+        let leaked = vm.get(global, "leaked").unwrap_or_else(|_| panic!());
+        assert_eq!(leaked.as_int32(), 1);
+        VirtualMachineRef::dispose(vm);
+
+        let mut vm = VirtualMachine::new(Options::builder().strict(true).build());
+        assert!(vm.eval("var declared = 1; declared = 2;").is_ok());
+        assert!(vm.eval("function f() { inner = 1; }").is_ok());
+        assert!(vm.eval("f();").is_err());
+        assert!(vm.eval(src).is_err());
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_gc_stress() {
+        let mut vm = VirtualMachine::new(Options::builder().gc_stress(true).build());
+        assert!(vm.space().is_gc_stress());
+        let res = vm.eval(
+            "function P(x) { this.x = x; this.y = x + 1; this.z = [x, x]; }
+             var a = new P(1);
+             var b = new P(2);
+             r = a.x + a.y + b.x + b.y + a.z[1] + b.z[0];",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let r = vm.get(global, "r").unwrap_or_else(|_| panic!());
+        assert!(r.is_int32() && r.as_int32() == 11);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_embedder_objects() {
+        let mut vm = VirtualMachine::new(Options::default());
+        {
+            let ctx = vm.space().new_local_context();
+            let nested = vm.new_object(&ctx);
+            let _ = vm.put(*nested, "x", JsValue::new(7), object_data());
+            let arr = vm.new_array(&ctx, &[JsValue::new(1), JsValue::new(2), JsValue::new(3)]);
+            let data = vm.new_object(&ctx);
+            assert!(vm
+                .put(*data, "inner", JsValue::new(*arr), object_data())
+                .is_ok());
+            assert!(vm
+                .put(*data, "nested", JsValue::new(*nested), object_data())
+                .is_ok());
+            let global = vm.global_object();
+            assert!(vm
+                .put(global, "data", JsValue::new(*data), object_data())
+                .is_ok());
+
+            assert!(vm.eval("r = data.inner[1]; s = data.nested.x;").is_ok());
+            let r = vm.get(global, "r").unwrap_or_else(|_| panic!());
+            assert!(r.is_int32() && r.as_int32() == 2);
+            let s = vm.get(global, "s").unwrap_or_else(|_| panic!());
+            assert!(s.is_int32() && s.as_int32() == 7);
+
+            let len = vm.get(*arr, "length").unwrap_or_else(|_| panic!());
+            assert_eq!(len.as_int32(), 3);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_debugger_hook() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let mut vm = VirtualMachine::new(Options::default());
+        // `debugger;` without a hook is a no-op.
+        assert!(vm.eval("debugger;").is_ok());
+
+        let hits = Rc::new(RefCell::new(vec![]));
+        let seen = hits.clone();
+        vm.on_debugger(Box::new(move |vm| {
+            let line = vm.debugger_line().unwrap();
+            let y = vm.intern("y");
+            let locals = vm.frame_locals();
+            let val = locals
+                .iter()
+                .find(|(name, _)| *name == y)
+                .map(|(_, val)| *val);
+            seen.borrow_mut()
+                .push((line, val.map(|val| val.as_int32())));
+        }));
+        let res = vm.eval(
+            "function f(x) {
+                 var y = x + 1;
+                 debugger;
+                 return y;
+             }
+             r = f(41);",
+        );
+        assert!(res.is_ok());
+        assert_eq!(&*hits.borrow(), &[(3, Some(42))]);
+        assert!(vm.debugger_line().is_none());
+        let global = vm.global_object();
+        let r = vm.get(global, "r").unwrap_or_else(|_| panic!());
+        assert_eq!(r.as_int32(), 42);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_for_each_cell() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let typename = std::any::type_name::<Structure>();
+        let count = |vm: &VirtualMachine| {
+            let mut count = 0;
+            vm.for_each_cell(|cell| {
+                if cell.get_typename() == typename {
+                    assert!(cell.compute_size() > 0);
+                    count += 1;
+                }
+            });
+            count
+        };
+        let before = count(&vm);
+        {
+            let ctx = vm.space().new_local_context();
+            for _ in 0..100 {
+                ctx.new_local(Structure::new_indexed(&mut vm, None, false));
+            }
+            vm.space().gc();
+            assert!(count(&vm) >= before + 100);
+        }
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_uncaught_microtask() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let mut vm = VirtualMachine::new(Options::default());
+        let errors = Rc::new(RefCell::new(vec![]));
+        let seen = errors.clone();
+        vm.on_uncaught_microtask(Box::new(move |vm, error, task| {
+            let error = error.to_string(vm).unwrap_or_else(|_| panic!());
+            seen.borrow_mut().push((task, error));
+        }));
+        let res = vm.eval(
+            "order = '';
+             function bad() { order = order + 'b'; return null.x; }
+             function good() { order = order + 'g'; }
+             queueMicrotask(bad);
+             queueMicrotask(good);
+             order = order + 's';",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let order = vm.get(global, "order").unwrap_or_else(|_| panic!());
+        assert_eq!(order.as_string().as_str(), "sbg");
+        let errors = errors.borrow();
+        assert_eq!(errors.len(), 1);
+        let bad = vm.get(global, "bad").unwrap_or_else(|_| panic!());
+        assert!(Gc::ptr_eq(errors[0].0.as_object(), bad.as_object()));
+        assert!(errors[0].1.contains("no properties"));
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_symbol_table_limit() {
+        let mut vm = VirtualMachine::new(Options::builder().symbol_table_limit(100).build());
+        // symbols interned outside of a script are never freed.
+        let pinned = vm.intern("pinned");
+        let before = vm.symbol_table_size();
+        assert!(vm
+            .eval(
+                "o = Object(); o.kept = 1;
+                 var t = {};
+                 function fill(lo, n) {
+                     if (n == 1) {
+                         t['transient' + lo] = lo;
+                     } else {
+                         fill(lo, n / 2);
+                         fill(lo + n / 2, n / 2);
+                     }
+                 }
+                 fill(0, 256);
+                 t = null;"
+            )
+            .is_ok());
+        assert!(vm.symbol_table_size() < before + 100);
+        // a collection outside of a script frees nothing.
+        let size = vm.symbol_table_size();
+        vm.space().gc();
+        assert_eq!(vm.symbol_table_size(), size);
+        assert!(vm.intern("pinned") == pinned);
+
+        // symbols used as property keys are kept.
+        let global = vm.global_object();
+        let o = vm.get(global, "o").unwrap_or_else(|_| panic!());
+        let kept = vm.get(o.as_object(), "kept").unwrap_or_else(|_| panic!());
+        assert_eq!(kept.as_int32(), 1);
+        let size = vm.symbol_table_size();
+        vm.intern("kept");
+        assert_eq!(vm.symbol_table_size(), size);
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_inspect() {
+        let mut vm = VirtualMachine::new(Options::default());
+        assert!(vm
+            .eval("o = { a: 1, b: [1, 'x', true], c: { d: { e: 1 } }, f: Object, g: {} };")
+            .is_ok());
+        let global = vm.global_object();
+        let o = vm.get(global, "o").unwrap_or_else(|_| panic!());
+        assert_eq!(
+            vm.inspect(o),
+            "{ a: 1, b: [1, \"x\", true], c: { d: [object Object] }, f: [Function], g: {} }"
+        );
+        assert_eq!(vm.inspect(JsValue::new_double(0.5)), "0.5");
+        assert_eq!(vm.inspect(JsValue::undefined()), "undefined");
+        VirtualMachineRef::dispose(vm);
+    }
+
+    #[test]
+    fn test_source_map() {
+        let mut vm = VirtualMachine::new(Options::default());
+        let res = vm.eval(
+            "function f(a, b) {
+    var x = a + 1;
+    var y = b * 2;
+    return x + y;
+}",
+        );
+        assert!(res.is_ok());
+        let global = vm.global_object();
+        let f = vm.get(global, "f").unwrap_or_else(|_| panic!());
+        let code = f.as_object().as_function().as_vm().code;
+        let map = code.source_map();
+        assert!(map.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        let positions = map
+            .iter()
+            .map(|&(_, line, column)| (line, column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 5), (3, 5), (4, 5)]);
+
+        // the multiplication is in the middle of the function, on its second statement.
+        let mut dump = String::new();
+        assert!(code.display_to(&mut dump).is_ok());
+        let mul = dump
+            .lines()
+            .find(|line| line.ends_with(": mul"))
+            .and_then(|line| line.split(':').next())
+            .and_then(|offset| offset.trim().parse::<usize>().ok())
+            .unwrap_or_else(|| panic!("no mul in {}", dump));
+        let (_, line, column) = *map.iter().rev().find(|entry| entry.0 <= mul).unwrap();
+        assert_eq!((line, column), (3, 5));
+        assert_eq!(code.position_at(mul as u32), (3, 5));
+        VirtualMachineRef::dispose(vm);
+    }
+}
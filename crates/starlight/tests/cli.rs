@@ -0,0 +1,25 @@
+use std::{env, fs, process::Command};
+
+#[test]
+fn test_repeat() {
+    let script = env::temp_dir().join(format!("starlight-repeat-{}.js", std::process::id()));
+    fs::write(&script, "x = [1, 2, 3].join();").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_starlight"))
+        .arg("--warmup")
+        .arg("1")
+        .arg("--repeat")
+        .arg("3")
+        .arg(&script)
+        .output()
+        .unwrap();
+    fs::remove_file(&script).unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "runs: 3 (warmup 1)");
+    assert!(lines[1].starts_with("time: min "));
+    assert!(lines[1].contains(" median ") && lines[1].contains(" max "));
+    assert!(lines[2].starts_with("allocations: min "));
+    assert!(lines[3].starts_with("collections: "));
+}
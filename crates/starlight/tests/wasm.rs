@@ -0,0 +1,19 @@
+//! Smoke test for WebAssembly builds, CI runs it under wasmtime with `--target wasm32-wasi`.
+//! It passes on native targets as well.
+use starlight::vm::{Options, VirtualMachine, VirtualMachineRef};
+
+#[test]
+fn test_eval() {
+    let mut vm = VirtualMachine::new(Options::default());
+    assert!(vm.eval("x = 1 + 1; o = { a: [x, 'str'] };").is_ok());
+    // wasm32 does not scan the stack, nothing above is referenced from Rust.
+    vm.space().gc();
+    assert!(vm.eval("y = x + o.a[0] + o.a[1];").is_ok());
+
+    let global = vm.global_object();
+    let x = vm.get(global, "x").unwrap_or_else(|_| panic!());
+    assert_eq!(x.number(), 2.0);
+    let y = vm.get(global, "y").unwrap_or_else(|_| panic!());
+    assert_eq!(y.as_string().as_str(), "4str");
+    VirtualMachineRef::dispose(vm);
+}
@@ -294,7 +294,7 @@ impl<T> RetainMut<T> for VecDeque<T> {
         }
     }
 }
-#[cfg(not(miri))]
+#[cfg(not(any(miri, target_arch = "wasm32")))]
 pub(crate) fn thread_self() -> u64 {
     #[cfg(windows)]
     unsafe {
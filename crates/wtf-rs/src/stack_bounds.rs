@@ -64,6 +64,17 @@ pub mod _impl {
         }
     }
 
+    /// WebAssembly keeps locals outside of linear memory, there is no stack to scan.
+    #[cfg(target_arch = "wasm32")]
+    impl StackBounds {
+        pub fn current_thread_stack_bounds() -> Self {
+            Self {
+                origin: core::ptr::null_mut(),
+                bound: core::ptr::null_mut(),
+            }
+        }
+    }
+
     #[cfg(windows)]
     impl StackBounds {
         pub unsafe fn current_thread_stack_bounds_internal() -> Self {